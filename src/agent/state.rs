@@ -5,17 +5,29 @@
 
 use crate::error::{SimonError, Result};
 use crate::gpu::GpuInfo;
+use crate::hwlog::{self, EventDevice, HardwareEvent};
 use crate::SiliconMonitor;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 use super::Query;
 
+/// How far back kernel log hardware events are included in agent context
+const HARDWARE_EVENT_WINDOW: Duration = Duration::from_secs(24 * 3600);
+
+/// Maximum number of hardware events included in agent context
+const MAX_HARDWARE_EVENTS: usize = 10;
+
 /// Condensed system state for agent context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemState {
     /// GPU information (only for queried GPUs)
     pub gpus: Vec<GpuState>,
 
+    /// Recent hardware events from the kernel log (newest last)
+    #[serde(default)]
+    pub hardware_events: Vec<HardwareEvent>,
+
     /// Timestamp of state capture
     pub timestamp: u64,
 }
@@ -32,6 +44,10 @@ pub struct GpuState {
     /// GPU vendor
     pub vendor: String,
 
+    /// PCI bus ID (e.g., "0000:01:00.0")
+    #[serde(default)]
+    pub pci_bus_id: Option<String>,
+
     /// Graphics utilization (0-100%)
     pub utilization: u32,
 
@@ -91,8 +107,14 @@ impl SystemState {
                 .collect()
         };
 
+        let mut hardware_events = hwlog::recent_hardware_events(HARDWARE_EVENT_WINDOW);
+        if hardware_events.len() > MAX_HARDWARE_EVENTS {
+            hardware_events.drain(..hardware_events.len() - MAX_HARDWARE_EVENTS);
+        }
+
         Ok(Self {
             gpus: gpu_states,
+            hardware_events,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -106,6 +128,7 @@ impl SystemState {
             index,
             name: info.static_info.name,
             vendor: format!("{:?}", info.static_info.vendor),
+            pci_bus_id: info.static_info.pci_bus_id,
             utilization: info.dynamic_info.utilization as u32,
             memory_used_mb: info.dynamic_info.memory.used / 1024 / 1024,
             memory_total_mb: info.dynamic_info.memory.total / 1024 / 1024,
@@ -154,9 +177,45 @@ impl SystemState {
             }
         }
 
+        if !self.hardware_events.is_empty() {
+            context.push_str("\nRecent Hardware Events (kernel log):\n");
+            for event in &self.hardware_events {
+                context.push_str(&format!("  [{}] {}", event.severity, event.kind));
+                if let Some(ref device) = event.device {
+                    match self.gpu_for_device(device) {
+                        Some(gpu) => context.push_str(&format!(" on GPU {} ({})", gpu.index, device)),
+                        None => context.push_str(&format!(" on {}", device)),
+                    }
+                }
+                if let Some(pid) = event.pid {
+                    match event.process {
+                        Some(ref name) => context.push_str(&format!(", process {} (PID {})", name, pid)),
+                        None => context.push_str(&format!(", PID {}", pid)),
+                    }
+                }
+                if let Some(age) = event.age() {
+                    context.push_str(&format!(", {} min ago", age.as_secs() / 60));
+                }
+                context.push('\n');
+            }
+        }
+
         context
     }
 
+    /// Find the GPU an event device refers to
+    fn gpu_for_device(&self, device: &EventDevice) -> Option<&GpuState> {
+        let EventDevice::Pci(ref bus_id) = device else {
+            return None;
+        };
+        self.gpus.iter().find(|gpu| {
+            gpu.pci_bus_id
+                .as_deref()
+                .map(|id| hwlog::normalize_pci_bus_id(id) == *bus_id)
+                .unwrap_or(false)
+        })
+    }
+
     /// Get GPU state by index
     pub fn get_gpu(&self, index: usize) -> Option<&GpuState> {
         self.gpus.iter().find(|g| g.index == index)
@@ -264,6 +323,7 @@ mod tests {
             index: 0,
             name: "Test GPU".to_string(),
             vendor: "NVIDIA".to_string(),
+            pci_bus_id: None,
             utilization: 75,
             memory_used_mb: 8000,
             memory_total_mb: 16000,
@@ -292,6 +352,7 @@ mod tests {
                     index: 0,
                     name: "GPU 0".to_string(),
                     vendor: "NVIDIA".to_string(),
+                    pci_bus_id: None,
                     utilization: 50,
                     memory_used_mb: 4000,
                     memory_total_mb: 8000,
@@ -307,6 +368,7 @@ mod tests {
                     index: 1,
                     name: "GPU 1".to_string(),
                     vendor: "AMD".to_string(),
+                    pci_bus_id: None,
                     utilization: 80,
                     memory_used_mb: 6000,
                     memory_total_mb: 8000,
@@ -319,6 +381,7 @@ mod tests {
                     process_count: 1,
                 },
             ],
+            hardware_events: Vec::new(),
             timestamp: 0,
         };
 
@@ -16,6 +16,7 @@ use crate::core::memory::MemoryStats;
 use crate::disk::{self, DiskDevice};
 use crate::error::{Result, SimonError};
 use crate::gpu::{GpuCollection, GpuDynamicInfo, GpuStaticInfo};
+use crate::hwlog::{HardwareEvent, HardwareEventMonitor};
 use crate::motherboard::{self, DriverInfo, MotherboardDevice, SystemInfo as MBSystemInfo};
 use crate::network_monitor::NetworkMonitor;
 use crate::process_monitor::{ProcessMonitor, ProcessMonitorInfo};
@@ -57,6 +58,10 @@ pub struct FullSystemState {
     /// System information
     pub system: Option<SystemInfoState>,
 
+    /// Recent hardware events from the kernel log (newest last)
+    #[serde(default)]
+    pub hardware_events: Vec<HardwareEvent>,
+
     /// Timestamp of state capture
     pub timestamp: u64,
 }
@@ -250,6 +255,7 @@ impl FullSystemState {
             network: Vec::new(),
             top_processes: Vec::new(),
             system: None,
+            hardware_events: Vec::new(),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -372,6 +378,25 @@ impl FullSystemState {
             }
         }
 
+        // Hardware events
+        if !self.hardware_events.is_empty() {
+            ctx.push_str("\nHardware Events (kernel log):\n");
+            for event in &self.hardware_events {
+                ctx.push_str(&format!("  [{}] {}", event.severity, event.kind));
+                if let Some(ref device) = event.device {
+                    ctx.push_str(&format!(" on {}", device));
+                }
+                if let Some(pid) = event.pid {
+                    ctx.push_str(&format!(" (PID {}", pid));
+                    if let Some(ref process) = event.process {
+                        ctx.push_str(&format!(", {}", process));
+                    }
+                    ctx.push(')');
+                }
+                ctx.push('\n');
+            }
+        }
+
         ctx
     }
 }
@@ -479,6 +504,9 @@ pub struct MonitoringBackend {
     /// Motherboard sensors
     motherboard_sensors: Vec<Box<dyn MotherboardDevice>>,
 
+    /// Kernel log hardware event monitor
+    hardware_event_monitor: Option<HardwareEventMonitor>,
+
     // === Cached data ===
     /// Cached CPU stats
     cpu_stats: Option<CpuStats>,
@@ -597,6 +625,9 @@ impl MonitoringBackend {
         // Initialize motherboard sensors
         let motherboard_sensors = motherboard::enumerate_sensors().unwrap_or_default();
 
+        // Initialize kernel log hardware event monitor
+        let hardware_event_monitor = HardwareEventMonitor::new().ok();
+
         // Get system info
         let system_info = motherboard::get_system_info().ok();
 
@@ -607,9 +638,14 @@ impl MonitoringBackend {
         let system_stats = SystemStats::new().ok();
 
         // Get hostname and OS info
+        #[cfg(any(feature = "cli", feature = "gui"))]
         let hostname = hostname::get()
             .map(|h| h.to_string_lossy().to_string())
             .unwrap_or_else(|_| "unknown".to_string());
+        #[cfg(not(any(feature = "cli", feature = "gui")))]
+        let hostname = std::fs::read_to_string("/proc/sys/kernel/hostname")
+            .map(|h| h.trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
 
         let os_info = std::env::consts::OS.to_string();
 
@@ -638,6 +674,7 @@ impl MonitoringBackend {
             connection_monitor,
             disks,
             motherboard_sensors,
+            hardware_event_monitor,
             cpu_stats: None,
             memory_stats: None,
             gpu_static_info,
@@ -686,6 +723,7 @@ impl MonitoringBackend {
         self.update_connections()?;
         self.update_disks()?;
         self.update_system_stats()?;
+        self.update_hardware_events()?;
 
         self.last_update = Instant::now();
         Ok(())
@@ -974,6 +1012,29 @@ impl MonitoringBackend {
         self.start_time.elapsed()
     }
 
+    // === Hardware Events ===
+
+    fn update_hardware_events(&mut self) -> Result<()> {
+        if let Some(ref mut monitor) = self.hardware_event_monitor {
+            // A failed read (e.g. journalctl gone) shouldn't stop other updates
+            let _ = monitor.poll();
+        }
+        Ok(())
+    }
+
+    /// Get the kernel log hardware event monitor
+    pub fn hardware_event_monitor(&self) -> Option<&HardwareEventMonitor> {
+        self.hardware_event_monitor.as_ref()
+    }
+
+    /// Get hardware events newer than `window`, oldest first
+    pub fn hardware_events(&self, window: Duration) -> Vec<&HardwareEvent> {
+        self.hardware_event_monitor
+            .as_ref()
+            .map(|m| m.recent(window))
+            .unwrap_or_default()
+    }
+
     // === AI Agent ===
 
     /// Check if AI agent is available
@@ -1168,6 +1229,14 @@ impl MonitoringBackend {
             });
         }
 
+        // Hardware events from the last day
+        let events = self.hardware_events(Duration::from_secs(24 * 3600));
+        state.hardware_events = events
+            .iter()
+            .skip(events.len().saturating_sub(10))
+            .map(|e| (*e).clone())
+            .collect();
+
         state.timestamp = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
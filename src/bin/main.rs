@@ -18,7 +18,7 @@ struct Cli {
     #[arg(short, long, default_value = "1.0", global = true)]
     interval: f64,
 
-    /// Output format (json or text)
+    /// Output format (json or text; csv[,noheader][,nounits] for gpu --query-*)
     #[arg(short, long, default_value = "text", global = true)]
     format: String,
 }
@@ -27,14 +27,115 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Launch Terminal User Interface (TUI) - interactive dashboard
-    Tui,
+    Tui {
+        /// Show a running simond (unix:/path, /path or host:port) instead of local data
+        #[arg(long)]
+        attach: Option<String>,
+        /// API token for the daemon (default: $SIMOND_TOKEN)
+        #[arg(long, requires = "attach")]
+        token: Option<String>,
+    },
     /// Launch Graphical User Interface (GUI) - desktop application
     #[cfg(feature = "gui")]
-    Gui,
+    Gui {
+        /// Show a running simond (unix:/path, /path or host:port) instead of local data
+        #[arg(long)]
+        attach: Option<String>,
+        /// API token for the daemon (default: $SIMOND_TOKEN)
+        #[arg(long, requires = "attach")]
+        token: Option<String>,
+    },
+    /// Watch simond on many hosts at once (cluster view)
+    Fleet {
+        /// Host list: one `<endpoint> [name=..] [tags=a,b] [token=..]` per line
+        #[arg(long)]
+        hosts: PathBuf,
+        /// Only hosts carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// API token for the daemons (default: $SIMOND_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+        /// Per-host request timeout in seconds
+        #[arg(long, default_value = "3.0")]
+        timeout: f64,
+        /// Poll once and print the cluster table instead of the TUI
+        #[arg(long)]
+        once: bool,
+        /// With --once, list the N worst GPUs across the fleet
+        #[arg(long, requires = "once")]
+        worst: Option<usize>,
+        /// Ranking for --worst (temperature, utilization, memory or idle)
+        #[arg(long, default_value = "temperature")]
+        rank: String,
+    },
+    /// Push metrics to an OpenTelemetry collector over OTLP/HTTP
+    Otlp {
+        /// Collector URL (default: $OTEL_EXPORTER_OTLP_ENDPOINT or http://localhost:4318)
+        #[arg(long)]
+        endpoint: Option<String>,
+        /// Payload encoding: http/protobuf or http/json
+        #[arg(long)]
+        protocol: Option<String>,
+        /// Extra request header as name=value (repeatable)
+        #[arg(long = "header")]
+        headers: Vec<String>,
+        /// Keep undeliverable requests here and resend them later
+        #[arg(long)]
+        buffer_dir: Option<PathBuf>,
+        /// Samples collected per export request
+        #[arg(long, default_value = "10")]
+        batch: u32,
+        /// Stop after this many samples (runs until interrupted if omitted)
+        #[arg(short, long)]
+        count: Option<u32>,
+        /// Skip the health checks
+        #[arg(long)]
+        no_health: bool,
+    },
+    /// Write metrics to the `[[sinks]]` in the config (InfluxDB, StatsD)
+    Sinks {
+        /// Config file (default: the standard config location)
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Stop after this many samples (runs until interrupted if omitted)
+        #[arg(short, long)]
+        count: Option<u32>,
+    },
+    /// Show hwmon chips like lm-sensors `sensors`, with stable chip names
+    Sensors {
+        /// Chips to show (e.g. coretemp-*, nvme-pci-0100); all if omitted
+        chips: Vec<String>,
+        /// Print JSON in `sensors -j` format
+        #[arg(short = 'j')]
+        json: bool,
+        /// sensors.conf-style file (TOML if it ends in .toml) instead of
+        /// /etc/sensors3.conf and /etc/sensors.d
+        #[arg(short = 'c', long = "config-file")]
+        config_file: Option<PathBuf>,
+        /// Write the `set` statements of the configuration to the chips (needs root)
+        #[arg(short = 's', long = "set")]
+        set: bool,
+    },
     /// Show board information
     Board,
     /// Monitor GPU statistics
-    Gpu,
+    Gpu {
+        /// nvidia-smi style field list, e.g. index,name,utilization.gpu,memory.used
+        /// (use with --format=csv[,noheader][,nounits])
+        #[arg(long = "query-gpu", value_name = "FIELDS")]
+        query_gpu: Option<String>,
+        /// nvidia-smi style per-process field list, e.g. pid,process_name,used_memory
+        #[arg(
+            long = "query-compute-apps",
+            value_name = "FIELDS",
+            conflicts_with = "query_gpu"
+        )]
+        query_compute_apps: Option<String>,
+        /// Repeat the query every SEC seconds (5 if no value is given)
+        #[arg(short = 'l', long = "loop", value_name = "SEC", num_args = 0..=1, default_missing_value = "5")]
+        loop_secs: Option<f64>,
+    },
     /// Monitor CPU statistics
     Cpu,
     /// Monitor memory statistics
@@ -51,6 +152,47 @@ enum Commands {
     All,
     /// Interactive real-time monitoring mode
     Monitor,
+    /// Run a command and report its CPU, memory, I/O, GPU and energy usage
+    Run {
+        /// Write the JSON summary to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Electricity price per kWh for the cost estimate
+        #[arg(long, default_value_t = simon::job_accounting::DEFAULT_PRICE_PER_KWH)]
+        price_per_kwh: f64,
+        /// Command to run, after `--`
+        #[arg(trailing_var_arg = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Show hardware inventory from the SMBIOS tables (like `dmidecode`)
+    Inventory,
+    /// Show GPU/NIC PCIe topology and NUMA affinity (like `nvidia-smi topo -m`)
+    Topo,
+    /// Show NIC driver, link, ring, offload and drop statistics (like `ethtool`)
+    #[cfg(target_os = "linux")]
+    Ethtool {
+        /// Interface name (all physical interfaces if omitted)
+        interface: Option<String>,
+        /// Print every driver statistic (like `ethtool -S`)
+        #[arg(short = 'S', long)]
+        stats: bool,
+        /// Print drop counter rates every interval until interrupted
+        #[arg(short, long)]
+        watch: bool,
+    },
+    /// Show per-process network bandwidth (like `nethogs`)
+    #[cfg(target_os = "linux")]
+    Nethogs {
+        /// Show individual connections instead of processes
+        #[arg(long)]
+        connections: bool,
+        /// Show totals per container
+        #[arg(long)]
+        containers: bool,
+        /// Stop after this many refreshes (runs until interrupted if omitted)
+        #[arg(short, long)]
+        count: Option<u32>,
+    },
     /// Ask AI agent about system state
     Ai {
         /// Question to ask the AI agent (if not provided, enters interactive mode)
@@ -73,6 +215,37 @@ enum Commands {
         #[command(subcommand)]
         action: SwapAction,
     },
+    /// Print tegrastats-compatible lines every --interval seconds, or read a tegrastats log
+    Tegrastats {
+        /// Append lines to this file instead of printing them (like `tegrastats --logfile`)
+        #[arg(long)]
+        logfile: Option<PathBuf>,
+        /// Parse a tegrastats log and print each sample (JSON lines with --format json)
+        #[arg(long, value_name = "FILE", conflicts_with = "logfile")]
+        parse: Option<PathBuf>,
+        /// Stop after this many samples (runs until interrupted if omitted)
+        #[arg(short, long)]
+        count: Option<u32>,
+        /// Leave out the timestamp prefix, as tegrastats on L4T 32 does
+        #[arg(long)]
+        no_timestamp: bool,
+    },
+    /// Serve stats every --interval seconds to unprivileged clients over a Unix socket (like jtop's service)
+    #[cfg(target_os = "linux")]
+    JetsonService {
+        /// Unix socket to listen on (or query)
+        #[arg(long, default_value = simon::daemon::DEFAULT_JETSON_SOCKET)]
+        socket: PathBuf,
+        /// Group whose members may control fans, nvpmodel and jetson_clocks
+        #[arg(long, default_value = simon::daemon::DEFAULT_CONTROL_GROUP, conflicts_with = "root_only")]
+        group: String,
+        /// Allow only root to send control requests
+        #[arg(long)]
+        root_only: bool,
+        /// Query a running service instead of starting one
+        #[arg(long)]
+        query: bool,
+    },
 }
 
 #[cfg(feature = "cli")]
@@ -111,6 +284,15 @@ enum NvpmodelAction {
         #[arg(short, long)]
         force: bool,
     },
+    /// Show each mode's CPU, GPU, EMC, DLA and TPC settings from nvpmodel.conf
+    Modes,
+    /// Show settings of the active mode that differ from sysfs
+    Drift,
+    /// Show what settings a power mode would change, without applying it
+    Preview {
+        /// Mode ID
+        mode_id: u32,
+    },
 }
 
 #[cfg(feature = "cli")]
@@ -157,14 +339,96 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match &cli.command {
         // TUI command - Terminal User Interface
-        Some(Commands::Tui) => {
-            simon::tui::run()?;
-        }
+        Some(Commands::Tui { attach, token }) => match attach {
+            Some(endpoint) => simon::tui::attach(daemon_client(endpoint, token.as_deref())?)?,
+            None => simon::tui::run()?,
+        },
 
         // GUI command - Graphical User Interface
         #[cfg(feature = "gui")]
-        Some(Commands::Gui) => {
-            simon::gui::run().map_err(|e| format!("GUI error: {}", e))?;
+        Some(Commands::Gui { attach, token }) => {
+            let client = attach
+                .as_deref()
+                .map(|endpoint| daemon_client(endpoint, token.as_deref()))
+                .transpose()?;
+            simon::gui::run_with(client).map_err(|e| format!("GUI error: {}", e))?;
+        }
+
+        // Fleet view across simond daemons
+        Some(Commands::Fleet {
+            hosts,
+            tag,
+            token,
+            timeout,
+            once,
+            worst,
+            rank,
+        }) => {
+            let mut monitor = simon::FleetMonitor::new(simon::fleet::load_host_list(hosts)?)
+                .with_timeout(Duration::from_secs_f64(timeout.max(0.1)));
+            if let Some(token) = token.clone().or_else(|| std::env::var("SIMOND_TOKEN").ok()) {
+                monitor = monitor.with_token(token);
+            }
+            if let Some(tag) = tag {
+                monitor = monitor.with_tag_filter(tag);
+            }
+            if monitor.hosts().is_empty() {
+                return Err("no hosts to monitor".into());
+            }
+            if *once {
+                handle_fleet_once(&monitor, *worst, rank.parse()?, &cli.format)?;
+            } else {
+                simon::tui::fleet(monitor, Duration::from_secs_f64(cli.interval.max(0.5)))?;
+            }
+        }
+
+        // OpenTelemetry metrics push
+        Some(Commands::Otlp {
+            endpoint,
+            protocol,
+            headers,
+            buffer_dir,
+            batch,
+            count,
+            no_health,
+        }) => {
+            let mut config = simon::OtlpConfig::from_env();
+            if let Some(endpoint) = endpoint {
+                config = config.with_endpoint(endpoint);
+            }
+            if let Some(protocol) = protocol {
+                config = config.with_protocol(protocol.parse()?);
+            }
+            for header in headers {
+                let (name, value) = header
+                    .split_once('=')
+                    .ok_or_else(|| format!("--header expects name=value, got '{}'", header))?;
+                config = config.with_header(name.trim(), value.trim());
+            }
+            if let Some(dir) = buffer_dir {
+                config = config.with_buffer_dir(dir);
+            }
+            handle_otlp(config, (*batch).max(1), *count, !no_health, cli.interval)?;
+        }
+
+        // InfluxDB / StatsD sinks
+        Some(Commands::Sinks { config, count }) => {
+            let config = match config {
+                Some(path) => simon::config::Config::load_from(path)?,
+                None => simon::config::Config::load()?,
+            };
+            handle_sinks(&config, *count, cli.interval)?;
+        }
+
+        // lm-sensors compatible output
+        Some(Commands::Sensors {
+            chips,
+            json,
+            config_file,
+            set,
+        }) => {
+            let json = *json || cli.format == "json";
+            handle_sensors(chips, json, config_file.as_deref(), *set)?;
         }
 
         // Monitoring commands
@@ -177,7 +441,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 print_board_info(board);
             }
         }
-        Some(Commands::Gpu) => {
+        Some(Commands::Gpu {
+            query_gpu,
+            query_compute_apps,
+            loop_secs,
+        }) if query_gpu.is_some() || query_compute_apps.is_some() => {
+            let query = match (query_gpu, query_compute_apps) {
+                (Some(fields), _) => simon::gpu::query::Query::gpu(fields)?,
+                (None, Some(fields)) => simon::gpu::query::Query::compute_apps(fields)?,
+                (None, None) => unreachable!(),
+            };
+            handle_gpu_query(&query, cli.format.parse()?, *loop_secs)?;
+        }
+        Some(Commands::Gpu { .. }) => {
             let mut stats = Simon::with_interval(cli.interval)?;
             let snapshot = stats.snapshot()?;
             if cli.format == "json" {
@@ -241,6 +517,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        // Job accounting command
+        Some(Commands::Run {
+            output,
+            price_per_kwh,
+            command,
+        }) => {
+            let code = handle_run(
+                command,
+                output.as_deref(),
+                *price_per_kwh,
+                cli.interval,
+                &cli.format,
+            )?;
+            std::process::exit(code);
+        }
+
+        // SMBIOS hardware inventory command
+        Some(Commands::Inventory) => {
+            handle_inventory(&cli.format)?;
+        }
+
+        // PCIe topology command
+        Some(Commands::Topo) => {
+            handle_topo(&cli.format)?;
+        }
+
+        // Ethtool NIC statistics command
+        #[cfg(target_os = "linux")]
+        Some(Commands::Ethtool {
+            interface,
+            stats,
+            watch,
+        }) => {
+            handle_ethtool(
+                interface.as_deref(),
+                *stats,
+                *watch,
+                cli.interval,
+                &cli.format,
+            )?;
+        }
+
+        // Per-process network bandwidth command
+        #[cfg(target_os = "linux")]
+        Some(Commands::Nethogs {
+            connections,
+            containers,
+            count,
+        }) => {
+            handle_nethogs(*connections, *containers, *count, cli.interval, &cli.format)?;
+        }
+
         // AI Agent command
         Some(Commands::Ai { query }) => {
             handle_ai_query(query.as_deref())?;
@@ -261,6 +589,33 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             handle_swap(action)?;
         }
 
+        // tegrastats emitter / log parser
+        Some(Commands::Tegrastats {
+            logfile,
+            parse,
+            count,
+            no_timestamp,
+        }) => match parse {
+            Some(path) => handle_tegrastats_log(path, &cli.format)?,
+            None => handle_tegrastats(logfile.as_deref(), *count, !no_timestamp, cli.interval)?,
+        },
+
+        // jtop-style stats service
+        #[cfg(target_os = "linux")]
+        Some(Commands::JetsonService {
+            socket,
+            group,
+            root_only,
+            query,
+        }) => {
+            if *query {
+                handle_jetson_service_query(socket, &cli.format)?;
+            } else {
+                let group = (!root_only).then(|| group.clone());
+                handle_jetson_service(socket, group, cli.interval)?;
+            }
+        }
+
         // Interactive monitoring mode
         Some(Commands::Monitor) => {
             let stats = Simon::with_interval(cli.interval)?;
@@ -477,12 +832,12 @@ fn handle_jetson_clocks(action: &JetsonClocksAction) -> Result<(), Box<dyn std::
     match action {
         JetsonClocksAction::Enable => {
             println!("Enabling jetson_clocks (maximizing performance)...");
-            clocks::enable()?;
+            print_clock_report(&clocks::enable()?)?;
             println!("jetson_clocks enabled successfully");
         }
         JetsonClocksAction::Disable => {
             println!("Disabling jetson_clocks (restoring settings)...");
-            clocks::disable()?;
+            print_clock_report(&clocks::disable()?)?;
             println!("jetson_clocks disabled successfully");
         }
         JetsonClocksAction::Status => {
@@ -504,11 +859,33 @@ fn handle_jetson_clocks(action: &JetsonClocksAction) -> Result<(), Box<dyn std::
     Ok(())
 }
 
+/// Print one line per jetson_clocks domain; errors if any domain failed
+#[cfg(feature = "cli")]
+fn print_clock_report(
+    report: &simon::utils::clocks::ClockReport,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for result in &report.results {
+        match &result.error {
+            None => println!("  {} {}: ok", result.domain, result.name),
+            Some(error) => println!("  {} {}: FAILED ({})", result.domain, result.name, error),
+        }
+    }
+    match report.failures().count() {
+        0 => Ok(()),
+        failed => Err(format!("{} of {} domains failed", failed, report.results.len()).into()),
+    }
+}
+
 #[cfg(feature = "cli")]
 fn handle_nvpmodel(action: &NvpmodelAction) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::utils::nvpmodel_conf::NvpmodelConfig;
     use simon::utils::power_mode;
 
-    if !power_mode::is_available() {
+    let native = matches!(
+        action,
+        NvpmodelAction::Modes | NvpmodelAction::Drift | NvpmodelAction::Preview { .. }
+    );
+    if !native && !power_mode::is_available() {
         eprintln!("nvpmodel is not available on this system");
         std::process::exit(1);
     }
@@ -549,6 +926,11 @@ fn handle_nvpmodel(action: &NvpmodelAction) -> Result<(), Box<dyn std::error::Er
             println!("  ID: {} - {}", status.default.id, status.default.name);
         }
         NvpmodelAction::Set { mode_id, force } => {
+            if let Ok(changes) = power_mode::preview_mode(*mode_id) {
+                for change in &changes {
+                    println!("  {}", change);
+                }
+            }
             println!("Setting power mode to ID {}...", mode_id);
             power_mode::set_mode(*mode_id, *force)?;
             println!("Power mode set successfully");
@@ -566,6 +948,53 @@ fn handle_nvpmodel(action: &NvpmodelAction) -> Result<(), Box<dyn std::error::Er
             let mode = power_mode::query()?;
             println!("New mode: {} ({})", mode.name, mode.id);
         }
+        NvpmodelAction::Modes => {
+            let config = NvpmodelConfig::load_system()?;
+            for mode in &config.modes {
+                let default = if config.default_mode == Some(mode.id) {
+                    " (default)"
+                } else {
+                    ""
+                };
+                println!("=== {} - {}{} ===", mode.id, mode.name, default);
+                println!("  CPU online mask: {:#x}", mode.cpu_online_mask());
+                for cluster in mode.clusters().iter().chain(mode.gpu().iter()) {
+                    println!(
+                        "  {}: min {} max {}",
+                        cluster.name,
+                        cluster.min.map_or("-".to_string(), |f| f.to_string()),
+                        cluster.max.map_or("-".to_string(), |f| f.to_string())
+                    );
+                }
+                if let Some(cap) = mode.emc_cap() {
+                    println!("  EMC cap: {}", cap);
+                }
+                for (name, cap) in mode.dla_caps() {
+                    println!("  {} cap: {}", name, cap);
+                }
+                if let Some(mask) = mode.tpc_pg_mask() {
+                    println!("  TPC PG mask: {:#x}", mask);
+                }
+            }
+        }
+        NvpmodelAction::Drift => {
+            let drift = power_mode::drift()?;
+            if drift.is_empty() {
+                println!("Active power mode matches sysfs");
+            }
+            for diff in &drift {
+                println!("{}", diff);
+            }
+        }
+        NvpmodelAction::Preview { mode_id } => {
+            let changes = power_mode::preview_mode(*mode_id)?;
+            if changes.is_empty() {
+                println!("Mode {} would not change anything", mode_id);
+            }
+            for change in &changes {
+                println!("{}", change);
+            }
+        }
     }
 
     Ok(())
@@ -696,6 +1125,734 @@ fn print_engine_info(engines: &simon::core::engine::EngineStats) {
     }
 }
 
+#[cfg(feature = "cli")]
+fn handle_inventory(format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::motherboard::{smbios, Error};
+
+    let inventory = match smbios::read_inventory() {
+        Ok(inventory) => inventory,
+        Err(Error::PermissionDenied(msg)) => {
+            eprintln!("{}; run as root for the full inventory", msg);
+            std::process::exit(1);
+        }
+        Err(Error::NotSupported(msg)) => {
+            eprintln!("No SMBIOS tables available: {}", msg);
+            std::process::exit(1);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&inventory)?);
+    } else {
+        print!("{}", inventory);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn handle_topo(format: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::gpu::GpuCollection;
+    use simon::topology::Topology;
+
+    let mut topo = Topology::discover()?;
+    if let Ok(gpus) = GpuCollection::auto_detect() {
+        topo.attach_gpus(&gpus);
+    }
+    for device in simon::gpu::enumerate_devices() {
+        topo.attach_nvlinks(device.as_ref());
+    }
+
+    if format == "json" {
+        let report = serde_json::json!({
+            "devices": &topo,
+            "matrix": topo.matrix(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if topo.gpus.is_empty() && topo.nics.is_empty() {
+        println!("No GPUs or NICs found");
+        return Ok(());
+    }
+    println!("{}", topo.matrix());
+    Ok(())
+}
+
+#[cfg(all(feature = "cli", target_os = "linux"))]
+fn handle_ethtool(
+    interface: Option<&str>,
+    all_stats: bool,
+    watch: bool,
+    interval: f64,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::ethtool::{physical_interfaces, Ethtool, EthtoolMonitor};
+
+    let interfaces = match interface {
+        Some(name) => vec![name.to_string()],
+        None => physical_interfaces(),
+    };
+
+    if watch {
+        let mut monitor = EthtoolMonitor::new()?.with_interfaces(interfaces);
+        monitor.refresh()?;
+        loop {
+            std::thread::sleep(Duration::from_secs_f64(interval.max(0.1)));
+            let rates = monitor.refresh()?;
+            if format == "json" {
+                println!("{}", serde_json::to_string(&rates)?);
+            } else if rates.is_empty() {
+                println!("no drops");
+            } else {
+                for rate in &rates {
+                    println!(
+                        "{:<12} {:<40} {:>10.1}/s (+{})",
+                        rate.interface, rate.counter, rate.per_sec, rate.delta
+                    );
+                }
+                println!();
+            }
+        }
+    }
+
+    let ethtool = Ethtool::new()?;
+    let infos = interfaces
+        .iter()
+        .map(|name| ethtool.info(name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if format == "json" {
+        println!("{}", serde_json::to_string_pretty(&infos)?);
+        return Ok(());
+    }
+    if infos.is_empty() {
+        println!("No physical network interfaces found");
+    }
+    for info in &infos {
+        print!("{}", info);
+        if all_stats {
+            println!("  Statistics:");
+            for (name, value) in &info.stats {
+                println!("    {:<40} {}", name, value);
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// Client for `--attach`, taking the token from `$SIMOND_TOKEN` if not given
+#[cfg(feature = "cli")]
+fn daemon_client(
+    endpoint: &str,
+    token: Option<&str>,
+) -> Result<simon::DaemonClient, Box<dyn std::error::Error>> {
+    let mut client = simon::DaemonClient::new(endpoint.parse()?);
+    if let Some(token) = token
+        .map(str::to_string)
+        .or_else(|| std::env::var("SIMOND_TOKEN").ok())
+    {
+        client = client.with_token(token);
+    }
+    // Fail early with a clear message rather than inside the UI
+    client.info()?;
+    Ok(client)
+}
+
+/// Sample every `interval` seconds and push every `batch` samples
+#[cfg(feature = "cli")]
+fn handle_otlp(
+    config: simon::OtlpConfig,
+    batch: u32,
+    count: Option<u32>,
+    health: bool,
+    interval: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::daemon::{BackendSampler, DaemonConfig, Sampler};
+    use simon::hwmon::HardwareMonitor;
+    use simon::otlp::Resource;
+
+    let interval = Duration::from_secs_f64(interval.max(0.1));
+    let mut sampler = BackendSampler::new(&DaemonConfig::default().with_interval(interval))?;
+    let mut sensors = HardwareMonitor::new();
+    let first = sampler.sample()?;
+    let resource = Resource::detect().with_gpus(&first.gpus);
+
+    eprintln!(
+        "Exporting to {} ({}) every {:.1}s, {} samples per request",
+        config.endpoint,
+        config.protocol,
+        interval.as_secs_f64(),
+        batch
+    );
+    let mut exporter = simon::OtlpExporter::new(config, resource);
+    let mut snapshot = Some(first);
+    let mut samples = 0u32;
+    loop {
+        let snapshot = match snapshot.take() {
+            Some(snapshot) => snapshot,
+            None => sampler.sample()?,
+        };
+        sensors.refresh();
+        let mut metrics = exporter.batch();
+        metrics
+            .record_snapshot(&snapshot)
+            .record_sensors(sensors.all_sensors());
+        if health {
+            if let Ok(health) = sampler.health() {
+                metrics.record_health(&health);
+            }
+        }
+        exporter.record(metrics);
+        samples += 1;
+
+        let done = count.is_some_and(|count| samples >= count);
+        if samples.is_multiple_of(batch) || done {
+            match exporter.flush() {
+                Ok(report) => {
+                    if let Some(error) = &report.error {
+                        eprintln!(
+                            "Collector unavailable ({}), {} requests buffered",
+                            error,
+                            exporter.buffered_requests()
+                        );
+                    }
+                    log::info!(
+                        "sent {} points in {} requests, replayed {} buffered",
+                        report.points_sent,
+                        report.batches_sent,
+                        report.batches_replayed
+                    );
+                }
+                Err(e) => eprintln!("Export failed: {}", e),
+            }
+        }
+        if done {
+            return Ok(());
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// Sample as often as the fastest sink needs and publish to every due sink
+#[cfg(feature = "cli")]
+fn handle_sinks(
+    config: &simon::config::Config,
+    count: Option<u32>,
+    interval: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::backend::{BackendConfig, MonitoringBackend};
+    use simon::hwmon::HardwareMonitor;
+    use simon::sinks::{flatten_sensors, flatten_state, SinkRunner};
+    use std::time::{Instant, SystemTime};
+
+    let default_interval = Duration::from_secs_f64(interval.max(0.1));
+    let mut runner = SinkRunner::from_config(&config.sinks, default_interval)?;
+    if runner.is_empty() {
+        return Err("no [[sinks]] configured; see `simon::sinks` for the format".into());
+    }
+    let mut backend = MonitoringBackend::with_config(BackendConfig::without_agent())?;
+    let mut sensors = HardwareMonitor::new();
+
+    let mut samples = 0u32;
+    loop {
+        backend.update()?;
+        sensors.refresh();
+        let mut measurements = flatten_state(&backend.get_full_system_state());
+        measurements.extend(flatten_sensors(sensors.all_sensors()));
+        for (sink, error) in runner.publish(Instant::now(), SystemTime::now(), &measurements) {
+            eprintln!("{}: {}", sink, error);
+        }
+        samples += 1;
+        if count.is_some_and(|count| samples >= count) {
+            return Ok(());
+        }
+        std::thread::sleep(runner.until_next(Instant::now()));
+    }
+}
+
+/// Print (or append to `logfile`) a tegrastats line per sample
+#[cfg(feature = "cli")]
+fn handle_tegrastats(
+    logfile: Option<&std::path::Path>,
+    count: Option<u32>,
+    timestamp: bool,
+    interval: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::utils::tegrastats::TegrastatsLine;
+    use simon::Simon;
+    use std::io::Write;
+
+    let mut out: Box<dyn Write> = match logfile {
+        Some(path) => Box::new(
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?,
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut stats = Simon::with_interval(interval)?;
+    let mut samples = 0u32;
+    loop {
+        let mut line = TegrastatsLine::from_snapshot(&stats.snapshot()?);
+        if timestamp {
+            line = line.with_timestamp(chrono::Local::now().naive_local());
+        }
+        writeln!(out, "{}", line)?;
+        out.flush()?;
+        samples += 1;
+        if count.is_some_and(|count| samples >= count) {
+            return Ok(());
+        }
+        std::thread::sleep(stats.interval().max(Duration::from_millis(100)));
+    }
+}
+
+/// Print the samples of a tegrastats log as JSON lines or normalized tegrastats lines
+#[cfg(feature = "cli")]
+fn handle_tegrastats_log(
+    path: &std::path::Path,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::utils::tegrastats::{read_log, TegrastatsLine};
+
+    for line in read_log(path)? {
+        let snapshot = line.to_snapshot();
+        if format == "json" {
+            let record = serde_json::json!({
+                "timestamp": line.timestamp.map(|t| t.to_string()),
+                "snapshot": snapshot,
+            });
+            println!("{}", serde_json::to_string(&record)?);
+        } else {
+            let mut normalized = TegrastatsLine::from_snapshot(&snapshot);
+            normalized.timestamp = line.timestamp;
+            println!("{}", normalized);
+        }
+    }
+    Ok(())
+}
+
+/// Run the jtop-style service until interrupted
+#[cfg(all(feature = "cli", target_os = "linux"))]
+fn handle_jetson_service(
+    socket: &std::path::Path,
+    group: Option<String>,
+    interval: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::daemon::{JetsonService, JetsonServiceConfig};
+
+    let config = JetsonServiceConfig::default()
+        .with_socket_path(socket)
+        .with_interval(Duration::from_secs_f64(interval.max(0.1)))
+        .with_control_group(group.clone());
+    let service = JetsonService::start(config)?;
+    eprintln!(
+        "Serving Jetson stats on {} (control: {})",
+        service.socket_path().display(),
+        match &group {
+            Some(group) => format!("root and group '{}'", group),
+            None => "root only".to_string(),
+        }
+    );
+    service.run();
+    Ok(())
+}
+
+/// Print a running service's settings and latest sample
+#[cfg(all(feature = "cli", target_os = "linux"))]
+fn handle_jetson_service_query(
+    socket: &std::path::Path,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client = simon::daemon::JetsonServiceClient::new(socket);
+    let info = client.info()?;
+    let stats = client.stats()?;
+    if format == "json" {
+        let record = serde_json::json!({ "info": info, "stats": stats });
+        println!("{}", serde_json::to_string_pretty(&record)?);
+        return Ok(());
+    }
+    println!("simon service {} on {}", info.version, socket.display());
+    println!("Interval:      {} ms", info.interval_ms);
+    println!(
+        "Control:       {}",
+        if info.can_control {
+            "allowed"
+        } else {
+            "denied"
+        }
+    );
+    println!("Sample:        #{}", stats.sequence);
+    println!(
+        "nvpmodel:      {}",
+        stats
+            .nvpmodel
+            .map_or_else(|| "unknown".to_string(), |mode| mode.to_string())
+    );
+    println!(
+        "jetson_clocks: {}",
+        if stats.jetson_clocks {
+            "active"
+        } else {
+            "inactive"
+        }
+    );
+    println!(
+        "RAM:           {:.1}%",
+        stats.snapshot.memory.ram_usage_percent()
+    );
+    Ok(())
+}
+
+/// Print nvidia-smi style CSV, once or every `loop_secs` seconds
+#[cfg(feature = "cli")]
+fn handle_gpu_query(
+    query: &simon::gpu::query::Query,
+    format: simon::gpu::query::QueryFormat,
+    loop_secs: Option<f64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::gpu::query::timestamp;
+    use std::io::Write;
+
+    let gpus = simon::gpu::GpuCollection::auto_detect()?;
+    let mut stdout = std::io::stdout();
+    if format.header {
+        writeln!(stdout, "{}", query.header())?;
+    }
+    loop {
+        for row in query.rows(&gpus.snapshot_all()?, format, &timestamp()) {
+            writeln!(stdout, "{}", row)?;
+        }
+        stdout.flush()?;
+        match loop_secs {
+            Some(secs) => std::thread::sleep(Duration::from_secs_f64(secs.max(0.1))),
+            None => return Ok(()),
+        }
+    }
+}
+
+/// Print (or with `set`, configure) hwmon chips like `sensors`
+#[cfg(feature = "cli")]
+fn handle_sensors(
+    patterns: &[String],
+    json: bool,
+    config_file: Option<&std::path::Path>,
+    set: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::hwmon::{read_chips, sensors_json, sensors_text, ChipPattern, SensorsConfig};
+
+    let config = match config_file {
+        Some(path) => SensorsConfig::load(path)?,
+        None => SensorsConfig::load_system(),
+    };
+    let patterns = patterns
+        .iter()
+        .map(|p| p.parse())
+        .collect::<Result<Vec<ChipPattern>, _>>()?;
+    let mut chips = read_chips();
+    chips.retain(|chip| patterns.is_empty() || patterns.iter().any(|p| p.matches(&chip.name)));
+    if chips.is_empty() {
+        return Err(if patterns.is_empty() {
+            "No sensors found!"
+        } else {
+            "Specified sensor(s) not found!"
+        }
+        .into());
+    }
+
+    if set {
+        for operation in config.set_operations(&chips)? {
+            match operation.apply() {
+                Ok(()) => println!(
+                    "{}: {} = {}",
+                    operation.chip, operation.attribute, operation.value
+                ),
+                Err(e) => eprintln!("{}: {}: {}", operation.chip, operation.attribute, e),
+            }
+        }
+        return Ok(());
+    }
+
+    config.apply(&mut chips);
+    if json {
+        println!("{}", sensors_json(&chips));
+    } else {
+        print!("{}", sensors_text(&chips));
+    }
+    Ok(())
+}
+
+/// Poll the fleet once and print a table (or JSON)
+#[cfg(feature = "cli")]
+fn handle_fleet_once(
+    monitor: &simon::FleetMonitor,
+    worst: Option<usize>,
+    ranking: simon::GpuRanking,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let statuses = monitor.poll();
+
+    if format == "json" {
+        let hosts: Vec<_> = statuses
+            .iter()
+            .map(|status| {
+                serde_json::json!({
+                    "name": status.name,
+                    "endpoint": status.endpoint,
+                    "tags": status.tags,
+                    "up": status.is_up(),
+                    "error": status.error,
+                    "latency_ms": status.latency_ms,
+                    "summary": status.summary(),
+                })
+            })
+            .collect();
+        let mut output = serde_json::json!({ "hosts": hosts });
+        if let Some(n) = worst {
+            output["worst_gpus"] =
+                serde_json::to_value(simon::fleet::worst_gpus(&statuses, ranking, n))?;
+        }
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    let percent = |value: Option<f32>| value.map_or("-".to_string(), |v| format!("{:.0}%", v));
+    println!(
+        "{:<20} {:>4} {:>6} {:>7} {:>6} {:>6} {:>6} {:>7}  FAILING",
+        "HOST", "GPUS", "GPU", "GPUMEM", "TEMP", "CPU", "MEM", "HEALTH"
+    );
+    for status in &statuses {
+        if let Some(error) = &status.error {
+            println!("{:<20} DOWN ({})", status.name, error);
+            continue;
+        }
+        let summary = status.summary();
+        println!(
+            "{:<20} {:>4} {:>6} {:>7} {:>6} {:>6} {:>6} {:>7}  {}",
+            status.name,
+            summary.gpu_count,
+            percent(summary.gpu_utilization),
+            percent(summary.gpu_memory_percent()),
+            summary
+                .max_gpu_temperature
+                .map_or("-".to_string(), |t| format!("{:.0}C", t)),
+            percent(summary.cpu_utilization),
+            percent(summary.memory_percent),
+            summary
+                .health_score
+                .map_or("-".to_string(), |score| score.to_string()),
+            summary.failing_checks.join(", ")
+        );
+    }
+    let up = statuses.iter().filter(|status| status.is_up()).count();
+    println!("\n{}/{} hosts up", up, statuses.len());
+
+    if let Some(n) = worst {
+        println!("\nWorst GPUs by {}:", ranking);
+        for gpu in simon::fleet::worst_gpus(&statuses, ranking, n) {
+            println!(
+                "  {:<20} GPU{:<2} {:<24} {:>4.0}% util {:>4.0}% mem {:>6}",
+                gpu.host,
+                gpu.index,
+                gpu.name,
+                gpu.utilization,
+                gpu.memory_percent,
+                gpu.temperature
+                    .map_or("-".to_string(), |t| format!("{:.0}C", t)),
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "cli", target_os = "linux"))]
+fn handle_nethogs(
+    connections: bool,
+    containers: bool,
+    count: Option<u32>,
+    interval: f64,
+    format: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simon::process_network::ProcessNetMonitor;
+
+    let rate = |bytes_per_sec: f64| format!("{:.1} KB/s", bytes_per_sec / 1024.0);
+    let mut monitor = ProcessNetMonitor::new()?;
+    monitor.refresh()?;
+
+    let mut refreshes = 0;
+    while count.is_none_or(|n| refreshes < n) {
+        std::thread::sleep(Duration::from_secs_f64(interval.max(0.1)));
+        let usage = monitor.refresh()?;
+        refreshes += 1;
+
+        if format == "json" {
+            println!("{}", serde_json::to_string(&usage)?);
+            continue;
+        }
+        if connections {
+            println!(
+                "{:>7}  {:<45} {:<45} {:>12} {:>12} {:>8} {:>6}",
+                "PID", "LOCAL", "REMOTE", "SENT", "RECEIVED", "RTT", "RETX"
+            );
+            for conn in usage.connections.iter().take(25) {
+                println!(
+                    "{:>7}  {:<45} {:<45} {:>12} {:>12} {:>8} {:>6}",
+                    conn.pid.map_or("?".to_string(), |p| p.to_string()),
+                    conn.local.to_string(),
+                    conn.remote.to_string(),
+                    rate(conn.tx_bytes_per_sec),
+                    rate(conn.rx_bytes_per_sec),
+                    conn.rtt_ms
+                        .map_or("-".to_string(), |r| format!("{:.1}ms", r)),
+                    conn.retransmits
+                );
+            }
+        } else if containers {
+            println!(
+                "{:<14} {:>6} {:>12} {:>12} {:>14} {:>14}",
+                "CONTAINER", "PROCS", "SENT", "RECEIVED", "NETNS SENT", "NETNS RECV"
+            );
+            for c in &usage.containers {
+                println!(
+                    "{:<14} {:>6} {:>12} {:>12} {:>14} {:>14}",
+                    c.container,
+                    c.pids.len(),
+                    rate(c.tx_bytes_per_sec),
+                    rate(c.rx_bytes_per_sec),
+                    c.netns_tx_bytes_per_sec.map_or("-".to_string(), rate),
+                    c.netns_rx_bytes_per_sec.map_or("-".to_string(), rate)
+                );
+            }
+        } else {
+            println!(
+                "{:>7}  {:<20} {:<14} {:>12} {:>12} {:>6} {:>6}",
+                "PID", "PROGRAM", "CONTAINER", "SENT", "RECEIVED", "CONNS", "RETX"
+            );
+            for p in usage.processes.iter().take(25) {
+                println!(
+                    "{:>7}  {:<20} {:<14} {:>12} {:>12} {:>6} {:>6}",
+                    p.pid,
+                    p.name,
+                    p.container.as_deref().unwrap_or("-"),
+                    rate(p.tx_bytes_per_sec),
+                    rate(p.rx_bytes_per_sec),
+                    p.tcp_connections + p.udp_sockets,
+                    p.retransmits
+                );
+            }
+            if usage.unattributed_rx_bytes_per_sec + usage.unattributed_tx_bytes_per_sec > 0.0 {
+                println!(
+                    "{:>7}  {:<20} {:<14} {:>12} {:>12}",
+                    "?",
+                    "unknown TCP",
+                    "-",
+                    rate(usage.unattributed_tx_bytes_per_sec),
+                    rate(usage.unattributed_rx_bytes_per_sec)
+                );
+            }
+        }
+        println!();
+    }
+    Ok(())
+}
+
+#[cfg(feature = "cli")]
+fn handle_run(
+    command: &[String],
+    output: Option<&std::path::Path>,
+    price_per_kwh: f64,
+    interval: f64,
+    format: &str,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    use simon::job_accounting::{run_job, JobConfig};
+
+    let config = JobConfig::default()
+        .with_sample_interval(Duration::from_secs_f64(interval.max(0.05)))
+        .with_price_per_kwh(price_per_kwh);
+
+    let summary = run_job(command, &config)?;
+
+    // Keep the command's own stdout clean: JSON goes to the output file,
+    // or to stderr like the text summary
+    if let Some(path) = output {
+        std::fs::write(path, serde_json::to_string_pretty(&summary)?)?;
+    }
+    if format != "json" {
+        print_job_summary(&summary);
+    } else if output.is_none() {
+        eprintln!("{}", serde_json::to_string_pretty(&summary)?);
+    }
+
+    // Mirror the child's exit status like a shell does: 128 + N when killed by signal N
+    Ok(summary
+        .exit_code
+        .or(summary.signal.map(|signal| 128 + signal))
+        .unwrap_or(1))
+}
+
+#[cfg(feature = "cli")]
+fn print_job_summary(summary: &simon::job_accounting::JobSummary) {
+    // Report on stderr so the command's own stdout stays clean
+    eprintln!("\n=== Job Summary ===");
+    eprintln!("Command: {}", summary.command.join(" "));
+    match (summary.exit_code, summary.signal) {
+        (Some(code), _) => eprintln!("Exit status: {}", code),
+        (None, Some(signal)) => eprintln!("Exit status: terminated by signal {}", signal),
+        (None, None) => eprintln!("Exit status: terminated by signal"),
+    }
+    eprintln!("Wall time: {:.2}s", summary.wall_time_secs);
+    eprintln!(
+        "CPU time: {:.2}s user, {:.2}s system ({:.0}% avg, {:.0}% peak)",
+        summary.cpu.user_secs,
+        summary.cpu.system_secs,
+        summary.cpu.avg_percent,
+        summary.cpu.peak_percent
+    );
+    eprintln!(
+        "Memory: {} peak, {} avg ({} largest process, {} processes max)",
+        format_size(summary.memory.peak_rss_bytes / 1024),
+        format_size(summary.memory.avg_rss_bytes / 1024),
+        format_size(summary.memory.peak_process_rss_bytes / 1024),
+        summary.memory.peak_processes
+    );
+    eprintln!(
+        "Disk I/O: {} read, {} written",
+        format_size(summary.io.read_bytes / 1024),
+        format_size(summary.io.write_bytes / 1024)
+    );
+
+    for gpu in summary.gpus.iter().filter(|g| g.peak_memory_bytes > 0) {
+        eprintln!(
+            "GPU {} ({}): {} peak memory, {:.1}s engine time ({:.0}% avg)",
+            gpu.index,
+            gpu.name,
+            format_size(gpu.peak_memory_bytes / 1024),
+            gpu.engine_secs,
+            gpu.avg_utilization
+        );
+    }
+
+    let energy = &summary.energy;
+    if let Some(cpu) = energy.cpu_package_joules {
+        eprintln!("CPU package energy: {:.1} J", cpu);
+    }
+    if let Some(gpu) = energy.gpu_joules {
+        eprintln!("GPU energy: {:.1} J", gpu);
+    }
+    eprintln!(
+        "Total energy: {:.1} J ({:.4} kWh, {:.1} W avg)",
+        energy.total_joules, energy.total_kwh, energy.avg_watts
+    );
+    eprintln!(
+        "Estimated cost: {:.4} (at {:.3}/kWh)",
+        summary.estimated_cost, summary.price_per_kwh
+    );
+}
+
 #[cfg(feature = "cli")]
 fn handle_ai_query(query: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
     use simon::agent::{Agent, AgentConfig};
@@ -0,0 +1,107 @@
+//! Silicon Monitor daemon (simond)
+//!
+//! Samples the monitoring backend once and serves the results to the TUI,
+//! GUI and scripts over a Unix socket and, optionally, TCP. See
+//! `simon::daemon` for the API.
+
+#[cfg(feature = "cli")]
+use clap::Parser;
+#[cfg(feature = "cli")]
+use std::path::PathBuf;
+
+#[cfg(feature = "cli")]
+#[derive(Parser)]
+#[command(name = "simond")]
+#[command(about = "Silicon Monitor daemon: serve monitoring data over a local HTTP/WebSocket API", long_about = None)]
+#[command(version)]
+struct Cli {
+    /// Unix socket path (default: /run/simond.sock as root, else $XDG_RUNTIME_DIR/simond.sock)
+    #[arg(long)]
+    socket: Option<PathBuf>,
+
+    /// Do not listen on a Unix socket
+    #[arg(long, conflicts_with = "socket")]
+    no_socket: bool,
+
+    /// Also listen on TCP, e.g. 0.0.0.0:9465 (requires --token-file)
+    #[arg(long)]
+    listen: Option<std::net::SocketAddr>,
+
+    /// File of API tokens, one `<token> [read|control]` per line
+    #[arg(long)]
+    token_file: Option<PathBuf>,
+
+    /// Permission of Unix socket clients without a token (read, control or none)
+    #[arg(long, default_value = "read")]
+    unix_permission: String,
+
+    /// Sampling interval in seconds
+    #[arg(short, long, default_value = "1.0")]
+    interval: f64,
+
+    /// Samples of history kept per metric
+    #[arg(long, default_value = "300")]
+    history: usize,
+
+    /// Service to alert on in the health checks (repeatable)
+    #[arg(long = "watch-service", value_name = "NAME")]
+    watch_services: Vec<String>,
+}
+
+#[cfg(feature = "cli")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use simon::daemon::{load_tokens, Daemon, DaemonConfig};
+    use std::time::Duration;
+
+    env_logger::init();
+    let cli = Cli::parse();
+
+    if !cli.interval.is_finite() || cli.interval <= 0.0 {
+        return Err("--interval must be positive".into());
+    }
+    let unix_permission = match cli.unix_permission.as_str() {
+        "none" => None,
+        permission => Some(permission.parse()?),
+    };
+    let socket_path = if cli.no_socket {
+        None
+    } else {
+        Some(
+            cli.socket
+                .unwrap_or_else(simon::daemon::default_socket_path),
+        )
+    };
+
+    let mut config = DaemonConfig::default()
+        .with_socket_path(socket_path)
+        .with_unix_permission(unix_permission)
+        .with_interval(Duration::from_secs_f64(cli.interval))
+        .with_history_size(cli.history);
+    if let Some(addr) = cli.listen {
+        config = config.with_listen(addr);
+    }
+    for service in cli.watch_services {
+        config = config.with_service(service);
+    }
+    if let Some(path) = &cli.token_file {
+        for token in load_tokens(path)? {
+            config = config.with_token(token);
+        }
+    }
+
+    let daemon = Daemon::start(config)?;
+    if let Some(path) = daemon.socket_path() {
+        eprintln!("simond: listening on unix:{}", path.display());
+    }
+    if let Some(addr) = daemon.local_addr() {
+        eprintln!("simond: listening on http://{}", addr);
+    }
+    daemon.run();
+    Ok(())
+}
+
+#[cfg(not(feature = "cli"))]
+fn main() {
+    eprintln!("CLI features not enabled. Please compile with --features cli");
+    std::process::exit(1);
+}
@@ -4,6 +4,7 @@
 //! display options, and monitoring settings.
 
 use crate::error::{SimonError, Result};
+use crate::sinks::SinkConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -18,6 +19,9 @@ pub struct Config {
     pub process: ProcessConfig,
     /// Chart/graph options
     pub chart: ChartConfig,
+    /// Metric output sinks (`[[sinks]]` tables)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<SinkConfig>,
 }
 
 /// General display configuration
@@ -135,6 +139,7 @@ impl Default for Config {
             gpu: GpuConfig::default(),
             process: ProcessConfig::default(),
             chart: ChartConfig::default(),
+            sinks: Vec::new(),
         }
     }
 }
@@ -4,6 +4,14 @@
 //! including TCP and UDP sockets with their states, local/remote addresses, and
 //! owning process information.
 //!
+//! On Linux sockets come from [`sock_diag`](crate::sock_diag) in one netlink
+//! dump per protocol, which also yields queue sizes and TCP RTT/retransmits,
+//! and owners are resolved through an incrementally updated
+//! [`SocketOwnerIndex`](crate::process_network::SocketOwnerIndex) instead of
+//! a full `/proc/*/fd` scan per query. SCTP and Unix domain sockets are Linux
+//! only. When netlink is unavailable `/proc/net/{tcp,udp}{,6}` is parsed
+//! instead.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -29,6 +37,11 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+#[cfg(target_os = "linux")]
+use crate::process_network::SocketOwnerIndex;
+#[cfg(target_os = "linux")]
+use crate::sock_diag::{SockDiag, SocketProtocol};
+
 /// Network connection information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionInfo {
@@ -52,6 +65,18 @@ pub struct ConnectionInfo {
     pub pid: Option<u32>,
     /// Owning process name (if available)
     pub process_name: Option<String>,
+    /// Socket inode (Linux)
+    pub inode: Option<u64>,
+    /// Owner UID (Linux)
+    pub uid: Option<u32>,
+    /// Bytes queued for sending (listeners: backlog)
+    pub send_queue: Option<u32>,
+    /// Bytes received but not read (listeners: pending connections)
+    pub recv_queue: Option<u32>,
+    /// Smoothed round trip time in milliseconds (TCP)
+    pub rtt_ms: Option<f64>,
+    /// Segments retransmitted over the connection lifetime (TCP)
+    pub retransmits: Option<u32>,
 }
 
 /// Network protocol
@@ -61,6 +86,10 @@ pub enum Protocol {
     Tcp6,
     Udp,
     Udp6,
+    Sctp,
+    Sctp6,
+    /// Unix domain socket
+    Unix,
 }
 
 impl fmt::Display for Protocol {
@@ -70,6 +99,9 @@ impl fmt::Display for Protocol {
             Protocol::Tcp6 => write!(f, "TCP6"),
             Protocol::Udp => write!(f, "UDP"),
             Protocol::Udp6 => write!(f, "UDP6"),
+            Protocol::Sctp => write!(f, "SCTP"),
+            Protocol::Sctp6 => write!(f, "SCTP6"),
+            Protocol::Unix => write!(f, "UNIX"),
         }
     }
 }
@@ -121,6 +153,12 @@ pub struct ConnectionMonitor {
     /// Cache of process names by PID
     #[allow(dead_code)]
     process_cache: std::collections::HashMap<u32, String>,
+    /// Owners of TCP, UDP and SCTP sockets
+    #[cfg(target_os = "linux")]
+    inet_owners: std::sync::Mutex<SocketOwnerIndex>,
+    /// Owners of Unix domain sockets
+    #[cfg(target_os = "linux")]
+    unix_owners: std::sync::Mutex<SocketOwnerIndex>,
 }
 
 impl ConnectionMonitor {
@@ -128,6 +166,10 @@ impl ConnectionMonitor {
     pub fn new() -> Result<Self, Error> {
         Ok(Self {
             process_cache: std::collections::HashMap::new(),
+            #[cfg(target_os = "linux")]
+            inet_owners: std::sync::Mutex::new(SocketOwnerIndex::new()),
+            #[cfg(target_os = "linux")]
+            unix_owners: std::sync::Mutex::new(SocketOwnerIndex::new()),
         })
     }
 
@@ -191,7 +233,33 @@ impl ConnectionMonitor {
         Err(Error::NotSupported("Platform not supported".into()))
     }
 
-    /// Get all connections (TCP + UDP, IPv4 + IPv6)
+    /// Get all SCTP associations and endpoints, IPv4 and IPv6 (Linux)
+    pub fn sctp_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
+        #[cfg(target_os = "linux")]
+        return self.linux_sctp_connections();
+
+        #[cfg(not(target_os = "linux"))]
+        Err(Error::NotSupported("SCTP monitoring is Linux only".into()))
+    }
+
+    /// Get all Unix domain sockets (Linux)
+    ///
+    /// The local address is the bound path (`@name` for abstract sockets,
+    /// `*` for unnamed ones); the remote address is the peer's path, or its
+    /// inode as `socket:[N]` when the peer is unnamed.
+    pub fn unix_sockets(&self) -> Result<Vec<ConnectionInfo>, Error> {
+        #[cfg(target_os = "linux")]
+        return self.linux_unix_sockets();
+
+        #[cfg(not(target_os = "linux"))]
+        Err(Error::NotSupported(
+            "Unix socket monitoring is Linux only".into(),
+        ))
+    }
+
+    /// Get all connections (TCP + UDP + SCTP, IPv4 + IPv6)
+    ///
+    /// Unix domain sockets are not included, see [`unix_sockets`](Self::unix_sockets).
     pub fn all_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
         let mut all = Vec::new();
 
@@ -207,6 +275,19 @@ impl ConnectionMonitor {
         if let Ok(udp6) = self.udp6_endpoints() {
             all.extend(udp6);
         }
+        if let Ok(sctp) = self.sctp_connections() {
+            all.extend(sctp);
+        }
+
+        // Everything was seen, so the owner index can drop closed sockets
+        #[cfg(target_os = "linux")]
+        {
+            let inodes: std::collections::HashSet<u64> =
+                all.iter().filter_map(|c| c.inode).collect();
+            if let Ok(mut owners) = self.inet_owners.lock() {
+                owners.retain(|inode| inodes.contains(&inode));
+            }
+        }
 
         Ok(all)
     }
@@ -316,6 +397,12 @@ impl ConnectionMonitor {
                 state,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                inode: None,
+                uid: None,
+                send_queue: None,
+                recv_queue: None,
+                rtt_ms: None,
+                retransmits: None,
             });
         }
 
@@ -404,6 +491,12 @@ impl ConnectionMonitor {
                 state,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                inode: None,
+                uid: None,
+                send_queue: None,
+                recv_queue: None,
+                rtt_ms: None,
+                retransmits: None,
             });
         }
 
@@ -477,6 +570,12 @@ impl ConnectionMonitor {
                 state: ConnectionState::Stateless,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                inode: None,
+                uid: None,
+                send_queue: None,
+                recv_queue: None,
+                rtt_ms: None,
+                retransmits: None,
             });
         }
 
@@ -550,6 +649,12 @@ impl ConnectionMonitor {
                 state: ConnectionState::Stateless,
                 pid: Some(pid),
                 process_name: self.get_process_name(pid),
+                inode: None,
+                uid: None,
+                send_queue: None,
+                recv_queue: None,
+                rtt_ms: None,
+                retransmits: None,
             });
         }
 
@@ -602,19 +707,138 @@ impl ConnectionMonitor {
 #[cfg(target_os = "linux")]
 impl ConnectionMonitor {
     fn linux_tcp_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/tcp", Protocol::Tcp)
+        self.linux_inet(libc::AF_INET, SocketProtocol::Tcp, Protocol::Tcp)
+            .or_else(|_| self.parse_proc_net("/proc/net/tcp", Protocol::Tcp))
     }
 
     fn linux_tcp6_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/tcp6", Protocol::Tcp6)
+        self.linux_inet(libc::AF_INET6, SocketProtocol::Tcp, Protocol::Tcp6)
+            .or_else(|_| self.parse_proc_net("/proc/net/tcp6", Protocol::Tcp6))
     }
 
     fn linux_udp_endpoints(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/udp", Protocol::Udp)
+        self.linux_inet(libc::AF_INET, SocketProtocol::Udp, Protocol::Udp)
+            .or_else(|_| self.parse_proc_net("/proc/net/udp", Protocol::Udp))
     }
 
     fn linux_udp6_endpoints(&self) -> Result<Vec<ConnectionInfo>, Error> {
-        self.parse_proc_net("/proc/net/udp6", Protocol::Udp6)
+        self.linux_inet(libc::AF_INET6, SocketProtocol::Udp, Protocol::Udp6)
+            .or_else(|_| self.parse_proc_net("/proc/net/udp6", Protocol::Udp6))
+    }
+
+    fn linux_sctp_connections(&self) -> Result<Vec<ConnectionInfo>, Error> {
+        let mut all = self.linux_inet(libc::AF_INET, SocketProtocol::Sctp, Protocol::Sctp)?;
+        all.extend(self.linux_inet(libc::AF_INET6, SocketProtocol::Sctp, Protocol::Sctp6)?);
+        Ok(all)
+    }
+
+    /// One sock_diag dump of an address family
+    fn linux_inet(
+        &self,
+        family: libc::c_int,
+        protocol: SocketProtocol,
+        kind: Protocol,
+    ) -> Result<Vec<ConnectionInfo>, Error> {
+        let sockets = SockDiag::new()
+            .and_then(|diag| diag.inet_sockets_family(family as u8, protocol))
+            .map_err(diag_error)?;
+
+        let mut connections: Vec<ConnectionInfo> = sockets
+            .into_iter()
+            .map(|socket| {
+                let connected = socket.state != ConnectionState::Listen && socket.is_connected();
+                let tcp_info = socket.tcp_info.as_ref();
+                ConnectionInfo {
+                    protocol: kind,
+                    local_address: socket.local.to_string(),
+                    local_ip: socket.local.ip(),
+                    local_port: socket.local.port(),
+                    remote_address: connected.then(|| socket.remote.to_string()),
+                    remote_ip: connected.then(|| socket.remote.ip()),
+                    remote_port: connected.then(|| socket.remote.port()),
+                    state: socket.state,
+                    pid: None,
+                    process_name: None,
+                    inode: Some(socket.inode),
+                    uid: Some(socket.uid),
+                    send_queue: Some(socket.send_queue),
+                    recv_queue: Some(socket.recv_queue),
+                    rtt_ms: tcp_info.map(|info| info.rtt_ms()),
+                    retransmits: tcp_info.map(|info| info.total_retrans),
+                }
+            })
+            .collect();
+        self.resolve_owners(&self.inet_owners, &mut connections);
+        Ok(connections)
+    }
+
+    fn linux_unix_sockets(&self) -> Result<Vec<ConnectionInfo>, Error> {
+        let sockets = SockDiag::new()
+            .and_then(|diag| diag.unix_sockets())
+            .map_err(diag_error)?;
+
+        // Accepted sockets carry the listener's path, so a client's peer
+        // usually resolves to the server's address
+        let paths: std::collections::HashMap<u64, &str> = sockets
+            .iter()
+            .filter_map(|s| Some((s.inode, s.path.as_deref()?)))
+            .collect();
+
+        let mut connections: Vec<ConnectionInfo> = sockets
+            .iter()
+            .map(|socket| ConnectionInfo {
+                protocol: Protocol::Unix,
+                local_address: socket.path.clone().unwrap_or_else(|| "*".to_string()),
+                local_ip: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+                local_port: 0,
+                remote_address: socket.peer_inode.map(|peer| match paths.get(&peer) {
+                    Some(path) => path.to_string(),
+                    None => format!("socket:[{}]", peer),
+                }),
+                remote_ip: None,
+                remote_port: None,
+                state: socket.state,
+                pid: None,
+                process_name: None,
+                inode: Some(socket.inode),
+                uid: socket.uid,
+                send_queue: Some(socket.send_queue),
+                recv_queue: Some(socket.recv_queue),
+                rtt_ms: None,
+                retransmits: None,
+            })
+            .collect();
+
+        if let Ok(mut owners) = self.unix_owners.lock() {
+            let inodes: std::collections::HashSet<u64> = sockets.iter().map(|s| s.inode).collect();
+            owners.retain(|inode| inodes.contains(&inode));
+        }
+        self.resolve_owners(&self.unix_owners, &mut connections);
+        Ok(connections)
+    }
+
+    /// Fill in `pid` and `process_name` from the owner index
+    fn resolve_owners(
+        &self,
+        index: &std::sync::Mutex<SocketOwnerIndex>,
+        connections: &mut [ConnectionInfo],
+    ) {
+        let Ok(mut index) = index.lock() else {
+            return;
+        };
+        index.resolve(connections.iter().filter_map(|c| c.inode));
+
+        let mut names: std::collections::HashMap<u32, Option<String>> =
+            std::collections::HashMap::new();
+        for conn in connections.iter_mut() {
+            conn.pid = conn.inode.and_then(|inode| index.owner(inode));
+            conn.process_name = conn.pid.and_then(|pid| {
+                names
+                    .entry(pid)
+                    .or_insert_with(|| self.get_process_name_linux(pid))
+                    .clone()
+            });
+        }
     }
 
     fn parse_proc_net(&self, path: &str, protocol: Protocol) -> Result<Vec<ConnectionInfo>, Error> {
@@ -636,6 +860,7 @@ impl ConnectionMonitor {
             }
         }
 
+        self.resolve_owners(&self.inet_owners, &mut connections);
         Ok(connections)
     }
 
@@ -648,8 +873,9 @@ impl ConnectionMonitor {
         let local = parts[1];
         let remote = parts[2];
         let state_hex = parts[3];
+        let (tx_queue, rx_queue) = parts[4].split_once(':')?;
         let uid = parts[7].parse::<u32>().ok();
-        let inode = parts[9];
+        let inode = parts[9].parse::<u64>().ok();
 
         let is_ipv6 = matches!(protocol, Protocol::Tcp6 | Protocol::Udp6);
 
@@ -662,9 +888,6 @@ impl ConnectionMonitor {
             ConnectionState::Stateless
         };
 
-        let pid = self.find_pid_by_inode(inode);
-        let process_name = pid.and_then(|p| self.get_process_name_linux(p));
-
         Some(ConnectionInfo {
             protocol,
             local_address: format!("{}:{}", local_ip, local_port),
@@ -686,8 +909,14 @@ impl ConnectionMonitor {
                 None
             },
             state,
-            pid,
-            process_name,
+            pid: None,
+            process_name: None,
+            inode,
+            uid,
+            send_queue: u32::from_str_radix(tx_queue, 16).ok(),
+            recv_queue: u32::from_str_radix(rx_queue, 16).ok(),
+            rtt_ms: None,
+            retransmits: None,
         })
     }
 
@@ -735,38 +964,6 @@ impl ConnectionMonitor {
         }
     }
 
-    fn find_pid_by_inode(&self, inode: &str) -> Option<u32> {
-        use std::fs;
-
-        let proc_dir = match fs::read_dir("/proc") {
-            Ok(d) => d,
-            Err(_) => return None,
-        };
-
-        for entry in proc_dir.flatten() {
-            let path = entry.path();
-            if let Some(name) = path.file_name() {
-                if let Some(name_str) = name.to_str() {
-                    if let Ok(pid) = name_str.parse::<u32>() {
-                        let fd_path = path.join("fd");
-                        if let Ok(fds) = fs::read_dir(&fd_path) {
-                            for fd in fds.flatten() {
-                                if let Ok(link) = fs::read_link(fd.path()) {
-                                    if let Some(link_str) = link.to_str() {
-                                        if link_str.contains(&format!("socket:[{}]", inode)) {
-                                            return Some(pid);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
     fn get_process_name_linux(&self, pid: u32) -> Option<String> {
         use std::fs;
 
@@ -825,3 +1022,56 @@ impl std::fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+#[cfg(target_os = "linux")]
+fn diag_error(err: crate::error::SimonError) -> Error {
+    match err {
+        crate::error::SimonError::FeatureNotAvailable(msg) => Error::NotSupported(msg),
+        err => Error::SystemError(err.to_string()),
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    #[test]
+    fn test_linux_connections_with_owners() {
+        let monitor = ConnectionMonitor::new().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).unwrap();
+        let _server = listener.accept().unwrap();
+
+        let connections = monitor.all_connections().unwrap();
+        let conn = connections
+            .iter()
+            .find(|c| c.local_address == client.local_addr().unwrap().to_string())
+            .expect("client connection");
+        assert_eq!(conn.state, ConnectionState::Established);
+        assert_eq!(conn.remote_address, Some(addr.to_string()));
+        assert_eq!(conn.pid, Some(std::process::id()));
+        assert!(conn.send_queue.is_some());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("listener.sock");
+        let unix_listener = UnixListener::bind(&path).unwrap();
+        let _unix_client = UnixStream::connect(&path).unwrap();
+        let _unix_server = unix_listener.accept().unwrap();
+        // unix_diag may be missing from minimal kernels
+        if let Ok(sockets) = monitor.unix_sockets() {
+            let path = path.to_string_lossy();
+            let listening = sockets
+                .iter()
+                .find(|s| s.local_address == path && s.state == ConnectionState::Listen)
+                .expect("unix listener");
+            assert_eq!(listening.pid, Some(std::process::id()));
+            assert!(sockets
+                .iter()
+                .any(|s| s.remote_address.as_deref() == Some(&*path)
+                    && s.state == ConnectionState::Established));
+        }
+    }
+}
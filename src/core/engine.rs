@@ -1,6 +1,8 @@
 //! Engine monitoring (DLA, APE, NVENC, NVDEC, etc.)
 
 use crate::error::Result;
+#[cfg(target_os = "linux")]
+use crate::platform::common::{path_exists, read_file_u32};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -0,0 +1,432 @@
+//! Client for a running daemon
+
+use super::http::{self, ClientResponse};
+use super::websocket;
+use super::{
+    default_socket_path, DaemonInfo, MetricHistory, Snapshot, StreamMessage, Topic, DEFAULT_PORT,
+};
+use crate::backend::FullSystemState;
+use crate::connections::ConnectionInfo;
+use crate::error::{Result, SimonError};
+use crate::health::SystemHealth;
+use crate::hwlog::HardwareEvent;
+use crate::process_monitor::ProcessMonitorInfo;
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::io::{self, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Largest response body the client accepts
+const MAX_RESPONSE: usize = 256 * 1024 * 1024;
+
+/// Where a daemon listens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    /// Unix socket path
+    Unix(PathBuf),
+    /// TCP `host:port`
+    Tcp(String),
+}
+
+impl Default for Endpoint {
+    fn default() -> Self {
+        Endpoint::Unix(default_socket_path())
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Unix(path) => write!(f, "unix:{}", path.display()),
+            Endpoint::Tcp(addr) => write!(f, "http://{}", addr),
+        }
+    }
+}
+
+impl std::str::FromStr for Endpoint {
+    type Err = SimonError;
+
+    /// Accepts `unix:/path`, `/path`, `http://host:port`, `host:port` or a
+    /// bare host (port 9465)
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Endpoint::Unix(PathBuf::from(path)));
+        }
+        if s.starts_with('/') || s.starts_with('.') {
+            return Ok(Endpoint::Unix(PathBuf::from(s)));
+        }
+        let addr = s
+            .strip_prefix("http://")
+            .or_else(|| s.strip_prefix("ws://"))
+            .unwrap_or(s)
+            .trim_end_matches('/');
+        if addr.is_empty() || addr.contains('/') {
+            return Err(SimonError::InvalidValue(format!(
+                "invalid daemon endpoint '{}'",
+                s
+            )));
+        }
+        // A bare host, or a bracketed IPv6 address without a port
+        let has_port = match addr.rsplit_once(':') {
+            Some((host, port)) => {
+                port.parse::<u16>().is_ok() && (!host.contains(':') || host.ends_with(']'))
+            }
+            None => false,
+        };
+        Ok(Endpoint::Tcp(if has_port {
+            addr.to_string()
+        } else {
+            format!("{}:{}", addr, DEFAULT_PORT)
+        }))
+    }
+}
+
+/// A connected socket of either kind
+trait Stream: Read + Write + Send {
+    fn try_clone_box(&self) -> io::Result<Box<dyn Stream>>;
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn try_clone_box(&self) -> io::Result<Box<dyn Stream>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+}
+
+#[cfg(unix)]
+impl Stream for std::os::unix::net::UnixStream {
+    fn try_clone_box(&self) -> io::Result<Box<dyn Stream>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    fn set_timeouts(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+}
+
+/// Talks to a daemon over its HTTP API
+#[derive(Debug, Clone)]
+pub struct DaemonClient {
+    endpoint: Endpoint,
+    token: Option<String>,
+    timeout: Duration,
+}
+
+impl DaemonClient {
+    /// Client for `endpoint`, without a token and with a 10 s timeout
+    pub fn new(endpoint: Endpoint) -> Self {
+        Self {
+            endpoint,
+            token: None,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Authenticate with a bearer token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Connect and read timeout for each request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The endpoint this client talks to
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Daemon version, interval and the caller's permission
+    pub fn info(&self) -> Result<DaemonInfo> {
+        self.get("/v1/info")
+    }
+
+    /// Summary state from the latest sample
+    pub fn state(&self) -> Result<FullSystemState> {
+        self.get("/v1/state")
+    }
+
+    /// Everything from the latest sample
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        self.get("/v1/snapshot")
+    }
+
+    /// Processes from the latest sample
+    pub fn processes(&self) -> Result<Vec<ProcessMonitorInfo>> {
+        self.get("/v1/processes")
+    }
+
+    /// Network connections from the latest sample
+    pub fn connections(&self) -> Result<Vec<ConnectionInfo>> {
+        self.get("/v1/connections")
+    }
+
+    /// Latest health check
+    pub fn health(&self) -> Result<SystemHealth> {
+        self.get("/v1/health")
+    }
+
+    /// Recent hardware events
+    pub fn events(&self) -> Result<Vec<HardwareEvent>> {
+        self.get("/v1/events")
+    }
+
+    /// Names of the metrics with history
+    pub fn metrics(&self) -> Result<Vec<String>> {
+        self.get("/v1/history")
+    }
+
+    /// Recent values of one metric
+    pub fn history(&self, metric: &str) -> Result<MetricHistory> {
+        self.get(&format!("/v1/history/{}", metric))
+    }
+
+    /// Change the sampling interval (control permission)
+    pub fn set_interval(&self, interval: Duration) -> Result<()> {
+        let body = serde_json::json!({ "interval_ms": interval.as_millis() as u64 });
+        self.post("/v1/control/interval", &body)
+    }
+
+    /// Clear the daemon's history buffers (control permission)
+    pub fn reset_history(&self) -> Result<()> {
+        self.post("/v1/control/reset-history", &serde_json::json!({}))
+    }
+
+    /// Take a sample now instead of waiting for the interval (control permission)
+    pub fn refresh(&self) -> Result<()> {
+        self.post("/v1/control/refresh", &serde_json::json!({}))
+    }
+
+    /// GET a path and decode its JSON body
+    pub fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self.request("GET", path, &[])?;
+        serde_json::from_slice(&response.body)
+            .map_err(|e| SimonError::Parse(format!("{} from {}: {}", path, self.endpoint, e)))
+    }
+
+    /// POST a JSON body, discarding the response body
+    pub fn post(&self, path: &str, body: &serde_json::Value) -> Result<()> {
+        self.request("POST", path, body.to_string().as_bytes())
+            .map(|_| ())
+    }
+
+    /// Open a WebSocket stream of `topics`
+    pub fn subscribe(&self, topics: &[Topic]) -> Result<Subscription> {
+        let names: Vec<&str> = topics.iter().map(Topic::as_str).collect();
+        let path = format!("/v1/stream?topics={}", names.join(","));
+        let stream = self.connect()?;
+        let mut writer = stream.try_clone_box()?;
+        let mut reader = BufReader::new(stream);
+
+        let key = websocket::client_key();
+        writer.write_all(
+            format!(
+                "GET {} HTTP/1.1\r\nHost: simond\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+                 Sec-WebSocket-Key: {}\r\nSec-WebSocket-Version: 13\r\n{}\r\n",
+                path,
+                key,
+                self.auth_header()
+            )
+            .as_bytes(),
+        )?;
+        writer.flush()?;
+
+        let response = http::read_response(&mut reader, true, 0)?;
+        if response.status != 101 {
+            let response = http::read_response_body(&mut reader, response, MAX_RESPONSE)?;
+            return Err(self.status_error(&response));
+        }
+        if response.header("Sec-WebSocket-Accept") != Some(websocket::accept_key(&key).as_str()) {
+            return Err(SimonError::Network(format!(
+                "{} sent a bad WebSocket handshake",
+                self.endpoint
+            )));
+        }
+        // The server pings idle streams, so no read timeout is needed
+        reader.get_ref().set_timeouts(None)?;
+        Ok(Subscription {
+            reader,
+            writer,
+            closed: false,
+        })
+    }
+
+    fn connect(&self) -> Result<Box<dyn Stream>> {
+        let unreachable =
+            |e: io::Error| SimonError::Network(format!("cannot reach {}: {}", self.endpoint, e));
+        let stream: Box<dyn Stream> = match &self.endpoint {
+            #[cfg(unix)]
+            Endpoint::Unix(path) => {
+                Box::new(std::os::unix::net::UnixStream::connect(path).map_err(unreachable)?)
+            }
+            #[cfg(not(unix))]
+            Endpoint::Unix(_) => {
+                return Err(SimonError::UnsupportedPlatform(
+                    "Unix sockets are not available on this platform".into(),
+                ))
+            }
+            Endpoint::Tcp(addr) => {
+                use std::net::ToSocketAddrs;
+                let addr = addr
+                    .to_socket_addrs()
+                    .map_err(unreachable)?
+                    .next()
+                    .ok_or_else(|| SimonError::Network(format!("cannot resolve {}", addr)))?;
+                Box::new(TcpStream::connect_timeout(&addr, self.timeout).map_err(unreachable)?)
+            }
+        };
+        stream.set_timeouts(Some(self.timeout))?;
+        Ok(stream)
+    }
+
+    fn auth_header(&self) -> String {
+        self.token
+            .as_ref()
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default()
+    }
+
+    fn request(&self, method: &str, path: &str, body: &[u8]) -> Result<ClientResponse> {
+        let mut stream = self.connect()?;
+        let mut request = format!(
+            "{} {} HTTP/1.1\r\nHost: simond\r\nConnection: close\r\n{}",
+            method,
+            path,
+            self.auth_header()
+        );
+        if method == "POST" {
+            request.push_str(&format!(
+                "Content-Type: application/json\r\nContent-Length: {}\r\n",
+                body.len()
+            ));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes())?;
+        stream.write_all(body)?;
+        stream.flush()?;
+
+        let response = http::read_response(&mut BufReader::new(stream), false, MAX_RESPONSE)
+            .map_err(|e| SimonError::Network(format!("{}{}: {}", self.endpoint, path, e)))?;
+        if response.status / 100 == 2 {
+            Ok(response)
+        } else {
+            Err(self.status_error(&response))
+        }
+    }
+
+    fn status_error(&self, response: &ClientResponse) -> SimonError {
+        let message = serde_json::from_slice::<serde_json::Value>(&response.body)
+            .ok()
+            .and_then(|body| body.get("error")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| http::reason(response.status).to_string());
+        let message = format!("{} ({})", message, response.status);
+        match response.status {
+            401 | 403 => SimonError::PermissionDenied(message),
+            400 | 404 => SimonError::InvalidValue(message),
+            _ => SimonError::Network(message),
+        }
+    }
+}
+
+/// A WebSocket stream from the daemon
+///
+/// Iterating yields messages until the daemon closes the stream or the
+/// connection fails; use [`next_message`](Self::next_message) to see errors.
+pub struct Subscription {
+    reader: BufReader<Box<dyn Stream>>,
+    writer: Box<dyn Stream>,
+    closed: bool,
+}
+
+impl Subscription {
+    /// Wait for the next message; `None` once the stream is closed
+    pub fn next_message(&mut self) -> Result<Option<StreamMessage>> {
+        if self.closed {
+            return Ok(None);
+        }
+        match websocket::read_message(&mut self.reader, &mut self.writer, MAX_RESPONSE, true)? {
+            Some(text) => serde_json::from_slice(&text)
+                .map(Some)
+                .map_err(|e| SimonError::Parse(format!("stream message: {}", e))),
+            None => {
+                self.closed = true;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Replace the subscribed topics
+    pub fn set_topics(&mut self, topics: &[Topic]) -> Result<()> {
+        let text = serde_json::json!({ "topics": topics }).to_string();
+        websocket::write_frame(
+            &mut self.writer,
+            websocket::OP_TEXT,
+            text.as_bytes(),
+            Some(websocket::random_mask()),
+        )?;
+        Ok(())
+    }
+
+    /// Close the stream
+    pub fn close(mut self) -> Result<()> {
+        self.closed = true;
+        websocket::write_frame(
+            &mut self.writer,
+            websocket::OP_CLOSE,
+            &1000u16.to_be_bytes(),
+            Some(websocket::random_mask()),
+        )?;
+        Ok(())
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = StreamMessage;
+
+    fn next(&mut self) -> Option<StreamMessage> {
+        match self.next_message() {
+            Ok(message) => message,
+            Err(e) => {
+                log::debug!("daemon stream ended: {}", e);
+                self.closed = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_endpoint() {
+        let parse = |s: &str| s.parse::<Endpoint>().unwrap();
+        assert_eq!(
+            parse("unix:/run/simond.sock"),
+            Endpoint::Unix("/run/simond.sock".into())
+        );
+        assert_eq!(parse("/tmp/s.sock"), Endpoint::Unix("/tmp/s.sock".into()));
+        assert_eq!(
+            parse("http://node1:8000/"),
+            Endpoint::Tcp("node1:8000".into())
+        );
+        assert_eq!(parse("node1"), Endpoint::Tcp("node1:9465".into()));
+        assert_eq!(parse("[::1]:80"), Endpoint::Tcp("[::1]:80".into()));
+        assert_eq!(parse("[::1]"), Endpoint::Tcp("[::1]:9465".into()));
+        assert!("http://node1/v1".parse::<Endpoint>().is_err());
+        assert_eq!(parse("node1:1").to_string(), "http://node1:1");
+    }
+}
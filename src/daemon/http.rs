@@ -0,0 +1,363 @@
+//! Minimal HTTP/1.1 request parsing and response writing
+//!
+//! The daemon serves a handful of JSON endpoints to local tools, so this
+//! handles only what they send: a request line, headers, and an optional
+//! body with `Content-Length`. Chunked bodies are rejected.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Longest accepted request head (request line plus headers)
+const MAX_HEAD_LEN: usize = 16 * 1024;
+/// Longest accepted request body
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// A parsed request
+#[derive(Debug, Default)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// Header value, case-insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Query parameter value
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether the header contains `token` in its comma-separated list
+    pub fn header_has(&self, name: &str, token: &str) -> bool {
+        self.header(name).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|part| part.trim().eq_ignore_ascii_case(token))
+        })
+    }
+
+    /// Whether the client wants the connection kept open after the response
+    pub fn keep_alive(&self) -> bool {
+        !self.header_has("Connection", "close")
+    }
+}
+
+/// Read one request; `None` when the client closed the connection cleanly
+pub(crate) fn read_request(r: &mut impl BufRead) -> io::Result<Option<Request>> {
+    let mut head_len = 0;
+    let mut line = String::new();
+    if read_line(r, &mut line, &mut head_len)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(invalid("unsupported HTTP version"));
+    }
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path, parse_query(query)),
+        None => (target, Vec::new()),
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        path: percent_decode(path),
+        query,
+        ..Default::default()
+    };
+
+    loop {
+        line.clear();
+        if read_line(r, &mut line, &mut head_len)? == 0 {
+            return Err(invalid("connection closed in headers"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        request
+            .headers
+            .push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    if request.header_has("Transfer-Encoding", "chunked") {
+        return Err(invalid("chunked request bodies are not supported"));
+    }
+    let length: usize = match request.header("Content-Length") {
+        Some(value) => value
+            .parse()
+            .map_err(|_| invalid("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_LEN {
+        return Err(invalid("request body too large"));
+    }
+    request.body = vec![0u8; length];
+    r.read_exact(&mut request.body)?;
+    Ok(Some(request))
+}
+
+fn read_line(r: &mut impl BufRead, line: &mut String, head_len: &mut usize) -> io::Result<usize> {
+    let n = r
+        .by_ref()
+        .take((MAX_HEAD_LEN - *head_len) as u64 + 1)
+        .read_line(line)?;
+    *head_len += n;
+    if *head_len > MAX_HEAD_LEN {
+        return Err(invalid("request head too large"));
+    }
+    Ok(n)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((name, value)) => (percent_decode(name), percent_decode(value)),
+            None => (percent_decode(pair), String::new()),
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = |b: u8| (b as char).to_digit(16);
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(hi), Some(lo)) => {
+                    out.push((hi * 16 + lo) as u8);
+                    i += 3;
+                    continue;
+                }
+                _ => out.push(b'%'),
+            },
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// A response ready to be written
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// JSON response
+    pub fn json(status: u16, value: &impl serde::Serialize) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                headers: Vec::new(),
+                body,
+            },
+            Err(e) => Self::error(500, &format!("serialization failed: {}", e)),
+        }
+    }
+
+    /// JSON error body `{"error": "..."}`
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: serde_json::json!({ "error": message })
+                .to_string()
+                .into_bytes(),
+        }
+    }
+
+    /// Add a header
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Serialize onto the connection
+    pub fn write_to(&self, w: &mut impl Write, keep_alive: bool) -> io::Result<()> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: {}\r\n",
+            self.status,
+            reason(self.status),
+            self.content_type,
+            self.body.len(),
+            if keep_alive { "keep-alive" } else { "close" },
+        );
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        w.write_all(head.as_bytes())?;
+        w.write_all(&self.body)?;
+        w.flush()
+    }
+}
+
+/// Reason phrase for the status codes the daemon uses
+pub(crate) fn reason(status: u16) -> &'static str {
+    match status {
+        101 => "Switching Protocols",
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Status, headers and body of a response read by the client
+#[derive(Debug)]
+pub(crate) struct ClientResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl ClientResponse {
+    /// Header value, case-insensitive
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Read a response head, and the body unless `head_only` (upgrades)
+pub(crate) fn read_response(
+    r: &mut impl BufRead,
+    head_only: bool,
+    max_body: usize,
+) -> io::Result<ClientResponse> {
+    let mut line = String::new();
+    r.read_line(&mut line)?;
+    let status = line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid("malformed status line"))?;
+    let mut response = ClientResponse {
+        status,
+        headers: Vec::new(),
+        body: Vec::new(),
+    };
+    loop {
+        line.clear();
+        if r.read_line(&mut line)? == 0 {
+            return Err(invalid("connection closed in headers"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            response
+                .headers
+                .push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if head_only {
+        return Ok(response);
+    }
+    read_response_body(r, response, max_body)
+}
+
+/// Read the body of a response whose head was read with `head_only`
+pub(crate) fn read_response_body(
+    r: &mut impl BufRead,
+    mut response: ClientResponse,
+    max_body: usize,
+) -> io::Result<ClientResponse> {
+    match response.header("Content-Length") {
+        Some(length) => {
+            let length: usize = length
+                .parse()
+                .map_err(|_| invalid("invalid Content-Length"))?;
+            if length > max_body {
+                return Err(invalid("response body too large"));
+            }
+            response.body = vec![0u8; length];
+            r.read_exact(&mut response.body)?;
+        }
+        None => {
+            r.take(max_body as u64).read_to_end(&mut response.body)?;
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let raw = b"POST /v1/control/interval?x=a%20b&flag HTTP/1.1\r\n\
+                    Host: localhost\r\nContent-Length: 5\r\nconnection: Close\r\n\r\nhello\
+                    GET /v1/state HTTP/1.1\r\n\r\n";
+        let mut reader = &raw[..];
+        let request = read_request(&mut reader).unwrap().unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/control/interval");
+        assert_eq!(request.query("x"), Some("a b"));
+        assert_eq!(request.query("flag"), Some(""));
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.body, b"hello");
+        assert!(!request.keep_alive());
+
+        let request = read_request(&mut reader).unwrap().unwrap();
+        assert_eq!(request.path, "/v1/state");
+        assert!(request.keep_alive());
+        assert!(read_request(&mut reader).unwrap().is_none());
+
+        assert!(read_request(&mut &b"garbage\r\n\r\n"[..]).is_err());
+    }
+
+    #[test]
+    fn test_response_round_trip() {
+        let mut wire = Vec::new();
+        Response::json(200, &serde_json::json!({"ok": true}))
+            .with_header("X-Test", "1")
+            .write_to(&mut wire, false)
+            .unwrap();
+        let response = read_response(&mut &wire[..], false, 1024).unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(response.header("x-test"), Some("1"));
+        assert_eq!(response.body, br#"{"ok":true}"#);
+    }
+}
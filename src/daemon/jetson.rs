@@ -0,0 +1,1137 @@
+//! jtop-style Jetson service: privileged sampling, unprivileged clients
+//!
+//! `jetson_stats` runs a root service that publishes stats over a local
+//! socket so `jtop` and applications never need sudo. [`JetsonService`] plays
+//! the same role: it runs as root, samples [`stats::Simon`](crate::stats::Simon)
+//! on a fixed interval and serves a Unix socket that any local user may
+//! connect to. Control requests are only honored for root and members of
+//! [`JetsonServiceConfig::control_group`] (checked from the peer's socket
+//! credentials), and every attempt is logged as a privileged operation.
+//!
+//! # Protocol
+//!
+//! Newline-delimited JSON. Each request is one object tagged by `cmd`; each
+//! reply is one [`ServiceResponse`] line:
+//!
+//! | Request | Permission | Reply |
+//! |---------|------------|-------|
+//! | `{"cmd":"info"}` | any | `info`: [`ServiceInfo`] |
+//! | `{"cmd":"stats"}` | any | `stats`: latest [`ServiceStats`] |
+//! | `{"cmd":"subscribe"}` | any | one `stats` line per sample until the client disconnects |
+//! | `{"cmd":"fan","speed":80,"fan":"pwmfan"}` | control | `result`: fan and speed set |
+//! | `{"cmd":"nvpmodel","mode":0,"force":false}` | control | `result`: mode and changed settings |
+//! | `{"cmd":"jetson_clocks","enable":true}` | control | `result`: per-domain outcome |
+//!
+//! `fan` defaults to the first fan and `force` to `false`. A reply always
+//! has `ok`; on failure it carries `error` instead of a payload:
+//!
+//! ```text
+//! {"ok":true,"stats":{"sequence":42,"timestamp_ms":1760000000000,"jetson_clocks":false,"nvpmodel":0,"snapshot":{...}}}
+//! {"ok":false,"error":"permission denied: control requires root or membership in group 'jtop'"}
+//! ```
+//!
+//! `snapshot` is a serialized [`stats::Snapshot`](crate::stats::Snapshot).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::daemon::{JetsonService, JetsonServiceConfig};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let service = JetsonService::start(JetsonServiceConfig::default())?;
+//! service.run();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! From an unprivileged application:
+//!
+//! ```no_run
+//! use simon::daemon::{JetsonServiceClient, DEFAULT_JETSON_SOCKET};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = JetsonServiceClient::new(DEFAULT_JETSON_SOCKET);
+//! for stats in client.subscribe()? {
+//!     let stats = stats?;
+//!     println!("{:.1}% RAM", stats.snapshot.memory.ram_usage_percent());
+//! }
+//! client.jetson_clocks(true)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::server::{self, SamplerReady, IDLE_TIMEOUT};
+use crate::error::{Result, SimonError};
+use crate::fan_control::FanMonitor;
+use crate::stats::{Simon, Snapshot};
+use crate::utils::{clocks, log_privileged_operation, nvpmodel_conf, power_mode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Socket the service listens on by default
+pub const DEFAULT_JETSON_SOCKET: &str = "/run/simon-jtop.sock";
+/// Group whose members may send control requests by default
+pub const DEFAULT_CONTROL_GROUP: &str = "jtop";
+
+/// Largest request line accepted from a client
+const MAX_REQUEST: u64 = 4096;
+/// How often waiting subscribers check for shutdown
+const WAKE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A client request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum ServiceRequest {
+    /// Service settings and the caller's rights
+    Info,
+    /// Latest sample
+    Stats,
+    /// Stream every new sample
+    Subscribe,
+    /// Set a fan's speed (percent)
+    Fan {
+        /// Speed, 0-100
+        speed: f32,
+        /// Fan name (`None` for the first fan)
+        #[serde(default)]
+        fan: Option<String>,
+    },
+    /// Switch the nvpmodel power mode
+    Nvpmodel {
+        /// Mode ID
+        mode: u32,
+        /// Pass `-f` to nvpmodel
+        #[serde(default)]
+        force: bool,
+    },
+    /// Enable or restore jetson_clocks
+    JetsonClocks {
+        /// `true` to lock clocks at maximum, `false` to restore
+        enable: bool,
+    },
+}
+
+impl ServiceRequest {
+    /// Whether the request changes hardware state
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            ServiceRequest::Fan { .. }
+                | ServiceRequest::Nvpmodel { .. }
+                | ServiceRequest::JetsonClocks { .. }
+        )
+    }
+
+    /// Operation name used in the audit log
+    fn describe(&self) -> String {
+        match self {
+            ServiceRequest::Info => "info".into(),
+            ServiceRequest::Stats => "stats".into(),
+            ServiceRequest::Subscribe => "subscribe".into(),
+            ServiceRequest::Fan { speed, fan } => {
+                format!("fan {} {}%", fan.as_deref().unwrap_or("default"), speed)
+            }
+            ServiceRequest::Nvpmodel { mode, .. } => format!("nvpmodel -m {}", mode),
+            ServiceRequest::JetsonClocks { enable: true } => "jetson_clocks".into(),
+            ServiceRequest::JetsonClocks { enable: false } => "jetson_clocks --restore".into(),
+        }
+    }
+}
+
+/// One published sample
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStats {
+    /// Sample number, starting at 1
+    pub sequence: u64,
+    /// When the sample was taken (ms since the Unix epoch)
+    pub timestamp_ms: u64,
+    /// Whether jetson_clocks is active
+    pub jetson_clocks: bool,
+    /// Active nvpmodel mode ID
+    pub nvpmodel: Option<u32>,
+    /// The sample itself
+    pub snapshot: Snapshot,
+}
+
+/// Service settings and the caller's rights
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    /// simon version
+    pub version: String,
+    /// Sampling interval (ms)
+    pub interval_ms: u64,
+    /// Group allowed to send control requests
+    pub control_group: Option<String>,
+    /// Whether the caller may send control requests
+    pub can_control: bool,
+}
+
+/// One reply line
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceResponse {
+    /// Whether the request succeeded
+    pub ok: bool,
+    /// Reply to `info`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ServiceInfo>,
+    /// Reply to `stats` and each `subscribe` message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<ServiceStats>,
+    /// Outcome of a control request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// Why the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ServiceResponse {
+    /// A failed reply
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            error: Some(message.into()),
+            ..Default::default()
+        }
+    }
+
+    fn into_result(self) -> Result<Self> {
+        if self.ok {
+            return Ok(self);
+        }
+        let message = self.error.unwrap_or_else(|| "request failed".into());
+        if message.starts_with("permission denied") {
+            Err(SimonError::PermissionDenied(message))
+        } else {
+            Err(SimonError::Other(message))
+        }
+    }
+}
+
+/// Service settings
+#[derive(Debug, Clone)]
+pub struct JetsonServiceConfig {
+    /// Unix socket to listen on
+    pub socket_path: PathBuf,
+    /// Sampling interval
+    pub interval: Duration,
+    /// Group whose members may send control requests (`None`: root only)
+    pub control_group: Option<String>,
+    /// Most concurrent client connections
+    pub max_connections: usize,
+    /// Most concurrent connections of one non-root user
+    pub max_connections_per_user: usize,
+}
+
+impl Default for JetsonServiceConfig {
+    fn default() -> Self {
+        Self {
+            socket_path: PathBuf::from(DEFAULT_JETSON_SOCKET),
+            interval: Duration::from_secs(1),
+            control_group: Some(DEFAULT_CONTROL_GROUP.to_string()),
+            max_connections: 64,
+            max_connections_per_user: 8,
+        }
+    }
+}
+
+impl JetsonServiceConfig {
+    /// Listen on a different Unix socket
+    pub fn with_socket_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.socket_path = path.into();
+        self
+    }
+
+    /// Set the sampling interval
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the control group, or `None` to allow only root
+    pub fn with_control_group(mut self, group: Option<String>) -> Self {
+        self.control_group = group;
+        self
+    }
+}
+
+/// Source of the service's samples
+///
+/// [`Simon`] is the real one; tests provide their own through
+/// [`JetsonService::start_with`].
+pub trait JetsonSampler {
+    /// Take one sample
+    fn sample(&mut self) -> Result<Snapshot>;
+
+    /// Whether jetson_clocks is active
+    fn jetson_clocks(&mut self) -> bool {
+        clocks::JetsonClocks::new().is_active()
+    }
+
+    /// Active nvpmodel mode
+    fn nvpmodel(&mut self) -> Option<u32> {
+        nvpmodel_conf::active_mode(Path::new("/")).ok()
+    }
+}
+
+impl JetsonSampler for Simon {
+    fn sample(&mut self) -> Result<Snapshot> {
+        self.snapshot()
+    }
+}
+
+/// Carries out authorized control requests
+///
+/// [`SystemControl`] changes the real hardware.
+pub trait JetsonControl: Send + Sync {
+    /// Set a fan's speed (percent)
+    fn set_fan(&self, fan: Option<&str>, speed: f32) -> Result<serde_json::Value>;
+
+    /// Switch the nvpmodel power mode
+    fn set_nvpmodel(&self, mode: u32, force: bool) -> Result<serde_json::Value>;
+
+    /// Enable or restore jetson_clocks
+    fn jetson_clocks(&self, enable: bool) -> Result<serde_json::Value>;
+}
+
+/// Controls the local hardware through [`FanMonitor`], [`power_mode`] and
+/// [`clocks`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemControl;
+
+impl JetsonControl for SystemControl {
+    fn set_fan(&self, fan: Option<&str>, speed: f32) -> Result<serde_json::Value> {
+        let monitor = FanMonitor::new()?;
+        let name = match fan {
+            Some(name) => name.to_string(),
+            None => monitor
+                .fans()
+                .first()
+                .map(|f| f.name.clone())
+                .ok_or_else(|| SimonError::DeviceNotFound("no controllable fan".into()))?,
+        };
+        monitor.set_speed(&name, speed)?;
+        Ok(serde_json::json!({ "fan": name, "speed": speed }))
+    }
+
+    fn set_nvpmodel(&self, mode: u32, force: bool) -> Result<serde_json::Value> {
+        let changes = power_mode::preview_mode(mode).unwrap_or_default();
+        power_mode::set_mode(mode, force)?;
+        Ok(serde_json::json!({
+            "mode": mode,
+            "changed": changes.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn jetson_clocks(&self, enable: bool) -> Result<serde_json::Value> {
+        let report = if enable {
+            clocks::enable()?
+        } else {
+            clocks::disable()?
+        };
+        if !report.results.is_empty() && report.failures().count() == report.results.len() {
+            return Err(SimonError::CommandFailed(format!(
+                "all {} domains failed",
+                report.results.len()
+            )));
+        }
+        let domains: Vec<_> = report
+            .results
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "domain": r.domain.to_string(),
+                    "name": r.name,
+                    "error": r.error,
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({ "enabled": enable, "domains": domains }))
+    }
+}
+
+/// Credentials of the process on the other end of a Unix socket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerCredentials {
+    /// Process ID
+    pub pid: i32,
+    /// User ID
+    pub uid: u32,
+    /// Primary group ID
+    pub gid: u32,
+    /// Supplementary group IDs
+    pub groups: Vec<u32>,
+}
+
+impl PeerCredentials {
+    /// Read the peer's credentials (`SO_PEERCRED`) and supplementary groups
+    /// (`SO_PEERGROUPS`)
+    pub fn of(stream: &UnixStream) -> io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+
+        let mut cred = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+        let rc = unsafe {
+            libc::getsockopt(
+                stream.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut cred as *mut libc::ucred as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let groups = match peer_groups(stream.as_raw_fd()) {
+            Ok(groups) => groups,
+            // SO_PEERGROUPS needs Linux 4.13; fall back to the user's groups
+            Err(e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => {
+                user_groups(cred.uid, cred.gid)
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            pid: cred.pid,
+            uid: cred.uid,
+            gid: cred.gid,
+            groups,
+        })
+    }
+
+    /// Whether the peer belongs to `gid`
+    pub fn is_member(&self, gid: u32) -> bool {
+        self.gid == gid || self.groups.contains(&gid)
+    }
+}
+
+/// Supplementary groups the peer had when it connected (`SO_PEERGROUPS`)
+///
+/// Taken from the socket rather than `/proc/<pid>`, which may belong to a
+/// different process once the peer's pid has been reused.
+fn peer_groups(fd: std::os::unix::io::RawFd) -> io::Result<Vec<u32>> {
+    let mut groups: Vec<libc::gid_t> = vec![0; 16];
+    loop {
+        let mut len = std::mem::size_of_val(groups.as_slice()) as libc::socklen_t;
+        let rc = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_PEERGROUPS,
+                groups.as_mut_ptr() as *mut libc::c_void,
+                &mut len,
+            )
+        };
+        let count = len as usize / std::mem::size_of::<libc::gid_t>();
+        if rc == 0 {
+            groups.truncate(count);
+            return Ok(groups);
+        }
+        let err = io::Error::last_os_error();
+        // The kernel reports the size it needs when the buffer is too small
+        if err.raw_os_error() != Some(libc::ERANGE) || count <= groups.len() {
+            return Err(err);
+        }
+        groups.resize(count, 0);
+    }
+}
+
+/// Groups of a user from the group database
+fn user_groups(uid: u32, gid: u32) -> Vec<u32> {
+    use nix::unistd::{getgrouplist, Gid, Uid, User};
+
+    let Ok(Some(user)) = User::from_uid(Uid::from_raw(uid)) else {
+        return Vec::new();
+    };
+    let Ok(name) = std::ffi::CString::new(user.name) else {
+        return Vec::new();
+    };
+    getgrouplist(&name, Gid::from_raw(gid))
+        .map(|groups| groups.into_iter().map(Gid::as_raw).collect())
+        .unwrap_or_default()
+}
+
+/// Whether a peer may send control requests: root, or a member of the
+/// control group
+pub fn is_authorized(peer: &PeerCredentials, control_gid: Option<u32>) -> bool {
+    peer.uid == 0 || control_gid.is_some_and(|gid| peer.is_member(gid))
+}
+
+/// Latest results of the sampler
+#[derive(Default)]
+struct Published {
+    stats: Option<Arc<ServiceStats>>,
+    /// Why the last sample failed
+    error: Option<String>,
+}
+
+/// State shared by the sampler and connection threads
+struct Shared {
+    published: Mutex<Published>,
+    updated: Condvar,
+    control: Box<dyn JetsonControl>,
+    control_group: Option<String>,
+    control_gid: Option<u32>,
+    interval: Duration,
+    max_connections: usize,
+    connections: Arc<AtomicUsize>,
+    max_connections_per_user: usize,
+    /// Open connections per UID
+    user_connections: Mutex<HashMap<u32, usize>>,
+    shutdown: AtomicBool,
+}
+
+/// A running Jetson service
+///
+/// Dropping the handle leaves the service running; call
+/// [`shutdown`](Self::shutdown) to stop it or [`run`](Self::run) to block
+/// on it.
+pub struct JetsonService {
+    shared: Arc<Shared>,
+    stop: mpsc::Sender<()>,
+    threads: Vec<JoinHandle<()>>,
+    socket_path: PathBuf,
+}
+
+impl JetsonService {
+    /// Start sampling [`Simon`] and serving `config.socket_path`, with
+    /// control requests applied to the local hardware
+    pub fn start(config: JetsonServiceConfig) -> Result<Self> {
+        let interval = config.interval.as_secs_f64();
+        Self::start_with(
+            config,
+            move || Simon::with_interval(interval),
+            SystemControl,
+        )
+    }
+
+    /// Start with a custom sample source and controller
+    ///
+    /// `factory` runs on the sampler thread, so the sampler itself need not
+    /// be `Send`.
+    pub fn start_with<S, F, C>(config: JetsonServiceConfig, factory: F, control: C) -> Result<Self>
+    where
+        S: JetsonSampler,
+        F: FnOnce() -> Result<S> + Send + 'static,
+        C: JetsonControl + 'static,
+    {
+        let control_gid = match &config.control_group {
+            Some(name) => {
+                let group = nix::unistd::Group::from_name(name)
+                    .map_err(|e| SimonError::ConfigError(format!("group '{}': {}", name, e)))?;
+                if group.is_none() {
+                    log::warn!(
+                        "simon jetson service: group '{}' does not exist; only root may control",
+                        name
+                    );
+                }
+                group.map(|g| g.gid.as_raw())
+            }
+            None => None,
+        };
+
+        // Anyone may connect and read; control is checked per request
+        let listener = super::server::bind_unix_socket(&config.socket_path, 0o666)?;
+
+        let shared = Arc::new(Shared {
+            published: Mutex::new(Published::default()),
+            updated: Condvar::new(),
+            control: Box::new(control),
+            control_group: config.control_group.clone(),
+            control_gid,
+            interval: config.interval,
+            max_connections: config.max_connections,
+            connections: Arc::new(AtomicUsize::new(0)),
+            max_connections_per_user: config.max_connections_per_user,
+            user_connections: Mutex::new(HashMap::new()),
+            shutdown: AtomicBool::new(false),
+        });
+
+        // The first sample is taken before any client is accepted
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let sampler_shared = Arc::clone(&shared);
+        let sampler =
+            match server::spawn_sampler("simon-jetson-sampler", factory, move |sampler, ready| {
+                sample_loop(sampler, &sampler_shared, stop_rx, ready)
+            }) {
+                Ok(sampler) => sampler,
+                Err(e) => {
+                    let _ = std::fs::remove_file(&config.socket_path);
+                    return Err(e);
+                }
+            };
+
+        let accept_shared = Arc::clone(&shared);
+        let acceptor = thread::spawn(move || accept_loop(listener, &accept_shared));
+
+        Ok(Self {
+            shared,
+            stop: stop_tx,
+            threads: vec![sampler, acceptor],
+            socket_path: config.socket_path,
+        })
+    }
+
+    /// Unix socket path
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Block until the service stops
+    pub fn run(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+
+    /// Stop sampling, close the listener and remove the socket file
+    pub fn shutdown(self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        let _ = self.stop.send(());
+        self.shared.updated.notify_all();
+        // Wake the accept loop
+        let _ = UnixStream::connect(&self.socket_path);
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+fn sample_loop<S: JetsonSampler>(
+    mut sampler: S,
+    shared: &Shared,
+    stop: mpsc::Receiver<()>,
+    mut ready: SamplerReady,
+) {
+    let mut sequence = 0;
+
+    while !shared.shutdown.load(Ordering::SeqCst) {
+        let sample = sampler.sample();
+        if let Ok(mut published) = shared.published.lock() {
+            match sample {
+                Ok(snapshot) => {
+                    sequence += 1;
+                    published.stats = Some(Arc::new(ServiceStats {
+                        sequence,
+                        timestamp_ms: SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|d| d.as_millis() as u64)
+                            .unwrap_or(0),
+                        jetson_clocks: sampler.jetson_clocks(),
+                        nvpmodel: sampler.nvpmodel(),
+                        snapshot,
+                    }));
+                    published.error = None;
+                }
+                Err(e) => {
+                    log::warn!("simon jetson service: sample failed: {}", e);
+                    published.error = Some(e.to_string());
+                }
+            }
+        }
+        shared.updated.notify_all();
+        ready.done();
+
+        match stop.recv_timeout(shared.interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+fn accept_loop(listener: UnixListener, shared: &Arc<Shared>) {
+    let client_shared = Arc::clone(shared);
+    server::accept_loop(
+        listener.incoming(),
+        &shared.connections,
+        shared.max_connections,
+        &shared.shutdown,
+        "simon-jetson-client",
+        |stream| {
+            let _ = write_response(stream, &ServiceResponse::error("too many connections"));
+        },
+        move |stream| serve(stream, &client_shared),
+    );
+}
+
+/// Releases a user's connection slot when its connection ends
+struct UserSlot<'a> {
+    shared: &'a Shared,
+    uid: u32,
+}
+
+impl Drop for UserSlot<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut users) = self.shared.user_connections.lock() {
+            if let Some(count) = users.get_mut(&self.uid) {
+                *count -= 1;
+                if *count == 0 {
+                    users.remove(&self.uid);
+                }
+            }
+        }
+    }
+}
+
+/// Take one of the user's connection slots; root is not limited
+fn user_slot(shared: &Shared, uid: u32) -> Option<UserSlot<'_>> {
+    let mut users = shared.user_connections.lock().ok()?;
+    let count = users.entry(uid).or_insert(0);
+    if uid != 0 && *count >= shared.max_connections_per_user {
+        return None;
+    }
+    *count += 1;
+    Some(UserSlot { shared, uid })
+}
+
+fn serve(stream: UnixStream, shared: &Shared) -> io::Result<()> {
+    // Idle clients and subscribers that stop reading give up their slot
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    stream.set_write_timeout(Some(IDLE_TIMEOUT))?;
+    let peer = PeerCredentials::of(&stream)?;
+    let mut writer = stream;
+    let Some(_slot) = user_slot(shared, peer.uid) else {
+        return write_response(
+            &mut writer,
+            &ServiceResponse::error("too many connections from this user"),
+        );
+    };
+    let can_control = is_authorized(&peer, shared.control_gid);
+    let mut reader = BufReader::new(writer.try_clone()?);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let read = (&mut reader).take(MAX_REQUEST).read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        if !line.ends_with('\n') && read as u64 >= MAX_REQUEST {
+            return write_response(&mut writer, &ServiceResponse::error("request too long"));
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: ServiceRequest = match serde_json::from_str(line.trim()) {
+            Ok(request) => request,
+            Err(e) => {
+                write_response(
+                    &mut writer,
+                    &ServiceResponse::error(format!("invalid request: {}", e)),
+                )?;
+                continue;
+            }
+        };
+        if request == ServiceRequest::Subscribe {
+            return subscribe(&mut writer, shared);
+        }
+        let response = handle(&request, &peer, can_control, shared);
+        write_response(&mut writer, &response)?;
+    }
+}
+
+fn handle(
+    request: &ServiceRequest,
+    peer: &PeerCredentials,
+    can_control: bool,
+    shared: &Shared,
+) -> ServiceResponse {
+    if request.is_control() {
+        let operation = format!(
+            "jetson service: {} (uid {}, pid {})",
+            request.describe(),
+            peer.uid,
+            peer.pid
+        );
+        if !can_control {
+            log_privileged_operation(&operation, false);
+            return ServiceResponse::error(match &shared.control_group {
+                Some(group) => format!(
+                    "permission denied: control requires root or membership in group '{}'",
+                    group
+                ),
+                None => "permission denied: control requires root".to_string(),
+            });
+        }
+        let result = match request {
+            ServiceRequest::Fan { speed, .. } if !(0.0..=100.0).contains(speed) => Err(
+                SimonError::InvalidValue(format!("fan speed {} is outside 0-100", speed)),
+            ),
+            ServiceRequest::Fan { speed, fan } => shared.control.set_fan(fan.as_deref(), *speed),
+            ServiceRequest::Nvpmodel { mode, force } => shared.control.set_nvpmodel(*mode, *force),
+            ServiceRequest::JetsonClocks { enable } => shared.control.jetson_clocks(*enable),
+            _ => unreachable!("not a control request"),
+        };
+        log_privileged_operation(&operation, result.is_ok());
+        return match result {
+            Ok(result) => ServiceResponse {
+                ok: true,
+                result: Some(result),
+                ..Default::default()
+            },
+            Err(e) => ServiceResponse::error(e.to_string()),
+        };
+    }
+
+    match request {
+        ServiceRequest::Info => ServiceResponse {
+            ok: true,
+            info: Some(ServiceInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                interval_ms: shared.interval.as_millis() as u64,
+                control_group: shared.control_group.clone(),
+                can_control,
+            }),
+            ..Default::default()
+        },
+        _ => {
+            let Ok(published) = shared.published.lock() else {
+                return ServiceResponse::error("service state unavailable");
+            };
+            match (&published.stats, &published.error) {
+                (_, Some(error)) => ServiceResponse::error(error.clone()),
+                (Some(stats), None) => ServiceResponse {
+                    ok: true,
+                    stats: Some(ServiceStats::clone(stats)),
+                    ..Default::default()
+                },
+                (None, None) => ServiceResponse::error("no sample yet"),
+            }
+        }
+    }
+}
+
+/// Send every new sample until the client disconnects or the service stops
+fn subscribe(writer: &mut UnixStream, shared: &Shared) -> io::Result<()> {
+    let mut last = 0;
+    loop {
+        let stats = {
+            let mut published = shared
+                .published
+                .lock()
+                .map_err(|_| io::Error::other("service state poisoned"))?;
+            loop {
+                if shared.shutdown.load(Ordering::SeqCst) {
+                    return Ok(());
+                }
+                match &published.stats {
+                    Some(stats) if stats.sequence > last => break Arc::clone(stats),
+                    _ => {}
+                }
+                published = shared
+                    .updated
+                    .wait_timeout(published, WAKE_INTERVAL)
+                    .map_err(|_| io::Error::other("service state poisoned"))?
+                    .0;
+            }
+        };
+        last = stats.sequence;
+        write_response(
+            writer,
+            &ServiceResponse {
+                ok: true,
+                stats: Some(ServiceStats::clone(&stats)),
+                ..Default::default()
+            },
+        )?;
+    }
+}
+
+fn write_response(writer: &mut impl Write, response: &ServiceResponse) -> io::Result<()> {
+    let mut line = serde_json::to_vec(response)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+/// Client for a running [`JetsonService`]
+#[derive(Debug, Clone)]
+pub struct JetsonServiceClient {
+    path: PathBuf,
+    timeout: Duration,
+}
+
+impl JetsonServiceClient {
+    /// Client for the service listening on `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Set the read/write timeout of one-shot requests
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Service settings and whether this client may send control requests
+    pub fn info(&self) -> Result<ServiceInfo> {
+        self.request(&ServiceRequest::Info)?
+            .info
+            .ok_or_else(|| SimonError::Other("reply has no info".into()))
+    }
+
+    /// Latest sample
+    pub fn stats(&self) -> Result<ServiceStats> {
+        self.request(&ServiceRequest::Stats)?
+            .stats
+            .ok_or_else(|| SimonError::Other("reply has no stats".into()))
+    }
+
+    /// Stream every new sample
+    pub fn subscribe(&self) -> Result<StatsStream> {
+        let mut stream = UnixStream::connect(&self.path)?;
+        send_request(&mut stream, &ServiceRequest::Subscribe)?;
+        Ok(StatsStream {
+            reader: BufReader::new(stream),
+        })
+    }
+
+    /// Set a fan's speed (percent); `None` picks the first fan
+    pub fn set_fan(&self, fan: Option<&str>, speed: f32) -> Result<serde_json::Value> {
+        self.control(&ServiceRequest::Fan {
+            speed,
+            fan: fan.map(str::to_string),
+        })
+    }
+
+    /// Switch the nvpmodel power mode
+    pub fn set_nvpmodel(&self, mode: u32, force: bool) -> Result<serde_json::Value> {
+        self.control(&ServiceRequest::Nvpmodel { mode, force })
+    }
+
+    /// Enable or restore jetson_clocks
+    pub fn jetson_clocks(&self, enable: bool) -> Result<serde_json::Value> {
+        self.control(&ServiceRequest::JetsonClocks { enable })
+    }
+
+    /// Send one request and read its reply; a failed reply becomes an error
+    pub fn request(&self, request: &ServiceRequest) -> Result<ServiceResponse> {
+        let mut stream = UnixStream::connect(&self.path)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        send_request(&mut stream, request)?;
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line)?;
+        if line.is_empty() {
+            return Err(SimonError::Other("service closed the connection".into()));
+        }
+        parse_response(&line)?.into_result()
+    }
+
+    fn control(&self, request: &ServiceRequest) -> Result<serde_json::Value> {
+        Ok(self.request(request)?.result.unwrap_or_default())
+    }
+}
+
+fn parse_response(line: &str) -> Result<ServiceResponse> {
+    serde_json::from_str(line).map_err(|e| SimonError::Parse(format!("service reply: {}", e)))
+}
+
+fn send_request(stream: &mut UnixStream, request: &ServiceRequest) -> Result<()> {
+    let mut line = serde_json::to_vec(request).map_err(io::Error::from)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+    Ok(())
+}
+
+/// Samples streamed by [`JetsonServiceClient::subscribe`]
+pub struct StatsStream {
+    reader: BufReader<UnixStream>,
+}
+
+impl Iterator for StatsStream {
+    type Item = Result<ServiceStats>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(
+                parse_response(&line)
+                    .and_then(ServiceResponse::into_result)
+                    .and_then(|r| {
+                        r.stats
+                            .ok_or_else(|| SimonError::Other("reply has no stats".into()))
+                    }),
+            ),
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::tegrastats::TegrastatsLine;
+    use tempfile::TempDir;
+
+    const LINE: &str = "RAM 2024/7765MB (lfb 1x4MB) SWAP 0/3882MB (cached 0MB) \
+        CPU [12%@1190,8%@1190,off,off] GR3D_FREQ 0%@[305] cpu@45.5C gpu@44C";
+
+    struct FakeSampler(Snapshot);
+
+    impl JetsonSampler for FakeSampler {
+        fn sample(&mut self) -> Result<Snapshot> {
+            Ok(self.0.clone())
+        }
+
+        fn jetson_clocks(&mut self) -> bool {
+            true
+        }
+
+        fn nvpmodel(&mut self) -> Option<u32> {
+            Some(2)
+        }
+    }
+
+    #[derive(Default)]
+    struct FakeControl(Arc<Mutex<Vec<String>>>);
+
+    impl JetsonControl for FakeControl {
+        fn set_fan(&self, fan: Option<&str>, speed: f32) -> Result<serde_json::Value> {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("fan {:?} {}", fan, speed));
+            Ok(serde_json::json!({ "speed": speed }))
+        }
+
+        fn set_nvpmodel(&self, mode: u32, _force: bool) -> Result<serde_json::Value> {
+            Err(SimonError::InvalidValue(format!("no mode {}", mode)))
+        }
+
+        fn jetson_clocks(&self, enable: bool) -> Result<serde_json::Value> {
+            self.0.lock().unwrap().push(format!("clocks {}", enable));
+            Ok(serde_json::json!({ "enabled": enable }))
+        }
+    }
+
+    #[test]
+    fn test_request_schema() {
+        let parse = |s: &str| serde_json::from_str::<ServiceRequest>(s).unwrap();
+        assert_eq!(parse(r#"{"cmd":"stats"}"#), ServiceRequest::Stats);
+        assert_eq!(
+            parse(r#"{"cmd":"fan","speed":80}"#),
+            ServiceRequest::Fan {
+                speed: 80.0,
+                fan: None
+            }
+        );
+        assert_eq!(
+            parse(r#"{"cmd":"nvpmodel","mode":1}"#),
+            ServiceRequest::Nvpmodel {
+                mode: 1,
+                force: false
+            }
+        );
+        assert!(parse(r#"{"cmd":"jetson_clocks","enable":false}"#).is_control());
+        assert!(serde_json::from_str::<ServiceRequest>(r#"{"cmd":"reboot"}"#).is_err());
+
+        let reply = serde_json::to_string(&ServiceResponse::error("nope")).unwrap();
+        assert_eq!(reply, r#"{"ok":false,"error":"nope"}"#);
+    }
+
+    #[test]
+    fn test_authorization() {
+        let (a, _b) = UnixStream::pair().unwrap();
+        let me = PeerCredentials::of(&a).unwrap();
+        assert_eq!(me.pid, std::process::id() as i32);
+        assert_eq!(me.uid, unsafe { libc::geteuid() });
+        let mut groups: Vec<u32> = nix::unistd::getgroups()
+            .unwrap()
+            .into_iter()
+            .map(|g| g.as_raw())
+            .collect();
+        groups.sort_unstable();
+        let mut peer_groups = me.groups.clone();
+        peer_groups.sort_unstable();
+        assert_eq!(peer_groups, groups);
+
+        let user = PeerCredentials {
+            pid: 1,
+            uid: 1000,
+            gid: 1000,
+            groups: vec![4, 27, 998],
+        };
+        assert!(is_authorized(&user, Some(998)));
+        assert!(is_authorized(&user, Some(1000)));
+        assert!(!is_authorized(&user, Some(999)));
+        assert!(!is_authorized(&user, None));
+
+        let root = PeerCredentials {
+            uid: 0,
+            gid: 0,
+            groups: Vec::new(),
+            ..user
+        };
+        assert!(is_authorized(&root, None));
+    }
+
+    #[test]
+    fn test_service_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let socket = dir.path().join("jtop.sock");
+        // Our own primary group grants control whether or not tests run as root
+        let group = nix::unistd::Group::from_gid(nix::unistd::getgid())
+            .unwrap()
+            .map(|g| g.name);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let snapshot = LINE.parse::<TegrastatsLine>().unwrap().to_snapshot();
+        let config = JetsonServiceConfig::default()
+            .with_socket_path(&socket)
+            .with_interval(Duration::from_millis(20))
+            .with_control_group(group);
+        let service = JetsonService::start_with(
+            config,
+            move || Ok(FakeSampler(snapshot)),
+            FakeControl(Arc::clone(&calls)),
+        )
+        .unwrap();
+
+        let client = JetsonServiceClient::new(service.socket_path());
+        let info = client.info().unwrap();
+        assert!(info.can_control);
+
+        let stats = client.stats().unwrap();
+        assert!(stats.sequence >= 1);
+        assert!(stats.jetson_clocks);
+        assert_eq!(stats.nvpmodel, Some(2));
+        assert_eq!(stats.snapshot.memory.ram.total, 7765 * 1024);
+
+        let sequences: Vec<u64> = client
+            .subscribe()
+            .unwrap()
+            .take(2)
+            .map(|s| s.unwrap().sequence)
+            .collect();
+        assert!(sequences[1] > sequences[0]);
+
+        client.set_fan(None, 60.0).unwrap();
+        assert!(client.set_fan(None, 150.0).is_err());
+        assert!(client.set_fan(None, -1.0).is_err());
+        client.jetson_clocks(false).unwrap();
+        assert!(client.set_nvpmodel(7, false).is_err());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["fan None 60".to_string(), "clocks false".to_string()]
+        );
+
+        service.shutdown();
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,496 @@
+//! Long-running monitoring daemon (`simond`) with a local HTTP/WebSocket API
+//!
+//! One daemon owns a single [`MonitoringBackend`] and samples it on a fixed
+//! interval, so the TUI, GUI, `amon` and scripts share its collectors instead
+//! of each paying discovery cost again. Clients talk to it over a Unix socket
+//! and, optionally, TCP:
+//!
+//! | Method | Path | Permission | Body |
+//! |--------|------|------------|------|
+//! | GET | `/v1/info` | read | [`DaemonInfo`] |
+//! | GET | `/v1/state` | read | [`FullSystemState`] |
+//! | GET | `/v1/snapshot` | read | [`Snapshot`] |
+//! | GET | `/v1/processes` | read | `[ProcessMonitorInfo]` |
+//! | GET | `/v1/connections` | read | `[ConnectionInfo]` |
+//! | GET | `/v1/health` | read | [`SystemHealth`] |
+//! | GET | `/v1/events` | read | `[HardwareEvent]` |
+//! | GET | `/v1/history` | read | metric names |
+//! | GET | `/v1/history/<metric>` | read | [`MetricHistory`] |
+//! | GET | `/v1/stream?topics=state,health,events` | read | WebSocket of [`StreamMessage`] |
+//! | POST | `/v1/control/interval` | control | `{"interval_ms": N}` |
+//! | POST | `/v1/control/reset-history` | control | |
+//! | POST | `/v1/control/refresh` | control | |
+//!
+//! Requests authenticate with `Authorization: Bearer <token>` (or
+//! `?token=` for browser WebSockets). Each [`ApiToken`] grants read-only or
+//! control [`Permission`]. Unix socket clients without a token get
+//! [`DaemonConfig::unix_permission`], so file permissions on the socket
+//! decide who may read; TCP always requires a token.
+//!
+//! On Jetson, [`JetsonService`] (Linux only) is a separate jtop-style service
+//! that serves [`stats::Snapshot`](crate::stats::Snapshot)s over a
+//! line-based JSON socket and gates fan, nvpmodel and jetson_clocks control
+//! by group membership.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::daemon::{ApiToken, Daemon, DaemonConfig, Permission};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = DaemonConfig::default()
+//!     .with_listen("127.0.0.1:9465".parse()?)
+//!     .with_token(ApiToken::new("s3cret", Permission::Read));
+//! let daemon = Daemon::start(config)?;
+//! daemon.run();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Reading from a running daemon:
+//!
+//! ```no_run
+//! use simon::daemon::{DaemonClient, Topic};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = DaemonClient::new("unix:/run/simond.sock".parse()?);
+//! let state = client.state()?;
+//! println!("{} accelerators", state.accelerators.len());
+//!
+//! for message in client.subscribe(&[Topic::Health])? {
+//!     println!("{}: {}", message.topic, message.data);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+pub(crate) mod http;
+#[cfg(target_os = "linux")]
+mod jetson;
+mod server;
+mod websocket;
+
+pub use client::{DaemonClient, Endpoint, Subscription};
+#[cfg(target_os = "linux")]
+pub use jetson::{
+    is_authorized, JetsonControl, JetsonSampler, JetsonService, JetsonServiceClient,
+    JetsonServiceConfig, PeerCredentials, ServiceInfo, ServiceRequest, ServiceResponse,
+    ServiceStats, StatsStream, SystemControl, DEFAULT_CONTROL_GROUP, DEFAULT_JETSON_SOCKET,
+};
+pub use server::Daemon;
+
+use crate::backend::{BackendConfig, FullSystemState, MonitoringBackend};
+use crate::connections::ConnectionInfo;
+use crate::core::cpu::CpuStats;
+use crate::core::memory::MemoryStats;
+use crate::error::{Result, SimonError};
+use crate::gpu::GpuInfo;
+use crate::health::{HealthMonitor, SystemHealth};
+use crate::process_monitor::ProcessMonitorInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default TCP port when TCP is enabled without an explicit address
+pub const DEFAULT_PORT: u16 = 9465;
+
+/// What a client may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// Read snapshots, history and streams
+    Read,
+    /// Read, plus change the sampling interval and reset history
+    Control,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Permission::Read => "read",
+            Permission::Control => "control",
+        })
+    }
+}
+
+impl std::str::FromStr for Permission {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "read" | "ro" | "read-only" => Ok(Permission::Read),
+            "control" | "rw" => Ok(Permission::Control),
+            _ => Err(SimonError::InvalidValue(format!(
+                "unknown permission '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+/// A bearer token and what it allows
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    /// Secret sent as `Authorization: Bearer <token>`
+    pub token: String,
+    /// Granted permission
+    pub permission: Permission,
+}
+
+impl ApiToken {
+    /// Create a token
+    pub fn new(token: impl Into<String>, permission: Permission) -> Self {
+        Self {
+            token: token.into(),
+            permission,
+        }
+    }
+}
+
+/// Parse a token file: one `<token> [read|control]` per line, `#` comments
+///
+/// Tokens without a permission are read-only.
+pub fn parse_tokens(content: &str) -> Result<Vec<ApiToken>> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let token = fields.next().unwrap_or_default();
+            let permission = match fields.next() {
+                Some(permission) => permission.parse()?,
+                None => Permission::Read,
+            };
+            Ok(ApiToken::new(token, permission))
+        })
+        .collect()
+}
+
+/// Load a token file (see [`parse_tokens`])
+pub fn load_tokens(path: &Path) -> Result<Vec<ApiToken>> {
+    parse_tokens(&std::fs::read_to_string(path)?)
+}
+
+/// Daemon settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// Unix socket to listen on (`None` disables it)
+    pub socket_path: Option<PathBuf>,
+    /// TCP address to listen on (`None` disables TCP)
+    pub listen: Option<SocketAddr>,
+    /// Accepted bearer tokens
+    pub tokens: Vec<ApiToken>,
+    /// Permission of Unix socket clients that send no token (`None` requires one)
+    pub unix_permission: Option<Permission>,
+    /// Sampling interval
+    pub interval: Duration,
+    /// Samples kept per history metric
+    pub history_size: usize,
+    /// How often the (slower) health checks run
+    pub health_interval: Duration,
+    /// Most concurrent client connections
+    pub max_connections: usize,
+    /// Services the health checks alert on
+    #[serde(default)]
+    pub services: Vec<String>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            socket_path: Some(default_socket_path()),
+            listen: None,
+            tokens: Vec::new(),
+            unix_permission: Some(Permission::Read),
+            interval: Duration::from_secs(1),
+            history_size: 300,
+            health_interval: Duration::from_secs(10),
+            max_connections: 64,
+            services: Vec::new(),
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// Listen on a different Unix socket, or none
+    pub fn with_socket_path(mut self, path: Option<PathBuf>) -> Self {
+        self.socket_path = path;
+        self
+    }
+
+    /// Also listen on TCP
+    pub fn with_listen(mut self, addr: SocketAddr) -> Self {
+        self.listen = Some(addr);
+        self
+    }
+
+    /// Accept a bearer token
+    pub fn with_token(mut self, token: ApiToken) -> Self {
+        self.tokens.push(token);
+        self
+    }
+
+    /// Permission of Unix socket clients without a token
+    pub fn with_unix_permission(mut self, permission: Option<Permission>) -> Self {
+        self.unix_permission = permission;
+        self
+    }
+
+    /// Set the sampling interval
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of samples kept per history metric
+    pub fn with_history_size(mut self, size: usize) -> Self {
+        self.history_size = size;
+        self
+    }
+
+    /// Set the health check interval
+    pub fn with_health_interval(mut self, interval: Duration) -> Self {
+        self.health_interval = interval;
+        self
+    }
+
+    /// Alert on a service in the health checks
+    pub fn with_service(mut self, name: impl Into<String>) -> Self {
+        self.services.push(name.into());
+        self
+    }
+}
+
+/// Socket path used when none is configured
+///
+/// `/run/simond.sock` for root, `$XDG_RUNTIME_DIR/simond.sock` otherwise,
+/// falling back to the temp directory.
+pub fn default_socket_path() -> PathBuf {
+    #[cfg(unix)]
+    if unsafe { libc::geteuid() } == 0 {
+        return PathBuf::from("/run/simond.sock");
+    }
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => PathBuf::from(dir).join("simond.sock"),
+        None => std::env::temp_dir().join("simond.sock"),
+    }
+}
+
+/// Everything one sample produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Summary state (the same shape the AI agent sees)
+    pub state: FullSystemState,
+    /// Raw CPU statistics
+    pub cpu: Option<CpuStats>,
+    /// Raw memory statistics
+    pub memory: Option<MemoryStats>,
+    /// Static and dynamic GPU information
+    pub gpus: Vec<GpuInfo>,
+    /// Processes with GPU attribution
+    pub processes: Vec<ProcessMonitorInfo>,
+    /// Network connections
+    pub connections: Vec<ConnectionInfo>,
+    /// Recent values per history metric, oldest first
+    pub history: BTreeMap<String, Vec<f32>>,
+}
+
+impl Snapshot {
+    /// A snapshot with no data
+    pub fn empty() -> Self {
+        Self {
+            state: FullSystemState::empty(),
+            cpu: None,
+            memory: None,
+            gpus: Vec::new(),
+            processes: Vec::new(),
+            connections: Vec::new(),
+            history: BTreeMap::new(),
+        }
+    }
+}
+
+/// Recent values of one metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricHistory {
+    /// Metric name (`cpu.utilization`, `gpu.0.temperature`)
+    pub metric: String,
+    /// Time between samples (ms)
+    pub interval_ms: u64,
+    /// Values, oldest first
+    pub values: Vec<f32>,
+}
+
+/// Daemon identity and settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonInfo {
+    /// simon version
+    pub version: String,
+    /// Host name
+    pub hostname: String,
+    /// Operating system
+    pub os: String,
+    /// Current sampling interval (ms)
+    pub interval_ms: u64,
+    /// Seconds since the daemon started
+    pub uptime_secs: u64,
+    /// Samples taken so far
+    pub sequence: u64,
+    /// The caller's permission
+    pub permission: Permission,
+}
+
+/// A WebSocket stream topic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    /// [`FullSystemState`] after every sample
+    State,
+    /// The full [`Snapshot`] after every sample
+    Snapshot,
+    /// [`SystemHealth`] whenever the checks run
+    Health,
+    /// Each new hardware event
+    Events,
+}
+
+impl Topic {
+    /// Every topic
+    pub const ALL: [Topic; 4] = [Topic::State, Topic::Snapshot, Topic::Health, Topic::Events];
+
+    /// Name used in `?topics=` and messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::State => "state",
+            Topic::Snapshot => "snapshot",
+            Topic::Health => "health",
+            Topic::Events => "events",
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Topic {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Topic::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| SimonError::InvalidValue(format!("unknown topic '{}'", s)))
+    }
+}
+
+/// One message on a WebSocket stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamMessage {
+    /// Topic the message belongs to
+    pub topic: Topic,
+    /// Sample sequence number
+    pub sequence: u64,
+    /// Payload; its shape depends on the topic
+    pub data: serde_json::Value,
+}
+
+/// Source of the daemon's samples
+///
+/// [`BackendSampler`] is the real one; tests and stand-in endpoints can
+/// provide their own through [`Daemon::start_with`].
+pub trait Sampler {
+    /// Take one sample
+    fn sample(&mut self) -> Result<Snapshot>;
+
+    /// Run the health checks
+    fn health(&mut self) -> Result<SystemHealth> {
+        SystemHealth::check()
+    }
+
+    /// Clear history buffers
+    fn reset_history(&mut self) {}
+}
+
+/// Samples a [`MonitoringBackend`]
+pub struct BackendSampler {
+    backend: MonitoringBackend,
+    health: HealthMonitor,
+}
+
+impl BackendSampler {
+    /// Create the backend (without the AI agent)
+    pub fn new(config: &DaemonConfig) -> Result<Self> {
+        let backend = MonitoringBackend::with_config(
+            BackendConfig::without_agent()
+                .with_history_size(config.history_size)
+                .with_update_interval(config.interval),
+        )?;
+        Ok(Self {
+            backend,
+            health: HealthMonitor::new().with_services(config.services.clone()),
+        })
+    }
+}
+
+impl Sampler for BackendSampler {
+    fn sample(&mut self) -> Result<Snapshot> {
+        let backend = &mut self.backend;
+        backend.update()?;
+
+        let mut history = BTreeMap::new();
+        history.insert(
+            "cpu.utilization".to_string(),
+            backend.cpu_history().to_vec(),
+        );
+        history.insert(
+            "memory.utilization".to_string(),
+            backend.memory_history().to_vec(),
+        );
+        for index in 0..backend.gpu_count() {
+            let buffers = [
+                ("utilization", backend.accelerator_history(index)),
+                ("memory", backend.accelerator_memory_history(index)),
+                ("temperature", backend.accelerator_temp_history(index)),
+            ];
+            for (name, buffer) in buffers {
+                if let Some(buffer) = buffer {
+                    history.insert(format!("gpu.{}.{}", index, name), buffer.to_vec());
+                }
+            }
+        }
+
+        Ok(Snapshot {
+            state: backend.get_full_system_state(),
+            cpu: backend.cpu_stats().cloned(),
+            memory: backend.memory_stats().cloned(),
+            gpus: backend
+                .gpu_static_info()
+                .iter()
+                .zip(backend.gpu_dynamic_info())
+                .map(|(static_info, dynamic_info)| GpuInfo {
+                    static_info: static_info.clone(),
+                    dynamic_info: dynamic_info.clone(),
+                })
+                .collect(),
+            processes: backend.processes().to_vec(),
+            connections: backend.connections().to_vec(),
+            history,
+        })
+    }
+
+    fn health(&mut self) -> Result<SystemHealth> {
+        self.health.check()
+    }
+
+    fn reset_history(&mut self) {
+        self.backend.reset_history();
+    }
+}
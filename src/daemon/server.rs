@@ -0,0 +1,977 @@
+//! Listeners, request routing and the sampler thread
+
+use super::http::{self, Request, Response};
+use super::websocket;
+use super::{
+    ApiToken, BackendSampler, DaemonConfig, DaemonInfo, MetricHistory, Permission, Sampler,
+    Snapshot, StreamMessage, Topic,
+};
+use crate::error::{Result, SimonError};
+use crate::health::SystemHealth;
+use crate::hwlog::HardwareEvent;
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Idle time after which a keep-alive connection is closed
+pub(super) const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Idle time after which a stream is pinged
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// Hardware events kept for stream subscribers
+const MAX_EVENTS: usize = 256;
+/// Largest message accepted from a stream client
+const MAX_CLIENT_MESSAGE: usize = 4096;
+/// Shortest sampling interval a client may set
+const MIN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Requests from clients to the sampler thread
+enum Control {
+    SetInterval(Duration),
+    ResetHistory,
+    Refresh,
+    Shutdown,
+}
+
+/// Which listener a connection came in on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Tcp,
+    #[cfg(unix)]
+    Unix,
+}
+
+/// Latest results of the sampler
+#[derive(Default)]
+struct Published {
+    sequence: u64,
+    interval: Duration,
+    snapshot: Option<Arc<Snapshot>>,
+    /// Health result with the sequence it was computed at
+    health: Option<(u64, Arc<SystemHealth>)>,
+    /// New hardware events with the sequence they appeared at
+    events: VecDeque<(u64, HardwareEvent)>,
+    /// Why the last sample failed
+    error: Option<String>,
+}
+
+/// State shared by the sampler and connection threads
+struct Shared {
+    published: Mutex<Published>,
+    updated: Condvar,
+    control: Mutex<Sender<Control>>,
+    tokens: Vec<ApiToken>,
+    unix_permission: Option<Permission>,
+    max_connections: usize,
+    connections: Arc<AtomicUsize>,
+    shutdown: AtomicBool,
+    started: Instant,
+    hostname: String,
+}
+
+impl Shared {
+    fn send(&self, control: Control) -> bool {
+        self.control
+            .lock()
+            .map(|sender| sender.send(control).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+/// A running daemon
+///
+/// Dropping the handle leaves the daemon running; call
+/// [`shutdown`](Self::shutdown) to stop it or [`run`](Self::run) to block
+/// on it.
+pub struct Daemon {
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+    local_addr: Option<SocketAddr>,
+    socket_path: Option<PathBuf>,
+}
+
+impl Daemon {
+    /// Start sampling a [`MonitoringBackend`](crate::backend::MonitoringBackend)
+    /// and serving `config`'s listeners
+    pub fn start(config: DaemonConfig) -> Result<Self> {
+        let sampler_config = config.clone();
+        Self::start_with(config, move || BackendSampler::new(&sampler_config))
+    }
+
+    /// Start with a custom sample source
+    ///
+    /// `factory` runs on the sampler thread, so the sampler itself need not
+    /// be `Send`.
+    pub fn start_with<S, F>(config: DaemonConfig, factory: F) -> Result<Self>
+    where
+        S: Sampler,
+        F: FnOnce() -> Result<S> + Send + 'static,
+    {
+        if config.listen.is_some() && config.tokens.is_empty() {
+            return Err(SimonError::ConfigError(
+                "listening on TCP requires at least one API token".into(),
+            ));
+        }
+        if config.listen.is_none() && config.socket_path.is_none() {
+            return Err(SimonError::ConfigError(
+                "no Unix socket or TCP address to listen on".into(),
+            ));
+        }
+
+        let tcp = config.listen.map(TcpListener::bind).transpose()?;
+        let local_addr = tcp.as_ref().map(|l| l.local_addr()).transpose()?;
+        #[cfg(unix)]
+        let unix = config
+            .socket_path
+            .as_deref()
+            .map(|path| bind_unix_socket(path, 0o660))
+            .transpose()?;
+
+        let (control_tx, control_rx) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            published: Mutex::new(Published {
+                interval: config.interval,
+                ..Default::default()
+            }),
+            updated: Condvar::new(),
+            control: Mutex::new(control_tx),
+            tokens: config.tokens.clone(),
+            unix_permission: config.unix_permission,
+            max_connections: config.max_connections,
+            connections: Arc::new(AtomicUsize::new(0)),
+            shutdown: AtomicBool::new(false),
+            started: Instant::now(),
+            hostname: hostname::get()
+                .map(|h| h.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        });
+
+        // The first sample is taken before any client is accepted
+        let sampler_shared = Arc::clone(&shared);
+        let (interval, health_interval) = (config.interval, config.health_interval);
+        let sampler = spawn_sampler("simond-sampler", factory, move |sampler, ready| {
+            sample_loop(
+                sampler,
+                &sampler_shared,
+                control_rx,
+                interval,
+                health_interval,
+                ready,
+            )
+        })?;
+
+        let mut threads = vec![sampler];
+        if let Some(listener) = tcp {
+            let shared = Arc::clone(&shared);
+            threads.push(thread::spawn(move || {
+                accept_clients(listener.incoming(), Transport::Tcp, &shared)
+            }));
+        }
+        #[cfg(unix)]
+        if let Some(listener) = unix {
+            let shared = Arc::clone(&shared);
+            threads.push(thread::spawn(move || {
+                accept_clients(listener.incoming(), Transport::Unix, &shared)
+            }));
+        }
+
+        Ok(Self {
+            shared,
+            threads,
+            local_addr,
+            socket_path: config.socket_path,
+        })
+    }
+
+    /// Bound TCP address (useful when listening on port 0)
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// Unix socket path
+    pub fn socket_path(&self) -> Option<&Path> {
+        self.socket_path.as_deref()
+    }
+
+    /// Block until the daemon stops
+    pub fn run(self) {
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+    }
+
+    /// Stop sampling, close the listeners and remove the socket file
+    pub fn shutdown(self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        self.shared.send(Control::Shutdown);
+        self.shared.updated.notify_all();
+        // Wake the accept loops
+        if let Some(addr) = self.local_addr {
+            let _ = TcpStream::connect(addr);
+        }
+        #[cfg(unix)]
+        if let Some(path) = &self.socket_path {
+            let _ = std::os::unix::net::UnixStream::connect(path);
+        }
+        for thread in self.threads {
+            let _ = thread.join();
+        }
+        #[cfg(unix)]
+        if let Some(path) = &self.socket_path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Bind the Unix socket with `mode`, replacing a stale socket file from a
+/// previous run
+#[cfg(unix)]
+pub(super) fn bind_unix_socket(path: &Path, mode: u32) -> Result<std::os::unix::net::UnixListener> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(SimonError::InitializationError(format!(
+                "another daemon is listening on {}",
+                path.display()
+            )));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(listener)
+}
+
+/// Reports the sampler's first sample to [`spawn_sampler`]
+pub(super) struct SamplerReady(Option<mpsc::SyncSender<Result<()>>>);
+
+impl SamplerReady {
+    /// Mark the first sample as published; later calls do nothing
+    pub(super) fn done(&mut self) {
+        if let Some(ready) = self.0.take() {
+            let _ = ready.send(Ok(()));
+        }
+    }
+}
+
+/// Spawn a sampler thread and wait until it has published its first sample
+///
+/// `factory` runs on the new thread, so the sampler itself need not be
+/// `Send`; its error is returned from here. `run` gets the sampler and
+/// calls [`SamplerReady::done`] once the first sample is published.
+pub(super) fn spawn_sampler<S, F, L>(name: &str, factory: F, run: L) -> Result<JoinHandle<()>>
+where
+    F: FnOnce() -> Result<S> + Send + 'static,
+    L: FnOnce(S, SamplerReady) + Send + 'static,
+{
+    let (ready_tx, ready_rx) = mpsc::sync_channel(1);
+    let thread = thread::Builder::new()
+        .name(name.into())
+        .spawn(move || match factory() {
+            Ok(sampler) => run(sampler, SamplerReady(Some(ready_tx))),
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+            }
+        })?;
+    ready_rx
+        .recv()
+        .map_err(|_| SimonError::InitializationError("sampler thread exited".into()))??;
+    Ok(thread)
+}
+
+fn sample_loop<S: Sampler>(
+    mut sampler: S,
+    shared: &Shared,
+    control: Receiver<Control>,
+    mut interval: Duration,
+    health_interval: Duration,
+    mut ready: SamplerReady,
+) {
+    let mut last_health: Option<Instant> = None;
+    let mut previous_events: Vec<HardwareEvent> = Vec::new();
+
+    while !shared.shutdown.load(Ordering::SeqCst) {
+        let sample = sampler.sample();
+        let health = match last_health {
+            Some(at) if at.elapsed() < health_interval => None,
+            _ => {
+                last_health = Some(Instant::now());
+                sampler.health().ok()
+            }
+        };
+
+        if let Ok(mut published) = shared.published.lock() {
+            published.sequence += 1;
+            let sequence = published.sequence;
+            match sample {
+                Ok(snapshot) => {
+                    let events = &snapshot.state.hardware_events;
+                    for event in events.iter().filter(|e| !previous_events.contains(e)) {
+                        published.events.push_back((sequence, event.clone()));
+                    }
+                    while published.events.len() > MAX_EVENTS {
+                        published.events.pop_front();
+                    }
+                    previous_events = events.clone();
+                    published.snapshot = Some(Arc::new(snapshot));
+                    published.error = None;
+                }
+                Err(e) => {
+                    log::warn!("simond: sample failed: {}", e);
+                    published.error = Some(e.to_string());
+                }
+            }
+            if let Some(health) = health {
+                published.health = Some((sequence, Arc::new(health)));
+            }
+        }
+        shared.updated.notify_all();
+        ready.done();
+
+        let deadline = Instant::now() + interval;
+        loop {
+            match control.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(Control::SetInterval(new_interval)) => {
+                    interval = new_interval;
+                    if let Ok(mut published) = shared.published.lock() {
+                        published.interval = interval;
+                    }
+                    break;
+                }
+                Ok(Control::ResetHistory) => sampler.reset_history(),
+                Ok(Control::Refresh) | Err(RecvTimeoutError::Timeout) => break,
+                Ok(Control::Shutdown) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+/// A client connection that can be split into reader and writer halves
+trait Connection: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+    fn shutdown(&self);
+}
+
+impl Connection for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn shutdown(&self) {
+        let _ = TcpStream::shutdown(self, Shutdown::Both);
+    }
+}
+
+#[cfg(unix)]
+impl Connection for std::os::unix::net::UnixStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        std::os::unix::net::UnixStream::try_clone(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        std::os::unix::net::UnixStream::set_read_timeout(self, timeout)
+    }
+
+    fn shutdown(&self) {
+        let _ = std::os::unix::net::UnixStream::shutdown(self, Shutdown::Both);
+    }
+}
+
+/// Decrements the connection count when a connection thread ends
+struct ConnectionGuard(Arc<AtomicUsize>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Serve each accepted connection on its own `thread_name` thread
+///
+/// At most `max_connections` are served at once, counted in `connections`;
+/// `reject` answers the ones over the limit before they are closed. Stops
+/// at the first connection after `shutdown` is set.
+pub(super) fn accept_loop<C, R, F>(
+    incoming: impl Iterator<Item = io::Result<C>>,
+    connections: &Arc<AtomicUsize>,
+    max_connections: usize,
+    shutdown: &AtomicBool,
+    thread_name: &str,
+    reject: R,
+    serve: F,
+) where
+    C: Send + 'static,
+    R: Fn(&mut C),
+    F: Fn(C) -> io::Result<()> + Clone + Send + 'static,
+{
+    for stream in incoming {
+        if shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        if connections.fetch_add(1, Ordering::SeqCst) >= max_connections {
+            connections.fetch_sub(1, Ordering::SeqCst);
+            reject(&mut stream);
+            continue;
+        }
+        // Dropped with the closure if the thread can't be spawned
+        let guard = ConnectionGuard(Arc::clone(connections));
+        let serve = serve.clone();
+        let name = thread_name.to_string();
+        let _ = thread::Builder::new()
+            .name(thread_name.into())
+            .spawn(move || {
+                let _guard = guard;
+                if let Err(e) = serve(stream) {
+                    log::debug!("{}: connection ended: {}", name, e);
+                }
+            });
+    }
+}
+
+fn accept_clients<C: Connection>(
+    incoming: impl Iterator<Item = io::Result<C>>,
+    transport: Transport,
+    shared: &Arc<Shared>,
+) {
+    let client_shared = Arc::clone(shared);
+    accept_loop(
+        incoming,
+        &shared.connections,
+        shared.max_connections,
+        &shared.shutdown,
+        "simond-client",
+        |stream: &mut C| {
+            let _ = Response::error(503, "too many connections").write_to(stream, false);
+        },
+        move |stream| serve(stream, transport, &client_shared),
+    );
+}
+
+fn serve<C: Connection>(stream: C, transport: Transport, shared: &Arc<Shared>) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let request = match http::read_request(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                return Response::error(400, &e.to_string()).write_to(&mut writer, false);
+            }
+            Err(e) => return Err(e),
+        };
+        let permission = match authenticate(&request, transport, shared) {
+            Ok(permission) => permission,
+            Err(response) => return response.write_to(&mut writer, false),
+        };
+        if request.path == "/v1/stream" {
+            return stream_topics(&request, reader, writer, shared);
+        }
+        let keep_alive = request.keep_alive();
+        route(&request, permission, shared).write_to(&mut writer, keep_alive)?;
+        if !keep_alive {
+            return Ok(());
+        }
+    }
+}
+
+/// Work out the caller's permission from its token and transport
+fn authenticate(
+    request: &Request,
+    transport: Transport,
+    shared: &Shared,
+) -> std::result::Result<Permission, Response> {
+    let token = request
+        .header("Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .or_else(|| request.query("token"));
+    let unauthorized = |message: &str| {
+        Response::error(401, message).with_header("WWW-Authenticate", "Bearer realm=\"simond\"")
+    };
+    match token {
+        Some(token) => shared
+            .tokens
+            .iter()
+            .find(|t| constant_time_eq(t.token.as_bytes(), token.as_bytes()))
+            .map(|t| t.permission)
+            .ok_or_else(|| unauthorized("invalid token")),
+        None => match transport {
+            #[cfg(unix)]
+            Transport::Unix => shared
+                .unix_permission
+                .ok_or_else(|| unauthorized("token required")),
+            Transport::Tcp => Err(unauthorized("token required")),
+        },
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn route(request: &Request, permission: Permission, shared: &Shared) -> Response {
+    let path = request.path.trim_end_matches('/');
+    if let Some(action) = path.strip_prefix("/v1/control/") {
+        if request.method != "POST" {
+            return Response::error(405, "use POST").with_header("Allow", "POST");
+        }
+        if permission < Permission::Control {
+            return Response::error(403, "control permission required");
+        }
+        return control(action, request, shared);
+    }
+    if request.method != "GET" {
+        return Response::error(405, "use GET").with_header("Allow", "GET");
+    }
+
+    let (snapshot, health, sequence, interval, error) = match shared.published.lock() {
+        Ok(published) => (
+            published.snapshot.clone(),
+            published.health.as_ref().map(|(_, h)| Arc::clone(h)),
+            published.sequence,
+            published.interval,
+            published.error.clone(),
+        ),
+        Err(_) => return Response::error(500, "daemon state poisoned"),
+    };
+    let unavailable = || {
+        Response::error(
+            503,
+            error.as_deref().unwrap_or("no sample has been taken yet"),
+        )
+    };
+
+    match path {
+        "/v1/info" => Response::json(
+            200,
+            &DaemonInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                hostname: shared.hostname.clone(),
+                os: std::env::consts::OS.to_string(),
+                interval_ms: interval.as_millis() as u64,
+                uptime_secs: shared.started.elapsed().as_secs(),
+                sequence,
+                permission,
+            },
+        ),
+        "/v1/health" => match health {
+            Some(health) => Response::json(200, &*health),
+            None => Response::error(503, "health checks have not run yet"),
+        },
+        "/v1/history" => match snapshot {
+            Some(snapshot) => Response::json(200, &snapshot.history.keys().collect::<Vec<_>>()),
+            None => unavailable(),
+        },
+        _ => {
+            let Some(snapshot) = snapshot else {
+                return if is_snapshot_path(path) {
+                    unavailable()
+                } else {
+                    Response::error(404, "no such endpoint")
+                };
+            };
+            match path {
+                "/v1/state" => Response::json(200, &snapshot.state),
+                "/v1/snapshot" => Response::json(200, &*snapshot),
+                "/v1/processes" => Response::json(200, &snapshot.processes),
+                "/v1/connections" => Response::json(200, &snapshot.connections),
+                "/v1/events" => Response::json(200, &snapshot.state.hardware_events),
+                _ => match path.strip_prefix("/v1/history/") {
+                    Some(metric) => match snapshot.history.get(metric) {
+                        Some(values) => Response::json(
+                            200,
+                            &MetricHistory {
+                                metric: metric.to_string(),
+                                interval_ms: interval.as_millis() as u64,
+                                values: values.clone(),
+                            },
+                        ),
+                        None => Response::error(404, &format!("unknown metric '{}'", metric)),
+                    },
+                    None => Response::error(404, "no such endpoint"),
+                },
+            }
+        }
+    }
+}
+
+fn is_snapshot_path(path: &str) -> bool {
+    matches!(
+        path,
+        "/v1/state" | "/v1/snapshot" | "/v1/processes" | "/v1/connections" | "/v1/events"
+    ) || path.starts_with("/v1/history/")
+}
+
+fn control(action: &str, request: &Request, shared: &Shared) -> Response {
+    let sent = match action {
+        "interval" => {
+            let interval_ms = serde_json::from_slice::<serde_json::Value>(&request.body)
+                .ok()
+                .and_then(|body| body.get("interval_ms")?.as_u64());
+            let Some(interval_ms) = interval_ms else {
+                return Response::error(400, "expected {\"interval_ms\": N}");
+            };
+            let interval = Duration::from_millis(interval_ms);
+            if interval < MIN_INTERVAL {
+                return Response::error(
+                    400,
+                    &format!("interval must be at least {} ms", MIN_INTERVAL.as_millis()),
+                );
+            }
+            shared.send(Control::SetInterval(interval))
+        }
+        "reset-history" => shared.send(Control::ResetHistory),
+        "refresh" => shared.send(Control::Refresh),
+        _ => return Response::error(404, &format!("unknown control '{}'", action)),
+    };
+    if sent {
+        Response::json(200, &serde_json::json!({ "ok": true }))
+    } else {
+        Response::error(503, "sampler is not running")
+    }
+}
+
+/// Upgrade to a WebSocket and push the requested topics after every sample
+fn stream_topics<C: Connection>(
+    request: &Request,
+    mut reader: BufReader<C>,
+    mut writer: C,
+    shared: &Arc<Shared>,
+) -> io::Result<()> {
+    let key = request.header("Sec-WebSocket-Key");
+    let (true, Some(key)) = (
+        request.method == "GET" && request.header_has("Upgrade", "websocket"),
+        key,
+    ) else {
+        return Response::error(400, "expected a WebSocket upgrade").write_to(&mut writer, false);
+    };
+    let topics = match request.query("topics") {
+        Some(list) => match list
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Topic>>>()
+        {
+            Ok(topics) => topics,
+            Err(e) => return Response::error(400, &e.to_string()).write_to(&mut writer, false),
+        },
+        None => vec![Topic::State, Topic::Health, Topic::Events],
+    };
+
+    writer.write_all(
+        format!(
+            "HTTP/1.1 101 {}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+             Sec-WebSocket-Accept: {}\r\n\r\n",
+            http::reason(101),
+            websocket::accept_key(key)
+        )
+        .as_bytes(),
+    )?;
+    writer.flush()?;
+    reader.get_ref().set_read_timeout(None)?;
+
+    let topics = Arc::new(Mutex::new(topics));
+    let closed = Arc::new(AtomicBool::new(false));
+    let writer = Arc::new(Mutex::new(writer));
+
+    // Reader half: close frames, pings and topic changes
+    let reader_thread = {
+        let (topics, closed, writer, shared) = (
+            Arc::clone(&topics),
+            Arc::clone(&closed),
+            Arc::clone(&writer),
+            Arc::clone(shared),
+        );
+        thread::spawn(move || {
+            while let Ok(frame) = websocket::read_frame(&mut reader, MAX_CLIENT_MESSAGE) {
+                match frame.opcode {
+                    websocket::OP_PING => {
+                        if let Ok(mut w) = writer.lock() {
+                            let _ = websocket::write_frame(
+                                &mut *w,
+                                websocket::OP_PONG,
+                                &frame.payload,
+                                None,
+                            );
+                        }
+                    }
+                    websocket::OP_CLOSE => break,
+                    websocket::OP_TEXT => {
+                        // {"topics": ["state", "health"]}
+                        let requested = serde_json::from_slice::<serde_json::Value>(&frame.payload)
+                            .ok()
+                            .and_then(|v| {
+                                serde_json::from_value::<Vec<Topic>>(v.get("topics")?.clone()).ok()
+                            });
+                        if let (Some(requested), Ok(mut topics)) = (requested, topics.lock()) {
+                            *topics = requested;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            closed.store(true, Ordering::SeqCst);
+            shared.updated.notify_all();
+        })
+    };
+
+    let result = push_topics(&topics, &closed, &writer, shared);
+    closed.store(true, Ordering::SeqCst);
+    if let Ok(mut w) = writer.lock() {
+        let _ = websocket::write_frame(&mut *w, websocket::OP_CLOSE, &1000u16.to_be_bytes(), None);
+        w.shutdown();
+    }
+    let _ = reader_thread.join();
+    result
+}
+
+fn push_topics<C: Connection>(
+    topics: &Mutex<Vec<Topic>>,
+    closed: &AtomicBool,
+    writer: &Mutex<C>,
+    shared: &Shared,
+) -> io::Result<()> {
+    let poisoned = || io::Error::other("daemon state poisoned");
+    // Send the current values first, then everything newer
+    let mut last_sequence = 0;
+    let mut last_health = 0;
+    let mut last_event = shared
+        .published
+        .lock()
+        .map_err(|_| poisoned())?
+        .events
+        .back()
+        .map_or(0, |(sequence, _)| *sequence);
+
+    loop {
+        let mut published = shared.published.lock().map_err(|_| poisoned())?;
+        let mut last_ping = Instant::now();
+        while published.sequence == last_sequence
+            && !closed.load(Ordering::SeqCst)
+            && !shared.shutdown.load(Ordering::SeqCst)
+        {
+            let (guard, timeout) = shared
+                .updated
+                .wait_timeout(published, PING_INTERVAL)
+                .map_err(|_| poisoned())?;
+            published = guard;
+            if timeout.timed_out() && last_ping.elapsed() >= PING_INTERVAL {
+                let mut w = writer.lock().map_err(|_| poisoned())?;
+                websocket::write_frame(&mut *w, websocket::OP_PING, b"", None)?;
+                last_ping = Instant::now();
+            }
+        }
+        if closed.load(Ordering::SeqCst) || shared.shutdown.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let sequence = published.sequence;
+        let snapshot = published.snapshot.clone();
+        let health = published
+            .health
+            .as_ref()
+            .filter(|(at, _)| *at > last_health)
+            .map(|(at, health)| (*at, Arc::clone(health)));
+        let events: Vec<(u64, HardwareEvent)> = published
+            .events
+            .iter()
+            .filter(|(at, _)| *at > last_event)
+            .cloned()
+            .collect();
+        drop(published);
+        last_sequence = sequence;
+
+        let topics = topics.lock().map_err(|_| poisoned())?.clone();
+        let mut messages = Vec::new();
+        for topic in &topics {
+            let data = match (topic, &snapshot) {
+                (Topic::State, Some(snapshot)) => serde_json::to_value(&snapshot.state),
+                (Topic::Snapshot, Some(snapshot)) => serde_json::to_value(&**snapshot),
+                (Topic::Health, _) => match &health {
+                    Some((_, health)) => serde_json::to_value(&**health),
+                    None => continue,
+                },
+                (Topic::Events, _) => {
+                    for (_, event) in &events {
+                        messages.push(StreamMessage {
+                            topic: Topic::Events,
+                            sequence,
+                            data: serde_json::to_value(event)?,
+                        });
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            messages.push(StreamMessage {
+                topic: *topic,
+                sequence,
+                data: data?,
+            });
+        }
+        if let Some((at, _)) = health {
+            last_health = at;
+        }
+        if let Some((at, _)) = events.last() {
+            last_event = *at;
+        }
+
+        let mut w = writer.lock().map_err(|_| poisoned())?;
+        for message in messages {
+            let text = serde_json::to_vec(&message)?;
+            websocket::write_frame(&mut *w, websocket::OP_TEXT, &text, None)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{DaemonClient, Endpoint};
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Serves a fixed snapshot whose CPU utilization counts samples
+    struct FakeSampler {
+        samples: u32,
+    }
+
+    impl Sampler for FakeSampler {
+        fn sample(&mut self) -> Result<Snapshot> {
+            self.samples += 1;
+            let mut snapshot = Snapshot::empty();
+            snapshot.state.system = Some(crate::backend::SystemInfoState {
+                hostname: "fake".into(),
+                os: "linux".into(),
+                kernel: "6.0".into(),
+                uptime_secs: 1,
+            });
+            snapshot
+                .history
+                .insert("cpu.utilization".into(), vec![self.samples as f32]);
+            Ok(snapshot)
+        }
+
+        fn health(&mut self) -> Result<SystemHealth> {
+            Err(SimonError::FeatureNotAvailable("no health in tests".into()))
+        }
+
+        fn reset_history(&mut self) {
+            self.samples = 0;
+        }
+    }
+
+    fn start(config: DaemonConfig) -> Daemon {
+        Daemon::start_with(config, || Ok(FakeSampler { samples: 0 })).unwrap()
+    }
+
+    fn tcp_config() -> DaemonConfig {
+        DaemonConfig::default()
+            .with_socket_path(None)
+            .with_listen("127.0.0.1:0".parse().unwrap())
+            .with_token(ApiToken::new("reader", Permission::Read))
+            .with_token(ApiToken::new("admin", Permission::Control))
+            .with_interval(Duration::from_millis(50))
+    }
+
+    #[test]
+    fn test_tcp_requires_token() {
+        let err = Daemon::start_with(
+            DaemonConfig::default()
+                .with_socket_path(None)
+                .with_listen("127.0.0.1:0".parse().unwrap()),
+            || Ok(FakeSampler { samples: 0 }),
+        );
+        assert!(err.is_err());
+
+        let daemon = start(tcp_config());
+        let endpoint = Endpoint::Tcp(daemon.local_addr().unwrap().to_string());
+        let anonymous = DaemonClient::new(endpoint.clone());
+        assert!(matches!(
+            anonymous.state(),
+            Err(SimonError::PermissionDenied(_))
+        ));
+        let wrong = DaemonClient::new(endpoint.clone()).with_token("nope");
+        assert!(wrong.info().is_err());
+
+        let reader = DaemonClient::new(endpoint.clone()).with_token("reader");
+        let info = reader.info().unwrap();
+        assert_eq!(info.permission, Permission::Read);
+        assert_eq!(
+            reader.state().unwrap().system.unwrap().hostname,
+            "fake".to_string()
+        );
+        assert!(matches!(
+            reader.reset_history(),
+            Err(SimonError::PermissionDenied(_))
+        ));
+
+        let admin = DaemonClient::new(endpoint).with_token("admin");
+        admin.set_interval(Duration::from_millis(200)).unwrap();
+        assert!(admin.set_interval(Duration::from_millis(1)).is_err());
+        assert_eq!(admin.info().unwrap().interval_ms, 200);
+        assert!(admin.history("gpu.9.utilization").is_err());
+        assert_eq!(admin.metrics().unwrap(), vec!["cpu.utilization"]);
+        daemon.shutdown();
+    }
+
+    #[test]
+    fn test_stream_subscription() {
+        let daemon = start(tcp_config());
+        let client = DaemonClient::new(Endpoint::Tcp(daemon.local_addr().unwrap().to_string()))
+            .with_token("reader");
+        let mut subscription = client.subscribe(&[Topic::Snapshot]).unwrap();
+        let first = subscription.next_message().unwrap().unwrap();
+        assert_eq!(first.topic, Topic::Snapshot);
+        let second = subscription.next_message().unwrap().unwrap();
+        assert!(second.sequence > first.sequence);
+        let snapshot: Snapshot = serde_json::from_value(second.data).unwrap();
+        assert!(snapshot.history["cpu.utilization"][0] >= 2.0);
+
+        subscription.set_topics(&[Topic::State]).unwrap();
+        let message = subscription
+            .find(|m| m.topic == Topic::State)
+            .expect("state message");
+        assert!(message.data.get("accelerators").is_some());
+        daemon.shutdown();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_without_token() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("simond.sock");
+        let daemon = start(
+            DaemonConfig::default()
+                .with_socket_path(Some(path.clone()))
+                .with_interval(Duration::from_millis(50)),
+        );
+        // A second daemon on the same socket is refused
+        assert!(Daemon::start_with(
+            DaemonConfig::default().with_socket_path(Some(path.clone())),
+            || Ok(FakeSampler { samples: 0 })
+        )
+        .is_err());
+
+        let client = DaemonClient::new(format!("unix:{}", path.display()).parse().unwrap());
+        assert_eq!(client.info().unwrap().permission, Permission::Read);
+        assert_eq!(client.history("cpu.utilization").unwrap().values.len(), 1);
+        daemon.shutdown();
+        assert!(!path.exists());
+    }
+}
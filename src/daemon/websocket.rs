@@ -0,0 +1,303 @@
+//! Minimal RFC 6455 WebSocket framing
+//!
+//! Just enough for the daemon's JSON streams: the opening handshake, text,
+//! ping/pong and close frames, and client-side masking. Fragmented messages
+//! are reassembled; extensions are not negotiated.
+
+use std::io::{self, Read, Write};
+
+/// GUID appended to the client key in the handshake
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Frame opcodes
+pub(crate) const OP_CONTINUATION: u8 = 0x0;
+pub(crate) const OP_TEXT: u8 = 0x1;
+pub(crate) const OP_BINARY: u8 = 0x2;
+pub(crate) const OP_CLOSE: u8 = 0x8;
+pub(crate) const OP_PING: u8 = 0x9;
+pub(crate) const OP_PONG: u8 = 0xA;
+
+/// One decoded frame
+#[derive(Debug)]
+pub(crate) struct Frame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub payload: Vec<u8>,
+}
+
+/// `Sec-WebSocket-Accept` value for a client's `Sec-WebSocket-Key`
+pub(crate) fn accept_key(key: &str) -> String {
+    let mut input = key.trim().as_bytes().to_vec();
+    input.extend_from_slice(HANDSHAKE_GUID.as_bytes());
+    base64_encode(&sha1(&input))
+}
+
+/// A fresh `Sec-WebSocket-Key`
+pub(crate) fn client_key() -> String {
+    let mut key = [0u8; 16];
+    key[..8].copy_from_slice(&random_u64().to_ne_bytes());
+    key[8..].copy_from_slice(&random_u64().to_ne_bytes());
+    base64_encode(&key)
+}
+
+/// Write one unfragmented frame; clients must pass a mask
+pub(crate) fn write_frame(
+    w: &mut impl Write,
+    opcode: u8,
+    payload: &[u8],
+    mask: Option<[u8; 4]>,
+) -> io::Result<()> {
+    let mut header = Vec::with_capacity(14);
+    header.push(0x80 | opcode);
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len if len < 126 => header.push(mask_bit | len as u8),
+        len if len <= u16::MAX as usize => {
+            header.push(mask_bit | 126);
+            header.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            header.push(mask_bit | 127);
+            header.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(mask) => {
+            header.extend_from_slice(&mask);
+            w.write_all(&header)?;
+            let masked: Vec<u8> = payload
+                .iter()
+                .enumerate()
+                .map(|(i, b)| b ^ mask[i % 4])
+                .collect();
+            w.write_all(&masked)?;
+        }
+        None => {
+            w.write_all(&header)?;
+            w.write_all(payload)?;
+        }
+    }
+    w.flush()
+}
+
+/// Read one frame, unmasking it, refusing payloads over `max_len`
+pub(crate) fn read_frame(r: &mut impl Read, max_len: usize) -> io::Result<Frame> {
+    let mut head = [0u8; 2];
+    r.read_exact(&mut head)?;
+    let fin = head[0] & 0x80 != 0;
+    let opcode = head[0] & 0x0F;
+    let masked = head[1] & 0x80 != 0;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut ext = [0u8; 2];
+            r.read_exact(&mut ext)?;
+            u16::from_be_bytes(ext) as u64
+        }
+        127 => {
+            let mut ext = [0u8; 8];
+            r.read_exact(&mut ext)?;
+            u64::from_be_bytes(ext)
+        }
+        len => len as u64,
+    };
+    if len > max_len as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("websocket frame of {} bytes exceeds {}", len, max_len),
+        ));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        r.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    r.read_exact(&mut payload)?;
+    if masked {
+        for (i, b) in payload.iter_mut().enumerate() {
+            *b ^= mask[i % 4];
+        }
+    }
+    Ok(Frame {
+        fin,
+        opcode,
+        payload,
+    })
+}
+
+/// Read the next text or binary message, answering pings along the way
+///
+/// Returns `None` once the peer sends a close frame.
+pub(crate) fn read_message(
+    r: &mut impl Read,
+    w: &mut impl Write,
+    max_len: usize,
+    mask: bool,
+) -> io::Result<Option<Vec<u8>>> {
+    let mut message = Vec::new();
+    loop {
+        let frame = read_frame(r, max_len)?;
+        match frame.opcode {
+            OP_PING => {
+                write_frame(w, OP_PONG, &frame.payload, mask.then(random_mask))?;
+                continue;
+            }
+            OP_PONG => continue,
+            OP_CLOSE => {
+                // Echo the close; the peer may already be gone
+                let _ = write_frame(w, OP_CLOSE, &frame.payload, mask.then(random_mask));
+                return Ok(None);
+            }
+            OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                if message.len() + frame.payload.len() > max_len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "websocket message too large",
+                    ));
+                }
+                message.extend_from_slice(&frame.payload);
+                if frame.fin {
+                    return Ok(Some(message));
+                }
+            }
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unknown websocket opcode {:#x}", other),
+                ))
+            }
+        }
+    }
+}
+
+/// Masking key for client frames
+pub(crate) fn random_mask() -> [u8; 4] {
+    (random_u64() as u32).to_ne_bytes()
+}
+
+/// Non-cryptographic randomness from std's per-process hasher keys
+fn random_u64() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    hasher.finish()
+}
+
+/// Standard base64 with padding
+pub(crate) fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (chunk.get(1).copied().unwrap_or(0) as u32) << 8
+            | chunk.get(2).copied().unwrap_or(0) as u32;
+        out.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        out.push(ALPHABET[(n >> 12) as usize & 63] as char);
+        out.push(if chunk.len() > 1 {
+            ALPHABET[(n >> 6) as usize & 63] as char
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            ALPHABET[n as usize & 63] as char
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// SHA-1, needed only for the handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455 section 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+        assert_eq!(base64_encode(b"a"), "YQ==");
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let payload = vec![b'x'; 70_000];
+        let mut wire = Vec::new();
+        write_frame(&mut wire, OP_TEXT, &payload, Some([1, 2, 3, 4])).unwrap();
+        write_frame(&mut wire, OP_PING, b"hi", Some([5, 6, 7, 8])).unwrap();
+        write_frame(&mut wire, OP_TEXT, b"short", None).unwrap();
+
+        let mut reader = &wire[..];
+        let frame = read_frame(&mut reader, 1 << 20).unwrap();
+        assert!(frame.fin);
+        assert_eq!(frame.opcode, OP_TEXT);
+        assert_eq!(frame.payload, payload);
+
+        // The ping is answered and skipped
+        let mut replies = Vec::new();
+        let message = read_message(&mut reader, &mut replies, 1 << 20, false).unwrap();
+        assert_eq!(message.as_deref(), Some(&b"short"[..]));
+        assert_eq!(replies, [0x80 | OP_PONG, 2, b'h', b'i']);
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, OP_TEXT, &payload, None).unwrap();
+        assert!(read_frame(&mut &wire[..], 1024).is_err());
+    }
+}
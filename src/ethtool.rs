@@ -0,0 +1,1050 @@
+//! Ethtool driver statistics and NIC settings (Linux)
+//!
+//! Queries network drivers through the `SIOCETHTOOL` ioctl, the interface the
+//! `ethtool` command uses: driver and firmware version (`ethtool -i`), link
+//! settings and link modes, ring sizes (`-g`), interrupt coalescing (`-c`),
+//! channels (`-l`), offload features (`-k`) and the driver-specific counters
+//! (`-S`). [`EthtoolMonitor`] turns the drop and miss counters, including
+//! per-queue ones, into rates, since a NIC that cannot keep up with its
+//! rings shows up there long before it shows up anywhere else.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::ethtool::{Ethtool, EthtoolMonitor};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let ethtool = Ethtool::new()?;
+//! let info = ethtool.info("eth0")?;
+//! print!("{}", info);
+//!
+//! // Drop counters per second
+//! let mut monitor = EthtoolMonitor::new()?;
+//! monitor.refresh()?;
+//! std::thread::sleep(Duration::from_secs(1));
+//! for rate in monitor.refresh()? {
+//!     println!("{} {}: {:.0}/s", rate.interface, rate.counter, rate.per_sec);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Length of an ethtool string (`ETH_GSTRING_LEN`)
+const GSTRING_LEN: usize = 32;
+
+/// Driver and firmware information (`ethtool -i`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriverInfo {
+    /// Driver name
+    pub driver: String,
+    /// Driver version
+    pub version: String,
+    /// Firmware version
+    pub fw_version: String,
+    /// Bus address (PCI address for PCI devices)
+    pub bus_info: String,
+    /// Expansion ROM version
+    pub expansion_rom: String,
+    /// Number of driver statistics
+    pub n_stats: u32,
+    /// Number of private flags
+    pub n_priv_flags: u32,
+}
+
+/// Link duplex mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Duplex {
+    /// Half duplex
+    Half,
+    /// Full duplex
+    Full,
+    /// No link or not reported
+    #[default]
+    Unknown,
+}
+
+impl fmt::Display for Duplex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Duplex::Half => "Half",
+            Duplex::Full => "Full",
+            Duplex::Unknown => "Unknown",
+        })
+    }
+}
+
+/// Link settings (`ethtool <iface>`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LinkSettings {
+    /// Speed in Mb/s, `None` without link
+    pub speed_mbps: Option<u32>,
+    /// Duplex mode
+    pub duplex: Duplex,
+    /// Autonegotiation enabled
+    pub autoneg: bool,
+    /// Connector (`Twisted Pair`, `FIBRE`, `Direct Attach Copper`)
+    pub port: String,
+    /// Link detected (`ETHTOOL_GLINK`)
+    pub link_detected: Option<bool>,
+    /// Supported link modes
+    pub supported: Vec<String>,
+    /// Advertised link modes
+    pub advertised: Vec<String>,
+    /// Link modes advertised by the link partner
+    pub partner_advertised: Vec<String>,
+}
+
+/// Ring sizes (`ethtool -g`)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RingParams {
+    /// RX ring entries
+    pub rx: u32,
+    /// Maximum RX ring entries
+    pub rx_max: u32,
+    /// RX mini ring entries
+    pub rx_mini: u32,
+    /// Maximum RX mini ring entries
+    pub rx_mini_max: u32,
+    /// RX jumbo ring entries
+    pub rx_jumbo: u32,
+    /// Maximum RX jumbo ring entries
+    pub rx_jumbo_max: u32,
+    /// TX ring entries
+    pub tx: u32,
+    /// Maximum TX ring entries
+    pub tx_max: u32,
+}
+
+impl RingParams {
+    /// Whether the RX ring is below the hardware maximum
+    pub fn rx_below_max(&self) -> bool {
+        self.rx < self.rx_max
+    }
+}
+
+/// Interrupt coalescing (`ethtool -c`)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Coalesce {
+    /// Microseconds to delay an RX interrupt
+    pub rx_usecs: u32,
+    /// Frames to wait for before an RX interrupt
+    pub rx_frames: u32,
+    /// Microseconds to delay a TX interrupt
+    pub tx_usecs: u32,
+    /// Frames to wait for before a TX interrupt
+    pub tx_frames: u32,
+    /// Adaptive RX coalescing
+    pub adaptive_rx: bool,
+    /// Adaptive TX coalescing
+    pub adaptive_tx: bool,
+}
+
+/// Queue counts (`ethtool -l`)
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Channels {
+    /// RX-only channels
+    pub rx: u32,
+    /// TX-only channels
+    pub tx: u32,
+    /// Other channels (link interrupts, SR-IOV)
+    pub other: u32,
+    /// Combined RX/TX channels
+    pub combined: u32,
+    /// Maximum RX-only channels
+    pub max_rx: u32,
+    /// Maximum TX-only channels
+    pub max_tx: u32,
+    /// Maximum other channels
+    pub max_other: u32,
+    /// Maximum combined channels
+    pub max_combined: u32,
+}
+
+/// One offload feature (`ethtool -k`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Feature {
+    /// Kernel feature name (`rx-checksum`, `tx-tcp-segmentation`)
+    pub name: String,
+    /// Currently on
+    pub active: bool,
+    /// Requested by the user
+    pub requested: bool,
+    /// Can be changed (not `[fixed]`)
+    pub changeable: bool,
+}
+
+/// Everything ethtool reports for one interface
+///
+/// Sections the driver does not implement are `None` or empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EthtoolInfo {
+    /// Interface name
+    pub interface: String,
+    /// Driver and firmware
+    pub driver: Option<DriverInfo>,
+    /// Link settings
+    pub link: Option<LinkSettings>,
+    /// Ring sizes
+    pub rings: Option<RingParams>,
+    /// Interrupt coalescing
+    pub coalesce: Option<Coalesce>,
+    /// Queue counts
+    pub channels: Option<Channels>,
+    /// Offload features
+    pub features: Vec<Feature>,
+    /// Driver statistics (`ethtool -S`)
+    pub stats: BTreeMap<String, u64>,
+}
+
+impl EthtoolInfo {
+    /// Whether a feature is on
+    pub fn feature(&self, name: &str) -> Option<bool> {
+        self.features
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.active)
+    }
+
+    /// Non-zero drop, miss and buffer-exhaustion counters
+    pub fn drops(&self) -> Vec<(&str, u64)> {
+        self.stats
+            .iter()
+            .filter(|(name, &value)| value > 0 && is_drop_counter(name))
+            .map(|(name, &value)| (name.as_str(), value))
+            .collect()
+    }
+}
+
+impl fmt::Display for EthtoolInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.interface)?;
+        if let Some(driver) = &self.driver {
+            writeln!(f, "  Driver:          {} {}", driver.driver, driver.version)?;
+            writeln!(f, "  Firmware:        {}", driver.fw_version)?;
+            writeln!(f, "  Bus:             {}", driver.bus_info)?;
+        }
+        if let Some(link) = &self.link {
+            let speed = link
+                .speed_mbps
+                .map_or("Unknown".to_string(), |s| format!("{}Mb/s", s));
+            writeln!(
+                f,
+                "  Link:            {} {} duplex, autoneg {}, {}{}",
+                speed,
+                link.duplex,
+                if link.autoneg { "on" } else { "off" },
+                link.port,
+                match link.link_detected {
+                    Some(true) => ", link detected",
+                    Some(false) => ", no link",
+                    None => "",
+                }
+            )?;
+            if !link.advertised.is_empty() {
+                writeln!(f, "  Advertised:      {}", link.advertised.join(" "))?;
+            }
+        }
+        if let Some(rings) = &self.rings {
+            writeln!(
+                f,
+                "  Rings:           RX {}/{}, TX {}/{}",
+                rings.rx, rings.rx_max, rings.tx, rings.tx_max
+            )?;
+        }
+        if let Some(channels) = &self.channels {
+            writeln!(
+                f,
+                "  Channels:        combined {}/{}, RX {}/{}, TX {}/{}",
+                channels.combined,
+                channels.max_combined,
+                channels.rx,
+                channels.max_rx,
+                channels.tx,
+                channels.max_tx
+            )?;
+        }
+        if let Some(c) = &self.coalesce {
+            writeln!(
+                f,
+                "  Coalescing:      rx-usecs {} rx-frames {} tx-usecs {} tx-frames {} adaptive rx {} tx {}",
+                c.rx_usecs,
+                c.rx_frames,
+                c.tx_usecs,
+                c.tx_frames,
+                if c.adaptive_rx { "on" } else { "off" },
+                if c.adaptive_tx { "on" } else { "off" }
+            )?;
+        }
+        let on: Vec<&str> = self
+            .features
+            .iter()
+            .filter(|f| f.active)
+            .map(|f| f.name.as_str())
+            .collect();
+        if !on.is_empty() {
+            writeln!(f, "  Offloads on:     {}", on.join(" "))?;
+        }
+        let drops = self.drops();
+        if !drops.is_empty() {
+            writeln!(f, "  Drop counters:")?;
+            for (name, value) in drops {
+                writeln!(f, "    {:<40} {}", name, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Direction of a per-queue counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum QueueDirection {
+    /// Receive queue
+    Rx,
+    /// Transmit queue
+    Tx,
+}
+
+/// A driver statistic that belongs to one queue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStat {
+    /// Queue direction
+    pub direction: QueueDirection,
+    /// Queue index
+    pub queue: u32,
+    /// Counter name without the queue prefix (`drops`, `packets`)
+    pub counter: String,
+}
+
+/// Split a per-queue driver statistic into direction, queue and counter
+///
+/// Drivers name these differently: `rx_queue_3_drops` (ixgbe, virtio),
+/// `rx3_packets` (mlx5), `rx-3.drops` (i40e, ice), `queue_3_rx_drops` (ena).
+pub fn parse_queue_stat(name: &str) -> Option<QueueStat> {
+    let direction = |s: &str| match s {
+        "rx" => Some(QueueDirection::Rx),
+        "tx" => Some(QueueDirection::Tx),
+        _ => None,
+    };
+    let split_number = |s: &str| -> Option<(u32, String)> {
+        let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+        let queue = s[..digits].parse().ok()?;
+        let counter = s[digits..].trim_start_matches(['_', '.']);
+        (!counter.is_empty()).then(|| (queue, counter.to_string()))
+    };
+
+    // queue_3_rx_drops
+    if let Some(rest) = name.strip_prefix("queue_") {
+        let (queue, rest) = split_number(rest)?;
+        let (dir, counter) = rest.split_once('_')?;
+        return Some(QueueStat {
+            direction: direction(dir)?,
+            queue,
+            counter: counter.to_string(),
+        });
+    }
+
+    let dir = direction(name.get(..2)?)?;
+    let rest = &name[2..];
+    let rest = rest
+        .strip_prefix("_queue_")
+        .or_else(|| rest.strip_prefix('-'))
+        .or_else(|| rest.strip_prefix("_q"))
+        .unwrap_or(rest);
+    if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let (queue, counter) = split_number(rest)?;
+    Some(QueueStat {
+        direction: dir,
+        queue,
+        counter,
+    })
+}
+
+/// Whether a driver statistic counts dropped or missed packets
+pub fn is_drop_counter(name: &str) -> bool {
+    const PATTERNS: [&str; 9] = [
+        "drop",
+        "miss",
+        "discard",
+        "out_of_buffer",
+        "no_buf",
+        "nobuf",
+        "fifo",
+        "overrun",
+        "alloc_fail",
+    ];
+    let name = name.to_ascii_lowercase();
+    PATTERNS.iter().any(|p| name.contains(p))
+}
+
+/// Rate of one drop counter between two refreshes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DropRate {
+    /// Interface name
+    pub interface: String,
+    /// Driver statistic name
+    pub counter: String,
+    /// Queue, for per-queue counters
+    pub queue: Option<QueueStat>,
+    /// Increase since the previous refresh
+    pub delta: u64,
+    /// Increase per second
+    pub per_sec: f64,
+}
+
+/// Increase of every drop counter between two snapshots of one interface
+fn drop_rates(
+    interface: &str,
+    previous: &BTreeMap<String, u64>,
+    current: &BTreeMap<String, u64>,
+    interval: Duration,
+) -> Vec<DropRate> {
+    let secs = interval.as_secs_f64().max(0.001);
+    current
+        .iter()
+        .filter(|(name, _)| is_drop_counter(name))
+        .filter_map(|(name, &value)| {
+            let delta = value.saturating_sub(*previous.get(name)?);
+            (delta > 0).then(|| DropRate {
+                interface: interface.to_string(),
+                counter: name.clone(),
+                queue: parse_queue_stat(name),
+                delta,
+                per_sec: delta as f64 / secs,
+            })
+        })
+        .collect()
+}
+
+/// Decode a block of NUL-padded ethtool strings
+fn parse_strings(data: &[u8]) -> Vec<String> {
+    data.chunks(GSTRING_LEN)
+        .map(|chunk| {
+            let end = chunk.iter().position(|&b| b == 0).unwrap_or(chunk.len());
+            String::from_utf8_lossy(&chunk[..end]).to_string()
+        })
+        .collect()
+}
+
+/// Names of the set bits in a link mode mask
+fn link_modes(mask: &[u32], names: &[String]) -> Vec<String> {
+    (0..mask.len() * 32)
+        .filter(|&bit| mask[bit / 32] & (1 << (bit % 32)) != 0)
+        .map(|bit| {
+            names
+                .get(bit)
+                .cloned()
+                .unwrap_or_else(|| format!("bit{}", bit))
+        })
+        .collect()
+}
+
+/// Connector name for `ethtool_link_settings::port`
+fn port_name(port: u8) -> &'static str {
+    match port {
+        0x00 => "Twisted Pair",
+        0x01 => "AUI",
+        0x02 => "BNC",
+        0x03 => "MII",
+        0x04 => "FIBRE",
+        0x05 => "Direct Attach Copper",
+        0xef => "None",
+        _ => "Other",
+    }
+}
+
+/// Whether an ioctl error means the driver lacks this operation
+fn is_unsupported(e: &std::io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EOPNOTSUPP) | Some(libc::EINVAL)
+    )
+}
+
+/// Map an ioctl result so unsupported operations become `None`
+fn optional<T>(interface: &str, result: std::io::Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if is_unsupported(&e) => Ok(None),
+        Err(e) if e.raw_os_error() == Some(libc::ENODEV) => {
+            Err(SimonError::DeviceNotFound(interface.to_string()))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => Err(
+            SimonError::PermissionDenied(format!("ethtool on {}: {}", interface, e)),
+        ),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Handle for ethtool queries
+pub struct Ethtool {
+    socket: sys::Socket,
+}
+
+impl Ethtool {
+    /// Open the control socket
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            socket: sys::Socket::open()?,
+        })
+    }
+
+    /// Driver and firmware (`ethtool -i`)
+    pub fn driver_info(&self, interface: &str) -> Result<Option<DriverInfo>> {
+        optional(interface, self.socket.driver_info(interface))
+    }
+
+    /// Link settings and link modes
+    pub fn link_settings(&self, interface: &str) -> Result<Option<LinkSettings>> {
+        let Some(raw) = optional(interface, self.socket.link_settings(interface))? else {
+            return Ok(None);
+        };
+        let names = self
+            .socket
+            .strings(interface, sys::ETH_SS_LINK_MODES)
+            .unwrap_or_default();
+        Ok(Some(LinkSettings {
+            speed_mbps: Some(raw.speed).filter(|&s| s != 0 && s != u32::MAX),
+            duplex: match raw.duplex {
+                0 => Duplex::Half,
+                1 => Duplex::Full,
+                _ => Duplex::Unknown,
+            },
+            autoneg: raw.autoneg != 0,
+            port: port_name(raw.port).to_string(),
+            link_detected: self.socket.link_detected(interface).ok(),
+            supported: link_modes(&raw.supported, &names),
+            advertised: link_modes(&raw.advertising, &names),
+            partner_advertised: link_modes(&raw.lp_advertising, &names),
+        }))
+    }
+
+    /// Ring sizes (`ethtool -g`)
+    pub fn ring_params(&self, interface: &str) -> Result<Option<RingParams>> {
+        optional(interface, self.socket.ring_params(interface))
+    }
+
+    /// Interrupt coalescing (`ethtool -c`)
+    pub fn coalesce(&self, interface: &str) -> Result<Option<Coalesce>> {
+        optional(interface, self.socket.coalesce(interface))
+    }
+
+    /// Queue counts (`ethtool -l`)
+    pub fn channels(&self, interface: &str) -> Result<Option<Channels>> {
+        optional(interface, self.socket.channels(interface))
+    }
+
+    /// Offload features (`ethtool -k`)
+    pub fn features(&self, interface: &str) -> Result<Vec<Feature>> {
+        Ok(optional(interface, self.socket.features(interface))?.unwrap_or_default())
+    }
+
+    /// Driver statistics (`ethtool -S`)
+    pub fn stats(&self, interface: &str) -> Result<BTreeMap<String, u64>> {
+        Ok(optional(interface, self.socket.stats(interface))?.unwrap_or_default())
+    }
+
+    /// Everything at once
+    pub fn info(&self, interface: &str) -> Result<EthtoolInfo> {
+        Ok(EthtoolInfo {
+            interface: interface.to_string(),
+            driver: self.driver_info(interface)?,
+            link: self.link_settings(interface)?,
+            rings: self.ring_params(interface)?,
+            coalesce: self.coalesce(interface)?,
+            channels: self.channels(interface)?,
+            features: self.features(interface)?,
+            stats: self.stats(interface)?,
+        })
+    }
+}
+
+/// Read ethtool information for one interface
+pub fn ethtool_info(interface: &str) -> Result<EthtoolInfo> {
+    Ethtool::new()?.info(interface)
+}
+
+/// Interfaces backed by a device (skips loopback, bridges, veths, tunnels)
+pub fn physical_interfaces() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().join("device").exists())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Driver statistics per interface
+type Snapshot = HashMap<String, BTreeMap<String, u64>>;
+
+/// Tracks driver drop counters and computes their rates
+pub struct EthtoolMonitor {
+    ethtool: Ethtool,
+    /// Interfaces to watch, all physical interfaces when `None`
+    interfaces: Option<Vec<String>>,
+    /// Statistics from the previous refresh
+    previous: Option<(Instant, Snapshot)>,
+}
+
+impl EthtoolMonitor {
+    /// Watch all physical interfaces
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            ethtool: Ethtool::new()?,
+            interfaces: None,
+            previous: None,
+        })
+    }
+
+    /// Watch only the given interfaces
+    pub fn with_interfaces<I, S>(mut self, interfaces: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.interfaces = Some(interfaces.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Read driver statistics and return drop rates since the last refresh
+    ///
+    /// The first call records a baseline and returns an empty list. The
+    /// kernel's `rx_missed_errors` is folded in for drivers that do not
+    /// report misses through ethtool. Rates are sorted busiest first.
+    pub fn refresh(&mut self) -> Result<Vec<DropRate>> {
+        let now = Instant::now();
+        let interfaces = self.interfaces.clone().unwrap_or_else(physical_interfaces);
+
+        let mut current = HashMap::new();
+        for interface in interfaces {
+            let mut stats = match self.ethtool.stats(&interface) {
+                Ok(stats) => stats,
+                Err(SimonError::DeviceNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            let missed = format!("/sys/class/net/{}/statistics/rx_missed_errors", interface);
+            if let Some(value) = std::fs::read_to_string(missed)
+                .ok()
+                .and_then(|s| s.trim().parse().ok())
+            {
+                stats.entry("rx_missed_errors".to_string()).or_insert(value);
+            }
+            current.insert(interface, stats);
+        }
+
+        let mut rates = Vec::new();
+        if let Some((then, previous)) = &self.previous {
+            let interval = now.duration_since(*then);
+            for (interface, stats) in &current {
+                if let Some(prev) = previous.get(interface) {
+                    rates.extend(drop_rates(interface, prev, stats, interval));
+                }
+            }
+        }
+        rates.sort_by(|a, b| b.per_sec.total_cmp(&a.per_sec));
+
+        self.previous = Some((now, current));
+        Ok(rates)
+    }
+}
+
+mod sys {
+    //! `SIOCETHTOOL` ioctl plumbing (`linux/ethtool.h`)
+
+    use super::*;
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    const SIOCETHTOOL: libc::c_ulong = 0x8946;
+
+    const ETHTOOL_GDRVINFO: u32 = 0x03;
+    const ETHTOOL_GLINK: u32 = 0x0a;
+    const ETHTOOL_GCOALESCE: u32 = 0x0e;
+    const ETHTOOL_GRINGPARAM: u32 = 0x10;
+    const ETHTOOL_GSTRINGS: u32 = 0x1b;
+    const ETHTOOL_GSTATS: u32 = 0x1d;
+    const ETHTOOL_GSSET_INFO: u32 = 0x37;
+    const ETHTOOL_GFEATURES: u32 = 0x3a;
+    const ETHTOOL_GCHANNELS: u32 = 0x3c;
+    const ETHTOOL_GLINKSETTINGS: u32 = 0x4c;
+
+    const ETH_SS_STATS: u32 = 1;
+    const ETH_SS_FEATURES: u32 = 4;
+    pub const ETH_SS_LINK_MODES: u32 = 9;
+
+    /// Largest `link_mode_masks_nwords` the kernel can ask for
+    const MAX_LINK_MODE_WORDS: usize = 127;
+
+    #[repr(C)]
+    struct IfReq {
+        name: [libc::c_char; libc::IFNAMSIZ],
+        data: *mut libc::c_void,
+        _pad: [u8; 16],
+    }
+
+    #[repr(C)]
+    struct DrvInfo {
+        cmd: u32,
+        driver: [u8; 32],
+        version: [u8; 32],
+        fw_version: [u8; 32],
+        bus_info: [u8; 32],
+        erom_version: [u8; 32],
+        reserved2: [u8; 12],
+        n_priv_flags: u32,
+        n_stats: u32,
+        testinfo_len: u32,
+        eedump_len: u32,
+        regdump_len: u32,
+    }
+
+    #[repr(C)]
+    struct LinkSettingsReq {
+        cmd: u32,
+        speed: u32,
+        duplex: u8,
+        port: u8,
+        phy_address: u8,
+        autoneg: u8,
+        mdio_support: u8,
+        eth_tp_mdix: u8,
+        eth_tp_mdix_ctrl: u8,
+        link_mode_masks_nwords: i8,
+        transceiver: u8,
+        master_slave_cfg: u8,
+        master_slave_state: u8,
+        rate_matching: u8,
+        reserved: [u32; 7],
+        link_mode_masks: [u32; 3 * MAX_LINK_MODE_WORDS],
+    }
+
+    #[repr(C)]
+    struct SsetInfo {
+        cmd: u32,
+        reserved: u32,
+        sset_mask: u64,
+        data: [u32; 1],
+    }
+
+    /// Link settings with the three link mode masks split out
+    pub struct RawLinkSettings {
+        pub speed: u32,
+        pub duplex: u8,
+        pub port: u8,
+        pub autoneg: u8,
+        pub supported: Vec<u32>,
+        pub advertising: Vec<u32>,
+        pub lp_advertising: Vec<u32>,
+    }
+
+    pub struct Socket(OwnedFd);
+
+    impl Socket {
+        pub fn open() -> io::Result<Self> {
+            let fd =
+                unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self(unsafe { OwnedFd::from_raw_fd(fd) }))
+        }
+
+        /// Run one ethtool command; `data` must start with the command word
+        fn ioctl(&self, interface: &str, data: *mut libc::c_void) -> io::Result<()> {
+            if interface.len() >= libc::IFNAMSIZ {
+                return Err(io::Error::from_raw_os_error(libc::ENODEV));
+            }
+            let mut req = IfReq {
+                name: [0; libc::IFNAMSIZ],
+                data,
+                _pad: [0; 16],
+            };
+            for (dst, &src) in req.name.iter_mut().zip(interface.as_bytes()) {
+                *dst = src as libc::c_char;
+            }
+            let ret = unsafe { libc::ioctl(self.0.as_raw_fd(), SIOCETHTOOL as _, &mut req) };
+            if ret < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        }
+
+        /// Run a command whose payload is a plain `u32` array
+        fn words<const N: usize>(&self, interface: &str, cmd: u32) -> io::Result<[u32; N]> {
+            let mut data = [0u32; N];
+            data[0] = cmd;
+            self.ioctl(interface, data.as_mut_ptr().cast())?;
+            Ok(data)
+        }
+
+        pub fn driver_info(&self, interface: &str) -> io::Result<DriverInfo> {
+            let mut info: DrvInfo = unsafe { std::mem::zeroed() };
+            info.cmd = ETHTOOL_GDRVINFO;
+            self.ioctl(interface, (&mut info as *mut DrvInfo).cast())?;
+            let text = |b: &[u8]| parse_strings(b).into_iter().next().unwrap_or_default();
+            Ok(DriverInfo {
+                driver: text(&info.driver),
+                version: text(&info.version),
+                fw_version: text(&info.fw_version),
+                bus_info: text(&info.bus_info),
+                expansion_rom: text(&info.erom_version),
+                n_stats: info.n_stats,
+                n_priv_flags: info.n_priv_flags,
+            })
+        }
+
+        pub fn link_detected(&self, interface: &str) -> io::Result<bool> {
+            let [_, link] = self.words::<2>(interface, ETHTOOL_GLINK)?;
+            Ok(link != 0)
+        }
+
+        pub fn link_settings(&self, interface: &str) -> io::Result<RawLinkSettings> {
+            let mut req: Box<LinkSettingsReq> = Box::new(unsafe { std::mem::zeroed() });
+            req.cmd = ETHTOOL_GLINKSETTINGS;
+            // Handshake: the kernel answers a zero word count with the
+            // negated count it wants
+            self.ioctl(interface, (&mut *req as *mut LinkSettingsReq).cast())?;
+            let nwords = req.link_mode_masks_nwords.unsigned_abs() as usize;
+            if req.link_mode_masks_nwords >= 0 || nwords > MAX_LINK_MODE_WORDS {
+                return Err(io::Error::from_raw_os_error(libc::EOPNOTSUPP));
+            }
+            *req = unsafe { std::mem::zeroed() };
+            req.cmd = ETHTOOL_GLINKSETTINGS;
+            req.link_mode_masks_nwords = nwords as i8;
+            self.ioctl(interface, (&mut *req as *mut LinkSettingsReq).cast())?;
+
+            let masks = &req.link_mode_masks;
+            Ok(RawLinkSettings {
+                speed: req.speed,
+                duplex: req.duplex,
+                port: req.port,
+                autoneg: req.autoneg,
+                supported: masks[..nwords].to_vec(),
+                advertising: masks[nwords..2 * nwords].to_vec(),
+                lp_advertising: masks[2 * nwords..3 * nwords].to_vec(),
+            })
+        }
+
+        pub fn ring_params(&self, interface: &str) -> io::Result<RingParams> {
+            let w = self.words::<9>(interface, ETHTOOL_GRINGPARAM)?;
+            Ok(RingParams {
+                rx_max: w[1],
+                rx_mini_max: w[2],
+                rx_jumbo_max: w[3],
+                tx_max: w[4],
+                rx: w[5],
+                rx_mini: w[6],
+                rx_jumbo: w[7],
+                tx: w[8],
+            })
+        }
+
+        pub fn coalesce(&self, interface: &str) -> io::Result<Coalesce> {
+            let w = self.words::<23>(interface, ETHTOOL_GCOALESCE)?;
+            Ok(Coalesce {
+                rx_usecs: w[1],
+                rx_frames: w[2],
+                tx_usecs: w[5],
+                tx_frames: w[6],
+                adaptive_rx: w[10] != 0,
+                adaptive_tx: w[11] != 0,
+            })
+        }
+
+        pub fn channels(&self, interface: &str) -> io::Result<Channels> {
+            let w = self.words::<9>(interface, ETHTOOL_GCHANNELS)?;
+            Ok(Channels {
+                max_rx: w[1],
+                max_tx: w[2],
+                max_other: w[3],
+                max_combined: w[4],
+                rx: w[5],
+                tx: w[6],
+                other: w[7],
+                combined: w[8],
+            })
+        }
+
+        /// Number of strings in a string set
+        fn string_count(&self, interface: &str, set: u32) -> io::Result<usize> {
+            let mut info = SsetInfo {
+                cmd: ETHTOOL_GSSET_INFO,
+                reserved: 0,
+                sset_mask: 1 << set,
+                data: [0],
+            };
+            self.ioctl(interface, (&mut info as *mut SsetInfo).cast())?;
+            if info.sset_mask & (1 << set) == 0 {
+                return Err(io::Error::from_raw_os_error(libc::EOPNOTSUPP));
+            }
+            Ok(info.data[0] as usize)
+        }
+
+        pub fn strings(&self, interface: &str, set: u32) -> io::Result<Vec<String>> {
+            let count = self.string_count(interface, set)?;
+            // cmd, string_set, len, then count * 32 bytes
+            let mut buf = vec![0u32; 3 + count * GSTRING_LEN / 4];
+            buf[0] = ETHTOOL_GSTRINGS;
+            buf[1] = set;
+            buf[2] = count as u32;
+            self.ioctl(interface, buf.as_mut_ptr().cast())?;
+            let len = (buf[2] as usize).min(count);
+            let bytes = unsafe {
+                std::slice::from_raw_parts(buf.as_ptr().add(3).cast::<u8>(), len * GSTRING_LEN)
+            };
+            Ok(parse_strings(bytes))
+        }
+
+        pub fn stats(&self, interface: &str) -> io::Result<BTreeMap<String, u64>> {
+            let names = self.strings(interface, ETH_SS_STATS)?;
+            // cmd and n_stats share the first u64, values follow
+            let mut buf = vec![0u64; 1 + names.len()];
+            let header = buf.as_mut_ptr().cast::<u32>();
+            unsafe {
+                *header = ETHTOOL_GSTATS;
+                *header.add(1) = names.len() as u32;
+            }
+            self.ioctl(interface, buf.as_mut_ptr().cast())?;
+            Ok(names.into_iter().zip(buf[1..].iter().copied()).collect())
+        }
+
+        pub fn features(&self, interface: &str) -> io::Result<Vec<Feature>> {
+            let names = self.strings(interface, ETH_SS_FEATURES)?;
+            let blocks = names.len().div_ceil(32);
+            // cmd, size, then per block: available, requested, active, never_changed
+            let mut buf = vec![0u32; 2 + 4 * blocks];
+            buf[0] = ETHTOOL_GFEATURES;
+            buf[1] = blocks as u32;
+            self.ioctl(interface, buf.as_mut_ptr().cast())?;
+            Ok(decode_features(&names, &buf[2..]))
+        }
+    }
+}
+
+/// Decode `ethtool_get_features_block`s (available, requested, active,
+/// never_changed) into named features
+fn decode_features(names: &[String], blocks: &[u32]) -> Vec<Feature> {
+    names
+        .iter()
+        .enumerate()
+        .filter(|(_, name)| !name.is_empty())
+        .filter_map(|(i, name)| {
+            let block = blocks.get(i / 32 * 4..i / 32 * 4 + 4)?;
+            let bit = 1 << (i % 32);
+            Some(Feature {
+                name: name.clone(),
+                changeable: block[0] & bit != 0,
+                requested: block[1] & bit != 0,
+                active: block[2] & bit != 0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_queue_stat() {
+        let q = |dir, queue, counter: &str| {
+            Some(QueueStat {
+                direction: dir,
+                queue,
+                counter: counter.to_string(),
+            })
+        };
+        use QueueDirection::*;
+        assert_eq!(parse_queue_stat("rx_queue_3_drops"), q(Rx, 3, "drops"));
+        assert_eq!(parse_queue_stat("tx_queue_0_packets"), q(Tx, 0, "packets"));
+        assert_eq!(parse_queue_stat("rx12_wqe_err"), q(Rx, 12, "wqe_err"));
+        assert_eq!(parse_queue_stat("rx-5.drops"), q(Rx, 5, "drops"));
+        assert_eq!(parse_queue_stat("queue_7_rx_drops"), q(Rx, 7, "drops"));
+        assert_eq!(parse_queue_stat("tx_q2_bytes"), q(Tx, 2, "bytes"));
+        assert_eq!(parse_queue_stat("rx_out_of_buffer"), None);
+        assert_eq!(parse_queue_stat("rx_missed_errors"), None);
+        assert_eq!(parse_queue_stat("tx_timeout"), None);
+    }
+
+    #[test]
+    fn test_drop_rates() {
+        let stats = |pairs: &[(&str, u64)]| -> BTreeMap<String, u64> {
+            pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+        };
+        let before = stats(&[
+            ("rx_packets", 100),
+            ("rx_missed_errors", 10),
+            ("rx_queue_1_drops", 0),
+            ("rx_out_of_buffer", 5),
+        ]);
+        let after = stats(&[
+            ("rx_packets", 900),
+            ("rx_missed_errors", 10),
+            ("rx_queue_1_drops", 40),
+            ("rx_out_of_buffer", 25),
+            ("tx_new_drops", 3),
+        ]);
+
+        let mut rates = drop_rates("eth0", &before, &after, Duration::from_secs(2));
+        rates.sort_by(|a, b| a.counter.cmp(&b.counter));
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].counter, "rx_out_of_buffer");
+        assert_eq!(rates[0].per_sec, 10.0);
+        assert_eq!(rates[1].delta, 40);
+        assert_eq!(rates[1].queue.as_ref().map(|q| q.queue), Some(1));
+        assert!(is_drop_counter("rx_fifo_errors"));
+        assert!(!is_drop_counter("rx_bytes"));
+    }
+
+    #[test]
+    fn test_decoding() {
+        let mut raw = vec![0u8; 3 * GSTRING_LEN];
+        raw[..10].copy_from_slice(b"rx-gro-hw\0");
+        raw[GSTRING_LEN..GSTRING_LEN + 2].copy_from_slice(b"tx");
+        assert_eq!(parse_strings(&raw), ["rx-gro-hw", "tx", ""]);
+
+        let names: Vec<String> = ["10baseT/Half", "10baseT/Full", "Autoneg"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(link_modes(&[0b110], &names), ["10baseT/Full", "Autoneg"]);
+        assert_eq!(link_modes(&[0, 1], &names), ["bit32"]);
+
+        let features = decode_features(
+            &["rx-checksum".to_string(), "tx-lockless".to_string()],
+            &[0b01, 0b01, 0b11, 0],
+        );
+        assert_eq!(features.len(), 2);
+        assert!(features[0].active && features[0].changeable);
+        assert!(features[1].active && !features[1].changeable);
+    }
+
+    #[test]
+    fn test_loopback() {
+        // lo implements the generic feature ops but no driver info
+        let Ok(ethtool) = Ethtool::new() else {
+            return;
+        };
+        assert!(ethtool.driver_info("lo").unwrap().is_none());
+        if let Ok(features) = ethtool.features("lo") {
+            assert!(features.iter().any(|f| f.name == "loopback"));
+        }
+        assert!(matches!(
+            ethtool.driver_info("no-such-if0"),
+            Err(SimonError::DeviceNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,647 @@
+//! Fleet-wide view across many `simond` daemons
+//!
+//! Polls the daemon on every host in a host list in parallel and condenses
+//! each one into a [`HostSummary`] (GPU utilization, memory and
+//! temperatures, health score and failing checks), so a cluster can be
+//! watched from one terminal instead of logging into each node:
+//!
+//! - [`parse_host_list`] reads hosts with optional names, tags and tokens;
+//!   `gpu[01-60]` expands to sixty hosts
+//! - [`FleetMonitor::poll`] queries every host's `/v1/state` and
+//!   `/v1/health`, recording unreachable hosts instead of failing
+//! - [`worst_gpus`] ranks GPUs across the fleet (hottest, busiest, fullest
+//!   or most idle) and [`group_by_tag`] groups hosts for per-rack or
+//!   per-partition views
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::fleet::{parse_host_list, worst_gpus, FleetMonitor, GpuRanking};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let hosts = parse_host_list("gpu[01-04]:9465 tags=rack-a\ngpu05 tags=rack-b")?;
+//! let monitor = FleetMonitor::new(hosts).with_token("s3cret");
+//!
+//! let statuses = monitor.poll();
+//! for status in &statuses {
+//!     let summary = status.summary();
+//!     println!(
+//!         "{}: {} GPUs, health {:?}, failing {:?}",
+//!         status.name, summary.gpu_count, summary.health_score, summary.failing_checks
+//!     );
+//! }
+//! for gpu in worst_gpus(&statuses, GpuRanking::Temperature, 5) {
+//!     println!("{} GPU{}: {:?}°C", gpu.host, gpu.index, gpu.temperature);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::backend::FullSystemState;
+use crate::daemon::{DaemonClient, Endpoint};
+use crate::error::{Result, SimonError};
+use crate::health::{HealthStatus, SystemHealth};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Tag shown for hosts without one in [`group_by_tag`]
+pub const UNTAGGED: &str = "untagged";
+
+/// One host in the fleet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetHost {
+    /// Display name
+    pub name: String,
+    /// Where its daemon listens
+    pub endpoint: Endpoint,
+    /// Free-form tags for grouping (`rack-a`, `partition=train`)
+    pub tags: Vec<String>,
+    /// Token for this host, overriding the fleet-wide one
+    pub token: Option<String>,
+}
+
+impl FleetHost {
+    /// Host named after its address, without tags
+    pub fn new(endpoint: Endpoint) -> Self {
+        let name = match &endpoint {
+            Endpoint::Tcp(addr) => addr
+                .rsplit_once(':')
+                .map_or(addr.as_str(), |(host, _)| host)
+                .trim_matches(|c| c == '[' || c == ']')
+                .to_string(),
+            Endpoint::Unix(path) => path.display().to_string(),
+        };
+        Self {
+            name,
+            endpoint,
+            tags: Vec::new(),
+            token: None,
+        }
+    }
+
+    /// Whether the host carries `tag`
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// Parse a host list
+///
+/// One host per line: an endpoint (see [`Endpoint`]) followed by optional
+/// `name=`, `tags=a,b` and `token=` fields. `#` starts a comment. A numeric
+/// range in brackets expands, keeping zero padding: `node[08-10]` gives
+/// `node08`, `node09` and `node10`. An expanded line ignores `name=`.
+pub fn parse_host_list(content: &str) -> Result<Vec<FleetHost>> {
+    let mut hosts = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut fields = line.split_whitespace();
+        let Some(target) = fields.next() else {
+            continue;
+        };
+        let invalid = |message: String| {
+            SimonError::InvalidValue(format!("host list line {}: {}", number + 1, message))
+        };
+
+        let (mut name, mut tags, mut token) = (None, Vec::new(), None);
+        for field in fields {
+            match field.split_once('=') {
+                Some(("name", value)) => name = Some(value.to_string()),
+                Some(("tags", value)) => tags.extend(
+                    value
+                        .split(',')
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string),
+                ),
+                Some(("token", value)) => token = Some(value.to_string()),
+                _ => return Err(invalid(format!("unknown field '{}'", field))),
+            }
+        }
+
+        let targets = expand_range(target).map_err(invalid)?;
+        let expanded = targets.len() > 1;
+        for target in targets {
+            let mut host = FleetHost::new(target.parse()?);
+            if let Some(name) = name.as_ref().filter(|_| !expanded) {
+                host.name = name.clone();
+            }
+            host.tags = tags.clone();
+            host.token = token.clone();
+            hosts.push(host);
+        }
+    }
+    Ok(hosts)
+}
+
+/// Load a host list file (see [`parse_host_list`])
+pub fn load_host_list(path: &Path) -> Result<Vec<FleetHost>> {
+    parse_host_list(&std::fs::read_to_string(path)?)
+}
+
+/// Expand one `[start-end]` range
+fn expand_range(target: &str) -> std::result::Result<Vec<String>, String> {
+    let Some((prefix, rest)) = target.split_once('[') else {
+        return Ok(vec![target.to_string()]);
+    };
+    let (range, suffix) = rest
+        .split_once(']')
+        .ok_or_else(|| format!("unclosed range in '{}'", target))?;
+    // `[::1]:9465` is an IPv6 address, not a range
+    let Some((start, end)) = range.split_once('-') else {
+        return Ok(vec![target.to_string()]);
+    };
+    let bad_range = || format!("invalid range '[{}]'", range);
+    let (first, last): (u64, u64) = (
+        start.parse().map_err(|_| bad_range())?,
+        end.parse().map_err(|_| bad_range())?,
+    );
+    if first > last || last - first >= 10_000 {
+        return Err(bad_range());
+    }
+    let width = if start.starts_with('0') {
+        start.len()
+    } else {
+        0
+    };
+    Ok((first..=last)
+        .map(|n| format!("{}{:0width$}{}", prefix, n, suffix, width = width))
+        .collect())
+}
+
+/// What one poll of a host returned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostStatus {
+    /// Host name
+    pub name: String,
+    /// Daemon endpoint
+    pub endpoint: String,
+    /// Host tags
+    pub tags: Vec<String>,
+    /// Latest state, if the daemon answered
+    pub state: Option<FullSystemState>,
+    /// Latest health check, if the daemon has run one
+    pub health: Option<SystemHealth>,
+    /// Why the host could not be polled
+    pub error: Option<String>,
+    /// Round-trip time of the poll (ms)
+    pub latency_ms: u64,
+    /// When the poll finished (Unix seconds)
+    pub polled_at: u64,
+}
+
+impl HostStatus {
+    /// Whether the daemon answered
+    pub fn is_up(&self) -> bool {
+        self.state.is_some()
+    }
+
+    /// Condensed view for the cluster table
+    pub fn summary(&self) -> HostSummary {
+        let Some(state) = &self.state else {
+            return HostSummary::default();
+        };
+        let gpus = &state.accelerators;
+        let gpu_utilization = (!gpus.is_empty())
+            .then(|| gpus.iter().map(|g| g.utilization).sum::<f32>() / gpus.len() as f32);
+        HostSummary {
+            gpu_count: gpus.len(),
+            gpu_utilization,
+            gpu_memory_used: gpus.iter().map(|g| g.memory_used_bytes).sum(),
+            gpu_memory_total: gpus.iter().map(|g| g.memory_total_bytes).sum(),
+            max_gpu_temperature: gpus.iter().filter_map(|g| g.temperature).reduce(f32::max),
+            gpu_power_watts: gpus
+                .iter()
+                .filter_map(|g| g.power_watts)
+                .reduce(|a, b| a + b),
+            cpu_utilization: state.cpu.as_ref().map(|cpu| cpu.utilization),
+            memory_percent: state.memory.as_ref().map(|memory| memory.usage_percent),
+            health_status: self.health.as_ref().map(|health| health.status),
+            health_score: self.health.as_ref().map(|health| health.score),
+            failing_checks: self
+                .health
+                .as_ref()
+                .map(|health| {
+                    health
+                        .issues()
+                        .into_iter()
+                        .map(|check| check.name.clone())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// One row of the cluster table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HostSummary {
+    /// Number of GPUs/accelerators
+    pub gpu_count: usize,
+    /// Mean GPU utilization (0-100%)
+    pub gpu_utilization: Option<f32>,
+    /// GPU memory used across all GPUs (bytes)
+    pub gpu_memory_used: u64,
+    /// GPU memory across all GPUs (bytes)
+    pub gpu_memory_total: u64,
+    /// Hottest GPU (Celsius)
+    pub max_gpu_temperature: Option<f32>,
+    /// Total GPU power draw (Watts)
+    pub gpu_power_watts: Option<f32>,
+    /// CPU utilization (0-100%)
+    pub cpu_utilization: Option<f32>,
+    /// Host memory usage (0-100%)
+    pub memory_percent: Option<f32>,
+    /// Overall health status
+    pub health_status: Option<HealthStatus>,
+    /// Health score (0-100)
+    pub health_score: Option<u8>,
+    /// Names of checks at warning or critical
+    pub failing_checks: Vec<String>,
+}
+
+impl HostSummary {
+    /// GPU memory usage (0-100%)
+    pub fn gpu_memory_percent(&self) -> Option<f32> {
+        (self.gpu_memory_total > 0)
+            .then(|| self.gpu_memory_used as f32 / self.gpu_memory_total as f32 * 100.0)
+    }
+}
+
+/// One GPU somewhere in the fleet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetGpu {
+    /// Host name
+    pub host: String,
+    /// Index on that host
+    pub index: usize,
+    /// Device name
+    pub name: String,
+    /// Utilization (0-100%)
+    pub utilization: f32,
+    /// Memory usage (0-100%)
+    pub memory_percent: f32,
+    /// Temperature (Celsius)
+    pub temperature: Option<f32>,
+    /// Power draw (Watts)
+    pub power_watts: Option<f32>,
+}
+
+/// How [`worst_gpus`] ranks GPUs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GpuRanking {
+    /// Hottest first
+    #[default]
+    Temperature,
+    /// Busiest first
+    Utilization,
+    /// Fullest memory first
+    Memory,
+    /// Least utilized first (stranded capacity)
+    Idle,
+}
+
+impl GpuRanking {
+    /// Every ranking, in the order the TUI cycles through them
+    pub const ALL: [GpuRanking; 4] = [
+        GpuRanking::Temperature,
+        GpuRanking::Utilization,
+        GpuRanking::Memory,
+        GpuRanking::Idle,
+    ];
+
+    /// The ranking after this one
+    pub fn next(self) -> Self {
+        let position = Self::ALL.iter().position(|r| *r == self).unwrap_or(0);
+        Self::ALL[(position + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for GpuRanking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GpuRanking::Temperature => "temperature",
+            GpuRanking::Utilization => "utilization",
+            GpuRanking::Memory => "memory",
+            GpuRanking::Idle => "idle",
+        })
+    }
+}
+
+impl std::str::FromStr for GpuRanking {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "temperature" | "temp" => Ok(GpuRanking::Temperature),
+            "utilization" | "util" => Ok(GpuRanking::Utilization),
+            "memory" | "mem" => Ok(GpuRanking::Memory),
+            "idle" => Ok(GpuRanking::Idle),
+            _ => Err(SimonError::InvalidValue(format!(
+                "unknown GPU ranking '{}' (temperature, utilization, memory, idle)",
+                s
+            ))),
+        }
+    }
+}
+
+/// The `n` worst GPUs across all reachable hosts
+pub fn worst_gpus(hosts: &[HostStatus], ranking: GpuRanking, n: usize) -> Vec<FleetGpu> {
+    let mut gpus: Vec<FleetGpu> = hosts
+        .iter()
+        .filter_map(|host| Some((host, host.state.as_ref()?)))
+        .flat_map(|(host, state)| {
+            state.accelerators.iter().map(move |gpu| FleetGpu {
+                host: host.name.clone(),
+                index: gpu.index,
+                name: gpu.name.clone(),
+                utilization: gpu.utilization,
+                memory_percent: gpu.memory_usage_percent,
+                temperature: gpu.temperature,
+                power_watts: gpu.power_watts,
+            })
+        })
+        .collect();
+    let key = |gpu: &FleetGpu| match ranking {
+        GpuRanking::Temperature => gpu.temperature.unwrap_or(f32::MIN),
+        GpuRanking::Utilization => gpu.utilization,
+        GpuRanking::Memory => gpu.memory_percent,
+        GpuRanking::Idle => -gpu.utilization,
+    };
+    gpus.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    gpus.truncate(n);
+    gpus
+}
+
+/// Hosts grouped by tag; a host with several tags appears in each group
+pub fn group_by_tag(hosts: &[HostStatus]) -> BTreeMap<String, Vec<&HostStatus>> {
+    let mut groups: BTreeMap<String, Vec<&HostStatus>> = BTreeMap::new();
+    for host in hosts {
+        if host.tags.is_empty() {
+            groups.entry(UNTAGGED.to_string()).or_default().push(host);
+        }
+        for tag in &host.tags {
+            groups.entry(tag.clone()).or_default().push(host);
+        }
+    }
+    groups
+}
+
+/// Polls every host's daemon
+#[derive(Debug, Clone)]
+pub struct FleetMonitor {
+    hosts: Vec<FleetHost>,
+    token: Option<String>,
+    timeout: Duration,
+    concurrency: usize,
+}
+
+impl FleetMonitor {
+    /// Monitor `hosts` with a 3 s timeout and 32 concurrent polls
+    pub fn new(hosts: Vec<FleetHost>) -> Self {
+        Self {
+            hosts,
+            token: None,
+            timeout: Duration::from_secs(3),
+            concurrency: 32,
+        }
+    }
+
+    /// Token for hosts without their own
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Per-request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Hosts polled at once
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Keep only hosts carrying `tag`
+    pub fn with_tag_filter(mut self, tag: &str) -> Self {
+        self.hosts.retain(|host| host.has_tag(tag));
+        self
+    }
+
+    /// Monitored hosts
+    pub fn hosts(&self) -> &[FleetHost] {
+        &self.hosts
+    }
+
+    /// Client for one host's daemon (for drill-down)
+    pub fn client(&self, host: &FleetHost) -> DaemonClient {
+        let client = DaemonClient::new(host.endpoint.clone()).with_timeout(self.timeout);
+        match host.token.as_ref().or(self.token.as_ref()) {
+            Some(token) => client.with_token(token.clone()),
+            None => client,
+        }
+    }
+
+    /// Poll every host, in host-list order
+    pub fn poll(&self) -> Vec<HostStatus> {
+        let mut statuses = Vec::with_capacity(self.hosts.len());
+        for batch in self.hosts.chunks(self.concurrency) {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|host| scope.spawn(move || self.poll_host(host)))
+                    .collect();
+                statuses.extend(handles.into_iter().zip(batch).map(|(handle, host)| {
+                    handle.join().unwrap_or_else(|_| HostStatus {
+                        error: Some("poll panicked".into()),
+                        ..self.empty_status(host)
+                    })
+                }));
+            });
+        }
+        statuses
+    }
+
+    /// Poll one host
+    pub fn poll_host(&self, host: &FleetHost) -> HostStatus {
+        let client = self.client(host);
+        let started = Instant::now();
+        let mut status = self.empty_status(host);
+        match client.state() {
+            Ok(state) => {
+                status.state = Some(state);
+                // The daemon answers 503 until its first health check
+                status.health = client.health().ok();
+            }
+            Err(e) => status.error = Some(e.to_string()),
+        }
+        status.latency_ms = started.elapsed().as_millis() as u64;
+        status.polled_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        status
+    }
+
+    fn empty_status(&self, host: &FleetHost) -> HostStatus {
+        HostStatus {
+            name: host.name.clone(),
+            endpoint: host.endpoint.to_string(),
+            tags: host.tags.clone(),
+            state: None,
+            health: None,
+            error: None,
+            latency_ms: 0,
+            polled_at: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::AcceleratorState;
+    use crate::daemon::{ApiToken, Daemon, DaemonConfig, Permission, Sampler, Snapshot};
+    use crate::health::HealthCheck;
+
+    /// Stand-in node with fixed GPU temperatures and one failing check
+    struct FakeNode {
+        temperatures: Vec<f32>,
+    }
+
+    impl Sampler for FakeNode {
+        fn sample(&mut self) -> Result<Snapshot> {
+            let mut snapshot = Snapshot::empty();
+            snapshot.state.accelerators = self
+                .temperatures
+                .iter()
+                .enumerate()
+                .map(|(index, &temperature)| AcceleratorState {
+                    index,
+                    accel_type: "GPU".into(),
+                    name: "Fake GPU".into(),
+                    vendor: "Test".into(),
+                    utilization: 10.0 * index as f32,
+                    memory_used_bytes: 1 << 30,
+                    memory_total_bytes: 4 << 30,
+                    memory_usage_percent: 25.0,
+                    temperature: Some(temperature),
+                    power_watts: Some(100.0),
+                    power_limit_watts: None,
+                    clock_mhz: None,
+                    memory_clock_mhz: None,
+                    process_count: 0,
+                })
+                .collect();
+            Ok(snapshot)
+        }
+
+        fn health(&mut self) -> Result<SystemHealth> {
+            Ok(SystemHealth {
+                status: HealthStatus::Warning,
+                score: 70,
+                checks: vec![HealthCheck::new("GPU Temperature", "gpu")
+                    .with_status(HealthStatus::Warning, "hot")],
+                healthy_count: 0,
+                warning_count: 1,
+                critical_count: 0,
+                timestamp: SystemTime::now(),
+            })
+        }
+    }
+
+    fn spawn_node(temperatures: Vec<f32>) -> Daemon {
+        let config = DaemonConfig::default()
+            .with_socket_path(None)
+            .with_listen("127.0.0.1:0".parse().unwrap())
+            .with_token(ApiToken::new("fleet", Permission::Read));
+        Daemon::start_with(config, move || Ok(FakeNode { temperatures })).unwrap()
+    }
+
+    #[test]
+    fn test_parse_host_list() {
+        let hosts = parse_host_list(
+            "# rack A\n\
+             gpu[08-10] tags=rack-a,train\n\
+             10.0.0.5:9000 name=login token=abc  # comment\n\
+             unix:/run/simond.sock\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = hosts.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["gpu08", "gpu09", "gpu10", "login", "/run/simond.sock"]
+        );
+        assert_eq!(hosts[0].endpoint, Endpoint::Tcp("gpu08:9465".into()));
+        assert!(hosts[2].has_tag("train"));
+        assert_eq!(hosts[3].token.as_deref(), Some("abc"));
+        assert_eq!(
+            FleetHost::new("[::1]:9465".parse().unwrap()).name,
+            "::1".to_string()
+        );
+
+        assert!(parse_host_list("gpu[3-1]").is_err());
+        assert!(parse_host_list("gpu01 rack=a").is_err());
+    }
+
+    #[test]
+    fn test_poll_fleet() {
+        let hot = spawn_node(vec![60.0, 91.0]);
+        let cool = spawn_node(vec![40.0]);
+        // A port nothing listens on
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_addr = closed.local_addr().unwrap();
+        drop(closed);
+
+        let list = format!(
+            "{} name=hot tags=rack-a\n{} name=cool tags=rack-a,rack-b\n{} name=down\n",
+            hot.local_addr().unwrap(),
+            cool.local_addr().unwrap(),
+            closed_addr
+        );
+        let monitor = FleetMonitor::new(parse_host_list(&list).unwrap())
+            .with_token("fleet")
+            .with_timeout(Duration::from_secs(2));
+        let statuses = monitor.poll();
+        assert_eq!(statuses.len(), 3);
+        assert!(statuses[0].is_up() && statuses[1].is_up());
+        assert!(!statuses[2].is_up());
+        assert!(statuses[2].error.is_some());
+
+        let summary = statuses[0].summary();
+        assert_eq!(summary.gpu_count, 2);
+        assert_eq!(summary.max_gpu_temperature, Some(91.0));
+        assert_eq!(summary.gpu_utilization, Some(5.0));
+        assert_eq!(summary.gpu_memory_percent(), Some(25.0));
+        assert_eq!(summary.health_score, Some(70));
+        assert_eq!(summary.failing_checks, vec!["GPU Temperature"]);
+
+        let worst = worst_gpus(&statuses, GpuRanking::Temperature, 2);
+        assert_eq!(
+            worst
+                .iter()
+                .map(|g| (g.host.as_str(), g.index))
+                .collect::<Vec<_>>(),
+            [("hot", 1), ("hot", 0)]
+        );
+        let idle = worst_gpus(&statuses, GpuRanking::Idle, 1);
+        assert_eq!(idle[0].utilization, 0.0);
+
+        let groups = group_by_tag(&statuses);
+        assert_eq!(groups["rack-a"].len(), 2);
+        assert_eq!(groups["rack-b"][0].name, "cool");
+        assert_eq!(groups[UNTAGGED][0].name, "down");
+
+        let rack_b = monitor.clone().with_tag_filter("rack-b");
+        assert_eq!(rack_b.hosts().len(), 1);
+
+        hot.shutdown();
+        cool.shutdown();
+    }
+}
@@ -14,10 +14,15 @@
 //!
 //! Based on nvtop's extract_gpuinfo_amdgpu.c implementation.
 
+#[cfg(target_os = "linux")]
+use crate::gpu::amdgpu_metrics::GpuMetrics;
 use crate::gpu::{
-    Gpu, GpuClocks, GpuCollection, GpuDynamicInfo, GpuEngines, GpuMemory, GpuPower, GpuProcess,
-    GpuStaticInfo, GpuThermal, GpuVendor, PcieLinkInfo,
+    snapshot_capabilities, Capabilities, Capability, Gpu, GpuClocks, GpuCollection, GpuDynamicInfo,
+    GpuEngines, GpuMemory, GpuPower, GpuProcess, GpuProcessType, GpuStaticInfo, GpuThermal,
+    GpuVendor, PcieLinkInfo,
 };
+#[cfg(target_os = "linux")]
+use crate::throttling::{amdgpu_throttle_status, GpuThrottleStatus};
 use crate::Error;
 
 #[cfg(target_os = "linux")]
@@ -185,10 +190,20 @@ impl Gpu for AmdGpu {
         {
             let device_path = format!("{}/device", self.card_path);
 
+            // The SMU metrics table covers most of what follows in one read;
+            // sysfs and hwmon fill in anything it doesn't report
+            let metrics = GpuMetrics::read(Path::new(&device_path)).ok();
+
             // Read GPU utilization
-            let utilization = fs::read_to_string(format!("{}/gpu_busy_percent", device_path))
-                .ok()
-                .and_then(|s| s.trim().parse::<u8>().ok())
+            let utilization = metrics
+                .as_ref()
+                .and_then(|m| m.gfx_activity)
+                .map(|a| a.min(100) as u8)
+                .or_else(|| {
+                    fs::read_to_string(format!("{}/gpu_busy_percent", device_path))
+                        .ok()
+                        .and_then(|s| s.trim().parse::<u8>().ok())
+                })
                 .unwrap_or(0);
 
             // Read memory info
@@ -209,27 +224,49 @@ impl Gpu for AmdGpu {
             } else {
                 (None, None)
             };
+            let graphics_clock = metrics
+                .as_ref()
+                .and_then(|m| m.gfxclk())
+                .map(u32::from)
+                .or(graphics_clock);
+            let memory_clock = metrics
+                .as_ref()
+                .and_then(|m| m.current_uclk)
+                .map(u32::from)
+                .or(memory_clock);
 
             // Read power from hwmon
             let power_draw = self
                 .hwmon_path
                 .as_ref()
-                .and_then(|hwmon| read_sysfs_microwatts(&format!("{}/power1_average", hwmon)));
+                .and_then(|hwmon| read_sysfs_microwatts(&format!("{}/power1_average", hwmon)))
+                .or_else(|| {
+                    metrics
+                        .as_ref()
+                        .and_then(|m| m.socket_power)
+                        .map(|w| (w * 1000.0) as u32)
+                });
 
             // Read temperature from hwmon
             let temperature = self.hwmon_path.as_ref().and_then(|hwmon| {
                 fs::read_to_string(format!("{}/temp1_input", hwmon))
                     .ok()
                     .and_then(|s| s.trim().parse::<i32>().ok())
-                    .map(|t| (t / 1000) as u32)
+                    .map(|t| t / 1000)
             });
 
+            let temperature = metrics
+                .as_ref()
+                .and_then(|m| m.primary_temperature())
+                .map(|t| t as i32)
+                .or(temperature);
+
             // Read critical temp
             let critical_temp = self.hwmon_path.as_ref().and_then(|hwmon| {
                 fs::read_to_string(format!("{}/temp1_crit", hwmon))
                     .ok()
                     .and_then(|s| s.trim().parse::<i32>().ok())
-                    .map(|t| (t / 1000) as u32)
+                    .map(|t| t / 1000)
             });
 
             // Read fan speed
@@ -237,6 +274,7 @@ impl Gpu for AmdGpu {
                 fs::read_to_string(format!("{}/fan1_input", hwmon))
                     .ok()
                     .and_then(|s| s.trim().parse::<u32>().ok())
+                    .or_else(|| metrics.as_ref()?.fan_speed.map(u32::from))
             });
 
             let fan_speed = self.hwmon_path.as_ref().and_then(|hwmon| {
@@ -277,11 +315,17 @@ impl Gpu for AmdGpu {
                     fan_rpm,
                 },
                 pcie: PcieLinkInfo {
-                    current_gen: None,
+                    current_gen: metrics.as_ref().and_then(|m| m.pcie_gen()),
                     max_gen: None,
-                    current_width: None,
+                    current_width: metrics
+                        .as_ref()
+                        .and_then(|m| m.pcie_link_width)
+                        .map(|w| w as u8),
                     max_width: None,
-                    current_speed: None,
+                    current_speed: metrics
+                        .as_ref()
+                        .and_then(|m| m.pcie_link_speed)
+                        .map(|s| s as u32 * 100),
                     max_speed: None,
                     tx_throughput: None,
                     rx_throughput: None,
@@ -290,9 +334,26 @@ impl Gpu for AmdGpu {
                     graphics: Some(utilization),
                     compute: None,
                     encoder: None,
-                    decoder: None,
+                    decoder: metrics
+                        .as_ref()
+                        .and_then(|m| {
+                            m.mm_activity
+                                .or(m.vcn_activity.iter().flatten().copied().max())
+                        })
+                        .map(|a| a.min(100) as u8),
                     copy: None,
-                    vendor_specific: vec![],
+                    vendor_specific: metrics
+                        .as_ref()
+                        .map(|m| {
+                            m.jpeg_activity
+                                .iter()
+                                .enumerate()
+                                .filter_map(|(i, a)| {
+                                    Some((format!("JPEG{}", i), (*a)?.min(100) as u8))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
                 },
                 processes: vec![],
             })
@@ -389,6 +450,23 @@ impl Gpu for AmdGpu {
             ))
         }
     }
+
+    #[cfg(target_os = "linux")]
+    fn throttle_status(&self) -> Result<GpuThrottleStatus, Error> {
+        let device_path = format!("{}/device", self.card_path);
+        amdgpu_throttle_status(Path::new(&device_path)).ok_or_else(|| {
+            Error::NotSupported("gpu_metrics throttle status not available".to_string())
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let caps = snapshot_capabilities(self);
+        if cfg!(unix) {
+            caps.with(Capability::KillProcess)
+        } else {
+            caps
+        }
+    }
 }
 
 /// Read a sysfs value as bytes
@@ -477,12 +555,15 @@ fn parse_fdinfo_processes(card_path: &str) -> Result<Vec<GpuProcess>, Error> {
                                 processes.push(GpuProcess {
                                     pid,
                                     name,
-                                    gpu_memory: vram_mem,
-                                    compute_util: None,
-                                    memory_util: None,
-                                    encoder_util: None,
-                                    decoder_util: None,
-                                    process_type: None,
+                                    user: String::new(),
+                                    process_type: GpuProcessType::Unknown,
+                                    gpu_usage: None,
+                                    memory_usage: Some(vram_mem),
+                                    memory_usage_percent: None,
+                                    encoder_usage: None,
+                                    decoder_usage: None,
+                                    cpu_usage: None,
+                                    cpu_memory: None,
                                 });
                             }
                             break; // Found amdgpu for this process
@@ -3,9 +3,11 @@
 //!
 //! This module provides AMD GPU monitoring through sysfs on Linux.
 
+use super::amdgpu_control::AmdGpuControl;
+use super::amdgpu_metrics::GpuMetrics;
 use super::traits::{
-    Clocks, Device, Error, FanSpeed, GpuProcess, Memory, PciInfo, Power, Temperature,
-    TemperatureThresholds, Utilization, Vendor,
+    Capabilities, Capability, Clocks, Device, Error, FanSpeed, GpuProcess, Memory, PciInfo, Power,
+    Temperature, TemperatureThresholds, Utilization, Vendor,
 };
 use std::fs;
 use std::path::PathBuf;
@@ -41,6 +43,10 @@ impl AmdGpu {
     fn read_sysfs_u64(&self, attr: &str) -> Option<u64> {
         self.read_sysfs_string(attr)?.parse::<u64>().ok()
     }
+
+    fn gpu_metrics(&self) -> Option<GpuMetrics> {
+        GpuMetrics::read(&self.device_path).ok()
+    }
 }
 
 impl AmdGpu {
@@ -200,11 +206,25 @@ impl Device for AmdGpu {
         Ok("amdgpu".to_string())
     }
     fn temperature(&self) -> Result<Temperature, Error> {
+        let metrics = self.gpu_metrics();
+
         // AMD GPUs expose temperature via hwmon
         // Find hwmon directory
         let hwmon_path = self.device_path.join("hwmon");
         if !hwmon_path.exists() {
-            return Err(Error::NotSupported);
+            // gpu_metrics alone still has the SMU temperatures
+            let m = metrics.ok_or(Error::NotSupported)?;
+            return Ok(Temperature {
+                edge: m.temperature_edge.or(m.temperature_gfx),
+                junction: m.temperature_hotspot,
+                memory: m.temperature_mem,
+                hotspot: m.temperature_hotspot,
+                vr_gfx: m.temperature_vrgfx,
+                vr_soc: m.temperature_vrsoc,
+                vr_mem: m.temperature_vrmem,
+                hbm: hbm_temperatures(&m),
+                thresholds: None,
+            });
         }
 
         let hwmon_dirs: Vec<_> = fs::read_dir(&hwmon_path)
@@ -227,15 +247,17 @@ impl Device for AmdGpu {
         // Read temperature thresholds
         let thresholds = self.get_temperature_thresholds(&hwmon);
 
+        // VR and HBM sensors are only in gpu_metrics
+        let m = metrics.unwrap_or_default();
         Ok(Temperature {
-            edge,
-            junction,
-            memory,
-            hotspot: None,
-            vr_gfx: None,
-            vr_soc: None,
-            vr_mem: None,
-            hbm: None,
+            edge: edge.or(m.temperature_edge),
+            junction: junction.or(m.temperature_hotspot),
+            memory: memory.or(m.temperature_mem),
+            hotspot: m.temperature_hotspot.or(junction),
+            vr_gfx: m.temperature_vrgfx,
+            vr_soc: m.temperature_vrsoc,
+            vr_mem: m.temperature_vrmem,
+            hbm: hbm_temperatures(&m),
             thresholds,
         })
     }
@@ -268,7 +290,13 @@ impl Device for AmdGpu {
                 .unwrap_or(0.0)
         };
 
-        let current = read_power("power1_average");
+        let mut current = read_power("power1_average");
+        if current == 0.0 {
+            // MI300 and APUs report socket power only through gpu_metrics
+            if let Some(power) = self.gpu_metrics().and_then(|m| m.socket_power) {
+                current = power;
+            }
+        }
         let limit = read_power("power1_cap");
         let max_limit = read_power("power1_cap_max");
         let min_limit = read_power("power1_cap_min");
@@ -286,14 +314,30 @@ impl Device for AmdGpu {
     fn clocks(&self) -> Result<Clocks, Error> {
         // Read clock frequencies from sysfs
         // AMD exposes current frequencies via pp_dpm_sclk (graphics) and pp_dpm_mclk (memory)
-        let graphics = self.read_current_clock("pp_dpm_sclk").unwrap_or(0);
-        let memory = self.read_current_clock("pp_dpm_mclk").unwrap_or(0);
+        // gpu_metrics has the actual clocks rather than the selected DPM level
+        let metrics = self.gpu_metrics().unwrap_or_default();
+        let graphics = metrics
+            .gfxclk()
+            .map(u32::from)
+            .or_else(|| self.read_current_clock("pp_dpm_sclk"))
+            .unwrap_or(0);
+        let memory = metrics
+            .current_uclk
+            .map(u32::from)
+            .or_else(|| self.read_current_clock("pp_dpm_mclk"))
+            .unwrap_or(0);
 
         Ok(Clocks {
             graphics,
             memory,
             sm: None,
-            video: None,
+            video: metrics
+                .current_vclk
+                .iter()
+                .flatten()
+                .copied()
+                .max()
+                .map(u32::from),
         })
     }
     fn utilization(&self) -> Result<Utilization, Error> {
@@ -303,16 +347,23 @@ impl Device for AmdGpu {
             .and_then(|s| s.parse::<f32>().ok())
             .unwrap_or(0.0);
 
-        // Memory utilization not directly available via sysfs
-        // Would need to calculate from memory bandwidth counters
-        let memory = 0.0;
+        // Memory controller and media engine activity come from gpu_metrics
+        let metrics = self.gpu_metrics().unwrap_or_default();
+        let memory = metrics.umc_activity.map(f32::from).unwrap_or(0.0);
+        let average = |values: &[Option<u16>]| {
+            let values: Vec<f32> = values.iter().flatten().map(|&v| f32::from(v)).collect();
+            (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+        };
 
         Ok(Utilization {
             gpu,
             memory,
             encoder: None,
-            decoder: None,
-            jpeg: None,
+            decoder: metrics
+                .mm_activity
+                .map(f32::from)
+                .or_else(|| average(&metrics.vcn_activity)),
+            jpeg: average(&metrics.jpeg_activity),
             ofa: None,
         })
     }
@@ -376,6 +427,71 @@ impl Device for AmdGpu {
     fn processes(&self) -> Result<Vec<Box<dyn GpuProcess>>, Error> {
         Ok(Vec::new())
     }
+    fn throttle_status(&self) -> Result<crate::throttling::GpuThrottleStatus, Error> {
+        crate::throttling::amdgpu_throttle_status(&self.device_path).ok_or(Error::NotSupported)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let mut caps = Capabilities::probe(self);
+        let control = AmdGpuControl::new(&self.device_path);
+        if control.power_cap().is_ok() {
+            caps.insert(Capability::SetPowerLimit);
+        }
+        if let Ok(od) = control.od_table() {
+            if od.sclk_range.is_some() {
+                caps.insert(Capability::LockClocks);
+            }
+            if od.mclk_range.is_some() {
+                caps.insert(Capability::LockMemoryClocks);
+            }
+            if od.voltage_offset_range.is_some() {
+                caps.insert(Capability::SetVoltageOffset);
+            }
+        }
+        if control.performance_level().is_ok() {
+            caps.insert(Capability::SetPerformanceLevel);
+        }
+        if control.power_profiles().is_ok_and(|p| !p.is_empty()) {
+            caps.insert(Capability::SetPowerProfile);
+        }
+        caps
+    }
+
+    // === Control Functions ===
+
+    fn set_power_limit(&mut self, watts: f32) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_power_cap(watts)
+    }
+
+    fn lock_gpu_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_sclk_range(min_mhz, max_mhz)
+    }
+
+    fn reset_gpu_clocks(&mut self) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).reset_clocks()
+    }
+
+    fn lock_memory_clocks(&mut self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_mclk_range(min_mhz, max_mhz)
+    }
+
+    fn set_voltage_offset(&mut self, offset_mv: i32) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_voltage_offset(offset_mv)
+    }
+
+    fn set_performance_level(&mut self, level: &str) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_performance_level(level.parse()?)
+    }
+
+    fn set_power_profile(&mut self, profile: &str) -> Result<(), Error> {
+        AmdGpuControl::new(&self.device_path).set_power_profile(profile)
+    }
+}
+
+/// Temperatures of the HBM stacks the SMU reports, if any
+fn hbm_temperatures(m: &GpuMetrics) -> Option<Vec<f32>> {
+    let hbm: Vec<f32> = m.temperature_hbm.iter().flatten().copied().collect();
+    (!hbm.is_empty()).then_some(hbm)
 }
 
 pub fn enumerate() -> Result<Vec<Box<dyn Device>>, Error> {
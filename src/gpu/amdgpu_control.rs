@@ -0,0 +1,712 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! amdgpu power-profile, overdrive and power-cap control via sysfs
+//!
+//! The amdgpu driver exposes its power management knobs as sysfs files under
+//! the PCI device directory (`/sys/class/drm/cardN/device`):
+//!
+//! | File | Purpose |
+//! |------|---------|
+//! | `power_dpm_force_performance_level` | DPM mode (`auto`, `manual`, `high`, ...) |
+//! | `pp_power_profile_mode` | Workload profile (3D, compute, VR, custom, ...) |
+//! | `pp_od_clk_voltage` | Overdrive sclk/mclk ranges and voltage offset |
+//! | `hwmon/hwmonN/power1_cap` | Board power limit (µW) |
+//!
+//! Writes are validated against the ranges the driver reports before they
+//! reach sysfs, and [`AmdGpuControl::restore_defaults`] puts everything back
+//! to the driver defaults. Writing requires root.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::gpu::amdgpu_control::{AmdGpuControl, PerformanceLevel};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let control = AmdGpuControl::new("/sys/class/drm/card0/device");
+//!
+//! for profile in control.power_profiles()? {
+//!     println!("{} {}{}", profile.index, profile.name, if profile.active { " *" } else { "" });
+//! }
+//! control.set_power_profile("COMPUTE")?;
+//!
+//! let od = control.od_table()?;
+//! println!("sclk range: {:?}", od.sclk_range);
+//! control.set_sclk_range(800, 2400)?;
+//! control.set_power_cap(200.0)?;
+//!
+//! control.restore_defaults()?;
+//! assert_eq!(control.performance_level()?, PerformanceLevel::Auto);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::gpu::traits::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// DPM performance level (`power_dpm_force_performance_level`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PerformanceLevel {
+    /// Driver-managed clocks (default)
+    Auto,
+    /// Lowest clocks
+    Low,
+    /// Highest clocks
+    High,
+    /// User-controlled via profiles and overdrive
+    Manual,
+    /// Fixed clocks for profiling
+    ProfileStandard,
+    /// Minimum sclk for profiling
+    ProfileMinSclk,
+    /// Minimum mclk for profiling
+    ProfileMinMclk,
+    /// Peak clocks for profiling
+    ProfilePeak,
+    /// Deterministic performance at a fixed clock (MI series)
+    PerfDeterminism,
+}
+
+impl PerformanceLevel {
+    /// Value as written to sysfs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PerformanceLevel::Auto => "auto",
+            PerformanceLevel::Low => "low",
+            PerformanceLevel::High => "high",
+            PerformanceLevel::Manual => "manual",
+            PerformanceLevel::ProfileStandard => "profile_standard",
+            PerformanceLevel::ProfileMinSclk => "profile_min_sclk",
+            PerformanceLevel::ProfileMinMclk => "profile_min_mclk",
+            PerformanceLevel::ProfilePeak => "profile_peak",
+            PerformanceLevel::PerfDeterminism => "perf_determinism",
+        }
+    }
+}
+
+impl fmt::Display for PerformanceLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PerformanceLevel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Ok(match s.trim() {
+            "auto" => PerformanceLevel::Auto,
+            "low" => PerformanceLevel::Low,
+            "high" => PerformanceLevel::High,
+            "manual" => PerformanceLevel::Manual,
+            "profile_standard" => PerformanceLevel::ProfileStandard,
+            "profile_min_sclk" => PerformanceLevel::ProfileMinSclk,
+            "profile_min_mclk" => PerformanceLevel::ProfileMinMclk,
+            "profile_peak" => PerformanceLevel::ProfilePeak,
+            "perf_determinism" => PerformanceLevel::PerfDeterminism,
+            other => {
+                return Err(Error::InvalidArgument(format!(
+                    "Unknown performance level: {}",
+                    other
+                )))
+            }
+        })
+    }
+}
+
+/// Workload power profile from `pp_power_profile_mode`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerProfile {
+    /// Index written to select the profile
+    pub index: u32,
+    /// Profile name (e.g. `BOOTUP_DEFAULT`, `3D_FULL_SCREEN`, `COMPUTE`)
+    pub name: String,
+    /// Whether this profile is currently selected
+    pub active: bool,
+}
+
+impl PowerProfile {
+    /// Whether this is the user-tunable `CUSTOM` profile
+    pub fn is_custom(&self) -> bool {
+        self.name.eq_ignore_ascii_case("CUSTOM")
+    }
+}
+
+/// Overdrive table from `pp_od_clk_voltage`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OdTable {
+    /// Current sclk points (index, MHz)
+    pub sclk: Vec<(u32, u32)>,
+    /// Current mclk points (index, MHz)
+    pub mclk: Vec<(u32, u32)>,
+    /// Current GFX voltage offset (mV)
+    pub voltage_offset: Option<i32>,
+    /// Allowed sclk range (MHz)
+    pub sclk_range: Option<(u32, u32)>,
+    /// Allowed mclk range (MHz)
+    pub mclk_range: Option<(u32, u32)>,
+    /// Allowed GFX voltage offset range (mV)
+    pub voltage_offset_range: Option<(i32, i32)>,
+}
+
+impl OdTable {
+    /// Parse the contents of `pp_od_clk_voltage`
+    pub fn parse(content: &str) -> Self {
+        let mut table = OdTable::default();
+        let mut section = "";
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with("OD_") && line.ends_with(':') {
+                section = line.trim_end_matches(':');
+                continue;
+            }
+            match section {
+                "OD_SCLK" | "OD_MCLK" => {
+                    // "0: 500Mhz" or "0: 300MHz 800mV" on older ASICs
+                    let Some((index, rest)) = line.split_once(':') else {
+                        continue;
+                    };
+                    let (Ok(index), Some(mhz)) = (
+                        index.trim().parse(),
+                        rest.split_whitespace().next().and_then(parse_unit),
+                    ) else {
+                        continue;
+                    };
+                    let points = if section == "OD_SCLK" {
+                        &mut table.sclk
+                    } else {
+                        &mut table.mclk
+                    };
+                    points.push((index, mhz as u32));
+                }
+                "OD_VDDGFX_OFFSET" => table.voltage_offset = parse_unit(line).map(|v| v as i32),
+                "OD_RANGE" => {
+                    let Some((name, rest)) = line.split_once(':') else {
+                        continue;
+                    };
+                    let mut values = rest.split_whitespace().filter_map(parse_unit);
+                    let (Some(min), Some(max)) = (values.next(), values.next()) else {
+                        continue;
+                    };
+                    match name.trim() {
+                        "SCLK" => table.sclk_range = Some((min as u32, max as u32)),
+                        "MCLK" => table.mclk_range = Some((min as u32, max as u32)),
+                        "VDDGFX_OFFSET" => {
+                            table.voltage_offset_range = Some((min as i32, max as i32))
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        table
+    }
+
+    /// Commands that set the sclk range, validated against `OD_RANGE`
+    pub fn sclk_commands(&self, min_mhz: u32, max_mhz: u32) -> Result<Vec<String>, Error> {
+        check_range("sclk", min_mhz, max_mhz, self.sclk_range)?;
+        range_commands('s', &self.sclk, min_mhz, max_mhz)
+    }
+
+    /// Commands that set the mclk range, validated against `OD_RANGE`
+    ///
+    /// Some ASICs only expose the maximum mclk point; the minimum is then
+    /// only validated.
+    pub fn mclk_commands(&self, min_mhz: u32, max_mhz: u32) -> Result<Vec<String>, Error> {
+        check_range("mclk", min_mhz, max_mhz, self.mclk_range)?;
+        range_commands('m', &self.mclk, min_mhz, max_mhz)
+    }
+
+    /// Command that sets the GFX voltage offset, validated against `OD_RANGE`
+    pub fn voltage_offset_command(&self, offset_mv: i32) -> Result<String, Error> {
+        let (min, max) = self
+            .voltage_offset_range
+            .ok_or_else(|| Error::InvalidArgument("Voltage offset is not adjustable".into()))?;
+        if offset_mv < min || offset_mv > max {
+            return Err(Error::InvalidArgument(format!(
+                "Voltage offset {} mV outside {}..={} mV",
+                offset_mv, min, max
+            )));
+        }
+        Ok(format!("vo {}", offset_mv))
+    }
+}
+
+/// Board power cap from hwmon (watts)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerCap {
+    /// Current cap
+    pub current: f32,
+    /// Minimum allowed cap
+    pub min: Option<f32>,
+    /// Maximum allowed cap
+    pub max: Option<f32>,
+    /// Driver default cap
+    pub default: Option<f32>,
+}
+
+/// Power management control for one amdgpu device
+#[derive(Debug, Clone)]
+pub struct AmdGpuControl {
+    device_path: PathBuf,
+}
+
+impl AmdGpuControl {
+    /// Create a controller for a PCI device directory
+    /// (`/sys/class/drm/cardN/device`)
+    pub fn new(device_path: impl Into<PathBuf>) -> Self {
+        Self {
+            device_path: device_path.into(),
+        }
+    }
+
+    /// PCI device directory being controlled
+    pub fn device_path(&self) -> &Path {
+        &self.device_path
+    }
+
+    /// Current DPM performance level
+    pub fn performance_level(&self) -> Result<PerformanceLevel, Error> {
+        self.read("power_dpm_force_performance_level")?.parse()
+    }
+
+    /// Set the DPM performance level
+    pub fn set_performance_level(&self, level: PerformanceLevel) -> Result<(), Error> {
+        self.write("power_dpm_force_performance_level", level.as_str())
+    }
+
+    /// Available workload power profiles
+    pub fn power_profiles(&self) -> Result<Vec<PowerProfile>, Error> {
+        Ok(parse_power_profiles(&self.read("pp_power_profile_mode")?))
+    }
+
+    /// Currently selected power profile
+    pub fn active_power_profile(&self) -> Result<Option<PowerProfile>, Error> {
+        Ok(self.power_profiles()?.into_iter().find(|p| p.active))
+    }
+
+    /// Select a power profile by name (case-insensitive)
+    ///
+    /// Profiles only take effect with the `manual` performance level, so
+    /// the level is switched first.
+    pub fn set_power_profile(&self, name: &str) -> Result<(), Error> {
+        let profile = self
+            .power_profiles()?
+            .into_iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown power profile: {}", name)))?;
+        self.ensure_manual()?;
+        self.write("pp_power_profile_mode", &profile.index.to_string())
+    }
+
+    /// Select the `CUSTOM` profile with ASIC-specific heuristic parameters
+    ///
+    /// `params` are written after the profile index in the column order
+    /// shown by `pp_power_profile_mode` for this ASIC.
+    pub fn set_custom_power_profile(&self, params: &[i64]) -> Result<(), Error> {
+        let custom = self
+            .power_profiles()?
+            .into_iter()
+            .find(PowerProfile::is_custom)
+            .ok_or_else(|| Error::InvalidArgument("No CUSTOM power profile".into()))?;
+        if params.is_empty() {
+            return Err(Error::InvalidArgument(
+                "Custom profile needs at least one parameter".into(),
+            ));
+        }
+        self.ensure_manual()?;
+        let mut command = custom.index.to_string();
+        for param in params {
+            command.push_str(&format!(" {}", param));
+        }
+        self.write("pp_power_profile_mode", &command)
+    }
+
+    /// Current overdrive table
+    pub fn od_table(&self) -> Result<OdTable, Error> {
+        Ok(OdTable::parse(&self.read("pp_od_clk_voltage")?))
+    }
+
+    /// Set the minimum and maximum GPU (sclk) clock
+    pub fn set_sclk_range(&self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        let commands = self.od_table()?.sclk_commands(min_mhz, max_mhz)?;
+        self.od_commit(&commands)
+    }
+
+    /// Set the minimum and maximum memory (mclk) clock
+    pub fn set_mclk_range(&self, min_mhz: u32, max_mhz: u32) -> Result<(), Error> {
+        let commands = self.od_table()?.mclk_commands(min_mhz, max_mhz)?;
+        self.od_commit(&commands)
+    }
+
+    /// Set the GFX voltage offset (mV, usually negative for undervolting)
+    pub fn set_voltage_offset(&self, offset_mv: i32) -> Result<(), Error> {
+        let command = self.od_table()?.voltage_offset_command(offset_mv)?;
+        self.od_commit(&[command])
+    }
+
+    /// Reset the overdrive table and return clocks to driver control
+    pub fn reset_clocks(&self) -> Result<(), Error> {
+        if self.device_path.join("pp_od_clk_voltage").exists() {
+            self.ensure_manual()?;
+            self.write("pp_od_clk_voltage", "r")?;
+            self.write("pp_od_clk_voltage", "c")?;
+        }
+        self.set_performance_level(PerformanceLevel::Auto)
+    }
+
+    /// Current board power cap
+    pub fn power_cap(&self) -> Result<PowerCap, Error> {
+        let hwmon = self.hwmon()?;
+        let watts = |name: &str| {
+            fs::read_to_string(hwmon.join(name))
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .map(|uw| uw as f32 / 1_000_000.0)
+        };
+        Ok(PowerCap {
+            current: watts("power1_cap").ok_or(Error::NotSupported)?,
+            min: watts("power1_cap_min"),
+            max: watts("power1_cap_max"),
+            default: watts("power1_cap_default"),
+        })
+    }
+
+    /// Set the board power cap, validated against `power1_cap_{min,max}`
+    pub fn set_power_cap(&self, watts: f32) -> Result<(), Error> {
+        let cap = self.power_cap()?;
+        let below = cap.min.is_some_and(|min| watts < min);
+        let above = cap.max.is_some_and(|max| watts > max);
+        if !watts.is_finite() || watts <= 0.0 || below || above {
+            return Err(Error::InvalidArgument(format!(
+                "Power cap {:.1} W outside {:.1}..={:.1} W",
+                watts,
+                cap.min.unwrap_or(0.0),
+                cap.max.unwrap_or(f32::INFINITY)
+            )));
+        }
+        let microwatts = (watts as f64 * 1_000_000.0).round() as u64;
+        write_file(&self.hwmon()?.join("power1_cap"), &microwatts.to_string())
+    }
+
+    /// Restore driver defaults: overdrive table, `auto` performance level,
+    /// the boot-up power profile and the default power cap
+    pub fn restore_defaults(&self) -> Result<(), Error> {
+        if self.device_path.join("pp_power_profile_mode").exists() {
+            if let Some(default) = self.power_profiles()?.first() {
+                self.write("pp_power_profile_mode", &default.index.to_string())?;
+            }
+        }
+        self.reset_clocks()?;
+        if let Ok(PowerCap {
+            default: Some(default),
+            ..
+        }) = self.power_cap()
+        {
+            self.set_power_cap(default)?;
+        }
+        Ok(())
+    }
+
+    /// Write overdrive commands followed by a commit
+    fn od_commit(&self, commands: &[String]) -> Result<(), Error> {
+        self.ensure_manual()?;
+        for command in commands {
+            self.write("pp_od_clk_voltage", command)?;
+        }
+        self.write("pp_od_clk_voltage", "c")
+    }
+
+    fn ensure_manual(&self) -> Result<(), Error> {
+        if self.performance_level()? != PerformanceLevel::Manual {
+            self.set_performance_level(PerformanceLevel::Manual)?;
+        }
+        Ok(())
+    }
+
+    fn hwmon(&self) -> Result<PathBuf, Error> {
+        fs::read_dir(self.device_path.join("hwmon"))
+            .map_err(|e| io_error("hwmon", e))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| {
+                p.file_name()
+                    .is_some_and(|n| n.to_string_lossy().starts_with("hwmon"))
+            })
+            .ok_or(Error::NotSupported)
+    }
+
+    fn read(&self, attr: &str) -> Result<String, Error> {
+        fs::read_to_string(self.device_path.join(attr)).map_err(|e| io_error(attr, e))
+    }
+
+    fn write(&self, attr: &str, value: &str) -> Result<(), Error> {
+        write_file(&self.device_path.join(attr), value)
+    }
+}
+
+/// Parse the contents of `pp_power_profile_mode`
+///
+/// Handles both the table layout of older ASICs
+/// (`  1 3D_FULL_SCREEN *:  0  100 ...`) and the per-clock layout of newer
+/// ones (` 1 3D_FULL_SCREEN*:` followed by indented clock rows).
+pub fn parse_power_profiles(content: &str) -> Vec<PowerProfile> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (head, _) = line.split_once(':')?;
+            let head = head.trim_start();
+            let (index, name) = head.split_once(char::is_whitespace)?;
+            let index = index.parse().ok()?;
+            let active = name.contains('*');
+            let name = name.replace('*', "").trim().to_string();
+            if name.is_empty() || name.contains('(') {
+                return None;
+            }
+            Some(PowerProfile {
+                index,
+                name,
+                active,
+            })
+        })
+        .collect()
+}
+
+fn check_range(
+    what: &str,
+    min_mhz: u32,
+    max_mhz: u32,
+    range: Option<(u32, u32)>,
+) -> Result<(), Error> {
+    let (lo, hi) =
+        range.ok_or_else(|| Error::InvalidArgument(format!("{} is not adjustable", what)))?;
+    if min_mhz > max_mhz || min_mhz < lo || max_mhz > hi {
+        return Err(Error::InvalidArgument(format!(
+            "{} range {}-{} MHz outside {}-{} MHz",
+            what, min_mhz, max_mhz, lo, hi
+        )));
+    }
+    Ok(())
+}
+
+/// `s|m <index> <MHz>` for each exposed point: the lowest index gets the
+/// minimum, the highest the maximum
+fn range_commands(
+    prefix: char,
+    points: &[(u32, u32)],
+    min_mhz: u32,
+    max_mhz: u32,
+) -> Result<Vec<String>, Error> {
+    let first = points
+        .iter()
+        .map(|p| p.0)
+        .min()
+        .ok_or(Error::NotSupported)?;
+    let last = points.iter().map(|p| p.0).max().unwrap_or(first);
+    let mut commands = Vec::new();
+    if first != last {
+        commands.push(format!("{} {} {}", prefix, first, min_mhz));
+    }
+    commands.push(format!("{} {} {}", prefix, last, max_mhz));
+    Ok(commands)
+}
+
+/// Parse a value with a unit suffix such as `500Mhz`, `-450mV` or `0mv`
+fn parse_unit(s: &str) -> Option<i64> {
+    let end = s
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+        .map_or(s.len(), |(i, _)| i);
+    s[..end].parse().ok()
+}
+
+fn write_file(path: &Path, value: &str) -> Result<(), Error> {
+    fs::write(path, format!("{}\n", value)).map_err(|e| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        io_error(&name, e)
+    })
+}
+
+fn io_error(attr: &str, e: io::Error) -> Error {
+    match e.kind() {
+        io::ErrorKind::NotFound => Error::NotSupported,
+        io::ErrorKind::PermissionDenied => {
+            Error::PermissionDenied(format!("{} requires root", attr))
+        }
+        _ => Error::ControlFailed(format!("{}: {}", attr, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const NAVI_PROFILES: &str = "\
+PROFILE_INDEX(NAME) CLOCK_TYPE(NAME) FPS MinActiveFreqType MinActiveFreq
+ 0 BOOTUP_DEFAULT :
+                    0(       GFXCLK)       0       5       1       0
+                    1(       SOCCLK)       0       5       1       0
+ 1 3D_FULL_SCREEN*:
+                    0(       GFXCLK)       1       5       1     650
+ 5 COMPUTE        :
+                    0(       GFXCLK)       0       5       1       0
+ 6 CUSTOM         :
+                    0(       GFXCLK)       0       5       1       0
+";
+
+    const NAVI_OD: &str = "\
+OD_SCLK:
+0: 500Mhz
+1: 2615Mhz
+OD_MCLK:
+1: 1000MHz
+OD_VDDGFX_OFFSET:
+0mV
+OD_RANGE:
+SCLK:     500Mhz       3150Mhz
+MCLK:     674Mhz       1200Mhz
+VDDGFX_OFFSET:    -450mv         0mv
+";
+
+    /// Fake amdgpu device directory
+    fn fake_device() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("hwmon/hwmon4")).unwrap();
+        fs::write(root.join("power_dpm_force_performance_level"), "auto\n").unwrap();
+        fs::write(root.join("pp_power_profile_mode"), NAVI_PROFILES).unwrap();
+        fs::write(root.join("pp_od_clk_voltage"), NAVI_OD).unwrap();
+        let hwmon = root.join("hwmon/hwmon4");
+        fs::write(hwmon.join("power1_cap"), "250000000\n").unwrap();
+        fs::write(hwmon.join("power1_cap_min"), "0\n").unwrap();
+        fs::write(hwmon.join("power1_cap_max"), "300000000\n").unwrap();
+        fs::write(hwmon.join("power1_cap_default"), "255000000\n").unwrap();
+        dir
+    }
+
+    fn read(root: &Path, attr: &str) -> String {
+        fs::read_to_string(root.join(attr))
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn test_parse_power_profiles() {
+        let profiles = parse_power_profiles(NAVI_PROFILES);
+        let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["BOOTUP_DEFAULT", "3D_FULL_SCREEN", "COMPUTE", "CUSTOM"]
+        );
+        assert!(profiles[1].active);
+        assert_eq!(profiles[2].index, 5);
+        assert!(profiles[3].is_custom());
+
+        // Older table layout
+        let polaris = "NUM        MODE_NAME     SCLK_UP_HYST   SCLK_DOWN_HYST\n  \
+                       0   BOOTUP_DEFAULT:        -             -\n  \
+                       1 3D_FULL_SCREEN *:        0           100\n";
+        let profiles = parse_power_profiles(polaris);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[1].name, "3D_FULL_SCREEN");
+        assert!(profiles[1].active);
+    }
+
+    #[test]
+    fn test_od_table() {
+        let od = OdTable::parse(NAVI_OD);
+        assert_eq!(od.sclk, vec![(0, 500), (1, 2615)]);
+        assert_eq!(od.mclk, vec![(1, 1000)]);
+        assert_eq!(od.voltage_offset, Some(0));
+        assert_eq!(od.sclk_range, Some((500, 3150)));
+        assert_eq!(od.mclk_range, Some((674, 1200)));
+        assert_eq!(od.voltage_offset_range, Some((-450, 0)));
+
+        assert_eq!(
+            od.sclk_commands(800, 2400).unwrap(),
+            ["s 0 800", "s 1 2400"]
+        );
+        assert_eq!(od.mclk_commands(674, 1100).unwrap(), ["m 1 1100"]);
+        assert_eq!(od.voltage_offset_command(-50).unwrap(), "vo -50");
+
+        // Outside the driver's ranges
+        assert!(matches!(
+            od.sclk_commands(400, 2400),
+            Err(Error::InvalidArgument(_))
+        ));
+        assert!(od.sclk_commands(2400, 800).is_err());
+        assert!(od.mclk_commands(700, 1300).is_err());
+        assert!(od.voltage_offset_command(50).is_err());
+    }
+
+    #[test]
+    fn test_profile_and_level_control() {
+        let dir = fake_device();
+        let root = dir.path();
+        let control = AmdGpuControl::new(root);
+        assert_eq!(control.performance_level().unwrap(), PerformanceLevel::Auto);
+        assert_eq!(
+            control.active_power_profile().unwrap().unwrap().name,
+            "3D_FULL_SCREEN"
+        );
+
+        control.set_power_profile("compute").unwrap();
+        assert_eq!(read(root, "pp_power_profile_mode"), "5");
+        assert_eq!(read(root, "power_dpm_force_performance_level"), "manual");
+        assert!(control.set_power_profile("TURBO").is_err());
+
+        fs::write(root.join("pp_power_profile_mode"), NAVI_PROFILES).unwrap();
+        control.set_custom_power_profile(&[0, 5, 1, 0]).unwrap();
+        assert_eq!(read(root, "pp_power_profile_mode"), "6 0 5 1 0");
+    }
+
+    #[test]
+    fn test_power_cap_and_restore() {
+        let dir = fake_device();
+        let root = dir.path();
+        let control = AmdGpuControl::new(root);
+        let cap = control.power_cap().unwrap();
+        assert_eq!(cap.current, 250.0);
+        assert_eq!(cap.max, Some(300.0));
+        assert_eq!(cap.default, Some(255.0));
+
+        control.set_power_cap(200.0).unwrap();
+        assert_eq!(read(root, "hwmon/hwmon4/power1_cap"), "200000000");
+        assert!(matches!(
+            control.set_power_cap(350.0),
+            Err(Error::InvalidArgument(_))
+        ));
+
+        control.set_sclk_range(800, 2400).unwrap();
+        assert_eq!(read(root, "pp_od_clk_voltage"), "c");
+
+        control.restore_defaults().unwrap();
+        assert_eq!(read(root, "pp_power_profile_mode"), "0");
+        assert_eq!(read(root, "power_dpm_force_performance_level"), "auto");
+        assert_eq!(read(root, "hwmon/hwmon4/power1_cap"), "255000000");
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let dir = fake_device();
+        let root = dir.path();
+        fs::remove_file(root.join("pp_od_clk_voltage")).unwrap();
+        fs::remove_dir_all(root.join("hwmon")).unwrap();
+        let control = AmdGpuControl::new(root);
+        assert!(matches!(control.od_table(), Err(Error::NotSupported)));
+        assert!(matches!(control.power_cap(), Err(Error::NotSupported)));
+        // Reset still returns the level to auto without an OD table
+        control.reset_clocks().unwrap();
+        assert_eq!(read(root, "power_dpm_force_performance_level"), "auto");
+    }
+}
@@ -0,0 +1,1194 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! amdgpu `gpu_metrics` table parser
+//!
+//! The amdgpu driver exposes `/sys/class/drm/cardN/device/gpu_metrics`, a
+//! binary snapshot of the SMU metrics table. One read returns temperatures,
+//! activity, power, clocks, throttle status and link state that would
+//! otherwise take a dozen sysfs and hwmon reads.
+//!
+//! The table starts with a 4-byte header (`structure_size`,
+//! `format_revision`, `content_revision`) followed by a revision-specific
+//! C struct (`struct gpu_metrics_vX_Y` in the kernel's
+//! `kgd_pp_interface.h`):
+//!
+//! | Format | Used by | Revisions |
+//! |--------|---------|-----------|
+//! | v1.x   | discrete GPUs (Navi, Arcturus, Aldebaran, MI300) | 1.0 - 1.6 |
+//! | v2.x   | APUs (Renoir, Van Gogh, Yellow Carp, Rembrandt) | 2.0 - 2.4 |
+//! | v3.x   | APUs (Strix Point and newer) | 3.0 |
+//!
+//! Fields the firmware doesn't populate are reported as all-ones; they are
+//! returned as `None` (or dropped from arrays). Units are normalized:
+//! temperatures in °C, power in watts, clocks in MHz.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::gpu::amdgpu_metrics::GpuMetrics;
+//! use std::path::Path;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let metrics = GpuMetrics::read(Path::new("/sys/class/drm/card0/device"))?;
+//! println!("gpu_metrics v{}.{}", metrics.format_revision, metrics.content_revision);
+//! if let Some(hotspot) = metrics.temperature_hotspot {
+//!     println!("Hotspot: {:.0}°C", hotspot);
+//! }
+//! if let Some(power) = metrics.socket_power {
+//!     println!("Socket power: {:.1} W", power);
+//! }
+//! println!("GFX clocks: {:?} MHz", metrics.current_gfxclk);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::throttling::{amdgpu_throttle_causes, GpuThrottleStatus, ThrottleCause};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Size of `struct metrics_table_header`
+const HEADER_SIZE: usize = 4;
+
+/// VCN instances in v1.4+ tables
+const NUM_VCN: usize = 4;
+/// JPEG engines in v1.5+ tables
+const NUM_JPEG_ENG: usize = 32;
+/// XGMI links in v1.4+ tables
+const NUM_XGMI_LINKS: usize = 8;
+/// Per-XCD graphics clocks in v1.4+ tables
+const MAX_GFX_CLKS: usize = 8;
+/// Per-instance SoC/video clocks in v1.4+ tables
+const MAX_CLKS: usize = 4;
+/// Partitions in v1.6 tables
+const NUM_XCP: usize = 8;
+/// XCCs per partition in v1.6 tables
+const MAX_XCC: usize = 8;
+/// HBM stacks in v1.1 - v1.3 tables
+const NUM_HBM_INSTANCES: usize = 4;
+
+/// Energy accumulator unit (15.259 µJ, 2^-16 J)
+const ENERGY_UNIT_JOULES: f64 = 1.0 / 65536.0;
+
+/// Decoded `gpu_metrics` table
+///
+/// Which fields are populated depends on the table revision and the ASIC.
+/// Per-instance arrays keep one entry per slot in the table, with `None`
+/// for instances the SMU leaves unsupported, so an index stays the
+/// instance number.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuMetrics {
+    /// Table format revision (1 = dGPU, 2/3 = APU)
+    pub format_revision: u8,
+    /// Table content revision
+    pub content_revision: u8,
+    /// Size of the table reported by the driver (bytes)
+    pub structure_size: u16,
+
+    // Temperatures (°C)
+    /// Edge temperature
+    pub temperature_edge: Option<f32>,
+    /// Junction (hotspot) temperature
+    pub temperature_hotspot: Option<f32>,
+    /// Memory temperature
+    pub temperature_mem: Option<f32>,
+    /// GFX voltage regulator temperature
+    pub temperature_vrgfx: Option<f32>,
+    /// SoC voltage regulator temperature
+    pub temperature_vrsoc: Option<f32>,
+    /// Memory voltage regulator temperature
+    pub temperature_vrmem: Option<f32>,
+    /// Per-stack HBM temperatures
+    pub temperature_hbm: Vec<Option<f32>>,
+    /// GFX temperature (APU)
+    pub temperature_gfx: Option<f32>,
+    /// SoC temperature (APU)
+    pub temperature_soc: Option<f32>,
+    /// Per-core CPU temperatures (APU)
+    pub temperature_core: Vec<Option<f32>>,
+    /// Per-complex L3 temperatures (APU)
+    pub temperature_l3: Vec<Option<f32>>,
+    /// Skin temperature (APU)
+    pub temperature_skin: Option<f32>,
+
+    // Activity (%)
+    /// Average GFX activity
+    pub gfx_activity: Option<u16>,
+    /// Average memory controller activity
+    pub umc_activity: Option<u16>,
+    /// Average multimedia (UVD/VCN) activity
+    pub mm_activity: Option<u16>,
+    /// Per-instance VCN activity
+    pub vcn_activity: Vec<Option<u16>>,
+    /// Per-engine JPEG activity
+    pub jpeg_activity: Vec<Option<u16>>,
+    /// Per-XCC GFX activity (partitioned GPUs)
+    pub xcc_activity: Vec<Option<u32>>,
+    /// Per-column IPU (NPU) activity
+    pub ipu_activity: Vec<Option<u16>>,
+    /// Per-core C0 residency (APU)
+    pub core_activity: Vec<Option<u16>>,
+    /// Accumulated GFX activity
+    pub gfx_activity_acc: Option<u32>,
+    /// Accumulated memory activity
+    pub mem_activity_acc: Option<u32>,
+
+    // Power (W)
+    /// Socket power (dGPU board or APU package)
+    pub socket_power: Option<f32>,
+    /// CPU power (APU)
+    pub cpu_power: Option<f32>,
+    /// SoC power (APU)
+    pub soc_power: Option<f32>,
+    /// GFX power (APU)
+    pub gfx_power: Option<f32>,
+    /// IPU (NPU) power (APU)
+    pub ipu_power: Option<f32>,
+    /// Per-core CPU power (APU)
+    pub core_power: Vec<Option<f32>>,
+    /// STAPM power limit (APU)
+    pub stapm_power_limit: Option<f32>,
+    /// Raw energy accumulator (15.259 µJ units)
+    pub energy_accumulator: Option<u64>,
+
+    // Average clocks (MHz)
+    /// Average GFX clock
+    pub average_gfxclk: Option<u16>,
+    /// Average SoC clock
+    pub average_socclk: Option<u16>,
+    /// Average memory clock
+    pub average_uclk: Option<u16>,
+    /// Average fabric clock
+    pub average_fclk: Option<u16>,
+    /// Average video clock (VCLK0)
+    pub average_vclk0: Option<u16>,
+    /// Average decode clock (DCLK0)
+    pub average_dclk0: Option<u16>,
+
+    // Current clocks (MHz)
+    /// Current GFX clock, one entry per XCD on partitioned GPUs
+    pub current_gfxclk: Vec<Option<u16>>,
+    /// Current SoC clock(s)
+    pub current_socclk: Vec<Option<u16>>,
+    /// Current memory clock
+    pub current_uclk: Option<u16>,
+    /// Current fabric clock
+    pub current_fclk: Option<u16>,
+    /// Current video clock(s)
+    pub current_vclk: Vec<Option<u16>>,
+    /// Current decode clock(s)
+    pub current_dclk: Vec<Option<u16>>,
+    /// Current per-core CPU clocks (APU)
+    pub current_coreclk: Vec<Option<u16>>,
+
+    // Throttling
+    /// ASIC-specific throttler bits
+    pub throttle_status: Option<u32>,
+    /// ASIC-independent throttler bits (`SMU_THROTTLER_*_BIT`)
+    pub indep_throttle_status: Option<u64>,
+    /// Accumulated throttler residencies by limiter name
+    pub throttle_residency: Vec<(String, u32)>,
+
+    // Fans, links and voltages
+    /// Fan speed (RPM)
+    pub fan_speed: Option<u16>,
+    /// Fan PWM (APU)
+    pub fan_pwm: Option<u16>,
+    /// PCIe link width (lanes)
+    pub pcie_link_width: Option<u16>,
+    /// PCIe link speed (0.1 GT/s)
+    pub pcie_link_speed: Option<u16>,
+    /// XGMI link width
+    pub xgmi_link_width: Option<u16>,
+    /// XGMI link speed (Gbps)
+    pub xgmi_link_speed: Option<u16>,
+    /// SoC voltage (mV)
+    pub voltage_soc: Option<u16>,
+    /// GFX voltage (mV)
+    pub voltage_gfx: Option<u16>,
+    /// Memory voltage (mV)
+    pub voltage_mem: Option<u16>,
+
+    // Timestamps
+    /// Driver timestamp (ns)
+    pub system_clock_counter: Option<u64>,
+    /// Firmware timestamp (10 ns units)
+    pub firmware_timestamp: Option<u64>,
+}
+
+impl GpuMetrics {
+    /// Read and parse `gpu_metrics` from a PCI device directory
+    pub fn read(device_path: &Path) -> Result<Self> {
+        Self::parse(&std::fs::read(device_path.join("gpu_metrics"))?)
+    }
+
+    /// Parse a raw `gpu_metrics` blob
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE {
+            return Err(SimonError::Parse(format!(
+                "gpu_metrics: {} bytes is shorter than the header",
+                data.len()
+            )));
+        }
+        let structure_size = u16::from_le_bytes([data[0], data[1]]);
+        let (format, content) = (data[2], data[3]);
+
+        let layout = layout(format, content).ok_or_else(|| {
+            SimonError::Parse(format!(
+                "gpu_metrics: unsupported revision v{}.{}",
+                format, content
+            ))
+        })?;
+        let table = Table::new(data, &layout);
+        if data.len() < table.size {
+            return Err(SimonError::Parse(format!(
+                "gpu_metrics v{}.{}: {} bytes, expected at least {}",
+                format,
+                content,
+                data.len(),
+                table.size
+            )));
+        }
+
+        let mut m = GpuMetrics {
+            format_revision: format,
+            content_revision: content,
+            structure_size,
+            ..Default::default()
+        };
+
+        // APU tables report temperatures in centi-degrees and power in mW
+        let (temp_scale, power_scale) = if format == 1 {
+            (1.0, 1.0)
+        } else {
+            (100.0, 1000.0)
+        };
+        let temp = |name| table.u16(name).map(|v| v as f32 / temp_scale);
+        let temps = |name| {
+            table
+                .u16s(name)
+                .into_iter()
+                .map(|v| v.map(|v| v as f32 / temp_scale))
+                .collect()
+        };
+        let power = |name| table.u32_or_u16(name).map(|v| v as f32 / power_scale);
+
+        m.temperature_edge = temp("temperature_edge");
+        m.temperature_hotspot = temp("temperature_hotspot");
+        m.temperature_mem = temp("temperature_mem");
+        m.temperature_vrgfx = temp("temperature_vrgfx");
+        m.temperature_vrsoc = temp("temperature_vrsoc");
+        m.temperature_vrmem = temp("temperature_vrmem");
+        m.temperature_hbm = temps("temperature_hbm");
+        m.temperature_gfx = temp("temperature_gfx");
+        m.temperature_soc = temp("temperature_soc");
+        m.temperature_core = temps("temperature_core");
+        m.temperature_l3 = temps("temperature_l3");
+        m.temperature_skin = temp("temperature_skin");
+
+        m.gfx_activity = table.u16("average_gfx_activity");
+        m.umc_activity = table.u16("average_umc_activity");
+        m.mm_activity = table
+            .u16("average_mm_activity")
+            .or_else(|| table.u16("average_vcn_activity"));
+        m.vcn_activity = table.u16s("vcn_activity");
+        m.jpeg_activity = table.u16s("jpeg_activity");
+        m.xcc_activity = table.u32s("xcp_gfx_busy_inst");
+        m.ipu_activity = table.u16s("average_ipu_activity");
+        m.core_activity = table.u16s("average_core_c0_activity");
+        m.gfx_activity_acc = table.u32("gfx_activity_acc");
+        m.mem_activity_acc = table.u32("mem_activity_acc");
+        if m.vcn_activity.is_empty() {
+            m.vcn_activity = table.u16s("xcp_vcn_busy");
+        }
+        if m.jpeg_activity.is_empty() {
+            m.jpeg_activity = table.u16s("xcp_jpeg_busy");
+        }
+
+        m.socket_power = power("average_socket_power").or_else(|| power("curr_socket_power"));
+        m.cpu_power = power("average_cpu_power").or_else(|| power("average_all_core_power"));
+        m.soc_power = power("average_soc_power");
+        m.gfx_power = power("average_gfx_power");
+        m.ipu_power = power("average_ipu_power");
+        m.core_power = table
+            .u16s("average_core_power")
+            .into_iter()
+            .map(|v| v.map(|v| v as f32 / power_scale))
+            .collect();
+        m.stapm_power_limit = power("stapm_power_limit");
+        m.energy_accumulator = table.u64("energy_accumulator");
+
+        m.average_gfxclk = table.u16("average_gfxclk_frequency");
+        m.average_socclk = table.u16("average_socclk_frequency");
+        m.average_uclk = table.u16("average_uclk_frequency");
+        m.average_fclk = table.u16("average_fclk_frequency");
+        m.average_vclk0 = table
+            .u16("average_vclk0_frequency")
+            .or_else(|| table.u16("average_vclk_frequency"));
+        m.average_dclk0 = table
+            .u16("average_dclk0_frequency")
+            .or_else(|| table.u16("average_dclk_frequency"));
+
+        m.current_gfxclk = table.u16s("current_gfxclk");
+        m.current_socclk = table.u16s("current_socclk");
+        m.current_uclk = table.u16("current_uclk");
+        m.current_fclk = table.u16("current_fclk");
+        m.current_vclk = [
+            table.u16s("current_vclk0"),
+            table.u16s("current_vclk1"),
+            table.u16s("current_vclk"),
+        ]
+        .concat();
+        m.current_dclk = [
+            table.u16s("current_dclk0"),
+            table.u16s("current_dclk1"),
+            table.u16s("current_dclk"),
+        ]
+        .concat();
+        m.current_coreclk = table.u16s("current_coreclk");
+
+        m.throttle_status = table.u32("throttle_status");
+        m.indep_throttle_status = table.u64("indep_throttle_status");
+        m.throttle_residency = RESIDENCY_FIELDS
+            .iter()
+            .filter_map(|&(field, name)| Some((name.to_string(), table.u32(field)?)))
+            .collect();
+
+        m.fan_speed = table.u16("current_fan_speed");
+        m.fan_pwm = table.u16("fan_pwm");
+        m.pcie_link_width = table.u16("pcie_link_width");
+        m.pcie_link_speed = table.u16("pcie_link_speed");
+        m.xgmi_link_width = table.u16("xgmi_link_width");
+        m.xgmi_link_speed = table.u16("xgmi_link_speed");
+        m.voltage_soc = table.u16("voltage_soc");
+        m.voltage_gfx = table.u16("voltage_gfx");
+        m.voltage_mem = table.u16("voltage_mem");
+
+        m.system_clock_counter = table.u64("system_clock_counter");
+        m.firmware_timestamp = table.u64("firmware_timestamp");
+
+        Ok(m)
+    }
+
+    /// Whether this is an APU table (v2.x / v3.x)
+    pub fn is_apu(&self) -> bool {
+        self.format_revision >= 2
+    }
+
+    /// Energy accumulator in joules
+    pub fn energy_joules(&self) -> Option<f64> {
+        self.energy_accumulator
+            .map(|e| e as f64 * ENERGY_UNIT_JOULES)
+    }
+
+    /// PCIe link speed in GT/s
+    pub fn pcie_link_speed_gts(&self) -> Option<f32> {
+        self.pcie_link_speed.map(|s| s as f32 / 10.0)
+    }
+
+    /// PCIe generation matching the current link speed
+    pub fn pcie_gen(&self) -> Option<u8> {
+        Some(match self.pcie_link_speed? {
+            0..=25 => 1,
+            26..=50 => 2,
+            51..=80 => 3,
+            81..=160 => 4,
+            161..=320 => 5,
+            _ => 6,
+        })
+    }
+
+    /// Highest current GFX clock across XCDs
+    pub fn gfxclk(&self) -> Option<u16> {
+        self.current_gfxclk.iter().flatten().copied().max()
+    }
+
+    /// Hottest reported GPU temperature (hotspot, edge or APU GFX)
+    pub fn primary_temperature(&self) -> Option<f32> {
+        self.temperature_hotspot
+            .or(self.temperature_edge)
+            .or(self.temperature_gfx)
+    }
+
+    /// Active throttle causes
+    ///
+    /// Decoded from the ASIC-independent status when the table has one.
+    /// Older tables only carry ASIC-specific bits, reported as
+    /// [`ThrottleCause::Other`]. Residency-only tables (v1.6, v3.0) can't
+    /// tell what is active from a single sample and report nothing.
+    pub fn throttle(&self) -> GpuThrottleStatus {
+        let active = match (self.indep_throttle_status, self.throttle_status) {
+            (Some(bits), _) => amdgpu_throttle_causes(bits),
+            (None, Some(bits)) if bits != 0 => vec![ThrottleCause::Other],
+            _ => Vec::new(),
+        };
+        GpuThrottleStatus {
+            active,
+            violation_time: Vec::new(),
+        }
+    }
+}
+
+/// Residency counters in v1.6 and v3.0 tables, with display names
+const RESIDENCY_FIELDS: &[(&str, &str)] = &[
+    ("prochot_residency_acc", "prochot"),
+    ("ppt_residency_acc", "ppt"),
+    ("socket_thm_residency_acc", "socket thermal"),
+    ("vr_thm_residency_acc", "vr thermal"),
+    ("hbm_thm_residency_acc", "hbm thermal"),
+    ("throttle_residency_prochot", "prochot"),
+    ("throttle_residency_spl", "spl"),
+    ("throttle_residency_fppt", "fppt"),
+    ("throttle_residency_sppt", "sppt"),
+    ("throttle_residency_thm_core", "core thermal"),
+    ("throttle_residency_thm_gfx", "gfx thermal"),
+    ("throttle_residency_thm_soc", "soc thermal"),
+];
+
+/// C field types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ty {
+    U16,
+    U32,
+    U64,
+    /// Start of a nested struct aligned to 8 bytes
+    Align8,
+}
+
+impl Ty {
+    fn size(self) -> usize {
+        match self {
+            Ty::U16 => 2,
+            Ty::U32 => 4,
+            Ty::U64 => 8,
+            Ty::Align8 => 0,
+        }
+    }
+
+    fn align(self) -> usize {
+        match self {
+            Ty::Align8 => 8,
+            ty => ty.size(),
+        }
+    }
+}
+
+/// A field after the header: name, type, array length
+type Field = (&'static str, Ty, usize);
+
+use Ty::{Align8, U16, U32, U64};
+
+/// Average and current clocks shared by v1.0 - v1.3
+const V1_CLOCKS: &[Field] = &[
+    ("average_gfxclk_frequency", U16, 1),
+    ("average_socclk_frequency", U16, 1),
+    ("average_uclk_frequency", U16, 1),
+    ("average_vclk0_frequency", U16, 1),
+    ("average_dclk0_frequency", U16, 1),
+    ("average_vclk1_frequency", U16, 1),
+    ("average_dclk1_frequency", U16, 1),
+    ("current_gfxclk", U16, 1),
+    ("current_socclk", U16, 1),
+    ("current_uclk", U16, 1),
+    ("current_vclk0", U16, 1),
+    ("current_dclk0", U16, 1),
+    ("current_vclk1", U16, 1),
+    ("current_dclk1", U16, 1),
+];
+
+/// Temperatures and activity shared by v1.0 - v1.3
+const V1_TEMPS_ACTIVITY: &[Field] = &[
+    ("temperature_edge", U16, 1),
+    ("temperature_hotspot", U16, 1),
+    ("temperature_mem", U16, 1),
+    ("temperature_vrgfx", U16, 1),
+    ("temperature_vrsoc", U16, 1),
+    ("temperature_vrmem", U16, 1),
+    ("average_gfx_activity", U16, 1),
+    ("average_umc_activity", U16, 1),
+    ("average_mm_activity", U16, 1),
+    ("average_socket_power", U16, 1),
+];
+
+/// Link and PCIe error accumulators shared by v1.4 - v1.6
+const V1_4_LINKS: &[Field] = &[
+    ("pcie_link_width", U16, 1),
+    ("pcie_link_speed", U16, 1),
+    ("xgmi_link_width", U16, 1),
+    ("xgmi_link_speed", U16, 1),
+    ("gfx_activity_acc", U32, 1),
+    ("mem_activity_acc", U32, 1),
+    ("pcie_bandwidth_acc", U64, 1),
+    ("pcie_bandwidth_inst", U64, 1),
+    ("pcie_l0_to_recov_count_acc", U64, 1),
+    ("pcie_replay_count_acc", U64, 1),
+    ("pcie_replay_rover_count_acc", U64, 1),
+];
+
+/// Per-instance clocks shared by v1.4 - v1.6
+const V1_4_CLOCKS: &[Field] = &[
+    ("current_gfxclk", U16, MAX_GFX_CLKS),
+    ("current_socclk", U16, MAX_CLKS),
+    ("current_vclk0", U16, MAX_CLKS),
+    ("current_dclk0", U16, MAX_CLKS),
+    ("current_uclk", U16, 1),
+];
+
+/// APU temperatures through current clocks, shared by v2.0 - v2.4
+const V2_BASE: &[Field] = &[
+    ("system_clock_counter", U64, 1),
+    ("temperature_gfx", U16, 1),
+    ("temperature_soc", U16, 1),
+    ("temperature_core", U16, 8),
+    ("temperature_l3", U16, 2),
+    ("average_gfx_activity", U16, 1),
+    ("average_mm_activity", U16, 1),
+    ("average_socket_power", U16, 1),
+    ("average_cpu_power", U16, 1),
+    ("average_soc_power", U16, 1),
+    ("average_gfx_power", U16, 1),
+    ("average_core_power", U16, 8),
+    ("average_gfxclk_frequency", U16, 1),
+    ("average_socclk_frequency", U16, 1),
+    ("average_uclk_frequency", U16, 1),
+    ("average_fclk_frequency", U16, 1),
+    ("average_vclk_frequency", U16, 1),
+    ("average_dclk_frequency", U16, 1),
+    ("current_gfxclk", U16, 1),
+    ("current_socclk", U16, 1),
+    ("current_uclk", U16, 1),
+    ("current_fclk", U16, 1),
+    ("current_vclk", U16, 1),
+    ("current_dclk", U16, 1),
+    ("current_coreclk", U16, 8),
+    ("current_l3clk", U16, 2),
+    ("throttle_status", U32, 1),
+    ("fan_pwm", U16, 1),
+];
+
+/// Field list for a table revision
+fn layout(format: u8, content: u8) -> Option<Vec<Field>> {
+    let mut f: Vec<Field> = Vec::new();
+    match (format, content) {
+        (1, 0) => {
+            f.push(("system_clock_counter", U64, 1));
+            f.extend_from_slice(V1_TEMPS_ACTIVITY);
+            f.push(("energy_accumulator", U32, 1));
+            f.extend_from_slice(V1_CLOCKS);
+            f.push(("throttle_status", U32, 1));
+            f.push(("current_fan_speed", U16, 1));
+            // u8 link width and speed, not decoded
+            f.push(("pcie_link", U16, 1));
+        }
+        (1, 1..=3) => {
+            f.extend_from_slice(V1_TEMPS_ACTIVITY);
+            f.push(("energy_accumulator", U64, 1));
+            f.push(("system_clock_counter", U64, 1));
+            f.extend_from_slice(V1_CLOCKS);
+            f.extend_from_slice(&[
+                ("throttle_status", U32, 1),
+                ("current_fan_speed", U16, 1),
+                ("pcie_link_width", U16, 1),
+                ("pcie_link_speed", U16, 1),
+                ("padding", U16, 1),
+                ("gfx_activity_acc", U32, 1),
+                ("mem_activity_acc", U32, 1),
+                ("temperature_hbm", U16, NUM_HBM_INSTANCES),
+            ]);
+            if content >= 2 {
+                f.push(("firmware_timestamp", U64, 1));
+            }
+            if content >= 3 {
+                f.extend_from_slice(&[
+                    ("voltage_soc", U16, 1),
+                    ("voltage_gfx", U16, 1),
+                    ("voltage_mem", U16, 1),
+                    ("padding1", U16, 1),
+                    ("indep_throttle_status", U64, 1),
+                ]);
+            }
+        }
+        (1, 4..=5) => {
+            f.extend_from_slice(&[
+                ("temperature_hotspot", U16, 1),
+                ("temperature_mem", U16, 1),
+                ("temperature_vrsoc", U16, 1),
+                ("curr_socket_power", U16, 1),
+                ("average_gfx_activity", U16, 1),
+                ("average_umc_activity", U16, 1),
+                ("vcn_activity", U16, NUM_VCN),
+            ]);
+            if content >= 5 {
+                f.push(("jpeg_activity", U16, NUM_JPEG_ENG));
+            }
+            f.extend_from_slice(&[
+                ("energy_accumulator", U64, 1),
+                ("system_clock_counter", U64, 1),
+                ("throttle_status", U32, 1),
+                ("gfxclk_lock_status", U32, 1),
+            ]);
+            f.extend_from_slice(V1_4_LINKS);
+            if content >= 5 {
+                f.push(("pcie_nak_sent_count_acc", U32, 1));
+                f.push(("pcie_nak_rcvd_count_acc", U32, 1));
+            }
+            f.extend_from_slice(&[
+                ("xgmi_read_data_acc", U64, NUM_XGMI_LINKS),
+                ("xgmi_write_data_acc", U64, NUM_XGMI_LINKS),
+                ("firmware_timestamp", U64, 1),
+            ]);
+            f.extend_from_slice(V1_4_CLOCKS);
+            f.push(("padding", U16, 1));
+        }
+        (1, 6) => {
+            f.extend_from_slice(&[
+                ("temperature_hotspot", U16, 1),
+                ("temperature_mem", U16, 1),
+                ("temperature_vrsoc", U16, 1),
+                ("curr_socket_power", U16, 1),
+                ("average_gfx_activity", U16, 1),
+                ("average_umc_activity", U16, 1),
+                ("energy_accumulator", U64, 1),
+                ("system_clock_counter", U64, 1),
+                ("accumulation_counter", U32, 1),
+                ("prochot_residency_acc", U32, 1),
+                ("ppt_residency_acc", U32, 1),
+                ("socket_thm_residency_acc", U32, 1),
+                ("vr_thm_residency_acc", U32, 1),
+                ("hbm_thm_residency_acc", U32, 1),
+                ("gfxclk_lock_status", U32, 1),
+            ]);
+            f.extend_from_slice(V1_4_LINKS);
+            f.extend_from_slice(&[
+                ("pcie_nak_sent_count_acc", U32, 1),
+                ("pcie_nak_rcvd_count_acc", U32, 1),
+                ("xgmi_read_data_acc", U64, NUM_XGMI_LINKS),
+                ("xgmi_write_data_acc", U64, NUM_XGMI_LINKS),
+                ("firmware_timestamp", U64, 1),
+            ]);
+            f.extend_from_slice(V1_4_CLOCKS);
+            f.push(("num_partition", U16, 1));
+            // struct amdgpu_xcp_metrics xcp_stats[NUM_XCP]
+            for _ in 0..NUM_XCP {
+                f.extend_from_slice(&[
+                    ("xcp", Align8, 1),
+                    ("xcp_gfx_busy_inst", U32, MAX_XCC),
+                    ("xcp_jpeg_busy", U16, NUM_JPEG_ENG),
+                    ("xcp_vcn_busy", U16, NUM_VCN),
+                    ("xcp_gfx_busy_acc", U64, MAX_XCC),
+                ]);
+            }
+            f.push(("pcie_lc_perf_other_end_recovery", U32, 1));
+        }
+        (2, 0..=4) => {
+            f.extend_from_slice(V2_BASE);
+            // v2.0 has a single padding word, v2.1+ three
+            f.push(("padding", U16, if content == 0 { 1 } else { 3 }));
+            if content >= 2 {
+                f.push(("indep_throttle_status", U64, 1));
+            }
+            if content >= 3 {
+                f.extend_from_slice(&[
+                    ("average_temperature_gfx", U16, 1),
+                    ("average_temperature_soc", U16, 1),
+                    ("average_temperature_core", U16, 8),
+                    ("average_temperature_l3", U16, 2),
+                ]);
+            }
+            if content >= 4 {
+                f.extend_from_slice(&[
+                    ("average_cpu_voltage", U16, 1),
+                    ("average_soc_voltage", U16, 1),
+                    ("average_gfx_voltage", U16, 1),
+                    ("average_cpu_current", U16, 1),
+                    ("average_soc_current", U16, 1),
+                    ("average_gfx_current", U16, 1),
+                ]);
+            }
+        }
+        (3, 0) => {
+            f.extend_from_slice(&[
+                ("temperature_gfx", U16, 1),
+                ("temperature_soc", U16, 1),
+                ("temperature_core", U16, 16),
+                ("temperature_skin", U16, 1),
+                ("average_gfx_activity", U16, 1),
+                ("average_vcn_activity", U16, 1),
+                ("average_ipu_activity", U16, 8),
+                ("average_core_c0_activity", U16, 16),
+                ("average_dram_reads", U16, 1),
+                ("average_dram_writes", U16, 1),
+                ("average_ipu_reads", U16, 1),
+                ("average_ipu_writes", U16, 1),
+                ("system_clock_counter", U64, 1),
+                ("average_socket_power", U32, 1),
+                ("average_ipu_power", U16, 1),
+                ("average_apu_power", U32, 1),
+                ("average_gfx_power", U32, 1),
+                ("average_dgpu_power", U32, 1),
+                ("average_all_core_power", U32, 1),
+                ("average_core_power", U16, 16),
+                ("average_sys_power", U16, 1),
+                ("stapm_power_limit", U16, 1),
+                ("current_stapm_power_limit", U16, 1),
+                ("average_gfxclk_frequency", U16, 1),
+                ("average_socclk_frequency", U16, 1),
+                ("average_vpeclk_frequency", U16, 1),
+                ("average_ipuclk_frequency", U16, 1),
+                ("average_fclk_frequency", U16, 1),
+                ("average_vclk_frequency", U16, 1),
+                ("average_uclk_frequency", U16, 1),
+                ("average_mpipu_frequency", U16, 1),
+                ("current_coreclk", U16, 16),
+                ("current_core_maxfreq", U16, 1),
+                ("current_gfx_maxfreq", U16, 1),
+                ("throttle_residency_prochot", U32, 1),
+                ("throttle_residency_spl", U32, 1),
+                ("throttle_residency_fppt", U32, 1),
+                ("throttle_residency_sppt", U32, 1),
+                ("throttle_residency_thm_core", U32, 1),
+                ("throttle_residency_thm_gfx", U32, 1),
+                ("throttle_residency_thm_soc", U32, 1),
+                ("time_filter_alphavalue", U32, 1),
+            ]);
+        }
+        _ => return None,
+    }
+    Some(f)
+}
+
+/// A located field: offset, type, array length
+struct Slot {
+    name: &'static str,
+    offset: usize,
+    ty: Ty,
+    count: usize,
+}
+
+/// Field offsets computed with C alignment rules, over a data buffer
+struct Table<'a> {
+    data: &'a [u8],
+    slots: Vec<Slot>,
+    /// Struct size including tail padding
+    size: usize,
+}
+
+impl<'a> Table<'a> {
+    fn new(data: &'a [u8], fields: &[Field]) -> Self {
+        let mut offset = HEADER_SIZE;
+        let mut max_align = 2;
+        let mut slots = Vec::with_capacity(fields.len());
+        for &(name, ty, count) in fields {
+            let align = ty.align();
+            max_align = max_align.max(align);
+            offset = offset.next_multiple_of(align);
+            slots.push(Slot {
+                name,
+                offset,
+                ty,
+                count,
+            });
+            offset += ty.size() * count;
+        }
+        Self {
+            data,
+            slots,
+            size: offset.next_multiple_of(max_align),
+        }
+    }
+
+    /// All raw values of a field (concatenated if it repeats), with `None`
+    /// for unsupported entries
+    fn values(&self, name: &str) -> Vec<Option<u64>> {
+        let mut out = Vec::new();
+        for slot in self.slots.iter().filter(|s| s.name == name) {
+            let size = slot.ty.size();
+            for i in 0..slot.count {
+                let at = slot.offset + i * size;
+                let Some(bytes) = self.data.get(at..at + size) else {
+                    out.push(None);
+                    continue;
+                };
+                let mut buf = [0u8; 8];
+                buf[..size].copy_from_slice(bytes);
+                let value = u64::from_le_bytes(buf);
+                let all_ones = if size == 8 {
+                    u64::MAX
+                } else {
+                    (1u64 << (size * 8)) - 1
+                };
+                out.push(Some(value).filter(|&v| v != all_ones));
+            }
+        }
+        out
+    }
+
+    fn scalar(&self, name: &str, ty: Ty) -> Option<u64> {
+        let slot = self.slots.iter().find(|s| s.name == name)?;
+        if slot.ty != ty {
+            return None;
+        }
+        self.values(name).first().copied().flatten()
+    }
+
+    fn u16(&self, name: &str) -> Option<u16> {
+        self.scalar(name, U16).map(|v| v as u16)
+    }
+
+    fn u32(&self, name: &str) -> Option<u32> {
+        self.scalar(name, U32).map(|v| v as u32)
+    }
+
+    fn u64(&self, name: &str) -> Option<u64> {
+        self.scalar(name, U64)
+    }
+
+    /// Power fields widened from u16 to u32 in v3.0
+    fn u32_or_u16(&self, name: &str) -> Option<u32> {
+        self.u32(name).or_else(|| self.u16(name).map(u32::from))
+    }
+
+    fn u16s(&self, name: &str) -> Vec<Option<u16>> {
+        self.values(name)
+            .into_iter()
+            .map(|v| v.map(|v| v as u16))
+            .collect()
+    }
+
+    fn u32s(&self, name: &str) -> Vec<Option<u32>> {
+        self.values(name)
+            .into_iter()
+            .map(|v| v.map(|v| v as u32))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Blob of `size` bytes with every field unsupported (all ones)
+    fn blob(size: usize, format: u8, content: u8) -> Vec<u8> {
+        let mut data = vec![0xff; size];
+        data[0..2].copy_from_slice(&(size as u16).to_le_bytes());
+        data[2] = format;
+        data[3] = content;
+        data
+    }
+
+    fn put_u16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Per-instance values with every instance supported
+    fn some<T: Copy>(values: &[T]) -> Vec<Option<T>> {
+        values.iter().copied().map(Some).collect()
+    }
+
+    /// `values` followed by `holes` unsupported instances
+    fn padded<T: Copy>(values: &[T], holes: usize) -> Vec<Option<T>> {
+        [some(values), vec![None; holes]].concat()
+    }
+
+    fn size_of(format: u8, content: u8) -> usize {
+        Table::new(&[], &layout(format, content).unwrap()).size
+    }
+
+    #[test]
+    fn test_struct_sizes() {
+        // sizeof(struct gpu_metrics_vX_Y)
+        assert_eq!(size_of(1, 0), 80);
+        assert_eq!(size_of(1, 1), 96);
+        assert_eq!(size_of(1, 2), 104);
+        assert_eq!(size_of(1, 3), 120);
+        assert_eq!(size_of(1, 4), 288);
+        assert_eq!(size_of(1, 5), 360);
+        assert_eq!(size_of(1, 6), 1664);
+        assert_eq!(size_of(2, 0), 120);
+        assert_eq!(size_of(2, 1), 128);
+        assert_eq!(size_of(2, 2), 136);
+        assert_eq!(size_of(2, 3), 160);
+        assert_eq!(size_of(2, 4), 176);
+        assert_eq!(size_of(3, 0), 264);
+    }
+
+    /// Table from `tests/fixtures/gpu_metrics`, checked against its header
+    fn fixture(name: &str) -> GpuMetrics {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/gpu_metrics")
+            .join(name);
+        let data = std::fs::read(path).unwrap();
+        let m = GpuMetrics::parse(&data).unwrap();
+        assert_eq!(m.structure_size as usize, data.len());
+        assert_eq!(size_of(m.format_revision, m.content_revision), data.len());
+        m
+    }
+
+    #[test]
+    fn test_fixture_navi21_v1_3() {
+        let m = fixture("navi21_v1_3.bin");
+        assert_eq!((m.format_revision, m.content_revision), (1, 3));
+        assert!(!m.is_apu());
+        assert_eq!(m.temperature_edge, Some(62.0));
+        assert_eq!(m.temperature_hotspot, Some(81.0));
+        assert_eq!(m.temperature_mem, Some(70.0));
+        assert_eq!(m.temperature_vrgfx, Some(58.0));
+        assert_eq!(m.temperature_vrsoc, Some(52.0));
+        assert_eq!(m.temperature_vrmem, Some(55.0));
+        // GDDR6 board: the HBM slots are all unsupported
+        assert_eq!(m.temperature_hbm, vec![None; NUM_HBM_INSTANCES]);
+        assert_eq!(m.primary_temperature(), Some(81.0));
+        assert_eq!(m.gfx_activity, Some(98));
+        assert_eq!(m.umc_activity, Some(41));
+        assert_eq!(m.mm_activity, Some(0));
+        assert_eq!(m.gfx_activity_acc, Some(1525311));
+        assert_eq!(m.mem_activity_acc, Some(648202));
+        assert_eq!(m.socket_power, Some(281.0));
+        assert_eq!(m.energy_accumulator, None);
+        assert_eq!(m.average_gfxclk, Some(2412));
+        assert_eq!(m.average_socclk, Some(1200));
+        assert_eq!(m.average_uclk, Some(1000));
+        assert_eq!(m.average_vclk0, Some(0));
+        assert_eq!(m.current_gfxclk, some(&[2425]));
+        assert_eq!(m.current_socclk, some(&[1200]));
+        assert_eq!(m.current_uclk, Some(1000));
+        // Navi 21 has one VCN instance; VCLK1/DCLK1 are left unsupported
+        assert_eq!(m.current_vclk, vec![Some(0), None]);
+        assert_eq!(m.current_dclk, vec![Some(0), None]);
+        assert_eq!(m.fan_speed, Some(1733));
+        assert_eq!(m.pcie_link_width, Some(16));
+        assert_eq!(m.pcie_link_speed_gts(), Some(16.0));
+        assert_eq!(m.pcie_gen(), Some(4));
+        assert_eq!(m.xgmi_link_width, None);
+        assert_eq!(m.voltage_soc, Some(1025));
+        assert_eq!(m.voltage_gfx, Some(1150));
+        assert_eq!(m.voltage_mem, Some(1350));
+        assert_eq!(m.system_clock_counter, Some(1834512345678));
+        assert_eq!(m.firmware_timestamp, Some(183451234567));
+        assert_eq!(m.throttle_status, Some(0x1));
+        assert_eq!(m.indep_throttle_status, Some(0x1));
+        assert_eq!(m.throttle().active, vec![ThrottleCause::PowerCap]);
+    }
+
+    #[test]
+    fn test_fixture_mi300x_v1_6() {
+        let m = fixture("mi300x_v1_6.bin");
+        assert_eq!((m.format_revision, m.content_revision), (1, 6));
+        assert_eq!(m.temperature_hotspot, Some(71.0));
+        assert_eq!(m.temperature_mem, Some(64.0));
+        assert_eq!(m.temperature_vrsoc, Some(49.0));
+        assert_eq!(m.temperature_edge, None);
+        assert_eq!(m.socket_power, Some(702.0));
+        assert_eq!(m.energy_joules(), Some(2_500_000.0));
+        assert_eq!(m.gfx_activity, Some(94));
+        assert_eq!(m.umc_activity, Some(57));
+        // Only the first partition is populated in SPX mode; the others
+        // stay in place as holes
+        assert_eq!(m.xcc_activity.len(), NUM_XCP * MAX_XCC);
+        assert_eq!(
+            m.xcc_activity[..MAX_XCC],
+            some(&[93, 95, 94, 96, 92, 95, 97, 94])
+        );
+        assert_eq!(m.vcn_activity.len(), NUM_XCP * NUM_VCN);
+        assert_eq!(m.vcn_activity[..NUM_VCN], some(&[0; 4]));
+        assert_eq!(m.jpeg_activity.len(), NUM_XCP * NUM_JPEG_ENG);
+        assert_eq!(m.jpeg_activity[..8], some(&[0; 8]));
+        assert!(m.xcc_activity[MAX_XCC..].iter().all(Option::is_none));
+        for unused in [&m.vcn_activity[NUM_VCN..], &m.jpeg_activity[8..]] {
+            assert!(unused.iter().all(Option::is_none));
+        }
+        assert_eq!(
+            m.current_gfxclk,
+            some(&[2100, 2098, 2100, 2095, 2100, 2099, 2100, 2097])
+        );
+        assert_eq!(m.gfxclk(), Some(2100));
+        assert_eq!(m.current_socclk, some(&[1143; 4]));
+        assert_eq!(m.current_vclk, some(&[29; 4]));
+        assert_eq!(m.current_dclk, some(&[22; 4]));
+        assert_eq!(m.current_uclk, Some(1300));
+        assert_eq!(m.pcie_link_width, Some(16));
+        assert_eq!(m.pcie_gen(), Some(5));
+        assert_eq!(m.xgmi_link_width, Some(16));
+        assert_eq!(m.xgmi_link_speed, Some(32));
+        assert_eq!(m.firmware_timestamp, Some(938249922368));
+        assert_eq!(
+            m.throttle_residency,
+            vec![
+                ("prochot".to_string(), 0),
+                ("ppt".to_string(), 8412),
+                ("socket thermal".to_string(), 0),
+                ("vr thermal".to_string(), 0),
+                ("hbm thermal".to_string(), 0),
+            ]
+        );
+        assert_eq!(m.throttle_status, None);
+        assert!(m.throttle().active.is_empty());
+    }
+
+    #[test]
+    fn test_parse_v1_5() {
+        // MI300 style table with per-XCD clocks
+        let mut data = blob(360, 1, 5);
+        put_u16(&mut data, 4, 45); // temperature_hotspot
+        put_u16(&mut data, 10, 550); // curr_socket_power
+        put_u16(&mut data, 16, 12); // vcn_activity[0]
+        put_u16(&mut data, 24, 3); // jpeg_activity[0]
+        put_u32(&mut data, 104, 0); // throttle_status
+        put_u16(&mut data, 112, 16); // pcie_link_width
+        put_u16(&mut data, 116, 16); // xgmi_link_width
+        for xcd in 0..8 {
+            put_u16(&mut data, 312 + xcd * 2, 2100 - xcd as u16);
+        }
+        put_u16(&mut data, 352, 1300); // current_uclk
+
+        let m = GpuMetrics::parse(&data).unwrap();
+        assert_eq!(m.temperature_hotspot, Some(45.0));
+        assert_eq!(m.socket_power, Some(550.0));
+        assert_eq!(m.vcn_activity[0], Some(12));
+        assert!(m.vcn_activity[1..].iter().all(Option::is_none));
+        assert_eq!(m.jpeg_activity[0], Some(3));
+        assert!(m.jpeg_activity[1..].iter().all(Option::is_none));
+        assert_eq!(m.current_gfxclk.len(), 8);
+        assert_eq!(m.gfxclk(), Some(2100));
+        assert_eq!(m.current_uclk, Some(1300));
+        assert_eq!(m.xgmi_link_width, Some(16));
+        assert!(m.throttle().active.is_empty());
+    }
+
+    #[test]
+    fn test_parse_v2_2() {
+        // Rembrandt style APU table: centi-degrees and milliwatts
+        let mut data = blob(136, 2, 2);
+        put_u16(&mut data, 16, 4550); // temperature_gfx
+        put_u16(&mut data, 20, 6025); // temperature_core[0]
+        put_u16(&mut data, 22, 5900); // temperature_core[1]
+        put_u16(&mut data, 40, 35); // average_gfx_activity
+        put_u16(&mut data, 44, 15000); // average_socket_power
+        put_u16(&mut data, 80, 2200); // current_gfxclk
+        put_u16(&mut data, 86, 1600); // current_fclk
+        put_u16(&mut data, 92, 4700); // current_coreclk[0]
+        put_u32(&mut data, 112, 0x40); // throttle_status
+        put_u64(&mut data, 128, 1 << 33); // indep_throttle_status: TEMP_CORE
+
+        let m = GpuMetrics::parse(&data).unwrap();
+        assert!(m.is_apu());
+        assert_eq!(m.temperature_gfx, Some(45.5));
+        assert_eq!(m.temperature_core[..2], some(&[60.25, 59.0]));
+        assert!(m.temperature_core[2..].iter().all(Option::is_none));
+        assert_eq!(m.primary_temperature(), Some(45.5));
+        assert_eq!(m.socket_power, Some(15.0));
+        assert_eq!(m.gfxclk(), Some(2200));
+        assert_eq!(m.current_fclk, Some(1600));
+        assert_eq!(m.current_coreclk[0], Some(4700));
+        assert_eq!(m.throttle_status, Some(0x40));
+        assert_eq!(m.throttle().active, vec![ThrottleCause::Thermal]);
+        assert_eq!(m.energy_accumulator, None);
+    }
+
+    #[test]
+    fn test_fixture_vangogh_v2_3() {
+        // APU tables: centi-degrees and milliwatts
+        let m = fixture("vangogh_v2_3.bin");
+        assert_eq!((m.format_revision, m.content_revision), (2, 3));
+        assert!(m.is_apu());
+        assert_eq!(m.temperature_gfx, Some(64.25));
+        assert_eq!(m.temperature_soc, Some(61.75));
+        // Four cores and one L3 complex in slots sized for eight and two
+        assert_eq!(m.temperature_core, padded(&[63.0, 62.5, 63.75, 62.0], 4));
+        assert_eq!(m.temperature_l3, padded(&[61.0], 1));
+        assert_eq!(m.primary_temperature(), Some(64.25));
+        assert_eq!(m.gfx_activity, Some(87));
+        assert_eq!(m.mm_activity, Some(0));
+        assert_eq!(m.socket_power, Some(14.812));
+        assert_eq!(m.cpu_power, Some(3.904));
+        assert_eq!(m.soc_power, Some(2.211));
+        assert_eq!(m.gfx_power, Some(8.697));
+        assert_eq!(m.core_power, padded(&[1.02, 0.955, 1.01, 0.919], 4));
+        assert_eq!(m.energy_accumulator, None);
+        assert_eq!(m.average_gfxclk, Some(1580));
+        assert_eq!(m.average_fclk, Some(1375));
+        assert_eq!(m.average_vclk0, Some(0));
+        assert_eq!(m.current_gfxclk, some(&[1600]));
+        assert_eq!(m.current_socclk, some(&[933]));
+        assert_eq!(m.current_uclk, Some(1375));
+        assert_eq!(m.current_fclk, Some(1375));
+        assert_eq!(m.current_coreclk, padded(&[3450, 3500, 3425, 3500], 4));
+        assert_eq!(m.fan_pwm, None);
+        assert_eq!(m.system_clock_counter, Some(512349876543));
+        assert_eq!(m.throttle_status, Some(0));
+        assert_eq!(m.indep_throttle_status, Some(1 << 4));
+        assert_eq!(m.throttle().active, vec![ThrottleCause::PowerCap]);
+    }
+
+    #[test]
+    fn test_fixture_strix_point_v3_0() {
+        let m = fixture("strix_point_v3_0.bin");
+        assert_eq!((m.format_revision, m.content_revision), (3, 0));
+        assert!(m.is_apu());
+        assert_eq!(m.temperature_gfx, Some(48.5));
+        assert_eq!(m.temperature_soc, Some(47.25));
+        // 12 cores in slots sized for 16
+        assert_eq!(m.temperature_core.len(), 16);
+        assert_eq!(m.temperature_core[0], Some(52.0));
+        assert!(m.temperature_core[..12].iter().all(Option::is_some));
+        assert!(m.temperature_core[12..].iter().all(Option::is_none));
+        assert_eq!(m.temperature_skin, Some(36.5));
+        assert_eq!(m.gfx_activity, Some(12));
+        assert_eq!(m.mm_activity, Some(4));
+        assert_eq!(m.ipu_activity, padded(&[35, 30, 0, 0], 4));
+        assert_eq!(
+            m.core_activity,
+            padded(&[42, 18, 9, 7, 3, 2, 2, 1, 1, 1, 1, 0], 4)
+        );
+        assert_eq!(m.socket_power, Some(11.342));
+        assert_eq!(m.cpu_power, Some(4.21));
+        assert_eq!(m.gfx_power, Some(1.873));
+        assert_eq!(m.ipu_power, Some(0.64));
+        assert_eq!(m.soc_power, None);
+        assert_eq!(m.core_power.len(), 16);
+        assert_eq!(m.core_power[0], Some(0.91));
+        assert_eq!(m.stapm_power_limit, Some(28.0));
+        assert_eq!(m.average_gfxclk, Some(812));
+        assert_eq!(m.average_socclk, Some(600));
+        assert_eq!(m.average_fclk, Some(1600));
+        assert_eq!(m.average_uclk, Some(3750));
+        assert_eq!(m.average_vclk0, Some(705));
+        assert!(m.current_gfxclk.is_empty());
+        assert_eq!(
+            m.current_coreclk,
+            padded(
+                &[4980, 3620, 2900, 2600, 2010, 2010, 2010, 2010, 1600, 1600, 1600, 1600],
+                4
+            )
+        );
+        assert_eq!(m.system_clock_counter, Some(77123456789));
+        assert_eq!(
+            m.throttle_residency,
+            vec![
+                ("prochot".to_string(), 0),
+                ("spl".to_string(), 1843),
+                ("fppt".to_string(), 12),
+                ("sppt".to_string(), 97),
+                ("core thermal".to_string(), 0),
+                ("gfx thermal".to_string(), 0),
+                ("soc thermal".to_string(), 0),
+            ]
+        );
+        assert!(m.throttle().active.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(GpuMetrics::parse(&[0x10, 0]).is_err());
+        assert!(GpuMetrics::parse(&blob(64, 1, 3)).is_err());
+        assert!(GpuMetrics::parse(&blob(256, 9, 0)).is_err());
+    }
+}
@@ -14,8 +14,9 @@
 //! Based on nvtop's extract_gpuinfo_intel.c implementation.
 
 use crate::gpu::{
-    Gpu, GpuClocks, GpuCollection, GpuDynamicInfo, GpuEngines, GpuMemory, GpuPower, GpuProcess,
-    GpuStaticInfo, GpuThermal, GpuVendor, PcieLinkInfo,
+    snapshot_capabilities, Capabilities, Capability, Gpu, GpuClocks, GpuCollection, GpuDynamicInfo,
+    GpuEngines, GpuMemory, GpuPower, GpuProcess, GpuProcessType, GpuStaticInfo, GpuThermal,
+    GpuVendor, PcieLinkInfo,
 };
 use crate::Error;
 
@@ -241,7 +242,7 @@ impl Gpu for IntelGpu {
                 fs::read_to_string(format!("{}/temp1_input", hwmon))
                     .ok()
                     .and_then(|s| s.trim().parse::<i32>().ok())
-                    .map(|t| (t / 1000) as u32)
+                    .map(|t| t / 1000)
             });
 
             Ok(GpuDynamicInfo {
@@ -386,6 +387,15 @@ impl Gpu for IntelGpu {
             ))
         }
     }
+
+    fn capabilities(&self) -> Capabilities {
+        let caps = snapshot_capabilities(self);
+        if cfg!(unix) {
+            caps.with(Capability::KillProcess)
+        } else {
+            caps
+        }
+    }
 }
 
 /// Parse fdinfo for Intel GPU processes
@@ -437,12 +447,15 @@ fn parse_intel_fdinfo_processes(card_path: &str, driver: &IntelDriver) -> Result
                                 processes.push(GpuProcess {
                                     pid,
                                     name,
-                                    gpu_memory: 0, // Intel iGPUs share system memory
-                                    compute_util: None,
-                                    memory_util: None,
-                                    encoder_util: None,
-                                    decoder_util: None,
-                                    process_type: None,
+                                    user: String::new(),
+                                    process_type: GpuProcessType::Unknown,
+                                    gpu_usage: None,
+                                    memory_usage: None, // Intel iGPUs share system memory
+                                    memory_usage_percent: None,
+                                    encoder_usage: None,
+                                    decoder_usage: None,
+                                    cpu_usage: None,
+                                    cpu_memory: None,
                                 });
                             }
                             break;
@@ -6,7 +6,10 @@
 //! This module provides a vendor-agnostic interface for GPU monitoring across NVIDIA, AMD, and
 //! Intel GPUs through a common trait-based system. The [`Device`] trait defines the core API
 //! that all GPU backends must implement, while [`GpuCollection`] provides convenient
-//! multi-vendor GPU management.
+//! multi-vendor GPU management. The collection wraps each [`Device`] in a
+//! [`DeviceGpu`], so every consumer sees the same numbers for a card; the
+//! older per-vendor [`Gpu`] backends are only used when no device backend
+//! finds a GPU.
 //!
 //! # Examples
 //!
@@ -64,6 +67,28 @@
 //! # }
 //! ```
 //!
+//! ## Query capabilities
+//!
+//! Every GPU reports which metrics and controls it supports, so callers can
+//! skip queries instead of probing for `NotSupported` errors.
+//!
+//! ```no_run
+//! use simon::gpu::{Capability, GpuCollection};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let gpus = GpuCollection::auto_detect()?;
+//!
+//! for gpu in gpus.gpus() {
+//!     let caps = gpu.capabilities();
+//!     println!("{}: {}", gpu.name()?, caps);
+//!     if caps.contains(Capability::ThrottleStatus) {
+//!         println!("  Throttling: {:?}", gpu.throttle_status()?.active);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Vendor-specific initialization
 //!
 //! ```no_run,ignore
@@ -106,9 +131,18 @@ use std::fmt;
 // New unified traits module
 pub mod traits;
 
+// amdgpu gpu_metrics table parser (pure parsing, available without the amd feature)
+pub mod amdgpu_metrics;
+
+// amdgpu power-profile and overdrive control via sysfs
+pub mod amdgpu_control;
+
+// nvidia-smi compatible --query-gpu / --query-compute-apps output
+pub mod query;
+
 // Re-export key types from traits (with GpuProcess renamed to avoid conflict with legacy)
 pub use traits::{
-    Clocks, ComputeMode, Device, EccErrors, Error as GpuError, FanSpeed,
+    Capabilities, Capability, Clocks, ComputeMode, Device, EccErrors, Error as GpuError, FanSpeed,
     GpuProcess as GpuProcessTrait, LinkState, Memory, MigMode, NvLinkStatus, PciInfo, Power,
     ProcessType, Temperature, TemperatureStatus, TemperatureThresholds, Utilization, Vendor,
 };
@@ -455,25 +489,90 @@ pub trait Gpu: Send + Sync {
         ))
     }
 
+    /// Get current throttle reasons (if supported)
+    fn throttle_status(&self) -> Result<crate::throttling::GpuThrottleStatus, crate::Error> {
+        Err(crate::Error::NotSupported(
+            "Throttle reasons not supported for this GPU".to_string(),
+        ))
+    }
+
+    /// Metrics and controls supported by this GPU
+    ///
+    /// The default derives metric support from one snapshot (see
+    /// [`snapshot_capabilities`]). Backends add the controls they implement.
+    fn capabilities(&self) -> Capabilities {
+        snapshot_capabilities(self)
+    }
+
     /// Get vendor-specific data as JSON (for advanced features)
     fn vendor_specific_data(&self) -> Result<serde_json::Value, crate::Error> {
         Ok(serde_json::Value::Null)
     }
 }
 
-/// Adapter to convert new trait-based Device to legacy Gpu trait
-#[allow(dead_code)]
-struct TraitGpuAdapter {
+/// Metric capabilities of a [`Gpu`], derived from one snapshot
+pub fn snapshot_capabilities<G: Gpu + ?Sized>(gpu: &G) -> Capabilities {
+    let mut caps = Capabilities::new();
+    if let Ok(info) = gpu.dynamic_info() {
+        let mut add = |cap, supported: bool| {
+            if supported {
+                caps.insert(cap);
+            }
+        };
+        add(Capability::Utilization, true);
+        add(Capability::Memory, info.memory.total > 0);
+        add(
+            Capability::Clocks,
+            info.clocks.graphics.is_some() || info.clocks.memory.is_some(),
+        );
+        add(Capability::Power, info.power.draw.is_some());
+        add(Capability::Temperature, info.thermal.temperature.is_some());
+        add(
+            Capability::FanSpeed,
+            info.thermal.fan_speed.is_some() || info.thermal.fan_rpm.is_some(),
+        );
+        add(
+            Capability::EncoderDecoder,
+            info.engines.encoder.is_some() || info.engines.decoder.is_some(),
+        );
+    }
+    if gpu.static_info().is_ok_and(|s| s.pci_bus_id.is_some()) {
+        caps.insert(Capability::PciInfo);
+    }
+    if gpu.processes().is_ok() {
+        caps.insert(Capability::Processes);
+    }
+    if gpu.throttle_status().is_ok() {
+        caps.insert(Capability::ThrottleStatus);
+    }
+    caps
+}
+
+/// [`Gpu`] view of a [`Device`]
+///
+/// [`GpuCollection`] wraps every device backend in this adapter, so the
+/// snapshot API and the TUI read the same backend and report the same
+/// numbers for a card.
+pub struct DeviceGpu {
     device: Box<dyn Device>,
 }
 
-impl TraitGpuAdapter {
-    #[allow(dead_code)]
-    fn new(device: Box<dyn Device>) -> Self {
+impl DeviceGpu {
+    /// Wrap a device backend
+    pub fn new(device: Box<dyn Device>) -> Self {
         Self { device }
     }
 
-    #[allow(dead_code)]
+    /// Underlying device
+    pub fn device(&self) -> &dyn Device {
+        self.device.as_ref()
+    }
+
+    /// Underlying device, mutably (for control functions)
+    pub fn device_mut(&mut self) -> &mut dyn Device {
+        self.device.as_mut()
+    }
+
     fn convert_vendor(vendor: Vendor) -> GpuVendor {
         match vendor {
             Vendor::Nvidia => GpuVendor::Nvidia,
@@ -484,7 +583,7 @@ impl TraitGpuAdapter {
     }
 }
 
-impl Gpu for TraitGpuAdapter {
+impl Gpu for DeviceGpu {
     fn static_info(&self) -> Result<GpuStaticInfo, crate::Error> {
         let name = self.device.name().unwrap_or_else(|_| "Unknown".to_string());
         let pci = self.device.pci_info().ok();
@@ -576,7 +675,8 @@ impl Gpu for TraitGpuAdapter {
 
         let thermal_info = if let Some(t) = temp {
             GpuThermal {
-                temperature: t.edge.map(|e| e as i32),
+                // Same sensor the TUI shows (junction/hotspot before edge)
+                temperature: t.primary().map(|e| e as i32),
                 max_temperature: t
                     .thresholds
                     .as_ref()
@@ -585,9 +685,9 @@ impl Gpu for TraitGpuAdapter {
                     .thresholds
                     .as_ref()
                     .and_then(|th| th.shutdown.map(|s| s as i32)),
-                fan_speed: fan.as_ref().map(|f| match f {
-                    FanSpeed::Percent(p) => *p as u8,
-                    FanSpeed::Rpm(_) => 0u8, // RPM doesn't map to percent
+                fan_speed: fan.as_ref().and_then(|f| match f {
+                    FanSpeed::Percent(p) => Some(*p as u8),
+                    FanSpeed::Rpm(_) => None, // RPM doesn't map to percent
                 }),
                 fan_rpm: fan.as_ref().and_then(|f| match f {
                     FanSpeed::Rpm(r) => Some(*r),
@@ -641,9 +741,13 @@ impl Gpu for TraitGpuAdapter {
                 encoder: util.as_ref().and_then(|u| u.encoder.map(|e| e as u8)),
                 decoder: util.as_ref().and_then(|u| u.decoder.map(|d| d as u8)),
                 copy: None,
-                vendor_specific: vec![],
+                vendor_specific: util
+                    .as_ref()
+                    .and_then(|u| u.jpeg)
+                    .map(|j| vec![("JPEG".to_string(), j as u8)])
+                    .unwrap_or_default(),
             },
-            processes: vec![],
+            processes: self.processes().unwrap_or_default(),
         })
     }
 
@@ -662,15 +766,103 @@ impl Gpu for TraitGpuAdapter {
     }
 
     fn processes(&self) -> Result<Vec<GpuProcess>, crate::Error> {
-        // For now, return empty - process monitoring requires more work
-        Ok(vec![])
+        let processes = self
+            .device
+            .processes()
+            .map_err(|e| crate::Error::GpuError(e.to_string()))?;
+        Ok(processes
+            .iter()
+            .map(|p| GpuProcess {
+                pid: p.pid(),
+                name: p.name().unwrap_or_default(),
+                user: String::new(),
+                process_type: match p.process_type() {
+                    ProcessType::Compute => GpuProcessType::Compute,
+                    ProcessType::Graphics => GpuProcessType::Graphics,
+                    ProcessType::Mixed => GpuProcessType::GraphicsAndCompute,
+                },
+                gpu_usage: p.sm_utilization().ok().flatten().map(|u| u as u8),
+                memory_usage: p.gpu_memory_used().ok(),
+                memory_usage_percent: None,
+                encoder_usage: p.encoder_utilization().ok().flatten().map(|u| u as u8),
+                decoder_usage: p.decoder_utilization().ok().flatten().map(|u| u as u8),
+                cpu_usage: p.cpu_utilization().ok().flatten().map(|u| u as u8),
+                cpu_memory: p.host_memory_used().ok().flatten(),
+            })
+            .collect())
     }
 
-    fn kill_process(&self, _pid: u32) -> Result<(), crate::Error> {
+    fn kill_process(&self, pid: u32) -> Result<(), crate::Error> {
+        // Only signal processes that are actually using this GPU
+        if !self.processes()?.iter().any(|p| p.pid == pid) {
+            return Err(crate::Error::ProcessError(format!(
+                "Process {} is not running on GPU {}",
+                pid,
+                self.device.index()
+            )));
+        }
+        #[cfg(unix)]
+        {
+            use nix::sys::signal::{kill, Signal};
+            use nix::unistd::Pid;
+            kill(Pid::from_raw(pid as i32), Signal::SIGTERM).map_err(|e| {
+                crate::Error::ProcessError(format!("Failed to kill process {}: {}", pid, e))
+            })
+        }
+        #[cfg(not(unix))]
         Err(crate::Error::NotSupported(
-            "Process kill not implemented for this adapter".to_string(),
+            "Process kill not supported on this platform".to_string(),
         ))
     }
+
+    fn set_power_limit(&mut self, limit_mw: u32) -> Result<(), crate::Error> {
+        self.device
+            .set_power_limit(limit_mw as f32 / 1000.0)
+            .map_err(|e| crate::Error::GpuError(e.to_string()))
+    }
+
+    fn throttle_status(&self) -> Result<crate::throttling::GpuThrottleStatus, crate::Error> {
+        self.device
+            .throttle_status()
+            .map_err(|e| crate::Error::GpuError(e.to_string()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let caps = self.device.capabilities();
+        if cfg!(unix) && caps.contains(Capability::Processes) {
+            caps.with(Capability::KillProcess)
+        } else {
+            caps
+        }
+    }
+}
+
+/// Enumerate every GPU through its [`Device`] backend
+///
+/// This is the single detection path shared by [`GpuCollection`] and the
+/// TUI. Vendors whose support isn't compiled in are skipped.
+pub fn enumerate_devices() -> Vec<Box<dyn Device>> {
+    #[allow(unused_mut)]
+    let mut devices: Vec<Box<dyn Device>> = Vec::new();
+
+    #[cfg(feature = "nvidia")]
+    if let Ok(nvidia_devices) = nvidia_new::enumerate() {
+        for device in nvidia_devices {
+            devices.push(Box::new(device));
+        }
+    }
+
+    #[cfg(feature = "amd")]
+    if let Ok(mut amd_devices) = amd_rocm::enumerate() {
+        devices.append(&mut amd_devices);
+    }
+
+    #[cfg(feature = "intel")]
+    if let Ok(mut intel_devices) = intel_levelzero::enumerate() {
+        devices.append(&mut intel_devices);
+    }
+
+    devices
 }
 
 /// GPU collection representing all detected GPUs
@@ -703,48 +895,55 @@ impl GpuCollection {
     /// Detect NVIDIA GPUs
     #[cfg(feature = "nvidia")]
     pub fn detect_nvidia(&mut self) -> Result<(), crate::Error> {
-        nvidia::detect_gpus(self)?;
+        let devices: Vec<Box<dyn Device>> = nvidia_new::enumerate()
+            .map(|gpus| {
+                gpus.into_iter()
+                    .map(|gpu| Box::new(gpu) as Box<dyn Device>)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !self.add_devices(devices) {
+            // Fall back to legacy implementation
+            nvidia::detect_gpus(self)?;
+        }
         Ok(())
     }
 
     /// Detect AMD GPUs
     #[cfg(feature = "amd")]
     pub fn detect_amd(&mut self) -> Result<(), crate::Error> {
-        // Try new trait-based implementation first (sysfs)
-        #[cfg(target_os = "linux")]
-        {
-            if let Ok(gpus) = amd_rocm::enumerate() {
-                for gpu in gpus {
-                    self.add_gpu(Box::new(TraitGpuAdapter::new(gpu)));
-                }
-                return Ok(());
-            }
+        if !self.add_devices(amd_rocm::enumerate().unwrap_or_default()) {
+            // Fall back to legacy implementation
+            amd::detect_gpus(self)?;
         }
-
-        // Fall back to legacy implementation
-        amd::detect_gpus(self)?;
         Ok(())
     }
 
     /// Detect Intel GPUs
     #[cfg(feature = "intel")]
     pub fn detect_intel(&mut self) -> Result<(), crate::Error> {
-        // Try new trait-based implementation first (sysfs)
-        #[cfg(target_os = "linux")]
-        {
-            if let Ok(gpus) = intel_levelzero::enumerate() {
-                for gpu in gpus {
-                    self.add_gpu(Box::new(TraitGpuAdapter::new(gpu)));
-                }
-                return Ok(());
-            }
+        if !self.add_devices(intel_levelzero::enumerate().unwrap_or_default()) {
+            // Fall back to legacy implementation
+            intel::detect_gpus(self)?;
         }
-
-        // Fall back to legacy implementation
-        intel::detect_gpus(self)?;
         Ok(())
     }
 
+    /// Add device backends, returning whether any were added
+    #[cfg(any(feature = "nvidia", feature = "amd", feature = "intel"))]
+    fn add_devices(&mut self, devices: Vec<Box<dyn Device>>) -> bool {
+        let added = !devices.is_empty();
+        for device in devices {
+            self.add_device(device);
+        }
+        added
+    }
+
+    /// Add a device backend to the collection
+    pub fn add_device(&mut self, device: Box<dyn Device>) {
+        self.add_gpu(Box::new(DeviceGpu::new(device)));
+    }
+
     /// Add a GPU to the collection
     pub fn add_gpu(&mut self, gpu: Box<dyn Gpu>) {
         self.gpus.push(gpu);
@@ -820,3 +1019,139 @@ impl Default for GpuCollection {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Device with temperature and memory but no power, fan or processes
+    struct MockDevice;
+
+    impl Device for MockDevice {
+        fn vendor(&self) -> Vendor {
+            Vendor::Amd
+        }
+        fn index(&self) -> u32 {
+            0
+        }
+        fn name(&self) -> Result<String, GpuError> {
+            Ok("Mock GPU".to_string())
+        }
+        fn uuid(&self) -> Result<String, GpuError> {
+            Err(GpuError::NotSupported)
+        }
+        fn pci_info(&self) -> Result<PciInfo, GpuError> {
+            Err(GpuError::NotSupported)
+        }
+        fn driver_version(&self) -> Result<String, GpuError> {
+            Ok("mock".to_string())
+        }
+        fn temperature(&self) -> Result<Temperature, GpuError> {
+            Ok(Temperature {
+                edge: Some(50.0),
+                junction: None,
+                memory: None,
+                hotspot: Some(72.0),
+                vr_gfx: None,
+                vr_soc: None,
+                vr_mem: None,
+                hbm: None,
+                thresholds: None,
+            })
+        }
+        fn power(&self) -> Result<Power, GpuError> {
+            Err(GpuError::NotSupported)
+        }
+        fn clocks(&self) -> Result<Clocks, GpuError> {
+            Ok(Clocks {
+                graphics: 1800,
+                memory: 1000,
+                sm: None,
+                video: None,
+            })
+        }
+        fn utilization(&self) -> Result<Utilization, GpuError> {
+            Ok(Utilization {
+                gpu: 40.0,
+                memory: 10.0,
+                encoder: None,
+                decoder: None,
+                jpeg: None,
+                ofa: None,
+            })
+        }
+        fn memory(&self) -> Result<Memory, GpuError> {
+            Ok(Memory {
+                total: 8 << 30,
+                used: 2 << 30,
+                free: 6 << 30,
+                bar1_total: None,
+                bar1_used: None,
+            })
+        }
+        fn fan_speed(&self) -> Result<Option<FanSpeed>, GpuError> {
+            Ok(None)
+        }
+        fn performance_state(&self) -> Result<Option<String>, GpuError> {
+            Ok(Some("auto".to_string()))
+        }
+        fn processes(&self) -> Result<Vec<Box<dyn GpuProcessTrait>>, GpuError> {
+            Err(GpuError::NotSupported)
+        }
+        fn capabilities(&self) -> Capabilities {
+            Capabilities::probe(self).with(Capability::SetPowerLimit)
+        }
+    }
+
+    #[test]
+    fn test_device_capabilities() {
+        let caps = MockDevice.capabilities();
+        for cap in [
+            Capability::Temperature,
+            Capability::Clocks,
+            Capability::Utilization,
+            Capability::Memory,
+            Capability::PerformanceState,
+            Capability::SetPowerLimit,
+        ] {
+            assert!(caps.contains(cap), "missing {}", cap);
+        }
+        for cap in [
+            Capability::Power,
+            Capability::FanSpeed,
+            Capability::Processes,
+            Capability::EncoderDecoder,
+            Capability::ThrottleStatus,
+            Capability::NvLink,
+        ] {
+            assert!(!caps.contains(cap), "unexpected {}", cap);
+        }
+        assert_eq!(
+            caps.controls().collect::<Vec<_>>(),
+            [Capability::SetPowerLimit]
+        );
+        assert_eq!(caps.metrics().count(), caps.len() - 1);
+    }
+
+    #[test]
+    fn test_device_gpu_matches_device() {
+        let mut gpus = GpuCollection::new();
+        gpus.add_device(Box::new(MockDevice));
+        let gpu = &gpus.gpus()[0];
+
+        let info = gpu.info().unwrap();
+        assert_eq!(info.static_info.name, "Mock GPU");
+        assert_eq!(info.static_info.vendor, GpuVendor::Amd);
+        // Same primary sensor as Temperature::primary(), not the edge sensor
+        assert_eq!(info.dynamic_info.thermal.temperature, Some(72));
+        assert_eq!(info.dynamic_info.utilization, 40);
+        assert_eq!(info.dynamic_info.clocks.graphics, Some(1800));
+        assert_eq!(info.dynamic_info.power.draw, None);
+        assert!(info.dynamic_info.processes.is_empty());
+
+        // Capabilities come from the device; no processes means no kill
+        let caps = gpu.capabilities();
+        assert_eq!(caps, MockDevice.capabilities());
+        assert!(!caps.contains(Capability::KillProcess));
+    }
+}
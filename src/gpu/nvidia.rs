@@ -15,9 +15,11 @@
 //! This integrates the existing Simon NVIDIA implementation with the unified GPU interface.
 
 use crate::gpu::{
-    Gpu, GpuClocks, GpuCollection, GpuDynamicInfo, GpuEngines, GpuMemory, GpuPower, GpuProcess,
-    GpuProcessType, GpuStaticInfo, GpuThermal, GpuVendor, PcieLinkInfo,
+    snapshot_capabilities, Capabilities, Capability, Gpu, GpuClocks, GpuCollection, GpuDynamicInfo,
+    GpuEngines, GpuMemory, GpuPower, GpuProcess, GpuProcessType, GpuStaticInfo, GpuThermal,
+    GpuVendor, PcieLinkInfo,
 };
+use crate::throttling::{GpuThrottleStatus, ThrottleCause};
 use crate::Error;
 
 #[cfg(feature = "nvidia")]
@@ -401,6 +403,84 @@ impl Gpu for NvidiaGpu {
             .map_err(|e| Error::GpuError(format!("Failed to set power limit: {}", e)))?;
         Ok(())
     }
+
+    #[cfg(feature = "nvidia")]
+    fn throttle_status(&self) -> Result<GpuThrottleStatus, Error> {
+        nvml_throttle_status(&self.device)
+            .map_err(|e| Error::GpuError(format!("Failed to get throttle reasons: {}", e)))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let caps = snapshot_capabilities(self).with(Capability::KillProcess);
+        if cfg!(feature = "nvidia") {
+            caps.with(Capability::SetPowerLimit)
+        } else {
+            caps
+        }
+    }
+}
+
+/// Current clock event (throttle) reasons and violation times from NVML
+///
+/// Idle, application clock and display clock reasons are not throttling
+/// and are left out.
+#[cfg(feature = "nvidia")]
+pub(crate) fn nvml_throttle_status(
+    device: &Device<'_>,
+) -> Result<GpuThrottleStatus, nvml_wrapper::error::NvmlError> {
+    use nvml_wrapper::bitmasks::device::ThrottleReasons;
+    use nvml_wrapper::enum_wrappers::device::PerformancePolicy;
+
+    let reasons = device.current_throttle_reasons()?;
+    let mut active = Vec::new();
+    if reasons
+        .intersects(ThrottleReasons::SW_THERMAL_SLOWDOWN | ThrottleReasons::HW_THERMAL_SLOWDOWN)
+    {
+        active.push(ThrottleCause::Thermal);
+    }
+    if reasons.contains(ThrottleReasons::SW_POWER_CAP) {
+        active.push(ThrottleCause::PowerCap);
+    }
+    if reasons.contains(ThrottleReasons::SYNC_BOOST) {
+        active.push(ThrottleCause::SyncBoost);
+    }
+    if reasons.contains(ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN) {
+        active.push(ThrottleCause::PowerBrake);
+    }
+    // HW_SLOWDOWN is also set alongside HW thermal and power brake
+    // slowdowns; on its own it means power draw tripped the fast-trigger
+    // (over-current) protection
+    if reasons.contains(ThrottleReasons::HW_SLOWDOWN)
+        && !reasons.intersects(
+            ThrottleReasons::HW_THERMAL_SLOWDOWN | ThrottleReasons::HW_POWER_BRAKE_SLOWDOWN,
+        )
+    {
+        active.push(ThrottleCause::Current);
+    }
+
+    // Violation counters are not supported on every board; skip the ones that fail
+    let policies = [
+        (PerformancePolicy::Thermal, ThrottleCause::Thermal),
+        (PerformancePolicy::Power, ThrottleCause::PowerCap),
+        (PerformancePolicy::SyncBoost, ThrottleCause::SyncBoost),
+        (
+            PerformancePolicy::BoardLimit,
+            ThrottleCause::HardwareSlowdown,
+        ),
+        (PerformancePolicy::Reliability, ThrottleCause::Reliability),
+    ];
+    let violation_time = policies
+        .into_iter()
+        .filter_map(|(policy, cause)| {
+            let time = device.violation_status(policy).ok()?;
+            Some((cause, std::time::Duration::from_nanos(time.violation_time)))
+        })
+        .collect();
+
+    Ok(GpuThrottleStatus {
+        active,
+        violation_time,
+    })
 }
 
 #[cfg(feature = "nvidia")]
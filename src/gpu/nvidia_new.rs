@@ -319,6 +319,17 @@ impl Device for NvidiaGpu {
         Ok(None)
     }
 
+    fn throttle_status(&self) -> Result<crate::throttling::GpuThrottleStatus, Error> {
+        Ok(crate::gpu::nvidia::nvml_throttle_status(&self.device)?)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::probe(self)
+            .with(Capability::SetPowerLimit)
+            .with(Capability::LockClocks)
+            .with(Capability::SetComputeMode)
+    }
+
     // === Control Functions ===
 
     fn set_power_limit(&mut self, watts: f32) -> Result<(), Error> {
@@ -0,0 +1,657 @@
+//! nvidia-smi compatible GPU queries
+//!
+//! Implements `nvidia-smi --query-gpu=...` and `--query-compute-apps=...`
+//! with `--format=csv[,noheader][,nounits]` on top of [`GpuInfo`], so the
+//! same scripts work on NVIDIA, AMD and Intel GPUs. Field names and their
+//! aliases (`name`/`gpu_name`, `clocks.gr`/`clocks.current.graphics`, ...)
+//! follow nvidia-smi, as do the output conventions: `, ` separators, units
+//! in the header (`memory.used [MiB]`) and after values unless `nounits`,
+//! and `[N/A]` for values the GPU does not report.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::gpu::query::{timestamp, Query, QueryFormat};
+//! use simon::gpu::GpuCollection;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let query = Query::gpu("index,name,utilization.gpu,memory.used,power.draw")?;
+//! let format: QueryFormat = "csv,noheader,nounits".parse()?;
+//! let gpus = GpuCollection::auto_detect()?.snapshot_all()?;
+//! print!("{}", query.csv(&gpus, format, &timestamp()));
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{GpuInfo, GpuProcess, GpuProcessType};
+use crate::error::{Result, SimonError};
+use std::str::FromStr;
+
+/// `--format` options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryFormat {
+    /// Print the header line
+    pub header: bool,
+    /// Append units to values
+    pub units: bool,
+}
+
+impl Default for QueryFormat {
+    fn default() -> Self {
+        Self {
+            header: true,
+            units: true,
+        }
+    }
+}
+
+impl FromStr for QueryFormat {
+    type Err = SimonError;
+
+    /// Parse `csv`, optionally followed by `,noheader` and/or `,nounits`
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(',').map(str::trim);
+        if parts.next() != Some("csv") {
+            return Err(SimonError::InvalidValue(format!(
+                "unsupported query format '{}' (expected csv[,noheader][,nounits])",
+                s
+            )));
+        }
+        let mut format = Self::default();
+        for part in parts {
+            match part {
+                "noheader" => format.header = false,
+                "nounits" => format.units = false,
+                other => {
+                    return Err(SimonError::InvalidValue(format!(
+                        "unknown format option '{}'",
+                        other
+                    )))
+                }
+            }
+        }
+        Ok(format)
+    }
+}
+
+/// One queried value
+enum Value {
+    NotAvailable,
+    Text(String),
+    Int(i64),
+    /// Printed with two decimals, like nvidia-smi's power readings
+    Fixed2(f64),
+}
+
+impl Value {
+    fn text(value: Option<&String>) -> Self {
+        value.map_or(Value::NotAvailable, |s| Value::Text(s.clone()))
+    }
+
+    fn int(value: Option<impl Into<i64>>) -> Self {
+        value.map_or(Value::NotAvailable, |v| Value::Int(v.into()))
+    }
+
+    fn render(&self, unit: &str, units: bool) -> String {
+        let number = match self {
+            Value::NotAvailable => return "[N/A]".to_string(),
+            Value::Text(s) => return s.clone(),
+            Value::Int(v) => v.to_string(),
+            Value::Fixed2(v) => format!("{:.2}", v),
+        };
+        if units && !unit.is_empty() {
+            format!("{} {}", number, unit)
+        } else {
+            number
+        }
+    }
+}
+
+/// What a field is evaluated against
+struct Row<'a> {
+    gpu: &'a GpuInfo,
+    process: Option<&'a GpuProcess>,
+    count: usize,
+    timestamp: &'a str,
+}
+
+/// A queryable field: canonical name first, then aliases
+struct Field {
+    names: &'static [&'static str],
+    unit: &'static str,
+    get: fn(&Row<'_>) -> Value,
+}
+
+const MIB: u64 = 1024 * 1024;
+
+static GPU_FIELDS: &[Field] = &[
+    Field {
+        names: &["timestamp"],
+        unit: "",
+        get: |r| Value::Text(r.timestamp.to_string()),
+    },
+    Field {
+        names: &["driver_version"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.driver_version.as_ref()),
+    },
+    Field {
+        names: &["count"],
+        unit: "",
+        get: |r| Value::Int(r.count as i64),
+    },
+    Field {
+        names: &["name", "gpu_name"],
+        unit: "",
+        get: |r| Value::Text(r.gpu.static_info.name.clone()),
+    },
+    Field {
+        names: &["vendor"],
+        unit: "",
+        get: |r| Value::Text(r.gpu.static_info.vendor.to_string()),
+    },
+    Field {
+        names: &["serial", "gpu_serial"],
+        unit: "",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["uuid", "gpu_uuid"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.uuid.as_ref()),
+    },
+    Field {
+        names: &["pci.bus_id", "gpu_bus_id"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.pci_bus_id.as_ref()),
+    },
+    Field {
+        names: &["vbios_version"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.vbios_version.as_ref()),
+    },
+    Field {
+        names: &["index"],
+        unit: "",
+        get: |r| Value::Int(r.gpu.static_info.index as i64),
+    },
+    Field {
+        names: &["compute_cap"],
+        unit: "",
+        get: |r| {
+            r.gpu
+                .static_info
+                .compute_capability
+                .map_or(Value::NotAvailable, |(major, minor)| {
+                    Value::Text(format!("{}.{}", major, minor))
+                })
+        },
+    },
+    Field {
+        names: &["persistence_mode"],
+        unit: "",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["pstate"],
+        unit: "",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["fan.speed"],
+        unit: "%",
+        get: |r| Value::int(r.gpu.dynamic_info.thermal.fan_speed),
+    },
+    Field {
+        names: &["memory.total"],
+        unit: "MiB",
+        get: |r| Value::Int((r.gpu.dynamic_info.memory.total / MIB) as i64),
+    },
+    Field {
+        names: &["memory.reserved"],
+        unit: "MiB",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["memory.used"],
+        unit: "MiB",
+        get: |r| Value::Int((r.gpu.dynamic_info.memory.used / MIB) as i64),
+    },
+    Field {
+        names: &["memory.free"],
+        unit: "MiB",
+        get: |r| Value::Int((r.gpu.dynamic_info.memory.free / MIB) as i64),
+    },
+    Field {
+        names: &["utilization.gpu"],
+        unit: "%",
+        get: |r| Value::Int(r.gpu.dynamic_info.utilization.into()),
+    },
+    Field {
+        names: &["utilization.memory"],
+        unit: "%",
+        get: |r| Value::Int(r.gpu.dynamic_info.memory.utilization.into()),
+    },
+    Field {
+        names: &["utilization.encoder"],
+        unit: "%",
+        get: |r| Value::int(r.gpu.dynamic_info.engines.encoder),
+    },
+    Field {
+        names: &["utilization.decoder"],
+        unit: "%",
+        get: |r| Value::int(r.gpu.dynamic_info.engines.decoder),
+    },
+    Field {
+        names: &["temperature.gpu"],
+        unit: "",
+        get: |r| Value::int(r.gpu.dynamic_info.thermal.temperature),
+    },
+    Field {
+        names: &["temperature.memory"],
+        unit: "",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["power.draw"],
+        unit: "W",
+        get: |r| watts(r.gpu.dynamic_info.power.draw),
+    },
+    Field {
+        names: &["power.limit"],
+        unit: "W",
+        get: |r| watts(r.gpu.dynamic_info.power.limit),
+    },
+    Field {
+        names: &["enforced.power.limit"],
+        unit: "W",
+        get: |r| watts(r.gpu.dynamic_info.power.limit),
+    },
+    Field {
+        names: &["power.default_limit"],
+        unit: "W",
+        get: |r| watts(r.gpu.dynamic_info.power.default_limit),
+    },
+    Field {
+        names: &["power.max_limit"],
+        unit: "W",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["clocks.current.graphics", "clocks.gr"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.graphics),
+    },
+    Field {
+        names: &["clocks.current.sm", "clocks.sm"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.sm),
+    },
+    Field {
+        names: &["clocks.current.memory", "clocks.mem"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.memory),
+    },
+    Field {
+        names: &["clocks.current.video", "clocks.video"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.video),
+    },
+    Field {
+        names: &["clocks.max.graphics", "clocks.max.gr"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.graphics_max),
+    },
+    // SM and graphics clocks share one domain, so their maxima match
+    Field {
+        names: &["clocks.max.sm"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.graphics_max),
+    },
+    Field {
+        names: &["clocks.max.memory", "clocks.max.mem"],
+        unit: "MHz",
+        get: |r| Value::int(r.gpu.dynamic_info.clocks.memory_max),
+    },
+    Field {
+        names: &["pcie.link.gen.current"],
+        unit: "",
+        get: |r| Value::int(r.gpu.dynamic_info.pcie.current_gen),
+    },
+    Field {
+        names: &["pcie.link.gen.max"],
+        unit: "",
+        get: |r| Value::int(r.gpu.dynamic_info.pcie.max_gen),
+    },
+    Field {
+        names: &["pcie.link.width.current"],
+        unit: "",
+        get: |r| Value::int(r.gpu.dynamic_info.pcie.current_width),
+    },
+    Field {
+        names: &["pcie.link.width.max"],
+        unit: "",
+        get: |r| Value::int(r.gpu.dynamic_info.pcie.max_width),
+    },
+];
+
+static APP_FIELDS: &[Field] = &[
+    Field {
+        names: &["timestamp"],
+        unit: "",
+        get: |r| Value::Text(r.timestamp.to_string()),
+    },
+    Field {
+        names: &["gpu_name"],
+        unit: "",
+        get: |r| Value::Text(r.gpu.static_info.name.clone()),
+    },
+    Field {
+        names: &["gpu_bus_id"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.pci_bus_id.as_ref()),
+    },
+    Field {
+        names: &["gpu_serial"],
+        unit: "",
+        get: |_| Value::NotAvailable,
+    },
+    Field {
+        names: &["gpu_uuid"],
+        unit: "",
+        get: |r| Value::text(r.gpu.static_info.uuid.as_ref()),
+    },
+    Field {
+        names: &["pid"],
+        unit: "",
+        get: |r| Value::int(r.process.map(|p| p.pid)),
+    },
+    Field {
+        names: &["process_name", "name"],
+        unit: "",
+        get: |r| Value::text(r.process.map(|p| &p.name)),
+    },
+    Field {
+        names: &["used_gpu_memory", "used_memory"],
+        unit: "MiB",
+        get: |r| {
+            Value::int(
+                r.process
+                    .and_then(|p| p.memory_usage)
+                    .map(|bytes| (bytes / MIB) as i64),
+            )
+        },
+    },
+];
+
+fn watts(milliwatts: Option<u32>) -> Value {
+    milliwatts.map_or(Value::NotAvailable, |mw| Value::Fixed2(mw as f64 / 1000.0))
+}
+
+/// A parsed `--query-gpu` or `--query-compute-apps` field list
+pub struct Query {
+    fields: Vec<&'static Field>,
+    compute_apps: bool,
+}
+
+impl Query {
+    /// Parse a `--query-gpu` field list: one row per GPU
+    pub fn gpu(spec: &str) -> Result<Self> {
+        Self::parse(spec, GPU_FIELDS, false)
+    }
+
+    /// Parse a `--query-compute-apps` field list: one row per compute process
+    pub fn compute_apps(spec: &str) -> Result<Self> {
+        Self::parse(spec, APP_FIELDS, true)
+    }
+
+    fn parse(spec: &str, table: &'static [Field], compute_apps: bool) -> Result<Self> {
+        let fields = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                table
+                    .iter()
+                    .find(|field| field.names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+                    .ok_or_else(|| {
+                        SimonError::InvalidValue(format!(
+                            "Field \"{}\" is not a valid field to query.",
+                            name
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if fields.is_empty() {
+            return Err(SimonError::InvalidValue("no fields to query".to_string()));
+        }
+        Ok(Self {
+            fields,
+            compute_apps,
+        })
+    }
+
+    /// Header line, with units in brackets (`memory.used [MiB]`)
+    pub fn header(&self) -> String {
+        self.fields
+            .iter()
+            .map(|field| {
+                if field.unit.is_empty() {
+                    field.names[0].to_string()
+                } else {
+                    format!("{} [{}]", field.names[0], field.unit)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Data lines for `gpus`
+    ///
+    /// Compute-app queries list every process that is not graphics-only.
+    pub fn rows(&self, gpus: &[GpuInfo], format: QueryFormat, timestamp: &str) -> Vec<String> {
+        let mut rows = Vec::new();
+        for gpu in gpus {
+            let mut row = Row {
+                gpu,
+                process: None,
+                count: gpus.len(),
+                timestamp,
+            };
+            if !self.compute_apps {
+                rows.push(self.render(&row, format));
+                continue;
+            }
+            for process in &gpu.dynamic_info.processes {
+                if process.process_type != GpuProcessType::Graphics {
+                    row.process = Some(process);
+                    rows.push(self.render(&row, format));
+                }
+            }
+        }
+        rows
+    }
+
+    /// Header (unless `noheader`) and rows, newline-terminated
+    pub fn csv(&self, gpus: &[GpuInfo], format: QueryFormat, timestamp: &str) -> String {
+        let mut out = String::new();
+        if format.header {
+            out.push_str(&self.header());
+            out.push('\n');
+        }
+        for row in self.rows(gpus, format, timestamp) {
+            out.push_str(&row);
+            out.push('\n');
+        }
+        out
+    }
+
+    fn render(&self, row: &Row<'_>, format: QueryFormat) -> String {
+        self.fields
+            .iter()
+            .map(|field| (field.get)(row).render(field.unit, format.units))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Current local time in nvidia-smi's `timestamp` format
+pub fn timestamp() -> String {
+    chrono::Local::now()
+        .format("%Y/%m/%d %H:%M:%S%.3f")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gpu::{
+        GpuClocks, GpuDynamicInfo, GpuEngines, GpuMemory, GpuPower, GpuStaticInfo, GpuThermal,
+        GpuVendor, PcieLinkInfo,
+    };
+
+    fn process(pid: u32, name: &str, process_type: GpuProcessType) -> GpuProcess {
+        GpuProcess {
+            pid,
+            name: name.to_string(),
+            user: "root".to_string(),
+            process_type,
+            gpu_usage: None,
+            memory_usage: Some(512 * MIB),
+            memory_usage_percent: None,
+            encoder_usage: None,
+            decoder_usage: None,
+            cpu_usage: None,
+            cpu_memory: None,
+        }
+    }
+
+    fn amd_gpu() -> GpuInfo {
+        GpuInfo {
+            static_info: GpuStaticInfo {
+                index: 1,
+                vendor: GpuVendor::Amd,
+                name: "AMD Instinct MI210".to_string(),
+                pci_bus_id: Some("0000:c1:00.0".to_string()),
+                uuid: None,
+                vbios_version: None,
+                driver_version: Some("6.7.0".to_string()),
+                compute_capability: None,
+                shader_cores: None,
+                l2_cache: None,
+                num_engines: None,
+                integrated: false,
+            },
+            dynamic_info: GpuDynamicInfo {
+                utilization: 87,
+                memory: GpuMemory {
+                    total: 65520 * MIB,
+                    used: 1234 * MIB + 5,
+                    free: 64286 * MIB,
+                    utilization: 2,
+                },
+                clocks: GpuClocks {
+                    graphics: Some(1700),
+                    graphics_max: Some(1700),
+                    memory: Some(1600),
+                    memory_max: Some(1600),
+                    sm: None,
+                    video: None,
+                },
+                power: GpuPower {
+                    draw: Some(250_500),
+                    limit: Some(300_000),
+                    default_limit: None,
+                    usage_percent: None,
+                },
+                thermal: GpuThermal {
+                    temperature: Some(65),
+                    max_temperature: None,
+                    critical_temperature: None,
+                    fan_speed: None,
+                    fan_rpm: None,
+                },
+                pcie: PcieLinkInfo {
+                    current_gen: Some(4),
+                    max_gen: Some(4),
+                    current_width: Some(16),
+                    max_width: Some(16),
+                    current_speed: None,
+                    max_speed: None,
+                    tx_throughput: None,
+                    rx_throughput: None,
+                },
+                engines: GpuEngines {
+                    graphics: None,
+                    compute: None,
+                    encoder: None,
+                    decoder: None,
+                    copy: None,
+                    vendor_specific: Vec::new(),
+                },
+                processes: vec![
+                    process(4242, "python3", GpuProcessType::Compute),
+                    process(999, "Xorg", GpuProcessType::Graphics),
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn test_query_gpu_csv() {
+        let query = Query::gpu(
+            "index,gpu_name,utilization.gpu,memory.used,temperature.gpu,power.draw,fan.speed,clocks.gr",
+        )
+        .unwrap();
+        let gpus = [amd_gpu()];
+
+        assert_eq!(
+            query.csv(&gpus, "csv".parse().unwrap(), ""),
+            "index, name, utilization.gpu [%], memory.used [MiB], temperature.gpu, power.draw [W], fan.speed [%], clocks.current.graphics [MHz]\n\
+             1, AMD Instinct MI210, 87 %, 1234 MiB, 65, 250.50 W, [N/A], 1700 MHz\n"
+        );
+        assert_eq!(
+            query.csv(&gpus, "csv,noheader,nounits".parse().unwrap(), ""),
+            "1, AMD Instinct MI210, 87, 1234, 65, 250.50, [N/A], 1700\n"
+        );
+        assert_eq!(
+            Query::gpu("count, driver_version ,timestamp")
+                .unwrap()
+                .rows(&gpus, QueryFormat::default(), "2024/01/15 10:30:00.123"),
+            vec!["1, 6.7.0, 2024/01/15 10:30:00.123"]
+        );
+    }
+
+    #[test]
+    fn test_query_compute_apps() {
+        let query = Query::compute_apps("pid,process_name,gpu_bus_id,used_memory").unwrap();
+        let format = "csv,noheader".parse().unwrap();
+        assert_eq!(
+            query.rows(&[amd_gpu()], format, ""),
+            vec!["4242, python3, 0000:c1:00.0, 512 MiB"]
+        );
+        assert_eq!(
+            query.header(),
+            "pid, process_name, gpu_bus_id, used_gpu_memory [MiB]"
+        );
+    }
+
+    #[test]
+    fn test_query_errors() {
+        let err = Query::gpu("index,bogus").err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("Field \"bogus\" is not a valid field to query."));
+        assert!(Query::compute_apps("utilization.gpu").is_err());
+        assert!(Query::gpu("").is_err());
+        assert!("json".parse::<QueryFormat>().is_err());
+        assert!("csv,noheader,bogus".parse::<QueryFormat>().is_err());
+        assert_eq!(
+            "csv, nounits".parse::<QueryFormat>().unwrap(),
+            QueryFormat {
+                header: true,
+                units: false
+            }
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! This module defines the common interface that all GPU backends must implement.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::fmt;
 
 /// GPU Vendor
@@ -100,6 +101,21 @@ pub trait Device: Send + Sync {
         Ok(None)
     }
 
+    /// Get current throttle reasons (NVIDIA, AMD)
+    fn throttle_status(&self) -> Result<crate::throttling::GpuThrottleStatus, Error> {
+        Err(Error::NotSupported)
+    }
+
+    // === Capabilities ===
+
+    /// Metrics and controls supported by this device
+    ///
+    /// The default probes each query once. Controls can't be probed without
+    /// side effects, so backends add the ones they implement.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::probe(self)
+    }
+
     // === Control Functions (may require root/admin) ===
 
     /// Set power limit (Watts)
@@ -117,6 +133,26 @@ pub trait Device: Send + Sync {
         Err(Error::NotSupported)
     }
 
+    /// Lock memory clocks to specified frequency range (MHz)
+    fn lock_memory_clocks(&mut self, _min_mhz: u32, _max_mhz: u32) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Offset the voltage curve (millivolts, AMD)
+    fn set_voltage_offset(&mut self, _offset_mv: i32) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Force a performance level, as reported by `performance_state` (AMD)
+    fn set_performance_level(&mut self, _level: &str) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Select a workload power profile by name (AMD)
+    fn set_power_profile(&mut self, _profile: &str) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
     /// Set persistence mode (NVIDIA)
     fn set_persistence_mode(&mut self, _enabled: bool) -> Result<(), Error> {
         Err(Error::NotSupported)
@@ -458,6 +494,206 @@ pub struct EccErrors {
     pub aggregate_double_bit: u64,
 }
 
+/// A metric or control a device may support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Capability {
+    // Metrics
+    PciInfo,
+    Temperature,
+    Power,
+    Clocks,
+    Utilization,
+    EncoderDecoder,
+    Memory,
+    FanSpeed,
+    PerformanceState,
+    Processes,
+    ThrottleStatus,
+    EccErrors,
+    NvLink,
+    Mig,
+    ComputeMode,
+    PersistenceMode,
+    // Controls
+    SetPowerLimit,
+    LockClocks,
+    LockMemoryClocks,
+    SetVoltageOffset,
+    SetPerformanceLevel,
+    SetPowerProfile,
+    SetPersistenceMode,
+    SetComputeMode,
+    SetFanSpeed,
+    KillProcess,
+}
+
+impl Capability {
+    /// Whether this capability changes device state
+    pub fn is_control(&self) -> bool {
+        matches!(
+            self,
+            Capability::SetPowerLimit
+                | Capability::LockClocks
+                | Capability::LockMemoryClocks
+                | Capability::SetVoltageOffset
+                | Capability::SetPerformanceLevel
+                | Capability::SetPowerProfile
+                | Capability::SetPersistenceMode
+                | Capability::SetComputeMode
+                | Capability::SetFanSpeed
+                | Capability::KillProcess
+        )
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::PciInfo => "pci-info",
+            Capability::Temperature => "temperature",
+            Capability::Power => "power",
+            Capability::Clocks => "clocks",
+            Capability::Utilization => "utilization",
+            Capability::EncoderDecoder => "encoder-decoder",
+            Capability::Memory => "memory",
+            Capability::FanSpeed => "fan-speed",
+            Capability::PerformanceState => "performance-state",
+            Capability::Processes => "processes",
+            Capability::ThrottleStatus => "throttle-status",
+            Capability::EccErrors => "ecc-errors",
+            Capability::NvLink => "nvlink",
+            Capability::Mig => "mig",
+            Capability::ComputeMode => "compute-mode",
+            Capability::PersistenceMode => "persistence-mode",
+            Capability::SetPowerLimit => "set-power-limit",
+            Capability::LockClocks => "lock-clocks",
+            Capability::LockMemoryClocks => "lock-memory-clocks",
+            Capability::SetVoltageOffset => "set-voltage-offset",
+            Capability::SetPerformanceLevel => "set-performance-level",
+            Capability::SetPowerProfile => "set-power-profile",
+            Capability::SetPersistenceMode => "set-persistence-mode",
+            Capability::SetComputeMode => "set-compute-mode",
+            Capability::SetFanSpeed => "set-fan-speed",
+            Capability::KillProcess => "kill-process",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Set of capabilities supported by a device
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities(BTreeSet<Capability>);
+
+impl Capabilities {
+    /// Empty capability set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Probe a device's query methods
+    ///
+    /// A metric counts as supported when its query succeeds and returns a
+    /// value. No control capabilities are added.
+    pub fn probe<D: Device + ?Sized>(device: &D) -> Self {
+        let mut caps = Self::new();
+        let mut add = |cap, supported: bool| {
+            if supported {
+                caps.insert(cap);
+            }
+        };
+        add(Capability::PciInfo, device.pci_info().is_ok());
+        add(Capability::Temperature, device.temperature().is_ok());
+        add(Capability::Power, device.power().is_ok());
+        add(Capability::Clocks, device.clocks().is_ok());
+        let utilization = device.utilization().ok();
+        add(Capability::Utilization, utilization.is_some());
+        add(
+            Capability::EncoderDecoder,
+            utilization.is_some_and(|u| u.encoder.is_some() || u.decoder.is_some()),
+        );
+        add(Capability::Memory, device.memory().is_ok());
+        add(
+            Capability::FanSpeed,
+            matches!(device.fan_speed(), Ok(Some(_))),
+        );
+        add(
+            Capability::PerformanceState,
+            matches!(device.performance_state(), Ok(Some(_))),
+        );
+        add(Capability::Processes, device.processes().is_ok());
+        add(Capability::ThrottleStatus, device.throttle_status().is_ok());
+        add(Capability::EccErrors, device.ecc_errors().is_ok());
+        add(
+            Capability::NvLink,
+            device.nvlink_status().is_ok_and(|links| !links.is_empty()),
+        );
+        add(Capability::Mig, device.mig_mode().is_ok());
+        add(
+            Capability::ComputeMode,
+            matches!(device.compute_mode(), Ok(Some(_))),
+        );
+        add(
+            Capability::PersistenceMode,
+            matches!(device.persistence_mode(), Ok(Some(_))),
+        );
+        caps
+    }
+
+    /// Add a capability (builder style)
+    pub fn with(mut self, cap: Capability) -> Self {
+        self.0.insert(cap);
+        self
+    }
+
+    /// Add a capability
+    pub fn insert(&mut self, cap: Capability) {
+        self.0.insert(cap);
+    }
+
+    /// Whether a capability is supported
+    pub fn contains(&self, cap: Capability) -> bool {
+        self.0.contains(&cap)
+    }
+
+    /// All supported capabilities in a stable order
+    pub fn iter(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Supported metrics
+    pub fn metrics(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.iter().filter(|c| !c.is_control())
+    }
+
+    /// Supported controls
+    pub fn controls(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.iter().filter(|c| c.is_control())
+    }
+
+    /// Number of supported capabilities
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether nothing is supported
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<Capability> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = Capability>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self.iter().map(|c| c.to_string()).collect();
+        f.write_str(&names.join(", "))
+    }
+}
+
 // === Error Types ===
 
 #[derive(Debug, thiserror::Error)]
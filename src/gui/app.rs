@@ -14,6 +14,7 @@ use super::widgets::{
 use crate::connections::{ConnectionInfo, ConnectionMonitor, ConnectionState, Protocol};
 use crate::core::cpu::CpuStats;
 use crate::core::memory::MemoryStats;
+use crate::daemon::DaemonClient;
 #[cfg(target_os = "windows")]
 use crate::platform::windows as platform_impl;
 use crate::disk::{self, DiskDevice};
@@ -122,6 +123,9 @@ pub struct SiliconMonitorApp {
     // Background system info loading
     system_info_receiver: Option<Receiver<SystemInfoResult>>,
     system_info_loading: bool,
+
+    // Daemon supplying the data when attached (local collectors are unused)
+    remote: Option<DaemonClient>,
 }
 
 /// Result from background system info loading
@@ -178,12 +182,34 @@ enum ProcessSortColumn {
 
 impl SiliconMonitorApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::with_remote(cc, None)
+    }
+
+    /// Show a running daemon's samples instead of local data
+    pub fn attach(cc: &eframe::CreationContext<'_>, client: DaemonClient) -> Self {
+        Self::with_remote(cc, Some(client))
+    }
+
+    fn with_remote(cc: &eframe::CreationContext<'_>, remote: Option<DaemonClient>) -> Self {
         // Apply cyber theme
         theme::apply_cyber_theme(&cc.egui_ctx);
 
+        let local = remote.is_none();
+        let initial = remote.as_ref().and_then(|client| client.snapshot().ok());
+
         // Initialize monitors
-        let gpu_collection = GpuCollection::auto_detect().ok();
-        let (gpu_static_info, gpu_dynamic_info) = if let Some(ref gpus) = gpu_collection {
+        let gpu_collection = if local {
+            GpuCollection::auto_detect().ok()
+        } else {
+            None
+        };
+        let (gpu_static_info, gpu_dynamic_info) = if let Some(ref snapshot) = initial {
+            snapshot
+                .gpus
+                .iter()
+                .map(|gpu| (gpu.static_info.clone(), gpu.dynamic_info.clone()))
+                .unzip()
+        } else if let Some(ref gpus) = gpu_collection {
             let static_info: Vec<GpuStaticInfo> = gpus
                 .gpus()
                 .iter()
@@ -222,6 +248,17 @@ impl SiliconMonitorApp {
         #[cfg(not(target_os = "windows"))]
         let initial_memory_stats = MemoryStats::new().ok();
 
+        // When attached, size the histories and panels for the remote host
+        let (cpu_core_count, initial_cpu_stats, initial_memory_stats) = match initial {
+            Some(ref snapshot) => (
+                snapshot.cpu.as_ref().map_or(0, |cpu| cpu.cores.len()),
+                snapshot.cpu.clone(),
+                snapshot.memory.clone(),
+            ),
+            None => (cpu_core_count, initial_cpu_stats, initial_memory_stats),
+        };
+        let remote_system = initial.and_then(|snapshot| snapshot.state.system);
+
         let mut app = Self {
             current_tab: Tab::Overview,
             cpu_stats: initial_cpu_stats,
@@ -229,11 +266,15 @@ impl SiliconMonitorApp {
             gpu_collection,
             gpu_static_info,
             gpu_dynamic_info,
-            network_monitor: NetworkMonitor::new().ok(),
-            process_monitor: ProcessMonitor::new().ok(),
+            network_monitor: local.then(|| NetworkMonitor::new().ok()).flatten(),
+            process_monitor: local.then(|| ProcessMonitor::new().ok()).flatten(),
             process_list: Vec::new(),
-            disks: disk::enumerate_disks().unwrap_or_default(),
-            connection_monitor: ConnectionMonitor::new().ok(),
+            disks: if local {
+                disk::enumerate_disks().unwrap_or_default()
+            } else {
+                Vec::new()
+            },
+            connection_monitor: local.then(|| ConnectionMonitor::new().ok()).flatten(),
             connections: Vec::new(),
             connection_filter: String::new(),
             connection_protocol_filter: None,
@@ -271,10 +312,16 @@ impl SiliconMonitorApp {
             last_update: Instant::now(),
             last_slow_update: Instant::now(),
             start_time: Instant::now(),
-            hostname: hostname::get()
-                .map(|h| h.to_string_lossy().to_string())
-                .unwrap_or_else(|_| "unknown".to_string()),
-            os_info: std::env::consts::OS.to_string(),
+            hostname: match remote_system {
+                Some(ref system) => system.hostname.clone(),
+                None => hostname::get()
+                    .map(|h| h.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| "unknown".to_string()),
+            },
+            os_info: match remote_system {
+                Some(ref system) => system.os.clone(),
+                None => std::env::consts::OS.to_string(),
+            },
             process_sort_column: ProcessSortColumn::Cpu,
             process_sort_ascending: false,
             process_filter: String::new(),
@@ -305,6 +352,8 @@ impl SiliconMonitorApp {
             // Background system info loading
             system_info_receiver: None,
             system_info_loading: false,
+
+            remote,
         };
 
         // Initialize history with zeros
@@ -341,6 +390,11 @@ impl SiliconMonitorApp {
     }
 
     fn update_data(&mut self) {
+        if self.remote.is_some() {
+            self.update_remote();
+            return;
+        }
+
         // Update CPU using platform-specific implementation
         #[cfg(target_os = "windows")]
         let cpu_result = platform_impl::read_cpu_stats();
@@ -348,20 +402,7 @@ impl SiliconMonitorApp {
         let cpu_result = CpuStats::new();
 
         if let Ok(stats) = cpu_result {
-            let cpu_usage = 100.0 - stats.total.idle;
-            self.cpu_history.pop_front();
-            self.cpu_history.push_back(cpu_usage);
-
-            // Update per-core history
-            for (i, core) in stats.cores.iter().enumerate() {
-                if i < self.per_core_history.len() {
-                    let util = core.user.unwrap_or(0.0) + core.system.unwrap_or(0.0);
-                    self.per_core_history[i].pop_front();
-                    self.per_core_history[i].push_back(util);
-                }
-            }
-
-            self.cpu_stats = Some(stats);
+            self.record_cpu(stats);
         }
 
         // Update Memory using platform-specific implementation
@@ -371,10 +412,7 @@ impl SiliconMonitorApp {
         let memory_result = MemoryStats::new();
 
         if let Ok(stats) = memory_result {
-            let usage = stats.ram_usage_percent();
-            self.memory_history.pop_front();
-            self.memory_history.push_back(usage);
-            self.memory_stats = Some(stats);
+            self.record_memory(stats);
         }
 
         // Update GPUs
@@ -384,31 +422,7 @@ impl SiliconMonitorApp {
                 .iter()
                 .filter_map(|g| g.dynamic_info().ok())
                 .collect();
-
-            for (i, info) in self.gpu_dynamic_info.iter().enumerate() {
-                if i < self.gpu_history.len() {
-                    self.gpu_history[i].pop_front();
-                    self.gpu_history[i].push_back(info.utilization as f32);
-                }
-
-                // GPU memory usage percentage
-                if i < self.gpu_memory_history.len() {
-                    self.gpu_memory_history[i].pop_front();
-                    let mem_pct = if info.memory.total > 0 {
-                        (info.memory.used as f32 / info.memory.total as f32) * 100.0
-                    } else {
-                        0.0
-                    };
-                    self.gpu_memory_history[i].push_back(mem_pct);
-                }
-
-                // GPU temperature
-                if i < self.gpu_temp_history.len() {
-                    self.gpu_temp_history[i].pop_front();
-                    let temp = info.thermal.temperature.unwrap_or(0) as f32;
-                    self.gpu_temp_history[i].push_back(temp);
-                }
-            }
+            self.record_gpu_history();
         }
 
         // Update Network
@@ -435,8 +449,90 @@ impl SiliconMonitorApp {
         }
     }
 
+    /// Fill the panels from the attached daemon's latest snapshot
+    fn update_remote(&mut self) {
+        let Some(client) = &self.remote else {
+            return;
+        };
+        let Ok(snapshot) = client.snapshot() else {
+            return;
+        };
+        if let Some(stats) = snapshot.cpu {
+            self.record_cpu(stats);
+        }
+        if let Some(stats) = snapshot.memory {
+            self.record_memory(stats);
+        }
+        let (static_info, dynamic_info) = snapshot
+            .gpus
+            .into_iter()
+            .map(|gpu| (gpu.static_info, gpu.dynamic_info))
+            .unzip();
+        self.gpu_static_info = static_info;
+        self.gpu_dynamic_info = dynamic_info;
+        self.record_gpu_history();
+        self.process_list = snapshot.processes;
+        self.connections = snapshot.connections;
+    }
+
+    fn record_cpu(&mut self, stats: CpuStats) {
+        let cpu_usage = 100.0 - stats.total.idle;
+        self.cpu_history.pop_front();
+        self.cpu_history.push_back(cpu_usage);
+
+        // Update per-core history
+        for (i, core) in stats.cores.iter().enumerate() {
+            if i < self.per_core_history.len() {
+                let util = core.user.unwrap_or(0.0) + core.system.unwrap_or(0.0);
+                self.per_core_history[i].pop_front();
+                self.per_core_history[i].push_back(util);
+            }
+        }
+
+        self.cpu_stats = Some(stats);
+    }
+
+    fn record_memory(&mut self, stats: MemoryStats) {
+        let usage = stats.ram_usage_percent();
+        self.memory_history.pop_front();
+        self.memory_history.push_back(usage);
+        self.memory_stats = Some(stats);
+    }
+
+    fn record_gpu_history(&mut self) {
+        for (i, info) in self.gpu_dynamic_info.iter().enumerate() {
+            if i < self.gpu_history.len() {
+                self.gpu_history[i].pop_front();
+                self.gpu_history[i].push_back(info.utilization as f32);
+            }
+
+            // GPU memory usage percentage
+            if i < self.gpu_memory_history.len() {
+                self.gpu_memory_history[i].pop_front();
+                let mem_pct = if info.memory.total > 0 {
+                    (info.memory.used as f32 / info.memory.total as f32) * 100.0
+                } else {
+                    0.0
+                };
+                self.gpu_memory_history[i].push_back(mem_pct);
+            }
+
+            // GPU temperature
+            if i < self.gpu_temp_history.len() {
+                self.gpu_temp_history[i].pop_front();
+                let temp = info.thermal.temperature.unwrap_or(0) as f32;
+                self.gpu_temp_history[i].push_back(temp);
+            }
+        }
+    }
+
     /// Slow update for heavy operations (processes, connections)
     fn update_data_slow(&mut self) {
+        // Processes and connections come with the daemon's snapshot
+        if self.remote.is_some() {
+            return;
+        }
+
         // Update Processes (only if tab is visible or list is empty)
         if self.current_tab == Tab::Processes || self.process_list.is_empty() {
             if let Some(ref mut monitor) = self.process_monitor {
@@ -2408,6 +2504,8 @@ impl SiliconMonitorApp {
                 let proto_color = match conn.protocol {
                     Protocol::Tcp | Protocol::Tcp6 => CyberColors::NEON_BLUE,
                     Protocol::Udp | Protocol::Udp6 => CyberColors::NEON_PURPLE,
+                    Protocol::Sctp | Protocol::Sctp6 => CyberColors::MAGENTA,
+                    Protocol::Unix => CyberColors::TEXT_MUTED,
                 };
 
                 let state_color = match conn.state {
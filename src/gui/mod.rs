@@ -14,6 +14,11 @@ pub use app::SiliconMonitorApp;
 
 /// Run the Silicon Monitor GUI application
 pub fn run() -> Result<(), eframe::Error> {
+    run_with(None)
+}
+
+/// Run the GUI, showing a running `simond`'s data when `remote` is given
+pub fn run_with(remote: Option<crate::daemon::DaemonClient>) -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1400.0, 900.0])
@@ -26,7 +31,12 @@ pub fn run() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Silicon Monitor",
         options,
-        Box::new(|cc| Ok(Box::new(SiliconMonitorApp::new(cc)))),
+        Box::new(move |cc| {
+            Ok(Box::new(match remote {
+                Some(client) => SiliconMonitorApp::attach(cc, client),
+                None => SiliconMonitorApp::new(cc),
+            }))
+        }),
     )
 }
 
@@ -24,7 +24,17 @@ use crate::core::cpu::CpuStats;
 use crate::core::memory::MemoryStats;
 use crate::error::Result;
 use crate::gpu::GpuCollection;
+use crate::hwlog::{EventSeverity, HardwareEvent, HardwareEventKind, HardwareEventMonitor};
+use crate::memory_errors::{ErrorRate, MemoryController, MemoryErrorMonitor};
+use crate::pcie::{endpoint_links, AerRate, PcieDevice, PcieMonitor};
+use crate::rdma::{PortState, RdmaDevice, RdmaMonitor, RdmaPortRate};
+#[cfg(all(target_os = "linux", feature = "systemd"))]
+use crate::services::systemd::ServiceEvents;
+use crate::services::{ServiceInfo, ServiceMonitor, ServiceStatus};
+use crate::throttling::{DeviceThrottle, ThrottleCause, ThrottleDevice, ThrottleMonitor};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Health status level
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -135,6 +145,34 @@ pub struct HealthThresholds {
     pub swap_warning: f32,
     /// Swap usage critical threshold (%)
     pub swap_critical: f32,
+    /// How far back kernel log hardware events count (seconds)
+    #[serde(default = "default_hardware_event_window")]
+    pub hardware_event_window_secs: u64,
+    /// Corrected memory errors per hour per DIMM before warning
+    #[serde(default = "default_memory_ce_rate_warning")]
+    pub memory_ce_rate_warning: f64,
+    /// Corrected memory errors per hour per DIMM before critical
+    #[serde(default = "default_memory_ce_rate_critical")]
+    pub memory_ce_rate_critical: f64,
+    /// Correctable PCIe AER errors per hour per device before warning
+    #[serde(default = "default_pcie_aer_rate_warning")]
+    pub pcie_aer_rate_warning: f64,
+}
+
+fn default_hardware_event_window() -> u64 {
+    3600
+}
+
+fn default_memory_ce_rate_warning() -> f64 {
+    1.0
+}
+
+fn default_memory_ce_rate_critical() -> f64 {
+    10.0
+}
+
+fn default_pcie_aer_rate_warning() -> f64 {
+    10.0
 }
 
 impl Default for HealthThresholds {
@@ -152,6 +190,10 @@ impl Default for HealthThresholds {
             disk_critical: 95.0,
             swap_warning: 50.0,
             swap_critical: 80.0,
+            hardware_event_window_secs: default_hardware_event_window(),
+            memory_ce_rate_warning: default_memory_ce_rate_warning(),
+            memory_ce_rate_critical: default_memory_ce_rate_critical(),
+            pcie_aer_rate_warning: default_pcie_aer_rate_warning(),
         }
     }
 }
@@ -182,7 +224,19 @@ impl SystemHealth {
     }
 
     /// Perform a full system health check with custom thresholds
+    ///
+    /// Kernel log hardware events are skipped, since reading them means
+    /// parsing the whole ring buffer. Use a [`HealthMonitor`] for those and
+    /// for repeated checks.
     pub fn check_with_thresholds(thresholds: &HealthThresholds) -> Result<Self> {
+        HealthMonitor::with_events(None)
+            .with_thresholds(thresholds.clone())
+            .check()
+    }
+
+    /// Run every check, using and updating the monitor's state
+    fn collect(monitor: &mut HealthMonitor) -> Result<Self> {
+        let thresholds = &monitor.thresholds;
         let mut checks = Vec::new();
 
         // CPU Health Check
@@ -283,6 +337,7 @@ impl SystemHealth {
 
         // GPU Health Checks
         if let Ok(gpus) = GpuCollection::auto_detect() {
+            monitor.throttle.update_gpus(&gpus);
             for (idx, gpu) in gpus.gpus().iter().enumerate() {
                 if let Ok(dynamic) = gpu.dynamic_info() {
                     // GPU Temperature
@@ -457,6 +512,69 @@ impl SystemHealth {
             }
         }
 
+        // Throttling (CPU thermal_throttle counters, GPU throttle reasons).
+        // CPU counters only show activity against the previous check's
+        // sample, so a first check has no CPU throttle state to report.
+        let cpu_sampled = monitor.last_check.is_some();
+        let _ = monitor.throttle.update();
+        checks.extend(throttle_checks(monitor.throttle.devices(), cpu_sampled));
+
+        // DRAM ECC error rates from EDAC since the previous check
+        let controllers = monitor.memory.controllers().unwrap_or_default();
+        let memory_rates = monitor
+            .memory
+            .refresh()
+            .ok()
+            .filter(|_| monitor.last_check.is_some());
+        checks.extend(memory_error_checks(
+            &controllers,
+            memory_rates.as_deref(),
+            thresholds,
+        ));
+
+        // PCIe link training, and AER errors since the previous check
+        let pcie_devices = monitor.pcie.devices().unwrap_or_default();
+        let aer_rates = monitor
+            .pcie
+            .refresh()
+            .ok()
+            .filter(|_| monitor.last_check.is_some());
+        checks.extend(pcie_checks(&pcie_devices, aer_rates.as_deref(), thresholds));
+
+        // InfiniBand/RoCE port state, and error counters over recent checks
+        let rdma_devices = monitor.rdma.devices().unwrap_or_default();
+        let rdma_rates = monitor
+            .rdma
+            .refresh()
+            .ok()
+            .filter(|_| monitor.last_check.is_some());
+        checks.extend(rdma_checks(&rdma_devices, rdma_rates.as_deref()));
+
+        // Watched services
+        if let Some(ref mut watched) = monitor.services {
+            let failures = watched.update();
+            checks.extend(match watched.monitor {
+                Some(ref services) => {
+                    service_checks(&watched.names, services.services(), &failures)
+                }
+                None => vec![HealthCheck::new("Services", "Services")
+                    .with_status(HealthStatus::Unknown, "Unable to read service state")],
+            });
+        }
+
+        // Hardware events from the kernel log (Xid, MCE, NVMe resets, ...)
+        if let Some(ref mut events) = monitor.events {
+            // Only messages since the previous check are read and parsed
+            let _ = events.poll();
+            let window = Duration::from_secs(thresholds.hardware_event_window_secs);
+            let mut events = events.recent(window);
+            // EDAC counter deltas above already cover these log lines
+            if memory_rates.is_some() && !controllers.is_empty() {
+                events.retain(|e| !matches!(e.kind, HardwareEventKind::EdacError { .. }));
+            }
+            checks.extend(hardware_event_checks(&events, window));
+        }
+
         // Calculate overall health
         let healthy_count = checks
             .iter()
@@ -496,6 +614,7 @@ impl SystemHealth {
             HealthStatus::Unknown
         };
 
+        monitor.last_check = Some(Instant::now());
         Ok(SystemHealth {
             status: overall_status,
             score: avg_score,
@@ -542,6 +661,627 @@ impl SystemHealth {
     }
 }
 
+/// Health checker that keeps state between checks
+///
+/// [`SystemHealth::check`] starts from scratch each time and leaves the
+/// kernel log out. A monitor opens the kernel log once and only reads new
+/// messages on later checks, and judges error counters by what changed
+/// over recent checks rather than by totals since boot, so it is the one
+/// to use for periodic checks (the daemon keeps one).
+///
+/// # Examples
+///
+/// ```no_run
+/// use simon::health::HealthMonitor;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut monitor = HealthMonitor::new();
+/// loop {
+///     let health = monitor.check()?;
+///     println!("{}", health.summary());
+///     std::thread::sleep(std::time::Duration::from_secs(60));
+/// }
+/// # }
+/// ```
+pub struct HealthMonitor {
+    /// Thresholds for the checks
+    thresholds: HealthThresholds,
+    /// Kernel log hardware events, `None` when the log can't be read
+    events: Option<HardwareEventMonitor>,
+    /// CPU throttle counters and GPU throttle state
+    throttle: ThrottleMonitor,
+    /// EDAC counters from the previous check
+    memory: MemoryErrorMonitor,
+    /// AER counters from the previous check
+    pcie: PcieMonitor,
+    /// RDMA port counters from the previous check
+    rdma: RdmaMonitor,
+    /// Services to alert on, if any
+    services: Option<WatchedServices>,
+    /// Time of the previous check
+    last_check: Option<Instant>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthMonitor {
+    /// Create a monitor with default thresholds
+    ///
+    /// Opens the kernel log and loads the events since boot.
+    pub fn new() -> Self {
+        Self::with_events(HardwareEventMonitor::new().ok())
+    }
+
+    /// Create a monitor with default thresholds and these kernel log events
+    fn with_events(events: Option<HardwareEventMonitor>) -> Self {
+        Self {
+            thresholds: HealthThresholds::default(),
+            events,
+            throttle: ThrottleMonitor::new(),
+            memory: MemoryErrorMonitor::new(),
+            pcie: PcieMonitor::new(),
+            rdma: RdmaMonitor::new(),
+            services: None,
+            last_check: None,
+        }
+    }
+
+    /// Use custom thresholds
+    pub fn with_thresholds(mut self, thresholds: HealthThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Check the state of these services
+    ///
+    /// A failed service is critical and a stopped or missing one warns.
+    /// On Linux with the `systemd` feature the service list is kept current
+    /// from the state changes systemd pushes, so a service that failed and
+    /// was restarted between two checks is still reported; elsewhere it is
+    /// re-read on each check.
+    pub fn with_services(mut self, names: Vec<String>) -> Self {
+        self.services = (!names.is_empty()).then(|| WatchedServices::new(names));
+        self
+    }
+
+    /// Thresholds in use
+    pub fn thresholds(&self) -> &HealthThresholds {
+        &self.thresholds
+    }
+
+    /// Perform a full system health check
+    pub fn check(&mut self) -> Result<SystemHealth> {
+        SystemHealth::collect(self)
+    }
+}
+
+/// Services a [`HealthMonitor`] alerts on
+struct WatchedServices {
+    /// Service names
+    names: Vec<String>,
+    /// Cached service state, `None` until it could be read
+    monitor: Option<ServiceMonitor>,
+    /// State changes pushed by systemd
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    events: Option<ServiceEvents>,
+}
+
+impl WatchedServices {
+    fn new(names: Vec<String>) -> Self {
+        Self {
+            names,
+            monitor: None,
+            #[cfg(all(target_os = "linux", feature = "systemd"))]
+            events: None,
+        }
+    }
+
+    /// Bring the cached state up to date
+    ///
+    /// Returns how many times each service failed since the previous update,
+    /// as far as pushed events tell.
+    fn update(&mut self) -> HashMap<String, usize> {
+        #[cfg_attr(not(all(target_os = "linux", feature = "systemd")), allow(unused_mut))]
+        let mut failures = HashMap::new();
+        let Some(ref mut monitor) = self.monitor else {
+            if let Ok(monitor) = ServiceMonitor::with_filter(self.names.clone()) {
+                #[cfg(all(target_os = "linux", feature = "systemd"))]
+                {
+                    self.events = monitor.watch().ok();
+                }
+                self.monitor = Some(monitor);
+            }
+            return failures;
+        };
+
+        #[cfg(all(target_os = "linux", feature = "systemd"))]
+        if let Some(ref events) = self.events {
+            while let Some(event) = events.try_recv() {
+                // Units with Restart= go through auto-restart instead of failed
+                if event.status == ServiceStatus::Failed
+                    || event.sub_state.as_deref() == Some("auto-restart")
+                {
+                    *failures.entry(event.name.clone()).or_insert(0) += 1;
+                }
+                monitor.apply_event(&event);
+            }
+            return failures;
+        }
+
+        let _ = monitor.refresh();
+        failures
+    }
+}
+
+/// Build one check per watched service
+///
+/// `failures` counts failures since the previous check; a service that
+/// has been restarted since still warns.
+fn service_checks(
+    names: &[String],
+    services: &[ServiceInfo],
+    failures: &HashMap<String, usize>,
+) -> Vec<HealthCheck> {
+    names
+        .iter()
+        .map(|name| {
+            let check = HealthCheck::new(&format!("Service {}", name), "Services");
+            let Some(service) = services.iter().find(|s| s.name == *name) else {
+                return check.with_status(HealthStatus::Warning, &format!("{} not found", name));
+            };
+            let failed = failures.get(name).copied().unwrap_or(0);
+            let (status, mut message) = match service.status {
+                ServiceStatus::Failed => (HealthStatus::Critical, format!("{} has failed", name)),
+                ServiceStatus::Running if failed > 0 => (
+                    HealthStatus::Warning,
+                    format!("{} is running but failed since the last check", name),
+                ),
+                ServiceStatus::Running => (HealthStatus::Healthy, format!("{} is running", name)),
+                ServiceStatus::Starting | ServiceStatus::Stopping => (
+                    HealthStatus::Good,
+                    format!("{} is {}", name, service.status),
+                ),
+                _ => (
+                    HealthStatus::Warning,
+                    format!("{} is {}", name, service.status),
+                ),
+            };
+            if let Some(ref error) = service.error_message {
+                message.push_str(&format!(" ({})", error));
+            }
+            check
+                .with_status(status, &message)
+                .with_value(failed as f64, None)
+        })
+        .collect()
+}
+
+/// Build one check per (event kind, device) seen in the kernel log
+fn hardware_event_checks(events: &[&HardwareEvent], window: Duration) -> Vec<HealthCheck> {
+    if events.is_empty() {
+        return vec![HealthCheck::new("Kernel Log", "System").with_status(
+            HealthStatus::Healthy,
+            &format!(
+                "No hardware errors in the last {} min",
+                window.as_secs() / 60
+            ),
+        )];
+    }
+
+    // Group by kind and device, keeping first-seen order
+    let mut groups: Vec<(String, Vec<&HardwareEvent>)> = Vec::new();
+    for event in events {
+        let name = match event.device {
+            Some(ref device) => format!("{} {}", event.kind.name(), device),
+            None => event.kind.name().to_string(),
+        };
+        match groups.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, group)) => group.push(event),
+            None => groups.push((name, vec![event])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(name, group)| {
+            let worst = group.iter().map(|e| e.severity).max();
+            let status = match worst {
+                Some(EventSeverity::Critical) => HealthStatus::Critical,
+                Some(EventSeverity::Warning) => HealthStatus::Warning,
+                _ => HealthStatus::Good,
+            };
+            let latest = group[group.len() - 1];
+            let mut message = format!(
+                "{} event(s) in the last {} min, latest: {}",
+                group.len(),
+                window.as_secs() / 60,
+                latest.kind
+            );
+            if let Some(pid) = latest.pid {
+                match latest.process {
+                    Some(ref process) => message.push_str(&format!(" ({} pid {})", process, pid)),
+                    None => message.push_str(&format!(" (pid {})", pid)),
+                }
+            }
+
+            HealthCheck::new(&name, latest.kind.category())
+                .with_status(status, &message)
+                .with_value(group.len() as f64, None)
+        })
+        .collect()
+}
+
+/// Build one check per throttled device, plus a summary of CPU throttle history
+///
+/// Hardware slowdowns and power brakes are critical since they point at the
+/// board or power delivery rather than the workload. Other active causes
+/// warn, including CPU packages and cores whose throttle counters moved
+/// since the previous sample; how long a cause has been active is noted.
+/// Without a previous sample (`cpu_sampled` false) CPU devices are only
+/// summarized from their counters since boot.
+fn throttle_checks(devices: &[DeviceThrottle], cpu_sampled: bool) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+
+    for device in devices
+        .iter()
+        .filter(|d| d.is_throttled() && (cpu_sampled || !d.device.is_cpu()))
+    {
+        let status = if device.active.iter().any(|c| {
+            matches!(
+                c,
+                ThrottleCause::HardwareSlowdown | ThrottleCause::PowerBrake
+            )
+        }) {
+            HealthStatus::Critical
+        } else {
+            HealthStatus::Warning
+        };
+        let causes: Vec<&str> = device.active.iter().map(|c| c.name()).collect();
+        let mut message = format!("{} throttled: {}", device.device, causes.join(", "));
+        let streak = device
+            .active
+            .iter()
+            .filter_map(|&c| device.cause(c))
+            .map(|stats| stats.current_streak)
+            .max()
+            .unwrap_or_default();
+        if streak >= Duration::from_secs(1) {
+            message.push_str(&format!(" for {:.0}s", streak.as_secs_f64()));
+        }
+        let category = if device.device.is_cpu() { "CPU" } else { "GPU" };
+        checks.push(
+            HealthCheck::new(&format!("{} Throttling", device.device), category)
+                .with_status(status, &message)
+                .with_value(device.total_time().as_secs_f64(), None),
+        );
+    }
+
+    // CPU counters are cumulative since boot; summarize them once
+    let history = |package: bool| {
+        devices
+            .iter()
+            .filter(|d| matches!(d.device, ThrottleDevice::CpuPackage { .. }) == package)
+            .filter(|d| d.device.is_cpu())
+            .filter_map(|d| d.cause(ThrottleCause::Thermal))
+            .fold((0u64, Duration::ZERO), |(events, time), s| {
+                (events + s.events.unwrap_or(0), time + s.total_time)
+            })
+    };
+    let (package_events, package_time) = history(true);
+    let (core_events, core_time) = history(false);
+    if package_events + core_events > 0 {
+        let mut message = format!(
+            "{} package and {} core thermal throttle events since boot",
+            package_events, core_events
+        );
+        let total = package_time.max(core_time);
+        if !total.is_zero() {
+            message.push_str(&format!(" ({:.1}s throttled)", total.as_secs_f64()));
+        }
+        checks.push(
+            HealthCheck::new("CPU Throttle History", "CPU")
+                .with_status(HealthStatus::Good, &message)
+                .with_value((package_events + core_events) as f64, None),
+        );
+    }
+
+    if checks.is_empty() {
+        let message = if cpu_sampled {
+            "No CPU or GPU throttling detected"
+        } else {
+            "No GPU throttling detected"
+        };
+        checks.push(
+            HealthCheck::new("Throttling", "System").with_status(HealthStatus::Healthy, message),
+        );
+    }
+    checks
+}
+
+/// Build one check per EDAC memory controller, plus one per DIMM with errors
+///
+/// Status comes from the errors within the monitor's rate window (`rates`,
+/// from [`MemoryErrorMonitor::refresh`]): any recent uncorrected error is
+/// critical and corrected error rates are held to the thresholds. Counts
+/// since the counters were reset are only noted, and without a previous
+/// check the kernel log covers recent errors instead.
+fn memory_error_checks(
+    controllers: &[MemoryController],
+    rates: Option<&[ErrorRate]>,
+    thresholds: &HealthThresholds,
+) -> Vec<HealthCheck> {
+    // Recent (corrected, uncorrected) errors and CE/hour of the matching locations
+    let recent = |matches: &dyn Fn(&ErrorRate) -> bool| {
+        rates.map(|rates| {
+            rates
+                .iter()
+                .filter(|r| matches(r))
+                .fold((0, 0, 0.0), |(ce, ue, rate), r| {
+                    (ce + r.ce_window, ue + r.ue_window, rate + r.ce_per_hour)
+                })
+        })
+    };
+    let window = rates
+        .and_then(|rates| rates.first())
+        .map_or(Duration::ZERO, |r| r.window);
+    let status_for = |ce: u64, ue: u64, recent: Option<(u64, u64, f64)>| match recent {
+        Some((_, new_ue, _)) if new_ue > 0 => HealthStatus::Critical,
+        Some((_, _, rate)) if rate >= thresholds.memory_ce_rate_critical => HealthStatus::Critical,
+        Some((_, _, rate)) if rate >= thresholds.memory_ce_rate_warning => HealthStatus::Warning,
+        _ if ce > 0 || ue > 0 => HealthStatus::Good,
+        _ => HealthStatus::Healthy,
+    };
+    let message_for = |what: &str, ce: u64, ue: u64, recent: Option<(u64, u64, f64)>| {
+        let mut message = format!("{}: {} corrected, {} uncorrected since reset", what, ce, ue);
+        if let Some((new_ce, new_ue, rate)) = recent.filter(|&(ce, ue, _)| ce + ue > 0) {
+            message.push_str(&format!(
+                ", {} corrected and {} uncorrected in the last {} min ({:.2} CE/hour)",
+                new_ce,
+                new_ue,
+                window.as_secs().div_ceil(60),
+                rate
+            ));
+        }
+        message
+    };
+    let value_for = |recent: Option<(u64, u64, f64)>| recent.map_or(0.0, |(_, _, rate)| rate);
+
+    let mut checks = Vec::new();
+    for mc in controllers {
+        let mc_recent = recent(&|r| r.controller == mc.index);
+        let what = if mc.mc_name.is_empty() {
+            mc.name.clone()
+        } else {
+            format!("{} ({})", mc.name, mc.mc_name)
+        };
+        checks.push(
+            HealthCheck::new(&format!("ECC {}", mc.name), "Memory")
+                .with_status(
+                    status_for(mc.ce_count, mc.ue_count, mc_recent),
+                    &message_for(&what, mc.ce_count, mc.ue_count, mc_recent),
+                )
+                .with_value(
+                    value_for(mc_recent),
+                    Some(thresholds.memory_ce_rate_warning),
+                ),
+        );
+
+        for dimm in mc.dimms.iter().filter(|d| d.ce_count > 0 || d.ue_count > 0) {
+            let dimm_recent = recent(&|r| r.controller == mc.index && r.dimm == Some(dimm.index));
+            checks.push(
+                HealthCheck::new(&format!("DIMM {}", dimm.label), "Memory")
+                    .with_status(
+                        status_for(dimm.ce_count, dimm.ue_count, dimm_recent),
+                        &message_for(&dimm.label, dimm.ce_count, dimm.ue_count, dimm_recent),
+                    )
+                    .with_value(
+                        value_for(dimm_recent),
+                        Some(thresholds.memory_ce_rate_warning),
+                    ),
+            );
+        }
+    }
+    checks
+}
+
+/// Build one check per degraded PCIe link and per device with AER errors
+///
+/// Width loss is a warning; speed loss alone on a GPU is only noted since
+/// GPUs drop link speed when idle. AER status comes from the errors within
+/// the monitor's rate window (`rates`, from [`PcieMonitor::refresh`]): a
+/// recent fatal error is critical, recent non-fatal errors and correctable
+/// error rates above the threshold warn. Totals since boot are only noted.
+fn pcie_checks(
+    devices: &[PcieDevice],
+    rates: Option<&[AerRate]>,
+    thresholds: &HealthThresholds,
+) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+
+    for device in endpoint_links(devices.to_vec()) {
+        let Some(link) = device
+            .link
+            .as_ref()
+            .filter(|l| l.is_degraded() || l.is_down())
+        else {
+            continue;
+        };
+        let what = format!("{} {}", device.class_name(), device.address);
+        let (status, message) = if link.is_down() {
+            (HealthStatus::Warning, format!("{} link is down", what))
+        } else if link.is_width_degraded() || !device.is_gpu() {
+            (
+                HealthStatus::Warning,
+                format!("{} link degraded: {}", what, link),
+            )
+        } else {
+            (
+                HealthStatus::Good,
+                format!("{} link at {} (GPU may be idle)", what, link),
+            )
+        };
+        checks.push(
+            HealthCheck::new(&format!("PCIe Link {}", device.address), "PCIe")
+                .with_status(status, &message)
+                .with_value(link.current_width.unwrap_or(0) as f64, None),
+        );
+    }
+
+    for device in devices {
+        let Some(aer) = device.aer.as_ref().filter(|a| a.has_errors()) else {
+            continue;
+        };
+        let recent = rates.and_then(|rates| rates.iter().find(|r| r.address == device.address));
+        let status = match recent {
+            Some(r) if r.fatal_window > 0 => HealthStatus::Critical,
+            Some(r)
+                if r.nonfatal_window > 0
+                    || r.correctable_per_hour >= thresholds.pcie_aer_rate_warning =>
+            {
+                HealthStatus::Warning
+            }
+            _ => HealthStatus::Good,
+        };
+        let mut message = format!(
+            "{} {}: {} correctable, {} non-fatal, {} fatal AER errors since boot",
+            device.class_name(),
+            device.address,
+            aer.correctable.total,
+            aer.nonfatal.total,
+            aer.fatal.total
+        );
+        if let Some(r) =
+            recent.filter(|r| r.correctable_window + r.nonfatal_window + r.fatal_window > 0)
+        {
+            message.push_str(&format!(
+                ", {} correctable, {} non-fatal, {} fatal in the last {} min ({:.1} correctable/hour)",
+                r.correctable_window,
+                r.nonfatal_window,
+                r.fatal_window,
+                r.window.as_secs().div_ceil(60),
+                r.correctable_per_hour
+            ));
+        }
+        let top: Vec<String> = aer
+            .fatal
+            .nonzero()
+            .into_iter()
+            .chain(aer.nonfatal.nonzero())
+            .chain(aer.correctable.nonzero())
+            .take(3)
+            .map(|(name, n)| format!("{} {}", name, n))
+            .collect();
+        if !top.is_empty() {
+            message.push_str(&format!(", top: {}", top.join(", ")));
+        }
+        checks.push(
+            HealthCheck::new(&format!("PCIe AER {}", device.address), "PCIe")
+                .with_status(status, &message)
+                .with_value(
+                    recent.map_or(0.0, |r| r.correctable_per_hour),
+                    Some(thresholds.pcie_aer_rate_warning),
+                ),
+        );
+    }
+    checks
+}
+
+/// Build one check per RDMA port that is not cleanly up
+///
+/// Ports without a cable (`Polling`, `Disabled`) are only noted. A trained
+/// link the subnet manager has not activated warns, and so do `link_downed`
+/// events and other error counters that moved within the monitor's rate
+/// window (`rates`, from [`RdmaMonitor::refresh`]). Counts since boot are
+/// only noted.
+fn rdma_checks(devices: &[RdmaDevice], rates: Option<&[RdmaPortRate]>) -> Vec<HealthCheck> {
+    let list = |errors: &mut dyn Iterator<Item = (&str, u64)>| {
+        errors
+            .map(|(counter, n)| format!("{} {}", counter, n))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut checks = Vec::new();
+    for device in devices {
+        for port in &device.ports {
+            let name = format!("{}/{}", device.name, port.port);
+            let errors = port.errors();
+            let recent = rates
+                .and_then(|rates| {
+                    rates
+                        .iter()
+                        .find(|r| r.device == device.name && r.port == port.port)
+                })
+                .filter(|r| !r.recent_errors.is_empty());
+            let minutes = recent.map_or(0, |r| r.window.as_secs().div_ceil(60));
+            let (status, mut message) = if !port.is_link_up() && port.state == PortState::Down {
+                (
+                    HealthStatus::Good,
+                    format!("{} has no link ({})", name, port.phys_state),
+                )
+            } else if !port.state.is_active() {
+                let hint = if port.is_roce() {
+                    ""
+                } else {
+                    " (subnet manager?)"
+                };
+                (
+                    HealthStatus::Warning,
+                    format!(
+                        "{} link is {} but port is {}{}",
+                        name, port.phys_state, port.state, hint
+                    ),
+                )
+            } else if let Some(downed) = recent.and_then(|r| r.recent_errors.get("link_downed")) {
+                (
+                    HealthStatus::Warning,
+                    format!(
+                        "{} {} link went down {} times in the last {} min",
+                        name, port.rate, downed, minutes
+                    ),
+                )
+            } else if recent.is_some() {
+                (
+                    HealthStatus::Warning,
+                    format!(
+                        "{} {} has new errors in the last {} min",
+                        name, port.rate, minutes
+                    ),
+                )
+            } else if !errors.is_empty() {
+                (
+                    HealthStatus::Good,
+                    format!("{} {} has error counts", name, port.rate),
+                )
+            } else {
+                continue;
+            };
+            if let Some(recent) = recent {
+                message.push_str(&format!(
+                    ": {}",
+                    list(&mut recent.recent_errors.iter().map(|(c, &n)| (c.as_str(), n)))
+                ));
+            }
+            if !errors.is_empty() {
+                message.push_str(&format!(
+                    "; since boot: {}",
+                    list(&mut errors.iter().copied())
+                ));
+            }
+            checks.push(
+                HealthCheck::new(&format!("RDMA {}", name), "Network")
+                    .with_status(status, &message)
+                    .with_value(port.error_count() as f64, None),
+            );
+        }
+    }
+    checks
+}
+
 /// Quick health check - returns overall status
 pub fn quick_health_check() -> HealthStatus {
     SystemHealth::check()
@@ -573,6 +1313,404 @@ mod tests {
         assert!(health.score <= 100);
     }
 
+    #[test]
+    fn test_hardware_event_checks() {
+        use crate::hwlog::{classify, LogRecord, LogSource};
+
+        let event = |msg: &str| {
+            let record = LogRecord {
+                message: msg.to_string(),
+                ..Default::default()
+            };
+            classify(&record, LogSource::Kmsg).unwrap()
+        };
+        let xid = event(
+            "NVRM: Xid (PCI:0000:01:00): 79, pid=42, name=python, GPU has fallen off the bus.",
+        );
+        let nvme = event("nvme nvme0: I/O 7 QID 2 timeout, reset controller");
+        let window = Duration::from_secs(3600);
+
+        let checks = hardware_event_checks(&[&xid, &nvme, &nvme], window);
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].name, "Xid 0000:01:00.0");
+        assert_eq!(checks[0].category, "GPU");
+        assert_eq!(checks[0].status, HealthStatus::Critical);
+        assert!(checks[0].message.contains("python pid 42"));
+        assert_eq!(checks[1].status, HealthStatus::Warning);
+        assert_eq!(checks[1].value, Some(2.0));
+
+        let checks = hardware_event_checks(&[], window);
+        assert_eq!(checks[0].status, HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_monitor_keeps_kernel_events() {
+        use crate::hwlog::{LogRecord, LogSource};
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let mut events = HardwareEventMonitor::default();
+        events.ingest(
+            &LogRecord {
+                timestamp_us: Some(now.as_micros() as u64),
+                message: "nvme nvme0: I/O 7 QID 2 timeout, reset controller".to_string(),
+                ..Default::default()
+            },
+            LogSource::Kmsg,
+        );
+
+        let mut monitor = HealthMonitor::new();
+        monitor.events = Some(events);
+        // Events read by an earlier check still count on later ones
+        for _ in 0..2 {
+            let health = monitor.check().unwrap();
+            let nvme = health
+                .checks
+                .iter()
+                .find(|c| c.name.starts_with("NVMe reset"))
+                .unwrap();
+            assert_eq!(nvme.status, HealthStatus::Warning);
+        }
+    }
+
+    #[test]
+    fn test_throttle_checks() {
+        use crate::throttling::{GpuThrottleStatus, ThrottleMonitor};
+
+        let mut monitor = ThrottleMonitor::with_sysfs_root("/nonexistent");
+        let checks = throttle_checks(monitor.devices(), true);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, HealthStatus::Healthy);
+        let checks = throttle_checks(monitor.devices(), false);
+        assert_eq!(checks[0].message, "No GPU throttling detected");
+
+        let status = GpuThrottleStatus {
+            active: vec![ThrottleCause::PowerCap],
+            violation_time: Vec::new(),
+        };
+        monitor.update_gpu(0, "Test GPU", &status);
+        let checks = throttle_checks(monitor.devices(), true);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "GPU 0 (Test GPU) Throttling");
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert!(checks[0].message.contains("power cap"));
+
+        let status = GpuThrottleStatus {
+            active: vec![ThrottleCause::HardwareSlowdown],
+            violation_time: Vec::new(),
+        };
+        monitor.update_gpu(0, "Test GPU", &status);
+        let checks = throttle_checks(monitor.devices(), true);
+        assert_eq!(checks[0].status, HealthStatus::Critical);
+
+        // CPU counters: history alone is only noted, movement warns
+        let dir = tempfile::TempDir::new().unwrap();
+        let throttle = dir.path().join("cpu0/thermal_throttle");
+        std::fs::create_dir_all(&throttle).unwrap();
+        std::fs::write(throttle.join("package_throttle_count"), "0\n").unwrap();
+        std::fs::write(throttle.join("core_throttle_count"), "4\n").unwrap();
+        let mut monitor = ThrottleMonitor::with_sysfs_root(dir.path());
+        monitor.update().unwrap();
+        monitor.update().unwrap();
+        let checks = throttle_checks(monitor.devices(), true);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "CPU Throttle History");
+        assert_eq!(checks[0].status, HealthStatus::Good);
+
+        for count in ["5\n", "6\n"] {
+            std::fs::write(throttle.join("core_throttle_count"), count).unwrap();
+            monitor.update().unwrap();
+        }
+        let checks = throttle_checks(monitor.devices(), true);
+        assert_eq!(checks[0].category, "CPU");
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert!(checks[0].message.ends_with("throttled: thermal"));
+
+        // Without a previous check's sample only the history is reported
+        let checks = throttle_checks(monitor.devices(), false);
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].name, "CPU Throttle History");
+    }
+
+    #[test]
+    fn test_memory_error_checks() {
+        use crate::memory_errors::Dimm;
+
+        let thresholds = HealthThresholds::default();
+        let mc = MemoryController {
+            name: "mc0".to_string(),
+            ce_count: 40,
+            seconds_since_reset: Some(7200),
+            dimms: vec![
+                Dimm {
+                    label: "CPU0_DIMM_A1".to_string(),
+                    ce_count: 40,
+                    ..Default::default()
+                },
+                Dimm {
+                    index: 1,
+                    label: "CPU0_DIMM_B1".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // First check: counts since reset are only noted
+        let checks = memory_error_checks(std::slice::from_ref(&mc), None, &thresholds);
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].name, "ECC mc0");
+        assert_eq!(checks[0].status, HealthStatus::Good);
+        assert_eq!(checks[1].name, "DIMM CPU0_DIMM_A1");
+        assert_eq!(checks[1].status, HealthStatus::Good);
+        assert_eq!(checks[1].value, Some(0.0));
+
+        let rate = |dimm: Option<u32>, ce: u64, ue: u64| ErrorRate {
+            location: String::new(),
+            controller: 0,
+            dimm,
+            ce_delta: 0,
+            ue_delta: 0,
+            ce_window: ce,
+            ue_window: ue,
+            ce_per_hour: ce as f64 * 4.0,
+            ue_per_hour: ue as f64 * 4.0,
+            interval: Duration::from_secs(10),
+            window: Duration::from_secs(300),
+        };
+
+        // Nothing within the rate window
+        let rates = [rate(None, 0, 0), rate(Some(0), 0, 0), rate(Some(1), 0, 0)];
+        let checks = memory_error_checks(std::slice::from_ref(&mc), Some(&rates), &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Good);
+        assert!(!checks[1].message.contains("last"));
+
+        // 3 corrected errors within the window is 12/hour
+        let rates = [rate(None, 0, 0), rate(Some(0), 3, 0), rate(Some(1), 0, 0)];
+        let checks = memory_error_checks(std::slice::from_ref(&mc), Some(&rates), &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Critical);
+        assert_eq!(checks[1].status, HealthStatus::Critical);
+        assert_eq!(checks[1].value, Some(12.0));
+        assert!(checks[1]
+            .message
+            .contains("3 corrected and 0 uncorrected in the last 5 min"));
+
+        // Errors the driver could not attribute count for the controller only
+        let rates = [rate(None, 1, 0), rate(Some(0), 0, 0), rate(Some(1), 0, 0)];
+        let checks = memory_error_checks(std::slice::from_ref(&mc), Some(&rates), &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert_eq!(checks[1].status, HealthStatus::Good);
+
+        let quiet = MemoryController {
+            ce_count: 3,
+            dimms: Vec::new(),
+            ..mc.clone()
+        };
+        let rates = [ErrorRate {
+            ce_per_hour: 2.0,
+            ..rate(None, 1, 0)
+        }];
+        let checks = memory_error_checks(&[quiet], Some(&rates), &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+
+        let uncorrected = MemoryController {
+            ce_count: 0,
+            ue_count: 1,
+            dimms: Vec::new(),
+            ..mc
+        };
+        let checks = memory_error_checks(std::slice::from_ref(&uncorrected), None, &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Good);
+        let rates = [rate(None, 0, 1)];
+        let checks = memory_error_checks(&[uncorrected], Some(&rates), &thresholds);
+        assert_eq!(checks[0].status, HealthStatus::Critical);
+    }
+
+    #[test]
+    fn test_pcie_checks() {
+        use crate::pcie::{AerCounter, AerCounters, PcieLink};
+
+        let thresholds = HealthThresholds::default();
+        let link = |cur: (f64, u8)| PcieLink {
+            current_speed: Some(cur.0),
+            current_width: Some(cur.1),
+            max_speed: Some(16.0),
+            max_width: Some(16),
+            ..Default::default()
+        };
+        let device = |address: &str, class: u32, link: PcieLink| PcieDevice {
+            address: address.to_string(),
+            class,
+            vendor_id: 0x10de,
+            device_id: 0x2330,
+            driver: None,
+            slot: None,
+            parent: None,
+            link: Some(link),
+            aer: None,
+        };
+        let mut nic = device("0000:02:00.0", 0x020000, link((16.0, 16)));
+        nic.aer = Some(AerCounters {
+            correctable: AerCounter::parse("BadTLP 400\nTOTAL_ERR_COR 400\n"),
+            ..Default::default()
+        });
+        let devices = vec![
+            device("0000:01:00.0", 0x030200, link((16.0, 8))),
+            device("0000:01:00.1", 0x040300, link((16.0, 8))),
+            device("0000:03:00.0", 0x030200, link((2.5, 16))),
+            nic,
+        ];
+
+        // First check: AER totals since boot are only noted
+        let checks = pcie_checks(&devices, None, &thresholds);
+        assert_eq!(checks.len(), 3);
+        assert_eq!(checks[0].name, "PCIe Link 0000:01:00.0");
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert!(checks[0].message.contains("Gen4 x8 (capable Gen4 x16)"));
+        // Idle GPU at Gen1 is not a warning
+        assert_eq!(checks[1].status, HealthStatus::Good);
+        assert_eq!(checks[2].name, "PCIe AER 0000:02:00.0");
+        assert_eq!(checks[2].status, HealthStatus::Good);
+        assert!(checks[2].message.contains("BadTLP 400"));
+
+        let rate = |correctable: u64, nonfatal: u64, fatal: u64| AerRate {
+            address: "0000:02:00.0".to_string(),
+            correctable_delta: 0,
+            nonfatal_delta: 0,
+            fatal_delta: 0,
+            correctable_window: correctable,
+            nonfatal_window: nonfatal,
+            fatal_window: fatal,
+            correctable_per_hour: correctable as f64 * 4.0,
+            interval: Duration::from_secs(10),
+            window: Duration::from_secs(300),
+        };
+        let aer_status =
+            |rate: AerRate| pcie_checks(&devices, Some(&[rate]), &thresholds)[2].clone();
+        let check = aer_status(rate(0, 0, 0));
+        assert_eq!(check.status, HealthStatus::Good);
+        assert!(!check.message.contains("last"));
+        // One error within the window stays below the threshold
+        assert_eq!(aer_status(rate(1, 0, 0)).status, HealthStatus::Good);
+        let check = aer_status(rate(3, 0, 0));
+        assert_eq!(check.status, HealthStatus::Warning);
+        assert_eq!(check.value, Some(12.0));
+        assert!(check
+            .message
+            .contains("3 correctable, 0 non-fatal, 0 fatal in the last 5 min"));
+        assert_eq!(aer_status(rate(0, 1, 0)).status, HealthStatus::Warning);
+        assert_eq!(aer_status(rate(0, 0, 1)).status, HealthStatus::Critical);
+    }
+
+    #[test]
+    fn test_rdma_checks() {
+        use crate::rdma::RdmaPort;
+        use std::collections::BTreeMap;
+
+        let port = |number: u32, state: PortState, phys: &str, downed: u64| RdmaPort {
+            port: number,
+            state,
+            phys_state: phys.to_string(),
+            rate: "200 Gb/sec (4X HDR)".to_string(),
+            link_layer: "InfiniBand".to_string(),
+            counters: [("link_downed".to_string(), downed)].into_iter().collect(),
+            ..Default::default()
+        };
+        let device = RdmaDevice {
+            name: "mlx5_0".to_string(),
+            ports: vec![
+                port(1, PortState::Active, "LinkUp", 0),
+                port(2, PortState::Init, "LinkUp", 0),
+                port(3, PortState::Active, "LinkUp", 2),
+                port(4, PortState::Down, "Polling", 0),
+            ],
+            ..Default::default()
+        };
+
+        // Flaps since boot are only noted
+        let checks = rdma_checks(std::slice::from_ref(&device), None);
+        assert_eq!(checks.len(), 3);
+        assert_eq!(checks[0].name, "RDMA mlx5_0/2");
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert!(checks[0].message.contains("subnet manager"));
+        assert_eq!(checks[1].status, HealthStatus::Good);
+        assert!(checks[1].message.contains("since boot: link_downed 2"));
+        assert_eq!(checks[2].status, HealthStatus::Good);
+
+        let rate = |port: u32, recent: &[(&str, u64)]| RdmaPortRate {
+            device: "mlx5_0".to_string(),
+            port,
+            rx_bytes_per_sec: 0.0,
+            tx_bytes_per_sec: 0.0,
+            utilization: None,
+            error_delta: 0,
+            counter_rates: BTreeMap::new(),
+            recent_errors: recent.iter().map(|&(c, n)| (c.to_string(), n)).collect(),
+            interval: Duration::from_secs(10),
+            window: Duration::from_secs(600),
+        };
+        let checks = rdma_checks(std::slice::from_ref(&device), Some(&[rate(3, &[])]));
+        assert_eq!(checks[1].status, HealthStatus::Good);
+
+        // A flap within the window warns
+        let rates = [rate(3, &[("link_downed", 1)])];
+        let checks = rdma_checks(std::slice::from_ref(&device), Some(&rates));
+        assert_eq!(checks[1].status, HealthStatus::Warning);
+        assert!(checks[1]
+            .message
+            .contains("went down 1 times in the last 10 min"));
+
+        // So do new errors on a clean port
+        let rates = [rate(1, &[("symbol_error", 4)])];
+        let checks = rdma_checks(&[device], Some(&rates));
+        assert_eq!(checks[0].name, "RDMA mlx5_0/1");
+        assert_eq!(checks[0].status, HealthStatus::Warning);
+        assert!(checks[0]
+            .message
+            .ends_with("in the last 10 min: symbol_error 4"));
+    }
+
+    #[test]
+    fn test_service_checks() {
+        let service = |name: &str, status: ServiceStatus| ServiceInfo {
+            status,
+            ..ServiceInfo::new(name)
+        };
+        let mut nginx = service("nginx", ServiceStatus::Failed);
+        nginx.error_message = Some("exit-code".to_string());
+        let services = [
+            nginx,
+            service("sshd", ServiceStatus::Running),
+            service("nginx-exporter", ServiceStatus::Running),
+            service("cron", ServiceStatus::Stopped),
+        ];
+        let names: Vec<String> = ["nginx", "sshd", "cron", "redis"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let checks = service_checks(&names, &services, &HashMap::new());
+        let statuses: Vec<HealthStatus> = checks.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                HealthStatus::Critical,
+                HealthStatus::Healthy,
+                HealthStatus::Warning,
+                HealthStatus::Warning,
+            ]
+        );
+        assert_eq!(checks[0].name, "Service nginx");
+        assert_eq!(checks[0].message, "nginx has failed (exit-code)");
+        assert_eq!(checks[3].message, "redis not found");
+
+        // Failed and restarted between two checks
+        let failures = [("sshd".to_string(), 2)].into_iter().collect();
+        let checks = service_checks(&names, &services, &failures);
+        assert_eq!(checks[1].status, HealthStatus::Warning);
+        assert_eq!(checks[1].value, Some(2.0));
+    }
+
     #[test]
     fn test_thresholds() {
         let thresholds = HealthThresholds::default();
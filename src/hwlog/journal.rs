@@ -0,0 +1,177 @@
+//! systemd journal export format parser
+//!
+//! Parses the output of `journalctl -o export`: entries separated by an
+//! empty line, each field either `KEY=value\n` or, for values containing
+//! newlines or binary data, `KEY\n` followed by a little-endian 64-bit
+//! length, the raw value and a trailing newline.
+//!
+//! Only kernel entries (`_TRANSPORT=kernel`) are returned.
+
+use super::LogRecord;
+use crate::error::{Result, SimonError};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// Iterator over kernel records in a journal export stream
+pub struct JournalExportReader<R> {
+    reader: R,
+    last_cursor: Option<String>,
+    done: bool,
+}
+
+impl<R: BufRead> JournalExportReader<R> {
+    /// Wrap a buffered reader over export-format data
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            last_cursor: None,
+            done: false,
+        }
+    }
+
+    /// Cursor of the last entry read (kernel or not)
+    pub fn last_cursor(&self) -> Option<&str> {
+        self.last_cursor.as_deref()
+    }
+
+    /// Read the next entry's fields, or None at end of input
+    fn read_entry(&mut self) -> Result<Option<HashMap<String, Vec<u8>>>> {
+        let mut fields = HashMap::new();
+        let mut line = Vec::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_until(b'\n', &mut line)? == 0 {
+                return Ok((!fields.is_empty()).then_some(fields));
+            }
+            if line.last() == Some(&b'\n') {
+                line.pop();
+            }
+            if line.is_empty() {
+                if fields.is_empty() {
+                    continue;
+                }
+                return Ok(Some(fields));
+            }
+
+            if let Some(eq) = line.iter().position(|&b| b == b'=') {
+                let key = String::from_utf8_lossy(&line[..eq]).into_owned();
+                fields.insert(key, line[eq + 1..].to_vec());
+            } else {
+                // Binary-safe field: KEY\n<u64 LE size><data>\n
+                let key = String::from_utf8_lossy(&line).into_owned();
+                let mut size = [0u8; 8];
+                self.reader.read_exact(&mut size)?;
+                let size = u64::from_le_bytes(size) as usize;
+                let mut data = vec![0u8; size];
+                self.reader.read_exact(&mut data)?;
+                let mut newline = [0u8; 1];
+                self.reader.read_exact(&mut newline)?;
+                if newline[0] != b'\n' {
+                    return Err(SimonError::Parse(format!(
+                        "journal export: missing newline after binary field {}",
+                        key
+                    )));
+                }
+                fields.insert(key, data);
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for JournalExportReader<R> {
+    type Item = Result<LogRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let fields = match self.read_entry() {
+                Ok(Some(fields)) => fields,
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            if let Some(cursor) = field_str(&fields, "__CURSOR") {
+                self.last_cursor = Some(cursor);
+            }
+            if let Some(record) = record_from_fields(&fields) {
+                return Some(Ok(record));
+            }
+        }
+        None
+    }
+}
+
+/// Build a record from a kernel entry's fields
+fn record_from_fields(fields: &HashMap<String, Vec<u8>>) -> Option<LogRecord> {
+    if field_str(fields, "_TRANSPORT")? != "kernel" {
+        return None;
+    }
+
+    Some(LogRecord {
+        priority: field_str(fields, "PRIORITY")
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(6),
+        uptime_us: field_str(fields, "__MONOTONIC_TIMESTAMP").and_then(|t| t.parse().ok()),
+        timestamp_us: field_str(fields, "__REALTIME_TIMESTAMP").and_then(|t| t.parse().ok()),
+        message: field_str(fields, "MESSAGE")?,
+        subsystem: field_str(fields, "_KERNEL_SUBSYSTEM"),
+        device: field_str(fields, "_KERNEL_DEVICE"),
+        pid: field_str(fields, "_PID").and_then(|p| p.parse().ok()),
+    })
+}
+
+fn field_str(fields: &HashMap<String, Vec<u8>>, key: &str) -> Option<String> {
+    fields
+        .get(key)
+        .map(|v| String::from_utf8_lossy(v).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export() {
+        let mut data = Vec::new();
+        data.extend_from_slice(
+            b"__CURSOR=s=abc;i=1\n__REALTIME_TIMESTAMP=1700000000000000\n\
+              __MONOTONIC_TIMESTAMP=5000000\n_TRANSPORT=kernel\nPRIORITY=4\n\
+              _KERNEL_DEVICE=+pci:0000:01:00.0\n_KERNEL_SUBSYSTEM=pci\n\
+              MESSAGE=NVRM: Xid (PCI:0000:01:00): 79, pid=1234, name=python3, GPU has fallen off the bus.\n\n",
+        );
+        // Userspace entry, skipped
+        data.extend_from_slice(b"__CURSOR=s=abc;i=2\n_TRANSPORT=journal\nMESSAGE=hello\n\n");
+        // Binary-safe MESSAGE field
+        let msg = b"line one\nline two";
+        data.extend_from_slice(b"__CURSOR=s=abc;i=3\n_TRANSPORT=kernel\nMESSAGE\n");
+        data.extend_from_slice(&(msg.len() as u64).to_le_bytes());
+        data.extend_from_slice(msg);
+        data.extend_from_slice(b"\n\n");
+
+        let mut reader = JournalExportReader::new(&data[..]);
+        let records: Vec<LogRecord> = reader.by_ref().map(|r| r.unwrap()).collect();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].priority, 4);
+        assert_eq!(records[0].timestamp_us, Some(1_700_000_000_000_000));
+        assert_eq!(records[0].uptime_us, Some(5_000_000));
+        assert_eq!(records[0].device.as_deref(), Some("+pci:0000:01:00.0"));
+        assert!(records[0].message.starts_with("NVRM: Xid"));
+        assert_eq!(records[1].message, "line one\nline two");
+        assert_eq!(reader.last_cursor(), Some("s=abc;i=3"));
+    }
+
+    #[test]
+    fn test_truncated_binary_field() {
+        let data = b"_TRANSPORT=kernel\nMESSAGE\n\x10\x00";
+        let mut reader = JournalExportReader::new(&data[..]);
+        assert!(matches!(reader.next(), Some(Err(_))));
+        assert!(reader.next().is_none());
+    }
+}
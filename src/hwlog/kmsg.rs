@@ -0,0 +1,193 @@
+//! `/dev/kmsg` reader
+//!
+//! Each `read()` on `/dev/kmsg` returns one record:
+//!
+//! ```text
+//! 3,1234,56789012,-;nvme nvme0: I/O 12 QID 3 timeout, reset controller
+//!  SUBSYSTEM=nvme
+//!  DEVICE=c241:0
+//! ```
+//!
+//! i.e. `priority,sequence,timestamp_us,flags;message` followed by optional
+//! ` KEY=value` continuation lines. The file is opened non-blocking so
+//! [`KmsgReader::read_available`] returns as soon as the buffer is drained.
+
+use super::LogRecord;
+use crate::error::{Result, SimonError};
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const KMSG_PATH: &str = "/dev/kmsg";
+
+/// Records are at most ~1 KiB of text plus the device dictionary
+const RECORD_BUFFER_SIZE: usize = 8192;
+
+/// Non-blocking reader for the kernel ring buffer
+pub struct KmsgReader {
+    file: File,
+    /// Wall-clock boot time (microseconds since the Unix epoch)
+    boot_time_us: Option<u64>,
+    /// Sequence number of the last record read
+    last_seq: Option<u64>,
+}
+
+impl KmsgReader {
+    /// Open `/dev/kmsg`, positioned at the oldest record still buffered
+    pub fn open() -> Result<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(KMSG_PATH)
+            .map_err(|e| match e.kind() {
+                ErrorKind::PermissionDenied => SimonError::PermissionDenied(format!(
+                    "{} (requires CAP_SYSLOG when kernel.dmesg_restrict=1)",
+                    KMSG_PATH
+                )),
+                _ => SimonError::Io(e),
+            })?;
+
+        Ok(Self {
+            file,
+            boot_time_us: boot_time_us(),
+            last_seq: None,
+        })
+    }
+
+    /// Read every record currently available
+    pub fn read_available(&mut self) -> Result<Vec<LogRecord>> {
+        let mut records = Vec::new();
+        let mut buf = vec![0u8; RECORD_BUFFER_SIZE];
+
+        loop {
+            match self.file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let text = String::from_utf8_lossy(&buf[..n]);
+                    if let Some((seq, mut record)) = parse_record(&text) {
+                        self.last_seq = Some(seq);
+                        record.timestamp_us = self
+                            .boot_time_us
+                            .zip(record.uptime_us)
+                            .map(|(boot, up)| boot + up);
+                        records.push(record);
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                // EPIPE: records were overwritten before we read them; the next
+                // read continues at the oldest remaining record
+                Err(e) if e.raw_os_error() == Some(libc::EPIPE) => continue,
+                Err(e) => return Err(SimonError::Io(e)),
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Sequence number of the last record read
+    pub fn last_sequence(&self) -> Option<u64> {
+        self.last_seq
+    }
+}
+
+/// Parse one `/dev/kmsg` record, returning its sequence number
+pub fn parse_record(text: &str) -> Option<(u64, LogRecord)> {
+    let (header, body) = text.split_once(';')?;
+    let mut fields = header.split(',');
+    let prefix: u32 = fields.next()?.parse().ok()?;
+    let seq: u64 = fields.next()?.parse().ok()?;
+    let uptime_us: u64 = fields.next()?.parse().ok()?;
+
+    let mut lines = body.split('\n');
+    let mut record = LogRecord {
+        priority: (prefix & 7) as u8,
+        uptime_us: Some(uptime_us),
+        message: unescape(lines.next().unwrap_or_default()),
+        ..Default::default()
+    };
+
+    for line in lines {
+        let Some((key, value)) = line.strip_prefix(' ').and_then(|l| l.split_once('=')) else {
+            continue;
+        };
+        match key {
+            "SUBSYSTEM" => record.subsystem = Some(value.to_string()),
+            "DEVICE" => record.device = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    Some((seq, record))
+}
+
+/// Undo the kernel's `\xNN` escaping of non-printable bytes
+fn unescape(msg: &str) -> String {
+    if !msg.contains("\\x") {
+        return msg.to_string();
+    }
+
+    let bytes = msg.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'x') && i + 3 < bytes.len() {
+            if let Some(b) = std::str::from_utf8(&bytes[i + 2..i + 4])
+                .ok()
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                out.push(b);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Estimate wall-clock boot time from `/proc/uptime` (like `dmesg -T`)
+///
+/// kmsg timestamps stop during suspend, so converted times drift after a
+/// suspend/resume cycle.
+fn boot_time_us() -> Option<u64> {
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    let uptime_secs: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    let now_us = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_micros() as u64;
+    now_us.checked_sub((uptime_secs * 1_000_000.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record() {
+        let text = "3,1234,56789012,-;nvme nvme0: I/O 12 QID 3 timeout, reset controller\n SUBSYSTEM=nvme\n DEVICE=c241:0\n";
+        let (seq, record) = parse_record(text).unwrap();
+        assert_eq!(seq, 1234);
+        assert_eq!(record.priority, 3);
+        assert_eq!(record.uptime_us, Some(56_789_012));
+        assert_eq!(
+            record.message,
+            "nvme nvme0: I/O 12 QID 3 timeout, reset controller"
+        );
+        assert_eq!(record.subsystem.as_deref(), Some("nvme"));
+        assert_eq!(record.device.as_deref(), Some("c241:0"));
+    }
+
+    #[test]
+    fn test_parse_record_facility_and_escapes() {
+        // facility 1 (user), level 6 (info)
+        let (_, record) = parse_record("14,7,100,-;tab\\x09here\n").unwrap();
+        assert_eq!(record.priority, 6);
+        assert_eq!(record.message, "tab\there");
+        assert!(parse_record("garbage").is_none());
+    }
+}
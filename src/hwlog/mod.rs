@@ -0,0 +1,1146 @@
+//! Kernel log and journal correlation for hardware events
+//!
+//! Reads kernel messages from `/dev/kmsg` (falling back to `journalctl -k`)
+//! or from journal export files, and classifies the lines that point at
+//! hardware trouble:
+//!
+//! - NVIDIA Xid errors
+//! - amdgpu ring timeouts
+//! - i915 GPU hangs
+//! - Machine check (MCE) and EDAC memory errors
+//! - NVMe controller resets and timeouts
+//! - Thermal throttling
+//! - OOM kills
+//!
+//! Each [`HardwareEvent`] carries the affected device (PCI bus id, disk,
+//! CPU or memory controller) and the PID involved, when the kernel reports
+//! one, so it can be lined up with GPU and process data.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::hwlog::HardwareEventMonitor;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = HardwareEventMonitor::new()?;
+//!
+//! for event in monitor.recent(Duration::from_secs(3600)) {
+//!     println!("[{}] {} {:?}: {}", event.severity, event.kind,
+//!         event.device, event.message);
+//! }
+//!
+//! // Later: pick up new kernel messages
+//! let new_events = monitor.poll()?;
+//! println!("{} new hardware events", new_events);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Offline analysis of a journal export (`journalctl -o export > boot.export`):
+//!
+//! ```no_run
+//! use simon::hwlog;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! for event in hwlog::journal_events("boot.export")? {
+//!     if let Some(pid) = event.pid {
+//!         println!("{} (pid {}): {}", event.kind, pid, event.message);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod journal;
+#[cfg(target_os = "linux")]
+pub mod kmsg;
+
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub use journal::JournalExportReader;
+
+/// Default number of events kept by [`HardwareEventMonitor`]
+pub const DEFAULT_EVENT_CAPACITY: usize = 512;
+
+/// Where a log record came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogSource {
+    /// Kernel ring buffer (`/dev/kmsg`)
+    Kmsg,
+    /// systemd journal (export format)
+    Journal,
+}
+
+/// A single kernel log record
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogRecord {
+    /// Syslog priority (0 = emerg ... 7 = debug)
+    pub priority: u8,
+    /// Time since boot (microseconds)
+    pub uptime_us: Option<u64>,
+    /// Wall-clock time (microseconds since the Unix epoch)
+    pub timestamp_us: Option<u64>,
+    /// Message text
+    pub message: String,
+    /// Kernel subsystem (e.g. "pci", "block")
+    pub subsystem: Option<String>,
+    /// Kernel device id (e.g. "+pci:0000:01:00.0", "b259:0")
+    pub device: Option<String>,
+    /// Originating PID (journal `_PID`)
+    pub pid: Option<u32>,
+}
+
+/// Kind of hardware event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HardwareEventKind {
+    /// NVIDIA Xid error
+    NvidiaXid {
+        /// Xid code
+        code: u32,
+    },
+    /// amdgpu ring (command queue) timeout
+    AmdgpuRingTimeout {
+        /// Ring name (e.g. "gfx_0.0.0", "sdma0")
+        ring: String,
+    },
+    /// Intel i915/xe GPU hang or engine reset
+    I915GpuHang {
+        /// Engine that hung (e.g. "rcs0"), if reported
+        engine: Option<String>,
+    },
+    /// Machine check exception
+    MachineCheck {
+        /// MCA bank, if reported
+        bank: Option<u32>,
+    },
+    /// EDAC memory controller error
+    EdacError {
+        /// Corrected (CE) or uncorrected (UE)
+        corrected: bool,
+        /// Number of errors reported by this line
+        count: u32,
+        /// DIMM/channel location, if reported
+        location: Option<String>,
+    },
+    /// NVMe controller reset or command timeout
+    NvmeReset,
+    /// CPU or thermal zone throttling
+    ThermalThrottle {
+        /// "core", "package" or a thermal zone name
+        zone: String,
+    },
+    /// Process killed by the OOM killer
+    OomKill {
+        /// Killed by a memory cgroup limit rather than global OOM
+        cgroup: bool,
+    },
+}
+
+impl HardwareEventKind {
+    /// Short name without event details (e.g. "Xid", "NVMe reset")
+    pub fn name(&self) -> &'static str {
+        match self {
+            HardwareEventKind::NvidiaXid { .. } => "Xid",
+            HardwareEventKind::AmdgpuRingTimeout { .. } => "Ring timeout",
+            HardwareEventKind::I915GpuHang { .. } => "GPU hang",
+            HardwareEventKind::MachineCheck { .. } => "Machine check",
+            HardwareEventKind::EdacError { .. } => "EDAC error",
+            HardwareEventKind::NvmeReset => "NVMe reset",
+            HardwareEventKind::ThermalThrottle { .. } => "Thermal throttle",
+            HardwareEventKind::OomKill { .. } => "OOM kill",
+        }
+    }
+
+    /// Health-report category for this kind of event
+    pub fn category(&self) -> &'static str {
+        match self {
+            HardwareEventKind::NvidiaXid { .. }
+            | HardwareEventKind::AmdgpuRingTimeout { .. }
+            | HardwareEventKind::I915GpuHang { .. } => "GPU",
+            HardwareEventKind::MachineCheck { .. } | HardwareEventKind::ThermalThrottle { .. } => {
+                "CPU"
+            }
+            HardwareEventKind::EdacError { .. } | HardwareEventKind::OomKill { .. } => "Memory",
+            HardwareEventKind::NvmeReset => "Storage",
+        }
+    }
+}
+
+impl std::fmt::Display for HardwareEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HardwareEventKind::NvidiaXid { code } => match xid_description(*code) {
+                Some(desc) => write!(f, "Xid {} ({})", code, desc),
+                None => write!(f, "Xid {}", code),
+            },
+            HardwareEventKind::AmdgpuRingTimeout { ring } => write!(f, "Ring {} timeout", ring),
+            HardwareEventKind::I915GpuHang { engine: Some(e) } => write!(f, "GPU hang on {}", e),
+            HardwareEventKind::I915GpuHang { engine: None } => write!(f, "GPU hang"),
+            HardwareEventKind::MachineCheck { bank: Some(b) } => {
+                write!(f, "Machine check (bank {})", b)
+            }
+            HardwareEventKind::MachineCheck { bank: None } => write!(f, "Machine check"),
+            HardwareEventKind::EdacError {
+                corrected, count, ..
+            } => write!(
+                f,
+                "EDAC {} x{}",
+                if *corrected { "CE" } else { "UE" },
+                count
+            ),
+            HardwareEventKind::NvmeReset => write!(f, "NVMe reset"),
+            HardwareEventKind::ThermalThrottle { zone } => write!(f, "Thermal throttle ({})", zone),
+            HardwareEventKind::OomKill { cgroup: true } => write!(f, "OOM kill (cgroup)"),
+            HardwareEventKind::OomKill { cgroup: false } => write!(f, "OOM kill"),
+        }
+    }
+}
+
+/// Event severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EventSeverity {
+    /// Informational
+    Info,
+    /// Degraded but recoverable
+    Warning,
+    /// Data loss, device loss or crash
+    Critical,
+}
+
+impl std::fmt::Display for EventSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventSeverity::Info => write!(f, "INFO"),
+            EventSeverity::Warning => write!(f, "WARN"),
+            EventSeverity::Critical => write!(f, "CRIT"),
+        }
+    }
+}
+
+/// Device an event refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventDevice {
+    /// PCI device by bus id (e.g. "0000:01:00.0")
+    Pci(String),
+    /// Block device (e.g. "nvme0", "sda")
+    Disk(String),
+    /// Logical CPU
+    Cpu(u32),
+    /// EDAC memory controller
+    MemoryController(u32),
+    /// Anything else (e.g. a thermal zone)
+    Other(String),
+}
+
+impl std::fmt::Display for EventDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventDevice::Pci(id) => write!(f, "{}", id),
+            EventDevice::Disk(name) => write!(f, "{}", name),
+            EventDevice::Cpu(n) => write!(f, "cpu{}", n),
+            EventDevice::MemoryController(n) => write!(f, "mc{}", n),
+            EventDevice::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A classified hardware event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HardwareEvent {
+    /// What happened
+    pub kind: HardwareEventKind,
+    /// How bad it is
+    pub severity: EventSeverity,
+    /// Where the record was read from
+    pub source: LogSource,
+    /// Affected device
+    pub device: Option<EventDevice>,
+    /// PID involved (faulting, hung or killed process)
+    pub pid: Option<u32>,
+    /// Process name, if reported
+    pub process: Option<String>,
+    /// Wall-clock time (microseconds since the Unix epoch)
+    pub timestamp_us: Option<u64>,
+    /// Time since boot (microseconds)
+    pub uptime_us: Option<u64>,
+    /// Original log message
+    pub message: String,
+}
+
+impl HardwareEvent {
+    /// Time elapsed since the event, if its wall-clock time is known
+    pub fn age(&self) -> Option<Duration> {
+        let ts = UNIX_EPOCH + Duration::from_micros(self.timestamp_us?);
+        Some(SystemTime::now().duration_since(ts).unwrap_or_default())
+    }
+
+    /// Whether the event refers to the given PCI bus id
+    ///
+    /// Bus ids are compared case-insensitively and with or without the
+    /// 8-digit domain that NVML uses (`00000000:01:00.0`).
+    pub fn is_on_pci_device(&self, bus_id: &str) -> bool {
+        match self.device {
+            Some(EventDevice::Pci(ref id)) => *id == normalize_pci_bus_id(bus_id),
+            _ => false,
+        }
+    }
+}
+
+/// Collects hardware events from the kernel log
+pub struct HardwareEventMonitor {
+    /// Open `/dev/kmsg` handle
+    #[cfg(target_os = "linux")]
+    kmsg: Option<kmsg::KmsgReader>,
+    /// Journal cursor when reading through `journalctl`
+    journal_cursor: Option<String>,
+    /// Whether to fall back to `journalctl` on each poll
+    use_journalctl: bool,
+    /// Classified events, oldest first
+    events: VecDeque<HardwareEvent>,
+    /// Maximum number of events kept
+    capacity: usize,
+}
+
+impl Default for HardwareEventMonitor {
+    fn default() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            kmsg: None,
+            journal_cursor: None,
+            use_journalctl: false,
+            events: VecDeque::new(),
+            capacity: DEFAULT_EVENT_CAPACITY,
+        }
+    }
+}
+
+impl HardwareEventMonitor {
+    /// Open the kernel log and load events since boot
+    ///
+    /// Uses `/dev/kmsg` when readable, otherwise `journalctl -k`.
+    #[cfg(target_os = "linux")]
+    pub fn new() -> Result<Self> {
+        let mut monitor = Self::default();
+        match kmsg::KmsgReader::open() {
+            Ok(reader) => monitor.kmsg = Some(reader),
+            Err(kmsg_err) => {
+                if journalctl_kernel(None).is_err() {
+                    return Err(kmsg_err);
+                }
+                monitor.use_journalctl = true;
+            }
+        }
+        monitor.poll()?;
+        Ok(monitor)
+    }
+
+    /// Open the kernel log and load events since boot
+    #[cfg(not(target_os = "linux"))]
+    pub fn new() -> Result<Self> {
+        Err(SimonError::UnsupportedPlatform(
+            "Kernel log monitoring is only available on Linux".to_string(),
+        ))
+    }
+
+    /// Set the maximum number of events kept
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        while self.events.len() > self.capacity {
+            self.events.pop_front();
+        }
+        self
+    }
+
+    /// Read new kernel messages and return the number of new events
+    pub fn poll(&mut self) -> Result<usize> {
+        let mut added = 0;
+
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut reader) = self.kmsg {
+            for record in reader.read_available()? {
+                added += self.ingest_counting(&record, LogSource::Kmsg);
+            }
+        }
+
+        if self.use_journalctl {
+            let (records, cursor) = journalctl_kernel(self.journal_cursor.as_deref())?;
+            for record in &records {
+                added += self.ingest_counting(record, LogSource::Journal);
+            }
+            if cursor.is_some() {
+                self.journal_cursor = cursor;
+            }
+        }
+
+        Ok(added)
+    }
+
+    /// Load events from a journal export file
+    pub fn load_journal_export<P: AsRef<Path>>(&mut self, path: P) -> Result<usize> {
+        let file = std::fs::File::open(path)?;
+        let mut added = 0;
+        for record in JournalExportReader::new(std::io::BufReader::new(file)) {
+            added += self.ingest_counting(&record?, LogSource::Journal);
+        }
+        Ok(added)
+    }
+
+    /// Classify one record and keep it if it is a hardware event
+    ///
+    /// Also attaches follow-up lines (such as the amdgpu "Process
+    /// information" line) to the event they belong to.
+    pub fn ingest(&mut self, record: &LogRecord, source: LogSource) -> Option<&HardwareEvent> {
+        if self.ingest_counting(record, source) > 0 {
+            self.events.back()
+        } else {
+            None
+        }
+    }
+
+    /// All retained events, oldest first
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &HardwareEvent> {
+        self.events.iter()
+    }
+
+    /// Number of retained events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether no events have been seen
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Events newer than `window`, oldest first
+    pub fn recent(&self, window: Duration) -> Vec<&HardwareEvent> {
+        self.events
+            .iter()
+            .filter(|e| e.age().map(|age| age <= window).unwrap_or(false))
+            .collect()
+    }
+
+    /// Events affecting a device (PCI bus id, disk name, "cpuN", "mcN")
+    pub fn for_device(&self, device: &str) -> Vec<&HardwareEvent> {
+        let pci = normalize_pci_bus_id(device);
+        self.events
+            .iter()
+            .filter(|e| match e.device {
+                Some(EventDevice::Pci(ref id)) => *id == pci,
+                Some(ref d) => d.to_string() == device,
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Events involving a PID
+    pub fn for_pid(&self, pid: u32) -> Vec<&HardwareEvent> {
+        self.events.iter().filter(|e| e.pid == Some(pid)).collect()
+    }
+
+    /// Forget all retained events
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Returns the number of events added (1 or 0)
+    fn ingest_counting(&mut self, record: &LogRecord, source: LogSource) -> usize {
+        if let Some(last) = self.events.back_mut() {
+            if attach_process_info(last, record) {
+                return 0;
+            }
+        }
+
+        let Some(event) = classify(record, source) else {
+            return 0;
+        };
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+        1
+    }
+}
+
+/// Read hardware events from the kernel log since boot
+#[cfg(target_os = "linux")]
+pub fn kernel_events() -> Result<Vec<HardwareEvent>> {
+    let monitor = HardwareEventMonitor::new()?;
+    Ok(monitor.events.into_iter().collect())
+}
+
+/// Read hardware events from the kernel log since boot
+#[cfg(not(target_os = "linux"))]
+pub fn kernel_events() -> Result<Vec<HardwareEvent>> {
+    HardwareEventMonitor::new().map(|m| m.events.into_iter().collect())
+}
+
+/// Read all kernel log records since boot (unclassified)
+///
+/// Uses `/dev/kmsg` when readable, otherwise `journalctl -k`.
+#[cfg(target_os = "linux")]
+pub fn kernel_records() -> Result<Vec<LogRecord>> {
+    match kmsg::KmsgReader::open() {
+        Ok(mut reader) => reader.read_available(),
+        Err(kmsg_err) => journalctl_kernel(None)
+            .map(|(records, _)| records)
+            .map_err(|_| kmsg_err),
+    }
+}
+
+/// Read all kernel log records since boot (unclassified)
+#[cfg(not(target_os = "linux"))]
+pub fn kernel_records() -> Result<Vec<LogRecord>> {
+    Err(SimonError::UnsupportedPlatform(
+        "Kernel log access is only available on Linux".to_string(),
+    ))
+}
+
+/// Read hardware events from a journal export file
+pub fn journal_events<P: AsRef<Path>>(path: P) -> Result<Vec<HardwareEvent>> {
+    let mut monitor = HardwareEventMonitor::default().with_capacity(usize::MAX);
+    monitor.load_journal_export(path)?;
+    Ok(monitor.events.into_iter().collect())
+}
+
+/// Hardware events from the kernel log newer than `window`
+///
+/// Returns an empty list when the kernel log cannot be read.
+pub fn recent_hardware_events(window: Duration) -> Vec<HardwareEvent> {
+    HardwareEventMonitor::new()
+        .map(|m| m.recent(window).into_iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Classify a log record as a hardware event
+pub fn classify(record: &LogRecord, source: LogSource) -> Option<HardwareEvent> {
+    let msg = record.message.trim_end();
+    let c = classify_xid(msg)
+        .or_else(|| classify_amdgpu(msg))
+        .or_else(|| classify_i915(msg))
+        .or_else(|| classify_edac(msg))
+        .or_else(|| classify_thermal(msg))
+        .or_else(|| classify_mce(msg))
+        .or_else(|| classify_nvme(msg))
+        .or_else(|| classify_oom(msg))?;
+
+    let device = c.device.or_else(|| {
+        record
+            .device
+            .as_deref()
+            .and_then(|d| d.strip_prefix("+pci:"))
+            .map(|id| EventDevice::Pci(normalize_pci_bus_id(id)))
+    });
+
+    Some(HardwareEvent {
+        kind: c.kind,
+        severity: c.severity,
+        source,
+        device,
+        pid: c.pid,
+        process: c.process,
+        timestamp_us: record.timestamp_us,
+        uptime_us: record.uptime_us,
+        message: msg.to_string(),
+    })
+}
+
+/// Short description of common NVIDIA Xid codes
+pub fn xid_description(code: u32) -> Option<&'static str> {
+    Some(match code {
+        13 => "graphics engine exception",
+        31 => "GPU memory page fault",
+        32 => "invalid push buffer stream",
+        38 => "driver firmware error",
+        43 => "GPU stopped processing",
+        45 => "preemptive cleanup",
+        48 => "double bit ECC error",
+        61 => "internal micro-controller breakpoint",
+        62 => "internal micro-controller halt",
+        63 => "ECC page retirement or row remapping event",
+        64 => "ECC page retirement or row remapping failure",
+        68 => "video processor exception",
+        69 => "graphics engine class error",
+        74 => "NVLink error",
+        79 => "GPU has fallen off the bus",
+        92 => "high single-bit ECC error rate",
+        94 => "contained ECC error",
+        95 => "uncontained ECC error",
+        119 => "GSP RPC timeout",
+        120 => "GSP error",
+        _ => return None,
+    })
+}
+
+/// Xid codes that mean the GPU or its memory is no longer trustworthy
+fn xid_is_critical(code: u32) -> bool {
+    matches!(code, 48 | 62 | 64 | 74 | 79 | 95 | 119 | 120)
+}
+
+/// Classification result before record metadata is attached
+struct Classified {
+    kind: HardwareEventKind,
+    severity: EventSeverity,
+    device: Option<EventDevice>,
+    pid: Option<u32>,
+    process: Option<String>,
+}
+
+impl Classified {
+    fn new(kind: HardwareEventKind, severity: EventSeverity) -> Self {
+        Self {
+            kind,
+            severity,
+            device: None,
+            pid: None,
+            process: None,
+        }
+    }
+
+    fn device(mut self, device: Option<EventDevice>) -> Self {
+        self.device = device;
+        self
+    }
+}
+
+/// `NVRM: Xid (PCI:0000:3b:00): 79, pid=1234, name=python3, GPU has fallen off the bus.`
+fn classify_xid(msg: &str) -> Option<Classified> {
+    let rest = msg.split_once("NVRM: Xid (")?.1;
+    let (bus, rest) = rest.split_once("):")?;
+    let rest = rest.trim_start();
+    let (code, tail) = rest.split_once(',').unwrap_or((rest, ""));
+    let code: u32 = code.trim().parse().ok()?;
+
+    let severity = if xid_is_critical(code) {
+        EventSeverity::Critical
+    } else {
+        EventSeverity::Warning
+    };
+    let mut c = Classified::new(HardwareEventKind::NvidiaXid { code }, severity).device(Some(
+        EventDevice::Pci(normalize_pci_bus_id(bus.trim_start_matches("PCI:"))),
+    ));
+    c.pid = key_value(tail, "pid=").and_then(|v| v.parse().ok());
+    c.process = key_value(tail, "name=").map(str::to_string);
+    Some(c)
+}
+
+/// `amdgpu 0000:03:00.0: amdgpu: ring gfx_0.0.0 timeout, signaled seq=1, emitted seq=3`
+///
+/// Older kernels log `[drm:amdgpu_job_timedout [amdgpu]] *ERROR* ring gfx
+/// timeout, ...` without naming the device.
+fn classify_amdgpu(msg: &str) -> Option<Classified> {
+    let (device, rest) = split_amdgpu_prefix(msg)?;
+    let ring = rest.split_once("ring ")?.1;
+    let (ring, after) = ring.split_once(' ')?;
+    if !after.starts_with("timeout") {
+        return None;
+    }
+    Some(
+        Classified::new(
+            HardwareEventKind::AmdgpuRingTimeout {
+                ring: ring.to_string(),
+            },
+            EventSeverity::Critical,
+        )
+        .device(device.map(|d| EventDevice::Pci(normalize_pci_bus_id(d)))),
+    )
+}
+
+/// `i915 0000:00:02.0: [drm] GPU HANG: ecode 12:1:85dffffb, in Xorg [1234]`
+fn classify_i915(msg: &str) -> Option<Classified> {
+    let (driver, device, rest) = split_driver_prefix(msg)?;
+    if driver != "i915" && driver != "xe" {
+        return None;
+    }
+
+    let mut c = if let Some(hang) = rest.split_once("GPU HANG:").map(|(_, h)| h) {
+        let mut c = Classified::new(
+            HardwareEventKind::I915GpuHang {
+                engine: hang
+                    .split_once("hang on ")
+                    .map(|(_, e)| first_word(e).to_string()),
+            },
+            EventSeverity::Warning,
+        );
+        // "..., in Xorg [1234]"
+        if let Some((_, proc_part)) = hang.split_once(", in ") {
+            if let Some((name, pid)) = proc_part.split_once(" [") {
+                c.process = Some(name.trim().to_string());
+                c.pid = pid.split(']').next().and_then(|p| p.parse().ok());
+            }
+        }
+        c
+    } else if let Some(reset) = rest.split_once("Resetting ").map(|(_, r)| r) {
+        // "Resetting rcs0 for preemption time out" / "Resetting chip for stopped heartbeat on rcs0"
+        let target = first_word(reset);
+        let engine = if target == "chip" {
+            reset
+                .rsplit_once(" on ")
+                .map(|(_, e)| first_word(e).to_string())
+        } else {
+            Some(target.to_string())
+        };
+        Classified::new(
+            HardwareEventKind::I915GpuHang { engine },
+            EventSeverity::Warning,
+        )
+    } else {
+        return None;
+    };
+
+    c.device = Some(EventDevice::Pci(normalize_pci_bus_id(device)));
+    Some(c)
+}
+
+/// `EDAC MC0: 1 CE memory read error on CPU_SrcID#0_MC#0_Chan#1_DIMM#0 (channel:1 slot:0 ...)`
+fn classify_edac(msg: &str) -> Option<Classified> {
+    let rest = msg.split_once("EDAC ")?.1;
+    let mc_start = rest.find("MC")?;
+    let rest = &rest[mc_start + 2..];
+    let (mc, rest) = rest.split_once(": ")?;
+    let mc: u32 = mc.parse().ok()?;
+
+    let mut words = rest.split_whitespace();
+    let count: u32 = words.next()?.parse().ok()?;
+    let corrected = match words.next()? {
+        "CE" => true,
+        "UE" => false,
+        _ => return None,
+    };
+    let location = rest.split_once(" on ").map(|(_, loc)| {
+        loc.split_once(" (")
+            .map(|(l, _)| l)
+            .unwrap_or(loc)
+            .trim()
+            .to_string()
+    });
+
+    let severity = if corrected {
+        EventSeverity::Warning
+    } else {
+        EventSeverity::Critical
+    };
+    Some(
+        Classified::new(
+            HardwareEventKind::EdacError {
+                corrected,
+                count,
+                location,
+            },
+            severity,
+        )
+        .device(Some(EventDevice::MemoryController(mc))),
+    )
+}
+
+/// `mce: [Hardware Error]: CPU 3: Machine Check: 0 Bank 5: be00000000800400`
+fn classify_mce(msg: &str) -> Option<Classified> {
+    let lower = msg.to_ascii_lowercase();
+    if !lower.starts_with("mce") && !lower.contains("[hardware error]") {
+        return None;
+    }
+
+    let device = msg
+        .split_once("CPU ")
+        .and_then(|(_, rest)| leading_number(rest))
+        .map(EventDevice::Cpu);
+
+    if lower.contains("machine check:") {
+        let bank = msg
+            .split_once("Bank ")
+            .and_then(|(_, rest)| leading_number(rest));
+        let severity = if lower.contains("uncorrect") || lower.contains("fatal") {
+            EventSeverity::Critical
+        } else {
+            EventSeverity::Warning
+        };
+        Some(Classified::new(HardwareEventKind::MachineCheck { bank }, severity).device(device))
+    } else if lower.contains("machine check events logged") {
+        Some(Classified::new(
+            HardwareEventKind::MachineCheck { bank: None },
+            EventSeverity::Warning,
+        ))
+    } else if lower.contains("uncorrected") || lower.contains("fatal") || lower.contains("panic") {
+        Some(
+            Classified::new(
+                HardwareEventKind::MachineCheck { bank: None },
+                EventSeverity::Critical,
+            )
+            .device(device),
+        )
+    } else {
+        None
+    }
+}
+
+/// `nvme nvme0: I/O 123 QID 4 timeout, reset controller`
+fn classify_nvme(msg: &str) -> Option<Classified> {
+    let (driver, device, rest) = split_driver_prefix(msg)?;
+    if driver != "nvme" {
+        return None;
+    }
+
+    const CRITICAL: [&str; 3] = [
+        "controller is down",
+        "Removing after probe failure",
+        "Device not ready",
+    ];
+    const WARNING: [&str; 4] = [
+        "reset controller",
+        "resetting controller",
+        "timeout, aborting",
+        "timeout, disable controller",
+    ];
+
+    let severity = if CRITICAL.iter().any(|p| rest.contains(p)) {
+        EventSeverity::Critical
+    } else if WARNING.iter().any(|p| rest.contains(p)) {
+        EventSeverity::Warning
+    } else {
+        return None;
+    };
+    Some(
+        Classified::new(HardwareEventKind::NvmeReset, severity)
+            .device(Some(EventDevice::Disk(device.to_string()))),
+    )
+}
+
+/// `CPU3: Core temperature above threshold, cpu clock throttled (total events = 1)`
+fn classify_thermal(msg: &str) -> Option<Classified> {
+    if msg.contains("critical temperature reached") {
+        // "thermal thermal_zone3: critical temperature reached (105 C), shutting down"
+        let zone = split_driver_prefix(msg)
+            .map(|(_, dev, _)| dev.to_string())
+            .unwrap_or_else(|| "thermal".to_string());
+        return Some(
+            Classified::new(
+                HardwareEventKind::ThermalThrottle { zone: zone.clone() },
+                EventSeverity::Critical,
+            )
+            .device(Some(EventDevice::Other(zone))),
+        );
+    }
+
+    let zone = if msg.contains("Core temperature above threshold") {
+        "core"
+    } else if msg.contains("Package temperature above threshold") {
+        "package"
+    } else {
+        return None;
+    };
+    let device = msg
+        .split_once("CPU")
+        .and_then(|(_, rest)| leading_number(rest))
+        .map(EventDevice::Cpu);
+    Some(
+        Classified::new(
+            HardwareEventKind::ThermalThrottle {
+                zone: zone.to_string(),
+            },
+            EventSeverity::Warning,
+        )
+        .device(device),
+    )
+}
+
+/// `Out of memory: Killed process 1234 (python3) total-vm:...`
+fn classify_oom(msg: &str) -> Option<Classified> {
+    let (before, rest) = msg.split_once("Killed process ")?;
+    if !before.contains("out of memory") && !before.contains("Out of memory") {
+        return None;
+    }
+    let pid = leading_number(rest)?;
+    let process = rest
+        .split_once('(')
+        .and_then(|(_, p)| p.split_once(')'))
+        .map(|(name, _)| name.to_string());
+
+    let mut c = Classified::new(
+        HardwareEventKind::OomKill {
+            cgroup: before.contains("Memory cgroup"),
+        },
+        EventSeverity::Warning,
+    );
+    c.pid = Some(pid);
+    c.process = process;
+    Some(c)
+}
+
+/// Attach an amdgpu "Process information" line to the ring timeout before it
+fn attach_process_info(last: &mut HardwareEvent, record: &LogRecord) -> bool {
+    if !matches!(last.kind, HardwareEventKind::AmdgpuRingTimeout { .. }) || last.pid.is_some() {
+        return false;
+    }
+    let Some((device, rest)) = split_amdgpu_prefix(&record.message) else {
+        return false;
+    };
+    if last.device != device.map(|d| EventDevice::Pci(normalize_pci_bus_id(d))) {
+        return false;
+    }
+
+    // "Process information: process chrome pid 4567 thread chrome:cs0 pid 4570"
+    // or (newer kernels) "Process chrome pid 4567 thread chrome:cs0 pid 4570"
+    let info = rest
+        .split_once("Process information: process ")
+        .or_else(|| rest.split_once("Process "))
+        .map(|(_, info)| info);
+    let Some((name, pid)) = info.and_then(|i| i.split_once(" pid ")) else {
+        return false;
+    };
+    last.process = Some(name.trim().to_string());
+    last.pid = leading_number(pid);
+    true
+}
+
+/// Split an amdgpu message into its PCI address, if any, and the rest
+///
+/// Handles `"amdgpu 0000:03:00.0: ..."` and the prefix-less
+/// `"[drm:amdgpu_job_timedout [amdgpu]] *ERROR* ..."` of 5.x kernels.
+fn split_amdgpu_prefix(msg: &str) -> Option<(Option<&str>, &str)> {
+    if let Some((driver, device, rest)) = split_driver_prefix(msg) {
+        return (driver == "amdgpu").then_some((Some(device), rest));
+    }
+    let (_, rest) = msg.strip_prefix("[drm:")?.split_once(" [amdgpu]] ")?;
+    Some((None, rest.trim_start_matches("*ERROR*").trim_start()))
+}
+
+/// Split `"driver device: rest"` (e.g. `"nvme nvme0: ..."`)
+fn split_driver_prefix(msg: &str) -> Option<(&str, &str, &str)> {
+    let (prefix, rest) = msg.split_once(": ")?;
+    let (driver, device) = prefix.split_once(' ')?;
+    if device.contains(' ') {
+        return None;
+    }
+    Some((driver, device, rest))
+}
+
+/// Normalize a PCI bus id to `dddd:bb:dd.f` in lowercase
+pub fn normalize_pci_bus_id(id: &str) -> String {
+    let id = id.trim().to_ascii_lowercase();
+    let mut parts: Vec<&str> = id.split(':').collect();
+    // NVML reports an 8-digit domain ("00000000:01:00.0")
+    if parts.len() == 3 && parts[0].len() > 4 {
+        parts[0] = &parts[0][parts[0].len() - 4..];
+    }
+    let mut out = parts.join(":");
+    if parts.len() == 3 && !out.contains('.') {
+        out.push_str(".0");
+    }
+    out
+}
+
+/// Value after `key` up to the next comma
+fn key_value<'a>(s: &'a str, key: &str) -> Option<&'a str> {
+    let v = s.split_once(key)?.1;
+    Some(v.split(',').next().unwrap_or(v).trim())
+}
+
+fn first_word(s: &str) -> &str {
+    s.split(|c: char| c.is_whitespace() || c == ',')
+        .next()
+        .unwrap_or(s)
+}
+
+fn leading_number(s: &str) -> Option<u32> {
+    let s = s.trim_start();
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+/// Read kernel records through `journalctl -k -o export`
+///
+/// Returns the records and the cursor of the last one.
+fn journalctl_kernel(after_cursor: Option<&str>) -> Result<(Vec<LogRecord>, Option<String>)> {
+    use std::process::Command;
+
+    let mut cmd = Command::new("journalctl");
+    cmd.args(["-k", "-b", "-o", "export", "--no-pager"]);
+    if let Some(cursor) = after_cursor {
+        cmd.arg(format!("--after-cursor={}", cursor));
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| SimonError::CommandFailed(format!("journalctl: {}", e)))?;
+    if !output.status.success() {
+        return Err(SimonError::CommandFailed(format!(
+            "journalctl: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let mut reader = JournalExportReader::new(&output.stdout[..]);
+    let mut records = Vec::new();
+    for record in reader.by_ref() {
+        records.push(record?);
+    }
+    Ok((records, reader.last_cursor().map(str::to_string)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kernel(message: &str) -> LogRecord {
+        LogRecord {
+            priority: 3,
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn event(message: &str) -> HardwareEvent {
+        classify(&kernel(message), LogSource::Kmsg).expect(message)
+    }
+
+    #[test]
+    fn test_nvidia_xid() {
+        let e = event(
+            "NVRM: Xid (PCI:0000:3B:00): 79, pid=2345, name=python3, GPU has fallen off the bus.",
+        );
+        assert_eq!(e.kind, HardwareEventKind::NvidiaXid { code: 79 });
+        assert_eq!(e.severity, EventSeverity::Critical);
+        assert_eq!(e.device, Some(EventDevice::Pci("0000:3b:00.0".to_string())));
+        assert_eq!(e.pid, Some(2345));
+        assert_eq!(e.process.as_deref(), Some("python3"));
+        assert!(e.is_on_pci_device("00000000:3B:00.0"));
+
+        let e = event(
+            "NVRM: Xid (PCI:0000:01:00): 13, pid='<unknown>', name=<unknown>, Graphics Exception",
+        );
+        assert_eq!(e.severity, EventSeverity::Warning);
+        assert_eq!(e.pid, None);
+    }
+
+    #[test]
+    fn test_amdgpu_ring_timeout_with_process() {
+        let mut monitor = HardwareEventMonitor::default();
+        monitor.ingest(
+            &kernel("amdgpu 0000:03:00.0: amdgpu: ring gfx_0.0.0 timeout, signaled seq=1234, emitted seq=1236"),
+            LogSource::Kmsg,
+        );
+        monitor.ingest(
+            &kernel("amdgpu 0000:03:00.0: amdgpu:  Process information: process chrome pid 4567 thread chrome:cs0 pid 4570"),
+            LogSource::Kmsg,
+        );
+
+        assert_eq!(monitor.len(), 1);
+        let e = monitor.events().next().unwrap();
+        assert_eq!(
+            e.kind,
+            HardwareEventKind::AmdgpuRingTimeout {
+                ring: "gfx_0.0.0".to_string()
+            }
+        );
+        assert_eq!(e.pid, Some(4567));
+        assert_eq!(e.process.as_deref(), Some("chrome"));
+        assert_eq!(monitor.for_pid(4567).len(), 1);
+        assert_eq!(monitor.for_device("0000:03:00.0").len(), 1);
+
+        // 5.x kernels leave out the device
+        let mut monitor = HardwareEventMonitor::default();
+        monitor.ingest(
+            &kernel("[drm:amdgpu_job_timedout [amdgpu]] *ERROR* ring gfx timeout, signaled seq=5231, emitted seq=5233"),
+            LogSource::Kmsg,
+        );
+        monitor.ingest(
+            &kernel("[drm:amdgpu_job_timedout [amdgpu]] *ERROR* Process information: process Xorg pid 1102 thread Xorg:cs0 pid 1110"),
+            LogSource::Kmsg,
+        );
+        assert_eq!(monitor.len(), 1);
+        let e = monitor.events().next().unwrap();
+        assert_eq!(
+            e.kind,
+            HardwareEventKind::AmdgpuRingTimeout {
+                ring: "gfx".to_string()
+            }
+        );
+        assert_eq!(e.device, None);
+        assert_eq!(e.pid, Some(1102));
+    }
+
+    #[test]
+    fn test_i915_hang() {
+        let e = event("i915 0000:00:02.0: [drm] GPU HANG: ecode 12:1:85dffffb, in Xorg [1234]");
+        assert_eq!(e.kind, HardwareEventKind::I915GpuHang { engine: None });
+        assert_eq!(e.pid, Some(1234));
+        assert_eq!(e.process.as_deref(), Some("Xorg"));
+
+        let e = event("i915 0000:00:02.0: [drm] Resetting chip for stopped heartbeat on rcs0");
+        assert_eq!(
+            e.kind,
+            HardwareEventKind::I915GpuHang {
+                engine: Some("rcs0".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_memory_errors() {
+        let e = event("EDAC MC0: 1 CE memory read error on CPU_SrcID#0_MC#0_Chan#1_DIMM#0 (channel:1 slot:0 page:0x12345 offset:0x0 grain:32)");
+        assert_eq!(
+            e.kind,
+            HardwareEventKind::EdacError {
+                corrected: true,
+                count: 1,
+                location: Some("CPU_SrcID#0_MC#0_Chan#1_DIMM#0".to_string()),
+            }
+        );
+        assert_eq!(e.device, Some(EventDevice::MemoryController(0)));
+        assert_eq!(e.severity, EventSeverity::Warning);
+
+        let e = event("EDAC MC1: 2 UE memory scrubbing error on DIMM_A1");
+        assert_eq!(e.severity, EventSeverity::Critical);
+
+        let e = event("mce: [Hardware Error]: CPU 3: Machine Check: 0 Bank 5: be00000000800400");
+        assert_eq!(e.kind, HardwareEventKind::MachineCheck { bank: Some(5) });
+        assert_eq!(e.device, Some(EventDevice::Cpu(3)));
+    }
+
+    #[test]
+    fn test_nvme_thermal_oom() {
+        let e = event("nvme nvme0: I/O 123 QID 4 timeout, reset controller");
+        assert_eq!(e.kind, HardwareEventKind::NvmeReset);
+        assert_eq!(e.device, Some(EventDevice::Disk("nvme0".to_string())));
+        assert!(classify(
+            &kernel("nvme nvme0: Shutdown timeout set to 10 seconds"),
+            LogSource::Kmsg
+        )
+        .is_none());
+
+        let e =
+            event("CPU3: Core temperature above threshold, cpu clock throttled (total events = 1)");
+        assert_eq!(
+            e.kind,
+            HardwareEventKind::ThermalThrottle {
+                zone: "core".to_string()
+            }
+        );
+        assert_eq!(e.device, Some(EventDevice::Cpu(3)));
+
+        let e = event("Memory cgroup out of memory: Killed process 9876 (trainer) total-vm:123kB, anon-rss:456kB");
+        assert_eq!(e.kind, HardwareEventKind::OomKill { cgroup: true });
+        assert_eq!(e.pid, Some(9876));
+        assert_eq!(e.process.as_deref(), Some("trainer"));
+    }
+
+    #[test]
+    fn test_unrelated_lines_ignored() {
+        for line in [
+            "Linux version 6.8.0 (gcc 13)",
+            "nvme nvme0: pci function 0000:01:00.0",
+            "amdgpu 0000:03:00.0: amdgpu: ring gfx_0.0.0 uses VM inv eng 0 on hub 0",
+            "CPU3: Core temperature/speed normal",
+        ] {
+            assert!(
+                classify(&kernel(line), LogSource::Kmsg).is_none(),
+                "{}",
+                line
+            );
+        }
+    }
+}
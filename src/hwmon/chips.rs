@@ -0,0 +1,633 @@
+// lm-sensors style chip view of /sys/class/hwmon
+//
+// hwmonN indices are assigned in probe order and shift between boots and
+// kernels. libsensors instead names a chip `<prefix>-<bus>-<address>` from the
+// driver name and the bus path of its parent device (`coretemp-isa-0000`,
+// `nvme-pci-0100`, `nct6775-isa-0290`, `lm75-i2c-1-48`), and features by
+// their sysfs stem (`temp1`, `in0`, `fan2`). The same scheme gives every
+// sensor a stable ID, `<chip>/<feature>`, and lets us print `sensors -j`.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bus a chip hangs off, as libsensors classifies it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChipBus {
+    /// ISA / platform device
+    Isa,
+    /// PCI device
+    Pci,
+    /// I2C adapter number
+    I2c(u16),
+    /// SPI bus number
+    Spi(u16),
+    /// ACPI device
+    Acpi,
+    /// HID bus number
+    Hid(u16),
+    /// MDIO bus
+    Mdio,
+    /// SCSI host number
+    Scsi(u16),
+    /// No parent device
+    Virtual,
+}
+
+/// libsensors chip name: `<prefix>-<bus>-<address>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipName {
+    /// Driver name (the hwmon `name` attribute)
+    pub prefix: String,
+    /// Bus type and number
+    pub bus: ChipBus,
+    /// Address on the bus
+    pub addr: u32,
+}
+
+impl fmt::Display for ChipName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = &self.prefix;
+        match self.bus {
+            ChipBus::Isa => write!(f, "{}-isa-{:04x}", p, self.addr),
+            ChipBus::Pci => write!(f, "{}-pci-{:04x}", p, self.addr),
+            ChipBus::I2c(nr) => write!(f, "{}-i2c-{}-{:02x}", p, nr, self.addr),
+            ChipBus::Spi(nr) => write!(f, "{}-spi-{}-{:x}", p, nr, self.addr),
+            ChipBus::Acpi => write!(f, "{}-acpi-{:x}", p, self.addr),
+            ChipBus::Hid(nr) => write!(f, "{}-hid-{}-{:x}", p, nr, self.addr),
+            ChipBus::Mdio => write!(f, "{}-mdio-{:x}", p, self.addr),
+            ChipBus::Scsi(nr) => write!(f, "{}-scsi-{}-{:x}", p, nr, self.addr),
+            ChipBus::Virtual => write!(f, "{}-virtual-{:x}", p, self.addr),
+        }
+    }
+}
+
+/// Kind of feature, in libsensors display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FeatureKind {
+    /// `inN`, volts
+    Voltage,
+    /// `fanN`, RPM
+    Fan,
+    /// `tempN`, °C
+    Temperature,
+    /// `powerN`, watts
+    Power,
+    /// `energyN`, joules
+    Energy,
+    /// `currN`, amperes
+    Current,
+    /// `humidityN`, %RH
+    Humidity,
+    /// `cpuN_vid`, volts
+    Vid,
+    /// `intrusionN`
+    Intrusion,
+}
+
+impl FeatureKind {
+    fn from_stem(stem: &str) -> Option<Self> {
+        Some(match stem {
+            "in" => Self::Voltage,
+            "fan" => Self::Fan,
+            "temp" => Self::Temperature,
+            "power" => Self::Power,
+            "energy" => Self::Energy,
+            "curr" => Self::Current,
+            "humidity" => Self::Humidity,
+            "cpu" => Self::Vid,
+            "intrusion" => Self::Intrusion,
+            _ => return None,
+        })
+    }
+
+    /// sysfs value per display unit
+    fn scale(self) -> f64 {
+        match self {
+            Self::Voltage | Self::Temperature | Self::Current | Self::Humidity | Self::Vid => 1e3,
+            Self::Power | Self::Energy => 1e6,
+            Self::Fan | Self::Intrusion => 1.0,
+        }
+    }
+
+    fn unit(self) -> &'static str {
+        match self {
+            Self::Voltage | Self::Vid => "V",
+            Self::Fan => "RPM",
+            Self::Temperature => "°C",
+            Self::Power => "W",
+            Self::Energy => "J",
+            Self::Current => "A",
+            Self::Humidity => "%RH",
+            Self::Intrusion => "",
+        }
+    }
+}
+
+/// One sysfs attribute of a feature (`temp1_input`, `temp1_crit`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subfeature {
+    /// Attribute file name
+    pub name: String,
+    /// Value in display units (°C, V, RPM, W, J, A)
+    pub value: f64,
+}
+
+impl Subfeature {
+    /// Part after the feature name (`input`, `max`, `crit_alarm`)
+    pub fn kind(&self) -> &str {
+        self.name.split_once('_').map_or("", |(_, kind)| kind)
+    }
+
+    /// Flags and enums (alarms, faults, types), which are never scaled
+    /// or run through `compute`
+    pub fn is_flag(&self) -> bool {
+        let kind = self.kind();
+        kind.ends_with("alarm")
+            || kind.ends_with("beep")
+            || matches!(kind, "fault" | "type" | "enable")
+    }
+}
+
+/// A sensor: `temp1`, `in0`, ... with its attributes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    /// sysfs stem (`temp1`), stable within the chip
+    pub name: String,
+    /// Feature kind
+    pub kind: FeatureKind,
+    /// Index in the stem
+    pub number: u32,
+    /// Display label: `sensors.conf` label, `<name>_label`, or the name
+    pub label: String,
+    /// Attributes, `_input` first
+    pub subfeatures: Vec<Subfeature>,
+}
+
+impl Feature {
+    /// The `_input` value
+    pub fn input(&self) -> Option<f64> {
+        self.subfeature("input")
+    }
+
+    /// Value of the attribute `<name>_<kind>`
+    pub fn subfeature(&self, kind: &str) -> Option<f64> {
+        self.subfeatures
+            .iter()
+            .find(|s| s.kind() == kind)
+            .map(|s| s.value)
+    }
+
+    /// Display unit
+    pub fn unit(&self) -> &'static str {
+        self.kind.unit()
+    }
+
+    /// sysfs value per display unit for non-flag attributes
+    pub fn scale(&self) -> f64 {
+        self.kind.scale()
+    }
+}
+
+/// One hwmon device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chip {
+    /// Stable libsensors name
+    pub name: ChipName,
+    /// Adapter description (`ISA adapter`, `PCI adapter`, i2c adapter name)
+    pub adapter: String,
+    /// `/sys/class/hwmon/hwmonN`
+    pub path: PathBuf,
+    /// Features in libsensors order
+    pub features: Vec<Feature>,
+}
+
+impl Chip {
+    /// Stable chip ID, e.g. `coretemp-isa-0000`
+    pub fn id(&self) -> String {
+        self.name.to_string()
+    }
+
+    /// Stable sensor ID, `<chip>/<feature>` (e.g. `nvme-pci-0100/temp1`)
+    pub fn sensor_id(&self, feature: &Feature) -> String {
+        format!("{}/{}", self.name, feature.name)
+    }
+
+    /// Feature by sysfs stem
+    pub fn feature(&self, name: &str) -> Option<&Feature> {
+        self.features.iter().find(|f| f.name == name)
+    }
+}
+
+/// Read every chip under `/sys/class/hwmon`
+pub fn read_chips() -> Vec<Chip> {
+    read_chips_from(Path::new("/sys"))
+}
+
+/// Read chips under `<sysfs>/class/hwmon`, sorted by name
+pub fn read_chips_from(sysfs: &Path) -> Vec<Chip> {
+    let Ok(entries) = fs::read_dir(sysfs.join("class/hwmon")) else {
+        return Vec::new();
+    };
+    let mut chips: Vec<Chip> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| read_chip(sysfs, &e.path()))
+        .collect();
+    chips.sort_by_key(|c| c.id());
+    chips
+}
+
+/// Stable name of a `hwmonN` directory; `None` if it has no `name`
+pub fn chip_name(hwmon: &Path) -> Option<ChipName> {
+    let prefix = read_trimmed(&hwmon.join("name"))?;
+    let (bus, addr) = classify_device(hwmon);
+    Some(ChipName { prefix, bus, addr })
+}
+
+/// Read one `hwmonN` directory; `None` if it has no `name`
+pub fn read_chip(sysfs: &Path, hwmon: &Path) -> Option<Chip> {
+    let name = chip_name(hwmon)?;
+    let adapter = match name.bus {
+        ChipBus::Isa => "ISA adapter".to_string(),
+        ChipBus::Pci => "PCI adapter".to_string(),
+        ChipBus::I2c(nr) => read_trimmed(&sysfs.join(format!("class/i2c-adapter/i2c-{}/name", nr)))
+            .unwrap_or_else(|| format!("i2c-{}", nr)),
+        ChipBus::Spi(_) => "SPI adapter".to_string(),
+        ChipBus::Acpi => "ACPI interface".to_string(),
+        ChipBus::Hid(_) => "HID adapter".to_string(),
+        ChipBus::Mdio => "MDIO adapter".to_string(),
+        ChipBus::Scsi(_) => "SCSI adapter".to_string(),
+        ChipBus::Virtual => "Virtual device".to_string(),
+    };
+    Some(Chip {
+        name,
+        adapter,
+        path: hwmon.to_path_buf(),
+        features: read_features(hwmon),
+    })
+}
+
+/// Bus and address of the device behind `hwmon/device`
+///
+/// Class devices (an NVMe controller, a DRM card) are skipped by walking up
+/// to the first ancestor on a bus libsensors knows, as `sensors` does.
+fn classify_device(hwmon: &Path) -> (ChipBus, u32) {
+    let Ok(mut device) = fs::canonicalize(hwmon.join("device")) else {
+        return (ChipBus::Virtual, 0);
+    };
+    for _ in 0..8 {
+        let subsystem = fs::read_link(device.join("subsystem"))
+            .ok()
+            .and_then(|link| link.file_name().map(|s| s.to_string_lossy().into_owned()));
+        let dev_name = device
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if let Some(classified) = subsystem.and_then(|s| classify_bus(&s, &dev_name)) {
+            return classified;
+        }
+        if !device.pop() {
+            break;
+        }
+    }
+    (ChipBus::Virtual, 0)
+}
+
+/// Bus and address from a subsystem and device name, as libsensors parses them
+fn classify_bus(subsystem: &str, dev_name: &str) -> Option<(ChipBus, u32)> {
+    let hex = |s: &str| u32::from_str_radix(s, 16).ok();
+    Some(match subsystem {
+        // 1-002d
+        "i2c" => {
+            let (nr, addr) = dev_name.split_once('-')?;
+            (ChipBus::I2c(nr.parse().ok()?), hex(addr)?)
+        }
+        // spi0.1
+        "spi" => {
+            let (nr, addr) = dev_name.strip_prefix("spi")?.split_once('.')?;
+            (ChipBus::Spi(nr.parse().ok()?), addr.parse().ok()?)
+        }
+        // 0000:03:00.0
+        "pci" => {
+            let mut parts = dev_name.split([':', '.']).map(hex);
+            let (domain, bus, slot, func) = (
+                parts.next()??,
+                parts.next()??,
+                parts.next()??,
+                parts.next()??,
+            );
+            (
+                ChipBus::Pci,
+                (domain << 16) + (bus << 8) + (slot << 3) + func,
+            )
+        }
+        // nct6775.656 (the I/O port in decimal), coretemp.0
+        "platform" | "of_platform" => {
+            let addr = dev_name
+                .rsplit_once('.')
+                .and_then(|(_, n)| n.parse().ok())
+                .unwrap_or(0);
+            (ChipBus::Isa, addr)
+        }
+        "acpi" => (ChipBus::Acpi, 0),
+        // 0003:046D:C52B.0005
+        "hid" => {
+            let (bus, rest) = dev_name.split_once(':')?;
+            let (_, id) = rest.rsplit_once('.')?;
+            (ChipBus::Hid(u16::from_str_radix(bus, 16).ok()?), hex(id)?)
+        }
+        // stmmac-0:01
+        "mdio_bus" => (ChipBus::Mdio, hex(dev_name.rsplit_once(':')?.1)?),
+        // host:channel:id:lun
+        "scsi" => {
+            let parts: Vec<u32> = dev_name
+                .split(':')
+                .map(|p| p.parse().ok())
+                .collect::<Option<_>>()?;
+            let [host, channel, id, lun] = parts[..] else {
+                return None;
+            };
+            (
+                ChipBus::Scsi(host as u16),
+                (channel << 8) + (id << 16) + lun,
+            )
+        }
+        _ => return None,
+    })
+}
+
+/// Group `<stem><n>_<attr>` files into features
+fn read_features(hwmon: &Path) -> Vec<Feature> {
+    let Ok(entries) = fs::read_dir(hwmon) else {
+        return Vec::new();
+    };
+    let mut features: Vec<Feature> = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let file = entry.file_name().to_string_lossy().into_owned();
+        let Some((name, attr)) = file.split_once('_') else {
+            continue;
+        };
+        let split = name
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(name.len());
+        let (Some(kind), Ok(number)) = (
+            FeatureKind::from_stem(&name[..split]),
+            name[split..].parse::<u32>(),
+        ) else {
+            continue;
+        };
+        if attr == "label" {
+            continue;
+        }
+        let Some(raw) = read_trimmed(&entry.path()).and_then(|s| s.parse::<f64>().ok()) else {
+            continue;
+        };
+
+        let index = match features.iter().position(|f| f.name == name) {
+            Some(index) => index,
+            None => {
+                let label = read_trimmed(&hwmon.join(format!("{}_label", name)))
+                    .unwrap_or_else(|| name.to_string());
+                features.push(Feature {
+                    name: name.to_string(),
+                    kind,
+                    number,
+                    label,
+                    subfeatures: Vec::new(),
+                });
+                features.len() - 1
+            }
+        };
+        let mut subfeature = Subfeature {
+            name: file.clone(),
+            value: raw,
+        };
+        if !subfeature.is_flag() {
+            subfeature.value /= kind.scale();
+        }
+        features[index].subfeatures.push(subfeature);
+    }
+
+    for feature in &mut features {
+        feature
+            .subfeatures
+            .sort_by(|a, b| (a.kind() != "input", &a.name).cmp(&(b.kind() != "input", &b.name)));
+    }
+    features.sort_by_key(|f| (f.kind, f.number));
+    features
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Render chips as `sensors -j` does
+pub fn sensors_json(chips: &[Chip]) -> String {
+    let quote = |s: &str| serde_json::to_string(s).unwrap_or_default();
+    let mut out = String::from("{\n");
+    for (i, chip) in chips.iter().enumerate() {
+        out.push_str(&format!("   {}:{{\n", quote(&chip.id())));
+        out.push_str(&format!("      \"Adapter\": {}", quote(&chip.adapter)));
+        for feature in &chip.features {
+            out.push_str(&format!(",\n      {}:{{\n", quote(&feature.label)));
+            let values: Vec<String> = feature
+                .subfeatures
+                .iter()
+                .filter(|s| s.value.is_finite())
+                .map(|s| format!("         {}: {:.3}", quote(&s.name), s.value))
+                .collect();
+            out.push_str(&values.join(",\n"));
+            out.push_str("\n      }");
+        }
+        out.push_str("\n   }");
+        if i + 1 < chips.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    out.push('}');
+    out
+}
+
+/// Render chips like plain `sensors`
+pub fn sensors_text(chips: &[Chip]) -> String {
+    let width = chips
+        .iter()
+        .flat_map(|c| &c.features)
+        .map(|f| f.label.chars().count() + 1)
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    let mut out = String::new();
+    for chip in chips {
+        out.push_str(&format!("{}\nAdapter: {}\n", chip.id(), chip.adapter));
+        for feature in &chip.features {
+            let value = feature
+                .input()
+                .map_or_else(|| "N/A".to_string(), |v| format_value(feature, v));
+            let limits: Vec<String> = [
+                ("min", "min"),
+                ("lcrit", "crit low"),
+                (
+                    "max",
+                    if feature.kind == FeatureKind::Temperature {
+                        "high"
+                    } else {
+                        "max"
+                    },
+                ),
+                ("crit", "crit"),
+            ]
+            .iter()
+            .filter_map(|(kind, name)| {
+                feature
+                    .subfeature(kind)
+                    .map(|v| format!("{} = {}", name, format_value(feature, v)))
+            })
+            .collect();
+            let mut line = format!(
+                "{:<width$} {:>10}",
+                format!("{}:", feature.label),
+                value,
+                width = width
+            );
+            if !limits.is_empty() {
+                line.push_str(&format!("  ({})", limits.join(", ")));
+            }
+            let alarm = feature
+                .subfeatures
+                .iter()
+                .any(|s| s.kind().ends_with("alarm") && s.value != 0.0);
+            if alarm {
+                line.push_str("  ALARM");
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn format_value(feature: &Feature, value: f64) -> String {
+    match feature.kind {
+        FeatureKind::Temperature => format!("{:+.1}{}", value, feature.unit()),
+        FeatureKind::Voltage | FeatureKind::Vid | FeatureKind::Current => {
+            format!("{:.2} {}", value, feature.unit())
+        }
+        FeatureKind::Fan => format!("{:.0} {}", value, feature.unit()),
+        FeatureKind::Intrusion => if value != 0.0 { "ALARM" } else { "OK" }.to_string(),
+        _ => format!("{:.2} {}", value, feature.unit()),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// Fake sysfs with a platform Super I/O chip, an NVMe drive behind its
+    /// controller class device, and a virtual chip
+    fn fake_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let write = |path: PathBuf, content: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        fs::create_dir_all(root.join("bus/platform")).unwrap();
+        fs::create_dir_all(root.join("bus/pci")).unwrap();
+        fs::create_dir_all(root.join("class/nvme")).unwrap();
+
+        let superio = root.join("devices/platform/nct6775.656");
+        fs::create_dir_all(&superio).unwrap();
+        symlink(root.join("bus/platform"), superio.join("subsystem")).unwrap();
+        let hwmon = root.join("class/hwmon/hwmon4");
+        write(hwmon.join("name"), "nct6775\n");
+        write(hwmon.join("in0_input"), "1104\n");
+        write(hwmon.join("in0_min"), "0\n");
+        write(hwmon.join("in0_max"), "1744\n");
+        write(hwmon.join("in0_alarm"), "0\n");
+        write(hwmon.join("in3_input"), "1656\n");
+        write(hwmon.join("fan2_input"), "1205\n");
+        write(hwmon.join("fan4_input"), "0\n");
+        write(hwmon.join("temp1_input"), "38500\n");
+        write(hwmon.join("temp1_label"), "SYSTIN\n");
+        write(hwmon.join("pwm2"), "128\n");
+        symlink(&superio, hwmon.join("device")).unwrap();
+
+        let pci = root.join("devices/pci0000:00/0000:01:00.0");
+        let ctrl = pci.join("nvme/nvme0");
+        fs::create_dir_all(&ctrl).unwrap();
+        symlink(root.join("bus/pci"), pci.join("subsystem")).unwrap();
+        symlink(root.join("class/nvme"), ctrl.join("subsystem")).unwrap();
+        let hwmon = root.join("class/hwmon/hwmon1");
+        write(hwmon.join("name"), "nvme\n");
+        write(hwmon.join("temp1_input"), "34900\n");
+        write(hwmon.join("temp1_crit"), "84900\n");
+        write(hwmon.join("temp1_label"), "Composite\n");
+        symlink(&ctrl, hwmon.join("device")).unwrap();
+
+        let hwmon = root.join("class/hwmon/hwmon0");
+        write(hwmon.join("name"), "iwlwifi_1\n");
+        write(hwmon.join("temp1_input"), "41000\n");
+        dir
+    }
+
+    #[test]
+    fn test_chip_names() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let chips = read_chips_from(root);
+        let ids: Vec<String> = chips.iter().map(Chip::id).collect();
+        assert_eq!(
+            ids,
+            ["iwlwifi_1-virtual-0", "nct6775-isa-0290", "nvme-pci-0100"]
+        );
+
+        let nct = &chips[1];
+        assert_eq!(nct.adapter, "ISA adapter");
+        let names: Vec<&str> = nct.features.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["in0", "in3", "fan2", "fan4", "temp1"]);
+        let in0 = nct.feature("in0").unwrap();
+        assert_eq!(in0.subfeatures[0].name, "in0_input");
+        assert_eq!(in0.input(), Some(1.104));
+        assert_eq!(in0.subfeature("max"), Some(1.744));
+        assert_eq!(nct.feature("temp1").unwrap().label, "SYSTIN");
+        assert_eq!(nct.sensor_id(in0), "nct6775-isa-0290/in0");
+
+        assert_eq!(classify_bus("i2c", "1-0048"), Some((ChipBus::I2c(1), 0x48)));
+        assert_eq!(
+            ChipName {
+                prefix: "lm75".into(),
+                bus: ChipBus::I2c(1),
+                addr: 0x48
+            }
+            .to_string(),
+            "lm75-i2c-1-48"
+        );
+    }
+
+    #[test]
+    fn test_sensors_json() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let chips: Vec<Chip> = read_chips_from(root)
+            .into_iter()
+            .filter(|c| c.name.prefix == "nvme")
+            .collect();
+        let json = sensors_json(&chips);
+        assert_eq!(
+            json,
+            "{\n   \"nvme-pci-0100\":{\n      \"Adapter\": \"PCI adapter\",\n      \"Composite\":{\n         \"temp1_input\": 34.900,\n         \"temp1_crit\": 84.900\n      }\n   }\n}"
+        );
+        let parsed: serde_json::Value =
+            serde_json::from_str(&sensors_json(&read_chips_from(root))).unwrap();
+        assert_eq!(parsed["nct6775-isa-0290"]["fan2"]["fan2_input"], 1205.0);
+
+        let text = sensors_text(&chips);
+        assert!(text.starts_with("nvme-pci-0100\nAdapter: PCI adapter\nComposite:"));
+        assert!(text.contains("+34.9°C  (crit = +84.9°C)"));
+    }
+}
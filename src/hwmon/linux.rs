@@ -6,7 +6,7 @@
 // - /sys/class/drm/* for GPU info
 // - /sys/class/block/*/device/* for storage
 
-use super::{HwSensor, HwSensorType, HwType};
+use super::{chip_name, HwSensor, HwSensorType, HwType};
 use std::fs;
 use std::path::Path;
 
@@ -23,13 +23,15 @@ pub fn read_all_hwmon_sensors() -> Vec<HwSensor> {
         for entry in entries.filter_map(|e| e.ok()) {
             let path = entry.path();
 
-            // Get chip name
-            let chip_name = fs::read_to_string(path.join("name"))
-                .map(|s| s.trim().to_string())
-                .unwrap_or_else(|_| entry.file_name().to_string_lossy().to_string());
-
-            // Determine hardware type based on chip name
-            let hw_type = classify_hwmon_chip(&chip_name);
+            // Stable libsensors chip name (e.g. nvme-pci-0100), not the hwmonN index
+            let (chip_name, hw_type) = match chip_name(&path) {
+                Some(name) => (name.to_string(), classify_hwmon_chip(&name.prefix)),
+                None => {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    let hw_type = classify_hwmon_chip(&name);
+                    (name, hw_type)
+                }
+            };
 
             // Read temperature sensors
             sensors.extend(read_temperature_inputs(&path, &chip_name, hw_type));
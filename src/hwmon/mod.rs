@@ -17,10 +17,17 @@ pub mod windows;
 #[cfg(target_os = "linux")]
 pub mod linux;
 
+mod chips;
 mod cpu_temp;
+mod sensors_conf;
 mod smart;
 
+pub use chips::{
+    chip_name, read_chip, read_chips, read_chips_from, sensors_json, sensors_text, Chip, ChipBus,
+    ChipName, Feature, FeatureKind, Subfeature,
+};
 pub use cpu_temp::read_cpu_temperatures;
+pub use sensors_conf::{ChipConfig, ChipPattern, Expr, SensorsConfig, SetOperation};
 pub use smart::read_storage_temperatures;
 
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,819 @@
+// sensors.conf handling: chip / label / ignore / compute / set
+//
+// Reads lm-sensors configuration (`/etc/sensors3.conf`, `/etc/sensors.d/*`)
+// or the equivalent TOML and applies it to [`Chip`]s:
+//
+//     chip "nct6775-isa-*"
+//         label in0 "Vcore"
+//         label temp1 "System"
+//         ignore fan4
+//         compute in3 @*2, @/2
+//         set in0_min 0.9
+//
+// or
+//
+//     [[chip]]
+//     name = "nct6775-isa-*"
+//     ignore = ["fan4"]
+//     label = { in0 = "Vcore", temp1 = "System" }
+//     compute = { in3 = "@*2, @/2" }
+//     set = { in0_min = 0.9 }
+//
+// Later chip blocks win over earlier ones, as in libsensors. `compute`
+// expressions support numbers, `@` (the raw value), other features of the
+// same chip by name (their raw input), + - * /, parentheses, `^` (exp) and
+// `` ` `` (ln).
+
+use super::chips::{Chip, ChipBus, ChipName};
+use crate::error::{Result, SimonError};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Arithmetic expression from a `compute` or `set` statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Literal
+    Number(f64),
+    /// `@`, the value being converted
+    Raw,
+    /// Another feature (or attribute) of the chip
+    Variable(String),
+    /// Unary minus
+    Negate(Box<Expr>),
+    /// `^x`, e to the x
+    Exp(Box<Expr>),
+    /// `` `x ``, natural logarithm
+    Ln(Box<Expr>),
+    /// Binary operation: `+`, `-`, `*` or `/`
+    Binary(char, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate with `@` = `raw`; `None` if a variable is unknown
+    pub fn eval(&self, raw: f64, lookup: &dyn Fn(&str) -> Option<f64>) -> Option<f64> {
+        Some(match self {
+            Expr::Number(n) => *n,
+            Expr::Raw => raw,
+            Expr::Variable(name) => lookup(name)?,
+            Expr::Negate(e) => -e.eval(raw, lookup)?,
+            Expr::Exp(e) => e.eval(raw, lookup)?.exp(),
+            Expr::Ln(e) => e.eval(raw, lookup)?.ln(),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(raw, lookup)?, b.eval(raw, lookup)?);
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    _ => a / b,
+                }
+            }
+        })
+    }
+}
+
+impl FromStr for Expr {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = ExprParser {
+            chars: s.chars().collect(),
+            pos: 0,
+        };
+        let expr = parser.sum()?;
+        parser.skip_space();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("unexpected input"));
+        }
+        Ok(expr)
+    }
+}
+
+/// Recursive-descent parser for [`Expr`]
+struct ExprParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn error(&self, what: &str) -> SimonError {
+        SimonError::Parse(format!(
+            "{} at column {} of '{}'",
+            what,
+            self.pos + 1,
+            self.chars.iter().collect::<String>()
+        ))
+    }
+
+    fn skip_space(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_space();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut left = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+        Ok(left)
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let wrap: fn(Box<Expr>) -> Expr = match self.peek() {
+            Some('-') => Expr::Negate,
+            Some('^') => Expr::Exp,
+            Some('`') => Expr::Ln,
+            _ => return self.primary(),
+        };
+        self.pos += 1;
+        Ok(wrap(Box::new(self.unary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        match self.peek() {
+            Some('@') => {
+                self.pos += 1;
+                Ok(Expr::Raw)
+            }
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("expected ')'"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == '.')
+                {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse()
+                    .map(Expr::Number)
+                    .map_err(|_| self.error("invalid number"))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    self.pos += 1;
+                }
+                Ok(Expr::Variable(self.chars[start..self.pos].iter().collect()))
+            }
+            _ => Err(self.error("expected a value")),
+        }
+    }
+}
+
+/// Chip name pattern: `nct6775-isa-0290`, `coretemp-*`, `*-i2c-1-*`, `*`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChipPattern {
+    prefix: Option<String>,
+    bus: Option<String>,
+    number: Option<u16>,
+    addr: Option<u32>,
+}
+
+impl ChipPattern {
+    /// Whether `name` matches
+    pub fn matches(&self, name: &ChipName) -> bool {
+        let (bus, number) = bus_parts(name.bus);
+        self.prefix.as_ref().is_none_or(|p| *p == name.prefix)
+            && self.bus.as_ref().is_none_or(|b| b == bus)
+            && self.number.is_none_or(|n| Some(n) == number)
+            && self.addr.is_none_or(|a| a == name.addr)
+    }
+}
+
+impl FromStr for ChipPattern {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || SimonError::Parse(format!("invalid chip name '{}'", s));
+        let any = |part: &str| part == "*";
+        let mut pattern = ChipPattern {
+            prefix: None,
+            bus: None,
+            number: None,
+            addr: None,
+        };
+        let (prefix, rest) = s.split_once('-').unwrap_or((s, "*"));
+        if prefix.is_empty() {
+            return Err(invalid());
+        }
+        if !any(prefix) {
+            pattern.prefix = Some(prefix.to_string());
+        }
+        let mut parts = rest.split('-');
+        let bus = parts.next().ok_or_else(invalid)?;
+        if any(bus) {
+            return if parts.next().is_none() {
+                Ok(pattern)
+            } else {
+                Err(invalid())
+            };
+        }
+        if !matches!(
+            bus,
+            "isa" | "pci" | "i2c" | "spi" | "acpi" | "hid" | "mdio" | "scsi" | "virtual"
+        ) {
+            return Err(invalid());
+        }
+        pattern.bus = Some(bus.to_string());
+        if matches!(bus, "i2c" | "spi" | "hid" | "scsi") {
+            match parts.next() {
+                Some(n) if !any(n) => pattern.number = Some(n.parse().map_err(|_| invalid())?),
+                _ => {}
+            }
+        }
+        match parts.next() {
+            Some(a) if !any(a) => {
+                pattern.addr = Some(u32::from_str_radix(a, 16).map_err(|_| invalid())?)
+            }
+            _ => {}
+        }
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(pattern)
+    }
+}
+
+fn bus_parts(bus: ChipBus) -> (&'static str, Option<u16>) {
+    match bus {
+        ChipBus::Isa => ("isa", None),
+        ChipBus::Pci => ("pci", None),
+        ChipBus::I2c(n) => ("i2c", Some(n)),
+        ChipBus::Spi(n) => ("spi", Some(n)),
+        ChipBus::Acpi => ("acpi", None),
+        ChipBus::Hid(n) => ("hid", Some(n)),
+        ChipBus::Mdio => ("mdio", None),
+        ChipBus::Scsi(n) => ("scsi", Some(n)),
+        ChipBus::Virtual => ("virtual", None),
+    }
+}
+
+/// Statements of one `chip` block
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChipConfig {
+    /// Chips the block applies to
+    pub patterns: Vec<ChipPattern>,
+    /// Feature labels
+    pub labels: BTreeMap<String, String>,
+    /// Hidden features
+    pub ignores: Vec<String>,
+    /// Feature conversions: (raw to shown, shown to raw)
+    pub computes: BTreeMap<String, (Expr, Expr)>,
+    /// Attribute values to write with `sensors -s`
+    pub sets: Vec<(String, Expr)>,
+}
+
+impl ChipConfig {
+    /// Whether the block applies to `name`
+    pub fn matches(&self, name: &ChipName) -> bool {
+        self.patterns.iter().any(|p| p.matches(name))
+    }
+}
+
+/// A pending `set` statement, resolved against a chip
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetOperation {
+    /// Chip ID
+    pub chip: String,
+    /// Attribute (`in0_min`)
+    pub attribute: String,
+    /// sysfs file to write
+    pub path: PathBuf,
+    /// Value in display units, before the inverse `compute`
+    pub value: f64,
+    /// Value written to sysfs
+    pub raw: i64,
+}
+
+impl SetOperation {
+    /// Write the value (needs root)
+    pub fn apply(&self) -> Result<()> {
+        let result = fs::write(&self.path, self.raw.to_string());
+        crate::utils::log_privileged_operation(
+            &format!("sensors set {} {}={}", self.chip, self.attribute, self.raw),
+            result.is_ok(),
+        );
+        Ok(result?)
+    }
+}
+
+/// Parsed sensors configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorsConfig {
+    /// `chip` blocks in file order
+    pub chips: Vec<ChipConfig>,
+}
+
+impl SensorsConfig {
+    /// Parse `sensors.conf` syntax; `source` names the file in errors
+    pub fn parse(text: &str, source: &str) -> Result<Self> {
+        let mut config = Self::default();
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line_no = index + 1;
+            let mut line = line.to_string();
+            while line.ends_with('\\') {
+                line.pop();
+                match lines.next() {
+                    Some((_, next)) => line.push_str(next),
+                    None => break,
+                }
+            }
+            let error = |msg: &str| SimonError::Parse(format!("{}:{}: {}", source, line_no, msg));
+            let line = strip_comment(&line);
+            let Some((keyword, rest)) = next_word(line).map_err(|e| error(&e))? else {
+                continue;
+            };
+
+            if keyword == "chip" {
+                let mut patterns = Vec::new();
+                let mut rest = rest;
+                while let Some((name, tail)) = next_word(rest).map_err(|e| error(&e))? {
+                    patterns.push(
+                        name.parse()
+                            .map_err(|e: SimonError| error(&e.to_string()))?,
+                    );
+                    rest = tail;
+                }
+                if patterns.is_empty() {
+                    return Err(error("chip statement without a chip name"));
+                }
+                config.chips.push(ChipConfig {
+                    patterns,
+                    ..ChipConfig::default()
+                });
+                continue;
+            }
+            if keyword == "bus" {
+                // Bus statements only rename i2c adapters; nothing to apply
+                continue;
+            }
+            let chip = config
+                .chips
+                .last_mut()
+                .ok_or_else(|| error(&format!("'{}' before the first chip statement", keyword)))?;
+            let (name, rest) = next_word(rest)
+                .map_err(|e| error(&e))?
+                .ok_or_else(|| error(&format!("'{}' needs a feature name", keyword)))?;
+            let expr = |text: &str| text.parse::<Expr>().map_err(|e| error(&e.to_string()));
+            match keyword.as_str() {
+                "label" => {
+                    let (label, tail) = next_word(rest)
+                        .map_err(|e| error(&e))?
+                        .ok_or_else(|| error("label statement without a label"))?;
+                    if !tail.trim().is_empty() {
+                        return Err(error("quote labels that contain spaces"));
+                    }
+                    chip.labels.insert(name, label);
+                }
+                "ignore" => chip.ignores.push(name),
+                "compute" => {
+                    let (from, to) = rest
+                        .split_once(',')
+                        .ok_or_else(|| error("compute needs two expressions: from, to"))?;
+                    chip.computes.insert(name, (expr(from)?, expr(to)?));
+                }
+                "set" => chip.sets.push((name, expr(rest)?)),
+                other => return Err(error(&format!("unknown statement '{}'", other))),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Parse the TOML form (`[[chip]]` tables)
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let file: TomlFile = toml::from_str(text)
+            .map_err(|e| SimonError::Parse(format!("invalid sensors TOML: {}", e)))?;
+        let mut config = Self::default();
+        for chip in file.chip {
+            let names = match chip.name {
+                OneOrMany::One(name) => vec![name],
+                OneOrMany::Many(names) => names,
+            };
+            let mut parsed = ChipConfig {
+                patterns: names.iter().map(|n| n.parse()).collect::<Result<_>>()?,
+                labels: chip.label,
+                ignores: chip.ignore,
+                ..ChipConfig::default()
+            };
+            for (feature, exprs) in chip.compute {
+                let (from, to) = exprs.split_once(',').ok_or_else(|| {
+                    SimonError::Parse(format!("compute {}: expected \"from, to\"", feature))
+                })?;
+                parsed
+                    .computes
+                    .insert(feature, (from.parse()?, to.parse()?));
+            }
+            for (attribute, value) in chip.set {
+                let expr = match value {
+                    SetValue::Number(n) => Expr::Number(n),
+                    SetValue::Expr(text) => text.parse()?,
+                };
+                parsed.sets.push((attribute, expr));
+            }
+            config.chips.push(parsed);
+        }
+        Ok(config)
+    }
+
+    /// Load a file: TOML if it ends in `.toml`, `sensors.conf` syntax otherwise
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)?;
+        if path.extension().is_some_and(|e| e == "toml") {
+            Self::from_toml(&text)
+        } else {
+            Self::parse(&text, &path.display().to_string())
+        }
+    }
+
+    /// The system configuration, as `sensors` reads it
+    ///
+    /// `/etc/sensors3.conf` (or `/etc/sensors.conf`), then `/etc/sensors.d/*`
+    /// in name order. Unreadable or invalid files are logged and skipped.
+    pub fn load_system() -> Self {
+        let mut config = Self::default();
+        let main = ["/etc/sensors3.conf", "/etc/sensors.conf"]
+            .iter()
+            .map(PathBuf::from)
+            .find(|p| p.is_file());
+        let mut extra: Vec<PathBuf> = fs::read_dir("/etc/sensors.d")
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+        extra.sort();
+        for path in main.into_iter().chain(extra) {
+            match Self::load(&path) {
+                Ok(loaded) => config.merge(loaded),
+                Err(e) => log::warn!("ignoring {}: {}", path.display(), e),
+            }
+        }
+        config
+    }
+
+    /// Append `other`'s blocks, which then take precedence
+    pub fn merge(&mut self, other: SensorsConfig) {
+        self.chips.extend(other.chips);
+    }
+
+    /// Apply labels, ignores and computes to `chips`
+    pub fn apply(&self, chips: &mut [Chip]) {
+        for chip in chips {
+            let blocks: Vec<&ChipConfig> = self
+                .chips
+                .iter()
+                .filter(|c| c.matches(&chip.name))
+                .collect();
+            if blocks.is_empty() {
+                continue;
+            }
+            let raw = chip.clone();
+            let raw_value = |name: &str| lookup(&raw, name);
+            chip.features
+                .retain(|f| !blocks.iter().any(|b| b.ignores.contains(&f.name)));
+            for feature in &mut chip.features {
+                if let Some(label) = blocks
+                    .iter()
+                    .rev()
+                    .find_map(|b| b.labels.get(&feature.name))
+                {
+                    feature.label = label.clone();
+                }
+                if let Some((from, _)) = blocks
+                    .iter()
+                    .rev()
+                    .find_map(|b| b.computes.get(&feature.name))
+                {
+                    for subfeature in &mut feature.subfeatures {
+                        if !subfeature.is_flag() {
+                            subfeature.value =
+                                from.eval(subfeature.value, &raw_value).unwrap_or(f64::NAN);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolve `set` statements against `chips` (as read, before [`apply`])
+    ///
+    /// [`apply`]: Self::apply
+    pub fn set_operations(&self, chips: &[Chip]) -> Result<Vec<SetOperation>> {
+        let mut operations = Vec::new();
+        for chip in chips {
+            let blocks: Vec<&ChipConfig> = self
+                .chips
+                .iter()
+                .filter(|c| c.matches(&chip.name))
+                .collect();
+            for block in &blocks {
+                for (attribute, expr) in &block.sets {
+                    let unknown = || {
+                        SimonError::InvalidValue(format!(
+                            "{}: cannot evaluate set {}",
+                            chip.id(),
+                            attribute
+                        ))
+                    };
+                    let value = expr
+                        .eval(f64::NAN, &|name| lookup(chip, name))
+                        .ok_or_else(unknown)?;
+                    let feature_name = attribute.split_once('_').map_or("", |(f, _)| f);
+                    let feature = chip.feature(feature_name);
+                    let shown = match blocks
+                        .iter()
+                        .rev()
+                        .find_map(|b| b.computes.get(feature_name))
+                    {
+                        Some((_, to)) => to
+                            .eval(value, &|name| lookup(chip, name))
+                            .ok_or_else(unknown)?,
+                        None => value,
+                    };
+                    let flag = attribute.ends_with("alarm")
+                        || attribute.ends_with("beep")
+                        || attribute.ends_with("enable");
+                    let scale = match feature {
+                        Some(feature) if !flag => feature.scale(),
+                        _ => 1.0,
+                    };
+                    operations.push(SetOperation {
+                        chip: chip.id(),
+                        attribute: attribute.clone(),
+                        path: chip.path.join(attribute),
+                        value,
+                        raw: (shown * scale).round() as i64,
+                    });
+                }
+            }
+        }
+        Ok(operations)
+    }
+}
+
+/// Raw value of a feature (its input) or of an attribute by file name
+fn lookup(chip: &Chip, name: &str) -> Option<f64> {
+    chip.feature(name).and_then(|f| f.input()).or_else(|| {
+        chip.features
+            .iter()
+            .flat_map(|f| &f.subfeatures)
+            .find(|s| s.name == name)
+            .map(|s| s.value)
+    })
+}
+
+/// Drop a `#` comment outside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Next bare or double-quoted word and the rest of the line
+fn next_word(s: &str) -> std::result::Result<Option<(String, &str)>, String> {
+    let s = s.trim_start();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    if let Some(quoted) = s.strip_prefix('"') {
+        let mut word = String::new();
+        let mut chars = quoted.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok(Some((word, &quoted[i + 1..]))),
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        word.push(escaped);
+                    }
+                }
+                _ => word.push(c),
+            }
+        }
+        return Err("unterminated string".to_string());
+    }
+    let end = s.find(char::is_whitespace).unwrap_or(s.len());
+    Ok(Some((s[..end].to_string(), &s[end..])))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlFile {
+    #[serde(default)]
+    chip: Vec<TomlChip>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TomlChip {
+    name: OneOrMany,
+    #[serde(default)]
+    label: BTreeMap<String, String>,
+    #[serde(default)]
+    ignore: Vec<String>,
+    #[serde(default)]
+    compute: BTreeMap<String, String>,
+    #[serde(default)]
+    set: BTreeMap<String, SetValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SetValue {
+    Number(f64),
+    Expr(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hwmon::chips::{Feature, FeatureKind, Subfeature};
+
+    fn feature(name: &str, kind: FeatureKind, values: &[(&str, f64)]) -> Feature {
+        Feature {
+            name: name.to_string(),
+            kind,
+            number: name[name.len() - 1..].parse().unwrap(),
+            label: name.to_string(),
+            subfeatures: values
+                .iter()
+                .map(|(attr, value)| Subfeature {
+                    name: format!("{}_{}", name, attr),
+                    value: *value,
+                })
+                .collect(),
+        }
+    }
+
+    fn nct6775() -> Chip {
+        Chip {
+            name: ChipName {
+                prefix: "nct6775".into(),
+                bus: ChipBus::Isa,
+                addr: 0x290,
+            },
+            adapter: "ISA adapter".into(),
+            path: PathBuf::from("/sys/class/hwmon/hwmon4"),
+            features: vec![
+                feature(
+                    "in0",
+                    FeatureKind::Voltage,
+                    &[("input", 1.104), ("min", 0.0)],
+                ),
+                feature(
+                    "in3",
+                    FeatureKind::Voltage,
+                    &[("input", 1.656), ("max", 1.8), ("alarm", 0.0)],
+                ),
+                feature("fan4", FeatureKind::Fan, &[("input", 0.0)]),
+                feature("temp1", FeatureKind::Temperature, &[("input", 38.5)]),
+            ],
+        }
+    }
+
+    const CONF: &str = r#"
+# Board-specific settings
+bus "i2c-0" "SMBus I801 adapter at 5000"
+
+chip "coretemp-*"
+    label temp1 "Package"
+
+chip "nct6775-isa-*" "nct6776-*"
+    label in0 "Vcore"
+    label temp1 "System #1"   # quoted '#' is not a comment
+    ignore fan4
+    compute in3 @*2, \
+        @/2
+    set in0_min in0 * 0.9
+    set in3_max 3.6
+
+chip "nct6775-isa-0290"
+    label temp1 "SYSTIN"
+"#;
+
+    #[test]
+    fn test_parse_and_apply() {
+        let config = SensorsConfig::parse(CONF, "sensors3.conf").unwrap();
+        assert_eq!(config.chips.len(), 3);
+        assert_eq!(config.chips[1].patterns.len(), 2);
+
+        let mut chips = vec![nct6775()];
+        config.apply(&mut chips);
+        let chip = &chips[0];
+        let labels: Vec<&str> = chip.features.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(labels, ["Vcore", "in3", "SYSTIN"]);
+        let in3 = chip.feature("in3").unwrap();
+        assert_eq!(in3.input(), Some(3.312));
+        assert_eq!(in3.subfeature("max"), Some(3.6));
+        assert_eq!(in3.subfeature("alarm"), Some(0.0));
+
+        let sets = config.set_operations(&[nct6775()]).unwrap();
+        assert_eq!(sets.len(), 2);
+        assert_eq!(
+            sets[0].path,
+            PathBuf::from("/sys/class/hwmon/hwmon4/in0_min")
+        );
+        assert_eq!(sets[0].raw, 994);
+        // 3.6 V shown is 1.8 V at the pin after the inverse compute
+        assert_eq!((sets[1].attribute.as_str(), sets[1].raw), ("in3_max", 1800));
+    }
+
+    #[test]
+    fn test_toml_equivalent() {
+        let config = SensorsConfig::from_toml(
+            r#"
+            [[chip]]
+            name = ["nct6775-isa-*", "nct6776-*"]
+            ignore = ["fan4"]
+            label = { in0 = "Vcore", temp1 = "SYSTIN" }
+            compute = { in3 = "@*2, @/2" }
+            set = { in0_min = "in0 * 0.9", in3_max = 3.6 }
+            "#,
+        )
+        .unwrap();
+        let mut from_toml = vec![nct6775()];
+        config.apply(&mut from_toml);
+        let mut from_conf = vec![nct6775()];
+        SensorsConfig::parse(CONF, "sensors3.conf")
+            .unwrap()
+            .apply(&mut from_conf);
+        assert_eq!(from_toml, from_conf);
+        assert_eq!(config.set_operations(&[nct6775()]).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_patterns_and_errors() {
+        let name = |prefix: &str, bus, addr| ChipName {
+            prefix: prefix.into(),
+            bus,
+            addr,
+        };
+        let pattern = |s: &str| s.parse::<ChipPattern>().unwrap();
+        assert!(pattern("*").matches(&name("k10temp", ChipBus::Pci, 0xc3)));
+        assert!(pattern("k10temp-pci-00c3").matches(&name("k10temp", ChipBus::Pci, 0xc3)));
+        assert!(pattern("*-i2c-1-*").matches(&name("lm75", ChipBus::I2c(1), 0x48)));
+        assert!(!pattern("*-i2c-0-*").matches(&name("lm75", ChipBus::I2c(1), 0x48)));
+        assert!(!pattern("coretemp-*").matches(&name("k10temp", ChipBus::Pci, 0xc3)));
+        assert!("nct6775-foo-0290".parse::<ChipPattern>().is_err());
+
+        let expr: Expr = "-(@ - 2) * ^0 + `1 / 4".parse().unwrap();
+        assert_eq!(expr.eval(5.0, &|_| None), Some(-3.0));
+        assert!("@ +".parse::<Expr>().is_err());
+
+        let err = SensorsConfig::parse("label in0 \"Vcore\"", "x.conf").unwrap_err();
+        assert!(err.to_string().contains("x.conf:1"));
+        let err = SensorsConfig::parse("chip \"*\"\ncompute in0 @*2", "x.conf").unwrap_err();
+        assert!(err.to_string().contains("x.conf:2"));
+    }
+}
@@ -451,9 +451,10 @@ fn read_linux_storage_temps() -> Vec<HwSensor> {
                 || name.starts_with("nvme") && name.contains("n1") && !name.contains("p")
             {
                 // Check for hwmon device
-                let hwmon_glob = entry.path().join("device/hwmon/hwmon*");
-                if let Ok(mut entries) = glob::glob(&hwmon_glob.to_string_lossy()) {
-                    if let Some(Ok(hwmon_path)) = entries.next() {
+                let hwmon_dir = entry.path().join("device/hwmon");
+                if let Ok(mut entries) = fs::read_dir(&hwmon_dir) {
+                    if let Some(Ok(hwmon_entry)) = entries.next() {
+                        let hwmon_path = hwmon_entry.path();
                         if let Ok(temp_str) = fs::read_to_string(hwmon_path.join("temp1_input")) {
                             if let Ok(temp_mc) = temp_str.trim().parse::<i32>() {
                                 let model = fs::read_to_string(entry.path().join("device/model"))
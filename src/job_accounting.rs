@@ -0,0 +1,781 @@
+//! Per-Job Resource and Energy Accounting
+//!
+//! Runs a command, follows its whole process tree and reports what it
+//! consumed, in the spirit of `/usr/bin/time -v` but including GPUs:
+//! CPU time, peak and average RSS, disk I/O, GPU memory and engine time,
+//! plus attributed CPU package and GPU energy with a cost estimate.
+//!
+//! Energy is attributed proportionally. For each sampling interval the job
+//! is charged the share of CPU package energy (RAPL) matching its share of
+//! busy CPU time, and the share of each GPU's energy matching its share of
+//! that GPU's utilization (or of its used memory, when the driver does not
+//! report per-process utilization).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::job_accounting::{run_job, JobConfig};
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = JobConfig::default()
+//!     .with_sample_interval(Duration::from_millis(500))
+//!     .with_price_per_kwh(0.12);
+//!
+//! let command = vec!["python3".to_string(), "train.py".to_string()];
+//! let summary = run_job(&command, &config)?;
+//!
+//! println!("Wall time: {:.1}s", summary.wall_time_secs);
+//! println!("Energy: {:.1} J", summary.energy.total_joules);
+//! println!("{}", serde_json::to_string_pretty(&summary)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, SimonError};
+use crate::gpu::GpuCollection;
+use crate::process_monitor::ProcessMonitor;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, ExitStatus};
+use std::time::{Duration, Instant};
+
+/// Default electricity price used for cost estimates (USD per kWh)
+pub const DEFAULT_PRICE_PER_KWH: f64 = 0.15;
+
+/// Default interval between samples of the process tree
+pub const DEFAULT_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Joules per kilowatt-hour
+const JOULES_PER_KWH: f64 = 3_600_000.0;
+
+/// Job accounting configuration
+#[derive(Debug, Clone)]
+pub struct JobConfig {
+    /// Interval between samples of the process tree
+    pub sample_interval: Duration,
+    /// Electricity price per kWh used for the cost estimate
+    pub price_per_kwh: f64,
+    /// Whether to track GPU usage (skips GPU detection when false)
+    pub track_gpus: bool,
+}
+
+impl Default for JobConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval: DEFAULT_SAMPLE_INTERVAL,
+            price_per_kwh: DEFAULT_PRICE_PER_KWH,
+            track_gpus: true,
+        }
+    }
+}
+
+impl JobConfig {
+    /// Set sampling interval
+    pub fn with_sample_interval(mut self, interval: Duration) -> Self {
+        self.sample_interval = interval;
+        self
+    }
+
+    /// Set electricity price per kWh
+    pub fn with_price_per_kwh(mut self, price: f64) -> Self {
+        self.price_per_kwh = price;
+        self
+    }
+
+    /// Enable or disable GPU tracking
+    pub fn with_gpus(mut self, track: bool) -> Self {
+        self.track_gpus = track;
+        self
+    }
+}
+
+/// CPU usage of a job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobCpuUsage {
+    /// User CPU time in seconds
+    pub user_secs: f64,
+    /// System CPU time in seconds
+    pub system_secs: f64,
+    /// Average CPU usage over the wall time (100% = one core)
+    pub avg_percent: f64,
+    /// Peak CPU usage over a single sampling interval (100% = one core)
+    pub peak_percent: f64,
+}
+
+/// Memory usage of a job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobMemoryUsage {
+    /// Average resident set size of the whole tree in bytes
+    pub avg_rss_bytes: u64,
+    /// Peak resident set size of the whole tree in bytes
+    pub peak_rss_bytes: u64,
+    /// Peak resident set size of any single process in bytes
+    pub peak_process_rss_bytes: u64,
+    /// Highest number of processes alive at once
+    pub peak_processes: usize,
+}
+
+/// Disk I/O of a job (from `/proc/[pid]/io` on Linux)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobIoUsage {
+    /// Bytes read
+    pub read_bytes: u64,
+    /// Bytes written
+    pub write_bytes: u64,
+    /// Read syscalls
+    pub read_syscalls: u64,
+    /// Write syscalls
+    pub write_syscalls: u64,
+}
+
+/// Usage of a single GPU by a job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobGpuUsage {
+    /// GPU index
+    pub index: usize,
+    /// GPU name
+    pub name: String,
+    /// Average GPU memory used by the job in bytes
+    pub avg_memory_bytes: u64,
+    /// Peak GPU memory used by the job in bytes
+    pub peak_memory_bytes: u64,
+    /// Engine time attributed to the job in seconds (busy-seconds at 100%)
+    pub engine_secs: f64,
+    /// Average utilization attributed to the job over the wall time (0-100)
+    pub avg_utilization: f64,
+    /// Energy attributed to the job in joules
+    pub energy_joules: Option<f64>,
+}
+
+/// Energy attributed to a job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobEnergy {
+    /// CPU package energy in joules (None when RAPL is unavailable)
+    pub cpu_package_joules: Option<f64>,
+    /// GPU energy in joules (None when no GPU reports power)
+    pub gpu_joules: Option<f64>,
+    /// Total attributed energy in joules
+    pub total_joules: f64,
+    /// Total attributed energy in kWh
+    pub total_kwh: f64,
+    /// Average attributed power in watts
+    pub avg_watts: f64,
+}
+
+/// Resource and energy summary of a finished job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSummary {
+    /// Command line that was run
+    pub command: Vec<String>,
+    /// PID of the root process
+    pub pid: u32,
+    /// Exit code (None when killed by a signal)
+    pub exit_code: Option<i32>,
+    /// Signal that killed the root process (Unix only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    /// Wall-clock time in seconds
+    pub wall_time_secs: f64,
+    /// Number of samples taken
+    pub samples: usize,
+    /// CPU usage
+    pub cpu: JobCpuUsage,
+    /// Memory usage
+    pub memory: JobMemoryUsage,
+    /// Disk I/O
+    pub io: JobIoUsage,
+    /// Per-GPU usage
+    pub gpus: Vec<JobGpuUsage>,
+    /// Attributed energy
+    pub energy: JobEnergy,
+    /// Electricity price used for the estimate
+    pub price_per_kwh: f64,
+    /// Estimated energy cost
+    pub estimated_cost: f64,
+}
+
+/// Samples a running process tree and accumulates its resource usage
+pub struct JobMonitor {
+    root_pid: u32,
+    config: JobConfig,
+    processes: ProcessMonitor,
+    started: Instant,
+    last_sample: Option<Instant>,
+    samples: usize,
+    /// Last seen cumulative CPU time per PID (kept after the process exits)
+    cpu_time_ms: HashMap<u32, u64>,
+    /// Last seen cumulative I/O per PID (kept after the process exits)
+    io: HashMap<u32, JobIoUsage>,
+    peak_cpu_percent: f64,
+    rss_byte_secs: f64,
+    memory: JobMemoryUsage,
+    gpus: Vec<GpuAccumulator>,
+    cpu_package_joules: Option<f64>,
+    rapl: RaplCounter,
+    system_cpu: Option<SystemCpuTimes>,
+}
+
+#[derive(Default)]
+struct GpuAccumulator {
+    usage: JobGpuUsage,
+    memory_byte_secs: f64,
+    energy_joules: Option<f64>,
+}
+
+impl JobMonitor {
+    /// Start accounting for an already running process tree
+    pub fn new(root_pid: u32, config: JobConfig) -> Result<Self> {
+        let processes = if config.track_gpus {
+            ProcessMonitor::with_gpus(GpuCollection::auto_detect().unwrap_or_default())?
+        } else {
+            ProcessMonitor::without_gpu()?
+        };
+
+        let gpus = processes
+            .gpus()
+            .map(|collection| {
+                collection
+                    .gpus()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, gpu)| GpuAccumulator {
+                        usage: JobGpuUsage {
+                            index,
+                            name: gpu.name().unwrap_or_else(|_| format!("GPU {}", index)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut rapl = RaplCounter::open();
+        rapl.delta_joules();
+
+        Ok(Self {
+            root_pid,
+            config,
+            processes,
+            started: Instant::now(),
+            last_sample: None,
+            samples: 0,
+            cpu_time_ms: HashMap::new(),
+            io: HashMap::new(),
+            peak_cpu_percent: 0.0,
+            rss_byte_secs: 0.0,
+            memory: JobMemoryUsage::default(),
+            gpus,
+            cpu_package_joules: None,
+            rapl,
+            system_cpu: SystemCpuTimes::read(),
+        })
+    }
+
+    /// PID of the root process being tracked
+    pub fn root_pid(&self) -> u32 {
+        self.root_pid
+    }
+
+    /// Sampling interval from the configuration
+    pub fn sample_interval(&self) -> Duration {
+        self.config.sample_interval
+    }
+
+    /// Take one sample of the process tree
+    pub fn sample(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let dt = now
+            .duration_since(self.last_sample.unwrap_or(self.started))
+            .as_secs_f64();
+        self.last_sample = Some(now);
+        self.samples += 1;
+
+        let tree = self.processes.process_tree(self.root_pid)?;
+        let pids: HashSet<u32> = tree.iter().map(|p| p.pid).collect();
+
+        // CPU time: remember the latest cumulative value per PID so that
+        // processes which exit between samples are still counted
+        let cpu_before: u64 = self.cpu_time_ms.values().sum();
+        for proc in &tree {
+            if let Some(ms) = proc.cpu_time_ms {
+                let entry = self.cpu_time_ms.entry(proc.pid).or_insert(0);
+                *entry = (*entry).max(ms);
+            }
+        }
+        let job_cpu_ms = self.cpu_time_ms.values().sum::<u64>() - cpu_before;
+        if dt > 0.0 {
+            let percent = job_cpu_ms as f64 / 10.0 / dt;
+            self.peak_cpu_percent = self.peak_cpu_percent.max(percent);
+        }
+
+        // Memory
+        let rss: u64 = tree.iter().map(|p| p.memory_bytes).sum();
+        let largest = tree.iter().map(|p| p.memory_bytes).max().unwrap_or(0);
+        self.rss_byte_secs += rss as f64 * dt;
+        self.memory.peak_rss_bytes = self.memory.peak_rss_bytes.max(rss);
+        self.memory.peak_process_rss_bytes = self.memory.peak_process_rss_bytes.max(largest);
+        self.memory.peak_processes = self.memory.peak_processes.max(tree.len());
+
+        // Disk I/O
+        #[cfg(target_os = "linux")]
+        for pid in &pids {
+            if let Ok(io) = crate::disk::linux::get_process_io(*pid) {
+                self.io.insert(
+                    *pid,
+                    JobIoUsage {
+                        read_bytes: io.read_bytes,
+                        write_bytes: io.write_bytes,
+                        read_syscalls: io.read_syscalls,
+                        write_syscalls: io.write_syscalls,
+                    },
+                );
+            }
+        }
+
+        // CPU package energy, charged by the job's share of busy CPU time
+        let system_cpu = SystemCpuTimes::read();
+        if let Some(package_joules) = self.rapl.delta_joules() {
+            let busy_ms = match (&self.system_cpu, &system_cpu) {
+                (Some(prev), Some(cur)) => cur.busy_ms.saturating_sub(prev.busy_ms),
+                _ => 0,
+            };
+            let share = cpu_share(job_cpu_ms, busy_ms);
+            *self.cpu_package_joules.get_or_insert(0.0) += package_joules * share;
+        }
+        self.system_cpu = system_cpu;
+
+        // GPUs
+        if let Some(collection) = self.processes.gpus() {
+            for (acc, gpu) in self.gpus.iter_mut().zip(collection.gpus()) {
+                let Ok(info) = gpu.dynamic_info() else {
+                    continue;
+                };
+
+                let job_procs: Vec<_> = info
+                    .processes
+                    .iter()
+                    .filter(|p| pids.contains(&p.pid))
+                    .collect();
+                let job_memory: u64 = job_procs.iter().filter_map(|p| p.memory_usage).sum();
+                let all_memory: u64 = info.processes.iter().filter_map(|p| p.memory_usage).sum();
+                let job_usage = job_procs
+                    .iter()
+                    .filter_map(|p| p.gpu_usage)
+                    .map(f64::from)
+                    .reduce(|a, b| a + b);
+
+                acc.usage.peak_memory_bytes = acc.usage.peak_memory_bytes.max(job_memory);
+                acc.memory_byte_secs += job_memory as f64 * dt;
+
+                if job_procs.is_empty() {
+                    continue;
+                }
+
+                let share = gpu_share(
+                    job_usage,
+                    f64::from(info.utilization),
+                    job_memory,
+                    all_memory,
+                );
+                let job_util = job_usage
+                    .unwrap_or(f64::from(info.utilization) * share)
+                    .min(100.0);
+                acc.usage.engine_secs += job_util / 100.0 * dt;
+
+                if let Some(draw_mw) = info.power.draw {
+                    let joules = draw_mw as f64 / 1000.0 * dt;
+                    *acc.energy_joules.get_or_insert(0.0) += joules * share;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish accounting and build the summary
+    ///
+    /// `rusage` holds the final user/system CPU seconds of the reaped tree
+    /// when the caller could obtain them; they take precedence over the
+    /// sampled values, which miss work done between the last sample and exit.
+    pub fn finish(
+        self,
+        command: Vec<String>,
+        status: ExitStatus,
+        rusage: Option<(f64, f64)>,
+    ) -> JobSummary {
+        let wall = self.started.elapsed().as_secs_f64().max(f64::EPSILON);
+
+        let sampled_cpu_secs = self.cpu_time_ms.values().sum::<u64>() as f64 / 1000.0;
+        let (user_secs, system_secs) = rusage.unwrap_or((sampled_cpu_secs, 0.0));
+
+        let io = self.io.values().fold(JobIoUsage::default(), |mut acc, io| {
+            acc.read_bytes += io.read_bytes;
+            acc.write_bytes += io.write_bytes;
+            acc.read_syscalls += io.read_syscalls;
+            acc.write_syscalls += io.write_syscalls;
+            acc
+        });
+
+        let mut memory = self.memory;
+        memory.avg_rss_bytes = (self.rss_byte_secs / wall) as u64;
+
+        let gpus: Vec<JobGpuUsage> = self
+            .gpus
+            .into_iter()
+            .map(|acc| {
+                let mut usage = acc.usage;
+                usage.avg_memory_bytes = (acc.memory_byte_secs / wall) as u64;
+                usage.avg_utilization = usage.engine_secs / wall * 100.0;
+                usage.energy_joules = acc.energy_joules;
+                usage
+            })
+            .collect();
+
+        let gpu_joules = gpus
+            .iter()
+            .filter_map(|g| g.energy_joules)
+            .reduce(|a, b| a + b);
+        let total_joules = self.cpu_package_joules.unwrap_or(0.0) + gpu_joules.unwrap_or(0.0);
+        let total_kwh = total_joules / JOULES_PER_KWH;
+
+        JobSummary {
+            command,
+            pid: self.root_pid,
+            exit_code: status.code(),
+            signal: exit_signal(&status),
+            wall_time_secs: wall,
+            samples: self.samples,
+            cpu: JobCpuUsage {
+                user_secs,
+                system_secs,
+                avg_percent: (user_secs + system_secs) / wall * 100.0,
+                peak_percent: self.peak_cpu_percent,
+            },
+            memory,
+            io,
+            gpus,
+            energy: JobEnergy {
+                cpu_package_joules: self.cpu_package_joules,
+                gpu_joules,
+                total_joules,
+                total_kwh,
+                avg_watts: total_joules / wall,
+            },
+            price_per_kwh: self.config.price_per_kwh,
+            estimated_cost: total_kwh * self.config.price_per_kwh,
+        }
+    }
+}
+
+/// Run a command to completion and account for its resource usage
+///
+/// The first element of `command` is the program, the rest are its
+/// arguments. Standard input and output are inherited.
+pub fn run_job(command: &[String], config: &JobConfig) -> Result<JobSummary> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| SimonError::InvalidValue("No command given".to_string()))?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .spawn()
+        .map_err(|e| SimonError::CommandFailed(format!("Failed to start {}: {}", program, e)))?;
+
+    // Spawned first so the job keeps the caller's dispositions
+    #[cfg(unix)]
+    let _interrupts = IgnoreInterrupts::new();
+
+    let mut monitor = JobMonitor::new(child.id(), config.clone())?;
+    let poll = config
+        .sample_interval
+        .min(Duration::from_millis(50))
+        .max(Duration::from_millis(1));
+
+    let (status, rusage) = loop {
+        // Sampling errors are not fatal: the tree may vanish mid-read
+        let _ = monitor.sample();
+
+        let deadline = Instant::now() + config.sample_interval;
+        let mut exited = None;
+        while Instant::now() < deadline {
+            if let Some(reaped) = try_reap(&mut child)? {
+                exited = Some(reaped);
+                break;
+            }
+            std::thread::sleep(poll);
+        }
+        if let Some(reaped) = exited {
+            break reaped;
+        }
+    };
+
+    Ok(monitor.finish(command.to_vec(), status, rusage))
+}
+
+/// Ignores SIGINT and SIGQUIT until dropped, as `time` does while it waits
+///
+/// A Ctrl-C from the terminal reaches the whole foreground process group;
+/// the job decides whether to exit, and the summary is still written when
+/// it does.
+#[cfg(unix)]
+struct IgnoreInterrupts([(libc::c_int, libc::sighandler_t); 2]);
+
+#[cfg(unix)]
+impl IgnoreInterrupts {
+    fn new() -> Self {
+        // SAFETY: SIG_IGN installs no handler code
+        Self(
+            [libc::SIGINT, libc::SIGQUIT]
+                .map(|signal| (signal, unsafe { libc::signal(signal, libc::SIG_IGN) })),
+        )
+    }
+}
+
+#[cfg(unix)]
+impl Drop for IgnoreInterrupts {
+    fn drop(&mut self) {
+        for &(signal, previous) in &self.0 {
+            if previous != libc::SIG_ERR {
+                // SAFETY: restores the disposition returned by signal()
+                unsafe { libc::signal(signal, previous) };
+            }
+        }
+    }
+}
+
+/// Exit status of the reaped root process and, when available, the
+/// user/system CPU seconds of it and its reaped descendants
+type Reaped = (ExitStatus, Option<(f64, f64)>);
+
+/// Reap the job's root process if it has exited
+///
+/// On Unix this uses `wait4` on the root PID so the returned user/system
+/// CPU seconds cover only this process and the descendants it reaped, not
+/// other children of the caller.
+#[cfg(unix)]
+fn try_reap(child: &mut std::process::Child) -> Result<Option<Reaped>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    // SAFETY: wait4 only writes into the provided status and rusage
+    let pid = unsafe {
+        libc::wait4(
+            child.id() as libc::pid_t,
+            &mut status,
+            libc::WNOHANG,
+            &mut usage,
+        )
+    };
+    match pid {
+        0 => Ok(None),
+        -1 => {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                Ok(None)
+            } else {
+                Err(err.into())
+            }
+        }
+        _ => {
+            let secs = |tv: libc::timeval| tv.tv_sec as f64 + tv.tv_usec as f64 / 1_000_000.0;
+            Ok(Some((
+                ExitStatus::from_raw(status),
+                Some((secs(usage.ru_utime), secs(usage.ru_stime))),
+            )))
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn try_reap(child: &mut std::process::Child) -> Result<Option<Reaped>> {
+    Ok(child.try_wait()?.map(|status| (status, None)))
+}
+
+/// Signal that terminated a process, if any
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Share of busy CPU time used by the job (0.0 - 1.0)
+fn cpu_share(job_cpu_ms: u64, system_busy_ms: u64) -> f64 {
+    if system_busy_ms == 0 {
+        return 0.0;
+    }
+    (job_cpu_ms as f64 / system_busy_ms as f64).clamp(0.0, 1.0)
+}
+
+/// Share of a GPU used by the job (0.0 - 1.0)
+///
+/// Uses per-process utilization when the driver reports it, otherwise the
+/// job's share of memory used by all processes on the device.
+fn gpu_share(
+    job_usage: Option<f64>,
+    device_utilization: f64,
+    job_memory: u64,
+    all_memory: u64,
+) -> f64 {
+    match job_usage {
+        Some(usage) if device_utilization > 0.0 => (usage / device_utilization).clamp(0.0, 1.0),
+        _ if all_memory > 0 => (job_memory as f64 / all_memory as f64).clamp(0.0, 1.0),
+        _ => 0.0,
+    }
+}
+
+/// Difference between two readings of a wrapping energy counter
+fn counter_delta(prev: u64, cur: u64, max_range: Option<u64>) -> u64 {
+    if cur >= prev {
+        cur - prev
+    } else {
+        match max_range {
+            Some(max) if max >= prev => max - prev + cur,
+            _ => 0,
+        }
+    }
+}
+
+/// Aggregate busy CPU time from `/proc/stat`
+struct SystemCpuTimes {
+    busy_ms: u64,
+}
+
+impl SystemCpuTimes {
+    #[cfg(target_os = "linux")]
+    fn read() -> Option<Self> {
+        let stat = std::fs::read_to_string("/proc/stat").ok()?;
+        let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+        let fields: Vec<u64> = line
+            .split_whitespace()
+            .skip(1)
+            .filter_map(|f| f.parse().ok())
+            .collect();
+        if fields.len() < 5 {
+            return None;
+        }
+        // user nice system idle iowait irq softirq steal ...
+        let total: u64 = fields.iter().take(8).sum();
+        let idle = fields[3] + fields[4];
+        // Jiffies at USER_HZ (100) to milliseconds
+        Some(Self {
+            busy_ms: total.saturating_sub(idle) * 10,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn read() -> Option<Self> {
+        None
+    }
+}
+
+/// CPU package energy counters (RAPL via powercap)
+struct RaplCounter {
+    /// (energy_uj path, max_energy_range_uj, last reading)
+    domains: Vec<(std::path::PathBuf, Option<u64>, Option<u64>)>,
+}
+
+impl RaplCounter {
+    #[cfg(target_os = "linux")]
+    fn open() -> Self {
+        let mut domains = Vec::new();
+        if let Ok(entries) = std::fs::read_dir("/sys/class/powercap") {
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                // Package domains are "intel-rapl:N"; "intel-rapl:N:M" are subdomains
+                if name.starts_with("intel-rapl:") && name.matches(':').count() == 1 {
+                    let path = entry.path();
+                    let max = std::fs::read_to_string(path.join("max_energy_range_uj"))
+                        .ok()
+                        .and_then(|s| s.trim().parse().ok());
+                    domains.push((path.join("energy_uj"), max, None));
+                }
+            }
+        }
+        Self { domains }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn open() -> Self {
+        Self {
+            domains: Vec::new(),
+        }
+    }
+
+    /// Energy in joules consumed by all packages since the previous call
+    fn delta_joules(&mut self) -> Option<f64> {
+        let mut total_uj = None;
+        for (path, max, last) in &mut self.domains {
+            let Some(cur) = std::fs::read_to_string(&*path)
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+            else {
+                continue;
+            };
+            if let Some(prev) = last.replace(cur) {
+                *total_uj.get_or_insert(0) += counter_delta(prev, cur, *max);
+            }
+        }
+        total_uj.map(|uj| uj as f64 / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_shares() {
+        assert_eq!(cpu_share(500, 1000), 0.5);
+        assert_eq!(cpu_share(2000, 1000), 1.0);
+        assert_eq!(cpu_share(10, 0), 0.0);
+
+        // Per-process utilization wins over memory share
+        assert_eq!(gpu_share(Some(30.0), 60.0, 1, 4), 0.5);
+        // Fallback to memory share when utilization is unknown
+        assert_eq!(gpu_share(None, 60.0, 1, 4), 0.25);
+        assert_eq!(gpu_share(Some(30.0), 0.0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_counter_wraparound() {
+        assert_eq!(counter_delta(100, 250, Some(1000)), 150);
+        assert_eq!(counter_delta(900, 100, Some(1000)), 200);
+        assert_eq!(counter_delta(900, 100, None), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_job() {
+        let config = JobConfig::default()
+            .with_sample_interval(Duration::from_millis(20))
+            .with_gpus(false);
+        let command = |script: &str| vec!["sh".to_string(), "-c".to_string(), script.to_string()];
+
+        // Another child of ours that burns CPU must not be charged to the job
+        let busy = Command::new("sh")
+            .args(["-c", "i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done"])
+            .status()
+            .unwrap();
+        assert!(busy.success());
+
+        let summary = run_job(&command("exit 3"), &config).unwrap();
+        assert_eq!(summary.exit_code, Some(3));
+        assert_eq!(summary.signal, None);
+        assert!(summary.samples >= 1);
+        assert!(summary.cpu.user_secs + summary.cpu.system_secs < 0.1);
+
+        let summary = run_job(&command("kill -TERM $$"), &config).unwrap();
+        assert_eq!(summary.exit_code, None);
+        assert_eq!(summary.signal, Some(libc::SIGTERM));
+
+        assert!(run_job(&[], &config).is_err());
+    }
+}
@@ -125,24 +125,44 @@ pub mod connections; // Network connection monitoring (netstat-like)
 pub mod consent; // User consent management for ethical data collection
 pub mod core;
 pub mod cpufreq; // CPU frequency scaling and governor control
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub mod daemon; // Long-running simond daemon with an HTTP/WebSocket API
 pub mod disk; // Disk/storage monitoring
 pub mod error;
+#[cfg(target_os = "linux")]
+pub mod ethtool; // NIC driver statistics, rings, coalescing and offloads via ethtool
 pub mod fan_control; // Advanced fan monitoring and control
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub mod fleet; // Fleet-wide polling of simond daemons across many hosts
 pub mod gpu; // GPU abstraction layer
 pub mod health; // System health scoring and alerts
 pub mod hwmon; // Hardware monitoring (temperatures, voltages, fans) - native implementation
+pub mod hwlog; // Kernel log and journal correlation for hardware events
+pub mod job_accounting; // Per-job resource and energy accounting (time -v style)
+pub mod memory_errors; // Host DRAM ECC (EDAC) and machine-check error monitoring
 pub mod memory_management; // Memory and swap management (jetson_stats style)
 pub mod motherboard; // Motherboard sensors, BIOS, system information
 pub mod network_monitor; // Network interface monitoring
 pub mod network_tools; // Network diagnostic tools (ping, traceroute, port scan) - nmap/netcat style
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub mod otlp; // OpenTelemetry OTLP/HTTP metrics export
+pub mod pcie; // PCIe link health and AER error monitoring
 pub mod platform;
 pub mod power_supply; // Battery and power supply monitoring
 pub mod process_monitor; // Unified process monitoring with GPU attribution
+#[cfg(target_os = "linux")]
+pub mod process_network; // Per-process network bandwidth (nethogs-style)
+pub mod rdma; // InfiniBand/RDMA port counters and link state
 pub mod sandbox; // Sandbox and VM detection for ethical data collection
 pub mod services; // System service monitoring and control
 pub mod silicon; // New: Unified silicon monitoring (CPU, NPU, I/O, network)
+pub mod sinks; // Metric output sinks (InfluxDB line protocol, StatsD/DogStatsD)
+#[cfg(target_os = "linux")]
+pub mod sock_diag; // Socket enumeration and tcp_info over NETLINK_SOCK_DIAG
 pub mod stats;
 pub mod system_stats; // System-wide stats (load avg, vmstat, uptime) - Linux/BSD style
+pub mod throttling; // Thermal and power throttling detection across CPUs and GPUs
+pub mod topology; // PCIe topology and NUMA affinity (nvidia-smi topo style)
 pub mod utils;
 
 // Unified backend for CLI, TUI, and GUI
@@ -181,7 +201,10 @@ pub use gpu::{
 // Re-export new unified GPU traits (preferred)
 pub use gpu::traits;
 // Note: GpuError conflicts with crate::Error::GpuError variant
-pub use gpu::{Clocks, Device, Memory, Power, Temperature, Utilization, Vendor};
+pub use gpu::{
+    Capabilities, Capability, Clocks, Device, DeviceGpu, Memory, Power, Temperature, Utilization,
+    Vendor,
+};
 
 // Re-export process monitor
 pub use process_monitor::{ProcessGpuType, ProcessMonitor, ProcessMonitorInfo};
@@ -192,6 +215,18 @@ pub use network_monitor::{NetworkInterfaceInfo, NetworkMonitor};
 // Re-export connection monitor (netstat-like)
 pub use connections::{ConnectionInfo, ConnectionMonitor, ConnectionState, Protocol};
 
+// Re-export monitoring daemon and client
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub use daemon::{Daemon, DaemonClient, DaemonConfig};
+
+// Re-export fleet view
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub use fleet::{FleetHost, FleetMonitor, GpuRanking, HostStatus, HostSummary};
+
+// Re-export OTLP metrics export
+#[cfg(any(feature = "cli", feature = "gui"))]
+pub use otlp::{OtlpConfig, OtlpExporter, OtlpProtocol};
+
 // Re-export AI workload monitoring
 pub use ai_workload::{
     AiFramework, AiWorkload, AiWorkloadMonitor, CloudProvider, DistributedConfig, InferenceMetrics,
@@ -268,8 +303,8 @@ pub use power_supply::{
 
 // Re-export system health monitoring
 pub use health::{
-    has_critical_issues, health_score, quick_health_check, HealthCheck, HealthStatus,
-    HealthThresholds, SystemHealth,
+    has_critical_issues, health_score, quick_health_check, HealthCheck, HealthMonitor,
+    HealthStatus, HealthThresholds, SystemHealth,
 };
 
 // Re-export bandwidth testing (iperf-style)
@@ -278,6 +313,49 @@ pub use bandwidth::{
     BandwidthConfig, BandwidthResult, MemoryBandwidthResult, DEFAULT_BUFFER_SIZE, DEFAULT_PORT,
 };
 
+// Re-export ethtool NIC statistics
+#[cfg(target_os = "linux")]
+pub use ethtool::{
+    Channels, Coalesce, DriverInfo, DropRate, Duplex, Ethtool, EthtoolInfo, EthtoolMonitor,
+    Feature, LinkSettings, QueueDirection, QueueStat, RingParams,
+};
+
+// Re-export per-process network bandwidth
+#[cfg(target_os = "linux")]
+pub use process_network::{
+    ConnectionRate, ContainerNetUsage, NetUsage, ProcessNetMonitor, ProcessNetUsage,
+    SocketOwnerIndex,
+};
+#[cfg(target_os = "linux")]
+pub use sock_diag::{InetSocket, SockDiag, SocketProtocol, TcpInfo, UnixSocket, UnixSocketType};
+
+// Re-export hardware event log correlation
+pub use hwlog::{
+    EventDevice, EventSeverity, HardwareEvent, HardwareEventKind, HardwareEventMonitor,
+};
+
+// Re-export memory error monitoring (EDAC / MCE)
+pub use memory_errors::{
+    Dimm, MceErrorClass, MceRecord, MceStatus, MemoryController, MemoryErrorMonitor,
+};
+
+// Re-export PCIe link health
+pub use pcie::{AerCounter, AerCounters, AerRate, PcieDevice, PcieLink, PcieMonitor};
+
+// Re-export RDMA port monitoring
+pub use rdma::{PortState, RdmaDevice, RdmaMonitor, RdmaPort, RdmaPortRate};
+
+// Re-export throttling detection
+pub use throttling::{
+    CauseStats, DeviceThrottle, GpuThrottleStatus, ThrottleCause, ThrottleDevice, ThrottleMonitor,
+};
+
+// Re-export PCIe topology
+pub use topology::{LinkType, TopoDevice, TopoDeviceKind, Topology, TopologyMatrix};
+
+// Re-export per-job accounting
+pub use job_accounting::{run_job, JobConfig, JobMonitor, JobSummary};
+
 // Re-export fan control
 pub use fan_control::{
     fan_summary, list_fans, list_thermal_zones, FanControlMode, FanCurve, FanCurvePoint, FanInfo,
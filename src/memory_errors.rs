@@ -0,0 +1,865 @@
+//! Host memory error monitoring (EDAC and machine checks)
+//!
+//! Reads DRAM error counters from the kernel's EDAC subsystem
+//! (`/sys/devices/system/edac/mc/mc*`) per memory controller, chip-select
+//! row and DIMM, and decodes machine check (MCE) records logged to the
+//! kernel log. Counter deltas over the last hour give error rates, which is
+//! what predicts a failing DIMM: a steady trickle of corrected errors
+//! usually precedes the uncorrectable one that takes the node down.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::memory_errors::MemoryErrorMonitor;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = MemoryErrorMonitor::new();
+//!
+//! for mc in monitor.controllers()? {
+//!     println!("{} ({}): {} CE, {} UE", mc.name, mc.mc_name, mc.ce_count, mc.ue_count);
+//!     for dimm in &mc.dimms {
+//!         println!("  {}: {} CE, {} UE", dimm.label, dimm.ce_count, dimm.ue_count);
+//!     }
+//! }
+//!
+//! // Rates over the refreshes of the last hour
+//! monitor.refresh()?;
+//! std::thread::sleep(Duration::from_secs(60));
+//! for rate in monitor.refresh()? {
+//!     if rate.ce_window > 0 {
+//!         println!("{}: {:.1} CE/hour", rate.location, rate.ce_per_hour);
+//!     }
+//! }
+//!
+//! // Decoded machine checks from the kernel log
+//! for mce in monitor.mce_records()? {
+//!     println!("CPU {:?} bank {}: {}", mce.cpu, mce.bank, mce.status());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::hwlog::{self, LogRecord};
+use crate::utils::{RateWindow, DEFAULT_RATE_WINDOW};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default EDAC sysfs root
+const EDAC_MC_PATH: &str = "/sys/devices/system/edac/mc";
+
+/// (controller index, DIMM index) of a counter; `None` for the controller
+type Location = (u32, Option<u32>);
+
+/// EDAC memory controller
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryController {
+    /// Controller index (N in mcN)
+    pub index: u32,
+    /// sysfs name ("mc0")
+    pub name: String,
+    /// Driver-reported controller name (e.g. "Skylake Socket#0 IMC#0")
+    pub mc_name: String,
+    /// Memory size managed by this controller (MB)
+    pub size_mb: Option<u64>,
+    /// Corrected error count
+    pub ce_count: u64,
+    /// Uncorrected error count
+    pub ue_count: u64,
+    /// Corrected errors that could not be attributed to a DIMM
+    pub ce_noinfo_count: u64,
+    /// Uncorrected errors that could not be attributed to a DIMM
+    pub ue_noinfo_count: u64,
+    /// Seconds since the counters were last reset (usually boot)
+    pub seconds_since_reset: Option<u64>,
+    /// Legacy chip-select rows
+    pub csrows: Vec<CsRow>,
+    /// DIMMs (or ranks) with per-DIMM counters
+    pub dimms: Vec<Dimm>,
+}
+
+impl MemoryController {
+    /// Average corrected errors per hour since the counters were reset
+    pub fn ce_rate_per_hour(&self) -> Option<f64> {
+        rate_per_hour(self.ce_count, self.seconds_since_reset?)
+    }
+
+    /// Average uncorrected errors per hour since the counters were reset
+    pub fn ue_rate_per_hour(&self) -> Option<f64> {
+        rate_per_hour(self.ue_count, self.seconds_since_reset?)
+    }
+}
+
+/// EDAC chip-select row
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsRow {
+    /// Row index
+    pub index: u32,
+    /// Row size (MB)
+    pub size_mb: Option<u64>,
+    /// Memory type (e.g. "Registered-DDR4")
+    pub mem_type: Option<String>,
+    /// ECC mode (e.g. "S4ECD4ED")
+    pub edac_mode: Option<String>,
+    /// Corrected error count
+    pub ce_count: u64,
+    /// Uncorrected error count
+    pub ue_count: u64,
+    /// Per-channel counters and labels
+    pub channels: Vec<CsRowChannel>,
+}
+
+/// Channel of a chip-select row
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsRowChannel {
+    /// Channel index
+    pub index: u32,
+    /// DIMM label (e.g. "CPU_SrcID#0_Ha#0_Chan#0_DIMM#0")
+    pub label: Option<String>,
+    /// Corrected error count
+    pub ce_count: u64,
+}
+
+/// DIMM (or rank) with its own error counters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dimm {
+    /// DIMM index within the controller
+    pub index: u32,
+    /// Silkscreen or BIOS label (e.g. "CPU0_DIMM_A1")
+    pub label: String,
+    /// Location within the controller (e.g. "channel 0 slot 0")
+    pub location: Option<String>,
+    /// Size (MB)
+    pub size_mb: Option<u64>,
+    /// Memory type (e.g. "Registered-DDR4")
+    pub mem_type: Option<String>,
+    /// ECC mode
+    pub edac_mode: Option<String>,
+    /// Corrected error count
+    pub ce_count: u64,
+    /// Uncorrected error count
+    pub ue_count: u64,
+}
+
+/// Recent errors of one DIMM or controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorRate {
+    /// DIMM label or controller name
+    pub location: String,
+    /// Controller index
+    pub controller: u32,
+    /// DIMM index, `None` for errors not attributed to a DIMM
+    pub dimm: Option<u32>,
+    /// New corrected errors since the previous refresh
+    pub ce_delta: u64,
+    /// New uncorrected errors since the previous refresh
+    pub ue_delta: u64,
+    /// Corrected errors within the rate window
+    pub ce_window: u64,
+    /// Uncorrected errors within the rate window
+    pub ue_window: u64,
+    /// Corrected errors per hour over the rate window
+    pub ce_per_hour: f64,
+    /// Uncorrected errors per hour over the rate window
+    pub ue_per_hour: f64,
+    /// Time since the previous refresh
+    pub interval: Duration,
+    /// Time the rate window covers so far
+    pub window: Duration,
+}
+
+/// Error class from the MCA error code (bits 15:0 of MCi_STATUS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MceErrorClass {
+    /// Memory controller error
+    Memory {
+        /// Channel, if specified
+        channel: Option<u8>,
+    },
+    /// Cache hierarchy error
+    Cache {
+        /// Cache level (0-2, 3 = generic)
+        level: u8,
+    },
+    /// TLB error
+    Tlb {
+        /// TLB level
+        level: u8,
+    },
+    /// Bus or interconnect error
+    Bus,
+    /// Internal unclassified or simple error
+    Internal,
+    /// Unrecognized error code
+    Unknown,
+}
+
+impl std::fmt::Display for MceErrorClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MceErrorClass::Memory { channel: Some(c) } => {
+                write!(f, "memory controller (channel {})", c)
+            }
+            MceErrorClass::Memory { channel: None } => write!(f, "memory controller"),
+            MceErrorClass::Cache { level: 3 } => write!(f, "cache"),
+            MceErrorClass::Cache { level } => write!(f, "L{} cache", level),
+            MceErrorClass::Tlb { level } => write!(f, "L{} TLB", level),
+            MceErrorClass::Bus => write!(f, "bus/interconnect"),
+            MceErrorClass::Internal => write!(f, "internal"),
+            MceErrorClass::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Decoded MCi_STATUS register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MceStatus(pub u64);
+
+impl MceStatus {
+    /// VAL: the register contains a valid error
+    pub fn valid(&self) -> bool {
+        self.bit(63)
+    }
+
+    /// OVER: an earlier error was overwritten
+    pub fn overflow(&self) -> bool {
+        self.bit(62)
+    }
+
+    /// UC: the error was not corrected
+    pub fn uncorrected(&self) -> bool {
+        self.bit(61)
+    }
+
+    /// EN: error reporting was enabled
+    pub fn enabled(&self) -> bool {
+        self.bit(60)
+    }
+
+    /// ADDRV: the ADDR register holds the error address
+    pub fn address_valid(&self) -> bool {
+        self.bit(58)
+    }
+
+    /// PCC: processor context corrupt
+    pub fn context_corrupt(&self) -> bool {
+        self.bit(57)
+    }
+
+    /// Corrected error count (bits 52:38, when CMCI is supported)
+    pub fn corrected_count(&self) -> u16 {
+        ((self.0 >> 38) & 0x7fff) as u16
+    }
+
+    /// MCA error code (bits 15:0)
+    pub fn mca_code(&self) -> u16 {
+        (self.0 & 0xffff) as u16
+    }
+
+    /// Model-specific error code (bits 31:16)
+    pub fn model_code(&self) -> u16 {
+        ((self.0 >> 16) & 0xffff) as u16
+    }
+
+    /// Classify the MCA error code
+    pub fn error_class(&self) -> MceErrorClass {
+        // Bit 12 is the "filtered" flag for compound codes
+        let code = self.mca_code() & !0x1000;
+        if code & 0xfffc == 0x000c {
+            MceErrorClass::Cache {
+                level: (code & 0x3) as u8,
+            }
+        } else if code & 0xfff0 == 0x0010 {
+            MceErrorClass::Tlb {
+                level: (code & 0x3) as u8,
+            }
+        } else if code & 0xff80 == 0x0080 {
+            let channel = (code & 0xf) as u8;
+            MceErrorClass::Memory {
+                channel: (channel != 0xf).then_some(channel),
+            }
+        } else if code & 0xff00 == 0x0100 {
+            MceErrorClass::Cache {
+                level: (code & 0x3) as u8,
+            }
+        } else if code & 0xf800 == 0x0800 {
+            MceErrorClass::Bus
+        } else if code & 0xfc00 == 0x0400 || (1..=6).contains(&code) {
+            MceErrorClass::Internal
+        } else {
+            MceErrorClass::Unknown
+        }
+    }
+
+    fn bit(&self, n: u32) -> bool {
+        self.0 & (1 << n) != 0
+    }
+}
+
+impl std::fmt::Display for MceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} error",
+            if self.uncorrected() {
+                "uncorrected"
+            } else {
+                "corrected"
+            },
+            self.error_class()
+        )?;
+        if self.context_corrupt() {
+            write!(f, ", context corrupt")?;
+        }
+        if self.overflow() {
+            write!(f, ", overflow")?;
+        }
+        Ok(())
+    }
+}
+
+/// Machine check record reassembled from the kernel log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MceRecord {
+    /// Logical CPU that logged the error
+    pub cpu: Option<u32>,
+    /// MCA bank
+    pub bank: u32,
+    /// MCi_STATUS
+    pub status: u64,
+    /// MCi_ADDR, if logged
+    pub address: Option<u64>,
+    /// MCi_MISC, if logged
+    pub misc: Option<u64>,
+    /// Socket
+    pub socket: Option<u32>,
+    /// APIC id
+    pub apic: Option<u32>,
+    /// Time of the error (Unix seconds, from the record itself)
+    pub time: Option<u64>,
+    /// Time the kernel logged the record (microseconds since the Unix epoch)
+    pub logged_us: Option<u64>,
+}
+
+impl MceRecord {
+    /// Decoded status register
+    pub fn status(&self) -> MceStatus {
+        MceStatus(self.status)
+    }
+
+    /// Whether the error came from a memory controller
+    pub fn is_memory_error(&self) -> bool {
+        matches!(self.status().error_class(), MceErrorClass::Memory { .. })
+    }
+}
+
+/// EDAC and machine check monitor
+pub struct MemoryErrorMonitor {
+    /// EDAC sysfs root (mc directory)
+    root: PathBuf,
+    /// Counter deltas of recent refreshes, keyed by location
+    rates: RateWindow<Location, 2>,
+    /// Location names from the latest refresh
+    names: HashMap<Location, String>,
+}
+
+impl Default for MemoryErrorMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryErrorMonitor {
+    /// Create a monitor for the system EDAC tree
+    pub fn new() -> Self {
+        Self::with_sysfs_root(EDAC_MC_PATH)
+    }
+
+    /// Create a monitor reading a different EDAC `mc` directory
+    pub fn with_sysfs_root<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            rates: RateWindow::new(DEFAULT_RATE_WINDOW),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Compute rates over a different window (default one hour)
+    pub fn with_rate_window(mut self, window: Duration) -> Self {
+        self.rates = RateWindow::new(window);
+        self
+    }
+
+    /// Whether an EDAC driver is loaded
+    pub fn is_available(&self) -> bool {
+        self.root.is_dir()
+    }
+
+    /// Read all memory controllers
+    ///
+    /// Returns an empty list when no EDAC driver is loaded.
+    pub fn controllers(&self) -> Result<Vec<MemoryController>> {
+        let entries = match std::fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut controllers: Vec<MemoryController> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let index = indexed_name(&name, "mc")?;
+                Some(read_controller(&entry.path(), index, name))
+            })
+            .collect();
+        controllers.sort_by_key(|mc| mc.index);
+        Ok(controllers)
+    }
+
+    /// Read counters and return per-DIMM errors and rates
+    ///
+    /// Rates are taken over the refreshes within the rate window, and
+    /// divided by at least 15 minutes so that one error shortly after
+    /// the first refresh does not extrapolate to a high rate. The first
+    /// call records a baseline and returns an empty list. Controllers
+    /// without per-DIMM counters are reported as a whole; with DIMM
+    /// counters, errors the driver could not attribute to a DIMM are
+    /// reported for the controller.
+    pub fn refresh(&mut self) -> Result<Vec<ErrorRate>> {
+        let mut totals = HashMap::new();
+        self.names.clear();
+        for (location, name, ce, ue) in counters_by_location(&self.controllers()?) {
+            totals.insert(location, [ce, ue]);
+            self.names.insert(location, name);
+        }
+
+        let Some(counts) = self.rates.update(Instant::now(), totals) else {
+            return Ok(Vec::new());
+        };
+        let mut rates: Vec<ErrorRate> = counts
+            .into_iter()
+            .map(|((controller, dimm), counts)| ErrorRate {
+                location: self.names[&(controller, dimm)].clone(),
+                controller,
+                dimm,
+                ce_delta: counts.delta[0],
+                ue_delta: counts.delta[1],
+                ce_window: counts.in_window[0],
+                ue_window: counts.in_window[1],
+                ce_per_hour: counts.per_hour(0),
+                ue_per_hour: counts.per_hour(1),
+                interval: counts.interval,
+                window: counts.span,
+            })
+            .collect();
+        rates.sort_by_key(|r| (r.controller, r.dimm));
+        Ok(rates)
+    }
+
+    /// Machine check records from the kernel log since boot
+    pub fn mce_records(&self) -> Result<Vec<MceRecord>> {
+        Ok(parse_mce_records(&hwlog::kernel_records()?))
+    }
+}
+
+/// Read all EDAC memory controllers
+pub fn memory_controllers() -> Result<Vec<MemoryController>> {
+    MemoryErrorMonitor::new().controllers()
+}
+
+/// Reassemble machine check records from kernel log lines
+///
+/// Understands the generic x86 format:
+///
+/// ```text
+/// mce: [Hardware Error]: CPU 2: Machine Check: 0 Bank 7: 8c00004000010090
+/// mce: [Hardware Error]: TSC 0 ADDR 3f9d6c000 MISC 140686886
+/// mce: [Hardware Error]: PROCESSOR 0:306f2 TIME 1520448052 SOCKET 0 APIC 4 microcode 3b
+/// ```
+///
+/// and the AMD decoder's `CPU:0 (17:71:0) MC27_STATUS[...]: 0x...` lines.
+pub fn parse_mce_records<'a, I>(records: I) -> Vec<MceRecord>
+where
+    I: IntoIterator<Item = &'a LogRecord>,
+{
+    let mut out: Vec<MceRecord> = Vec::new();
+
+    for record in records {
+        let msg = record.message.as_str();
+        let Some((_, body)) = msg.split_once("[Hardware Error]: ") else {
+            continue;
+        };
+
+        if let Some(mce) = parse_bank_line(body).or_else(|| parse_amd_status_line(body)) {
+            out.push(MceRecord {
+                logged_us: record.timestamp_us,
+                ..mce
+            });
+            continue;
+        }
+
+        // Continuation lines complete the last record
+        let Some(last) = out.last_mut() else {
+            continue;
+        };
+        if body.starts_with("TSC ") || body.starts_with("ADDR ") || body.starts_with("MISC ") {
+            last.address = hex_after(body, "ADDR ").or(last.address);
+            last.misc = hex_after(body, "MISC ").or(last.misc);
+        } else if body.starts_with("PROCESSOR ") {
+            last.time = word_after(body, "TIME ").and_then(|t| t.parse().ok());
+            last.socket = word_after(body, "SOCKET ").and_then(|s| s.parse().ok());
+            last.apic = word_after(body, "APIC ").and_then(|a| a.parse().ok());
+        } else if let Some(addr) = body.strip_prefix("Error Addr: 0x") {
+            // AMD decoder
+            last.address = u64::from_str_radix(addr.trim(), 16).ok();
+        }
+    }
+
+    out
+}
+
+/// `CPU 2: Machine Check: 0 Bank 7: 8c00004000010090`
+fn parse_bank_line(body: &str) -> Option<MceRecord> {
+    if !body.contains("Machine Check") {
+        return None;
+    }
+    let cpu = word_after(body, "CPU ")
+        .map(|c| c.trim_end_matches(':'))
+        .and_then(|c| c.parse().ok());
+    let (_, bank_part) = body.split_once(" Bank ")?;
+    let (bank, status) = bank_part.split_once(": ")?;
+    Some(MceRecord {
+        cpu,
+        bank: bank.trim().parse().ok()?,
+        status: u64::from_str_radix(status.split_whitespace().next()?, 16).ok()?,
+        address: None,
+        misc: None,
+        socket: None,
+        apic: None,
+        time: None,
+        logged_us: None,
+    })
+}
+
+/// `CPU:0 (17:71:0) MC27_STATUS[Over|CE|MiscV|AddrV|-|-|SyndV|CECC|-|-|-]: 0xdc2040000000011b`
+fn parse_amd_status_line(body: &str) -> Option<MceRecord> {
+    let cpu = body
+        .strip_prefix("CPU:")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|c| c.parse().ok());
+    let (_, mc) = body.split_once(" MC")?;
+    let (bank, rest) = mc.split_once("_STATUS")?;
+    let (_, status) = rest.rsplit_once(": 0x")?;
+    Some(MceRecord {
+        cpu,
+        bank: bank.parse().ok()?,
+        status: u64::from_str_radix(status.trim(), 16).ok()?,
+        address: None,
+        misc: None,
+        socket: None,
+        apic: None,
+        time: None,
+        logged_us: None,
+    })
+}
+
+fn read_controller(path: &Path, index: u32, name: String) -> MemoryController {
+    let mut mc = MemoryController {
+        index,
+        name,
+        mc_name: read_string(&path.join("mc_name")).unwrap_or_default(),
+        size_mb: read_u64(&path.join("size_mb")),
+        ce_count: read_u64(&path.join("ce_count")).unwrap_or(0),
+        ue_count: read_u64(&path.join("ue_count")).unwrap_or(0),
+        ce_noinfo_count: read_u64(&path.join("ce_noinfo_count")).unwrap_or(0),
+        ue_noinfo_count: read_u64(&path.join("ue_noinfo_count")).unwrap_or(0),
+        seconds_since_reset: read_u64(&path.join("seconds_since_reset")),
+        csrows: Vec::new(),
+        dimms: Vec::new(),
+    };
+
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return mc;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let dir = entry.path();
+        if let Some(i) = indexed_name(&name, "csrow") {
+            mc.csrows.push(read_csrow(&dir, i));
+        } else if let Some(i) = indexed_name(&name, "dimm").or_else(|| indexed_name(&name, "rank"))
+        {
+            mc.dimms.push(read_dimm(&dir, i));
+        }
+    }
+    mc.csrows.sort_by_key(|r| r.index);
+    mc.dimms.sort_by_key(|d| d.index);
+    mc
+}
+
+fn read_csrow(path: &Path, index: u32) -> CsRow {
+    let mut channels = Vec::new();
+    for ch in 0.. {
+        let ce = path.join(format!("ch{}_ce_count", ch));
+        if !ce.exists() {
+            break;
+        }
+        channels.push(CsRowChannel {
+            index: ch,
+            label: read_string(&path.join(format!("ch{}_dimm_label", ch)))
+                .filter(|l| !l.is_empty()),
+            ce_count: read_u64(&ce).unwrap_or(0),
+        });
+    }
+
+    CsRow {
+        index,
+        size_mb: read_u64(&path.join("size_mb")),
+        mem_type: read_string(&path.join("mem_type")),
+        edac_mode: read_string(&path.join("edac_mode")),
+        ce_count: read_u64(&path.join("ce_count")).unwrap_or(0),
+        ue_count: read_u64(&path.join("ue_count")).unwrap_or(0),
+        channels,
+    }
+}
+
+fn read_dimm(path: &Path, index: u32) -> Dimm {
+    Dimm {
+        index,
+        label: read_string(&path.join("dimm_label"))
+            .filter(|l| !l.is_empty())
+            .unwrap_or_else(|| format!("dimm{}", index)),
+        location: read_string(&path.join("dimm_location")),
+        size_mb: read_u64(&path.join("size")),
+        mem_type: read_string(&path.join("dimm_mem_type")),
+        edac_mode: read_string(&path.join("dimm_edac_mode")),
+        ce_count: read_u64(&path.join("dimm_ce_count")).unwrap_or(0),
+        ue_count: read_u64(&path.join("dimm_ue_count")).unwrap_or(0),
+    }
+}
+
+/// (location, name, CE count, UE count) per DIMM and per controller
+///
+/// A controller without DIMM counters is reported with its totals; one
+/// with DIMM counters with the errors it could not attribute to a DIMM.
+fn counters_by_location(controllers: &[MemoryController]) -> Vec<(Location, String, u64, u64)> {
+    let mut counts = Vec::new();
+    for mc in controllers {
+        if mc.dimms.is_empty() {
+            counts.push(((mc.index, None), mc.name.clone(), mc.ce_count, mc.ue_count));
+            continue;
+        }
+        counts.push((
+            (mc.index, None),
+            mc.name.clone(),
+            mc.ce_noinfo_count,
+            mc.ue_noinfo_count,
+        ));
+        for dimm in &mc.dimms {
+            counts.push((
+                (mc.index, Some(dimm.index)),
+                format!("{}/{}", mc.name, dimm.label),
+                dimm.ce_count,
+                dimm.ue_count,
+            ));
+        }
+    }
+    counts
+}
+
+fn rate_per_hour(count: u64, secs: u64) -> Option<f64> {
+    (secs > 0).then(|| count as f64 * 3600.0 / secs as f64)
+}
+
+/// Parse "mc3" / "csrow1" / "dimm0" into its index
+fn indexed_name(name: &str, prefix: &str) -> Option<u32> {
+    name.strip_prefix(prefix)?.parse().ok()
+}
+
+fn word_after<'a>(s: &'a str, key: &str) -> Option<&'a str> {
+    s.split_once(key)?.1.split_whitespace().next()
+}
+
+fn hex_after(s: &str, key: &str) -> Option<u64> {
+    u64::from_str_radix(word_after(s, key)?.trim_start_matches("0x"), 16).ok()
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_string(path)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Build a fake EDAC tree with one controller and two DIMMs
+    fn fake_edac() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let mc = root.join("mc0");
+        fs::create_dir_all(mc.join("dimm0")).unwrap();
+        fs::create_dir_all(mc.join("dimm1")).unwrap();
+        fs::create_dir_all(mc.join("csrow0")).unwrap();
+
+        let write = |path: &str, value: &str| fs::write(mc.join(path), value).unwrap();
+        write("mc_name", "Skylake Socket#0 IMC#0\n");
+        write("size_mb", "65536\n");
+        write("ce_count", "7\n");
+        write("ue_count", "0\n");
+        write("seconds_since_reset", "7200\n");
+        write("dimm0/dimm_label", "CPU0_DIMM_A1\n");
+        write("dimm0/dimm_location", "channel 0 slot 0\n");
+        write("dimm0/dimm_ce_count", "7\n");
+        write("dimm0/dimm_ue_count", "0\n");
+        write("dimm1/dimm_label", "CPU0_DIMM_B1\n");
+        write("dimm1/dimm_ce_count", "0\n");
+        write("dimm1/dimm_ue_count", "0\n");
+        write("csrow0/ce_count", "7\n");
+        write("csrow0/ch0_ce_count", "7\n");
+        write("csrow0/ch0_dimm_label", "CPU0_DIMM_A1\n");
+        write("csrow0/ch1_ce_count", "0\n");
+        dir
+    }
+
+    #[test]
+    fn test_read_controllers() {
+        let dir = fake_edac();
+        let root = dir.path();
+        let controllers = MemoryErrorMonitor::with_sysfs_root(root)
+            .controllers()
+            .unwrap();
+
+        assert_eq!(controllers.len(), 1);
+        let mc = &controllers[0];
+        assert_eq!(mc.mc_name, "Skylake Socket#0 IMC#0");
+        assert_eq!(mc.ce_count, 7);
+        assert_eq!(mc.ce_rate_per_hour(), Some(3.5));
+        assert_eq!(mc.dimms.len(), 2);
+        assert_eq!(mc.dimms[0].label, "CPU0_DIMM_A1");
+        assert_eq!(mc.dimms[0].location.as_deref(), Some("channel 0 slot 0"));
+        assert_eq!(mc.csrows[0].channels.len(), 2);
+        assert_eq!(
+            mc.csrows[0].channels[0].label.as_deref(),
+            Some("CPU0_DIMM_A1")
+        );
+    }
+
+    #[test]
+    fn test_refresh_rates() {
+        let dir = fake_edac();
+        let root = dir.path();
+        let mut monitor = MemoryErrorMonitor::with_sysfs_root(root);
+        assert!(monitor.refresh().unwrap().is_empty());
+
+        fs::write(root.join("mc0/dimm0/dimm_ce_count"), "10\n").unwrap();
+        let rates = monitor.refresh().unwrap();
+        let a1 = rates.iter().find(|r| r.dimm == Some(0)).unwrap();
+        assert_eq!(a1.location, "mc0/CPU0_DIMM_A1");
+        assert_eq!(a1.ce_delta, 3);
+        assert_eq!(a1.ce_window, 3);
+        // Divided by the 15 minute minimum span, not the refresh interval
+        assert_eq!(a1.ce_per_hour, 12.0);
+        let b1 = rates.iter().find(|r| r.dimm == Some(1)).unwrap();
+        assert_eq!(b1.ce_delta, 0);
+
+        // Still counted in the window after a quiet refresh
+        let rates = monitor.refresh().unwrap();
+        let a1 = rates.iter().find(|r| r.dimm == Some(0)).unwrap();
+        assert_eq!(a1.ce_delta, 0);
+        assert_eq!(a1.ce_window, 3);
+    }
+
+    #[test]
+    fn test_refresh_same_labels_and_noinfo() {
+        let dir = fake_edac();
+        let root = dir.path();
+        // Both DIMMs carry the driver's placeholder label
+        fs::write(root.join("mc0/dimm1/dimm_label"), "CPU0_DIMM_A1\n").unwrap();
+        let mut monitor = MemoryErrorMonitor::with_sysfs_root(root);
+        monitor.refresh().unwrap();
+
+        fs::write(root.join("mc0/dimm1/dimm_ce_count"), "2\n").unwrap();
+        fs::write(root.join("mc0/ce_noinfo_count"), "4\n").unwrap();
+        let rates = monitor.refresh().unwrap();
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].dimm, None);
+        assert_eq!(rates[0].ce_delta, 4);
+        assert_eq!(rates[1].ce_delta, 0);
+        assert_eq!(rates[2].location, "mc0/CPU0_DIMM_A1");
+        assert_eq!(rates[2].ce_delta, 2);
+    }
+
+    #[test]
+    fn test_missing_edac() {
+        let monitor = MemoryErrorMonitor::with_sysfs_root("/nonexistent/edac/mc");
+        assert!(!monitor.is_available());
+        assert!(monitor.controllers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_status() {
+        // Corrected memory read error on channel 0
+        let status = MceStatus(0x8c00004000010090);
+        assert!(status.valid());
+        assert!(!status.uncorrected());
+        assert!(status.address_valid());
+        assert_eq!(status.corrected_count(), 1);
+        assert_eq!(
+            status.error_class(),
+            MceErrorClass::Memory { channel: Some(0) }
+        );
+
+        // Uncorrected, processor context corrupt, L2 cache error
+        let status = MceStatus(0xb200000000000136);
+        assert!(status.uncorrected());
+        assert!(status.context_corrupt());
+        assert_eq!(status.error_class(), MceErrorClass::Cache { level: 2 });
+        assert_eq!(
+            status.to_string(),
+            "uncorrected L2 cache error, context corrupt"
+        );
+    }
+
+    #[test]
+    fn test_parse_mce_records() {
+        let lines = [
+            "mce: [Hardware Error]: Machine check events logged",
+            "mce: [Hardware Error]: CPU 2: Machine Check: 0 Bank 7: 8c00004000010090",
+            "mce: [Hardware Error]: TSC 0 ADDR 3f9d6c000 MISC 140686886 ",
+            "mce: [Hardware Error]: PROCESSOR 0:306f2 TIME 1520448052 SOCKET 0 APIC 4 microcode 3b",
+            "[Hardware Error]: CPU:0 (17:71:0) MC27_STATUS[Over|CE|MiscV|AddrV|-|-|SyndV|CECC|-|-|-]: 0xdc2040000000011b",
+            "[Hardware Error]: Error Addr: 0x00000002f0c0e1c0",
+        ];
+        let records: Vec<LogRecord> = lines
+            .iter()
+            .map(|l| LogRecord {
+                message: l.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let mces = parse_mce_records(&records);
+        assert_eq!(mces.len(), 2);
+        assert_eq!(mces[0].cpu, Some(2));
+        assert_eq!(mces[0].bank, 7);
+        assert_eq!(mces[0].address, Some(0x3f9d6c000));
+        assert_eq!(mces[0].time, Some(1520448052));
+        assert_eq!(mces[0].apic, Some(4));
+        assert!(mces[0].is_memory_error());
+        assert_eq!(mces[1].cpu, Some(0));
+        assert_eq!(mces[1].bank, 27);
+        assert_eq!(mces[1].address, Some(0x2f0c0e1c0));
+        assert!(mces[1].status().overflow());
+    }
+}
@@ -313,12 +313,17 @@ pub fn get_system_info() -> Result<SystemInfo, Error> {
         .ok()
         .map(|s| s.trim().to_string());
 
+    // Raw SMBIOS table (root only) fills in what /sys/class/dmi/id hides
+    let inventory = super::smbios::read_inventory().ok();
+    let smbios_bios = inventory.as_ref().and_then(|i| i.bios.as_ref());
+    let smbios_system = inventory.as_ref().and_then(|i| i.system.as_ref());
+
     // BIOS information
     let bios = BiosInfo {
         vendor: read_dmi("bios_vendor"),
         version: read_dmi("bios_version"),
         release_date: read_dmi("bios_date"),
-        revision: None,
+        revision: smbios_bios.and_then(|b| b.revision.clone()),
         firmware_type: detect_firmware_type(),
         secure_boot: None, // Would need to parse /sys/firmware/efi/efivars/SecureBoot-*
     };
@@ -326,8 +331,8 @@ pub fn get_system_info() -> Result<SystemInfo, Error> {
     // Hardware information
     let manufacturer = read_dmi("sys_vendor");
     let product_name = read_dmi("product_name");
-    let serial_number = read_dmi("product_serial");
-    let uuid = read_dmi("product_uuid");
+    let serial_number = read_dmi("product_serial").or_else(|| smbios_system?.serial_number.clone());
+    let uuid = read_dmi("product_uuid").or_else(|| smbios_system?.uuid.clone());
 
     let board_vendor = read_dmi("board_vendor");
     let board_name = read_dmi("board_name");
@@ -375,6 +380,7 @@ pub fn get_system_info() -> Result<SystemInfo, Error> {
         cpu_name,
         cpu_cores,
         cpu_threads,
+        inventory,
     })
 }
 
@@ -448,3 +454,76 @@ pub fn get_driver_versions() -> Result<Vec<DriverInfo>, Error> {
 
     Ok(drivers)
 }
+
+/// Get PCIe devices with link state and AER counters
+///
+/// Bridges (root and switch ports) are left out.
+pub fn get_pcie_devices() -> Result<Vec<PcieDeviceInfo>, Error> {
+    let devices = crate::pcie::pcie_devices().map_err(|e| Error::QueryFailed(e.to_string()))?;
+
+    Ok(devices
+        .into_iter()
+        .filter(|d| !d.is_bridge())
+        .map(|d| {
+            let vendor = pci_vendor_name(d.vendor_id);
+            let name = match (&vendor, &d.driver) {
+                (Some(vendor), Some(driver)) => {
+                    format!("{} {} ({})", vendor, d.class_name(), driver)
+                }
+                (Some(vendor), None) => format!("{} {}", vendor, d.class_name()),
+                (None, Some(driver)) => format!("{} ({})", d.class_name(), driver),
+                (None, None) => d.class_name().to_string(),
+            };
+            let link = d.link.as_ref();
+            let speed = |gts: f64| format!("{} GT/s", gts);
+            PcieDeviceInfo {
+                name,
+                device_id: Some(format!("{:04x}:{:04x}", d.vendor_id, d.device_id)),
+                vendor: vendor
+                    .map(str::to_string)
+                    .or(Some(format!("{:04x}", d.vendor_id))),
+                pcie_version: link
+                    .and_then(|l| l.current_gen())
+                    .map(|gen| format!("PCIe {}.0", gen)),
+                link_width: link.and_then(|l| l.current_width),
+                link_speed: link.and_then(|l| l.current_speed).map(speed),
+                slot: d.slot.clone(),
+                device_class: Some(d.class_name().to_string()),
+                max_link_width: link.and_then(|l| l.capable_width()),
+                max_link_speed: link.and_then(|l| l.capable_speed()).map(speed),
+                link_degraded: link.is_some_and(|l| l.is_degraded()),
+                aer_correctable: d.aer.as_ref().map(|a| a.correctable.total),
+                aer_nonfatal: d.aer.as_ref().map(|a| a.nonfatal.total),
+                aer_fatal: d.aer.as_ref().map(|a| a.fatal.total),
+                address: Some(d.address),
+            }
+        })
+        .collect())
+}
+
+/// Names of common PCI vendors
+fn pci_vendor_name(vendor_id: u16) -> Option<&'static str> {
+    Some(match vendor_id {
+        0x10de => "NVIDIA",
+        0x1002 => "AMD",
+        0x1022 => "AMD",
+        0x8086 => "Intel",
+        0x15b3 => "Mellanox",
+        0x14e4 => "Broadcom",
+        0x144d => "Samsung",
+        0x1c5c => "SK hynix",
+        0x15b7 => "Sandisk",
+        0x1987 => "Phison",
+        0x126f => "Silicon Motion",
+        0x10ec => "Realtek",
+        0x1d0f => "Amazon",
+        0x1af4 => "Red Hat (virtio)",
+        0x1000 => "Broadcom / LSI",
+        0x9005 => "Microchip (Adaptec)",
+        0x1a03 => "ASPEED",
+        0x1b21 => "ASMedia",
+        0x10b5 => "PLX / Broadcom",
+        0x1ded => "Alibaba",
+        _ => return None,
+    })
+}
@@ -85,6 +85,7 @@ pub fn get_system_info() -> Result<SystemInfo, Error> {
         cpu_name: None,
         cpu_cores: None,
         cpu_threads: None,
+        inventory: None,
     })
 }
 
@@ -6,6 +6,7 @@
 // - System information (manufacturer, model, serial)
 // - Hardware driver versions
 
+pub mod smbios;
 pub mod traits;
 
 #[cfg(target_os = "linux")]
@@ -108,7 +109,12 @@ pub fn get_pcie_devices() -> Result<Vec<PcieDeviceInfo>, Error> {
         windows::get_pcie_devices()
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "linux")]
+    {
+        linux::get_pcie_devices()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
     {
         Err(Error::NotSupported(
             "PCIe device enumeration not yet implemented for this platform".into(),
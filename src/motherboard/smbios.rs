@@ -0,0 +1,1811 @@
+// SMBIOS/DMI table decoder
+//
+// Decodes the raw firmware tables the kernel exports at
+// /sys/firmware/dmi/tables/{smbios_entry_point,DMI} without dmidecode.
+// /sys/class/dmi/id only carries a handful of type 0/1/2/3 strings; the raw
+// table also has every DIMM, CPU socket, cache, slot and onboard device.
+//
+// Supported structure types:
+// - 0  BIOS information
+// - 1  System information
+// - 2  Baseboard
+// - 3  Chassis
+// - 4  Processor
+// - 7  Cache
+// - 9  System slots
+// - 16 Physical memory array
+// - 17 Memory device
+// - 19 Memory array mapped address
+// - 38 IPMI device
+// - 41 Onboard devices extended
+//
+// Reading the raw table needs root; the files are mode 0400.
+
+use super::traits::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Directory holding the raw SMBIOS tables
+pub const DMI_TABLES_PATH: &str = "/sys/firmware/dmi/tables";
+
+/// Structure type marking the end of the table
+const END_OF_TABLE: u8 = 127;
+
+/// Entry point of the SMBIOS table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryPoint {
+    /// SMBIOS major version
+    pub major: u8,
+    /// SMBIOS minor version
+    pub minor: u8,
+    /// Document revision (3.x only)
+    pub docrev: u8,
+    /// Physical address of the table
+    pub table_address: u64,
+    /// Table length (2.x) or maximum size (3.x) in bytes
+    pub table_length: u32,
+    /// Number of structures (2.x only)
+    pub structure_count: Option<u16>,
+}
+
+impl EntryPoint {
+    /// Parse a 32-bit (`_SM_`), 64-bit (`_SM3_`) or legacy (`_DMI_`) entry point
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        let truncated = || Error::ParseError("SMBIOS entry point truncated".into());
+        // The declared length may be shorter than the fields read below
+        let byte = |offset: usize| data.get(offset).copied().ok_or_else(truncated);
+        let checksum = |len: usize| -> Result<(), Error> {
+            let bytes = data.get(..len).ok_or_else(truncated)?;
+            if bytes.iter().fold(0u8, |sum, b| sum.wrapping_add(*b)) != 0 {
+                return Err(Error::ParseError(
+                    "SMBIOS entry point checksum mismatch".into(),
+                ));
+            }
+            Ok(())
+        };
+
+        if data.starts_with(b"_SM3_") {
+            checksum(*data.get(0x06).unwrap_or(&0x18) as usize)?;
+            Ok(EntryPoint {
+                major: byte(0x07)?,
+                minor: byte(0x08)?,
+                docrev: byte(0x09)?,
+                table_length: le_u32(data, 0x0C).unwrap_or(0),
+                table_address: le_u64(data, 0x10).unwrap_or(0),
+                structure_count: None,
+            })
+        } else if data.starts_with(b"_SM_") {
+            checksum(*data.get(0x05).unwrap_or(&0x1F) as usize)?;
+            let (mut major, mut minor) = (byte(0x06)?, byte(0x07)?);
+            // Same fixups as dmidecode for firmware with bogus versions
+            match (major, minor) {
+                (2, 31) | (2, 33) => minor = 3,
+                (2, 51) => minor = 6,
+                _ => {}
+            }
+            if major == 0 {
+                major = 2;
+            }
+            Ok(EntryPoint {
+                major,
+                minor,
+                docrev: 0,
+                table_length: le_u16(data, 0x16).unwrap_or(0) as u32,
+                table_address: le_u32(data, 0x18).unwrap_or(0) as u64,
+                structure_count: le_u16(data, 0x1C),
+            })
+        } else if data.starts_with(b"_DMI_") {
+            checksum(0x0F)?;
+            Ok(EntryPoint {
+                major: byte(0x0E)? >> 4,
+                minor: byte(0x0E)? & 0x0F,
+                docrev: 0,
+                table_length: le_u16(data, 0x06).unwrap_or(0) as u32,
+                table_address: le_u32(data, 0x08).unwrap_or(0) as u64,
+                structure_count: le_u16(data, 0x0C),
+            })
+        } else {
+            Err(Error::ParseError(
+                "Unknown SMBIOS entry point anchor".into(),
+            ))
+        }
+    }
+
+    /// Version as `major.minor`
+    pub fn version(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+}
+
+/// One raw SMBIOS structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Structure {
+    /// Structure type
+    pub kind: u8,
+    /// Structure handle
+    pub handle: u16,
+    /// Formatted area, including the 4-byte header
+    pub data: Vec<u8>,
+    /// String set, in order (string 1 is `strings[0]`)
+    pub strings: Vec<String>,
+}
+
+impl Structure {
+    /// Byte at `offset`, if the formatted area is long enough
+    pub fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    /// Little-endian word at `offset`
+    pub fn word(&self, offset: usize) -> Option<u16> {
+        le_u16(&self.data, offset)
+    }
+
+    /// Little-endian double word at `offset`
+    pub fn dword(&self, offset: usize) -> Option<u32> {
+        le_u32(&self.data, offset)
+    }
+
+    /// Little-endian quad word at `offset`
+    pub fn qword(&self, offset: usize) -> Option<u64> {
+        le_u64(&self.data, offset)
+    }
+
+    /// String referenced by the index byte at `offset`
+    ///
+    /// Empty and all-space strings are treated as absent.
+    pub fn string(&self, offset: usize) -> Option<String> {
+        let index = self.byte(offset)? as usize;
+        let s = self.strings.get(index.checked_sub(1)?)?.trim();
+        (!s.is_empty()).then(|| s.to_string())
+    }
+}
+
+/// A decoded SMBIOS table
+#[derive(Debug, Clone)]
+pub struct SmbiosTable {
+    /// Entry point
+    pub entry_point: EntryPoint,
+    /// All structures in table order
+    pub structures: Vec<Structure>,
+}
+
+impl SmbiosTable {
+    /// Read the table from `/sys/firmware/dmi/tables`
+    pub fn read() -> Result<Self, Error> {
+        Self::from_dir(DMI_TABLES_PATH)
+    }
+
+    /// Read `smbios_entry_point` and `DMI` from a directory
+    ///
+    /// Works on the sysfs directory or on files dumped from it.
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let read = |name: &str| {
+            fs::read(dir.join(name)).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => {
+                    Error::NotSupported(format!("{} not found", dir.join(name).display()))
+                }
+                std::io::ErrorKind::PermissionDenied => Error::PermissionDenied(format!(
+                    "{} is readable by root only",
+                    dir.join(name).display()
+                )),
+                _ => Error::IoError(e),
+            })
+        };
+        Self::parse(&read("smbios_entry_point")?, &read("DMI")?)
+    }
+
+    /// Parse an entry point and the table it describes
+    pub fn parse(entry_point: &[u8], table: &[u8]) -> Result<Self, Error> {
+        let entry_point = EntryPoint::parse(entry_point)?;
+        let mut structures = Vec::new();
+        let mut offset = 0;
+
+        while offset + 4 <= table.len() {
+            if entry_point
+                .structure_count
+                .is_some_and(|count| structures.len() >= count as usize)
+            {
+                break;
+            }
+            let kind = table[offset];
+            let length = table[offset + 1] as usize;
+            let handle = u16::from_le_bytes([table[offset + 2], table[offset + 3]]);
+            if length < 4 || offset + length > table.len() {
+                return Err(Error::ParseError(format!(
+                    "Invalid SMBIOS structure length {} at offset {}",
+                    length, offset
+                )));
+            }
+            let data = table[offset..offset + length].to_vec();
+
+            // String set ends with a double NUL
+            let strings_start = offset + length;
+            let Some(strings_len) = table[strings_start..].windows(2).position(|w| w == [0, 0])
+            else {
+                break;
+            };
+            let strings = table[strings_start..strings_start + strings_len]
+                .split(|&b| b == 0)
+                .filter(|s| !s.is_empty())
+                .map(|s| String::from_utf8_lossy(s).to_string())
+                .collect();
+            offset = strings_start + strings_len + 2;
+
+            structures.push(Structure {
+                kind,
+                handle,
+                data,
+                strings,
+            });
+            if kind == END_OF_TABLE {
+                break;
+            }
+        }
+
+        Ok(SmbiosTable {
+            entry_point,
+            structures,
+        })
+    }
+
+    /// Structures of one type
+    pub fn of_type(&self, kind: u8) -> impl Iterator<Item = &Structure> {
+        self.structures.iter().filter(move |s| s.kind == kind)
+    }
+
+    /// Decode the supported structure types
+    pub fn inventory(&self) -> Inventory {
+        Inventory {
+            smbios_version: self.entry_point.version(),
+            bios: self.of_type(0).next().map(BiosInformation::decode),
+            system: self.of_type(1).next().map(SystemInformation::decode),
+            baseboards: self.of_type(2).map(Baseboard::decode).collect(),
+            chassis: self.of_type(3).map(Chassis::decode).collect(),
+            processors: self.of_type(4).map(Processor::decode).collect(),
+            caches: self.of_type(7).map(Cache::decode).collect(),
+            slots: self.of_type(9).map(SystemSlot::decode).collect(),
+            memory_arrays: self.of_type(16).map(MemoryArray::decode).collect(),
+            memory_devices: self.of_type(17).map(MemoryDevice::decode).collect(),
+            memory_ranges: self.of_type(19).map(MemoryRange::decode).collect(),
+            ipmi: self.of_type(38).next().map(IpmiDevice::decode),
+            onboard_devices: self.of_type(41).map(OnboardDevice::decode).collect(),
+        }
+    }
+}
+
+/// Read and decode the system SMBIOS table
+pub fn read_inventory() -> Result<Inventory, Error> {
+    Ok(SmbiosTable::read()?.inventory())
+}
+
+/// Hardware inventory decoded from SMBIOS
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Inventory {
+    /// SMBIOS version (`3.3`)
+    pub smbios_version: String,
+    /// Type 0
+    pub bios: Option<BiosInformation>,
+    /// Type 1
+    pub system: Option<SystemInformation>,
+    /// Type 2
+    pub baseboards: Vec<Baseboard>,
+    /// Type 3
+    pub chassis: Vec<Chassis>,
+    /// Type 4
+    pub processors: Vec<Processor>,
+    /// Type 7
+    pub caches: Vec<Cache>,
+    /// Type 9
+    pub slots: Vec<SystemSlot>,
+    /// Type 16
+    pub memory_arrays: Vec<MemoryArray>,
+    /// Type 17
+    pub memory_devices: Vec<MemoryDevice>,
+    /// Type 19
+    pub memory_ranges: Vec<MemoryRange>,
+    /// Type 38
+    pub ipmi: Option<IpmiDevice>,
+    /// Type 41
+    pub onboard_devices: Vec<OnboardDevice>,
+}
+
+impl Inventory {
+    /// Memory devices with a module installed
+    pub fn populated_dimms(&self) -> impl Iterator<Item = &MemoryDevice> {
+        self.memory_devices.iter().filter(|d| d.is_populated())
+    }
+
+    /// Total installed memory (MB)
+    pub fn total_memory_mb(&self) -> u64 {
+        self.populated_dimms().filter_map(|d| d.size_mb).sum()
+    }
+
+    /// Processor sockets with a CPU installed
+    pub fn populated_sockets(&self) -> impl Iterator<Item = &Processor> {
+        self.processors.iter().filter(|p| p.populated)
+    }
+
+    /// Cache structure by handle
+    pub fn cache(&self, handle: Option<u16>) -> Option<&Cache> {
+        let handle = handle?;
+        self.caches.iter().find(|c| c.handle == handle)
+    }
+}
+
+/// Type 0: BIOS information
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiosInformation {
+    /// Vendor
+    pub vendor: Option<String>,
+    /// Version string
+    pub version: Option<String>,
+    /// Release date
+    pub release_date: Option<String>,
+    /// ROM size (KB)
+    pub rom_size_kb: Option<u64>,
+    /// BIOS revision (`5.17`)
+    pub revision: Option<String>,
+    /// Embedded controller firmware revision
+    pub ec_revision: Option<String>,
+    /// UEFI is supported
+    pub uefi: bool,
+    /// Is a virtual machine
+    pub virtual_machine: bool,
+}
+
+impl BiosInformation {
+    fn decode(s: &Structure) -> Self {
+        let rom_size_kb = match s.byte(0x09) {
+            // Before 3.1 there is no extended field and 0xFF is 16 MB
+            Some(0xFF) => s
+                .word(0x18)
+                .map(|ext| {
+                    let size = (ext & 0x3FFF) as u64;
+                    if ext >> 14 == 1 {
+                        size * 1024 * 1024
+                    } else {
+                        size * 1024
+                    }
+                })
+                .or(Some(16 * 1024)),
+            Some(n) => Some((n as u64 + 1) * 64),
+            None => None,
+        };
+        let version_pair = |offset: usize| match (s.byte(offset), s.byte(offset + 1)) {
+            (Some(major), Some(minor)) if major != 0xFF => Some(format!("{}.{}", major, minor)),
+            _ => None,
+        };
+        let ext2 = s.byte(0x13).unwrap_or(0);
+        BiosInformation {
+            vendor: s.string(0x04),
+            version: s.string(0x05),
+            release_date: s.string(0x08),
+            rom_size_kb,
+            revision: version_pair(0x14),
+            ec_revision: version_pair(0x16),
+            uefi: ext2 & 0x08 != 0,
+            virtual_machine: ext2 & 0x10 != 0,
+        }
+    }
+}
+
+/// Type 1: System information
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemInformation {
+    /// Manufacturer
+    pub manufacturer: Option<String>,
+    /// Product name
+    pub product_name: Option<String>,
+    /// Version
+    pub version: Option<String>,
+    /// Serial number
+    pub serial_number: Option<String>,
+    /// UUID
+    pub uuid: Option<String>,
+    /// SKU number
+    pub sku_number: Option<String>,
+    /// Family
+    pub family: Option<String>,
+}
+
+impl SystemInformation {
+    fn decode(s: &Structure) -> Self {
+        let uuid = s.data.get(0x08..0x18).and_then(|bytes| {
+            // All zeros: not present; all ones: not set
+            if bytes.iter().all(|&b| b == 0) || bytes.iter().all(|&b| b == 0xFF) {
+                return None;
+            }
+            let mut b: [u8; 16] = bytes.try_into().ok()?;
+            // The first three fields are little-endian since SMBIOS 2.6
+            b[0..4].reverse();
+            b[4..6].reverse();
+            b[6..8].reverse();
+            let hex: Vec<String> = b.iter().map(|x| format!("{:02x}", x)).collect();
+            Some(format!(
+                "{}-{}-{}-{}-{}",
+                hex[0..4].concat(),
+                hex[4..6].concat(),
+                hex[6..8].concat(),
+                hex[8..10].concat(),
+                hex[10..16].concat()
+            ))
+        });
+        SystemInformation {
+            manufacturer: s.string(0x04),
+            product_name: s.string(0x05),
+            version: s.string(0x06),
+            serial_number: s.string(0x07),
+            uuid,
+            sku_number: s.string(0x19),
+            family: s.string(0x1A),
+        }
+    }
+}
+
+/// Type 2: Baseboard (motherboard)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseboard {
+    /// Manufacturer
+    pub manufacturer: Option<String>,
+    /// Product name
+    pub product: Option<String>,
+    /// Version
+    pub version: Option<String>,
+    /// Serial number
+    pub serial_number: Option<String>,
+    /// Asset tag
+    pub asset_tag: Option<String>,
+    /// Location in chassis
+    pub location: Option<String>,
+}
+
+impl Baseboard {
+    fn decode(s: &Structure) -> Self {
+        Baseboard {
+            manufacturer: s.string(0x04),
+            product: s.string(0x05),
+            version: s.string(0x06),
+            serial_number: s.string(0x07),
+            asset_tag: s.string(0x08),
+            location: s.string(0x0A),
+        }
+    }
+}
+
+/// Type 3: Chassis
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chassis {
+    /// Manufacturer
+    pub manufacturer: Option<String>,
+    /// Chassis type (`Rack Mount Chassis`)
+    pub chassis_type: String,
+    /// Chassis lock present
+    pub lock: bool,
+    /// Version
+    pub version: Option<String>,
+    /// Serial number
+    pub serial_number: Option<String>,
+    /// Asset tag
+    pub asset_tag: Option<String>,
+    /// Height in rack units
+    pub height_u: Option<u8>,
+    /// Number of power cords
+    pub power_cords: Option<u8>,
+}
+
+impl Chassis {
+    fn decode(s: &Structure) -> Self {
+        let kind = s.byte(0x05).unwrap_or(0x02);
+        Chassis {
+            manufacturer: s.string(0x04),
+            chassis_type: chassis_type_name(kind & 0x7F),
+            lock: kind & 0x80 != 0,
+            version: s.string(0x06),
+            serial_number: s.string(0x07),
+            asset_tag: s.string(0x08),
+            height_u: s.byte(0x11).filter(|&h| h != 0),
+            power_cords: s.byte(0x12).filter(|&n| n != 0),
+        }
+    }
+}
+
+/// Type 4: Processor socket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Processor {
+    /// Socket designation (`CPU0`)
+    pub socket: Option<String>,
+    /// Manufacturer
+    pub manufacturer: Option<String>,
+    /// Version string (model name)
+    pub version: Option<String>,
+    /// Processor family code
+    pub family: u16,
+    /// Processor ID (CPUID signature and feature flags on x86)
+    pub id: u64,
+    /// External clock (MHz)
+    pub external_clock_mhz: Option<u16>,
+    /// Maximum speed (MHz)
+    pub max_speed_mhz: Option<u16>,
+    /// Current speed (MHz)
+    pub current_speed_mhz: Option<u16>,
+    /// Socket holds a CPU
+    pub populated: bool,
+    /// CPU is enabled
+    pub enabled: bool,
+    /// L1 cache handle
+    pub l1_cache: Option<u16>,
+    /// L2 cache handle
+    pub l2_cache: Option<u16>,
+    /// L3 cache handle
+    pub l3_cache: Option<u16>,
+    /// Serial number
+    pub serial_number: Option<String>,
+    /// Asset tag
+    pub asset_tag: Option<String>,
+    /// Part number
+    pub part_number: Option<String>,
+    /// Number of cores
+    pub core_count: Option<u16>,
+    /// Number of enabled cores
+    pub cores_enabled: Option<u16>,
+    /// Number of threads
+    pub thread_count: Option<u16>,
+}
+
+impl Processor {
+    fn decode(s: &Structure) -> Self {
+        let status = s.byte(0x18).unwrap_or(0);
+        let speed = |offset| s.word(offset).filter(|&v| v != 0);
+        let cache = |offset| s.word(offset).filter(|&h| h != 0xFFFF);
+        // 0xFF in the byte fields means "see the 3.0 word field"
+        let count = |byte_offset: usize, word_offset: usize| match s.byte(byte_offset) {
+            Some(0xFF) => s.word(word_offset),
+            Some(0) | None => None,
+            Some(n) => Some(n as u16),
+        };
+        let family = match s.byte(0x06) {
+            Some(0xFE) => s.word(0x28).unwrap_or(0xFE),
+            Some(n) => n as u16,
+            None => 0,
+        };
+        Processor {
+            socket: s.string(0x04),
+            manufacturer: s.string(0x07),
+            version: s.string(0x10),
+            family,
+            id: s.qword(0x08).unwrap_or(0),
+            external_clock_mhz: speed(0x12),
+            max_speed_mhz: speed(0x14),
+            current_speed_mhz: speed(0x16),
+            populated: status & 0x40 != 0,
+            enabled: status & 0x07 == 1,
+            l1_cache: cache(0x1A),
+            l2_cache: cache(0x1C),
+            l3_cache: cache(0x1E),
+            serial_number: s.string(0x20),
+            asset_tag: s.string(0x21),
+            part_number: s.string(0x22),
+            core_count: count(0x23, 0x2A),
+            cores_enabled: count(0x24, 0x2C),
+            thread_count: count(0x25, 0x2E),
+        }
+    }
+}
+
+/// Type 7: Cache
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cache {
+    /// Structure handle, referenced by processors
+    pub handle: u16,
+    /// Socket designation (`L2 Cache`)
+    pub designation: Option<String>,
+    /// Cache level (1-8)
+    pub level: u8,
+    /// Cache is enabled
+    pub enabled: bool,
+    /// Installed size (KB)
+    pub installed_size_kb: u64,
+    /// Maximum size (KB)
+    pub maximum_size_kb: u64,
+    /// Instruction, Data or Unified
+    pub cache_type: String,
+    /// Associativity (`8-way Set-associative`)
+    pub associativity: String,
+}
+
+impl Cache {
+    fn decode(s: &Structure) -> Self {
+        let config = s.word(0x05).unwrap_or(0);
+        // Bit 15 (or 31 for the 3.1 fields) selects 64K granularity
+        let size16 = |v: u16| {
+            let kb = (v & 0x7FFF) as u64;
+            if v & 0x8000 != 0 {
+                kb * 64
+            } else {
+                kb
+            }
+        };
+        let size32 = |v: u32| {
+            let kb = (v & 0x7FFF_FFFF) as u64;
+            if v & 0x8000_0000 != 0 {
+                kb * 64
+            } else {
+                kb
+            }
+        };
+        let size = |offset16: usize, offset32: usize| match s.word(offset16) {
+            Some(0xFFFF) => s.dword(offset32).map(size32).unwrap_or(0),
+            Some(v) => size16(v),
+            None => 0,
+        };
+        Cache {
+            handle: s.handle,
+            designation: s.string(0x04),
+            level: (config & 0x07) as u8 + 1,
+            enabled: config & 0x80 != 0,
+            maximum_size_kb: size(0x07, 0x13),
+            installed_size_kb: size(0x09, 0x17),
+            cache_type: match s.byte(0x11) {
+                Some(0x03) => "Instruction",
+                Some(0x04) => "Data",
+                Some(0x05) => "Unified",
+                Some(0x01) => "Other",
+                _ => "Unknown",
+            }
+            .to_string(),
+            associativity: match s.byte(0x12) {
+                Some(0x03) => "Direct Mapped".to_string(),
+                Some(0x06) => "Fully Associative".to_string(),
+                Some(n) => match associativity_ways(n) {
+                    Some(ways) => format!("{}-way Set-associative", ways),
+                    None => "Unknown".to_string(),
+                },
+                None => "Unknown".to_string(),
+            },
+        }
+    }
+}
+
+/// Type 9: System slot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemSlot {
+    /// Slot designation (`PCIE1`)
+    pub designation: Option<String>,
+    /// Slot type (`PCI Express 4 x16`)
+    pub slot_type: String,
+    /// Data bus width (`x16`)
+    pub bus_width: String,
+    /// Something is installed in the slot
+    pub in_use: Option<bool>,
+    /// Slot ID
+    pub id: u16,
+    /// PCI address of the device in the slot (`0000:3b:00.0`)
+    pub pci_address: Option<String>,
+}
+
+impl SystemSlot {
+    fn decode(s: &Structure) -> Self {
+        SystemSlot {
+            designation: s.string(0x04),
+            slot_type: slot_type_name(s.byte(0x05).unwrap_or(0x02)),
+            bus_width: match s.byte(0x06) {
+                Some(0x03) => "8 bit",
+                Some(0x04) => "16 bit",
+                Some(0x05) => "32 bit",
+                Some(0x06) => "64 bit",
+                Some(0x07) => "128 bit",
+                Some(0x08) => "x1",
+                Some(0x09) => "x2",
+                Some(0x0A) => "x4",
+                Some(0x0B) => "x8",
+                Some(0x0C) => "x12",
+                Some(0x0D) => "x16",
+                Some(0x0E) => "x32",
+                Some(0x01) => "Other",
+                _ => "Unknown",
+            }
+            .to_string(),
+            in_use: match s.byte(0x07) {
+                Some(0x03) => Some(false),
+                Some(0x04) => Some(true),
+                _ => None,
+            },
+            id: s.word(0x09).unwrap_or(0),
+            pci_address: pci_address(s, 0x0D),
+        }
+    }
+}
+
+/// Type 16: Physical memory array
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryArray {
+    /// Structure handle, referenced by memory devices
+    pub handle: u16,
+    /// Location (`System Board Or Motherboard`)
+    pub location: String,
+    /// Use (`System Memory`)
+    pub usage: String,
+    /// Error correction (`Multi-bit ECC`)
+    pub error_correction: String,
+    /// Maximum capacity (MB)
+    pub maximum_capacity_mb: Option<u64>,
+    /// Number of memory device slots
+    pub device_count: u16,
+}
+
+impl MemoryArray {
+    fn decode(s: &Structure) -> Self {
+        let maximum_capacity_mb = match s.dword(0x07) {
+            Some(0x8000_0000) => s.qword(0x0F).map(|bytes| bytes / (1024 * 1024)),
+            Some(kb) => Some(kb as u64 / 1024),
+            None => None,
+        };
+        MemoryArray {
+            handle: s.handle,
+            location: match s.byte(0x04) {
+                Some(0x03) => "System Board Or Motherboard",
+                Some(0x04) => "ISA Add-on Card",
+                Some(0x05) => "EISA Add-on Card",
+                Some(0x06) => "PCI Add-on Card",
+                Some(0x0A) => "Proprietary Add-on Card",
+                Some(0x0B) => "NuBus",
+                Some(0x01) => "Other",
+                _ => "Unknown",
+            }
+            .to_string(),
+            usage: match s.byte(0x05) {
+                Some(0x03) => "System Memory",
+                Some(0x04) => "Video Memory",
+                Some(0x05) => "Flash Memory",
+                Some(0x06) => "Non-volatile RAM",
+                Some(0x07) => "Cache Memory",
+                Some(0x01) => "Other",
+                _ => "Unknown",
+            }
+            .to_string(),
+            error_correction: match s.byte(0x06) {
+                Some(0x03) => "None",
+                Some(0x04) => "Parity",
+                Some(0x05) => "Single-bit ECC",
+                Some(0x06) => "Multi-bit ECC",
+                Some(0x07) => "CRC",
+                Some(0x01) => "Other",
+                _ => "Unknown",
+            }
+            .to_string(),
+            maximum_capacity_mb,
+            device_count: s.word(0x0D).unwrap_or(0),
+        }
+    }
+}
+
+/// Type 17: Memory device (DIMM slot)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryDevice {
+    /// Handle of the memory array this slot belongs to
+    pub array_handle: u16,
+    /// Slot locator (`DIMM_A1`)
+    pub locator: Option<String>,
+    /// Bank locator (`P0_Node0_Channel0_Dimm0`)
+    pub bank_locator: Option<String>,
+    /// Size (MB); `None` for empty slots
+    pub size_mb: Option<u64>,
+    /// Form factor (`DIMM`, `SODIMM`)
+    pub form_factor: String,
+    /// Memory type (`DDR5`)
+    pub memory_type: String,
+    /// Total width including ECC bits
+    pub total_width: Option<u16>,
+    /// Data width
+    pub data_width: Option<u16>,
+    /// Rated speed (MT/s)
+    pub speed_mts: Option<u32>,
+    /// Configured speed (MT/s)
+    pub configured_speed_mts: Option<u32>,
+    /// Manufacturer
+    pub manufacturer: Option<String>,
+    /// Serial number
+    pub serial_number: Option<String>,
+    /// Asset tag
+    pub asset_tag: Option<String>,
+    /// Part number
+    pub part_number: Option<String>,
+    /// Number of ranks
+    pub rank: Option<u8>,
+    /// Configured voltage (mV)
+    pub configured_voltage_mv: Option<u16>,
+}
+
+impl MemoryDevice {
+    fn decode(s: &Structure) -> Self {
+        let size_mb = match s.word(0x0C) {
+            Some(0) | Some(0xFFFF) | None => None,
+            Some(0x7FFF) => s.dword(0x1C).map(|mb| (mb & 0x7FFF_FFFF) as u64),
+            // Bit 15 set: size is in KB
+            Some(v) if v & 0x8000 != 0 => Some((v & 0x7FFF) as u64 / 1024),
+            Some(v) => Some(v as u64),
+        };
+        let width = |offset| s.word(offset).filter(|&w| w != 0 && w != 0xFFFF);
+        let speed = |offset16: usize, offset32: usize| match s.word(offset16) {
+            Some(0) | None => None,
+            Some(0xFFFF) => s.dword(offset32).map(|v| v & 0x7FFF_FFFF),
+            Some(v) => Some(v as u32),
+        };
+        MemoryDevice {
+            array_handle: s.word(0x04).unwrap_or(0),
+            locator: s.string(0x10),
+            bank_locator: s.string(0x11),
+            size_mb,
+            form_factor: form_factor_name(s.byte(0x0E).unwrap_or(0x02)),
+            memory_type: memory_type_name(s.byte(0x12).unwrap_or(0x02)),
+            total_width: width(0x08),
+            data_width: width(0x0A),
+            speed_mts: speed(0x15, 0x54),
+            configured_speed_mts: speed(0x20, 0x58),
+            manufacturer: s.string(0x17),
+            serial_number: s.string(0x18),
+            asset_tag: s.string(0x19),
+            part_number: s.string(0x1A),
+            rank: s.byte(0x1B).map(|r| r & 0x0F).filter(|&r| r != 0),
+            configured_voltage_mv: s.word(0x26).filter(|&v| v != 0),
+        }
+    }
+
+    /// Whether a module is installed
+    pub fn is_populated(&self) -> bool {
+        self.size_mb.is_some()
+    }
+
+    /// Whether the module has ECC bits (total width above data width)
+    pub fn has_ecc(&self) -> bool {
+        matches!((self.total_width, self.data_width), (Some(t), Some(d)) if t > d)
+    }
+}
+
+/// Type 19: Memory array mapped address range
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryRange {
+    /// Start address (bytes)
+    pub start: u64,
+    /// End address, inclusive (bytes)
+    pub end: u64,
+    /// Handle of the memory array
+    pub array_handle: u16,
+    /// Number of devices forming one row
+    pub partition_width: u8,
+}
+
+impl MemoryRange {
+    fn decode(s: &Structure) -> Self {
+        let (start, end) = match s.dword(0x04) {
+            Some(0xFFFF_FFFF) => (s.qword(0x0F).unwrap_or(0), s.qword(0x17).unwrap_or(0)),
+            Some(start_kb) => (
+                start_kb as u64 * 1024,
+                s.dword(0x08).unwrap_or(0) as u64 * 1024 + 1023,
+            ),
+            None => (0, 0),
+        };
+        MemoryRange {
+            start,
+            end,
+            array_handle: s.word(0x0C).unwrap_or(0),
+            partition_width: s.byte(0x0E).unwrap_or(0),
+        }
+    }
+
+    /// Range size (bytes)
+    pub fn size(&self) -> u64 {
+        (self.end + 1).saturating_sub(self.start)
+    }
+}
+
+/// Type 38: IPMI device (BMC)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpmiDevice {
+    /// Interface type (`KCS`, `SSIF`)
+    pub interface: String,
+    /// IPMI specification revision (`2.0`)
+    pub spec_revision: String,
+    /// I2C slave address
+    pub i2c_address: u8,
+    /// Base address (I/O port or memory)
+    pub base_address: u64,
+}
+
+impl IpmiDevice {
+    fn decode(s: &Structure) -> Self {
+        let rev = s.byte(0x05).unwrap_or(0);
+        IpmiDevice {
+            interface: match s.byte(0x04) {
+                Some(0x01) => "KCS",
+                Some(0x02) => "SMIC",
+                Some(0x03) => "BT",
+                Some(0x04) => "SSIF",
+                _ => "Unknown",
+            }
+            .to_string(),
+            spec_revision: format!("{}.{}", rev >> 4, rev & 0x0F),
+            i2c_address: s.byte(0x06).unwrap_or(0) >> 1,
+            base_address: s.qword(0x08).unwrap_or(0),
+        }
+    }
+}
+
+/// Type 41: Onboard device
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnboardDevice {
+    /// Reference designation (`Onboard LAN 1`)
+    pub designation: Option<String>,
+    /// Device type (`Ethernet`)
+    pub device_type: String,
+    /// Device is enabled
+    pub enabled: bool,
+    /// Instance number of this type
+    pub instance: u8,
+    /// PCI address (`0000:03:00.0`)
+    pub pci_address: Option<String>,
+}
+
+impl OnboardDevice {
+    fn decode(s: &Structure) -> Self {
+        let kind = s.byte(0x05).unwrap_or(0x02);
+        OnboardDevice {
+            designation: s.string(0x04),
+            device_type: match kind & 0x7F {
+                0x01 => "Other",
+                0x03 => "Video",
+                0x04 => "SCSI Controller",
+                0x05 => "Ethernet",
+                0x06 => "Token Ring",
+                0x07 => "Sound",
+                0x08 => "PATA Controller",
+                0x09 => "SATA Controller",
+                0x0A => "SAS Controller",
+                0x0B => "Wireless LAN",
+                0x0C => "Bluetooth",
+                0x0D => "WWAN",
+                0x0E => "eMMC",
+                0x0F => "NVMe Controller",
+                0x10 => "UFS Controller",
+                _ => "Unknown",
+            }
+            .to_string(),
+            enabled: kind & 0x80 != 0,
+            instance: s.byte(0x06).unwrap_or(0),
+            pci_address: pci_address(s, 0x07),
+        }
+    }
+}
+
+impl fmt::Display for Inventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+        writeln!(f, "SMBIOS {}", self.smbios_version)?;
+
+        if let Some(system) = &self.system {
+            writeln!(f, "\nSystem")?;
+            writeln!(
+                f,
+                "  {} {}",
+                show(&system.manufacturer),
+                show(&system.product_name)
+            )?;
+            writeln!(f, "  Serial: {}", show(&system.serial_number))?;
+            writeln!(f, "  UUID:   {}", show(&system.uuid))?;
+        }
+        for board in &self.baseboards {
+            writeln!(f, "\nBaseboard")?;
+            writeln!(
+                f,
+                "  {} {} {}",
+                show(&board.manufacturer),
+                show(&board.product),
+                show(&board.version)
+            )?;
+            writeln!(f, "  Serial: {}", show(&board.serial_number))?;
+        }
+        for chassis in &self.chassis {
+            writeln!(f, "\nChassis")?;
+            write!(
+                f,
+                "  {} {}",
+                show(&chassis.manufacturer),
+                chassis.chassis_type
+            )?;
+            if let Some(u) = chassis.height_u {
+                write!(f, " ({}U)", u)?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(bios) = &self.bios {
+            writeln!(f, "\nBIOS")?;
+            writeln!(
+                f,
+                "  {} {} ({})",
+                show(&bios.vendor),
+                show(&bios.version),
+                show(&bios.release_date)
+            )?;
+        }
+
+        writeln!(f, "\nProcessors")?;
+        for cpu in &self.processors {
+            if !cpu.populated {
+                writeln!(f, "  {}: empty", show(&cpu.socket))?;
+                continue;
+            }
+            write!(f, "  {}: {}", show(&cpu.socket), show(&cpu.version))?;
+            if let (Some(cores), Some(threads)) = (cpu.core_count, cpu.thread_count) {
+                write!(f, ", {} cores / {} threads", cores, threads)?;
+            }
+            if let Some(mhz) = cpu.max_speed_mhz {
+                write!(f, ", max {} MHz", mhz)?;
+            }
+            writeln!(f)?;
+            let caches: Vec<String> = [cpu.l1_cache, cpu.l2_cache, cpu.l3_cache]
+                .into_iter()
+                .filter_map(|h| self.cache(h))
+                .filter(|c| c.installed_size_kb > 0)
+                .map(|c| format!("L{} {}", c.level, format_kb(c.installed_size_kb)))
+                .collect();
+            if !caches.is_empty() {
+                writeln!(f, "    Cache: {}", caches.join(", "))?;
+            }
+        }
+
+        writeln!(f, "\nMemory")?;
+        for array in &self.memory_arrays {
+            write!(
+                f,
+                "  {} slots, {}",
+                array.device_count, array.error_correction
+            )?;
+            if let Some(max) = array.maximum_capacity_mb {
+                write!(f, ", max {}", format_kb(max * 1024))?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "  Installed: {} in {} DIMMs",
+            format_kb(self.total_memory_mb() * 1024),
+            self.populated_dimms().count()
+        )?;
+        for dimm in &self.memory_devices {
+            let Some(size) = dimm.size_mb else {
+                writeln!(f, "  {:<16} empty", show(&dimm.locator))?;
+                continue;
+            };
+            write!(
+                f,
+                "  {:<16} {} {} {}",
+                show(&dimm.locator),
+                format_kb(size * 1024),
+                dimm.memory_type,
+                dimm.form_factor
+            )?;
+            if let Some(speed) = dimm.configured_speed_mts.or(dimm.speed_mts) {
+                write!(f, " {} MT/s", speed)?;
+            }
+            writeln!(
+                f,
+                " {} {}",
+                show(&dimm.manufacturer),
+                show(&dimm.part_number)
+            )?;
+        }
+
+        if !self.slots.is_empty() {
+            writeln!(f, "\nSlots")?;
+            for slot in &self.slots {
+                let usage = match slot.in_use {
+                    Some(true) => "in use",
+                    Some(false) => "available",
+                    None => "unknown",
+                };
+                write!(
+                    f,
+                    "  {:<16} {} ({})",
+                    show(&slot.designation),
+                    slot.slot_type,
+                    usage
+                )?;
+                if let Some(address) = &slot.pci_address {
+                    write!(f, " {}", address)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        if !self.onboard_devices.is_empty() {
+            writeln!(f, "\nOnboard Devices")?;
+            for device in &self.onboard_devices {
+                write!(
+                    f,
+                    "  {:<24} {}{}",
+                    show(&device.designation),
+                    device.device_type,
+                    if device.enabled { "" } else { " (disabled)" }
+                )?;
+                if let Some(address) = &device.pci_address {
+                    write!(f, " {}", address)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        if let Some(ipmi) = &self.ipmi {
+            writeln!(f, "\nIPMI")?;
+            writeln!(
+                f,
+                "  {} interface, IPMI {}, base address {:#x}",
+                ipmi.interface, ipmi.spec_revision, ipmi.base_address
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Segment/bus/devfn at `offset`, as a PCI address
+///
+/// All-ones values mean the address is not provided.
+fn pci_address(s: &Structure, offset: usize) -> Option<String> {
+    let segment = s.word(offset)?;
+    let bus = s.byte(offset + 2)?;
+    let devfn = s.byte(offset + 3)?;
+    if (segment == 0xFFFF && bus == 0xFF) || devfn == 0xFF {
+        return None;
+    }
+    Some(format!(
+        "{:04x}:{:02x}:{:02x}.{}",
+        segment,
+        bus,
+        devfn >> 3,
+        devfn & 0x07
+    ))
+}
+
+fn format_kb(kb: u64) -> String {
+    const UNITS: [(u64, &str); 3] = [(1 << 30, "TB"), (1 << 20, "GB"), (1 << 10, "MB")];
+    for (size, unit) in UNITS {
+        if kb >= size {
+            return if kb.is_multiple_of(size) {
+                format!("{} {}", kb / size, unit)
+            } else {
+                format!("{:.1} {}", kb as f64 / size as f64, unit)
+            };
+        }
+    }
+    format!("{} KB", kb)
+}
+
+fn associativity_ways(code: u8) -> Option<u32> {
+    Some(match code {
+        0x04 => 2,
+        0x05 => 4,
+        0x07 => 8,
+        0x08 => 16,
+        0x09 => 12,
+        0x0A => 24,
+        0x0B => 32,
+        0x0C => 48,
+        0x0D => 64,
+        0x0E => 20,
+        _ => return None,
+    })
+}
+
+fn chassis_type_name(code: u8) -> String {
+    const NAMES: [&str; 36] = [
+        "Other",
+        "Unknown",
+        "Desktop",
+        "Low Profile Desktop",
+        "Pizza Box",
+        "Mini Tower",
+        "Tower",
+        "Portable",
+        "Laptop",
+        "Notebook",
+        "Hand Held",
+        "Docking Station",
+        "All In One",
+        "Sub Notebook",
+        "Space-saving",
+        "Lunch Box",
+        "Main Server Chassis",
+        "Expansion Chassis",
+        "Sub Chassis",
+        "Bus Expansion Chassis",
+        "Peripheral Chassis",
+        "RAID Chassis",
+        "Rack Mount Chassis",
+        "Sealed-case PC",
+        "Multi-system",
+        "CompactPCI",
+        "AdvancedTCA",
+        "Blade",
+        "Blade Enclosing",
+        "Tablet",
+        "Convertible",
+        "Detachable",
+        "IoT Gateway",
+        "Embedded PC",
+        "Mini PC",
+        "Stick PC",
+    ];
+    table_name(&NAMES, code)
+}
+
+fn memory_type_name(code: u8) -> String {
+    const NAMES: [&str; 36] = [
+        "Other",
+        "Unknown",
+        "DRAM",
+        "EDRAM",
+        "VRAM",
+        "SRAM",
+        "RAM",
+        "ROM",
+        "Flash",
+        "EEPROM",
+        "FEPROM",
+        "EPROM",
+        "CDRAM",
+        "3DRAM",
+        "SDRAM",
+        "SGRAM",
+        "RDRAM",
+        "DDR",
+        "DDR2",
+        "DDR2 FB-DIMM",
+        "Reserved",
+        "Reserved",
+        "Reserved",
+        "DDR3",
+        "FBD2",
+        "DDR4",
+        "LPDDR",
+        "LPDDR2",
+        "LPDDR3",
+        "LPDDR4",
+        "Logical non-volatile device",
+        "HBM",
+        "HBM2",
+        "DDR5",
+        "LPDDR5",
+        "HBM3",
+    ];
+    table_name(&NAMES, code)
+}
+
+fn form_factor_name(code: u8) -> String {
+    const NAMES: [&str; 16] = [
+        "Other",
+        "Unknown",
+        "SIMM",
+        "SIP",
+        "Chip",
+        "DIP",
+        "ZIP",
+        "Proprietary Card",
+        "DIMM",
+        "TSOP",
+        "Row Of Chips",
+        "RIMM",
+        "SODIMM",
+        "SRIMM",
+        "FB-DIMM",
+        "Die",
+    ];
+    table_name(&NAMES, code)
+}
+
+fn slot_type_name(code: u8) -> String {
+    // PCI Express generations come in runs of six: base type, then x1..x16
+    let pcie = |base: u8, gen: &str| {
+        let widths = ["", " x1", " x2", " x4", " x8", " x16"];
+        let suffix = widths[(code - base) as usize];
+        format!("PCI Express{}{}", gen, suffix)
+    };
+    match code {
+        0x06 => "PCI".to_string(),
+        0x0E => "PCI-66".to_string(),
+        0x0F..=0x11 => "AGP".to_string(),
+        0x12 => "PCI-X".to_string(),
+        0x13..=0x17 => "M.2".to_string(),
+        0x1F..=0x24 => "PCI Express SFF-8639 (U.2)".to_string(),
+        0xA5..=0xAA => pcie(0xA5, ""),
+        0xAB..=0xB0 => pcie(0xAB, " 2"),
+        0xB1..=0xB6 => pcie(0xB1, " 3"),
+        0xB8..=0xBD => pcie(0xB8, " 4"),
+        0xBE..=0xC3 => pcie(0xBE, " 5"),
+        0xC4..=0xC9 => pcie(0xC4, " 6"),
+        0x01 => "Other".to_string(),
+        0x02 => "Unknown".to_string(),
+        _ => format!("Other ({:#04x})", code),
+    }
+}
+
+/// Look up a 1-based SMBIOS enumeration value
+fn table_name(names: &[&str], code: u8) -> String {
+    code.checked_sub(1)
+        .and_then(|i| names.get(i as usize))
+        .map_or_else(|| format!("Unknown ({:#04x})", code), |s| s.to_string())
+}
+
+fn le_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn le_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    /// Table directory from `tests/fixtures/smbios`
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/smbios")
+            .join(name)
+    }
+
+    #[test]
+    fn test_entry_points() {
+        let ep = fs::read(fixture("server-3.3").join("smbios_entry_point")).unwrap();
+        let ep = EntryPoint::parse(&ep).unwrap();
+        assert_eq!(ep.version(), "3.3");
+        assert_eq!(ep.table_length, 2862);
+        assert_eq!(ep.table_address, 0x6F2C_3000);
+        assert_eq!(ep.structure_count, None);
+
+        let mut ep2 = fs::read(fixture("desktop-2.8").join("smbios_entry_point")).unwrap();
+        let ep = EntryPoint::parse(&ep2).unwrap();
+        assert_eq!(ep.version(), "2.8");
+        assert_eq!(ep.table_length, 1288);
+        assert_eq!(ep.table_address, 0x000E_C3B0);
+        assert_eq!(ep.structure_count, Some(23));
+
+        ep2[0x04] = ep2[0x04].wrapping_add(1);
+        assert!(EntryPoint::parse(&ep2).is_err());
+        assert!(EntryPoint::parse(b"garbage").is_err());
+    }
+
+    #[test]
+    fn test_truncated_entry_point() {
+        // Anchor, checksum and a declared length that stops before the version
+        let entry_point = |anchor: &[u8], len: u8| {
+            let mut data = anchor.to_vec();
+            let sum = data.iter().fold(len, |sum, b| sum.wrapping_add(*b));
+            data.push(0u8.wrapping_sub(sum));
+            data.push(len);
+            data
+        };
+        for data in [entry_point(b"_SM3_", 7), entry_point(b"_SM_", 6)] {
+            assert!(matches!(
+                EntryPoint::parse(&data),
+                Err(Error::ParseError(msg)) if msg.contains("truncated")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_server_inventory() {
+        let smbios = SmbiosTable::from_dir(fixture("server-3.3")).unwrap();
+        // Stops at the end-of-table structure, not at the end of the file
+        assert_eq!(smbios.structures.len(), 35);
+        assert_eq!(smbios.structures.last().unwrap().kind, END_OF_TABLE);
+        let inv = smbios.inventory();
+        assert_eq!(inv.smbios_version, "3.3");
+
+        let bios = inv.bios.as_ref().unwrap();
+        assert_eq!(
+            bios.vendor.as_deref(),
+            Some("American Megatrends International, LLC.")
+        );
+        assert_eq!(bios.rom_size_kb, Some(32 * 1024));
+        assert_eq!(bios.revision.as_deref(), Some("5.22"));
+        assert_eq!(bios.ec_revision, None);
+        assert!(bios.uefi);
+        let system = inv.system.as_ref().unwrap();
+        assert_eq!(system.product_name.as_deref(), Some("SYS-420GP-TNR"));
+        assert_eq!(
+            system.uuid.as_deref(),
+            Some("12345678-1234-5678-9abc-3cecef123456")
+        );
+        assert_eq!(inv.baseboards[0].product.as_deref(), Some("X12DPG-OA6"));
+        assert_eq!(inv.chassis[0].chassis_type, "Rack Mount Chassis");
+        assert_eq!(inv.chassis[0].height_u, Some(4));
+
+        // Type 4
+        assert_eq!(inv.processors.len(), 2);
+        assert_eq!(inv.populated_sockets().count(), 2);
+        let cpu = &inv.processors[1];
+        assert_eq!(cpu.socket.as_deref(), Some("CPU2"));
+        assert_eq!(cpu.manufacturer.as_deref(), Some("Intel(R) Corporation"));
+        assert_eq!(
+            cpu.version.as_deref(),
+            Some("Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz")
+        );
+        assert_eq!(cpu.family, 0xB3);
+        assert_eq!(cpu.id, 0xBFEB_FBFF_0006_06A6);
+        assert_eq!(cpu.external_clock_mhz, Some(100));
+        assert_eq!(cpu.max_speed_mhz, Some(3200));
+        assert_eq!(cpu.current_speed_mhz, Some(2000));
+        assert!(cpu.enabled);
+        assert_eq!(
+            (cpu.l1_cache, cpu.l2_cache, cpu.l3_cache),
+            (Some(0x14), Some(0x15), Some(0x16))
+        );
+        assert_eq!(cpu.serial_number, None);
+        assert_eq!(cpu.core_count, Some(32));
+        assert_eq!(cpu.cores_enabled, Some(32));
+        assert_eq!(cpu.thread_count, Some(64));
+
+        // Type 7, 3.1 layout with 64K granularity for L2 and L3
+        assert_eq!(inv.caches.len(), 6);
+        let l1 = inv.cache(cpu.l1_cache).unwrap();
+        assert_eq!(l1.designation.as_deref(), Some("L1-Cache"));
+        assert_eq!((l1.level, l1.enabled), (1, true));
+        assert_eq!(l1.installed_size_kb, 2560);
+        assert_eq!(l1.cache_type, "Data");
+        assert_eq!(l1.associativity, "12-way Set-associative");
+        let l2 = inv.cache(cpu.l2_cache).unwrap();
+        assert_eq!(l2.level, 2);
+        assert_eq!(l2.installed_size_kb, 40 * 1024);
+        assert_eq!(l2.maximum_size_kb, 40 * 1024);
+        assert_eq!(l2.cache_type, "Unified");
+        assert_eq!(l2.associativity, "20-way Set-associative");
+        let l3 = inv.cache(cpu.l3_cache).unwrap();
+        assert_eq!(l3.level, 3);
+        assert_eq!(l3.installed_size_kb, 48 * 1024);
+        assert_eq!(l3.associativity, "12-way Set-associative");
+
+        // Type 9
+        let slots: Vec<_> = inv
+            .slots
+            .iter()
+            .map(|s| {
+                (
+                    s.designation.as_deref().unwrap(),
+                    s.slot_type.as_str(),
+                    s.bus_width.as_str(),
+                    s.in_use,
+                    s.id,
+                    s.pci_address.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (
+                    "CPU1 SLOT1 PCI-E 4.0 X16",
+                    "PCI Express 4 x16",
+                    "x16",
+                    Some(true),
+                    1,
+                    Some("0000:4b:00.0")
+                ),
+                (
+                    "CPU1 SLOT2 PCI-E 4.0 X8",
+                    "PCI Express 4 x8",
+                    "x8",
+                    Some(false),
+                    2,
+                    None
+                ),
+                (
+                    "CPU2 SLOT3 PCI-E 4.0 X16",
+                    "PCI Express 4 x16",
+                    "x16",
+                    Some(true),
+                    3,
+                    Some("0000:ca:00.0")
+                ),
+            ]
+        );
+
+        // Type 16, capacity in the 64-bit field
+        assert_eq!(inv.memory_arrays.len(), 2);
+        for (array, handle) in inv.memory_arrays.iter().zip([0x40, 0x41]) {
+            assert_eq!(array.handle, handle);
+            assert_eq!(array.location, "System Board Or Motherboard");
+            assert_eq!(array.usage, "System Memory");
+            assert_eq!(array.error_correction, "Multi-bit ECC");
+            assert_eq!(array.maximum_capacity_mb, Some(2 * 1024 * 1024));
+            assert_eq!(array.device_count, 4);
+        }
+
+        // Type 17, 64 GB modules in the extended size field
+        assert_eq!(inv.memory_devices.len(), 8);
+        assert_eq!(inv.populated_dimms().count(), 6);
+        assert_eq!(inv.total_memory_mb(), 384 * 1024);
+        let dimm = &inv.memory_devices[4];
+        assert_eq!(dimm.array_handle, 0x41);
+        assert_eq!(dimm.locator.as_deref(), Some("P2-DIMMA1"));
+        assert_eq!(
+            dimm.bank_locator.as_deref(),
+            Some("P1_Node1_Channel0_Dimm0")
+        );
+        assert_eq!(dimm.size_mb, Some(65536));
+        assert_eq!(dimm.form_factor, "DIMM");
+        assert_eq!(dimm.memory_type, "DDR4");
+        assert_eq!((dimm.total_width, dimm.data_width), (Some(72), Some(64)));
+        assert!(dimm.has_ecc());
+        assert_eq!(dimm.speed_mts, Some(3200));
+        assert_eq!(dimm.configured_speed_mts, Some(3200));
+        assert_eq!(dimm.manufacturer.as_deref(), Some("Samsung"));
+        assert_eq!(dimm.serial_number.as_deref(), Some("03A1B204"));
+        assert_eq!(dimm.part_number.as_deref(), Some("M393A8G40AB2-CWE"));
+        assert_eq!(dimm.rank, Some(2));
+        assert_eq!(dimm.configured_voltage_mv, Some(1200));
+        let empty = &inv.memory_devices[5];
+        assert_eq!(empty.locator.as_deref(), Some("P2-DIMMB1"));
+        assert!(!empty.is_populated());
+        assert_eq!(empty.memory_type, "Unknown");
+        assert_eq!((empty.total_width, empty.data_width), (None, None));
+        assert_eq!(empty.speed_mts, None);
+        assert_eq!(empty.rank, None);
+
+        // Type 19
+        let ranges: Vec<_> = inv
+            .memory_ranges
+            .iter()
+            .map(|r| (r.start, r.size(), r.array_handle, r.partition_width))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![(0, 256 << 30, 0x40, 4), (256 << 30, 128 << 30, 0x41, 2)]
+        );
+
+        // Type 38
+        let ipmi = inv.ipmi.as_ref().unwrap();
+        assert_eq!(ipmi.interface, "KCS");
+        assert_eq!(ipmi.spec_revision, "2.0");
+        assert_eq!(ipmi.i2c_address, 0x10);
+        assert_eq!(ipmi.base_address, 0xCA3);
+
+        // Type 41
+        let onboard: Vec<_> = inv
+            .onboard_devices
+            .iter()
+            .map(|d| {
+                (
+                    d.designation.as_deref().unwrap(),
+                    d.device_type.as_str(),
+                    d.enabled,
+                    d.instance,
+                    d.pci_address.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            onboard,
+            vec![
+                ("ASPEED Video AST2600", "Video", true, 1, "0000:03:00.0"),
+                (
+                    "Intel Ethernet X710 #1",
+                    "Ethernet",
+                    true,
+                    1,
+                    "0000:51:00.0"
+                ),
+                (
+                    "Intel Ethernet X710 #2",
+                    "Ethernet",
+                    true,
+                    2,
+                    "0000:51:00.1"
+                ),
+                ("Onboard SATA", "SATA Controller", false, 1, "0000:00:17.0"),
+            ]
+        );
+
+        let text = inv.to_string();
+        assert!(
+            text.contains("CPU2: Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz, 32 cores / 64 threads")
+        );
+        assert!(text.contains("Cache: L1 2.5 MB, L2 40 MB, L3 48 MB"));
+        assert!(text.contains("4 slots, Multi-bit ECC, max 2 TB"));
+        assert!(text.contains("Installed: 384 GB in 6 DIMMs"));
+        assert!(text.contains("KCS interface, IPMI 2.0, base address 0xca3"));
+    }
+
+    #[test]
+    fn test_desktop_inventory() {
+        let smbios = SmbiosTable::from_dir(fixture("desktop-2.8")).unwrap();
+        assert_eq!(smbios.structures.len(), 23);
+        let inv = smbios.inventory();
+        assert_eq!(inv.smbios_version, "2.8");
+
+        let bios = inv.bios.as_ref().unwrap();
+        assert_eq!(bios.version.as_deref(), Some("1301"));
+        assert_eq!(bios.release_date.as_deref(), Some("03/14/2018"));
+        assert_eq!(bios.rom_size_kb, Some(16 * 1024));
+        assert_eq!(bios.revision.as_deref(), Some("5.12"));
+        let system = inv.system.as_ref().unwrap();
+        assert_eq!(
+            system.uuid.as_deref(),
+            Some("d32a1c60-4e9b-e811-a1b2-704d7b621a3c")
+        );
+        assert_eq!(inv.baseboards[0].product.as_deref(), Some("PRIME Z270-A"));
+        assert_eq!(inv.chassis[0].chassis_type, "Desktop");
+        assert_eq!(inv.chassis[0].height_u, None);
+        assert_eq!(inv.chassis[0].power_cords, Some(1));
+
+        // Type 4, 2.x layout without the 3.0 count words
+        assert_eq!(inv.processors.len(), 1);
+        let cpu = &inv.processors[0];
+        assert_eq!(cpu.socket.as_deref(), Some("LGA1151"));
+        assert_eq!(
+            cpu.version.as_deref(),
+            Some("Intel(R) Core(TM) i7-7700K CPU @ 4.20GHz")
+        );
+        assert_eq!(cpu.family, 0xC6);
+        assert_eq!(cpu.id, 0xBFEB_FBFF_0009_06E9);
+        assert_eq!(cpu.max_speed_mhz, Some(4500));
+        assert_eq!(cpu.current_speed_mhz, Some(4200));
+        assert!(cpu.populated && cpu.enabled);
+        assert_eq!(cpu.core_count, Some(4));
+        assert_eq!(cpu.cores_enabled, Some(4));
+        assert_eq!(cpu.thread_count, Some(8));
+
+        // Type 7, 2.x layout
+        let caches: Vec<_> = [cpu.l1_cache, cpu.l2_cache, cpu.l3_cache]
+            .into_iter()
+            .map(|h| {
+                let c = inv.cache(h).unwrap();
+                (
+                    c.level,
+                    c.installed_size_kb,
+                    c.cache_type.as_str(),
+                    c.associativity.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            caches,
+            vec![
+                (1, 256, "Data", "8-way Set-associative"),
+                (2, 1024, "Unified", "4-way Set-associative"),
+                (3, 8192, "Unified", "16-way Set-associative"),
+            ]
+        );
+
+        // Type 9
+        let slots: Vec<_> = inv
+            .slots
+            .iter()
+            .map(|s| {
+                (
+                    s.designation.as_deref().unwrap(),
+                    s.slot_type.as_str(),
+                    s.bus_width.as_str(),
+                    s.in_use,
+                    s.pci_address.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (
+                    "PCIEX16_1",
+                    "PCI Express 3 x16",
+                    "x16",
+                    Some(true),
+                    Some("0000:01:00.0")
+                ),
+                ("PCIEX1_1", "PCI Express 3 x1", "x1", Some(false), None),
+                ("PCIEX16_2", "PCI Express 3 x16", "x8", Some(false), None),
+                (
+                    "M.2(SOCKET3)",
+                    "M.2",
+                    "x4",
+                    Some(true),
+                    Some("0000:02:00.0")
+                ),
+            ]
+        );
+
+        // Type 16, capacity in the 32-bit field
+        let array = &inv.memory_arrays[0];
+        assert_eq!(array.error_correction, "None");
+        assert_eq!(array.maximum_capacity_mb, Some(64 * 1024));
+        assert_eq!(array.device_count, 4);
+
+        // Type 17
+        assert_eq!(inv.memory_devices.len(), 4);
+        let populated: Vec<_> = inv
+            .populated_dimms()
+            .map(|d| d.locator.as_deref().unwrap())
+            .collect();
+        assert_eq!(populated, vec!["ChannelA-DIMM1", "ChannelB-DIMM1"]);
+        assert_eq!(inv.total_memory_mb(), 16 * 1024);
+        let dimm = &inv.memory_devices[1];
+        assert_eq!(dimm.bank_locator.as_deref(), Some("BANK 1"));
+        assert_eq!(dimm.size_mb, Some(8192));
+        assert_eq!(dimm.memory_type, "DDR4");
+        assert!(!dimm.has_ecc());
+        assert_eq!(dimm.speed_mts, Some(2400));
+        assert_eq!(dimm.configured_speed_mts, Some(2400));
+        assert_eq!(dimm.manufacturer.as_deref(), Some("029E"));
+        assert_eq!(dimm.part_number.as_deref(), Some("CMK16GX4M2A2400C14"));
+        assert_eq!(dimm.rank, Some(1));
+        let empty = &inv.memory_devices[0];
+        assert!(!empty.is_populated());
+        assert_eq!(empty.manufacturer, None);
+
+        // Type 19
+        let range = &inv.memory_ranges[0];
+        assert_eq!((range.start, range.size()), (0, 16 << 30));
+        assert_eq!(range.partition_width, 2);
+
+        // No BMC
+        assert!(inv.ipmi.is_none());
+
+        // Type 41
+        let onboard: Vec<_> = inv
+            .onboard_devices
+            .iter()
+            .map(|d| {
+                (
+                    d.device_type.as_str(),
+                    d.enabled,
+                    d.pci_address.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            onboard,
+            vec![
+                ("Video", true, "0000:00:02.0"),
+                ("Ethernet", true, "0000:00:1f.6"),
+                ("Sound", false, "0000:00:1f.3"),
+            ]
+        );
+
+        let text = inv.to_string();
+        assert!(text.contains("Installed: 16 GB in 2 DIMMs"));
+        assert!(text.contains("ChannelA-DIMM0   empty"));
+        assert!(text.contains("Onboard Audio            Sound (disabled) 0000:00:1f.3"));
+    }
+
+    #[test]
+    fn test_truncated_table() {
+        let dir = fixture("desktop-2.8");
+        let ep = fs::read(dir.join("smbios_entry_point")).unwrap();
+        let mut table = fs::read(dir.join("DMI")).unwrap();
+        table[1] = 2;
+        assert!(SmbiosTable::parse(&ep, &table).is_err());
+        assert!(SmbiosTable::from_dir("/nonexistent").is_err());
+
+        // A table dumped without its entry point
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("DMI"), &table).unwrap();
+        assert!(matches!(
+            SmbiosTable::from_dir(tmp.path()),
+            Err(Error::NotSupported(_))
+        ));
+    }
+}
@@ -140,6 +140,10 @@ pub struct SystemInfo {
     pub cpu_name: Option<String>,
     pub cpu_cores: Option<u32>,
     pub cpu_threads: Option<u32>,
+
+    // Full SMBIOS inventory (DIMMs, sockets, caches, slots), when readable
+    #[serde(default)]
+    pub inventory: Option<super::smbios::Inventory>,
 }
 
 /// BIOS/UEFI information
@@ -175,6 +179,20 @@ pub struct PcieDeviceInfo {
     pub link_speed: Option<String>,   // 2.5 GT/s, 5 GT/s, 8 GT/s, etc.
     pub slot: Option<String>,
     pub device_class: Option<String>, // VGA, Network, Storage, etc.
+    #[serde(default)]
+    pub address: Option<String>, // 0000:01:00.0
+    #[serde(default)]
+    pub max_link_width: Option<u8>, // Best width both ends support
+    #[serde(default)]
+    pub max_link_speed: Option<String>, // Best speed both ends support
+    #[serde(default)]
+    pub link_degraded: bool, // Trained below max width or speed
+    #[serde(default)]
+    pub aer_correctable: Option<u64>, // AER counters since boot
+    #[serde(default)]
+    pub aer_nonfatal: Option<u64>,
+    #[serde(default)]
+    pub aer_fatal: Option<u64>,
 }
 
 /// SATA device information
@@ -654,6 +654,7 @@ pub fn get_system_info() -> Result<SystemInfo, Error> {
         cpu_threads: proc_info
             .as_ref()
             .and_then(|p| p.number_of_logical_processors),
+        inventory: None,
     })
 }
 
@@ -864,6 +865,13 @@ pub fn get_pcie_devices() -> Result<Vec<PcieDeviceInfo>, Error> {
                     link_speed: None,
                     slot: None,
                     device_class,
+                    address: None,
+                    max_link_width: None,
+                    max_link_speed: None,
+                    link_degraded: false,
+                    aer_correctable: None,
+                    aer_nonfatal: None,
+                    aer_fatal: None,
                 });
             }
         }
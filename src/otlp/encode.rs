@@ -0,0 +1,415 @@
+//! OTLP wire encodings
+//!
+//! `ExportMetricsServiceRequest` in protobuf (written by hand, the message
+//! set is small) and in the OTLP/JSON mapping: camelCase field names, enums
+//! as integers and 64-bit integers as decimal strings.
+
+use super::{AttributeValue, DataPoint, Metric, MetricKind, NumberValue, Resource};
+use serde_json::{json, Value};
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE`
+const CUMULATIVE: u64 = 2;
+
+const VARINT: u32 = 0;
+const FIXED64: u32 = 1;
+const LEN: u32 = 2;
+const FIXED32: u32 = 5;
+
+/// Instrumentation scope name and version
+fn scope() -> (&'static str, &'static str) {
+    ("simon", env!("CARGO_PKG_VERSION"))
+}
+
+/// Encode an export request as protobuf
+pub(super) fn protobuf(resource: &Resource, metrics: &[Metric]) -> Vec<u8> {
+    let mut request = Vec::new();
+    message(&mut request, 1, |rm| {
+        message(rm, 1, |r| {
+            for (key, value) in &resource.attributes {
+                message(r, 1, |kv| key_value(kv, key, value));
+            }
+        });
+        message(rm, 2, |sm| {
+            message(sm, 1, |s| {
+                let (name, version) = scope();
+                string(s, 1, name);
+                string(s, 2, version);
+            });
+            for metric in metrics {
+                message(sm, 2, |m| encode_metric(m, metric));
+            }
+        });
+    });
+    request
+}
+
+fn encode_metric(buf: &mut Vec<u8>, metric: &Metric) {
+    string(buf, 1, &metric.name);
+    string(buf, 2, &metric.description);
+    string(buf, 3, &metric.unit);
+    match metric.kind {
+        MetricKind::Gauge => message(buf, 5, |g| {
+            for point in &metric.points {
+                message(g, 1, |p| encode_point(p, point));
+            }
+        }),
+        MetricKind::Sum { monotonic } => message(buf, 7, |s| {
+            for point in &metric.points {
+                message(s, 1, |p| encode_point(p, point));
+            }
+            tag(s, 2, VARINT);
+            varint(s, CUMULATIVE);
+            if monotonic {
+                tag(s, 3, VARINT);
+                varint(s, 1);
+            }
+        }),
+    }
+}
+
+fn encode_point(buf: &mut Vec<u8>, point: &DataPoint) {
+    if point.start_time_unix_nano != 0 {
+        tag(buf, 2, FIXED64);
+        buf.extend_from_slice(&point.start_time_unix_nano.to_le_bytes());
+    }
+    tag(buf, 3, FIXED64);
+    buf.extend_from_slice(&point.time_unix_nano.to_le_bytes());
+    match point.value {
+        NumberValue::Double(value) => {
+            tag(buf, 4, FIXED64);
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        NumberValue::Int(value) => {
+            tag(buf, 6, FIXED64);
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    for (key, value) in &point.attributes {
+        message(buf, 7, |kv| key_value(kv, key, value));
+    }
+}
+
+fn key_value(buf: &mut Vec<u8>, key: &str, value: &AttributeValue) {
+    string(buf, 1, key);
+    message(buf, 2, |v| any_value(v, value));
+}
+
+fn any_value(buf: &mut Vec<u8>, value: &AttributeValue) {
+    match value {
+        AttributeValue::String(s) => string(buf, 1, s),
+        AttributeValue::Bool(b) => {
+            tag(buf, 2, VARINT);
+            varint(buf, *b as u64);
+        }
+        AttributeValue::Int(i) => {
+            tag(buf, 3, VARINT);
+            varint(buf, *i as u64);
+        }
+        AttributeValue::Double(d) => {
+            tag(buf, 4, FIXED64);
+            buf.extend_from_slice(&d.to_le_bytes());
+        }
+        AttributeValue::Array(values) => message(buf, 5, |array| {
+            for value in values {
+                message(array, 1, |v| any_value(v, value));
+            }
+        }),
+    }
+}
+
+fn tag(buf: &mut Vec<u8>, field: u32, wire_type: u32) {
+    varint(buf, ((field << 3) | wire_type) as u64);
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// Non-empty string field (proto3 omits defaults)
+fn string(buf: &mut Vec<u8>, field: u32, value: &str) {
+    if !value.is_empty() {
+        tag(buf, field, LEN);
+        varint(buf, value.len() as u64);
+        buf.extend_from_slice(value.as_bytes());
+    }
+}
+
+/// Length-delimited sub-message written by `body`
+fn message(buf: &mut Vec<u8>, field: u32, body: impl FnOnce(&mut Vec<u8>)) {
+    let mut inner = Vec::new();
+    body(&mut inner);
+    tag(buf, field, LEN);
+    varint(buf, inner.len() as u64);
+    buf.extend_from_slice(&inner);
+}
+
+/// One decoded protobuf field value
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Field<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Split a protobuf message into `(field number, value)` pairs
+pub(super) fn read_fields(mut buf: &[u8]) -> Option<Vec<(u32, Field<'_>)>> {
+    fn read_varint(buf: &mut &[u8]) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = buf.split_first()?;
+            *buf = rest;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+    fn take<'a>(buf: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+        if buf.len() < n {
+            return None;
+        }
+        let (head, rest) = buf.split_at(n);
+        *buf = rest;
+        Some(head)
+    }
+
+    let mut fields = Vec::new();
+    while !buf.is_empty() {
+        let key = read_varint(&mut buf)?;
+        let field = (key >> 3) as u32;
+        let value = match (key & 7) as u32 {
+            VARINT => Field::Varint(read_varint(&mut buf)?),
+            FIXED64 => Field::Fixed64(u64::from_le_bytes(take(&mut buf, 8)?.try_into().ok()?)),
+            LEN => {
+                let len = read_varint(&mut buf)? as usize;
+                Field::Bytes(take(&mut buf, len)?)
+            }
+            FIXED32 => Field::Fixed32(u32::from_le_bytes(take(&mut buf, 4)?.try_into().ok()?)),
+            _ => return None,
+        };
+        fields.push((field, value));
+    }
+    Some(fields)
+}
+
+/// Rejected data points and message from an `ExportMetricsServiceResponse`
+pub(super) fn partial_success_protobuf(body: &[u8]) -> (u64, Option<String>) {
+    let mut result = (0, None);
+    let Some(fields) = read_fields(body) else {
+        return result;
+    };
+    for (number, value) in fields {
+        let (1, Field::Bytes(partial)) = (number, value) else {
+            continue;
+        };
+        for (number, value) in read_fields(partial).unwrap_or_default() {
+            match (number, value) {
+                (1, Field::Varint(rejected)) => result.0 = rejected,
+                (2, Field::Bytes(message)) if !message.is_empty() => {
+                    result.1 = Some(String::from_utf8_lossy(message).into_owned())
+                }
+                _ => {}
+            }
+        }
+    }
+    result
+}
+
+/// Encode an export request as OTLP/JSON
+pub(super) fn json(resource: &Resource, metrics: &[Metric]) -> Vec<u8> {
+    let (name, version) = scope();
+    let request = json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": json_attributes(&resource.attributes) },
+            "scopeMetrics": [{
+                "scope": { "name": name, "version": version },
+                "metrics": metrics.iter().map(json_metric).collect::<Vec<_>>(),
+            }],
+        }],
+    });
+    serde_json::to_vec(&request).unwrap_or_default()
+}
+
+fn json_metric(metric: &Metric) -> Value {
+    let points: Vec<Value> = metric.points.iter().map(json_point).collect();
+    let mut value = json!({
+        "name": metric.name,
+        "description": metric.description,
+        "unit": metric.unit,
+    });
+    match metric.kind {
+        MetricKind::Gauge => value["gauge"] = json!({ "dataPoints": points }),
+        MetricKind::Sum { monotonic } => {
+            value["sum"] = json!({
+                "dataPoints": points,
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": monotonic,
+            })
+        }
+    }
+    value
+}
+
+fn json_point(point: &DataPoint) -> Value {
+    let mut value = json!({
+        "attributes": json_attributes(&point.attributes),
+        "timeUnixNano": point.time_unix_nano.to_string(),
+    });
+    if point.start_time_unix_nano != 0 {
+        value["startTimeUnixNano"] = json!(point.start_time_unix_nano.to_string());
+    }
+    match point.value {
+        NumberValue::Double(d) => value["asDouble"] = json!(d),
+        NumberValue::Int(i) => value["asInt"] = json!(i.to_string()),
+    }
+    value
+}
+
+fn json_attributes(attributes: &[(String, AttributeValue)]) -> Vec<Value> {
+    attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": json_any_value(value) }))
+        .collect()
+}
+
+fn json_any_value(value: &AttributeValue) -> Value {
+    match value {
+        AttributeValue::String(s) => json!({ "stringValue": s }),
+        AttributeValue::Bool(b) => json!({ "boolValue": b }),
+        AttributeValue::Int(i) => json!({ "intValue": i.to_string() }),
+        AttributeValue::Double(d) => json!({ "doubleValue": d }),
+        AttributeValue::Array(values) => {
+            json!({ "arrayValue": { "values": values.iter().map(json_any_value).collect::<Vec<_>>() } })
+        }
+    }
+}
+
+/// Rejected data points and message from an OTLP/JSON response
+pub(super) fn partial_success_json(body: &[u8]) -> (u64, Option<String>) {
+    let Ok(value) = serde_json::from_slice::<Value>(body) else {
+        return (0, None);
+    };
+    let partial = &value["partialSuccess"];
+    // int64 is a string in OTLP/JSON, but accept plain numbers too
+    let rejected = match &partial["rejectedDataPoints"] {
+        Value::String(s) => s.parse().unwrap_or(0),
+        other => other.as_u64().unwrap_or(0),
+    };
+    let message = partial["errorMessage"]
+        .as_str()
+        .filter(|m| !m.is_empty())
+        .map(str::to_string);
+    (rejected, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (Resource, Vec<Metric>) {
+        let resource = Resource::empty()
+            .with_attribute("host.name", "node01")
+            .with_attribute("simon.gpu.uuids", vec!["GPU-a".to_string()]);
+        let metric = Metric::sum("system.network.io", "Network bytes", "By", true).with_point(
+            DataPoint::int(
+                1_000,
+                vec![("network.io.direction".into(), "receive".into())],
+            )
+            .at(5, 7),
+        );
+        let gauge = Metric::gauge("hw.temperature", "Temperature", "Cel")
+            .with_point(DataPoint::double(71.5, Vec::new()).at(0, 7));
+        (resource, vec![metric, gauge])
+    }
+
+    fn bytes<'a>(fields: &[(u32, Field<'a>)], number: u32) -> Vec<&'a [u8]> {
+        fields
+            .iter()
+            .filter_map(|(n, v)| match v {
+                Field::Bytes(b) if *n == number => Some(*b),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_protobuf_layout() {
+        let (resource, metrics) = sample();
+        let encoded = protobuf(&resource, &metrics);
+
+        let request = read_fields(&encoded).unwrap();
+        let resource_metrics = read_fields(bytes(&request, 1)[0]).unwrap();
+        let resource = read_fields(bytes(&resource_metrics, 1)[0]).unwrap();
+        assert_eq!(bytes(&resource, 1).len(), 2);
+
+        let scope_metrics = read_fields(bytes(&resource_metrics, 2)[0]).unwrap();
+        let metrics = bytes(&scope_metrics, 2);
+        assert_eq!(metrics.len(), 2);
+
+        let sum = read_fields(metrics[0]).unwrap();
+        assert_eq!(bytes(&sum, 1), vec![b"system.network.io".as_slice()]);
+        let sum = read_fields(bytes(&sum, 7)[0]).unwrap();
+        assert!(sum.contains(&(2, Field::Varint(CUMULATIVE))));
+        assert!(sum.contains(&(3, Field::Varint(1))));
+        let point = read_fields(bytes(&sum, 1)[0]).unwrap();
+        assert!(point.contains(&(2, Field::Fixed64(5))));
+        assert!(point.contains(&(3, Field::Fixed64(7))));
+        assert!(point.contains(&(6, Field::Fixed64(1_000))));
+
+        let gauge = read_fields(metrics[1]).unwrap();
+        let gauge = read_fields(bytes(&gauge, 5)[0]).unwrap();
+        let point = read_fields(bytes(&gauge, 1)[0]).unwrap();
+        assert!(point.contains(&(4, Field::Fixed64(71.5f64.to_bits()))));
+        assert!(!point.iter().any(|(n, _)| *n == 2));
+    }
+
+    #[test]
+    fn test_json_mapping() {
+        let (resource, metrics) = sample();
+        let encoded: Value = serde_json::from_slice(&json(&resource, &metrics)).unwrap();
+
+        let rm = &encoded["resourceMetrics"][0];
+        assert_eq!(
+            rm["resource"]["attributes"][0]["value"]["stringValue"],
+            "node01"
+        );
+        assert_eq!(
+            rm["resource"]["attributes"][1]["value"]["arrayValue"]["values"][0]["stringValue"],
+            "GPU-a"
+        );
+        let sum = &rm["scopeMetrics"][0]["metrics"][0]["sum"];
+        assert_eq!(sum["aggregationTemporality"], 2);
+        assert_eq!(sum["isMonotonic"], true);
+        assert_eq!(sum["dataPoints"][0]["asInt"], "1000");
+        assert_eq!(sum["dataPoints"][0]["startTimeUnixNano"], "5");
+        let gauge = &rm["scopeMetrics"][0]["metrics"][1]["gauge"];
+        assert_eq!(gauge["dataPoints"][0]["asDouble"], 71.5);
+        assert!(gauge["dataPoints"][0].get("startTimeUnixNano").is_none());
+    }
+
+    #[test]
+    fn test_partial_success() {
+        let mut body = Vec::new();
+        message(&mut body, 1, |p| {
+            tag(p, 1, VARINT);
+            varint(p, 3);
+            string(p, 2, "bad unit");
+        });
+        assert_eq!(
+            partial_success_protobuf(&body),
+            (3, Some("bad unit".to_string()))
+        );
+        assert_eq!(partial_success_protobuf(&[]), (0, None));
+        assert_eq!(
+            partial_success_json(br#"{"partialSuccess":{"rejectedDataPoints":"2"}}"#),
+            (2, None)
+        );
+        assert_eq!(partial_success_json(b"{}"), (0, None));
+    }
+}
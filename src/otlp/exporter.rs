@@ -0,0 +1,548 @@
+//! OTLP/HTTP delivery: batching, retries and the disk buffer
+
+use super::{encode, now_unix_nano, Metric, MetricBatch, OtlpConfig, OtlpProtocol, Resource};
+use crate::daemon::http;
+use crate::error::{Result, SimonError};
+use crate::utils::split_http_url;
+use std::fs;
+use std::io::{BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Largest collector response read
+const MAX_RESPONSE: usize = 1024 * 1024;
+
+/// Outcome of [`OtlpExporter::flush`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportReport {
+    /// Requests the collector accepted
+    pub batches_sent: usize,
+    /// Data points in those requests
+    pub points_sent: usize,
+    /// Requests from the disk buffer delivered
+    pub batches_replayed: usize,
+    /// Requests written to the disk buffer
+    pub batches_buffered: usize,
+    /// Data points the collector reported as rejected
+    pub rejected_points: u64,
+    /// Why the collector could not be reached, if it could not
+    pub error: Option<String>,
+}
+
+/// Why a request was not delivered
+#[derive(Debug)]
+enum SendError {
+    /// Unreachable, throttled or temporarily failing; worth retrying
+    Unavailable(String, Option<Duration>),
+    /// The collector refused the request; retrying will not help
+    Rejected(String),
+}
+
+/// Pushes metrics to an OTLP/HTTP collector
+pub struct OtlpExporter {
+    config: OtlpConfig,
+    resource: Resource,
+    start_time_unix_nano: u64,
+    pending: Vec<Metric>,
+    sequence: u64,
+}
+
+impl OtlpExporter {
+    /// Exporter for `resource`; cumulative sums start now
+    pub fn new(config: OtlpConfig, resource: Resource) -> Self {
+        Self {
+            config,
+            resource,
+            start_time_unix_nano: now_unix_nano(),
+            pending: Vec::new(),
+            sequence: 0,
+        }
+    }
+
+    /// Configuration
+    pub fn config(&self) -> &OtlpConfig {
+        &self.config
+    }
+
+    /// Resource attached to every request
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    /// Empty batch for the next sample
+    pub fn batch(&self) -> MetricBatch {
+        MetricBatch::new(self.start_time_unix_nano)
+    }
+
+    /// Queue a batch until the next [`flush`](Self::flush)
+    pub fn record(&mut self, batch: MetricBatch) {
+        for metric in batch.into_metrics() {
+            match self.pending.iter_mut().find(|m| m.name == metric.name) {
+                Some(pending) => pending.points.extend(metric.points),
+                None => self.pending.push(metric),
+            }
+        }
+    }
+
+    /// Data points waiting for [`flush`](Self::flush)
+    pub fn pending_points(&self) -> usize {
+        self.pending.iter().map(|m| m.points.len()).sum()
+    }
+
+    /// Requests waiting in the disk buffer
+    pub fn buffered_requests(&self) -> usize {
+        self.buffer()
+            .and_then(|buffer| buffer.entries().ok())
+            .map_or(0, |entries| entries.len())
+    }
+
+    /// Deliver the disk buffer, then everything queued
+    ///
+    /// Once the collector is unreachable, the remaining requests go to the
+    /// disk buffer (reported in [`ExportReport::error`]); without a buffer
+    /// they are dropped and an error is returned. Requests the collector
+    /// refuses outright (`400 Bad Request`) are dropped with an error.
+    pub fn flush(&mut self) -> Result<ExportReport> {
+        let protocol = self.config.protocol;
+        let requests: Vec<(Vec<u8>, usize)> = split(
+            std::mem::take(&mut self.pending),
+            self.config.max_batch_points,
+        )
+        .into_iter()
+        .map(|(metrics, points)| (protocol.encode(&self.resource, &metrics), points))
+        .collect();
+
+        let mut report = ExportReport::default();
+        let mut rejected = None;
+        let buffer = self.buffer();
+
+        if let Some(buffer) = &buffer {
+            for (path, protocol) in buffer.entries()? {
+                let body = fs::read(&path)?;
+                match self.send(&body, protocol) {
+                    Ok(points) => {
+                        report.batches_replayed += 1;
+                        report.rejected_points += points;
+                        buffer.remove(&path);
+                    }
+                    Err(SendError::Unavailable(message, _)) => {
+                        report.error = Some(message);
+                        break;
+                    }
+                    Err(SendError::Rejected(message)) => {
+                        log::warn!(
+                            "dropping buffered OTLP request {}: {}",
+                            path.display(),
+                            message
+                        );
+                        buffer.remove(&path);
+                    }
+                }
+            }
+        }
+
+        let (mut dropped, mut sequence) = (0, self.sequence);
+        for (body, points) in requests {
+            let result = match &report.error {
+                // Don't wait out the backoff again for every request
+                Some(message) => Err(SendError::Unavailable(message.clone(), None)),
+                None => self.send(&body, protocol),
+            };
+            match result {
+                Ok(points_rejected) => {
+                    report.batches_sent += 1;
+                    report.points_sent += points;
+                    report.rejected_points += points_rejected;
+                }
+                Err(SendError::Unavailable(message, _)) => {
+                    report.error = Some(message);
+                    match &buffer {
+                        Some(buffer) => {
+                            sequence += 1;
+                            buffer.push(&body, protocol, sequence)?;
+                            report.batches_buffered += 1;
+                        }
+                        None => dropped += points,
+                    }
+                }
+                Err(SendError::Rejected(message)) => rejected = Some(message),
+            }
+        }
+
+        self.sequence = sequence;
+
+        if let Some(message) = rejected {
+            return Err(SimonError::InvalidValue(format!(
+                "OTLP collector rejected metrics: {}",
+                message
+            )));
+        }
+        if dropped > 0 {
+            return Err(SimonError::Network(format!(
+                "dropped {} OTLP data points: {}",
+                dropped,
+                report.error.unwrap_or_default()
+            )));
+        }
+        Ok(report)
+    }
+
+    fn buffer(&self) -> Option<DiskBuffer<'_>> {
+        self.config.buffer_dir.as_deref().map(|dir| DiskBuffer {
+            dir,
+            max_bytes: self.config.buffer_max_bytes,
+        })
+    }
+
+    /// POST with retries; returns the points the collector rejected
+    fn send(&self, body: &[u8], protocol: OtlpProtocol) -> std::result::Result<u64, SendError> {
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            match self.post(body, protocol) {
+                Err(SendError::Unavailable(message, retry_after))
+                    if attempt < self.config.max_retries =>
+                {
+                    let delay = retry_after.unwrap_or(backoff).min(self.config.max_backoff);
+                    log::debug!("OTLP export failed ({}), retrying in {:?}", message, delay);
+                    std::thread::sleep(delay);
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn post(&self, body: &[u8], protocol: OtlpProtocol) -> std::result::Result<u64, SendError> {
+        let (authority, path) =
+            split_http_url(&self.config.endpoint, 80).map_err(SendError::Rejected)?;
+        let unavailable = |e: std::io::Error| {
+            SendError::Unavailable(format!("{}: {}", self.config.endpoint, e), None)
+        };
+
+        let addr = authority
+            .to_socket_addrs()
+            .map_err(unavailable)?
+            .next()
+            .ok_or_else(|| SendError::Unavailable(format!("cannot resolve {}", authority), None))?;
+        let mut stream =
+            TcpStream::connect_timeout(&addr, self.config.timeout).map_err(unavailable)?;
+        stream
+            .set_read_timeout(Some(self.config.timeout))
+            .and_then(|_| stream.set_write_timeout(Some(self.config.timeout)))
+            .map_err(unavailable)?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nUser-Agent: simon/{}\r\nConnection: close\r\n",
+            path,
+            authority,
+            protocol.content_type(),
+            body.len(),
+            env!("CARGO_PKG_VERSION"),
+        );
+        for (name, value) in &self.config.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str("\r\n");
+        stream
+            .write_all(head.as_bytes())
+            .and_then(|_| stream.write_all(body))
+            .and_then(|_| stream.flush())
+            .map_err(unavailable)?;
+
+        let response = http::read_response(&mut BufReader::new(stream), false, MAX_RESPONSE)
+            .map_err(unavailable)?;
+        match response.status {
+            200..=299 => {
+                let (rejected, message) = match protocol {
+                    OtlpProtocol::Protobuf => encode::partial_success_protobuf(&response.body),
+                    OtlpProtocol::Json => encode::partial_success_json(&response.body),
+                };
+                if rejected > 0 || message.is_some() {
+                    log::warn!(
+                        "OTLP collector rejected {} data points: {}",
+                        rejected,
+                        message.unwrap_or_default()
+                    );
+                }
+                Ok(rejected)
+            }
+            429 | 502 | 503 | 504 => {
+                let retry_after = response
+                    .header("Retry-After")
+                    .and_then(|s| s.trim().parse().ok())
+                    .map(Duration::from_secs);
+                Err(SendError::Unavailable(
+                    format!("{} returned HTTP {}", self.config.endpoint, response.status),
+                    retry_after,
+                ))
+            }
+            status => {
+                let detail = String::from_utf8_lossy(&response.body);
+                Err(SendError::Rejected(format!(
+                    "HTTP {} {}",
+                    status,
+                    detail.chars().take(200).collect::<String>()
+                )))
+            }
+        }
+    }
+}
+
+/// Split metrics into requests of at most `max_points` points
+fn split(metrics: Vec<Metric>, max_points: usize) -> Vec<(Vec<Metric>, usize)> {
+    let max_points = max_points.max(1);
+    let mut requests = Vec::new();
+    let (mut current, mut count): (Vec<Metric>, usize) = (Vec::new(), 0);
+    for metric in metrics {
+        let points = metric.points;
+        let template = Metric {
+            points: Vec::new(),
+            ..metric
+        };
+        for point in points {
+            if count == max_points {
+                requests.push((std::mem::take(&mut current), count));
+                count = 0;
+            }
+            match current.last_mut() {
+                Some(last) if last.name == template.name => last.points.push(point),
+                _ => current.push(template.clone().with_point(point)),
+            }
+            count += 1;
+        }
+    }
+    if count > 0 {
+        requests.push((current, count));
+    }
+    requests
+}
+
+/// Encoded requests waiting on disk, oldest first by file name
+struct DiskBuffer<'a> {
+    dir: &'a Path,
+    max_bytes: u64,
+}
+
+impl DiskBuffer<'_> {
+    /// Buffered requests, oldest first
+    fn entries(&self) -> Result<Vec<(PathBuf, OtlpProtocol)>> {
+        let read_dir = match fs::read_dir(self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries: Vec<_> = read_dir
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter_map(|path| {
+                let protocol = match path.extension()?.to_str()? {
+                    "pb" => OtlpProtocol::Protobuf,
+                    "json" => OtlpProtocol::Json,
+                    _ => return None,
+                };
+                Some((path, protocol))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(entries)
+    }
+
+    /// Store a request, dropping the oldest ones over the size cap
+    fn push(&self, body: &[u8], protocol: OtlpProtocol, sequence: u64) -> Result<()> {
+        fs::create_dir_all(self.dir)?;
+        let name = format!(
+            "{:020}-{:06}.{}",
+            now_unix_nano(),
+            sequence % 1_000_000,
+            protocol.extension()
+        );
+        // Write then rename so a crash never leaves a truncated request
+        let temporary = self.dir.join(format!("{}.tmp", name));
+        fs::write(&temporary, body)?;
+        fs::rename(&temporary, self.dir.join(name))?;
+
+        let entries = self.entries()?;
+        let sizes: Vec<u64> = entries
+            .iter()
+            .map(|(path, _)| fs::metadata(path).map_or(0, |m| m.len()))
+            .collect();
+        let mut total: u64 = sizes.iter().sum();
+        for ((path, _), size) in entries.iter().zip(sizes) {
+            if total <= self.max_bytes {
+                break;
+            }
+            log::warn!("OTLP buffer full, dropping {}", path.display());
+            self.remove(path);
+            total -= size;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) {
+        if let Err(e) = fs::remove_file(path) {
+            log::warn!("cannot remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::DataPoint;
+    use super::*;
+    use std::collections::VecDeque;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// Request seen by the mock collector: path, content type, body
+    type Received = Arc<Mutex<Vec<(String, String, Vec<u8>)>>>;
+
+    /// Collector answering with `statuses` in turn, then `200 OK`
+    fn mock_collector(statuses: &[u16]) -> (String, Received) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let received: Received = Arc::default();
+        let mut statuses: VecDeque<u16> = statuses.iter().copied().collect();
+
+        let log = Arc::clone(&received);
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let Ok(Some(request)) = http::read_request(&mut reader) else {
+                    continue;
+                };
+                let content_type = request.header("Content-Type").unwrap_or("").to_string();
+                log.lock()
+                    .unwrap()
+                    .push((request.path.clone(), content_type, request.body));
+                let response = http::Response {
+                    status: statuses.pop_front().unwrap_or(200),
+                    content_type: "application/json",
+                    headers: Vec::new(),
+                    body: Vec::new(),
+                };
+                let _ = response.write_to(&mut stream, false);
+            }
+        });
+        (endpoint, received)
+    }
+
+    fn batch(exporter: &OtlpExporter, points: usize) -> MetricBatch {
+        let mut batch = exporter.batch();
+        for core in 0..points {
+            batch.push(
+                Metric::gauge("system.cpu.utilization", "CPU utilization", "1"),
+                DataPoint::double(
+                    0.5,
+                    vec![("cpu.logical_number".into(), (core as i64).into())],
+                ),
+            );
+        }
+        batch
+    }
+
+    #[test]
+    fn test_export_batches_protobuf() {
+        let (endpoint, received) = mock_collector(&[]);
+        let config = OtlpConfig::default()
+            .with_endpoint(&endpoint)
+            .with_max_batch_points(2);
+        let resource = Resource::empty().with_attribute("host.name", "node01");
+        let mut exporter = OtlpExporter::new(config, resource);
+        exporter.record(batch(&exporter, 2));
+        exporter.record(batch(&exporter, 1));
+        assert_eq!(exporter.pending_points(), 3);
+
+        let report = exporter.flush().unwrap();
+        assert_eq!(report.batches_sent, 2);
+        assert_eq!(report.points_sent, 3);
+        assert_eq!(exporter.pending_points(), 0);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        let (path, content_type, body) = &received[0];
+        assert_eq!(path, "/v1/metrics");
+        assert_eq!(content_type, "application/x-protobuf");
+        let contains = |needle: &[u8]| body.windows(needle.len()).any(|w| w == needle);
+        assert!(contains(b"node01"));
+        assert!(contains(b"system.cpu.utilization"));
+    }
+
+    #[test]
+    fn test_retry_json() {
+        let (endpoint, received) = mock_collector(&[503, 429]);
+        let config = OtlpConfig::default()
+            .with_endpoint(&endpoint)
+            .with_protocol(OtlpProtocol::Json)
+            .with_retry(2, Duration::from_millis(1));
+        let mut exporter = OtlpExporter::new(config, Resource::empty());
+        exporter.record(batch(&exporter, 1));
+
+        let report = exporter.flush().unwrap();
+        assert_eq!(report.batches_sent, 1);
+        assert_eq!(report.error, None);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let body: serde_json::Value = serde_json::from_slice(&received[2].2).unwrap();
+        let metric = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"][0];
+        assert_eq!(metric["name"], "system.cpu.utilization");
+        assert_eq!(received[2].1, "application/json");
+    }
+
+    #[test]
+    fn test_rejected_request_is_dropped() {
+        let (endpoint, _received) = mock_collector(&[400]);
+        let dir = TempDir::new().unwrap();
+        let config = OtlpConfig::default()
+            .with_endpoint(&endpoint)
+            .with_buffer_dir(dir.path());
+        let mut exporter = OtlpExporter::new(config, Resource::empty());
+        exporter.record(batch(&exporter, 1));
+
+        assert!(matches!(exporter.flush(), Err(SimonError::InvalidValue(_))));
+        assert_eq!(exporter.buffered_requests(), 0);
+    }
+
+    #[test]
+    fn test_disk_buffer_replay() {
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let down = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let dir = TempDir::new().unwrap();
+
+        let config = OtlpConfig::default()
+            .with_endpoint(&down)
+            .with_retry(0, Duration::from_millis(1))
+            .with_max_batch_points(1)
+            .with_buffer_dir(dir.path());
+        let mut exporter = OtlpExporter::new(config.clone(), Resource::empty());
+        exporter.record(batch(&exporter, 2));
+        let report = exporter.flush().unwrap();
+        assert_eq!(report.batches_buffered, 2);
+        assert!(report.error.is_some());
+        assert_eq!(exporter.buffered_requests(), 2);
+
+        // Without a buffer the points are lost, which is an error
+        let mut unbuffered = OtlpExporter::new(
+            OtlpConfig {
+                buffer_dir: None,
+                ..config.clone()
+            },
+            Resource::empty(),
+        );
+        unbuffered.record(batch(&unbuffered, 1));
+        assert!(matches!(unbuffered.flush(), Err(SimonError::Network(_))));
+
+        let (endpoint, received) = mock_collector(&[]);
+        let mut exporter = OtlpExporter::new(config.with_endpoint(&endpoint), Resource::empty());
+        exporter.record(batch(&exporter, 1));
+        let report = exporter.flush().unwrap();
+        assert_eq!(report.batches_replayed, 2);
+        assert_eq!(report.batches_sent, 1);
+        assert_eq!(exporter.buffered_requests(), 0);
+        assert_eq!(received.lock().unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,1260 @@
+//! OpenTelemetry (OTLP) metrics export
+//!
+//! Pushes samples to an OTLP collector over OTLP/HTTP, in protobuf or JSON,
+//! as a push-based alternative to scraping. [`MetricBatch`] maps simon's
+//! types onto the OpenTelemetry semantic conventions:
+//!
+//! | Source | Metrics |
+//! |--------|---------|
+//! | [`FullSystemState`] | `system.cpu.utilization`, `system.cpu.frequency`, `system.memory.usage`, `system.memory.utilization`, `system.paging.usage`, `system.filesystem.usage`, `system.filesystem.utilization`, `system.network.io`, `system.uptime` |
+//! | [`GpuInfo`] / [`AcceleratorState`] | `hw.gpu.utilization`, `hw.gpu.memory.usage`, `hw.gpu.memory.limit`, `hw.gpu.memory.utilization`, `hw.temperature`, `hw.power`, `hw.fan.speed` |
+//! | [`HwSensor`] | `hw.temperature`, `hw.fan.speed`, `hw.voltage`, `hw.power`, `hw.energy` |
+//! | [`ProcessMonitorInfo`] | `process.gpu.memory.usage`, `process.gpu.utilization` |
+//! | [`SystemHealth`] | `simon.health.score`, `simon.health.check.score` |
+//!
+//! GPUs are identified by `hw.id` (their UUID when known). The [`Resource`]
+//! carries `host.*`, `os.*`, the board (`simon.board.vendor`,
+//! `simon.board.name`) and all GPU UUIDs (`simon.gpu.uuids`), plus anything
+//! in `OTEL_RESOURCE_ATTRIBUTES`.
+//!
+//! [`OtlpExporter`] queues batches, sends them in requests of at most
+//! [`OtlpConfig::max_batch_points`] points, retries throttled or unavailable
+//! collectors with exponential backoff, and can spill requests it could not
+//! deliver to a disk buffer that is replayed once the collector is back.
+//! Only plain `http://` collectors are supported; put a local collector or
+//! TLS-terminating proxy in front of remote HTTPS endpoints.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::daemon::{BackendSampler, DaemonConfig, Sampler};
+//! use simon::otlp::{OtlpConfig, OtlpExporter, OtlpProtocol, Resource};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = OtlpConfig::from_env()
+//!     .with_endpoint("http://collector:4318")
+//!     .with_protocol(OtlpProtocol::Json)
+//!     .with_buffer_dir("/var/lib/simon/otlp");
+//! let mut sampler = BackendSampler::new(&DaemonConfig::default())?;
+//! let snapshot = sampler.sample()?;
+//!
+//! let resource = Resource::detect().with_gpus(&snapshot.gpus);
+//! let mut exporter = OtlpExporter::new(config, resource);
+//! let mut batch = exporter.batch();
+//! batch.record_snapshot(&snapshot).record_health(&sampler.health()?);
+//! exporter.record(batch);
+//!
+//! let report = exporter.flush()?;
+//! println!("sent {} points", report.points_sent);
+//! # Ok(())
+//! # }
+//! ```
+
+mod encode;
+mod exporter;
+
+pub use exporter::{ExportReport, OtlpExporter};
+
+use crate::backend::{AcceleratorState, FullSystemState};
+use crate::daemon::Snapshot;
+use crate::error::{Result, SimonError};
+use crate::gpu::GpuInfo;
+use crate::health::{HealthStatus, SystemHealth};
+use crate::hwmon::{HwSensor, HwSensorType, HwType};
+use crate::process_monitor::ProcessMonitorInfo;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default OTLP/HTTP metrics endpoint
+pub const DEFAULT_ENDPOINT: &str = "http://localhost:4318/v1/metrics";
+
+/// Attribute value
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    String(String),
+    Bool(bool),
+    Int(i64),
+    Double(f64),
+    Array(Vec<AttributeValue>),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        Self::Double(value)
+    }
+}
+
+impl From<Vec<String>> for AttributeValue {
+    fn from(values: Vec<String>) -> Self {
+        Self::Array(values.into_iter().map(Self::String).collect())
+    }
+}
+
+/// Key/value attributes of a resource or data point
+pub type Attributes = Vec<(String, AttributeValue)>;
+
+/// Value of a data point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberValue {
+    Int(i64),
+    Double(f64),
+}
+
+/// One data point
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataPoint {
+    /// Attributes identifying the series
+    pub attributes: Attributes,
+    /// Value
+    pub value: NumberValue,
+    /// Start of a cumulative sum (0 for gauges)
+    pub start_time_unix_nano: u64,
+    /// Sample time
+    pub time_unix_nano: u64,
+}
+
+impl DataPoint {
+    /// Integer point
+    pub fn int(value: i64, attributes: Attributes) -> Self {
+        Self::new(NumberValue::Int(value), attributes)
+    }
+
+    /// Floating-point point
+    pub fn double(value: f64, attributes: Attributes) -> Self {
+        Self::new(NumberValue::Double(value), attributes)
+    }
+
+    fn new(value: NumberValue, attributes: Attributes) -> Self {
+        Self {
+            attributes,
+            value,
+            start_time_unix_nano: 0,
+            time_unix_nano: 0,
+        }
+    }
+
+    /// Set start and sample time
+    pub fn at(mut self, start_time_unix_nano: u64, time_unix_nano: u64) -> Self {
+        self.start_time_unix_nano = start_time_unix_nano;
+        self.time_unix_nano = time_unix_nano;
+        self
+    }
+}
+
+/// Kind of metric
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Last value
+    Gauge,
+    /// Cumulative sum; non-monotonic sums are up/down counters
+    Sum { monotonic: bool },
+}
+
+/// A named metric with its data points
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metric {
+    /// Semantic-convention name
+    pub name: String,
+    /// Description
+    pub description: String,
+    /// UCUM unit (`1`, `By`, `Cel`, `W`)
+    pub unit: String,
+    /// Gauge or sum
+    pub kind: MetricKind,
+    /// Data points
+    pub points: Vec<DataPoint>,
+}
+
+impl Metric {
+    /// Empty gauge
+    pub fn gauge(name: &str, description: &str, unit: &str) -> Self {
+        Self::new(name, description, unit, MetricKind::Gauge)
+    }
+
+    /// Empty cumulative sum
+    pub fn sum(name: &str, description: &str, unit: &str, monotonic: bool) -> Self {
+        Self::new(name, description, unit, MetricKind::Sum { monotonic })
+    }
+
+    fn new(name: &str, description: &str, unit: &str, kind: MetricKind) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            unit: unit.to_string(),
+            kind,
+            points: Vec::new(),
+        }
+    }
+
+    /// Add a data point
+    pub fn with_point(mut self, point: DataPoint) -> Self {
+        self.points.push(point);
+        self
+    }
+}
+
+/// Entity that produced the metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Resource {
+    /// Resource attributes
+    pub attributes: Attributes,
+}
+
+impl Resource {
+    /// Resource without attributes
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Detect host, OS and board attributes
+    ///
+    /// `OTEL_SERVICE_NAME` and `OTEL_RESOURCE_ATTRIBUTES` override the
+    /// detected values.
+    pub fn detect() -> Self {
+        let mut resource = Self::empty()
+            .with_attribute("service.name", "simon")
+            .with_attribute("service.version", env!("CARGO_PKG_VERSION"))
+            .with_attribute("os.type", os_type())
+            .with_attribute("host.arch", host_arch());
+        if let Ok(hostname) = hostname::get() {
+            resource = resource.with_attribute("host.name", hostname.to_string_lossy().as_ref());
+        }
+        if let Ok(id) = std::fs::read_to_string("/etc/machine-id") {
+            if !id.trim().is_empty() {
+                resource = resource.with_attribute("host.id", id.trim());
+            }
+        }
+        if let Ok(info) = crate::motherboard::get_system_info() {
+            resource = resource
+                .with_attribute(
+                    "os.description",
+                    format!("{} {}", info.os_name, info.os_version).trim(),
+                )
+                .with_optional("host.type", info.product_name)
+                .with_optional("simon.board.vendor", info.board_vendor)
+                .with_optional("simon.board.name", info.board_name);
+        }
+        if let Ok(attributes) = std::env::var("OTEL_RESOURCE_ATTRIBUTES") {
+            for (key, value) in parse_key_values(&attributes) {
+                resource = resource.with_attribute(&key, value);
+            }
+        }
+        if let Ok(name) = std::env::var("OTEL_SERVICE_NAME") {
+            resource = resource.with_attribute("service.name", name);
+        }
+        resource
+    }
+
+    /// Set an attribute, replacing any previous value
+    pub fn with_attribute(mut self, key: &str, value: impl Into<AttributeValue>) -> Self {
+        let value = value.into();
+        match self.attributes.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.attributes.push((key.to_string(), value)),
+        }
+        self
+    }
+
+    fn with_optional(self, key: &str, value: Option<String>) -> Self {
+        match value.filter(|v| !v.trim().is_empty()) {
+            Some(value) => self.with_attribute(key, value.trim()),
+            None => self,
+        }
+    }
+
+    /// Record the UUIDs of `gpus` as `simon.gpu.uuids`
+    pub fn with_gpus(self, gpus: &[GpuInfo]) -> Self {
+        let uuids: Vec<String> = gpus
+            .iter()
+            .filter_map(|gpu| gpu.static_info.uuid.clone())
+            .collect();
+        if uuids.is_empty() {
+            return self;
+        }
+        self.with_attribute("simon.gpu.uuids", uuids)
+    }
+
+    /// Attribute value by key
+    pub fn attribute(&self, key: &str) -> Option<&AttributeValue> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+/// `os.type` value
+fn os_type() -> &'static str {
+    match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    }
+}
+
+/// `host.arch` value
+fn host_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "x86",
+        "powerpc64" => "ppc64",
+        arch => arch,
+    }
+}
+
+/// Parse `key1=value1,key2=value2` (the `OTEL_*` list format)
+fn parse_key_values(list: &str) -> Vec<(String, String)> {
+    list.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (percent_decode(k.trim()), percent_decode(v.trim())))
+        .filter(|(k, _)| !k.is_empty())
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = s
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Current time in nanoseconds since the Unix epoch
+pub fn now_unix_nano() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// OTLP/HTTP payload encoding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OtlpProtocol {
+    /// `application/x-protobuf`
+    #[default]
+    Protobuf,
+    /// `application/json`
+    Json,
+}
+
+impl OtlpProtocol {
+    /// HTTP content type
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Protobuf => "application/x-protobuf",
+            Self::Json => "application/json",
+        }
+    }
+
+    /// Encode an export request
+    pub fn encode(&self, resource: &Resource, metrics: &[Metric]) -> Vec<u8> {
+        match self {
+            Self::Protobuf => encode::protobuf(resource, metrics),
+            Self::Json => encode::json(resource, metrics),
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Protobuf => "pb",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for OtlpProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Protobuf => write!(f, "http/protobuf"),
+            Self::Json => write!(f, "http/json"),
+        }
+    }
+}
+
+impl FromStr for OtlpProtocol {
+    type Err = SimonError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "http/protobuf" | "protobuf" | "proto" => Ok(Self::Protobuf),
+            "http/json" | "json" => Ok(Self::Json),
+            other => Err(SimonError::InvalidValue(format!(
+                "unsupported OTLP protocol '{}' (expected http/protobuf or http/json)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Exporter configuration
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Full metrics URL (`http://host:4318/v1/metrics`)
+    pub endpoint: String,
+    /// Payload encoding
+    pub protocol: OtlpProtocol,
+    /// Extra request headers (API keys, tenant IDs)
+    pub headers: Vec<(String, String)>,
+    /// Per-request timeout
+    pub timeout: Duration,
+    /// Most data points per request
+    pub max_batch_points: usize,
+    /// Retries after the first attempt for retryable failures
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled each time
+    pub initial_backoff: Duration,
+    /// Cap on the backoff (and on a collector's `Retry-After`)
+    pub max_backoff: Duration,
+    /// Directory for requests that could not be delivered
+    pub buffer_dir: Option<PathBuf>,
+    /// Size cap of the disk buffer; the oldest requests are dropped first
+    pub buffer_max_bytes: u64,
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            protocol: OtlpProtocol::default(),
+            headers: Vec::new(),
+            timeout: Duration::from_secs(10),
+            max_batch_points: 2000,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            buffer_dir: None,
+            buffer_max_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl OtlpConfig {
+    /// Defaults overridden by the standard `OTEL_EXPORTER_OTLP_*` variables
+    ///
+    /// Reads the endpoint, protocol, headers and timeout, preferring the
+    /// `..._METRICS_...` variants. Invalid values are ignored.
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            std::env::var(format!("OTEL_EXPORTER_OTLP_METRICS_{}", name))
+                .or_else(|_| std::env::var(format!("OTEL_EXPORTER_OTLP_{}", name)))
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+        };
+        let mut config = Self::default();
+        if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_METRICS_ENDPOINT") {
+            config.endpoint = endpoint.trim().to_string();
+        } else if let Ok(endpoint) = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            config = config.with_endpoint(&endpoint);
+        }
+        if let Some(protocol) = var("PROTOCOL").and_then(|p| p.parse().ok()) {
+            config.protocol = protocol;
+        }
+        if let Some(headers) = var("HEADERS") {
+            config.headers.extend(parse_key_values(&headers));
+        }
+        if let Some(timeout) = var("TIMEOUT").and_then(|t| t.trim().parse().ok()) {
+            config.timeout = Duration::from_millis(timeout);
+        }
+        config
+    }
+
+    /// Collector URL; `/v1/metrics` is appended when it has no path
+    pub fn with_endpoint(mut self, endpoint: &str) -> Self {
+        let endpoint = endpoint.trim().trim_end_matches('/');
+        let has_path = endpoint
+            .split_once("://")
+            .map_or(endpoint, |(_, rest)| rest)
+            .contains('/');
+        self.endpoint = if has_path {
+            endpoint.to_string()
+        } else {
+            format!("{}/v1/metrics", endpoint)
+        };
+        self
+    }
+
+    /// Payload encoding
+    pub fn with_protocol(mut self, protocol: OtlpProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Add a request header
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Per-request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Most data points per request
+    pub fn with_max_batch_points(mut self, points: usize) -> Self {
+        self.max_batch_points = points.max(1);
+        self
+    }
+
+    /// Retry count and first backoff
+    pub fn with_retry(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Buffer undeliverable requests in `dir`
+    pub fn with_buffer_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.buffer_dir = Some(dir.into());
+        self
+    }
+
+    /// Size cap of the disk buffer
+    pub fn with_buffer_max_bytes(mut self, bytes: u64) -> Self {
+        self.buffer_max_bytes = bytes;
+        self
+    }
+}
+
+/// Metrics from one sample, mapped to the semantic conventions
+///
+/// Points recorded under the same name are collected into one [`Metric`].
+#[derive(Debug, Clone)]
+pub struct MetricBatch {
+    start_time_unix_nano: u64,
+    time_unix_nano: u64,
+    metrics: Vec<Metric>,
+}
+
+impl MetricBatch {
+    /// Empty batch sampled now; cumulative sums start at `start_time_unix_nano`
+    pub fn new(start_time_unix_nano: u64) -> Self {
+        Self {
+            start_time_unix_nano,
+            time_unix_nano: now_unix_nano(),
+            metrics: Vec::new(),
+        }
+    }
+
+    /// Override the sample time
+    pub fn with_time(mut self, time_unix_nano: u64) -> Self {
+        self.time_unix_nano = time_unix_nano;
+        self
+    }
+
+    /// Recorded metrics
+    pub fn metrics(&self) -> &[Metric] {
+        &self.metrics
+    }
+
+    /// Take the recorded metrics
+    pub fn into_metrics(self) -> Vec<Metric> {
+        self.metrics
+    }
+
+    /// Number of data points
+    pub fn point_count(&self) -> usize {
+        self.metrics.iter().map(|m| m.points.len()).sum()
+    }
+
+    /// Add a point to the metric called `template.name`, creating it if needed
+    pub fn push(&mut self, template: Metric, point: DataPoint) {
+        match self.metrics.iter_mut().find(|m| m.name == template.name) {
+            Some(metric) => metric.points.push(point),
+            None => self.metrics.push(template.with_point(point)),
+        }
+    }
+
+    fn gauge(&mut self, name: &str, description: &str, unit: &str, value: f64, attrs: Attributes) {
+        let point = DataPoint::double(value, attrs).at(0, self.time_unix_nano);
+        self.push(Metric::gauge(name, description, unit), point);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn sum(
+        &mut self,
+        name: &str,
+        description: &str,
+        unit: &str,
+        monotonic: bool,
+        start: u64,
+        value: i64,
+        attrs: Attributes,
+    ) {
+        let point = DataPoint::int(value, attrs).at(start, self.time_unix_nano);
+        self.push(Metric::sum(name, description, unit, monotonic), point);
+    }
+
+    /// Record CPU, memory, swap, filesystems, network and uptime
+    ///
+    /// Accelerators are left to [`record_gpus`](Self::record_gpus) or
+    /// [`record_accelerators`](Self::record_accelerators).
+    pub fn record_state(&mut self, state: &FullSystemState) -> &mut Self {
+        let start = self.start_time_unix_nano;
+        // Kernel counters run from boot
+        let boot = state
+            .system
+            .as_ref()
+            .map(|system| {
+                self.time_unix_nano
+                    .saturating_sub(system.uptime_secs.saturating_mul(1_000_000_000))
+            })
+            .unwrap_or(start);
+
+        if let Some(cpu) = &state.cpu {
+            self.gauge(
+                "system.cpu.utilization",
+                "CPU utilization",
+                "1",
+                cpu.utilization as f64 / 100.0,
+                Vec::new(),
+            );
+            for (core, usage) in cpu.per_core_usage.iter().enumerate() {
+                self.gauge(
+                    "system.cpu.utilization",
+                    "CPU utilization",
+                    "1",
+                    *usage as f64 / 100.0,
+                    vec![attr("cpu.logical_number", core as i64)],
+                );
+            }
+            if let Some(mhz) = cpu.frequency_mhz {
+                self.gauge(
+                    "system.cpu.frequency",
+                    "CPU frequency",
+                    "Hz",
+                    mhz as f64 * 1e6,
+                    Vec::new(),
+                );
+            }
+            if let Some(temperature) = cpu.temperature {
+                self.temperature("cpu.temperature", "cpu", temperature as f64);
+            }
+        }
+
+        if let Some(memory) = &state.memory {
+            let free = memory.total_bytes.saturating_sub(memory.used_bytes);
+            for (memory_state, bytes) in [("used", memory.used_bytes), ("free", free)] {
+                self.sum(
+                    "system.memory.usage",
+                    "Memory in use",
+                    "By",
+                    false,
+                    start,
+                    bytes as i64,
+                    vec![attr("system.memory.state", memory_state)],
+                );
+            }
+            self.gauge(
+                "system.memory.utilization",
+                "Memory utilization",
+                "1",
+                memory.usage_percent as f64 / 100.0,
+                vec![attr("system.memory.state", "used")],
+            );
+            if memory.swap_total_bytes > 0 {
+                let free = memory
+                    .swap_total_bytes
+                    .saturating_sub(memory.swap_used_bytes);
+                for (paging_state, bytes) in [("used", memory.swap_used_bytes), ("free", free)] {
+                    self.sum(
+                        "system.paging.usage",
+                        "Swap in use",
+                        "By",
+                        false,
+                        start,
+                        bytes as i64,
+                        vec![attr("system.paging.state", paging_state)],
+                    );
+                }
+            }
+        }
+
+        for disk in &state.disks {
+            let free = disk.total_bytes.saturating_sub(disk.used_bytes);
+            let attrs = |fs_state: &str| {
+                vec![
+                    attr("system.device", disk.name.as_str()),
+                    attr("system.filesystem.mountpoint", disk.mount_point.as_str()),
+                    attr("system.filesystem.type", disk.filesystem.as_str()),
+                    attr("system.filesystem.state", fs_state),
+                ]
+            };
+            for (fs_state, bytes) in [("used", disk.used_bytes), ("free", free)] {
+                self.sum(
+                    "system.filesystem.usage",
+                    "Filesystem space",
+                    "By",
+                    false,
+                    start,
+                    bytes as i64,
+                    attrs(fs_state),
+                );
+            }
+            self.gauge(
+                "system.filesystem.utilization",
+                "Filesystem utilization",
+                "1",
+                disk.usage_percent as f64 / 100.0,
+                attrs("used"),
+            );
+        }
+
+        for interface in &state.network {
+            for (direction, bytes) in [
+                ("receive", interface.rx_bytes),
+                ("transmit", interface.tx_bytes),
+            ] {
+                self.sum(
+                    "system.network.io",
+                    "Network bytes",
+                    "By",
+                    true,
+                    boot,
+                    bytes as i64,
+                    vec![
+                        attr("network.interface.name", interface.name.as_str()),
+                        attr("network.io.direction", direction),
+                    ],
+                );
+            }
+        }
+
+        if let Some(system) = &state.system {
+            self.gauge(
+                "system.uptime",
+                "Time since boot",
+                "s",
+                system.uptime_secs as f64,
+                Vec::new(),
+            );
+        }
+        self
+    }
+
+    /// Record GPUs, identified by UUID where known
+    pub fn record_gpus(&mut self, gpus: &[GpuInfo]) -> &mut Self {
+        for gpu in gpus {
+            let (info, dynamic) = (&gpu.static_info, &gpu.dynamic_info);
+            let id = gpu_id(gpu);
+            let attrs = |extra: Vec<(String, AttributeValue)>| {
+                let mut attrs = vec![
+                    attr("hw.id", id.as_str()),
+                    attr("hw.name", info.name.as_str()),
+                    attr("hw.vendor", info.vendor.to_string()),
+                    attr("hw.type", "gpu"),
+                ];
+                attrs.extend(extra);
+                attrs
+            };
+
+            let tasks = [
+                ("general", Some(dynamic.utilization)),
+                ("encoder", dynamic.engines.encoder),
+                ("decoder", dynamic.engines.decoder),
+            ];
+            for (task, utilization) in tasks {
+                if let Some(utilization) = utilization {
+                    self.gauge(
+                        "hw.gpu.utilization",
+                        "GPU utilization",
+                        "1",
+                        utilization as f64 / 100.0,
+                        attrs(vec![attr("hw.gpu.task", task)]),
+                    );
+                }
+            }
+            self.gpu_memory(dynamic.memory.used, dynamic.memory.total, attrs(Vec::new()));
+            if let Some(watts) = dynamic.power.draw {
+                self.gauge(
+                    "hw.power",
+                    "Power draw",
+                    "W",
+                    watts as f64 / 1000.0,
+                    attrs(Vec::new()),
+                );
+            }
+            if let Some(temperature) = dynamic.thermal.temperature {
+                self.temperature(&format!("{}.temperature", id), &id, temperature as f64);
+            }
+            if let Some(rpm) = dynamic.thermal.fan_rpm {
+                self.gauge(
+                    "hw.fan.speed",
+                    "Fan speed",
+                    "rpm",
+                    rpm as f64,
+                    vec![
+                        attr("hw.id", format!("{}.fan", id)),
+                        attr("hw.type", "fan"),
+                        attr("hw.parent", id.as_str()),
+                    ],
+                );
+            }
+        }
+        self
+    }
+
+    /// Record accelerators from a [`FullSystemState`] (no UUIDs)
+    pub fn record_accelerators(&mut self, accelerators: &[AcceleratorState]) -> &mut Self {
+        for accelerator in accelerators {
+            let id = format!(
+                "{}{}",
+                accelerator.accel_type.to_ascii_lowercase(),
+                accelerator.index
+            );
+            let attrs = || {
+                vec![
+                    attr("hw.id", id.as_str()),
+                    attr("hw.name", accelerator.name.as_str()),
+                    attr("hw.vendor", accelerator.vendor.as_str()),
+                    attr("hw.type", "gpu"),
+                ]
+            };
+            let mut utilization_attrs = attrs();
+            utilization_attrs.push(attr("hw.gpu.task", "general"));
+            self.gauge(
+                "hw.gpu.utilization",
+                "GPU utilization",
+                "1",
+                accelerator.utilization as f64 / 100.0,
+                utilization_attrs,
+            );
+            self.gpu_memory(
+                accelerator.memory_used_bytes,
+                accelerator.memory_total_bytes,
+                attrs(),
+            );
+            if let Some(watts) = accelerator.power_watts {
+                self.gauge("hw.power", "Power draw", "W", watts as f64, attrs());
+            }
+            if let Some(temperature) = accelerator.temperature {
+                self.temperature(&format!("{}.temperature", id), &id, temperature as f64);
+            }
+        }
+        self
+    }
+
+    fn gpu_memory(&mut self, used: u64, total: u64, attrs: Attributes) {
+        let start = self.start_time_unix_nano;
+        self.sum(
+            "hw.gpu.memory.usage",
+            "GPU memory in use",
+            "By",
+            false,
+            start,
+            used as i64,
+            attrs.clone(),
+        );
+        if total > 0 {
+            self.sum(
+                "hw.gpu.memory.limit",
+                "GPU memory size",
+                "By",
+                false,
+                start,
+                total as i64,
+                attrs.clone(),
+            );
+            self.gauge(
+                "hw.gpu.memory.utilization",
+                "GPU memory utilization",
+                "1",
+                used as f64 / total as f64,
+                attrs,
+            );
+        }
+    }
+
+    fn temperature(&mut self, id: &str, parent: &str, celsius: f64) {
+        self.gauge(
+            "hw.temperature",
+            "Temperature",
+            "Cel",
+            celsius,
+            vec![
+                attr("hw.id", id),
+                attr("hw.type", "temperature"),
+                attr("hw.parent", parent),
+            ],
+        );
+    }
+
+    /// Record hwmon sensors (temperatures, fans, voltages, power, energy)
+    pub fn record_sensors(&mut self, sensors: &[HwSensor]) -> &mut Self {
+        let start = self.start_time_unix_nano;
+        for sensor in sensors {
+            let (name, description, unit, hw_type) = match sensor.sensor_type {
+                HwSensorType::Temperature => {
+                    ("hw.temperature", "Temperature", "Cel", "temperature")
+                }
+                HwSensorType::Fan => ("hw.fan.speed", "Fan speed", "rpm", "fan"),
+                HwSensorType::Voltage => ("hw.voltage", "Voltage", "V", "voltage"),
+                HwSensorType::Power => ("hw.power", "Power draw", "W", "power"),
+                HwSensorType::Energy => ("hw.energy", "Energy consumed", "J", "energy"),
+                _ => continue,
+            };
+            let parent = hardware_name(sensor.hardware_type);
+            let attrs = vec![
+                attr("hw.id", format!("{}.{}", parent, slug(&sensor.name))),
+                attr("hw.name", sensor.name.as_str()),
+                attr("hw.type", hw_type),
+                attr("hw.parent", parent),
+            ];
+            if sensor.sensor_type == HwSensorType::Energy {
+                let point =
+                    DataPoint::double(sensor.value as f64, attrs).at(start, self.time_unix_nano);
+                self.push(Metric::sum(name, description, unit, true), point);
+            } else {
+                self.gauge(name, description, unit, sensor.value as f64, attrs);
+            }
+        }
+        self
+    }
+
+    /// Record per-process GPU memory and utilization
+    ///
+    /// `gpus` maps device indices to `hw.id`s; indices without an entry use
+    /// `gpu<index>`.
+    pub fn record_processes(
+        &mut self,
+        processes: &[ProcessMonitorInfo],
+        gpus: &[GpuInfo],
+    ) -> &mut Self {
+        let start = self.start_time_unix_nano;
+        let device_id = |index: usize| {
+            gpus.iter()
+                .find(|gpu| gpu.static_info.index == index)
+                .map(gpu_id)
+                .unwrap_or_else(|| format!("gpu{}", index))
+        };
+        for process in processes {
+            let attrs = |extra: Vec<(String, AttributeValue)>| {
+                let mut attrs = vec![
+                    attr("process.pid", process.pid as i64),
+                    attr("process.executable.name", process.name.as_str()),
+                ];
+                attrs.extend(extra);
+                attrs
+            };
+            let mut devices: Vec<_> = process.gpu_memory_per_device.iter().collect();
+            devices.sort();
+            for (&index, &bytes) in devices {
+                self.sum(
+                    "process.gpu.memory.usage",
+                    "GPU memory used by the process",
+                    "By",
+                    false,
+                    start,
+                    bytes as i64,
+                    attrs(vec![attr("hw.id", device_id(index))]),
+                );
+            }
+            if let Some(usage) = process.gpu_usage_percent {
+                self.gauge(
+                    "process.gpu.utilization",
+                    "GPU utilization by the process",
+                    "1",
+                    usage as f64 / 100.0,
+                    attrs(Vec::new()),
+                );
+            }
+        }
+        self
+    }
+
+    /// Record the overall health score and each check's score
+    pub fn record_health(&mut self, health: &SystemHealth) -> &mut Self {
+        self.gauge(
+            "simon.health.score",
+            "Overall health score",
+            "1",
+            health.score as f64 / 100.0,
+            vec![attr("simon.health.status", status_name(health.status))],
+        );
+        for check in &health.checks {
+            self.gauge(
+                "simon.health.check.score",
+                "Health check score",
+                "1",
+                check.score as f64 / 100.0,
+                vec![
+                    attr("simon.health.check.name", check.name.as_str()),
+                    attr("simon.health.check.category", check.category.as_str()),
+                    attr("simon.health.status", status_name(check.status)),
+                ],
+            );
+        }
+        self
+    }
+
+    /// Record a daemon [`Snapshot`]: state, GPUs and GPU processes
+    pub fn record_snapshot(&mut self, snapshot: &Snapshot) -> &mut Self {
+        self.record_state(&snapshot.state);
+        if snapshot.gpus.is_empty() {
+            self.record_accelerators(&snapshot.state.accelerators);
+        } else {
+            self.record_gpus(&snapshot.gpus);
+        }
+        self.record_processes(&snapshot.processes, &snapshot.gpus)
+    }
+}
+
+fn attr(key: &str, value: impl Into<AttributeValue>) -> (String, AttributeValue) {
+    (key.to_string(), value.into())
+}
+
+/// `hw.id` of a GPU: its UUID, else `gpu<index>`
+fn gpu_id(gpu: &GpuInfo) -> String {
+    gpu.static_info
+        .uuid
+        .clone()
+        .filter(|uuid| !uuid.is_empty())
+        .unwrap_or_else(|| format!("gpu{}", gpu.static_info.index))
+}
+
+fn hardware_name(hw_type: HwType) -> &'static str {
+    match hw_type {
+        HwType::Cpu => "cpu",
+        HwType::Gpu => "gpu",
+        HwType::Motherboard => "motherboard",
+        HwType::Storage => "storage",
+        HwType::Memory => "memory",
+        HwType::Network => "network",
+        HwType::Psu => "psu",
+        HwType::Other => "other",
+    }
+}
+
+fn status_name(status: HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Healthy => "healthy",
+        HealthStatus::Good => "good",
+        HealthStatus::Warning => "warning",
+        HealthStatus::Critical => "critical",
+        HealthStatus::Unknown => "unknown",
+    }
+}
+
+/// Lowercase identifier with runs of other characters as `_`
+fn slug(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    slug.trim_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{CpuState, MemoryState, NetworkState, SystemInfoState};
+
+    fn state() -> FullSystemState {
+        let mut state = FullSystemState::empty();
+        state.cpu = Some(CpuState {
+            name: "Test CPU".into(),
+            cores: 2,
+            threads: 2,
+            utilization: 50.0,
+            temperature: Some(60.0),
+            frequency_mhz: Some(3000),
+            per_core_usage: vec![40.0, 60.0],
+        });
+        state.memory = Some(MemoryState {
+            total_bytes: 1000,
+            used_bytes: 250,
+            available_bytes: 750,
+            usage_percent: 25.0,
+            swap_total_bytes: 0,
+            swap_used_bytes: 0,
+            swap_usage_percent: 0.0,
+        });
+        state.network = vec![NetworkState {
+            name: "eth0".into(),
+            is_up: true,
+            rx_bytes: 10,
+            tx_bytes: 20,
+            rx_rate: 0.0,
+            tx_rate: 0.0,
+        }];
+        state.system = Some(SystemInfoState {
+            hostname: "node01".into(),
+            os: "Linux".into(),
+            kernel: "6.1".into(),
+            uptime_secs: 100,
+        });
+        state
+    }
+
+    fn find<'a>(batch: &'a MetricBatch, name: &str) -> &'a Metric {
+        batch
+            .metrics()
+            .iter()
+            .find(|m| m.name == name)
+            .unwrap_or_else(|| panic!("missing {}", name))
+    }
+
+    #[test]
+    fn test_record_state() {
+        let time = 1_000 * 1_000_000_000;
+        let mut batch = MetricBatch::new(7).with_time(time);
+        batch.record_state(&state());
+
+        let cpu = find(&batch, "system.cpu.utilization");
+        assert_eq!(cpu.kind, MetricKind::Gauge);
+        assert_eq!(cpu.unit, "1");
+        assert_eq!(cpu.points.len(), 3);
+        assert_eq!(cpu.points[0].value, NumberValue::Double(0.5));
+        assert_eq!(
+            cpu.points[2].attributes,
+            vec![attr("cpu.logical_number", 1i64)]
+        );
+        assert_eq!(
+            find(&batch, "system.cpu.frequency").points[0].value,
+            NumberValue::Double(3e9)
+        );
+
+        let memory = find(&batch, "system.memory.usage");
+        assert_eq!(memory.kind, MetricKind::Sum { monotonic: false });
+        assert_eq!(memory.points[1].value, NumberValue::Int(750));
+        assert_eq!(memory.points[1].start_time_unix_nano, 7);
+        assert!(batch
+            .metrics()
+            .iter()
+            .all(|m| m.name != "system.paging.usage"));
+
+        // Network counters start at boot
+        let network = find(&batch, "system.network.io");
+        assert_eq!(network.kind, MetricKind::Sum { monotonic: true });
+        assert_eq!(network.points.len(), 2);
+        assert_eq!(network.points[0].start_time_unix_nano, 900 * 1_000_000_000);
+
+        let temperature = find(&batch, "hw.temperature");
+        assert_eq!(temperature.unit, "Cel");
+        assert!(temperature.points[0]
+            .attributes
+            .contains(&attr("hw.parent", "cpu")));
+    }
+
+    #[test]
+    fn test_record_sensors_and_health() {
+        let sensors = vec![
+            HwSensor {
+                name: "Package id 0".into(),
+                value: 55.0,
+                min: None,
+                max: None,
+                sensor_type: HwSensorType::Temperature,
+                hardware_type: HwType::Cpu,
+            },
+            HwSensor {
+                name: "energy1".into(),
+                value: 1234.5,
+                min: None,
+                max: None,
+                sensor_type: HwSensorType::Energy,
+                hardware_type: HwType::Cpu,
+            },
+            HwSensor {
+                name: "load".into(),
+                value: 1.0,
+                min: None,
+                max: None,
+                sensor_type: HwSensorType::Load,
+                hardware_type: HwType::Cpu,
+            },
+        ];
+        let health = SystemHealth {
+            status: HealthStatus::Good,
+            score: 80,
+            checks: Vec::new(),
+            healthy_count: 0,
+            warning_count: 0,
+            critical_count: 0,
+            timestamp: SystemTime::now(),
+        };
+
+        let mut batch = MetricBatch::new(0);
+        batch.record_sensors(&sensors).record_health(&health);
+
+        assert_eq!(batch.point_count(), 3);
+        let temperature = find(&batch, "hw.temperature");
+        assert!(temperature.points[0]
+            .attributes
+            .contains(&attr("hw.id", "cpu.package_id_0")));
+        assert_eq!(
+            find(&batch, "hw.energy").kind,
+            MetricKind::Sum { monotonic: true }
+        );
+        let score = find(&batch, "simon.health.score");
+        assert_eq!(score.points[0].value, NumberValue::Double(0.8));
+        assert!(score.points[0]
+            .attributes
+            .contains(&attr("simon.health.status", "good")));
+    }
+
+    #[test]
+    fn test_config_endpoint() {
+        let config = OtlpConfig::default().with_endpoint("http://collector:4318/");
+        assert_eq!(config.endpoint, "http://collector:4318/v1/metrics");
+        let config = config.with_endpoint("http://gateway/otlp/v1/metrics");
+        assert_eq!(config.endpoint, "http://gateway/otlp/v1/metrics");
+
+        assert_eq!(
+            "http/json".parse::<OtlpProtocol>().unwrap(),
+            OtlpProtocol::Json
+        );
+        assert!("grpc".parse::<OtlpProtocol>().is_err());
+        assert_eq!(
+            parse_key_values("deployment.environment=prod, team=ml%20infra"),
+            vec![
+                ("deployment.environment".to_string(), "prod".to_string()),
+                ("team".to_string(), "ml infra".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,689 @@
+//! PCIe link health and AER error monitoring
+//!
+//! Reads every PCI function under `/sys/bus/pci/devices` and compares the
+//! negotiated link (`current_link_speed`/`current_link_width`) with what
+//! the device and its upstream port can both do. A GPU trained at x8 or an
+//! NVMe drive stuck at Gen3 usually means a bad riser, a dirty connector or
+//! a misconfigured BIOS bifurcation, and shows up as nothing more than
+//! "the job is slow".
+//!
+//! Advanced Error Reporting counters (`aer_dev_correctable`,
+//! `aer_dev_nonfatal`, `aer_dev_fatal`) are read per device, with rates
+//! over the refreshes of the last hour. A steady stream of correctable errors (bad TLPs,
+//! receiver errors) is the usual sign of marginal signal integrity.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::pcie::PcieMonitor;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = PcieMonitor::new();
+//!
+//! for device in monitor.devices()? {
+//!     if let Some(link) = &device.link {
+//!         if link.is_degraded() {
+//!             println!("{} {}: {}", device.address, device.class_name(), link);
+//!         }
+//!     }
+//! }
+//!
+//! // AER error rates over the refreshes of the last hour
+//! monitor.refresh()?;
+//! std::thread::sleep(Duration::from_secs(60));
+//! for rate in monitor.refresh()? {
+//!     if rate.correctable_window > 0 {
+//!         println!("{}: {:.1} correctable/hour", rate.address, rate.correctable_per_hour);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::utils::{RateWindow, DEFAULT_RATE_WINDOW};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default sysfs mount point
+const SYSFS_PATH: &str = "/sys";
+
+/// PCI class code for PCI-to-PCI bridges (root and switch ports)
+const CLASS_PCI_BRIDGE: u32 = 0x0604;
+
+/// Negotiated and maximum PCIe link parameters of one device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PcieLink {
+    /// Current link speed (GT/s)
+    pub current_speed: Option<f64>,
+    /// Current link width (lanes); 0 when the link is down
+    pub current_width: Option<u8>,
+    /// Maximum speed the device supports (GT/s)
+    pub max_speed: Option<f64>,
+    /// Maximum width the device supports (lanes)
+    pub max_width: Option<u8>,
+    /// Maximum speed of the upstream port (GT/s)
+    pub upstream_max_speed: Option<f64>,
+    /// Maximum width of the upstream port (lanes)
+    pub upstream_max_width: Option<u8>,
+}
+
+impl PcieLink {
+    /// Best speed both ends of the link support (GT/s)
+    pub fn capable_speed(&self) -> Option<f64> {
+        min_known(self.max_speed, self.upstream_max_speed)
+    }
+
+    /// Best width both ends of the link support (lanes)
+    pub fn capable_width(&self) -> Option<u8> {
+        min_known(self.max_width, self.upstream_max_width)
+    }
+
+    /// Current PCIe generation
+    pub fn current_gen(&self) -> Option<u32> {
+        self.current_speed.and_then(pcie_gen)
+    }
+
+    /// Maximum PCIe generation of the device
+    pub fn max_gen(&self) -> Option<u32> {
+        self.max_speed.and_then(pcie_gen)
+    }
+
+    /// Whether the link trained below the capable speed
+    pub fn is_speed_degraded(&self) -> bool {
+        matches!((self.current_speed, self.capable_speed()), (Some(cur), Some(cap)) if cur + 0.01 < cap)
+    }
+
+    /// Whether the link trained below the capable width
+    pub fn is_width_degraded(&self) -> bool {
+        matches!((self.current_width, self.capable_width()), (Some(cur), Some(cap)) if cur < cap)
+    }
+
+    /// Whether the link is down (width 0)
+    pub fn is_down(&self) -> bool {
+        self.current_width == Some(0)
+    }
+
+    /// Whether the link runs below what both ends support
+    pub fn is_degraded(&self) -> bool {
+        self.is_speed_degraded() || self.is_width_degraded()
+    }
+
+    /// Whether the upstream port, not the device, caps the link
+    pub fn is_slot_limited(&self) -> bool {
+        let speed =
+            matches!((self.max_speed, self.upstream_max_speed), (Some(dev), Some(up)) if up < dev);
+        let width =
+            matches!((self.max_width, self.upstream_max_width), (Some(dev), Some(up)) if up < dev);
+        speed || width
+    }
+}
+
+impl fmt::Display for PcieLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let link = |speed: Option<f64>, width: Option<u8>| {
+            let gen = speed
+                .and_then(pcie_gen)
+                .map_or("Gen?".to_string(), |g| format!("Gen{}", g));
+            let width = width.map_or("x?".to_string(), |w| format!("x{}", w));
+            format!("{} {}", gen, width)
+        };
+        write!(f, "{}", link(self.current_speed, self.current_width))?;
+        if self.is_degraded() {
+            write!(
+                f,
+                " (capable {})",
+                link(self.capable_speed(), self.capable_width())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One AER counter file: per-error-type counts and the total
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AerCounter {
+    /// Total errors (`TOTAL_ERR_*`)
+    pub total: u64,
+    /// Count per error type (`RxErr`, `BadTLP`, `CmpltTO`, ...)
+    pub by_type: BTreeMap<String, u64>,
+}
+
+impl AerCounter {
+    /// Parse an `aer_dev_*` file
+    ///
+    /// ```text
+    /// RxErr 2
+    /// BadTLP 5
+    /// TOTAL_ERR_COR 7
+    /// ```
+    pub fn parse(content: &str) -> Self {
+        let mut counter = AerCounter::default();
+        for line in content.lines() {
+            let Some((name, value)) = line.split_once(char::is_whitespace) else {
+                continue;
+            };
+            let Ok(value) = value.trim().parse::<u64>() else {
+                continue;
+            };
+            if name.starts_with("TOTAL_ERR") {
+                counter.total = value;
+            } else {
+                counter.by_type.insert(name.to_string(), value);
+            }
+        }
+        if counter.total == 0 {
+            counter.total = counter.by_type.values().sum();
+        }
+        counter
+    }
+
+    /// Error types with a non-zero count, highest first
+    pub fn nonzero(&self) -> Vec<(&str, u64)> {
+        let mut types: Vec<_> = self
+            .by_type
+            .iter()
+            .filter(|(_, &n)| n > 0)
+            .map(|(name, &n)| (name.as_str(), n))
+            .collect();
+        types.sort_by_key(|t| std::cmp::Reverse(t.1));
+        types
+    }
+}
+
+/// AER counters of one device since boot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AerCounters {
+    /// Correctable errors
+    pub correctable: AerCounter,
+    /// Uncorrectable non-fatal errors
+    pub nonfatal: AerCounter,
+    /// Uncorrectable fatal errors
+    pub fatal: AerCounter,
+}
+
+impl AerCounters {
+    /// Whether any error was recorded
+    pub fn has_errors(&self) -> bool {
+        self.correctable.total + self.nonfatal.total + self.fatal.total > 0
+    }
+}
+
+/// A PCI function with its link state and AER counters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PcieDevice {
+    /// PCI address (`0000:01:00.0`)
+    pub address: String,
+    /// PCI class code (24-bit)
+    pub class: u32,
+    /// PCI vendor ID
+    pub vendor_id: u16,
+    /// PCI device ID
+    pub device_id: u16,
+    /// Bound kernel driver
+    pub driver: Option<String>,
+    /// Physical slot name, when the platform reports one
+    pub slot: Option<String>,
+    /// Upstream bridge address
+    pub parent: Option<String>,
+    /// Link state; `None` for devices without a PCIe link (integrated, VFs)
+    pub link: Option<PcieLink>,
+    /// AER counters; `None` when AER is not enabled for the device
+    pub aer: Option<AerCounters>,
+}
+
+impl PcieDevice {
+    /// Whether the function is a root or switch port
+    pub fn is_bridge(&self) -> bool {
+        self.class >> 8 == CLASS_PCI_BRIDGE
+    }
+
+    /// Whether the function is a GPU or accelerator
+    pub fn is_gpu(&self) -> bool {
+        matches!(self.class >> 16, 0x03 | 0x12)
+    }
+
+    /// Short class name (`Display`, `Network`, `Storage`, ...)
+    pub fn class_name(&self) -> &'static str {
+        class_name(self.class)
+    }
+
+    /// Address without the function number (`0000:01:00`)
+    ///
+    /// Functions of a multi-function device share one link.
+    pub fn slot_address(&self) -> &str {
+        self.address
+            .rsplit_once('.')
+            .map_or(&self.address, |(slot, _)| slot)
+    }
+}
+
+/// Recent AER errors of one device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AerRate {
+    /// PCI address
+    pub address: String,
+    /// New correctable errors since the previous refresh
+    pub correctable_delta: u64,
+    /// New non-fatal errors since the previous refresh
+    pub nonfatal_delta: u64,
+    /// New fatal errors since the previous refresh
+    pub fatal_delta: u64,
+    /// Correctable errors within the rate window
+    pub correctable_window: u64,
+    /// Non-fatal errors within the rate window
+    pub nonfatal_window: u64,
+    /// Fatal errors within the rate window
+    pub fatal_window: u64,
+    /// Correctable errors per hour over the rate window
+    pub correctable_per_hour: f64,
+    /// Time since the previous refresh
+    pub interval: Duration,
+    /// Time the rate window covers so far
+    pub window: Duration,
+}
+
+/// PCIe link and AER monitor
+pub struct PcieMonitor {
+    /// sysfs root
+    root: PathBuf,
+    /// (correctable, non-fatal, fatal) deltas of recent refreshes
+    rates: RateWindow<String, 3>,
+}
+
+impl Default for PcieMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PcieMonitor {
+    /// Create a monitor for the system sysfs tree
+    pub fn new() -> Self {
+        Self::with_sysfs_root(SYSFS_PATH)
+    }
+
+    /// Create a monitor reading a different sysfs root
+    pub fn with_sysfs_root<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            rates: RateWindow::new(DEFAULT_RATE_WINDOW),
+        }
+    }
+
+    /// Compute rates over a different window (default one hour)
+    pub fn with_rate_window(mut self, window: Duration) -> Self {
+        self.rates = RateWindow::new(window);
+        self
+    }
+
+    /// Read all PCI functions, sorted by address
+    pub fn devices(&self) -> Result<Vec<PcieDevice>> {
+        let entries = match fs::read_dir(self.root.join("bus/pci/devices")) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let slots = self.slots();
+
+        let mut devices: Vec<PcieDevice> = entries
+            .flatten()
+            .map(|entry| read_device(&entry.path(), &slots))
+            .collect();
+        devices.sort_by(|a, b| a.address.cmp(&b.address));
+        Ok(devices)
+    }
+
+    /// Endpoints whose link runs below what both ends support
+    ///
+    /// Bridges are left out since a degraded switch shows up on the
+    /// devices below it, and only function 0 of multi-function devices
+    /// is reported.
+    pub fn degraded_links(&self) -> Result<Vec<PcieDevice>> {
+        Ok(endpoint_links(self.devices()?)
+            .into_iter()
+            .filter(|d| d.link.as_ref().is_some_and(PcieLink::is_degraded))
+            .collect())
+    }
+
+    /// Read AER counters and return per-device errors and rates
+    ///
+    /// Rates are taken over the refreshes within the rate window, and
+    /// divided by at least 15 minutes so that one error shortly after the
+    /// first refresh does not extrapolate to a high rate. The first call
+    /// records a baseline and returns an empty list.
+    pub fn refresh(&mut self) -> Result<Vec<AerRate>> {
+        let totals: HashMap<String, [u64; 3]> = self
+            .devices()?
+            .into_iter()
+            .filter_map(|d| {
+                let aer = d.aer?;
+                Some((
+                    d.address,
+                    [aer.correctable.total, aer.nonfatal.total, aer.fatal.total],
+                ))
+            })
+            .collect();
+
+        let Some(counts) = self.rates.update(Instant::now(), totals) else {
+            return Ok(Vec::new());
+        };
+        let mut rates: Vec<AerRate> = counts
+            .into_iter()
+            .map(|(address, counts)| AerRate {
+                address,
+                correctable_delta: counts.delta[0],
+                nonfatal_delta: counts.delta[1],
+                fatal_delta: counts.delta[2],
+                correctable_window: counts.in_window[0],
+                nonfatal_window: counts.in_window[1],
+                fatal_window: counts.in_window[2],
+                correctable_per_hour: counts.per_hour(0),
+                interval: counts.interval,
+                window: counts.span,
+            })
+            .collect();
+        rates.sort_by(|a, b| a.address.cmp(&b.address));
+        Ok(rates)
+    }
+
+    /// Physical slot names keyed by `dddd:bb:dd`
+    fn slots(&self) -> HashMap<String, String> {
+        let Ok(entries) = fs::read_dir(self.root.join("bus/pci/slots")) else {
+            return HashMap::new();
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let address = read_string(&entry.path().join("address"))?;
+                Some((address, entry.file_name().to_string_lossy().to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Read all PCI functions from `/sys`
+pub fn pcie_devices() -> Result<Vec<PcieDevice>> {
+    PcieMonitor::new().devices()
+}
+
+/// Endpoints with a link, one per multi-function device
+pub fn endpoint_links(devices: Vec<PcieDevice>) -> Vec<PcieDevice> {
+    let mut seen = std::collections::HashSet::new();
+    devices
+        .into_iter()
+        .filter(|d| !d.is_bridge() && d.link.is_some())
+        .filter(|d| seen.insert(d.slot_address().to_string()))
+        .collect()
+}
+
+/// Parse a sysfs link speed (`16.0 GT/s PCIe`, `8 GT/s`) into GT/s
+pub fn parse_link_speed(s: &str) -> Option<f64> {
+    let value: f64 = s.split_whitespace().next()?.parse().ok()?;
+    (value > 0.0).then_some(value)
+}
+
+/// PCIe generation for a link speed in GT/s
+pub fn pcie_gen(speed_gts: f64) -> Option<u32> {
+    const SPEEDS: [(f64, u32); 6] = [
+        (2.5, 1),
+        (5.0, 2),
+        (8.0, 3),
+        (16.0, 4),
+        (32.0, 5),
+        (64.0, 6),
+    ];
+    SPEEDS
+        .iter()
+        .find(|(gts, _)| (speed_gts - gts).abs() < 0.1)
+        .map(|&(_, gen)| gen)
+}
+
+/// Short name for a PCI class code
+pub fn class_name(class: u32) -> &'static str {
+    match (class >> 16, (class >> 8) & 0xff) {
+        (0x01, _) => "Storage",
+        (0x02, _) => "Network",
+        (0x03, _) => "Display",
+        (0x04, 0x03) => "Audio",
+        (0x04, _) => "Multimedia",
+        (0x05, _) => "Memory",
+        (0x06, _) => "Bridge",
+        (0x07, _) => "Communication",
+        (0x08, _) => "System",
+        (0x0c, 0x03) => "USB",
+        (0x0c, _) => "Serial Bus",
+        (0x0d, _) => "Wireless",
+        (0x10, _) => "Encryption",
+        (0x11, _) => "Signal Processing",
+        (0x12, _) => "Accelerator",
+        _ => "Other",
+    }
+}
+
+fn read_device(path: &Path, slots: &HashMap<String, String>) -> PcieDevice {
+    let address = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let parent = fs::canonicalize(path).ok().and_then(|p| {
+        let name = p.parent()?.file_name()?.to_string_lossy().to_string();
+        // Root complex directories are named pciDDDD:BB
+        (!name.starts_with("pci")).then_some(name)
+    });
+
+    let mut link = read_link(path);
+    if let (Some(link), Some(parent)) = (link.as_mut(), parent.as_ref()) {
+        let parent_path = path.with_file_name(parent);
+        link.upstream_max_speed =
+            read_string(&parent_path.join("max_link_speed")).and_then(|s| parse_link_speed(&s));
+        link.upstream_max_width =
+            read_string(&parent_path.join("max_link_width")).and_then(|s| s.parse().ok());
+    }
+
+    let aer = fs::read_to_string(path.join("aer_dev_correctable"))
+        .ok()
+        .map(|correctable| AerCounters {
+            correctable: AerCounter::parse(&correctable),
+            nonfatal: AerCounter::parse(
+                &fs::read_to_string(path.join("aer_dev_nonfatal")).unwrap_or_default(),
+            ),
+            fatal: AerCounter::parse(
+                &fs::read_to_string(path.join("aer_dev_fatal")).unwrap_or_default(),
+            ),
+        });
+
+    PcieDevice {
+        class: read_hex(&path.join("class")).unwrap_or(0) as u32,
+        vendor_id: read_hex(&path.join("vendor")).unwrap_or(0) as u16,
+        device_id: read_hex(&path.join("device")).unwrap_or(0) as u16,
+        driver: fs::read_link(path.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())),
+        slot: address
+            .rsplit_once('.')
+            .and_then(|(slot, _)| slots.get(slot).cloned()),
+        parent,
+        link,
+        aer,
+        address,
+    }
+}
+
+fn read_link(path: &Path) -> Option<PcieLink> {
+    let current_speed = read_string(&path.join("current_link_speed"));
+    let max_speed = read_string(&path.join("max_link_speed"));
+    if current_speed.is_none() && max_speed.is_none() {
+        return None;
+    }
+    Some(PcieLink {
+        current_speed: current_speed.and_then(|s| parse_link_speed(&s)),
+        current_width: read_string(&path.join("current_link_width")).and_then(|s| s.parse().ok()),
+        max_speed: max_speed.and_then(|s| parse_link_speed(&s)),
+        max_width: read_string(&path.join("max_link_width")).and_then(|s| s.parse().ok()),
+        upstream_max_speed: None,
+        upstream_max_width: None,
+    })
+}
+
+fn min_known<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b < a { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn read_hex(path: &Path) -> Option<u64> {
+    u64::from_str_radix(read_string(path)?.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    fn write_link(dir: &Path, cur: (&str, &str), max: (&str, &str)) {
+        fs::write(dir.join("current_link_speed"), format!("{}\n", cur.0)).unwrap();
+        fs::write(dir.join("current_link_width"), format!("{}\n", cur.1)).unwrap();
+        fs::write(dir.join("max_link_speed"), format!("{}\n", max.0)).unwrap();
+        fs::write(dir.join("max_link_width"), format!("{}\n", max.1)).unwrap();
+    }
+
+    /// Root port 00:01.0 (Gen4 x16) with a GPU trained at Gen4 x8 and its
+    /// audio function; root port 00:02.0 (Gen3 x4) with a Gen4 NVMe drive
+    fn fake_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("bus/pci/devices")).unwrap();
+        fs::create_dir_all(root.join("bus/pci/slots/3")).unwrap();
+        fs::write(root.join("bus/pci/slots/3/address"), "0000:01:00\n").unwrap();
+
+        let add = |path: &str, class: &str| {
+            let dir = root.join("devices").join(path);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("class"), format!("{}\n", class)).unwrap();
+            fs::write(dir.join("vendor"), "0x10de\n").unwrap();
+            fs::write(dir.join("device"), "0x2330\n").unwrap();
+            let address = path.rsplit('/').next().unwrap();
+            symlink(&dir, root.join("bus/pci/devices").join(address)).unwrap();
+            dir
+        };
+
+        let port0 = add("pci0000:00/0000:00:01.0", "0x060400");
+        write_link(&port0, ("16.0 GT/s PCIe", "16"), ("16.0 GT/s PCIe", "16"));
+        let gpu = add("pci0000:00/0000:00:01.0/0000:01:00.0", "0x030200");
+        write_link(&gpu, ("16.0 GT/s PCIe", "8"), ("16.0 GT/s PCIe", "16"));
+        fs::write(
+            gpu.join("aer_dev_correctable"),
+            "RxErr 3\nBadTLP 40\nBadDLLP 2\nTOTAL_ERR_COR 45\n",
+        )
+        .unwrap();
+        fs::write(
+            gpu.join("aer_dev_nonfatal"),
+            "CmpltTO 0\nTOTAL_ERR_NONFATAL 0\n",
+        )
+        .unwrap();
+        fs::write(gpu.join("aer_dev_fatal"), "TOTAL_ERR_FATAL 0\n").unwrap();
+        let audio = add("pci0000:00/0000:00:01.0/0000:01:00.1", "0x040300");
+        write_link(&audio, ("16.0 GT/s PCIe", "8"), ("16.0 GT/s PCIe", "16"));
+
+        let port1 = add("pci0000:00/0000:00:02.0", "0x060400");
+        write_link(&port1, ("8.0 GT/s PCIe", "4"), ("8.0 GT/s PCIe", "4"));
+        let nvme = add("pci0000:00/0000:00:02.0/0000:02:00.0", "0x010802");
+        write_link(&nvme, ("8.0 GT/s PCIe", "4"), ("16.0 GT/s PCIe", "4"));
+        dir
+    }
+
+    #[test]
+    fn test_devices() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let monitor = PcieMonitor::with_sysfs_root(root);
+        let devices = monitor.devices().unwrap();
+        assert_eq!(devices.len(), 5);
+
+        let gpu = devices
+            .iter()
+            .find(|d| d.address == "0000:01:00.0")
+            .unwrap();
+        assert_eq!(gpu.class_name(), "Display");
+        assert_eq!(gpu.parent.as_deref(), Some("0000:00:01.0"));
+        assert_eq!(gpu.slot.as_deref(), Some("3"));
+        let link = gpu.link.as_ref().unwrap();
+        assert_eq!(link.current_gen(), Some(4));
+        assert!(link.is_width_degraded());
+        assert!(!link.is_speed_degraded());
+        assert_eq!(link.to_string(), "Gen4 x8 (capable Gen4 x16)");
+        let aer = gpu.aer.as_ref().unwrap();
+        assert_eq!(aer.correctable.total, 45);
+        assert_eq!(aer.correctable.nonzero()[0], ("BadTLP", 40));
+        assert!(aer.has_errors());
+
+        // Gen4 drive in a Gen3 slot is limited, not degraded
+        let nvme = devices
+            .iter()
+            .find(|d| d.address == "0000:02:00.0")
+            .unwrap();
+        let link = nvme.link.as_ref().unwrap();
+        assert!(!link.is_degraded());
+        assert!(link.is_slot_limited());
+        assert!(nvme.aer.is_none());
+
+        // Only the GPU's function 0 is reported
+        let degraded = monitor.degraded_links().unwrap();
+        assert_eq!(degraded.len(), 1);
+        assert_eq!(degraded[0].address, "0000:01:00.0");
+    }
+
+    #[test]
+    fn test_aer_rates() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let mut monitor = PcieMonitor::with_sysfs_root(root);
+        assert!(monitor.refresh().unwrap().is_empty());
+
+        let gpu = root.join("devices/pci0000:00/0000:00:01.0/0000:01:00.0");
+        fs::write(
+            gpu.join("aer_dev_correctable"),
+            "BadTLP 50\nTOTAL_ERR_COR 55\n",
+        )
+        .unwrap();
+        let rates = monitor.refresh().unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].correctable_delta, 10);
+        assert_eq!(rates[0].fatal_delta, 0);
+        // Divided by the 15 minute minimum span, not the refresh interval
+        assert_eq!(rates[0].correctable_per_hour, 40.0);
+
+        let rates = monitor.refresh().unwrap();
+        assert_eq!(rates[0].correctable_delta, 0);
+        assert_eq!(rates[0].correctable_window, 10);
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(parse_link_speed("16.0 GT/s PCIe"), Some(16.0));
+        assert_eq!(parse_link_speed("2.5 GT/s"), Some(2.5));
+        assert_eq!(parse_link_speed("Unknown"), None);
+        assert_eq!(pcie_gen(32.0), Some(5));
+        assert_eq!(pcie_gen(7.0), None);
+
+        let counter = AerCounter::parse("RxErr 1\nBadTLP 2\n");
+        assert_eq!(counter.total, 3);
+        assert_eq!(class_name(0x010802), "Storage");
+        assert_eq!(class_name(0x0c0330), "USB");
+    }
+}
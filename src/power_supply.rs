@@ -358,7 +358,7 @@ impl PowerSupplyMonitor {
         }
 
         let entries = fs::read_dir(power_supply_path)
-            .map_err(|e| SimonError::Other(format!("Failed to read power_supply: {}", e)))?;
+            .map_err(|e| crate::error::SimonError::Other(format!("Failed to read power_supply: {}", e)))?;
 
         for entry in entries.flatten() {
             let name = entry.file_name().to_string_lossy().to_string();
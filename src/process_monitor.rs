@@ -171,6 +171,10 @@ pub struct ProcessMonitorInfo {
     pub state: char,
     /// Process priority/nice value
     pub priority: Option<i32>,
+    /// Parent process ID
+    pub parent_pid: Option<u32>,
+    /// Cumulative CPU time (user + system) in milliseconds
+    pub cpu_time_ms: Option<u64>,
 
     // nvtop feature parity: Per-process engine utilization
     /// GPU graphics engine time used (nanoseconds)
@@ -327,6 +331,20 @@ impl ProcessMonitor {
         self.gpu_collection.as_ref().map(|gc| gc.len()).unwrap_or(0)
     }
 
+    /// Get the GPU collection used for attribution, if any
+    pub fn gpus(&self) -> Option<&GpuCollection> {
+        self.gpu_collection.as_ref()
+    }
+
+    /// Get a process and all of its descendants
+    ///
+    /// The tree is rebuilt from parent PIDs on every call, so children spawned
+    /// since the last call are picked up. The root process comes first.
+    pub fn process_tree(&mut self, root_pid: u32) -> Result<Vec<ProcessMonitorInfo>> {
+        let procs = self.processes()?;
+        Ok(collect_tree(procs, root_pid))
+    }
+
     /// Update process list (refresh data)
     ///
     /// This method triggers a refresh of the internal process cache.
@@ -512,6 +530,32 @@ impl ProcessMonitor {
     }
 }
 
+/// Select `root_pid` and its descendants from a flat process list
+fn collect_tree(procs: Vec<ProcessMonitorInfo>, root_pid: u32) -> Vec<ProcessMonitorInfo> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for proc in &procs {
+        if let Some(ppid) = proc.parent_pid {
+            if ppid != proc.pid {
+                children.entry(ppid).or_default().push(proc.pid);
+            }
+        }
+    }
+
+    let mut by_pid: HashMap<u32, ProcessMonitorInfo> =
+        procs.into_iter().map(|p| (p.pid, p)).collect();
+    let mut tree = Vec::new();
+    let mut stack = vec![root_pid];
+    while let Some(pid) = stack.pop() {
+        if let Some(proc) = by_pid.remove(&pid) {
+            tree.push(proc);
+            if let Some(kids) = children.get(&pid) {
+                stack.extend(kids.iter().copied());
+            }
+        }
+    }
+    tree
+}
+
 impl Default for ProcessMonitor {
     fn default() -> Self {
         Self::new().unwrap_or_else(|_| Self {
@@ -595,6 +639,7 @@ mod linux {
 
         // Extract fields (0-indexed after splitting on ')')
         let state = stat_fields[0].chars().next().unwrap_or('?');
+        let parent_pid: Option<u32> = stat_fields[1].parse().ok();
         let utime: u64 = stat_fields[11].parse().unwrap_or(0);
         let stime: u64 = stat_fields[12].parse().unwrap_or(0);
         let priority: i32 = stat_fields[15].parse().unwrap_or(0);
@@ -635,6 +680,8 @@ mod linux {
             total_gpu_memory_bytes: 0,
             state,
             priority: Some(priority),
+            parent_pid,
+            cpu_time_ms: Some(((utime + stime) as f64 / clk_tck * 1000.0) as u64),
             gfx_engine_used: None,
             compute_engine_used: None,
             enc_engine_used: None,
@@ -805,7 +852,7 @@ mod windows_impl {
                         let mut kernel_time = Default::default();
                         let mut user_time = Default::default();
 
-                        let _cpu_time_ms = if GetProcessTimes(
+                        let cpu_time_ms = if GetProcessTimes(
                             handle,
                             &mut creation_time,
                             &mut exit_time,
@@ -835,6 +882,8 @@ mod windows_impl {
                             total_gpu_memory_bytes: 0,
                             state: 'R', // Windows doesn't expose state easily - assume running
                             priority: Some(entry.th32DefaultHeapID as i32), // Use heap ID as proxy for priority
+                            parent_pid: Some(entry.th32ParentProcessID),
+                            cpu_time_ms: Some(cpu_time_ms),
                             gfx_engine_used: None,
                             compute_engine_used: None,
                             enc_engine_used: None,
@@ -1054,6 +1103,8 @@ mod macos {
                     total_gpu_memory_bytes: 0,
                     state: status.chars().next().unwrap_or('U'), // First char of status
                     priority: Some(task_info.ptinfo.pti_priority),
+                    parent_pid: Some(task_info.pbsd.pbi_ppid),
+                    cpu_time_ms: Some(cpu_time_ms),
                     gfx_engine_used: None,
                     compute_engine_used: None,
                     enc_engine_used: None,
@@ -1070,3 +1121,45 @@ mod macos {
         Ok(processes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_tree_with_child() {
+        let mut parent = std::process::Command::new("sh")
+            .args(["-c", "sleep 5 & wait"])
+            .spawn()
+            .unwrap();
+        let root = parent.id();
+        let mut monitor = ProcessMonitor::without_gpu().unwrap();
+
+        // The shell forks its child asynchronously
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let tree = loop {
+            let tree = monitor.process_tree(root).unwrap();
+            if tree.len() >= 2 || Instant::now() > deadline {
+                break tree;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        for proc in tree.iter().rev() {
+            // SAFETY: kill only sends a signal to our own test processes
+            unsafe { libc::kill(proc.pid as libc::pid_t, libc::SIGKILL) };
+        }
+        let _ = parent.wait();
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].pid, root);
+        assert_eq!(tree[1].parent_pid, Some(root));
+        assert!(tree[1].name.contains("sleep"));
+    }
+
+    #[test]
+    fn test_collect_tree_unknown_root() {
+        assert!(collect_tree(Vec::new(), 1).is_empty());
+    }
+}
@@ -0,0 +1,782 @@
+//! Per-process network bandwidth (nethogs-style, Linux)
+//!
+//! Samples every TCP socket's `tcp_info` byte counters through
+//! [`sock_diag`](crate::sock_diag), attributes sockets to processes through
+//! their inode in `/proc/<pid>/fd`, and turns the deltas into per-connection,
+//! per-process and per-container throughput. Unlike packet capture this needs
+//! no privileges for the caller's own sockets; root sees everyone's.
+//!
+//! UDP has no per-socket byte counters, so UDP sockets are counted but carry
+//! no rate. Containers in their own network namespace are invisible to
+//! sock_diag from the host; for those the namespace's interface counters
+//! (`/proc/<pid>/net/dev`) give the container total, UDP included.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::process_network::ProcessNetMonitor;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = ProcessNetMonitor::new()?;
+//! monitor.refresh()?;
+//! std::thread::sleep(Duration::from_secs(1));
+//!
+//! let usage = monitor.refresh()?;
+//! for process in usage.processes.iter().take(10) {
+//!     println!(
+//!         "{:>7} {:<16} sent {:>10.0} B/s  recv {:>10.0} B/s",
+//!         process.pid, process.name, process.tx_bytes_per_sec, process.rx_bytes_per_sec
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::connections::ConnectionState;
+use crate::error::Result;
+use crate::sock_diag::{InetSocket, SockDiag, SocketProtocol};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Throughput of one connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionRate {
+    /// Owning process, if it could be found
+    pub pid: Option<u32>,
+    /// Transport protocol
+    pub protocol: SocketProtocol,
+    /// Local address
+    pub local: SocketAddr,
+    /// Remote address
+    pub remote: SocketAddr,
+    /// TCP state
+    pub state: ConnectionState,
+    /// Received bytes per second
+    pub rx_bytes_per_sec: f64,
+    /// Sent (acknowledged) bytes per second
+    pub tx_bytes_per_sec: f64,
+    /// Smoothed round trip time (ms)
+    pub rtt_ms: Option<f64>,
+    /// Retransmitted segments during the interval
+    pub retransmits: u32,
+    /// Congestion window (segments)
+    pub cwnd: Option<u32>,
+    /// Socket inode
+    pub inode: u64,
+}
+
+/// Network usage of one process
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessNetUsage {
+    /// Process ID
+    pub pid: u32,
+    /// Command name
+    pub name: String,
+    /// Short container ID (Docker, containerd, CRI-O, Podman)
+    pub container: Option<String>,
+    /// Received bytes per second
+    pub rx_bytes_per_sec: f64,
+    /// Sent bytes per second
+    pub tx_bytes_per_sec: f64,
+    /// Open TCP connections (listeners excluded)
+    pub tcp_connections: usize,
+    /// Open UDP sockets
+    pub udp_sockets: usize,
+    /// Retransmitted segments during the interval
+    pub retransmits: u64,
+}
+
+impl ProcessNetUsage {
+    /// Combined send and receive rate
+    pub fn total_bytes_per_sec(&self) -> f64 {
+        self.rx_bytes_per_sec + self.tx_bytes_per_sec
+    }
+}
+
+/// Network usage of one container
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContainerNetUsage {
+    /// Short container ID
+    pub container: String,
+    /// Processes seen in the container
+    pub pids: Vec<u32>,
+    /// Received bytes per second over attributed sockets
+    pub rx_bytes_per_sec: f64,
+    /// Sent bytes per second over attributed sockets
+    pub tx_bytes_per_sec: f64,
+    /// Received bytes per second on the container's own network namespace
+    pub netns_rx_bytes_per_sec: Option<f64>,
+    /// Sent bytes per second on the container's own network namespace
+    pub netns_tx_bytes_per_sec: Option<f64>,
+}
+
+/// One sample of network usage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetUsage {
+    /// Time since the previous sample
+    pub interval: Duration,
+    /// Processes with sockets, busiest first
+    pub processes: Vec<ProcessNetUsage>,
+    /// Connections, busiest first
+    pub connections: Vec<ConnectionRate>,
+    /// Containers, busiest first
+    pub containers: Vec<ContainerNetUsage>,
+    /// Received bytes per second on sockets without a known owner
+    pub unattributed_rx_bytes_per_sec: f64,
+    /// Sent bytes per second on sockets without a known owner
+    pub unattributed_tx_bytes_per_sec: f64,
+}
+
+impl NetUsage {
+    /// Usage of one process
+    pub fn process(&self, pid: u32) -> Option<&ProcessNetUsage> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+}
+
+/// Counters remembered for one socket
+#[derive(Debug, Clone, Copy)]
+struct SocketCounters {
+    rx: u64,
+    tx: u64,
+    retrans: u32,
+}
+
+/// Tracks per-socket byte counters between refreshes
+pub struct ProcessNetMonitor {
+    diag: SockDiag,
+    owners: SocketOwnerIndex,
+    /// Socket counters from the previous refresh, keyed by socket cookie
+    previous: Option<(Instant, HashMap<u64, SocketCounters>)>,
+    /// Interface totals per foreign network namespace, keyed by netns inode
+    previous_netns: HashMap<u64, (u64, u64)>,
+}
+
+impl ProcessNetMonitor {
+    /// Open the sock_diag socket
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            diag: SockDiag::new()?,
+            owners: SocketOwnerIndex::new(),
+            previous: None,
+            previous_netns: HashMap::new(),
+        })
+    }
+
+    /// Sample sockets and return usage since the last refresh
+    ///
+    /// The first call records a baseline and returns an empty sample.
+    /// Connections opened between two refreshes count in full, so short
+    /// transfers are not lost; bytes sent by sockets that closed in between
+    /// are.
+    pub fn refresh(&mut self) -> Result<NetUsage> {
+        let now = Instant::now();
+        let mut sockets = self.diag.inet_sockets(SocketProtocol::Tcp)?;
+        // UDP diag lives in a module that may not be loaded
+        sockets.extend(
+            self.diag
+                .inet_sockets(SocketProtocol::Udp)
+                .unwrap_or_default(),
+        );
+        let inodes: HashSet<u64> = sockets.iter().map(|s| s.inode).collect();
+        self.owners.retain(|inode| inodes.contains(&inode));
+        self.owners.resolve(inodes.iter().copied());
+        let owners = self.owners.owners();
+
+        let current: HashMap<u64, SocketCounters> = sockets
+            .iter()
+            .filter_map(|s| {
+                let info = s.tcp_info.as_ref()?;
+                Some((
+                    s.cookie,
+                    SocketCounters {
+                        rx: info.bytes_received,
+                        tx: info.bytes_acked,
+                        retrans: info.total_retrans,
+                    },
+                ))
+            })
+            .collect();
+
+        let mut usage = match &self.previous {
+            Some((then, previous)) => {
+                aggregate(&sockets, owners, previous, now.duration_since(*then))
+            }
+            None => NetUsage::default(),
+        };
+        let interval = usage.interval;
+        self.add_netns_rates(&mut usage, interval);
+        self.previous = Some((now, current));
+        Ok(usage)
+    }
+
+    /// Add namespace-level rates for containers with their own netns
+    ///
+    /// Sockets in those namespaces are invisible to sock_diag, so such
+    /// containers are added from their interface counters alone.
+    fn add_netns_rates(&mut self, usage: &mut NetUsage, interval: Duration) {
+        let Some(host_ns) = netns_inode(Path::new("/proc/self")) else {
+            return;
+        };
+        let namespaces = foreign_namespaces(Path::new("/proc"), host_ns);
+        let secs = interval.as_secs_f64();
+
+        for (ns, namespace) in &namespaces {
+            let Some(id) = &namespace.container else {
+                continue;
+            };
+            let Some(&(rx, tx)) = self.previous_netns.get(ns).filter(|_| secs > 0.0) else {
+                continue;
+            };
+            let index = match usage.containers.iter().position(|c| &c.container == id) {
+                Some(index) => index,
+                None => {
+                    usage.containers.push(ContainerNetUsage {
+                        container: id.clone(),
+                        pids: namespace.pids.clone(),
+                        ..Default::default()
+                    });
+                    usage.containers.len() - 1
+                }
+            };
+            let container = &mut usage.containers[index];
+            container.netns_rx_bytes_per_sec =
+                Some(namespace.totals.0.saturating_sub(rx) as f64 / secs);
+            container.netns_tx_bytes_per_sec =
+                Some(namespace.totals.1.saturating_sub(tx) as f64 / secs);
+        }
+
+        self.previous_netns = namespaces
+            .into_iter()
+            .map(|(ns, namespace)| (ns, namespace.totals))
+            .collect();
+    }
+}
+
+/// Build per-connection, per-process and per-container usage from one sample
+fn aggregate(
+    sockets: &[InetSocket],
+    owners: &HashMap<u64, u32>,
+    previous: &HashMap<u64, SocketCounters>,
+    interval: Duration,
+) -> NetUsage {
+    let secs = interval.as_secs_f64().max(0.001);
+    let mut usage = NetUsage {
+        interval,
+        ..Default::default()
+    };
+    let mut processes: HashMap<u32, ProcessNetUsage> = HashMap::new();
+
+    for socket in sockets {
+        if socket.state == ConnectionState::Listen || socket.inode == 0 {
+            continue;
+        }
+        let pid = owners.get(&socket.inode).copied();
+        let (rx, tx, retrans) = match (&socket.tcp_info, previous.get(&socket.cookie)) {
+            (Some(info), Some(prev)) => (
+                info.bytes_received.saturating_sub(prev.rx),
+                info.bytes_acked.saturating_sub(prev.tx),
+                info.total_retrans.saturating_sub(prev.retrans),
+            ),
+            // Opened since the last refresh
+            (Some(info), None) => (info.bytes_received, info.bytes_acked, info.total_retrans),
+            (None, _) => (0, 0, 0),
+        };
+        let (rx_rate, tx_rate) = (rx as f64 / secs, tx as f64 / secs);
+
+        match pid {
+            Some(pid) => {
+                let entry = processes.entry(pid).or_insert_with(|| ProcessNetUsage {
+                    pid,
+                    ..Default::default()
+                });
+                entry.rx_bytes_per_sec += rx_rate;
+                entry.tx_bytes_per_sec += tx_rate;
+                entry.retransmits += retrans as u64;
+                match socket.protocol {
+                    SocketProtocol::Tcp => entry.tcp_connections += 1,
+                    SocketProtocol::Udp => entry.udp_sockets += 1,
+                    SocketProtocol::Sctp => {}
+                }
+            }
+            None => {
+                usage.unattributed_rx_bytes_per_sec += rx_rate;
+                usage.unattributed_tx_bytes_per_sec += tx_rate;
+            }
+        }
+
+        if socket.protocol == SocketProtocol::Tcp {
+            usage.connections.push(ConnectionRate {
+                pid,
+                protocol: socket.protocol,
+                local: socket.local,
+                remote: socket.remote,
+                state: socket.state,
+                rx_bytes_per_sec: rx_rate,
+                tx_bytes_per_sec: tx_rate,
+                rtt_ms: socket.tcp_info.as_ref().map(|i| i.rtt_ms()),
+                retransmits: retrans,
+                cwnd: socket.tcp_info.as_ref().map(|i| i.snd_cwnd),
+                inode: socket.inode,
+            });
+        }
+    }
+
+    let mut containers: HashMap<String, ContainerNetUsage> = HashMap::new();
+    for process in processes.values_mut() {
+        let dir = Path::new("/proc").join(process.pid.to_string());
+        process.name = fs::read_to_string(dir.join("comm"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default();
+        process.container = fs::read_to_string(dir.join("cgroup"))
+            .ok()
+            .and_then(|cgroup| container_id(&cgroup));
+        if let Some(id) = &process.container {
+            let entry = containers
+                .entry(id.clone())
+                .or_insert_with(|| ContainerNetUsage {
+                    container: id.clone(),
+                    ..Default::default()
+                });
+            entry.pids.push(process.pid);
+            entry.rx_bytes_per_sec += process.rx_bytes_per_sec;
+            entry.tx_bytes_per_sec += process.tx_bytes_per_sec;
+        }
+    }
+
+    usage.processes = processes.into_values().collect();
+    usage.processes.sort_by(|a, b| {
+        b.total_bytes_per_sec()
+            .total_cmp(&a.total_bytes_per_sec())
+            .then(a.pid.cmp(&b.pid))
+    });
+    let total = |c: &ConnectionRate| c.rx_bytes_per_sec + c.tx_bytes_per_sec;
+    usage
+        .connections
+        .sort_by(|a, b| total(b).total_cmp(&total(a)));
+    usage.containers = containers.into_values().collect();
+    for container in &mut usage.containers {
+        container.pids.sort_unstable();
+    }
+    usage.containers.sort_by(|a, b| {
+        (b.rx_bytes_per_sec + b.tx_bytes_per_sec)
+            .total_cmp(&(a.rx_bytes_per_sec + a.tx_bytes_per_sec))
+    });
+    usage
+}
+
+/// Map socket inodes to the PID holding them, from `/proc/<pid>/fd`
+///
+/// Sockets shared by several processes (forked servers) go to the lowest
+/// PID.
+pub fn socket_owners(proc_root: &Path) -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+    let mut pids: Vec<u32> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().to_str()?.parse().ok())
+        .collect();
+    pids.sort_unstable();
+    for pid in pids {
+        let Ok(fds) = fs::read_dir(proc_root.join(pid.to_string()).join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Some(inode) = fs::read_link(fd.path())
+                .ok()
+                .and_then(|link| parse_socket_link(&link.to_string_lossy()))
+            {
+                owners.entry(inode).or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+/// Socket inode → owning PID index, updated incrementally
+///
+/// A full `/proc/*/fd` scan reads one symlink per open file on the host,
+/// which takes seconds on busy servers. The index remembers each process's
+/// sockets and on [`resolve`](Self::resolve) only looks for inodes it has
+/// not seen: new processes are scanned first, then known socket owners
+/// (busiest first), stopping as soon as everything is found. Inodes no
+/// process holds (kernel sockets, sockets closed since the dump) are
+/// remembered so they don't trigger a rescan every time.
+#[derive(Debug)]
+pub struct SocketOwnerIndex {
+    proc_root: PathBuf,
+    /// Inode → PID
+    owners: HashMap<u64, u32>,
+    /// PID → socket inodes at its last scan
+    by_pid: HashMap<u32, Vec<u64>>,
+    /// Inodes a complete scan could not place
+    orphans: HashSet<u64>,
+}
+
+impl Default for SocketOwnerIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SocketOwnerIndex {
+    /// Index over `/proc`
+    pub fn new() -> Self {
+        Self::with_proc_root("/proc")
+    }
+
+    /// Index over a different procfs mount (e.g. a container's host `/proc`)
+    pub fn with_proc_root(proc_root: impl Into<PathBuf>) -> Self {
+        Self {
+            proc_root: proc_root.into(),
+            owners: HashMap::new(),
+            by_pid: HashMap::new(),
+            orphans: HashSet::new(),
+        }
+    }
+
+    /// PID holding a socket inode
+    pub fn owner(&self, inode: u64) -> Option<u32> {
+        self.owners.get(&inode).copied()
+    }
+
+    /// The current inode → PID map
+    pub fn owners(&self) -> &HashMap<u64, u32> {
+        &self.owners
+    }
+
+    /// Make sure `inodes` are resolved, scanning as few processes as possible
+    pub fn resolve(&mut self, inodes: impl IntoIterator<Item = u64>) {
+        let live = self.live_pids();
+        self.by_pid.retain(|pid, _| live.contains(pid));
+        let by_pid = &self.by_pid;
+        self.owners.retain(|_, pid| by_pid.contains_key(pid));
+
+        // Inode 0 is a socket without a file (TIME_WAIT, orphaned FIN_WAIT)
+        let mut missing: HashSet<u64> = inodes
+            .into_iter()
+            .filter(|&inode| {
+                inode != 0 && !self.owners.contains_key(&inode) && !self.orphans.contains(&inode)
+            })
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let mut new: Vec<u32> = live
+            .iter()
+            .copied()
+            .filter(|pid| !self.by_pid.contains_key(pid))
+            .collect();
+        new.sort_unstable();
+        let mut known: Vec<(u32, usize)> = self
+            .by_pid
+            .iter()
+            .map(|(&pid, inodes)| (pid, inodes.len()))
+            .collect();
+        known.sort_unstable_by_key(|&(pid, sockets)| (std::cmp::Reverse(sockets), pid));
+
+        for pid in new.into_iter().chain(known.into_iter().map(|(pid, _)| pid)) {
+            for inode in self.scan(pid) {
+                missing.remove(&inode);
+            }
+            if missing.is_empty() {
+                return;
+            }
+        }
+        self.orphans.extend(missing);
+    }
+
+    /// Forget inodes that no longer exist
+    pub fn retain(&mut self, mut keep: impl FnMut(u64) -> bool) {
+        self.owners.retain(|&inode, _| keep(inode));
+        self.orphans.retain(|&inode| keep(inode));
+        for inodes in self.by_pid.values_mut() {
+            inodes.retain(|&inode| keep(inode));
+        }
+    }
+
+    fn live_pids(&self) -> HashSet<u32> {
+        fs::read_dir(&self.proc_root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Re-read one process's fd table, returning its socket inodes
+    fn scan(&mut self, pid: u32) -> Vec<u64> {
+        let fd_dir = self.proc_root.join(pid.to_string()).join("fd");
+        let inodes: Vec<u64> = fs::read_dir(fd_dir)
+            .map(|fds| {
+                fds.flatten()
+                    .filter_map(|fd| {
+                        parse_socket_link(&fs::read_link(fd.path()).ok()?.to_string_lossy())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if let Some(previous) = self.by_pid.get(&pid) {
+            for inode in previous {
+                if self.owners.get(inode) == Some(&pid) {
+                    self.owners.remove(inode);
+                }
+            }
+        }
+        for &inode in &inodes {
+            // Shared sockets stay with whoever was found first
+            self.owners.entry(inode).or_insert(pid);
+            self.orphans.remove(&inode);
+        }
+        self.by_pid.insert(pid, inodes.clone());
+        inodes
+    }
+}
+
+/// Parse an fd link target `socket:[12345]`
+pub fn parse_socket_link(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Short container ID from the contents of `/proc/<pid>/cgroup`
+///
+/// Recognizes Docker (`/docker/<id>`, `docker-<id>.scope`), containerd
+/// (`cri-containerd-<id>.scope`), CRI-O (`crio-<id>.scope`) and Podman
+/// (`libpod-<id>.scope`), including under Kubernetes `kubepods` slices.
+pub fn container_id(cgroup: &str) -> Option<String> {
+    const PREFIXES: [&str; 5] = [
+        "docker-",
+        "cri-containerd-",
+        "crio-",
+        "libpod-",
+        "containerd-",
+    ];
+    cgroup
+        .lines()
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .flat_map(|path| path.split('/'))
+        .find_map(|segment| {
+            let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+            let id = PREFIXES
+                .iter()
+                .find_map(|p| segment.strip_prefix(p))
+                .unwrap_or(segment);
+            (id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()))
+                .then(|| id[..12].to_string())
+        })
+}
+
+/// Network namespace inode of a process
+fn netns_inode(proc_dir: &Path) -> Option<u64> {
+    let link = fs::read_link(proc_dir.join("ns/net")).ok()?;
+    link.to_string_lossy()
+        .strip_prefix("net:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Receive and transmit byte totals over all non-loopback interfaces of the
+/// process's network namespace
+fn netns_totals(proc_dir: &Path) -> Option<(u64, u64)> {
+    let content = fs::read_to_string(proc_dir.join("net/dev")).ok()?;
+    Some(parse_net_dev(&content))
+}
+
+/// Sum `/proc/net/dev` byte counters, skipping `lo`
+fn parse_net_dev(content: &str) -> (u64, u64) {
+    content
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, stats) = line.split_once(':')?;
+            if name.trim() == "lo" {
+                return None;
+            }
+            let fields: Vec<u64> = stats
+                .split_whitespace()
+                .filter_map(|f| f.parse().ok())
+                .collect();
+            Some((*fields.first()?, *fields.get(8)?))
+        })
+        .fold((0, 0), |(rx, tx), (r, t)| (rx + r, tx + t))
+}
+
+/// A network namespace other than the host's
+struct ForeignNamespace {
+    /// Processes in the namespace
+    pids: Vec<u32>,
+    /// Container of the first process, if any
+    container: Option<String>,
+    /// Receive and transmit byte totals
+    totals: (u64, u64),
+}
+
+/// Every network namespace other than the host's, keyed by inode
+fn foreign_namespaces(proc_root: &Path, host_ns: u64) -> HashMap<u64, ForeignNamespace> {
+    let mut namespaces: HashMap<u64, ForeignNamespace> = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return namespaces;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let dir = entry.path();
+        let Some(ns) = netns_inode(&dir).filter(|&ns| ns != host_ns) else {
+            continue;
+        };
+        if let Some(namespace) = namespaces.get_mut(&ns) {
+            namespace.pids.push(pid);
+            continue;
+        }
+        let Some(totals) = netns_totals(&dir) else {
+            continue;
+        };
+        namespaces.insert(
+            ns,
+            ForeignNamespace {
+                pids: vec![pid],
+                container: fs::read_to_string(dir.join("cgroup"))
+                    .ok()
+                    .and_then(|cgroup| container_id(&cgroup)),
+                totals,
+            },
+        );
+    }
+    namespaces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_container_id() {
+        let id = "4f5b8c1d2e3a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f9a0b1c2d3e";
+        assert_eq!(
+            container_id(&format!("0::/system.slice/docker-{}.scope\n", id)).as_deref(),
+            Some("4f5b8c1d2e3a")
+        );
+        assert_eq!(
+            container_id(&format!("12:memory:/docker/{}\n", id)).as_deref(),
+            Some("4f5b8c1d2e3a")
+        );
+        assert_eq!(
+            container_id(&format!(
+                "0::/kubepods.slice/kubepods-besteffort.slice/kubepods-besteffort-pod1234.slice/cri-containerd-{}.scope\n",
+                id
+            ))
+            .as_deref(),
+            Some("4f5b8c1d2e3a")
+        );
+        assert_eq!(
+            container_id("0::/user.slice/user-1000.slice/session-2.scope\n"),
+            None
+        );
+        assert_eq!(parse_socket_link("socket:[98765]"), Some(98765));
+        assert_eq!(parse_socket_link("pipe:[98765]"), None);
+    }
+
+    #[test]
+    fn test_socket_owner_index() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let link = |pid: u32, fd: u32, target: &str| {
+            let dir = root.join(pid.to_string()).join("fd");
+            fs::create_dir_all(&dir).unwrap();
+            std::os::unix::fs::symlink(target, dir.join(fd.to_string())).unwrap();
+        };
+        link(100, 3, "socket:[1000]");
+        link(100, 4, "/dev/null");
+        link(200, 3, "socket:[2000]");
+
+        let mut index = SocketOwnerIndex::with_proc_root(root);
+        index.resolve([1000, 2000, 3000, 0]);
+        assert_eq!(index.owner(1000), Some(100));
+        assert_eq!(index.owner(2000), Some(200));
+        assert_eq!(index.owner(3000), None);
+
+        // A new socket in a known process and a new process
+        link(200, 5, "socket:[3000]");
+        link(300, 3, "socket:[4000]");
+        index.resolve([4000]);
+        assert_eq!(index.owner(4000), Some(300));
+        // 3000 was an orphan at the last complete scan, so it isn't retried
+        index.resolve([3000]);
+        assert_eq!(index.owner(3000), None);
+        index.retain(|inode| inode != 3000);
+        index.resolve([3000]);
+        assert_eq!(index.owner(3000), Some(200));
+
+        // Exited processes drop out
+        fs::remove_dir_all(root.join("100")).unwrap();
+        index.resolve([2000]);
+        assert_eq!(index.owner(1000), None);
+    }
+
+    #[test]
+    fn test_parse_net_dev() {
+        let content = "Inter-|   Receive                                                |  Transmit\n \
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed\n    \
+             lo: 5000 50 0 0 0 0 0 0 5000 50 0 0 0 0 0 0\n  \
+             eth0: 1000 10 0 0 0 0 0 0 2000 20 0 0 0 0 0 0\n  \
+             eth1: 300 3 0 0 0 0 0 0 400 4 0 0 0 0 0 0\n";
+        assert_eq!(parse_net_dev(content), (1300, 2400));
+    }
+
+    #[test]
+    fn test_loopback_bandwidth() {
+        let Ok(mut monitor) = ProcessNetMonitor::new() else {
+            return;
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+        assert!(monitor.refresh().unwrap().processes.is_empty());
+
+        let payload = vec![1u8; 512 * 1024];
+        client.write_all(&payload).unwrap();
+        let mut received = vec![0u8; payload.len()];
+        server.read_exact(&mut received).unwrap();
+
+        let usage = monitor.refresh().unwrap();
+        let me = usage
+            .process(std::process::id())
+            .expect("test process has traffic");
+        assert!(me.tcp_connections >= 2);
+        // Both ends belong to this process
+        let bytes = payload.len() as f64 * usage.interval.as_secs_f64().max(0.001).recip();
+        assert!(me.tx_bytes_per_sec >= bytes * 0.99);
+        assert!(me.rx_bytes_per_sec >= bytes * 0.99);
+        let local = client.local_addr().unwrap();
+        let conn = usage
+            .connections
+            .iter()
+            .find(|c| c.local == local)
+            .expect("client connection");
+        assert_eq!(conn.pid, Some(std::process::id()));
+        assert!(conn.rtt_ms.is_some());
+    }
+}
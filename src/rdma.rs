@@ -0,0 +1,661 @@
+//! InfiniBand and RoCE (RDMA) port monitoring
+//!
+//! Reads HCA ports from `/sys/class/infiniband/<dev>/ports/<n>`: logical and
+//! physical state, rate, link layer, LID/GID, and both the standard
+//! `counters` (IB PortCounters) and the driver's `hw_counters` (RoCE
+//! congestion, retransmits, out-of-buffer drops). Each HCA is mapped to its
+//! network interface and PCIe/NUMA location. RDMA traffic bypasses the
+//! kernel network stack, so none of it shows up in
+//! [`NetworkMonitor`](crate::NetworkMonitor).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::rdma::RdmaMonitor;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = RdmaMonitor::new();
+//!
+//! for device in monitor.devices()? {
+//!     for port in &device.ports {
+//!         println!(
+//!             "{}/{} {} {} ({}) netdev={:?} numa={:?}",
+//!             device.name, port.port, port.state, port.rate, port.link_layer,
+//!             port.netdev, device.numa_node
+//!         );
+//!     }
+//! }
+//!
+//! // Throughput and error rates since the previous refresh
+//! monitor.refresh()?;
+//! std::thread::sleep(Duration::from_secs(1));
+//! for rate in monitor.refresh()? {
+//!     println!(
+//!         "{}: rx {:.1} Gb/s, tx {:.1} Gb/s, {} new errors",
+//!         rate.name(),
+//!         rate.rx_bytes_per_sec * 8.0 / 1e9,
+//!         rate.tx_bytes_per_sec * 8.0 / 1e9,
+//!         rate.error_delta
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::topology::parse_cpu_list;
+use crate::utils::{RateWindow, DEFAULT_RATE_WINDOW};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default sysfs class directory for RDMA devices
+const INFINIBAND_PATH: &str = "/sys/class/infiniband";
+
+/// Port counters that indicate link or signal problems
+pub const ERROR_COUNTERS: [&str; 8] = [
+    "symbol_error",
+    "link_error_recovery",
+    "link_downed",
+    "port_rcv_errors",
+    "port_rcv_remote_physical_errors",
+    "local_link_integrity_errors",
+    "excessive_buffer_overrun_errors",
+    "port_xmit_discards",
+];
+
+/// Counters that indicate congestion rather than faults
+pub const CONGESTION_COUNTERS: [&str; 5] = [
+    "port_xmit_wait",
+    "np_cnp_sent",
+    "rp_cnp_handled",
+    "np_ecn_marked_roce_packets",
+    "out_of_buffer",
+];
+
+/// Logical port state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortState {
+    /// No link
+    Down,
+    /// Link up, waiting for the subnet manager
+    Init,
+    /// Configured by the subnet manager, not yet active
+    Armed,
+    /// Ready for traffic
+    Active,
+    /// Active, deferred
+    ActiveDefer,
+    /// Unrecognized state
+    #[default]
+    Unknown,
+}
+
+impl PortState {
+    /// Parse sysfs `state` (`4: ACTIVE`)
+    pub fn parse(s: &str) -> Self {
+        match strip_index(s) {
+            "DOWN" => PortState::Down,
+            "INIT" => PortState::Init,
+            "ARMED" => PortState::Armed,
+            "ACTIVE" => PortState::Active,
+            "ACTIVE_DEFER" => PortState::ActiveDefer,
+            _ => PortState::Unknown,
+        }
+    }
+
+    /// Whether the port can carry traffic
+    pub fn is_active(&self) -> bool {
+        matches!(self, PortState::Active | PortState::ActiveDefer)
+    }
+}
+
+impl fmt::Display for PortState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PortState::Down => "DOWN",
+            PortState::Init => "INIT",
+            PortState::Armed => "ARMED",
+            PortState::Active => "ACTIVE",
+            PortState::ActiveDefer => "ACTIVE_DEFER",
+            PortState::Unknown => "UNKNOWN",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One RDMA port
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RdmaPort {
+    /// Port number (1-based)
+    pub port: u32,
+    /// Logical state
+    pub state: PortState,
+    /// Physical state (`LinkUp`, `Polling`, `Disabled`)
+    pub phys_state: String,
+    /// Rate as reported (`200 Gb/sec (4X HDR)`)
+    pub rate: String,
+    /// Rate in Gb/s
+    pub rate_gbps: Option<f64>,
+    /// `InfiniBand` or `Ethernet` (RoCE)
+    pub link_layer: String,
+    /// Local identifier (InfiniBand only)
+    pub lid: Option<u16>,
+    /// Subnet manager LID (InfiniBand only)
+    pub sm_lid: Option<u16>,
+    /// GID at index 0
+    pub gid: Option<String>,
+    /// Associated network interface
+    pub netdev: Option<String>,
+    /// Standard port counters (`counters/`)
+    pub counters: BTreeMap<String, u64>,
+    /// Driver-specific counters (`hw_counters/`)
+    pub hw_counters: BTreeMap<String, u64>,
+}
+
+impl RdmaPort {
+    /// Whether this is a RoCE port
+    pub fn is_roce(&self) -> bool {
+        self.link_layer.eq_ignore_ascii_case("ethernet")
+    }
+
+    /// Whether a cable is connected and trained
+    pub fn is_link_up(&self) -> bool {
+        self.phys_state.eq_ignore_ascii_case("LinkUp")
+    }
+
+    /// Counter by name from either counter set
+    pub fn counter(&self, name: &str) -> Option<u64> {
+        self.counters
+            .get(name)
+            .or_else(|| self.hw_counters.get(name))
+            .copied()
+    }
+
+    /// Bytes received (`port_rcv_data` counts 4-byte words)
+    pub fn rx_bytes(&self) -> Option<u64> {
+        self.counter("port_rcv_data").map(|words| words * 4)
+    }
+
+    /// Bytes transmitted (`port_xmit_data` counts 4-byte words)
+    pub fn tx_bytes(&self) -> Option<u64> {
+        self.counter("port_xmit_data").map(|words| words * 4)
+    }
+
+    /// Sum of the [`ERROR_COUNTERS`]
+    pub fn error_count(&self) -> u64 {
+        ERROR_COUNTERS.iter().filter_map(|c| self.counter(c)).sum()
+    }
+
+    /// Non-zero error counters
+    pub fn errors(&self) -> Vec<(&'static str, u64)> {
+        ERROR_COUNTERS
+            .iter()
+            .filter_map(|&c| Some((c, self.counter(c).filter(|&n| n > 0)?)))
+            .collect()
+    }
+}
+
+/// An RDMA device (HCA or RoCE NIC) with its ports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RdmaDevice {
+    /// Device name (`mlx5_0`)
+    pub name: String,
+    /// Node type (`CA`, `Switch`, `Router`)
+    pub node_type: Option<String>,
+    /// Node GUID
+    pub node_guid: Option<String>,
+    /// Firmware version
+    pub fw_ver: Option<String>,
+    /// HCA type (`MT4123`)
+    pub hca_type: Option<String>,
+    /// Board ID (PSID)
+    pub board_id: Option<String>,
+    /// PCI address
+    pub pci_address: Option<String>,
+    /// NUMA node
+    pub numa_node: Option<u32>,
+    /// CPUs local to the device
+    pub local_cpus: Vec<u32>,
+    /// Ports
+    pub ports: Vec<RdmaPort>,
+}
+
+/// Throughput and error rates of one port between two refreshes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RdmaPortRate {
+    /// Device name
+    pub device: String,
+    /// Port number
+    pub port: u32,
+    /// Received bytes per second
+    pub rx_bytes_per_sec: f64,
+    /// Transmitted bytes per second
+    pub tx_bytes_per_sec: f64,
+    /// Link utilization of the busier direction (%)
+    pub utilization: Option<f64>,
+    /// New errors across the [`ERROR_COUNTERS`]
+    pub error_delta: u64,
+    /// Per-second rate of every counter that changed
+    pub counter_rates: BTreeMap<String, f64>,
+    /// [`ERROR_COUNTERS`] that moved within the rate window, with how much
+    pub recent_errors: BTreeMap<String, u64>,
+    /// Time between the two refreshes
+    pub interval: Duration,
+    /// Time the rate window covers so far
+    pub window: Duration,
+}
+
+impl RdmaPortRate {
+    /// `device/port` label
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.device, self.port)
+    }
+
+    /// Rate of one counter per second, 0 when unchanged
+    pub fn counter_rate(&self, name: &str) -> f64 {
+        self.counter_rates.get(name).copied().unwrap_or(0.0)
+    }
+
+    /// Combined per-second rate of the [`CONGESTION_COUNTERS`]
+    pub fn congestion_rate(&self) -> f64 {
+        CONGESTION_COUNTERS
+            .iter()
+            .map(|c| self.counter_rate(c))
+            .sum()
+    }
+}
+
+/// RDMA device monitor
+pub struct RdmaMonitor {
+    /// Class directory (`/sys/class/infiniband`)
+    root: PathBuf,
+    /// Counters from the previous refresh, keyed by `device/port`
+    previous: Option<(Instant, HashMap<String, RdmaPort>)>,
+    /// [`ERROR_COUNTERS`] deltas of recent refreshes, keyed by `device/port`
+    errors: RateWindow<String, { ERROR_COUNTERS.len() }>,
+}
+
+impl Default for RdmaMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RdmaMonitor {
+    /// Create a monitor for the system RDMA devices
+    pub fn new() -> Self {
+        Self::with_sysfs_root(INFINIBAND_PATH)
+    }
+
+    /// Create a monitor reading a different `infiniband` class directory
+    pub fn with_sysfs_root<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            previous: None,
+            errors: RateWindow::new(DEFAULT_RATE_WINDOW),
+        }
+    }
+
+    /// Keep error counts over a different window (default one hour)
+    pub fn with_rate_window(mut self, window: Duration) -> Self {
+        self.errors = RateWindow::new(window);
+        self
+    }
+
+    /// Whether any RDMA device is present
+    pub fn is_available(&self) -> bool {
+        fs::read_dir(&self.root).is_ok_and(|mut entries| entries.next().is_some())
+    }
+
+    /// Read all RDMA devices, sorted by name
+    pub fn devices(&self) -> Result<Vec<RdmaDevice>> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut devices: Vec<RdmaDevice> = entries
+            .flatten()
+            .map(|entry| read_device(&entry.path()))
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(devices)
+    }
+
+    /// Read counters and return per-port rates since the last refresh
+    ///
+    /// Error counters are also kept over the refreshes within the rate
+    /// window, as [`RdmaPortRate::recent_errors`]. The first call records
+    /// a baseline and returns an empty list.
+    pub fn refresh(&mut self) -> Result<Vec<RdmaPortRate>> {
+        let now = Instant::now();
+        let devices = self.devices()?;
+        let mut current = HashMap::new();
+        let mut totals = HashMap::new();
+        let mut rates = Vec::new();
+
+        for device in &devices {
+            for port in &device.ports {
+                let key = format!("{}/{}", device.name, port.port);
+                if let Some((then, previous)) = &self.previous {
+                    if let Some(prev) = previous.get(&key) {
+                        let interval = now.duration_since(*then);
+                        rates.push(port_rate(&device.name, prev, port, interval));
+                    }
+                }
+                totals.insert(
+                    key.clone(),
+                    ERROR_COUNTERS.map(|c| port.counter(c).unwrap_or(0)),
+                );
+                current.insert(key, port.clone());
+            }
+        }
+
+        let recent: HashMap<_, _> = self
+            .errors
+            .update(now, totals)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        for rate in &mut rates {
+            if let Some(counts) = recent.get(&rate.name()) {
+                rate.recent_errors = ERROR_COUNTERS
+                    .iter()
+                    .zip(counts.in_window)
+                    .filter(|&(_, n)| n > 0)
+                    .map(|(c, n)| (c.to_string(), n))
+                    .collect();
+                rate.window = counts.span;
+            }
+        }
+
+        self.previous = Some((now, current));
+        Ok(rates)
+    }
+}
+
+/// Read all RDMA devices from `/sys/class/infiniband`
+pub fn rdma_devices() -> Result<Vec<RdmaDevice>> {
+    RdmaMonitor::new().devices()
+}
+
+/// Parse a sysfs rate (`200 Gb/sec (4X HDR)`) into Gb/s
+pub fn parse_rate(s: &str) -> Option<f64> {
+    s.split_whitespace().next()?.parse().ok()
+}
+
+/// Rates between two readings of the same port
+fn port_rate(device: &str, prev: &RdmaPort, cur: &RdmaPort, interval: Duration) -> RdmaPortRate {
+    let secs = interval.as_secs_f64().max(0.001);
+    let delta = |a: Option<u64>, b: Option<u64>| match (a, b) {
+        // Counters go backwards when reset with perfquery -R
+        (Some(prev), Some(cur)) => cur.saturating_sub(prev),
+        _ => 0,
+    };
+
+    let rx_bytes_per_sec = delta(prev.rx_bytes(), cur.rx_bytes()) as f64 / secs;
+    let tx_bytes_per_sec = delta(prev.tx_bytes(), cur.tx_bytes()) as f64 / secs;
+    let utilization = cur
+        .rate_gbps
+        .filter(|&gbps| gbps > 0.0)
+        .map(|gbps| rx_bytes_per_sec.max(tx_bytes_per_sec) * 8.0 / (gbps * 1e9) * 100.0);
+
+    let mut counter_rates = BTreeMap::new();
+    for (name, &value) in cur.counters.iter().chain(&cur.hw_counters) {
+        let d = delta(prev.counter(name), Some(value));
+        if d > 0 {
+            counter_rates.insert(name.clone(), d as f64 / secs);
+        }
+    }
+
+    RdmaPortRate {
+        device: device.to_string(),
+        port: cur.port,
+        rx_bytes_per_sec,
+        tx_bytes_per_sec,
+        utilization,
+        error_delta: cur.error_count().saturating_sub(prev.error_count()),
+        counter_rates,
+        recent_errors: BTreeMap::new(),
+        interval,
+        window: interval,
+    }
+}
+
+fn read_device(path: &Path) -> RdmaDevice {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let pci = path.join("device");
+    let pci_address = fs::canonicalize(&pci)
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()));
+    let device_netdevs = list_dir(&pci.join("net"));
+
+    let mut ports: Vec<RdmaPort> = list_dir(&path.join("ports"))
+        .iter()
+        .filter_map(|port| {
+            let number = port.parse().ok()?;
+            Some(read_port(&path.join("ports").join(port), number))
+        })
+        .collect();
+    ports.sort_by_key(|p| p.port);
+    // Fall back to the PCI function's interfaces when the port has no GID
+    // netdev (InfiniBand ports with IPoIB, older kernels)
+    for port in &mut ports {
+        if port.netdev.is_none() {
+            port.netdev = device_netdevs
+                .get(port.port as usize - 1)
+                .or(device_netdevs.first())
+                .cloned();
+        }
+    }
+
+    RdmaDevice {
+        name,
+        node_type: read_string(&path.join("node_type")).map(|s| strip_index(&s).to_string()),
+        node_guid: read_string(&path.join("node_guid")),
+        fw_ver: read_string(&path.join("fw_ver")),
+        hca_type: read_string(&path.join("hca_type")),
+        board_id: read_string(&path.join("board_id")),
+        pci_address,
+        numa_node: read_string(&pci.join("numa_node"))
+            .and_then(|s| s.parse::<i64>().ok())
+            .and_then(|n| u32::try_from(n).ok()),
+        local_cpus: read_string(&pci.join("local_cpulist"))
+            .map(|s| parse_cpu_list(&s))
+            .unwrap_or_default(),
+        ports,
+    }
+}
+
+fn read_port(path: &Path, port: u32) -> RdmaPort {
+    let rate = read_string(&path.join("rate")).unwrap_or_default();
+    let lid = |name: &str| {
+        read_string(&path.join(name))
+            .and_then(|s| u16::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+            .filter(|&lid| lid != 0)
+    };
+    RdmaPort {
+        port,
+        state: read_string(&path.join("state"))
+            .map(|s| PortState::parse(&s))
+            .unwrap_or_default(),
+        phys_state: read_string(&path.join("phys_state"))
+            .map(|s| strip_index(&s).to_string())
+            .unwrap_or_default(),
+        rate_gbps: parse_rate(&rate),
+        rate,
+        link_layer: read_string(&path.join("link_layer")).unwrap_or_default(),
+        lid: lid("lid"),
+        sm_lid: lid("sm_lid"),
+        gid: read_string(&path.join("gids/0")).filter(|g| g.chars().any(|c| c != '0' && c != ':')),
+        netdev: read_string(&path.join("gid_attrs/ndevs/0")),
+        counters: read_counters(&path.join("counters")),
+        hw_counters: read_counters(&path.join("hw_counters")),
+    }
+}
+
+/// Drop the numeric prefix of enum-like attributes (`5: LinkUp`)
+fn strip_index(s: &str) -> &str {
+    s.split_once(':').map_or(s, |(_, name)| name).trim()
+}
+
+fn read_counters(dir: &Path) -> BTreeMap<String, u64> {
+    list_dir(dir)
+        .into_iter()
+        .filter_map(|name| {
+            let value = read_string(&dir.join(&name))?.parse().ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+fn list_dir(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// mlx5_0: InfiniBand HDR port on NUMA node 1; mlx5_1: RoCE port, down
+    fn fake_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let write = |path: PathBuf, value: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, format!("{}\n", value)).unwrap();
+        };
+
+        let pci = root.join("devices/pci0000:80/0000:80:01.0/0000:81:00.0");
+        write(pci.join("numa_node"), "1");
+        write(pci.join("local_cpulist"), "32-63");
+        fs::create_dir_all(pci.join("net/ib0")).unwrap();
+        let ib = root.join("class/mlx5_0");
+        fs::create_dir_all(&ib).unwrap();
+        symlink(&pci, ib.join("device")).unwrap();
+        write(ib.join("node_type"), "1: CA");
+        write(ib.join("fw_ver"), "20.39.1002");
+        write(ib.join("hca_type"), "MT4123");
+        let port = ib.join("ports/1");
+        write(port.join("state"), "4: ACTIVE");
+        write(port.join("phys_state"), "5: LinkUp");
+        write(port.join("rate"), "200 Gb/sec (4X HDR)");
+        write(port.join("link_layer"), "InfiniBand");
+        write(port.join("lid"), "0x1a");
+        write(port.join("sm_lid"), "0x1");
+        write(
+            port.join("gids/0"),
+            "fe80:0000:0000:0000:0c42:a103:0012:3456",
+        );
+        write(port.join("counters/port_rcv_data"), "1000");
+        write(port.join("counters/port_xmit_data"), "2000");
+        write(port.join("counters/symbol_error"), "0");
+        write(port.join("counters/link_downed"), "1");
+        write(port.join("hw_counters/out_of_buffer"), "0");
+
+        let roce = root.join("class/mlx5_1");
+        let port = roce.join("ports/1");
+        write(port.join("state"), "1: DOWN");
+        write(port.join("phys_state"), "3: Disabled");
+        write(port.join("rate"), "40 Gb/sec (4X QDR)");
+        write(port.join("link_layer"), "Ethernet");
+        write(port.join("lid"), "0x0");
+        write(port.join("gid_attrs/ndevs/0"), "ens2f1np1");
+        dir
+    }
+
+    #[test]
+    fn test_devices() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let monitor = RdmaMonitor::with_sysfs_root(root.join("class"));
+        assert!(monitor.is_available());
+        let devices = monitor.devices().unwrap();
+        assert_eq!(devices.len(), 2);
+
+        let ib = &devices[0];
+        assert_eq!(ib.name, "mlx5_0");
+        assert_eq!(ib.node_type.as_deref(), Some("CA"));
+        assert_eq!(ib.pci_address.as_deref(), Some("0000:81:00.0"));
+        assert_eq!(ib.numa_node, Some(1));
+        assert_eq!(ib.local_cpus.len(), 32);
+        let port = &ib.ports[0];
+        assert_eq!(port.state, PortState::Active);
+        assert_eq!(port.phys_state, "LinkUp");
+        assert_eq!(port.rate_gbps, Some(200.0));
+        assert_eq!(port.lid, Some(0x1a));
+        assert_eq!(port.netdev.as_deref(), Some("ib0"));
+        assert_eq!(port.rx_bytes(), Some(4000));
+        assert_eq!(port.errors(), [("link_downed", 1)]);
+        assert!(!port.is_roce());
+
+        let roce = &devices[1].ports[0];
+        assert_eq!(roce.state, PortState::Down);
+        assert!(roce.is_roce());
+        assert!(!roce.is_link_up());
+        assert_eq!(roce.lid, None);
+        assert_eq!(roce.netdev.as_deref(), Some("ens2f1np1"));
+    }
+
+    #[test]
+    fn test_rates() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let mut monitor = RdmaMonitor::with_sysfs_root(root.join("class"));
+        assert!(monitor.refresh().unwrap().is_empty());
+
+        let counters = root.join("class/mlx5_0/ports/1/counters");
+        fs::write(counters.join("port_xmit_data"), "250002000\n").unwrap();
+        fs::write(counters.join("symbol_error"), "3\n").unwrap();
+        let rates = monitor.refresh().unwrap();
+        assert_eq!(rates.len(), 2);
+        let rate = &rates[0];
+        assert_eq!(rate.name(), "mlx5_0/1");
+        assert_eq!(rate.error_delta, 3);
+        assert!(rate.tx_bytes_per_sec > 0.0);
+        assert_eq!(rate.rx_bytes_per_sec, 0.0);
+        assert!(rate.counter_rate("symbol_error") > 0.0);
+        assert_eq!(rate.counter_rate("link_downed"), 0.0);
+        assert_eq!(rate.congestion_rate(), 0.0);
+        assert_eq!(rate.recent_errors.get("symbol_error"), Some(&3));
+
+        // Still within the window on the next refresh
+        let rates = monitor.refresh().unwrap();
+        assert_eq!(rates[0].error_delta, 0);
+        assert_eq!(rates[0].recent_errors.get("symbol_error"), Some(&3));
+    }
+
+    #[test]
+    fn test_parsing() {
+        assert_eq!(PortState::parse("4: ACTIVE"), PortState::Active);
+        assert_eq!(PortState::parse("2: INIT"), PortState::Init);
+        assert_eq!(parse_rate("100 Gb/sec (2X HDR)"), Some(100.0));
+        assert_eq!(parse_rate("2.5 Gb/sec (1X SDR)"), Some(2.5));
+    }
+}
@@ -20,12 +20,21 @@
 //!     println!("SSH: {:?}", status.status);
 //! }
 //! ```
+//!
+//! On Linux with the `systemd` feature, services are read and controlled
+//! through systemd's D-Bus API (see [`systemd`]), falling back to parsing
+//! `systemctl` output when the system bus is unavailable.
 
 use crate::error::{SimonError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(all(target_os = "linux", feature = "systemd"))]
+use std::sync::OnceLock;
 use std::time::Instant;
 
+#[cfg(all(target_os = "linux", feature = "systemd"))]
+pub mod systemd;
+
 /// Service status states
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ServiceStatus {
@@ -191,33 +200,82 @@ pub struct ServiceMonitor {
     last_update: Instant,
     /// Filter for specific services
     filter: Option<Vec<String>>,
+    /// systemd D-Bus client, connected on first use (None falls back to
+    /// systemctl)
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    systemd: OnceLock<Option<systemd::SystemdClient>>,
 }
 
 impl ServiceMonitor {
     /// Create a new service monitor
     pub fn new() -> Result<Self> {
-        let mut monitor = Self {
-            services: Vec::new(),
-            service_map: HashMap::new(),
-            last_update: Instant::now(),
-            filter: None,
-        };
-
+        let mut monitor = Self::empty(None);
         monitor.discover()?;
         Ok(monitor)
     }
 
     /// Create a monitor for specific services
     pub fn with_filter(service_names: Vec<String>) -> Result<Self> {
-        let mut monitor = Self {
+        let mut monitor = Self::empty(Some(service_names));
+        monitor.discover()?;
+        Ok(monitor)
+    }
+
+    /// Create a monitor backed by an existing systemd D-Bus client
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    pub fn with_systemd(
+        client: systemd::SystemdClient,
+        filter: Option<Vec<String>>,
+    ) -> Result<Self> {
+        let mut monitor = Self::empty(filter);
+        monitor.systemd = OnceLock::from(Some(client));
+        monitor.discover()?;
+        Ok(monitor)
+    }
+
+    fn empty(filter: Option<Vec<String>>) -> Self {
+        Self {
             services: Vec::new(),
             service_map: HashMap::new(),
             last_update: Instant::now(),
-            filter: Some(service_names),
-        };
+            filter,
+            #[cfg(all(target_os = "linux", feature = "systemd"))]
+            systemd: OnceLock::new(),
+        }
+    }
 
-        monitor.discover()?;
-        Ok(monitor)
+    /// The systemd client, connecting to the system bus on first use
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    fn systemd(&self) -> Option<&systemd::SystemdClient> {
+        self.systemd
+            .get_or_init(|| systemd::SystemdClient::system().ok())
+            .as_ref()
+    }
+
+    /// Subscribe to service state changes pushed by systemd
+    ///
+    /// Feed the received events to [`ServiceMonitor::apply_event`] to keep
+    /// the cached service list current without polling.
+    /// [`HealthMonitor::with_services`](crate::health::HealthMonitor::with_services)
+    /// does this to alert on service failures.
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    pub fn watch(&self) -> Result<systemd::ServiceEvents> {
+        self.systemd()
+            .ok_or_else(|| SimonError::FeatureNotAvailable("systemd D-Bus connection".to_string()))?
+            .subscribe()
+    }
+
+    /// Update the cached state of a service from a pushed event
+    #[cfg(all(target_os = "linux", feature = "systemd"))]
+    pub fn apply_event(&mut self, event: &systemd::ServiceEvent) {
+        let Some(&idx) = self.service_map.get(&event.name) else {
+            return;
+        };
+        let service = &mut self.services[idx];
+        service.status = event.status.clone();
+        if let Some(ref sub_state) = event.sub_state {
+            service.sub_state = Some(sub_state.clone());
+        }
     }
 
     /// Refresh service information
@@ -401,6 +459,24 @@ impl ServiceMonitor {
     fn linux_discover(&mut self) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            if let Ok(services) = client.list_services() {
+                for service in services {
+                    if let Some(ref filter) = self.filter {
+                        if !filter.iter().any(|f| service.name.contains(f)) {
+                            continue;
+                        }
+                    }
+
+                    let idx = self.services.len();
+                    self.service_map.insert(service.name.clone(), idx);
+                    self.services.push(service);
+                }
+                return Ok(());
+            }
+        }
+
         // Use systemctl to list services
         let output = Command::new("systemctl")
             .args([
@@ -435,7 +511,7 @@ impl ServiceMonitor {
 
         // Get detailed info for each service
         for service in &mut self.services {
-            self.linux_get_service_details(service);
+            Self::linux_get_service_details(service);
         }
 
         Ok(())
@@ -453,19 +529,7 @@ impl ServiceMonitor {
         let active_state = parts[2].to_string();
         let sub_state = parts[3].to_string();
 
-        let status = match active_state.as_str() {
-            "active" => match sub_state.as_str() {
-                "running" => ServiceStatus::Running,
-                "exited" => ServiceStatus::Stopped,
-                "waiting" => ServiceStatus::Starting,
-                _ => ServiceStatus::Running,
-            },
-            "inactive" => ServiceStatus::Stopped,
-            "failed" => ServiceStatus::Failed,
-            "activating" => ServiceStatus::Starting,
-            "deactivating" => ServiceStatus::Stopping,
-            _ => ServiceStatus::Unknown,
-        };
+        let status = service_status_from_states(&active_state, &sub_state);
 
         let mut service = ServiceInfo::new(&name);
         service.status = status;
@@ -481,7 +545,7 @@ impl ServiceMonitor {
     }
 
     #[cfg(target_os = "linux")]
-    fn linux_get_service_details(&self, service: &mut ServiceInfo) {
+    fn linux_get_service_details(service: &mut ServiceInfo) {
         use std::process::Command;
 
         // Get detailed service info
@@ -514,25 +578,11 @@ impl ServiceMonitor {
                                 }
                             }
                             "Type" => {
-                                service.service_type = match value {
-                                    "simple" => ServiceType::Simple,
-                                    "forking" => ServiceType::Forking,
-                                    "oneshot" => ServiceType::Oneshot,
-                                    "dbus" => ServiceType::Dbus,
-                                    "notify" => ServiceType::Notify,
-                                    "idle" => ServiceType::Idle,
-                                    _ => ServiceType::Unknown,
-                                };
+                                service.service_type = service_type_from_str(value);
                             }
                             "UnitFileState" => {
                                 service.enabled = value == "enabled";
-                                service.startup_type = match value {
-                                    "enabled" => StartupType::Automatic,
-                                    "disabled" => StartupType::Disabled,
-                                    "static" => StartupType::Manual,
-                                    "masked" => StartupType::Disabled,
-                                    _ => StartupType::Unknown,
-                                };
+                                service.startup_type = startup_type_from_unit_file_state(value);
                             }
                             "FragmentPath" => {
                                 if !value.is_empty() {
@@ -567,6 +617,12 @@ impl ServiceMonitor {
     fn linux_start_service(&self, name: &str) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            let result = client.start_and_wait(name, systemd::DEFAULT_JOB_TIMEOUT)?;
+            return job_result_to_error(name, "start", result);
+        }
+
         let status = Command::new("systemctl")
             .args(["start", &format!("{}.service", name)])
             .status();
@@ -588,6 +644,12 @@ impl ServiceMonitor {
     fn linux_stop_service(&self, name: &str) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            let result = client.stop_and_wait(name, systemd::DEFAULT_JOB_TIMEOUT)?;
+            return job_result_to_error(name, "stop", result);
+        }
+
         let status = Command::new("systemctl")
             .args(["stop", &format!("{}.service", name)])
             .status();
@@ -609,6 +671,12 @@ impl ServiceMonitor {
     fn linux_restart_service(&self, name: &str) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            let result = client.restart_and_wait(name, systemd::DEFAULT_JOB_TIMEOUT)?;
+            return job_result_to_error(name, "restart", result);
+        }
+
         let status = Command::new("systemctl")
             .args(["restart", &format!("{}.service", name)])
             .status();
@@ -630,6 +698,11 @@ impl ServiceMonitor {
     fn linux_enable_service(&self, name: &str) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            return client.enable(name);
+        }
+
         let status = Command::new("systemctl")
             .args(["enable", &format!("{}.service", name)])
             .status();
@@ -651,6 +724,11 @@ impl ServiceMonitor {
     fn linux_disable_service(&self, name: &str) -> Result<()> {
         use std::process::Command;
 
+        #[cfg(feature = "systemd")]
+        if let Some(client) = self.systemd() {
+            return client.disable(name);
+        }
+
         let status = Command::new("systemctl")
             .args(["disable", &format!("{}.service", name)])
             .status();
@@ -872,6 +950,59 @@ impl ServiceMonitor {
     }
 }
 
+/// Map systemd active/sub states to a [`ServiceStatus`]
+pub(crate) fn service_status_from_states(active_state: &str, sub_state: &str) -> ServiceStatus {
+    match active_state {
+        "active" | "reloading" => match sub_state {
+            "running" => ServiceStatus::Running,
+            "exited" => ServiceStatus::Stopped,
+            "waiting" => ServiceStatus::Starting,
+            _ => ServiceStatus::Running,
+        },
+        "inactive" => ServiceStatus::Stopped,
+        "failed" => ServiceStatus::Failed,
+        "activating" => ServiceStatus::Starting,
+        "deactivating" => ServiceStatus::Stopping,
+        _ => ServiceStatus::Unknown,
+    }
+}
+
+/// Map a systemd `Type=` value to a [`ServiceType`]
+pub(crate) fn service_type_from_str(kind: &str) -> ServiceType {
+    match kind {
+        "simple" | "exec" => ServiceType::Simple,
+        "forking" => ServiceType::Forking,
+        "oneshot" => ServiceType::Oneshot,
+        "dbus" => ServiceType::Dbus,
+        "notify" | "notify-reload" => ServiceType::Notify,
+        "idle" => ServiceType::Idle,
+        _ => ServiceType::Unknown,
+    }
+}
+
+/// Map a systemd `UnitFileState` value to a [`StartupType`]
+pub(crate) fn startup_type_from_unit_file_state(state: &str) -> StartupType {
+    match state {
+        "enabled" | "enabled-runtime" => StartupType::Automatic,
+        "disabled" | "masked" | "masked-runtime" => StartupType::Disabled,
+        "static" | "indirect" => StartupType::Manual,
+        "generated" | "transient" => StartupType::OnDemand,
+        _ => StartupType::Unknown,
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "systemd"))]
+fn job_result_to_error(name: &str, action: &str, result: systemd::JobResult) -> Result<()> {
+    if result.is_success() {
+        Ok(())
+    } else {
+        Err(SimonError::System(format!(
+            "Failed to {} service '{}': job {}",
+            action, name, result
+        )))
+    }
+}
+
 /// Service summary for quick overview
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceSummary {
@@ -0,0 +1,1136 @@
+//! Native systemd backend over D-Bus
+//!
+//! Talks to `org.freedesktop.systemd1` on the system bus instead of spawning
+//! `systemctl` and scraping its text output. Besides unit properties and
+//! start/stop/restart/enable jobs, it can track job completion through the
+//! manager's `JobRemoved` signal and push unit state changes as they happen
+//! through `PropertiesChanged`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use simon::services::systemd::SystemdClient;
+//! use std::time::Duration;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SystemdClient::system()?;
+//!
+//! // Restart a service and wait for systemd to finish the job
+//! let result = client.restart_and_wait("nginx", Duration::from_secs(30))?;
+//! println!("restart: {}", result);
+//!
+//! // Watch for failures
+//! let events = client.subscribe()?;
+//! for event in events.iter() {
+//!     if event.status == simon::ServiceStatus::Failed {
+//!         eprintln!("{} failed", event.name);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use super::{
+    service_status_from_states, service_type_from_str, startup_type_from_unit_file_state,
+    ServiceInfo, ServiceStatus,
+};
+use crate::error::{Result, SimonError};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::{Duration, Instant};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type as MessageType;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::MatchRule;
+
+const DESTINATION: &str = "org.freedesktop.systemd1";
+const MANAGER_PATH: &str = "/org/freedesktop/systemd1";
+const MANAGER_INTERFACE: &str = "org.freedesktop.systemd1.Manager";
+const UNIT_INTERFACE: &str = "org.freedesktop.systemd1.Unit";
+const SERVICE_INTERFACE: &str = "org.freedesktop.systemd1.Service";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const UNIT_PATH_PREFIX: &str = "/org/freedesktop/systemd1/unit";
+
+/// Unit type suffixes systemd knows (see systemd.unit(5))
+const UNIT_SUFFIXES: &[&str] = &[
+    "service",
+    "socket",
+    "device",
+    "mount",
+    "automount",
+    "swap",
+    "target",
+    "path",
+    "timer",
+    "slice",
+    "scope",
+];
+
+/// Default time to wait for a start/stop/restart job to finish
+pub const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// One row of `Manager.ListUnits`
+type UnitRow = (
+    String,          // name
+    String,          // description
+    String,          // load state
+    String,          // active state
+    String,          // sub state
+    String,          // following
+    OwnedObjectPath, // unit object path
+    u32,             // job id
+    String,          // job type
+    OwnedObjectPath, // job object path
+);
+
+/// Outcome of a systemd job, as reported by `JobRemoved`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobResult {
+    /// Job finished successfully
+    Done,
+    /// Job was canceled before it finished
+    Canceled,
+    /// Job timed out inside systemd
+    Timeout,
+    /// Job failed
+    Failed,
+    /// A dependency of the job failed
+    Dependency,
+    /// Job was skipped (unit already in the requested state)
+    Skipped,
+    /// Other result string
+    Other(String),
+}
+
+impl JobResult {
+    fn parse(result: &str) -> Self {
+        match result {
+            "done" => JobResult::Done,
+            "canceled" => JobResult::Canceled,
+            "timeout" => JobResult::Timeout,
+            "failed" => JobResult::Failed,
+            "dependency" => JobResult::Dependency,
+            "skipped" => JobResult::Skipped,
+            other => JobResult::Other(other.to_string()),
+        }
+    }
+
+    /// Whether the job reached the requested state
+    pub fn is_success(&self) -> bool {
+        matches!(self, JobResult::Done | JobResult::Skipped)
+    }
+}
+
+impl std::fmt::Display for JobResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobResult::Done => write!(f, "done"),
+            JobResult::Canceled => write!(f, "canceled"),
+            JobResult::Timeout => write!(f, "timeout"),
+            JobResult::Failed => write!(f, "failed"),
+            JobResult::Dependency => write!(f, "dependency"),
+            JobResult::Skipped => write!(f, "skipped"),
+            JobResult::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// A job queued in systemd
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceJob {
+    /// Job object path
+    pub path: OwnedObjectPath,
+    /// Unit the job acts on
+    pub unit: String,
+}
+
+/// A pushed change of a service's state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceEvent {
+    /// Service name (without the `.service` suffix)
+    pub name: String,
+    /// New active state, if it changed
+    pub active_state: Option<String>,
+    /// New sub-state, if it changed
+    pub sub_state: Option<String>,
+    /// Service status from the unit's current active and sub-state (states
+    /// the signal left out are filled in from earlier signals or read)
+    pub status: ServiceStatus,
+}
+
+/// Stream of [`ServiceEvent`]s pushed by systemd
+pub struct ServiceEvents {
+    rx: Receiver<ServiceEvent>,
+}
+
+impl ServiceEvents {
+    /// Block until the next event (None once the bus connection is gone)
+    pub fn recv(&self) -> Option<ServiceEvent> {
+        self.rx.recv().ok()
+    }
+
+    /// Wait up to `timeout` for the next event
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ServiceEvent> {
+        self.rx.recv_timeout(timeout).ok()
+    }
+
+    /// Return the next event if one is already queued
+    pub fn try_recv(&self) -> Option<ServiceEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Blocking iterator over events
+    pub fn iter(&self) -> impl Iterator<Item = ServiceEvent> + '_ {
+        self.rx.iter()
+    }
+}
+
+/// Job results collected by a client's `JobRemoved` listener
+#[derive(Default)]
+struct JobState {
+    /// Live [`JobWatcher`]s; results are only kept while there is one
+    watchers: usize,
+    /// Results of removed jobs by job path, until a watcher collects them
+    finished: HashMap<OwnedObjectPath, String>,
+    /// Set once the listener has stopped (the bus connection closed)
+    closed: bool,
+}
+
+/// Shared between a client, its watchers and its listener thread
+#[derive(Default)]
+struct JobTracker {
+    state: Mutex<JobState>,
+    removed: Condvar,
+}
+
+/// Collects `JobRemoved` signals so a job's completion can be awaited
+///
+/// Create the watcher before queueing the job, otherwise a fast job may
+/// finish before anyone is listening. All watchers of a client share one
+/// listener thread.
+pub struct JobWatcher {
+    tracker: Arc<JobTracker>,
+}
+
+impl JobWatcher {
+    fn new(tracker: Arc<JobTracker>) -> Self {
+        tracker.state.lock().unwrap().watchers += 1;
+        Self { tracker }
+    }
+
+    /// Wait for `job` to be removed and return its result
+    pub fn wait(&self, job: &ServiceJob, timeout: Duration) -> Result<JobResult> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.tracker.state.lock().unwrap();
+        loop {
+            if let Some(result) = state.finished.remove(&job.path) {
+                return Ok(JobResult::parse(&result));
+            }
+            if state.closed {
+                return Err(SimonError::System(
+                    "D-Bus connection closed while waiting for job".to_string(),
+                ));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(SimonError::System(format!(
+                    "Timed out waiting for job on {}",
+                    job.unit
+                )));
+            }
+            state = self
+                .tracker
+                .removed
+                .wait_timeout(state, remaining)
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+impl Drop for JobWatcher {
+    fn drop(&mut self) {
+        let mut state = self.tracker.state.lock().unwrap();
+        state.watchers -= 1;
+        // Results nobody collected belong to jobs no one waits for
+        if state.watchers == 0 {
+            state.finished.clear();
+        }
+    }
+}
+
+/// Client for the systemd manager on D-Bus
+pub struct SystemdClient {
+    conn: Connection,
+    /// `JobRemoved` listener, started by the first [`JobWatcher`]
+    jobs: Mutex<Option<Arc<JobTracker>>>,
+    /// Whether this client called `Subscribe` (undone on drop)
+    subscribed: AtomicBool,
+}
+
+impl SystemdClient {
+    /// Connect to systemd on the system bus
+    pub fn system() -> Result<Self> {
+        let conn = Connection::system().map_err(dbus_error)?;
+        Ok(Self::with_connection(conn))
+    }
+
+    /// Use an existing connection (e.g. a private or test bus)
+    pub fn with_connection(conn: Connection) -> Self {
+        Self {
+            conn,
+            jobs: Mutex::new(None),
+            subscribed: AtomicBool::new(false),
+        }
+    }
+
+    /// List all loaded service units with their properties
+    pub fn list_services(&self) -> Result<Vec<ServiceInfo>> {
+        let units: Vec<UnitRow> = self.call_manager("ListUnits", &())?;
+
+        let mut services = Vec::new();
+        for row in units {
+            let Some(name) = row.0.strip_suffix(".service") else {
+                continue;
+            };
+            let mut service = ServiceInfo::new(name);
+            service.display_name = Some(row.1).filter(|d| !d.is_empty());
+            service.load_state = Some(row.2);
+            service.status = service_status_from_states(&row.3, &row.4);
+            service.sub_state = Some(row.4);
+
+            // Units can vanish between ListUnits and the property reads
+            let _ = self.fill_details(&mut service, &row.6);
+            services.push(service);
+        }
+        Ok(services)
+    }
+
+    /// Get a single service by name (loads the unit if needed)
+    pub fn service(&self, name: &str) -> Result<ServiceInfo> {
+        let path = self.unit_path(name)?;
+        let mut service = ServiceInfo::new(name.trim_end_matches(".service"));
+        self.fill_details(&mut service, &path)?;
+        Ok(service)
+    }
+
+    /// Read all properties of one interface on a unit object
+    pub fn unit_properties(
+        &self,
+        path: &OwnedObjectPath,
+        interface: &str,
+    ) -> Result<HashMap<String, OwnedValue>> {
+        let reply = self
+            .conn
+            .call_method(
+                Some(DESTINATION),
+                path.as_str(),
+                Some(PROPERTIES_INTERFACE),
+                "GetAll",
+                &(interface,),
+            )
+            .map_err(dbus_error)?;
+        reply.body().deserialize().map_err(dbus_error)
+    }
+
+    /// Queue a start job
+    pub fn start(&self, name: &str) -> Result<ServiceJob> {
+        self.queue_job("StartUnit", name)
+    }
+
+    /// Queue a stop job
+    pub fn stop(&self, name: &str) -> Result<ServiceJob> {
+        self.queue_job("StopUnit", name)
+    }
+
+    /// Queue a restart job
+    pub fn restart(&self, name: &str) -> Result<ServiceJob> {
+        self.queue_job("RestartUnit", name)
+    }
+
+    /// Start a service and wait for the job to finish
+    pub fn start_and_wait(&self, name: &str, timeout: Duration) -> Result<JobResult> {
+        self.run_job("StartUnit", name, timeout)
+    }
+
+    /// Stop a service and wait for the job to finish
+    pub fn stop_and_wait(&self, name: &str, timeout: Duration) -> Result<JobResult> {
+        self.run_job("StopUnit", name, timeout)
+    }
+
+    /// Restart a service and wait for the job to finish
+    pub fn restart_and_wait(&self, name: &str, timeout: Duration) -> Result<JobResult> {
+        self.run_job("RestartUnit", name, timeout)
+    }
+
+    /// Enable a service at boot and reload the manager
+    pub fn enable(&self, name: &str) -> Result<()> {
+        let files = [unit_name(name)];
+        let _: (bool, Vec<(String, String, String)>) =
+            self.call_manager("EnableUnitFiles", &(&files[..], false, false))?;
+        self.call_manager::<_, ()>("Reload", &())
+    }
+
+    /// Disable a service at boot and reload the manager
+    pub fn disable(&self, name: &str) -> Result<()> {
+        let files = [unit_name(name)];
+        let _: Vec<(String, String, String)> =
+            self.call_manager("DisableUnitFiles", &(&files[..], false))?;
+        self.call_manager::<_, ()>("Reload", &())
+    }
+
+    /// Start collecting `JobRemoved` signals
+    ///
+    /// The first watcher starts a listener thread that later watchers
+    /// share; it stays until the bus connection closes or the client is
+    /// dropped.
+    pub fn job_watcher(&self) -> Result<JobWatcher> {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(tracker) = jobs.as_ref() {
+            if !tracker.state.lock().unwrap().closed {
+                return Ok(JobWatcher::new(Arc::clone(tracker)));
+            }
+        }
+
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(MANAGER_INTERFACE)
+            .and_then(|b| b.member("JobRemoved"))
+            .and_then(|b| b.path(MANAGER_PATH))
+            .map_err(dbus_error)?
+            .build();
+        let messages =
+            MessageIterator::for_match_rule(rule, &self.conn, None).map_err(dbus_error)?;
+
+        let tracker = Arc::new(JobTracker::default());
+        let weak = Arc::downgrade(&tracker);
+        std::thread::spawn(move || listen_for_jobs(messages, weak));
+        *jobs = Some(Arc::clone(&tracker));
+        Ok(JobWatcher::new(tracker))
+    }
+
+    /// Subscribe to service state changes pushed by systemd
+    ///
+    /// The client unsubscribes when it is dropped, after which the stream
+    /// gets no further events.
+    pub fn subscribe(&self) -> Result<ServiceEvents> {
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface(PROPERTIES_INTERFACE)
+            .and_then(|b| b.member("PropertiesChanged"))
+            .and_then(|b| b.path_namespace(UNIT_PATH_PREFIX))
+            .map_err(dbus_error)?
+            .build();
+        let messages =
+            MessageIterator::for_match_rule(rule, &self.conn, None).map_err(dbus_error)?;
+
+        // systemd only emits unit signals once at least one client
+        // subscribed, and rejects a second Subscribe from the same client
+        if !self.subscribed.swap(true, Ordering::SeqCst) {
+            if let Err(e) = self.call_manager::<_, ()>("Subscribe", &()) {
+                self.subscribed.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let conn = self.conn.clone();
+        std::thread::spawn(move || {
+            // Last known (active, sub) state per service
+            let mut states: HashMap<String, (String, String)> = HashMap::new();
+            for msg in messages.flatten() {
+                let header = msg.header();
+                let Some(path) = header.path() else {
+                    continue;
+                };
+                let Some(name) = unit_name_from_path(path.as_str())
+                    .and_then(|u| u.strip_suffix(".service").map(str::to_string))
+                else {
+                    continue;
+                };
+
+                let body = msg.body();
+                let Ok((interface, changed, _)) =
+                    body.deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+                else {
+                    continue;
+                };
+                if interface != UNIT_INTERFACE {
+                    continue;
+                }
+
+                let active_state = prop_string(&changed, "ActiveState");
+                let sub_state = prop_string(&changed, "SubState");
+                if active_state.is_none() && sub_state.is_none() {
+                    continue;
+                }
+
+                let (active, sub) = match (&active_state, &sub_state) {
+                    (Some(active), Some(sub)) => (active.clone(), sub.clone()),
+                    _ => {
+                        let (active, sub) = states.get(&name).cloned().unwrap_or_else(|| {
+                            let read = |property| {
+                                unit_state(&conn, path.as_str(), property).unwrap_or_default()
+                            };
+                            (read("ActiveState"), read("SubState"))
+                        });
+                        (
+                            active_state.clone().unwrap_or(active),
+                            sub_state.clone().unwrap_or(sub),
+                        )
+                    }
+                };
+                let status = service_status_from_states(&active, &sub);
+                states.insert(name.clone(), (active, sub));
+                let event = ServiceEvent {
+                    name,
+                    active_state,
+                    sub_state,
+                    status,
+                };
+                if tx.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ServiceEvents { rx })
+    }
+
+    fn run_job(&self, method: &str, name: &str, timeout: Duration) -> Result<JobResult> {
+        let watcher = self.job_watcher()?;
+        let job = self.queue_job(method, name)?;
+        watcher.wait(&job, timeout)
+    }
+
+    fn queue_job(&self, method: &str, name: &str) -> Result<ServiceJob> {
+        let unit = unit_name(name);
+        let path: OwnedObjectPath = self.call_manager(method, &(unit.as_str(), "replace"))?;
+        Ok(ServiceJob { path, unit })
+    }
+
+    fn unit_path(&self, name: &str) -> Result<OwnedObjectPath> {
+        let unit = unit_name(name);
+        self.call_manager("GetUnit", &(unit.as_str(),))
+            .or_else(|_| self.call_manager("LoadUnit", &(unit.as_str(),)))
+    }
+
+    fn fill_details(&self, service: &mut ServiceInfo, path: &OwnedObjectPath) -> Result<()> {
+        let unit = self.unit_properties(path, UNIT_INTERFACE)?;
+
+        if let Some(description) = prop_string(&unit, "Description").filter(|d| !d.is_empty()) {
+            service.display_name = Some(description);
+        }
+        if let Some(load_state) = prop_string(&unit, "LoadState") {
+            service.load_state = Some(load_state);
+        }
+        if let (Some(active), Some(sub)) = (
+            prop_string(&unit, "ActiveState"),
+            prop_string(&unit, "SubState"),
+        ) {
+            service.status = service_status_from_states(&active, &sub);
+            service.sub_state = Some(sub);
+        }
+        if let Some(state) = prop_string(&unit, "UnitFileState") {
+            service.enabled = state == "enabled";
+            service.startup_type = startup_type_from_unit_file_state(&state);
+        }
+        service.unit_file = prop_string(&unit, "FragmentPath").filter(|p| !p.is_empty());
+        service.dependencies = ["Requires", "Wants"]
+            .iter()
+            .flat_map(|key| prop_strings(&unit, key))
+            .map(|dep| dep.trim_end_matches(".service").to_string())
+            .collect();
+        service.dependents = ["RequiredBy", "WantedBy"]
+            .iter()
+            .flat_map(|key| prop_strings(&unit, key))
+            .map(|dep| dep.trim_end_matches(".service").to_string())
+            .collect();
+        service.start_time = prop_u64(&unit, "ActiveEnterTimestamp")
+            .filter(|&us| us > 0)
+            .and_then(|us| chrono::DateTime::from_timestamp_micros(us as i64))
+            .map(|t| t.to_rfc3339());
+
+        // Service-specific properties are absent on units that are not loaded
+        if let Ok(svc) = self.unit_properties(path, SERVICE_INTERFACE) {
+            service.pid = prop_u32(&svc, "MainPID").filter(|&pid| pid > 0);
+            service.memory_bytes = prop_u64(&svc, "MemoryCurrent").filter(|&m| m < u64::MAX);
+            service.cpu_time_secs = prop_u64(&svc, "CPUUsageNSec")
+                .filter(|&ns| ns < u64::MAX)
+                .map(|ns| ns as f64 / 1_000_000_000.0);
+            service.exit_code = prop_i32(&svc, "ExecMainStatus");
+            if let Some(kind) = prop_string(&svc, "Type") {
+                service.service_type = service_type_from_str(&kind);
+            }
+            service.error_message = prop_string(&svc, "Result").filter(|r| r != "success");
+        }
+
+        Ok(())
+    }
+
+    fn call_manager<B, R>(&self, method: &str, body: &B) -> Result<R>
+    where
+        B: serde::Serialize + zbus::zvariant::DynamicType,
+        R: for<'d> zbus::zvariant::DynamicDeserialize<'d>,
+    {
+        let reply = self
+            .conn
+            .call_method(
+                Some(DESTINATION),
+                MANAGER_PATH,
+                Some(MANAGER_INTERFACE),
+                method,
+                body,
+            )
+            .map_err(dbus_error)?;
+        reply.body().deserialize().map_err(dbus_error)
+    }
+}
+
+impl Drop for SystemdClient {
+    fn drop(&mut self) {
+        // The connection outlives the client in the listener threads, so
+        // systemd would keep emitting unit signals for it
+        if *self.subscribed.get_mut() {
+            let _ = self.call_manager::<_, ()>("Unsubscribe", &());
+        }
+    }
+}
+
+/// Record `JobRemoved` results for the tracker's watchers
+///
+/// Ends at the first signal after the client and its watchers are gone,
+/// or when the connection closes.
+fn listen_for_jobs(messages: MessageIterator, tracker: Weak<JobTracker>) {
+    for msg in messages.flatten() {
+        let Some(tracker) = tracker.upgrade() else {
+            return;
+        };
+        let body = msg.body();
+        let Ok((_, path, _, result)) = body.deserialize::<(u32, OwnedObjectPath, String, String)>()
+        else {
+            continue;
+        };
+        let mut state = tracker.state.lock().unwrap();
+        if state.watchers > 0 {
+            state.finished.insert(path, result);
+            tracker.removed.notify_all();
+        }
+    }
+    if let Some(tracker) = tracker.upgrade() {
+        tracker.state.lock().unwrap().closed = true;
+        tracker.removed.notify_all();
+    }
+}
+
+/// Read one string property of a unit's `Unit` interface
+fn unit_state(conn: &Connection, path: &str, property: &str) -> Option<String> {
+    let reply = conn
+        .call_method(
+            Some(DESTINATION),
+            path,
+            Some(PROPERTIES_INTERFACE),
+            "Get",
+            &(UNIT_INTERFACE, property),
+        )
+        .ok()?;
+    let value: OwnedValue = reply.body().deserialize().ok()?;
+    value.downcast_ref::<&str>().ok().map(str::to_string)
+}
+
+/// Append `.service` unless the name already has a unit type suffix
+///
+/// Names like `php8.2-fpm` contain dots without being full unit names.
+fn unit_name(name: &str) -> String {
+    match name.rsplit_once('.') {
+        Some((_, suffix)) if UNIT_SUFFIXES.contains(&suffix) => name.to_string(),
+        _ => format!("{}.service", name),
+    }
+}
+
+/// Decode a unit object path (`.../unit/nginx_2eservice`) to its unit name
+///
+/// systemd escapes every byte outside `[A-Za-z0-9]` as `_xx` (lowercase hex).
+pub fn unit_name_from_path(path: &str) -> Option<String> {
+    let label = path.strip_prefix(UNIT_PATH_PREFIX)?.strip_prefix('/')?;
+    if label.is_empty() || label == "_" {
+        return None;
+    }
+
+    let bytes = label.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'_' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn prop_string(props: &HashMap<String, OwnedValue>, key: &str) -> Option<String> {
+    props
+        .get(key)
+        .and_then(|v| v.downcast_ref::<&str>().ok())
+        .map(str::to_string)
+}
+
+fn prop_strings(props: &HashMap<String, OwnedValue>, key: &str) -> Vec<String> {
+    props
+        .get(key)
+        .and_then(|v| v.try_clone().ok())
+        .and_then(|v| Vec::<String>::try_from(v).ok())
+        .unwrap_or_default()
+}
+
+fn prop_u32(props: &HashMap<String, OwnedValue>, key: &str) -> Option<u32> {
+    props.get(key).and_then(|v| v.downcast_ref::<u32>().ok())
+}
+
+fn prop_i32(props: &HashMap<String, OwnedValue>, key: &str) -> Option<i32> {
+    props.get(key).and_then(|v| v.downcast_ref::<i32>().ok())
+}
+
+fn prop_u64(props: &HashMap<String, OwnedValue>, key: &str) -> Option<u64> {
+    props.get(key).and_then(|v| v.downcast_ref::<u64>().ok())
+}
+
+fn dbus_error(e: impl std::fmt::Display) -> SimonError {
+    SimonError::System(format!("systemd D-Bus error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use zbus::blocking::connection::Builder;
+    use zbus::object_server::SignalEmitter;
+    use zbus::zvariant::ObjectPath;
+    use zbus::Guid;
+
+    const NGINX_PATH: &str = "/org/freedesktop/systemd1/unit/nginx_2eservice";
+    const JOB_PATH: &str = "/org/freedesktop/systemd1/job/42";
+
+    fn object_path(path: &str) -> OwnedObjectPath {
+        ObjectPath::try_from(path).unwrap().into()
+    }
+
+    #[derive(Default)]
+    struct MockManager {
+        subscribed: bool,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Manager")]
+    impl MockManager {
+        fn list_units(&self) -> Vec<UnitRow> {
+            let unit = |name: &str, path: &str| {
+                (
+                    name.to_string(),
+                    String::new(),
+                    "loaded".to_string(),
+                    "active".to_string(),
+                    "running".to_string(),
+                    String::new(),
+                    object_path(path),
+                    0,
+                    String::new(),
+                    object_path("/"),
+                )
+            };
+            vec![
+                unit("nginx.service", NGINX_PATH),
+                unit("-.mount", "/org/freedesktop/systemd1/unit/_2d_2emount"),
+            ]
+        }
+
+        fn get_unit(&self, name: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+            match name {
+                "nginx.service" => Ok(object_path(NGINX_PATH)),
+                _ => Err(zbus::fdo::Error::Failed(format!(
+                    "Unit {} not loaded.",
+                    name
+                ))),
+            }
+        }
+
+        fn load_unit(&self, name: &str) -> zbus::fdo::Result<OwnedObjectPath> {
+            self.get_unit(name)
+        }
+
+        async fn start_unit(
+            &self,
+            name: &str,
+            _mode: &str,
+            #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+        ) -> zbus::fdo::Result<OwnedObjectPath> {
+            let result = if name == "nginx.service" {
+                "done"
+            } else {
+                "failed"
+            };
+            Self::job_removed(&emitter, 42, object_path(JOB_PATH), name, result).await?;
+            Ok(object_path(JOB_PATH))
+        }
+
+        fn enable_unit_files(
+            &self,
+            files: Vec<String>,
+            _runtime: bool,
+            _force: bool,
+        ) -> (bool, Vec<(String, String, String)>) {
+            let changes = files
+                .into_iter()
+                .map(|f| ("symlink".to_string(), f, String::new()))
+                .collect();
+            (true, changes)
+        }
+
+        fn subscribe(&mut self) -> zbus::fdo::Result<()> {
+            if self.subscribed {
+                return Err(zbus::fdo::Error::Failed(
+                    "Client is already subscribed.".to_string(),
+                ));
+            }
+            self.subscribed = true;
+            Ok(())
+        }
+
+        fn unsubscribe(&mut self) -> zbus::fdo::Result<()> {
+            if !self.subscribed {
+                return Err(zbus::fdo::Error::Failed(
+                    "Client is not subscribed.".to_string(),
+                ));
+            }
+            self.subscribed = false;
+            Ok(())
+        }
+
+        fn reload(&self) {}
+
+        #[zbus(signal)]
+        async fn job_removed(
+            emitter: &SignalEmitter<'_>,
+            id: u32,
+            job: OwnedObjectPath,
+            unit: &str,
+            result: &str,
+        ) -> zbus::Result<()>;
+    }
+
+    struct MockUnit {
+        active_state: String,
+        sub_state: String,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Unit")]
+    impl MockUnit {
+        #[zbus(property)]
+        fn description(&self) -> &str {
+            "A high performance web server"
+        }
+
+        #[zbus(property)]
+        fn load_state(&self) -> &str {
+            "loaded"
+        }
+
+        #[zbus(property)]
+        fn active_state(&self) -> &str {
+            &self.active_state
+        }
+
+        #[zbus(property)]
+        fn sub_state(&self) -> &str {
+            &self.sub_state
+        }
+
+        #[zbus(property)]
+        fn unit_file_state(&self) -> &str {
+            "enabled"
+        }
+
+        #[zbus(property)]
+        fn fragment_path(&self) -> &str {
+            "/usr/lib/systemd/system/nginx.service"
+        }
+
+        #[zbus(property)]
+        fn wants(&self) -> Vec<String> {
+            vec!["network-online.target".to_string()]
+        }
+
+        #[zbus(property)]
+        fn wanted_by(&self) -> Vec<String> {
+            vec!["multi-user.target".to_string()]
+        }
+
+        #[zbus(property)]
+        fn active_enter_timestamp(&self) -> u64 {
+            1_700_000_000_000_000
+        }
+    }
+
+    struct MockService;
+
+    #[zbus::interface(name = "org.freedesktop.systemd1.Service")]
+    impl MockService {
+        #[zbus(property, name = "MainPID")]
+        fn main_pid(&self) -> u32 {
+            1234
+        }
+
+        #[zbus(property)]
+        fn memory_current(&self) -> u64 {
+            8 * 1024 * 1024
+        }
+
+        #[zbus(property, name = "CPUUsageNSec")]
+        fn cpu_usage_nsec(&self) -> u64 {
+            2_500_000_000
+        }
+
+        #[zbus(property)]
+        fn exec_main_status(&self) -> i32 {
+            0
+        }
+
+        #[zbus(property, name = "Type")]
+        fn service_type(&self) -> &str {
+            "forking"
+        }
+
+        #[zbus(property, name = "Result")]
+        fn result(&self) -> &str {
+            "success"
+        }
+    }
+
+    /// Connect a client to an in-process mock systemd over a socket pair
+    fn mock_systemd() -> (SystemdClient, Connection) {
+        let (server_sock, client_sock) = UnixStream::pair().unwrap();
+        let server = std::thread::spawn(move || {
+            Builder::async_io_unix_stream(server_sock)
+                .server(Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at(MANAGER_PATH, MockManager::default())
+                .unwrap()
+                .serve_at(
+                    NGINX_PATH,
+                    MockUnit {
+                        active_state: "active".to_string(),
+                        sub_state: "running".to_string(),
+                    },
+                )
+                .unwrap()
+                .serve_at(NGINX_PATH, MockService)
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        let client = Builder::async_io_unix_stream(client_sock)
+            .p2p()
+            .build()
+            .unwrap();
+        let server = server.join().unwrap();
+        (SystemdClient::with_connection(client), server)
+    }
+
+    #[test]
+    fn test_unit_name_from_path() {
+        assert_eq!(
+            unit_name_from_path(NGINX_PATH).as_deref(),
+            Some("nginx.service")
+        );
+        assert_eq!(
+            unit_name_from_path("/org/freedesktop/systemd1/unit/systemd_2djournald_2eservice")
+                .as_deref(),
+            Some("systemd-journald.service")
+        );
+        assert_eq!(
+            unit_name_from_path("/org/freedesktop/systemd1/unit/_"),
+            None
+        );
+        assert_eq!(unit_name_from_path("/org/freedesktop/systemd1"), None);
+    }
+
+    #[test]
+    fn test_unit_name() {
+        assert_eq!(unit_name("nginx"), "nginx.service");
+        assert_eq!(unit_name("nginx.service"), "nginx.service");
+        assert_eq!(unit_name("sshd.socket"), "sshd.socket");
+        assert_eq!(unit_name("fstrim.timer"), "fstrim.timer");
+        assert_eq!(unit_name("php8.2-fpm"), "php8.2-fpm.service");
+        assert_eq!(unit_name("foo.bar"), "foo.bar.service");
+    }
+
+    #[test]
+    fn test_list_services() {
+        let (client, _server) = mock_systemd();
+        let services = client.list_services().unwrap();
+
+        assert_eq!(services.len(), 1);
+        let nginx = &services[0];
+        assert_eq!(nginx.name, "nginx");
+        assert_eq!(nginx.status, ServiceStatus::Running);
+        assert_eq!(
+            nginx.display_name.as_deref(),
+            Some("A high performance web server")
+        );
+        assert!(nginx.enabled);
+        assert_eq!(nginx.pid, Some(1234));
+        assert_eq!(nginx.memory_bytes, Some(8 * 1024 * 1024));
+        assert_eq!(nginx.cpu_time_secs, Some(2.5));
+        assert_eq!(nginx.service_type, crate::services::ServiceType::Forking);
+        assert_eq!(nginx.dependencies, vec!["network-online.target"]);
+        assert_eq!(nginx.dependents, vec!["multi-user.target"]);
+        assert!(nginx.start_time.is_some());
+        assert!(nginx.error_message.is_none());
+    }
+
+    #[test]
+    fn test_service_lookup() {
+        let (client, _server) = mock_systemd();
+        assert_eq!(client.service("nginx").unwrap().pid, Some(1234));
+        assert!(client.service("missing").is_err());
+    }
+
+    #[test]
+    fn test_start_and_wait() {
+        let (client, _server) = mock_systemd();
+        let result = client
+            .start_and_wait("nginx", Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(result, JobResult::Done);
+        assert!(result.is_success());
+        let tracker = client.jobs.lock().unwrap().clone().unwrap();
+
+        let result = client
+            .start_and_wait("broken", Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(result, JobResult::Failed);
+
+        // Both jobs went through the same listener, which kept nothing
+        let jobs = client.jobs.lock().unwrap();
+        assert!(Arc::ptr_eq(&tracker, jobs.as_ref().unwrap()));
+        let state = tracker.state.lock().unwrap();
+        assert_eq!(state.watchers, 0);
+        assert!(state.finished.is_empty());
+    }
+
+    #[test]
+    fn test_enable() {
+        let (client, _server) = mock_systemd();
+        client.enable("nginx").unwrap();
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let (client, server) = mock_systemd();
+        let events = client.subscribe().unwrap();
+
+        let iface = server
+            .object_server()
+            .interface::<_, MockUnit>(NGINX_PATH)
+            .unwrap();
+        {
+            let mut unit = iface.get_mut();
+            unit.active_state = "failed".to_string();
+            unit.sub_state = "failed".to_string();
+        }
+        zbus::block_on(async {
+            let unit = iface.get();
+            unit.active_state_changed(iface.signal_emitter())
+                .await
+                .unwrap();
+        });
+
+        let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event.name, "nginx");
+        assert_eq!(event.active_state.as_deref(), Some("failed"));
+        assert_eq!(event.status, ServiceStatus::Failed);
+    }
+
+    #[test]
+    fn test_unsubscribe_on_drop() {
+        let (client, server) = mock_systemd();
+        let manager = server
+            .object_server()
+            .interface::<_, MockManager>(MANAGER_PATH)
+            .unwrap();
+
+        // A second stream shares the client's subscription
+        let _events = client.subscribe().unwrap();
+        let _more = client.subscribe().unwrap();
+        assert!(manager.get().subscribed);
+
+        drop(client);
+        assert!(!manager.get().subscribed);
+    }
+
+    #[test]
+    fn test_subscribe_sub_state_only() {
+        let (client, server) = mock_systemd();
+        let events = client.subscribe().unwrap();
+
+        let iface = server
+            .object_server()
+            .interface::<_, MockUnit>(NGINX_PATH)
+            .unwrap();
+        let set_sub_state = |sub_state: &str| {
+            iface.get_mut().sub_state = sub_state.to_string();
+            zbus::block_on(async {
+                let unit = iface.get();
+                unit.sub_state_changed(iface.signal_emitter())
+                    .await
+                    .unwrap();
+            });
+            events.recv_timeout(Duration::from_secs(5)).unwrap()
+        };
+
+        // The active state is read from the unit the first time
+        let event = set_sub_state("exited");
+        assert_eq!(event.active_state, None);
+        assert_eq!(event.sub_state.as_deref(), Some("exited"));
+        assert_eq!(event.status, ServiceStatus::Stopped);
+
+        let event = set_sub_state("running");
+        assert_eq!(event.status, ServiceStatus::Running);
+    }
+
+    #[test]
+    fn test_monitor_applies_events() {
+        use crate::services::ServiceMonitor;
+
+        let (client, server) = mock_systemd();
+        let mut monitor =
+            ServiceMonitor::with_systemd(client, Some(vec!["nginx".to_string()])).unwrap();
+        assert!(monitor.is_active("nginx"));
+        let events = monitor.watch().unwrap();
+
+        let iface = server
+            .object_server()
+            .interface::<_, MockUnit>(NGINX_PATH)
+            .unwrap();
+        iface.get_mut().sub_state = "exited".to_string();
+        zbus::block_on(async {
+            let unit = iface.get();
+            unit.sub_state_changed(iface.signal_emitter())
+                .await
+                .unwrap();
+        });
+        let event = events.recv_timeout(Duration::from_secs(5)).unwrap();
+        monitor.apply_event(&event);
+
+        let nginx = monitor.get_service("nginx").unwrap();
+        assert_eq!(nginx.status, ServiceStatus::Stopped);
+        assert_eq!(nginx.sub_state.as_deref(), Some("exited"));
+    }
+}
@@ -0,0 +1,308 @@
+//! InfluxDB line protocol sink
+
+use super::{Measurement, MetricSink};
+use crate::error::{Result, SimonError};
+use crate::utils::split_http_url;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// InfluxDB HTTP API port
+const DEFAULT_PORT: u16 = 8086;
+
+/// Where line protocol goes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InfluxOutput {
+    /// Standard output (e.g. for Telegraf's `execd` input)
+    Stdout,
+    /// Appended to a file
+    File(PathBuf),
+    /// InfluxDB 2.x `POST /api/v2/write`
+    Http {
+        /// Server URL (`http://influx:8086`)
+        url: String,
+        /// Organization
+        org: String,
+        /// Bucket
+        bucket: String,
+        /// API token
+        token: Option<String>,
+    },
+}
+
+/// Writes measurements as InfluxDB line protocol
+pub struct InfluxSink {
+    output: InfluxOutput,
+    file: Option<File>,
+    timeout: Duration,
+}
+
+impl InfluxSink {
+    /// Sink for `output`; opens a file output for appending
+    pub fn new(output: InfluxOutput) -> Result<Self> {
+        let file = match &output {
+            InfluxOutput::File(path) => {
+                Some(OpenOptions::new().create(true).append(true).open(path)?)
+            }
+            InfluxOutput::Http { url, .. } => {
+                split_url(url)?;
+                None
+            }
+            InfluxOutput::Stdout => None,
+        };
+        Ok(Self {
+            output,
+            file,
+            timeout: Duration::from_secs(10),
+        })
+    }
+
+    /// HTTP request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn post(
+        &self,
+        url: &str,
+        org: &str,
+        bucket: &str,
+        token: Option<&str>,
+        body: &[u8],
+    ) -> Result<()> {
+        let (authority, base) = split_url(url)?;
+        let path = format!(
+            "{}/api/v2/write?org={}&bucket={}&precision=ns",
+            base.trim_end_matches('/'),
+            percent_encode(org),
+            percent_encode(bucket)
+        );
+        let network = |e: std::io::Error| SimonError::Network(format!("{}: {}", url, e));
+
+        let addr = authority
+            .to_socket_addrs()
+            .map_err(network)?
+            .next()
+            .ok_or_else(|| SimonError::Network(format!("cannot resolve {}", authority)))?;
+        let mut stream = TcpStream::connect_timeout(&addr, self.timeout).map_err(network)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+            path,
+            authority,
+            body.len()
+        );
+        if let Some(token) = token {
+            head.push_str(&format!("Authorization: Token {}\r\n", token));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).map_err(network)?;
+        stream.write_all(body).map_err(network)?;
+
+        let mut reader = BufReader::new(stream);
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line).map_err(network)?;
+        let status: u16 = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| SimonError::Network(format!("{}: malformed response", url)))?;
+        if (200..300).contains(&status) {
+            return Ok(());
+        }
+        // Connection: close, so the rest is headers and the error body
+        let mut rest = String::new();
+        let _ = reader.take(64 * 1024).read_to_string(&mut rest);
+        let detail = rest.split("\r\n\r\n").nth(1).unwrap_or("").trim();
+        Err(SimonError::Network(format!(
+            "{} returned HTTP {}: {}",
+            url,
+            status,
+            detail.chars().take(200).collect::<String>()
+        )))
+    }
+}
+
+impl MetricSink for InfluxSink {
+    fn name(&self) -> String {
+        match &self.output {
+            InfluxOutput::Stdout => "influxdb stdout".to_string(),
+            InfluxOutput::File(path) => format!("influxdb {}", path.display()),
+            InfluxOutput::Http { url, .. } => format!("influxdb {}", url),
+        }
+    }
+
+    fn write(&mut self, measurements: &[Measurement], timestamp: SystemTime) -> Result<()> {
+        let lines = line_protocol(measurements, timestamp);
+        if lines.is_empty() {
+            return Ok(());
+        }
+        match &self.output {
+            InfluxOutput::Stdout => {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(lines.as_bytes())?;
+                stdout.flush()?;
+            }
+            InfluxOutput::File(_) => {
+                if let Some(file) = &mut self.file {
+                    file.write_all(lines.as_bytes())?;
+                }
+            }
+            InfluxOutput::Http {
+                url,
+                org,
+                bucket,
+                token,
+            } => self.post(url, org, bucket, token.as_deref(), lines.as_bytes())?,
+        }
+        Ok(())
+    }
+}
+
+/// Encode measurements as line protocol, one line each, nanosecond timestamps
+///
+/// Tags are sorted by key and empty tags dropped; non-finite fields are
+/// skipped, and measurements left without fields produce no line.
+pub fn line_protocol(measurements: &[Measurement], timestamp: SystemTime) -> String {
+    let nanos = timestamp
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let mut out = String::new();
+    for measurement in measurements {
+        let fields: Vec<String> = measurement
+            .fields
+            .iter()
+            .filter(|(_, value)| value.is_finite())
+            .map(|(key, value)| format!("{}={}", escape(key, ",= "), value))
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let mut tags: Vec<_> = measurement
+            .tags
+            .iter()
+            .filter(|(key, value)| !key.is_empty() && !value.is_empty())
+            .collect();
+        tags.sort_by(|a, b| a.0.cmp(&b.0));
+
+        out.push_str(&escape(&measurement.name, ", "));
+        for (key, value) in tags {
+            out.push_str(&format!(",{}={}", escape(key, ",= "), escape(value, ",= ")));
+        }
+        out.push_str(&format!(" {} {}\n", fields.join(","), nanos));
+    }
+    out
+}
+
+/// Backslash-escape `special` characters; newlines become spaces (escaped)
+fn escape(s: &str, special: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        let c = if c == '\n' || c == '\r' { ' ' } else { c };
+        if special.contains(c) || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Split `http://host[:port][/base]` into `host:port` and `/base`
+fn split_url(url: &str) -> Result<(String, String)> {
+    split_http_url(url, DEFAULT_PORT).map_err(SimonError::Configuration)
+}
+
+fn percent_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_line_protocol() {
+        let timestamp = UNIX_EPOCH + Duration::from_nanos(1_700_000_000_123_456_789);
+        let measurements = vec![
+            Measurement::new("disk")
+                .with_tag("mount_point", "/mnt/my data")
+                .with_tag("device", "sda1")
+                .with_tag("filesystem", "")
+                .with_field("usage_percent", 42.5)
+                .with_field("used_bytes", 1024.0),
+            Measurement::new("gpu").with_field("temperature", f64::NAN),
+        ];
+        assert_eq!(
+            line_protocol(&measurements, timestamp),
+            "disk,device=sda1,mount_point=/mnt/my\\ data usage_percent=42.5,used_bytes=1024 1700000000123456789\n"
+        );
+        assert_eq!(escape("a,b=c d\\", ",= "), "a\\,b\\=c\\ d\\\\");
+    }
+
+    #[test]
+    fn test_http_write() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.strip_prefix("Content-Length: ") {
+                    length = value.trim().parse().unwrap();
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            (&stream)
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            (head, String::from_utf8(body).unwrap())
+        });
+
+        let mut sink = InfluxSink::new(InfluxOutput::Http {
+            url,
+            org: "my lab".into(),
+            bucket: "simon".into(),
+            token: Some("t0k3n".into()),
+        })
+        .unwrap();
+        let measurement = Measurement::new("cpu").with_field("utilization", 50.0);
+        sink.write(&[measurement], UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap();
+
+        let (head, body) = server.join().unwrap();
+        assert!(head
+            .starts_with("POST /api/v2/write?org=my%20lab&bucket=simon&precision=ns HTTP/1.1\r\n"));
+        assert!(head.contains("Authorization: Token t0k3n\r\n"));
+        assert_eq!(body, "cpu utilization=50 1000000000\n");
+
+        assert!(InfluxSink::new(InfluxOutput::Http {
+            url: "https://influx".into(),
+            org: String::new(),
+            bucket: "simon".into(),
+            token: None,
+        })
+        .is_err());
+    }
+}
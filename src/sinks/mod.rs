@@ -0,0 +1,702 @@
+//! Pluggable metric output sinks (InfluxDB line protocol, StatsD)
+//!
+//! Samples are flattened into [`Measurement`]s — a measurement name, tags
+//! and numeric fields, addressed in filters as `measurement.field`
+//! (`gpu.utilization`, `sensors.temperature`) — and written to any number of
+//! [`MetricSink`]s:
+//!
+//! - [`InfluxSink`]: InfluxDB line protocol to stdout, a file, or an
+//!   InfluxDB 2.x `/api/v2/write` endpoint (what Telegraf's `influxdb_v2`
+//!   output speaks)
+//! - [`StatsdSink`]: StatsD gauges over UDP, with DogStatsD `#tag:value`
+//!   tags or tag values folded into Graphite-style names
+//!
+//! Sinks are configured as `[[sinks]]` tables in the TOML config, each with
+//! its own interval, extra tags and include/exclude filters:
+//!
+//! ```toml
+//! [[sinks]]
+//! type = "influxdb"
+//! output = "http"
+//! url = "http://influx:8086"
+//! org = "lab"
+//! bucket = "simon"
+//! token = "..."
+//! interval_ms = 10000
+//! include = ["gpu.*", "cpu.utilization"]
+//!
+//! [[sinks]]
+//! type = "statsd"
+//! address = "127.0.0.1:8125"
+//! dogstatsd = true
+//! exclude = ["disk.*"]
+//! tags = { rack = "a12" }
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::backend::{BackendConfig, MonitoringBackend};
+//! use simon::config::Config;
+//! use simon::sinks::{flatten_state, SinkRunner};
+//! use std::time::{Duration, Instant, SystemTime};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?;
+//! let mut runner = SinkRunner::from_config(&config.sinks, Duration::from_secs(1))?;
+//! let mut backend = MonitoringBackend::with_config(BackendConfig::without_agent())?;
+//!
+//! loop {
+//!     backend.update()?;
+//!     let measurements = flatten_state(&backend.get_full_system_state());
+//!     for (sink, error) in runner.publish(Instant::now(), SystemTime::now(), &measurements) {
+//!         eprintln!("{}: {}", sink, error);
+//!     }
+//!     std::thread::sleep(runner.until_next(Instant::now()));
+//! }
+//! # }
+//! ```
+
+mod influx;
+mod statsd;
+
+pub use influx::{InfluxOutput, InfluxSink};
+pub use statsd::StatsdSink;
+
+use crate::backend::FullSystemState;
+use crate::error::{Result, SimonError};
+use crate::hwmon::{HwSensor, HwSensorType, HwType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// One measurement: a name, identifying tags and numeric fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    /// Measurement name (`cpu`, `gpu`, `sensors`)
+    pub name: String,
+    /// Tags identifying the series
+    pub tags: Vec<(String, String)>,
+    /// Field values
+    pub fields: Vec<(String, f64)>,
+}
+
+impl Measurement {
+    /// Measurement without tags or fields
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            tags: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a tag
+    pub fn with_tag(mut self, key: &str, value: impl Into<String>) -> Self {
+        self.tags.push((key.to_string(), value.into()));
+        self
+    }
+
+    /// Add a field
+    pub fn with_field(mut self, key: &str, value: f64) -> Self {
+        self.fields.push((key.to_string(), value));
+        self
+    }
+
+    /// Add a field if there is a value
+    pub fn with_optional(self, key: &str, value: Option<f64>) -> Self {
+        match value {
+            Some(value) => self.with_field(key, value),
+            None => self,
+        }
+    }
+}
+
+/// Flatten a [`FullSystemState`] into measurements
+///
+/// | Measurement | Tags | Fields |
+/// |-------------|------|--------|
+/// | `cpu` | | `utilization`, `temperature`, `frequency_mhz` |
+/// | `cpu_core` | `core` | `utilization` |
+/// | `memory` | | `total_bytes`, `used_bytes`, `available_bytes`, `usage_percent`, `swap_*` |
+/// | `gpu` | `index`, `name`, `vendor`, `type` | `utilization`, `memory_*`, `temperature`, `power_*`, `clock_mhz`, `memory_clock_mhz`, `process_count` |
+/// | `disk` | `device`, `mount_point`, `filesystem` | `total_bytes`, `used_bytes`, `usage_percent` |
+/// | `net` | `interface` | `up`, `rx_bytes`, `tx_bytes`, `rx_rate`, `tx_rate` |
+/// | `system` | | `uptime_secs` |
+///
+/// Every measurement is tagged with `host` when the hostname is known.
+pub fn flatten_state(state: &FullSystemState) -> Vec<Measurement> {
+    let mut measurements = Vec::new();
+
+    if let Some(cpu) = &state.cpu {
+        measurements.push(
+            Measurement::new("cpu")
+                .with_field("utilization", cpu.utilization as f64)
+                .with_optional("temperature", cpu.temperature.map(f64::from))
+                .with_optional("frequency_mhz", cpu.frequency_mhz.map(|f| f as f64)),
+        );
+        for (core, usage) in cpu.per_core_usage.iter().enumerate() {
+            measurements.push(
+                Measurement::new("cpu_core")
+                    .with_tag("core", core.to_string())
+                    .with_field("utilization", *usage as f64),
+            );
+        }
+    }
+
+    if let Some(memory) = &state.memory {
+        measurements.push(
+            Measurement::new("memory")
+                .with_field("total_bytes", memory.total_bytes as f64)
+                .with_field("used_bytes", memory.used_bytes as f64)
+                .with_field("available_bytes", memory.available_bytes as f64)
+                .with_field("usage_percent", memory.usage_percent as f64)
+                .with_field("swap_total_bytes", memory.swap_total_bytes as f64)
+                .with_field("swap_used_bytes", memory.swap_used_bytes as f64)
+                .with_field("swap_usage_percent", memory.swap_usage_percent as f64),
+        );
+    }
+
+    for gpu in &state.accelerators {
+        measurements.push(
+            Measurement::new("gpu")
+                .with_tag("index", gpu.index.to_string())
+                .with_tag("name", gpu.name.as_str())
+                .with_tag("vendor", gpu.vendor.as_str())
+                .with_tag("type", gpu.accel_type.as_str())
+                .with_field("utilization", gpu.utilization as f64)
+                .with_field("memory_used_bytes", gpu.memory_used_bytes as f64)
+                .with_field("memory_total_bytes", gpu.memory_total_bytes as f64)
+                .with_field("memory_usage_percent", gpu.memory_usage_percent as f64)
+                .with_optional("temperature", gpu.temperature.map(f64::from))
+                .with_optional("power_watts", gpu.power_watts.map(f64::from))
+                .with_optional("power_limit_watts", gpu.power_limit_watts.map(f64::from))
+                .with_optional("clock_mhz", gpu.clock_mhz.map(f64::from))
+                .with_optional("memory_clock_mhz", gpu.memory_clock_mhz.map(f64::from))
+                .with_field("process_count", gpu.process_count as f64),
+        );
+    }
+
+    for disk in &state.disks {
+        measurements.push(
+            Measurement::new("disk")
+                .with_tag("device", disk.name.as_str())
+                .with_tag("mount_point", disk.mount_point.as_str())
+                .with_tag("filesystem", disk.filesystem.as_str())
+                .with_field("total_bytes", disk.total_bytes as f64)
+                .with_field("used_bytes", disk.used_bytes as f64)
+                .with_field("usage_percent", disk.usage_percent as f64),
+        );
+    }
+
+    for interface in &state.network {
+        measurements.push(
+            Measurement::new("net")
+                .with_tag("interface", interface.name.as_str())
+                .with_field("up", if interface.is_up { 1.0 } else { 0.0 })
+                .with_field("rx_bytes", interface.rx_bytes as f64)
+                .with_field("tx_bytes", interface.tx_bytes as f64)
+                .with_field("rx_rate", interface.rx_rate)
+                .with_field("tx_rate", interface.tx_rate),
+        );
+    }
+
+    if let Some(system) = &state.system {
+        measurements
+            .push(Measurement::new("system").with_field("uptime_secs", system.uptime_secs as f64));
+        if !system.hostname.is_empty() {
+            for measurement in &mut measurements {
+                measurement
+                    .tags
+                    .insert(0, ("host".to_string(), system.hostname.clone()));
+            }
+        }
+    }
+    measurements
+}
+
+/// Flatten hwmon sensors into `sensors` measurements
+///
+/// Tagged with `hardware` (`cpu`, `gpu`, `motherboard`, ...) and `sensor`;
+/// the field is named after the sensor type (`temperature`, `fan`,
+/// `voltage`, `power`, ...).
+pub fn flatten_sensors(sensors: &[HwSensor]) -> Vec<Measurement> {
+    sensors
+        .iter()
+        .map(|sensor| {
+            Measurement::new("sensors")
+                .with_tag("hardware", hardware_name(sensor.hardware_type))
+                .with_tag("sensor", sensor.name.as_str())
+                .with_field(sensor_field(sensor.sensor_type), sensor.value as f64)
+        })
+        .collect()
+}
+
+fn hardware_name(hw_type: HwType) -> &'static str {
+    match hw_type {
+        HwType::Cpu => "cpu",
+        HwType::Gpu => "gpu",
+        HwType::Motherboard => "motherboard",
+        HwType::Storage => "storage",
+        HwType::Memory => "memory",
+        HwType::Network => "network",
+        HwType::Psu => "psu",
+        HwType::Other => "other",
+    }
+}
+
+fn sensor_field(sensor_type: HwSensorType) -> &'static str {
+    match sensor_type {
+        HwSensorType::Temperature => "temperature",
+        HwSensorType::Voltage => "voltage",
+        HwSensorType::Fan => "fan",
+        HwSensorType::Power => "power",
+        HwSensorType::Clock => "clock",
+        HwSensorType::Load => "load",
+        HwSensorType::Data => "data",
+        HwSensorType::SmallData => "small_data",
+        HwSensorType::Throughput => "throughput",
+        HwSensorType::Control => "control",
+        HwSensorType::Energy => "energy",
+    }
+}
+
+/// Include/exclude patterns over `measurement.field` names
+///
+/// Patterns may use `*` as a wildcard. A field passes when it matches an
+/// include pattern (or there are none) and no exclude pattern.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricFilter {
+    /// Patterns to keep; empty keeps everything
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Patterns to drop
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl MetricFilter {
+    /// Whether `measurement.field` passes
+    pub fn matches(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| glob_match(p, name)))
+            && !self.exclude.iter().any(|p| glob_match(p, name))
+    }
+
+    /// Drop filtered fields, and measurements left without fields
+    pub fn apply(&self, measurements: &[Measurement]) -> Vec<Measurement> {
+        measurements
+            .iter()
+            .filter_map(|measurement| {
+                let fields: Vec<_> = measurement
+                    .fields
+                    .iter()
+                    .filter(|(field, _)| self.matches(&format!("{}.{}", measurement.name, field)))
+                    .cloned()
+                    .collect();
+                (!fields.is_empty()).then(|| Measurement {
+                    fields,
+                    ..measurement.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any run of characters
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == name;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if name.len() < first.len() + last.len() || !name.starts_with(first) || !name.ends_with(last) {
+        return false;
+    }
+    let mut rest = &name[first.len()..name.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// A destination for measurements
+pub trait MetricSink: Send {
+    /// Short description for logs (`influxdb http://influx:8086`)
+    fn name(&self) -> String;
+
+    /// Write measurements taken at `timestamp`
+    fn write(&mut self, measurements: &[Measurement], timestamp: SystemTime) -> Result<()>;
+}
+
+/// One `[[sinks]]` entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SinkConfig {
+    /// Sink type and its settings
+    #[serde(flatten)]
+    pub kind: SinkKind,
+    /// Write interval (default: the general update interval)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_ms: Option<u64>,
+    /// Metric include/exclude patterns
+    #[serde(flatten)]
+    pub filter: MetricFilter,
+    /// Extra tags added to every measurement
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
+}
+
+/// Sink type and its settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkKind {
+    /// InfluxDB line protocol
+    Influxdb {
+        /// `stdout`, `file` or `http`
+        #[serde(default = "default_influx_output")]
+        output: String,
+        /// File path for `output = "file"`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+        /// Server URL for `output = "http"` (`http://influx:8086`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        /// Organization
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        /// Bucket
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bucket: Option<String>,
+        /// API token
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// StatsD or DogStatsD over UDP
+    Statsd {
+        /// Server address
+        #[serde(default = "default_statsd_address")]
+        address: String,
+        /// Prefix of every metric name
+        #[serde(default = "default_statsd_prefix")]
+        prefix: String,
+        /// Send tags the DogStatsD way instead of folding them into names
+        #[serde(default)]
+        dogstatsd: bool,
+    },
+}
+
+fn default_influx_output() -> String {
+    "stdout".to_string()
+}
+
+fn default_statsd_address() -> String {
+    "127.0.0.1:8125".to_string()
+}
+
+fn default_statsd_prefix() -> String {
+    "simon".to_string()
+}
+
+impl SinkConfig {
+    /// Create the sink
+    pub fn build(&self) -> Result<Box<dyn MetricSink>> {
+        match &self.kind {
+            SinkKind::Influxdb {
+                output,
+                path,
+                url,
+                org,
+                bucket,
+                token,
+            } => {
+                let output = match output.as_str() {
+                    "stdout" => InfluxOutput::Stdout,
+                    "file" => InfluxOutput::File(path.clone().ok_or_else(|| {
+                        SimonError::Configuration("influxdb file sink needs a path".into())
+                    })?),
+                    "http" => InfluxOutput::Http {
+                        url: url.clone().ok_or_else(|| {
+                            SimonError::Configuration("influxdb http sink needs a url".into())
+                        })?,
+                        org: org.clone().unwrap_or_default(),
+                        bucket: bucket.clone().ok_or_else(|| {
+                            SimonError::Configuration("influxdb http sink needs a bucket".into())
+                        })?,
+                        token: token.clone(),
+                    },
+                    other => {
+                        return Err(SimonError::Configuration(format!(
+                            "unknown influxdb output '{}' (expected stdout, file or http)",
+                            other
+                        )))
+                    }
+                };
+                Ok(Box::new(InfluxSink::new(output)?))
+            }
+            SinkKind::Statsd {
+                address,
+                prefix,
+                dogstatsd,
+            } => Ok(Box::new(
+                StatsdSink::new(address)?
+                    .with_prefix(prefix)
+                    .with_dogstatsd(*dogstatsd),
+            )),
+        }
+    }
+}
+
+/// A sink with its schedule, filter and extra tags
+struct ScheduledSink {
+    sink: Box<dyn MetricSink>,
+    filter: MetricFilter,
+    tags: Vec<(String, String)>,
+    interval: Duration,
+    next_due: Option<Instant>,
+}
+
+/// Writes measurements to each sink on its own interval
+#[derive(Default)]
+pub struct SinkRunner {
+    sinks: Vec<ScheduledSink>,
+}
+
+impl SinkRunner {
+    /// Runner without sinks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build every configured sink; `default_interval` applies to sinks
+    /// without `interval_ms`
+    pub fn from_config(configs: &[SinkConfig], default_interval: Duration) -> Result<Self> {
+        let mut runner = Self::new();
+        for config in configs {
+            let interval = config
+                .interval_ms
+                .map_or(default_interval, Duration::from_millis);
+            runner = runner.with_sink(config.build()?, config.filter.clone(), interval);
+            if let Some(scheduled) = runner.sinks.last_mut() {
+                scheduled.tags = config
+                    .tags
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+            }
+        }
+        Ok(runner)
+    }
+
+    /// Add a sink
+    pub fn with_sink(
+        mut self,
+        sink: Box<dyn MetricSink>,
+        filter: MetricFilter,
+        interval: Duration,
+    ) -> Self {
+        self.sinks.push(ScheduledSink {
+            sink,
+            filter,
+            tags: Vec::new(),
+            interval,
+            next_due: None,
+        });
+        self
+    }
+
+    /// Whether there are no sinks
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Shortest sink interval, i.e. how often to sample
+    pub fn min_interval(&self) -> Option<Duration> {
+        self.sinks.iter().map(|s| s.interval).min()
+    }
+
+    /// Time until the next sink is due
+    pub fn until_next(&self, now: Instant) -> Duration {
+        self.sinks
+            .iter()
+            .map(|s| {
+                s.next_due
+                    .map_or(Duration::ZERO, |due| due.saturating_duration_since(now))
+            })
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+
+    /// Write to every sink that is due; returns the sinks that failed
+    ///
+    /// A failing sink is retried on its next interval.
+    pub fn publish(
+        &mut self,
+        now: Instant,
+        timestamp: SystemTime,
+        measurements: &[Measurement],
+    ) -> Vec<(String, SimonError)> {
+        let mut errors = Vec::new();
+        for scheduled in &mut self.sinks {
+            if scheduled.next_due.is_some_and(|due| now < due) {
+                continue;
+            }
+            scheduled.next_due = Some(now + scheduled.interval);
+
+            let mut filtered = scheduled.filter.apply(measurements);
+            for measurement in &mut filtered {
+                measurement.tags.extend(scheduled.tags.iter().cloned());
+            }
+            if let Err(e) = scheduled.sink.write(&filtered, timestamp) {
+                errors.push((scheduled.sink.name(), e));
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{CpuState, SystemInfoState};
+    use std::sync::{Arc, Mutex};
+
+    /// Sink recording what it was given
+    struct Recorder(Arc<Mutex<Vec<Vec<Measurement>>>>);
+
+    impl MetricSink for Recorder {
+        fn name(&self) -> String {
+            "recorder".into()
+        }
+
+        fn write(&mut self, measurements: &[Measurement], _: SystemTime) -> Result<()> {
+            self.0.lock().unwrap().push(measurements.to_vec());
+            Ok(())
+        }
+    }
+
+    fn state() -> FullSystemState {
+        let mut state = FullSystemState::empty();
+        state.cpu = Some(CpuState {
+            name: "Test CPU".into(),
+            cores: 2,
+            threads: 2,
+            utilization: 50.0,
+            temperature: None,
+            frequency_mhz: Some(3000),
+            per_core_usage: vec![40.0, 60.0],
+        });
+        state.system = Some(SystemInfoState {
+            hostname: "node01".into(),
+            os: "Linux".into(),
+            kernel: "6.1".into(),
+            uptime_secs: 100,
+        });
+        state
+    }
+
+    #[test]
+    fn test_flatten_and_filter() {
+        let measurements = flatten_state(&state());
+        assert_eq!(measurements.len(), 4);
+        assert_eq!(
+            measurements[0],
+            Measurement::new("cpu")
+                .with_tag("host", "node01")
+                .with_field("utilization", 50.0)
+                .with_field("frequency_mhz", 3000.0)
+        );
+
+        let filter = MetricFilter {
+            include: vec!["cpu*.utilization".into(), "system.*".into()],
+            exclude: vec!["cpu_core.*".into()],
+        };
+        let filtered = filter.apply(&measurements);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].fields, vec![("utilization".to_string(), 50.0)]);
+        assert_eq!(filtered[1].name, "system");
+
+        assert!(glob_match("*", "gpu.utilization"));
+        assert!(glob_match("gpu.*_bytes", "gpu.memory_used_bytes"));
+        assert!(!glob_match("gpu.*_bytes", "gpu.utilization"));
+        assert!(!glob_match("a*a", "a"));
+    }
+
+    #[test]
+    fn test_sink_config_toml() {
+        let config: crate::config::Config = toml::from_str(
+            r#"
+            [general]
+            [gpu]
+            [process]
+            [chart]
+
+            [[sinks]]
+            type = "influxdb"
+            output = "http"
+            url = "http://influx:8086"
+            bucket = "simon"
+            interval_ms = 10000
+            include = ["gpu.*"]
+
+            [[sinks]]
+            type = "statsd"
+            dogstatsd = true
+            exclude = ["disk.*"]
+            tags = { rack = "a12" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.sinks.len(), 2);
+        assert_eq!(config.sinks[0].interval_ms, Some(10000));
+        assert_eq!(config.sinks[0].filter.include, vec!["gpu.*"]);
+        assert!(matches!(
+            &config.sinks[0].kind,
+            SinkKind::Influxdb { url: Some(url), .. } if url == "http://influx:8086"
+        ));
+        assert_eq!(
+            config.sinks[1].kind,
+            SinkKind::Statsd {
+                address: "127.0.0.1:8125".into(),
+                prefix: "simon".into(),
+                dogstatsd: true,
+            }
+        );
+        assert_eq!(
+            config.sinks[1].tags.get("rack").map(String::as_str),
+            Some("a12")
+        );
+    }
+
+    #[test]
+    fn test_runner_intervals() {
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let mut runner = SinkRunner::new().with_sink(
+            Box::new(Recorder(Arc::clone(&written))),
+            MetricFilter {
+                include: vec!["system.*".into()],
+                exclude: Vec::new(),
+            },
+            Duration::from_secs(10),
+        );
+        let measurements = flatten_state(&state());
+        let start = Instant::now();
+
+        for offset in [0, 5, 10] {
+            let now = start + Duration::from_secs(offset);
+            assert!(runner
+                .publish(now, SystemTime::now(), &measurements)
+                .is_empty());
+        }
+        let written = written.lock().unwrap();
+        assert_eq!(written.len(), 2);
+        assert_eq!(written[0].len(), 1);
+        assert_eq!(
+            runner.until_next(start + Duration::from_secs(12)),
+            Duration::from_secs(8)
+        );
+    }
+}
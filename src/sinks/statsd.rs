@@ -0,0 +1,214 @@
+//! StatsD / DogStatsD sink
+
+use super::{Measurement, MetricSink};
+use crate::error::{Result, SimonError};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::SystemTime;
+
+/// Default payload limit, safe for a 1500-byte MTU
+const DEFAULT_MAX_PACKET: usize = 1432;
+
+/// Sends measurements as StatsD gauges over UDP
+///
+/// Each field becomes `<prefix>.<measurement>.<field>`. With DogStatsD the
+/// tags are sent as `|#key:value`; plain StatsD has no tags, so tag values
+/// are folded into the name (`simon.gpu.node01.0.NVIDIA_A100.utilization`).
+pub struct StatsdSink {
+    socket: UdpSocket,
+    address: SocketAddr,
+    prefix: String,
+    dogstatsd: bool,
+    max_packet: usize,
+}
+
+impl StatsdSink {
+    /// Sink sending to `address` (`host:port`)
+    pub fn new(address: &str) -> Result<Self> {
+        let address = address
+            .to_socket_addrs()
+            .map_err(|e| SimonError::Configuration(format!("statsd address {}: {}", address, e)))?
+            .next()
+            .ok_or_else(|| {
+                SimonError::Configuration(format!("cannot resolve statsd address {}", address))
+            })?;
+        let bind = if address.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind)?;
+        Ok(Self {
+            socket,
+            address,
+            prefix: "simon".to_string(),
+            dogstatsd: false,
+            max_packet: DEFAULT_MAX_PACKET,
+        })
+    }
+
+    /// Prefix of every metric name (empty for none)
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.trim_matches('.').to_string();
+        self
+    }
+
+    /// Send DogStatsD tags instead of folding tag values into names
+    pub fn with_dogstatsd(mut self, dogstatsd: bool) -> Self {
+        self.dogstatsd = dogstatsd;
+        self
+    }
+
+    /// Largest datagram payload; several metrics share a datagram up to it
+    pub fn with_max_packet_size(mut self, bytes: usize) -> Self {
+        self.max_packet = bytes.max(64);
+        self
+    }
+
+    /// StatsD lines for `measurements`
+    pub fn lines(&self, measurements: &[Measurement]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for measurement in measurements {
+            let mut path: Vec<String> = Vec::new();
+            if !self.prefix.is_empty() {
+                path.push(self.prefix.clone());
+            }
+            path.push(sanitize(&measurement.name));
+            let mut suffix = "|g".to_string();
+            if self.dogstatsd {
+                let tags: Vec<String> = measurement
+                    .tags
+                    .iter()
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(key, value)| format!("{}:{}", sanitize_tag(key), sanitize_tag(value)))
+                    .collect();
+                if !tags.is_empty() {
+                    suffix.push_str(&format!("|#{}", tags.join(",")));
+                }
+            } else {
+                path.extend(
+                    measurement
+                        .tags
+                        .iter()
+                        .filter(|(_, value)| !value.is_empty())
+                        .map(|(_, value)| sanitize(value)),
+                );
+            }
+
+            for (field, value) in &measurement.fields {
+                if !value.is_finite() {
+                    continue;
+                }
+                let name = format!("{}.{}", path.join("."), sanitize(field));
+                // A leading sign makes a gauge relative, so reset to zero first
+                if *value < 0.0 {
+                    lines.push(format!("{}:0{}", name, suffix));
+                }
+                lines.push(format!("{}:{}{}", name, value, suffix));
+            }
+        }
+        lines
+    }
+}
+
+impl MetricSink for StatsdSink {
+    fn name(&self) -> String {
+        let kind = if self.dogstatsd {
+            "dogstatsd"
+        } else {
+            "statsd"
+        };
+        format!("{} {}", kind, self.address)
+    }
+
+    fn write(&mut self, measurements: &[Measurement], _timestamp: SystemTime) -> Result<()> {
+        let mut packet = String::new();
+        for line in self.lines(measurements) {
+            if !packet.is_empty() && packet.len() + 1 + line.len() > self.max_packet {
+                self.socket.send_to(packet.as_bytes(), self.address)?;
+                packet.clear();
+            }
+            if !packet.is_empty() {
+                packet.push('\n');
+            }
+            packet.push_str(&line);
+        }
+        if !packet.is_empty() {
+            self.socket.send_to(packet.as_bytes(), self.address)?;
+        }
+        Ok(())
+    }
+}
+
+/// One name component: characters other than `[A-Za-z0-9_-]` become `_`
+fn sanitize(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// DogStatsD tag text: no separators (`,` `|` `#` `:`) or whitespace
+fn sanitize_tag(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c == ',' || c == '|' || c == '#' || c == ':' || c.is_whitespace() {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statsd_packets() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        let address = server.local_addr().unwrap().to_string();
+        let measurements = vec![
+            Measurement::new("gpu")
+                .with_tag("index", "0")
+                .with_tag("name", "NVIDIA A100")
+                .with_field("utilization", 87.5)
+                .with_field("temperature", f64::NAN),
+            Measurement::new("sensors")
+                .with_tag("sensor", "in0")
+                .with_field("voltage", -1.5),
+        ];
+
+        let mut dogstatsd = StatsdSink::new(&address).unwrap().with_dogstatsd(true);
+        dogstatsd.write(&measurements, SystemTime::now()).unwrap();
+        let mut buf = [0u8; 2048];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            "simon.gpu.utilization:87.5|g|#index:0,name:NVIDIA_A100\n\
+             simon.sensors.voltage:0|g|#sensor:in0\n\
+             simon.sensors.voltage:-1.5|g|#sensor:in0"
+        );
+
+        // Plain StatsD folds tags into the name, and packets are size-capped
+        let mut statsd = StatsdSink::new(&address)
+            .unwrap()
+            .with_prefix("lab.")
+            .with_max_packet_size(64);
+        statsd.write(&measurements[..1], SystemTime::now()).unwrap();
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            "lab.gpu.0.NVIDIA_A100.utilization:87.5|g"
+        );
+        assert_eq!(statsd.lines(&measurements).len(), 3);
+    }
+}
@@ -0,0 +1,676 @@
+//! Thermal and power throttling detection
+//!
+//! Answers "is this machine throttling, and why?" across CPUs and GPUs:
+//!
+//! - **CPU**: the kernel's `thermal_throttle` counters
+//!   (`/sys/devices/system/cpu/cpu*/thermal_throttle/{core,package}_throttle_*`),
+//!   which count PROCHOT events per core and per package along with the
+//!   total and longest time spent throttled
+//! - **NVIDIA**: NVML clock event (throttle) reasons and cumulative
+//!   violation times per policy
+//! - **AMD**: the ASIC-independent throttle status from `gpu_metrics`
+//!
+//! Counter-based sources report events and durations directly. For
+//! instantaneous sources (a bitmask of currently active reasons) the
+//! [`ThrottleMonitor`] accumulates how long each cause was active between
+//! updates.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::throttling::ThrottleMonitor;
+//! use simon::gpu::GpuCollection;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut monitor = ThrottleMonitor::new();
+//! let gpus = GpuCollection::auto_detect()?;
+//!
+//! monitor.update()?;
+//! monitor.update_gpus(&gpus);
+//!
+//! for device in monitor.devices() {
+//!     if device.is_throttled() {
+//!         println!("{} throttled now: {:?}", device.device, device.active);
+//!     }
+//!     for stats in &device.causes {
+//!         println!(
+//!             "  {}: {:?} events, {:.1}s total",
+//!             stats.cause,
+//!             stats.events,
+//!             stats.total_time.as_secs_f64()
+//!         );
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::gpu::amdgpu_metrics::GpuMetrics;
+use crate::gpu::GpuCollection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Default CPU sysfs root
+const CPU_SYSFS_PATH: &str = "/sys/devices/system/cpu";
+
+/// Why a device is running below its requested clocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ThrottleCause {
+    /// Temperature limit (software or hardware thermal slowdown, PROCHOT)
+    Thermal,
+    /// Power limit (power cap, PPT)
+    PowerCap,
+    /// Current limit (TDC/EDC, NVIDIA fast-trigger slowdown)
+    Current,
+    /// Clocks held back to match other GPUs in a sync boost group
+    SyncBoost,
+    /// Hardware slowdown signalled by the board (e.g. power supply or
+    /// external sensor)
+    HardwareSlowdown,
+    /// External power brake assertion
+    PowerBrake,
+    /// Reliability or voltage limit
+    Reliability,
+    /// Vendor reported a reason that doesn't map to the above
+    Other,
+}
+
+impl ThrottleCause {
+    /// Short lowercase name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThrottleCause::Thermal => "thermal",
+            ThrottleCause::PowerCap => "power cap",
+            ThrottleCause::Current => "current",
+            ThrottleCause::SyncBoost => "sync boost",
+            ThrottleCause::HardwareSlowdown => "hw slowdown",
+            ThrottleCause::PowerBrake => "power brake",
+            ThrottleCause::Reliability => "reliability",
+            ThrottleCause::Other => "other",
+        }
+    }
+}
+
+impl std::fmt::Display for ThrottleCause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Throttle state reported by a GPU backend
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GpuThrottleStatus {
+    /// Causes active right now
+    pub active: Vec<ThrottleCause>,
+    /// Cumulative time spent throttled per cause, when the driver tracks it
+    pub violation_time: Vec<(ThrottleCause, Duration)>,
+}
+
+impl GpuThrottleStatus {
+    /// Whether any cause is active
+    pub fn is_throttled(&self) -> bool {
+        !self.active.is_empty()
+    }
+}
+
+/// Device a throttle state belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ThrottleDevice {
+    /// CPU package (socket)
+    CpuPackage {
+        /// Physical package id
+        package: u32,
+    },
+    /// Physical CPU core
+    CpuCore {
+        /// Physical package id
+        package: u32,
+        /// Core id within the package
+        core: u32,
+        /// First logical CPU on the core
+        cpu: u32,
+    },
+    /// GPU
+    Gpu {
+        /// GPU index
+        index: usize,
+        /// GPU name
+        name: String,
+    },
+}
+
+impl ThrottleDevice {
+    /// Whether this is a CPU package or core
+    pub fn is_cpu(&self) -> bool {
+        !matches!(self, ThrottleDevice::Gpu { .. })
+    }
+}
+
+impl std::fmt::Display for ThrottleDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThrottleDevice::CpuPackage { package } => write!(f, "CPU package {}", package),
+            ThrottleDevice::CpuCore { package, core, .. } => {
+                write!(f, "CPU package {} core {}", package, core)
+            }
+            ThrottleDevice::Gpu { index, name } => write!(f, "GPU {} ({})", index, name),
+        }
+    }
+}
+
+/// Accumulated statistics for one cause on one device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CauseStats {
+    /// Throttle cause
+    pub cause: ThrottleCause,
+    /// Number of throttle events, when the source counts them
+    pub events: Option<u64>,
+    /// Total time spent throttled
+    pub total_time: Duration,
+    /// Longest single throttle event, when the source tracks it
+    pub max_time: Option<Duration>,
+    /// How long the cause has been continuously active (zero if inactive)
+    pub current_streak: Duration,
+}
+
+impl CauseStats {
+    fn new(cause: ThrottleCause) -> Self {
+        Self {
+            cause,
+            events: None,
+            total_time: Duration::ZERO,
+            max_time: None,
+            current_streak: Duration::ZERO,
+        }
+    }
+}
+
+/// Throttle state of one device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceThrottle {
+    /// Device
+    pub device: ThrottleDevice,
+    /// Causes active now (for counter sources: counters moved since the
+    /// previous update)
+    pub active: Vec<ThrottleCause>,
+    /// Per-cause statistics, including inactive causes with history
+    pub causes: Vec<CauseStats>,
+}
+
+impl DeviceThrottle {
+    /// Whether the device is throttled now
+    pub fn is_throttled(&self) -> bool {
+        !self.active.is_empty()
+    }
+
+    /// Whether the device has throttled at any point
+    pub fn has_throttled(&self) -> bool {
+        self.is_throttled()
+            || self
+                .causes
+                .iter()
+                .any(|c| c.events.unwrap_or(0) > 0 || !c.total_time.is_zero())
+    }
+
+    /// Total time throttled across all causes
+    pub fn total_time(&self) -> Duration {
+        self.causes.iter().map(|c| c.total_time).sum()
+    }
+
+    /// Statistics for one cause
+    pub fn cause(&self, cause: ThrottleCause) -> Option<&CauseStats> {
+        self.causes.iter().find(|c| c.cause == cause)
+    }
+
+    fn stats_mut(&mut self, cause: ThrottleCause) -> &mut CauseStats {
+        match self.causes.iter().position(|c| c.cause == cause) {
+            Some(i) => &mut self.causes[i],
+            None => {
+                self.causes.push(CauseStats::new(cause));
+                self.causes.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// Throttling monitor across CPUs and GPUs
+pub struct ThrottleMonitor {
+    /// CPU sysfs root
+    cpu_root: PathBuf,
+    /// Event counts from the previous CPU update
+    cpu_counts: HashMap<ThrottleDevice, u64>,
+    /// Time of the previous CPU update
+    cpu_updated: Option<Instant>,
+    /// Devices in update order: CPU packages, CPU cores, GPUs
+    devices: Vec<DeviceThrottle>,
+    /// Time of the previous update per GPU index
+    gpu_updated: HashMap<usize, Instant>,
+}
+
+impl Default for ThrottleMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThrottleMonitor {
+    /// Create a monitor for the system CPU sysfs tree
+    pub fn new() -> Self {
+        Self::with_sysfs_root(CPU_SYSFS_PATH)
+    }
+
+    /// Create a monitor reading a different `/sys/devices/system/cpu`
+    pub fn with_sysfs_root<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            cpu_root: root.as_ref().to_path_buf(),
+            cpu_counts: HashMap::new(),
+            cpu_updated: None,
+            devices: Vec::new(),
+            gpu_updated: HashMap::new(),
+        }
+    }
+
+    /// Re-read CPU throttle counters
+    ///
+    /// Packages and cores are reported once each, even though the kernel
+    /// exposes the counters on every logical CPU. A CPU device is active
+    /// when its event count increased since the previous update, and its
+    /// streak grows while it stays active across updates.
+    pub fn update(&mut self) -> Result<()> {
+        let counters = read_cpu_counters(&self.cpu_root)?;
+        let now = Instant::now();
+        let elapsed = self
+            .cpu_updated
+            .replace(now)
+            .map(|then| now.duration_since(then))
+            .unwrap_or_default();
+
+        for counter in counters {
+            let previous = self
+                .cpu_counts
+                .insert(counter.device.clone(), counter.count);
+            let moved = previous.is_some_and(|p| counter.count > p);
+
+            let device = self.device_mut(counter.device);
+            let was_active = device.is_throttled();
+            device.active = if moved {
+                vec![ThrottleCause::Thermal]
+            } else {
+                Vec::new()
+            };
+            let stats = device.stats_mut(ThrottleCause::Thermal);
+            stats.events = Some(counter.count);
+            stats.total_time = counter.total_time.unwrap_or(stats.total_time);
+            stats.max_time = counter.max_time;
+            stats.current_streak = if moved && was_active {
+                stats.current_streak + elapsed
+            } else {
+                Duration::ZERO
+            };
+        }
+
+        self.sort_devices();
+        Ok(())
+    }
+
+    /// Record a GPU's throttle status
+    ///
+    /// Driver-provided violation times replace the accumulated time for
+    /// their cause; other active causes accumulate the time since this
+    /// GPU's previous update.
+    pub fn update_gpu(&mut self, index: usize, name: &str, status: &GpuThrottleStatus) {
+        let now = Instant::now();
+        let elapsed = self
+            .gpu_updated
+            .insert(index, now)
+            .map(|then| now.duration_since(then))
+            .unwrap_or_default();
+
+        let device = self.device_mut(ThrottleDevice::Gpu {
+            index,
+            name: name.to_string(),
+        });
+        let was_active = std::mem::take(&mut device.active);
+
+        for &cause in &status.active {
+            let stats = device.stats_mut(cause);
+            if was_active.contains(&cause) {
+                stats.total_time += elapsed;
+                stats.current_streak += elapsed;
+            } else {
+                stats.events = Some(stats.events.unwrap_or(0) + 1);
+                stats.current_streak = Duration::ZERO;
+            }
+        }
+        for stats in &mut device.causes {
+            if !status.active.contains(&stats.cause) {
+                stats.current_streak = Duration::ZERO;
+            }
+        }
+        for &(cause, time) in &status.violation_time {
+            device.stats_mut(cause).total_time = time;
+        }
+
+        device.active = status.active.clone();
+        device.active.sort();
+        device.active.dedup();
+        self.sort_devices();
+    }
+
+    /// Record the throttle status of every GPU in a collection
+    ///
+    /// GPUs whose backend doesn't report throttle reasons are skipped.
+    pub fn update_gpus(&mut self, gpus: &GpuCollection) {
+        for gpu in gpus.gpus() {
+            if let Ok(status) = gpu.throttle_status() {
+                let name = gpu
+                    .name()
+                    .unwrap_or_else(|_| format!("GPU {}", gpu.index()));
+                self.update_gpu(gpu.index(), &name, &status);
+            }
+        }
+    }
+
+    /// All devices seen so far
+    pub fn devices(&self) -> &[DeviceThrottle] {
+        &self.devices
+    }
+
+    /// Devices throttled now
+    pub fn throttled(&self) -> impl Iterator<Item = &DeviceThrottle> {
+        self.devices.iter().filter(|d| d.is_throttled())
+    }
+
+    /// Whether any CPU package or core is throttled now
+    pub fn cpu_throttled(&self) -> bool {
+        self.throttled().any(|d| d.device.is_cpu())
+    }
+
+    /// State of one GPU
+    pub fn gpu(&self, index: usize) -> Option<&DeviceThrottle> {
+        self.devices
+            .iter()
+            .find(|d| matches!(d.device, ThrottleDevice::Gpu { index: i, .. } if i == index))
+    }
+
+    fn device_mut(&mut self, device: ThrottleDevice) -> &mut DeviceThrottle {
+        // GPU names can change between backends; match GPUs by index
+        let key = |d: &ThrottleDevice| match d {
+            ThrottleDevice::Gpu { index, .. } => ThrottleDevice::Gpu {
+                index: *index,
+                name: String::new(),
+            },
+            other => other.clone(),
+        };
+        let wanted = key(&device);
+        match self.devices.iter().position(|d| key(&d.device) == wanted) {
+            Some(i) => &mut self.devices[i],
+            None => {
+                self.devices.push(DeviceThrottle {
+                    device,
+                    active: Vec::new(),
+                    causes: Vec::new(),
+                });
+                self.devices.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn sort_devices(&mut self) {
+        let rank = |d: &ThrottleDevice| match *d {
+            ThrottleDevice::CpuPackage { package } => (0, package as usize, 0),
+            ThrottleDevice::CpuCore { package, core, .. } => (1, package as usize, core as usize),
+            ThrottleDevice::Gpu { index, .. } => (2, index, 0),
+        };
+        self.devices.sort_by_key(|d| rank(&d.device));
+    }
+}
+
+/// One-shot throttle status of all CPUs and detected GPUs
+///
+/// CPU devices are never reported active by a single sample; use
+/// [`ThrottleMonitor`] to detect throttling as it happens.
+pub fn throttle_status() -> Result<Vec<DeviceThrottle>> {
+    let mut monitor = ThrottleMonitor::new();
+    monitor.update()?;
+    if let Ok(gpus) = GpuCollection::auto_detect() {
+        monitor.update_gpus(&gpus);
+    }
+    Ok(monitor.devices)
+}
+
+/// CPU throttle counter for one package or core
+struct CpuCounter {
+    device: ThrottleDevice,
+    count: u64,
+    total_time: Option<Duration>,
+    max_time: Option<Duration>,
+}
+
+/// Read package and core throttle counters, deduplicated across siblings
+fn read_cpu_counters(root: &Path) -> Result<Vec<CpuCounter>> {
+    let entries = match std::fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut cpus: Vec<(u32, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let cpu = name.strip_prefix("cpu")?.parse().ok()?;
+            Some((cpu, entry.path()))
+        })
+        .collect();
+    cpus.sort_by_key(|(cpu, _)| *cpu);
+
+    let mut counters: Vec<CpuCounter> = Vec::new();
+    for (cpu, path) in cpus {
+        let throttle = path.join("thermal_throttle");
+        if !throttle.is_dir() {
+            continue;
+        }
+        let package = read_u64(&path.join("topology/physical_package_id")).unwrap_or(0) as u32;
+        let core = read_u64(&path.join("topology/core_id")).unwrap_or(cpu as u64) as u32;
+
+        let devices = [
+            ("package", ThrottleDevice::CpuPackage { package }),
+            ("core", ThrottleDevice::CpuCore { package, core, cpu }),
+        ];
+        for (prefix, device) in devices {
+            let seen = counters.iter().any(|c| match (&c.device, &device) {
+                (
+                    ThrottleDevice::CpuCore {
+                        package: p1,
+                        core: c1,
+                        ..
+                    },
+                    ThrottleDevice::CpuCore {
+                        package: p2,
+                        core: c2,
+                        ..
+                    },
+                ) => p1 == p2 && c1 == c2,
+                (a, b) => a == b,
+            });
+            if seen {
+                continue;
+            }
+            let Some(count) = read_u64(&throttle.join(format!("{}_throttle_count", prefix))) else {
+                continue;
+            };
+            counters.push(CpuCounter {
+                device,
+                count,
+                total_time: read_u64(&throttle.join(format!("{}_throttle_total_time_ms", prefix)))
+                    .map(Duration::from_millis),
+                max_time: read_u64(&throttle.join(format!("{}_throttle_max_time_ms", prefix)))
+                    .map(Duration::from_millis),
+            });
+        }
+    }
+
+    Ok(counters)
+}
+
+/// Read the throttle status of an amdgpu device from its `gpu_metrics` file
+///
+/// `device_path` is the PCI device directory (`/sys/class/drm/cardN/device`).
+/// Returns `None` when the file is missing or can't be parsed. See
+/// [`GpuMetrics::throttle`] for how causes are decoded.
+pub fn amdgpu_throttle_status(device_path: &Path) -> Option<GpuThrottleStatus> {
+    Some(GpuMetrics::read(device_path).ok()?.throttle())
+}
+
+/// Map amdgpu ASIC-independent throttler bits (`SMU_THROTTLER_*_BIT`) to causes
+pub fn amdgpu_throttle_causes(bits: u64) -> Vec<ThrottleCause> {
+    let mut causes = Vec::new();
+    let mut add = |mask: u64, cause: ThrottleCause| {
+        if bits & mask != 0 && !causes.contains(&cause) {
+            causes.push(cause);
+        }
+    };
+    // PPT0-3, SPL, FPPT, SPPT, SPPT_APU
+    add(0x0000_0000_0000_00ff, ThrottleCause::PowerCap);
+    // TDC_GFX, TDC_SOC, TDC_MEM, TDC_VDD, TDC_CVIP, EDC_CPU, EDC_GFX, APCC
+    add(0x0000_0000_00ff_0000, ThrottleCause::Current);
+    // TEMP_* and VRHOT*
+    add(0x0000_3fff_0000_0000, ThrottleCause::Thermal);
+    // PROCHOT_CPU, PROCHOT_GFX
+    add(0x0300_0000_0000_0000, ThrottleCause::Thermal);
+    // PPM, FIT
+    add(0x3000_0000_0000_0000, ThrottleCause::Reliability);
+    causes
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Two packages' worth of counters: cpu0/cpu1 are siblings on core 0
+    fn fake_cpu_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        for (cpu, core) in [(0, 0), (1, 0), (2, 1)] {
+            let dir = root.join(format!("cpu{}", cpu));
+            fs::create_dir_all(dir.join("thermal_throttle")).unwrap();
+            fs::create_dir_all(dir.join("topology")).unwrap();
+            fs::write(dir.join("topology/physical_package_id"), "0\n").unwrap();
+            fs::write(dir.join("topology/core_id"), format!("{}\n", core)).unwrap();
+            let t = dir.join("thermal_throttle");
+            fs::write(t.join("core_throttle_count"), format!("{}\n", core * 5)).unwrap();
+            fs::write(t.join("core_throttle_total_time_ms"), "1500\n").unwrap();
+            fs::write(t.join("core_throttle_max_time_ms"), "700\n").unwrap();
+            fs::write(t.join("package_throttle_count"), "3\n").unwrap();
+        }
+        fs::create_dir_all(root.join("cpufreq")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_cpu_counters() {
+        let dir = fake_cpu_sysfs();
+        let root = dir.path();
+        let mut monitor = ThrottleMonitor::with_sysfs_root(root);
+        monitor.update().unwrap();
+
+        // One package and two physical cores
+        assert_eq!(monitor.devices().len(), 3);
+        let package = &monitor.devices()[0];
+        assert_eq!(package.device, ThrottleDevice::CpuPackage { package: 0 });
+        assert_eq!(package.causes[0].events, Some(3));
+        assert!(!package.is_throttled());
+
+        let core1 = &monitor.devices()[2];
+        let stats = core1.cause(ThrottleCause::Thermal).unwrap();
+        assert_eq!(stats.events, Some(5));
+        assert_eq!(stats.total_time, Duration::from_millis(1500));
+        assert_eq!(stats.max_time, Some(Duration::from_millis(700)));
+
+        // Counter moves between updates -> active
+        fs::write(
+            root.join("cpu2/thermal_throttle/core_throttle_count"),
+            "6\n",
+        )
+        .unwrap();
+        monitor.update().unwrap();
+        assert!(monitor.cpu_throttled());
+        assert_eq!(monitor.throttled().count(), 1);
+        let streak = |monitor: &ThrottleMonitor| {
+            monitor.devices()[2]
+                .cause(ThrottleCause::Thermal)
+                .unwrap()
+                .current_streak
+        };
+        assert_eq!(streak(&monitor), Duration::ZERO);
+
+        // Still moving on the next update -> sustained
+        std::thread::sleep(Duration::from_millis(20));
+        fs::write(
+            root.join("cpu2/thermal_throttle/core_throttle_count"),
+            "7\n",
+        )
+        .unwrap();
+        monitor.update().unwrap();
+        assert!(streak(&monitor) >= Duration::from_millis(20));
+
+        monitor.update().unwrap();
+        assert!(!monitor.cpu_throttled());
+        assert_eq!(streak(&monitor), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_gpu_accumulation() {
+        let mut monitor = ThrottleMonitor::with_sysfs_root("/nonexistent");
+        let power = GpuThrottleStatus {
+            active: vec![ThrottleCause::PowerCap],
+            violation_time: Vec::new(),
+        };
+        monitor.update_gpu(0, "Test GPU", &power);
+        std::thread::sleep(Duration::from_millis(20));
+        monitor.update_gpu(0, "Test GPU", &power);
+
+        let gpu = monitor.gpu(0).unwrap();
+        assert!(gpu.is_throttled());
+        let stats = gpu.cause(ThrottleCause::PowerCap).unwrap();
+        assert_eq!(stats.events, Some(1));
+        assert!(stats.total_time >= Duration::from_millis(20));
+        assert_eq!(stats.current_streak, stats.total_time);
+
+        // Driver-reported violation time wins; inactive cause resets streak
+        let thermal = GpuThrottleStatus {
+            active: vec![ThrottleCause::Thermal],
+            violation_time: vec![(ThrottleCause::PowerCap, Duration::from_secs(42))],
+        };
+        monitor.update_gpu(0, "Test GPU", &thermal);
+        let gpu = monitor.gpu(0).unwrap();
+        assert_eq!(gpu.active, vec![ThrottleCause::Thermal]);
+        let stats = gpu.cause(ThrottleCause::PowerCap).unwrap();
+        assert_eq!(stats.total_time, Duration::from_secs(42));
+        assert_eq!(stats.current_streak, Duration::ZERO);
+        assert!(gpu.has_throttled());
+    }
+
+    #[test]
+    fn test_amdgpu_throttle_bits() {
+        assert!(amdgpu_throttle_causes(0).is_empty());
+        assert_eq!(
+            amdgpu_throttle_causes((1 << 4) | (1 << 36) | (1 << 57)),
+            vec![ThrottleCause::PowerCap, ThrottleCause::Thermal]
+        );
+        assert_eq!(
+            amdgpu_throttle_causes(1 << 16),
+            vec![ThrottleCause::Current]
+        );
+    }
+}
@@ -0,0 +1,802 @@
+//! PCIe topology and NUMA affinity
+//!
+//! Builds the PCIe tree from `/sys/bus/pci/devices` and places every GPU,
+//! NIC and NVMe drive under its root complex, root port and switch, along
+//! with its NUMA node and local CPU list. Each pair of GPUs and NICs is
+//! classified the way `nvidia-smi topo -m` does, for all vendors:
+//!
+//! | Link | Meaning |
+//! |------|---------|
+//! | `X`    | Self |
+//! | `NV#`  | Bonded set of # NVLinks |
+//! | `PIX`  | At most one PCIe switch between the devices |
+//! | `PXB`  | Multiple PCIe bridges, without crossing the host bridge |
+//! | `PHB`  | Same PCIe host bridge (root complex) |
+//! | `NODE` | Different host bridges within one NUMA node |
+//! | `SYS`  | Crosses the inter-socket (SMP) interconnect |
+//!
+//! Job launchers use this to pin data-loader threads to the CPUs local to
+//! a GPU and to pick the closest NIC for each rank.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::gpu::GpuCollection;
+//! use simon::topology::Topology;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut topo = Topology::discover()?;
+//! if let Ok(gpus) = GpuCollection::auto_detect() {
+//!     topo.attach_gpus(&gpus);
+//! }
+//!
+//! // nvidia-smi topo -m style matrix
+//! println!("{}", topo.matrix());
+//!
+//! // Pin rank 0 next to GPU 0 and its nearest NIC
+//! if let Some(gpu) = topo.gpu(0) {
+//!     println!("GPU 0 CPUs: {}", gpu.cpu_affinity());
+//!     if let Some(nic) = topo.nearest_nics(0).first() {
+//!         println!("GPU 0 NIC: {} ({})", nic.name(), topo.link(gpu, nic));
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::gpu::traits::{Device, LinkState};
+use crate::gpu::GpuCollection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Default sysfs mount point
+const SYSFS_PATH: &str = "/sys";
+
+/// PCI vendors whose display/accelerator functions are treated as GPUs
+/// (NVIDIA, AMD, Intel). Excludes BMC VGA controllers.
+const GPU_VENDORS: [u16; 3] = [0x10de, 0x1002, 0x8086];
+
+/// Connection between two devices, ordered from closest to farthest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LinkType {
+    /// Same device
+    SelfLink,
+    /// NVLink with this many bonded links
+    NvLink(u32),
+    /// At most one PCIe switch between the devices
+    Pix,
+    /// Multiple PCIe bridges, without crossing the host bridge
+    Pxb,
+    /// Same PCIe host bridge (root complex)
+    Phb,
+    /// Different host bridges within one NUMA node
+    Node,
+    /// Crosses the inter-socket interconnect
+    Sys,
+}
+
+impl fmt::Display for LinkType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkType::SelfLink => f.write_str("X"),
+            LinkType::NvLink(n) => write!(f, "NV{}", n),
+            LinkType::Pix => f.write_str("PIX"),
+            LinkType::Pxb => f.write_str("PXB"),
+            LinkType::Phb => f.write_str("PHB"),
+            LinkType::Node => f.write_str("NODE"),
+            LinkType::Sys => f.write_str("SYS"),
+        }
+    }
+}
+
+/// What kind of endpoint a topology device is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TopoDeviceKind {
+    /// GPU or accelerator
+    Gpu {
+        /// Index in the [`GpuCollection`], or discovery order if not attached
+        index: usize,
+        /// Model name, when known
+        name: Option<String>,
+    },
+    /// Network adapter
+    Nic {
+        /// RDMA device (`mlx5_0`) or interface name (`eth0`)
+        name: String,
+    },
+    /// NVMe controller
+    Nvme {
+        /// Controller name (`nvme0`)
+        name: String,
+    },
+}
+
+/// A GPU, NIC or NVMe drive placed in the PCIe tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopoDevice {
+    /// Endpoint kind
+    pub kind: TopoDeviceKind,
+    /// PCI address (`0000:01:00.0`)
+    pub address: String,
+    /// PCI vendor ID
+    pub vendor_id: u16,
+    /// PCI device ID
+    pub device_id: u16,
+    /// Bound kernel driver
+    pub driver: Option<String>,
+    /// Root complex (`0000:00`)
+    pub root_complex: String,
+    /// Root port the device hangs off
+    pub root_port: Option<String>,
+    /// Upstream port of the nearest PCIe switch
+    pub switch: Option<String>,
+    /// Bridges from the root port down to the device
+    pub bridges: Vec<String>,
+    /// NUMA node
+    pub numa_node: Option<u32>,
+    /// CPUs local to the device
+    pub local_cpus: Vec<u32>,
+}
+
+impl TopoDevice {
+    /// Short label used in the matrix (`GPU0`, `NIC1`, `NVME0`)
+    pub fn name(&self) -> String {
+        match &self.kind {
+            TopoDeviceKind::Gpu { index, .. } => format!("GPU{}", index),
+            TopoDeviceKind::Nic { name } | TopoDeviceKind::Nvme { name } => name.clone(),
+        }
+    }
+
+    /// Whether this is a GPU
+    pub fn is_gpu(&self) -> bool {
+        matches!(self.kind, TopoDeviceKind::Gpu { .. })
+    }
+
+    /// Whether this is a NIC
+    pub fn is_nic(&self) -> bool {
+        matches!(self.kind, TopoDeviceKind::Nic { .. })
+    }
+
+    /// Local CPUs in kernel list format (`0-15,32-47`)
+    pub fn cpu_affinity(&self) -> String {
+        format_cpu_list(&self.local_cpus)
+    }
+}
+
+/// PCIe and NUMA topology of a host
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Topology {
+    /// GPUs, in index order
+    pub gpus: Vec<TopoDevice>,
+    /// Network adapters, in PCI address order
+    pub nics: Vec<TopoDevice>,
+    /// NVMe controllers, in PCI address order
+    pub nvmes: Vec<TopoDevice>,
+    /// NVLink counts between GPU PCI addresses
+    #[serde(skip)]
+    nvlinks: HashMap<(String, String), u32>,
+}
+
+impl Topology {
+    /// Read the topology from `/sys`
+    pub fn discover() -> Result<Self> {
+        Self::from_sysfs(SYSFS_PATH)
+    }
+
+    /// Read the topology from a sysfs tree rooted at `root`
+    pub fn from_sysfs(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let mut entries: Vec<_> = fs::read_dir(root.join("bus/pci/devices"))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .collect();
+        entries.sort();
+
+        let mut topo = Topology::default();
+        for path in entries {
+            let Some(address) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let class = read_hex(&path.join("class")).unwrap_or(0) as u32;
+            let vendor_id = read_hex(&path.join("vendor")).unwrap_or(0) as u16;
+            // SR-IOV virtual functions share their parent's placement
+            if path.join("physfn").exists() {
+                continue;
+            }
+
+            let kind = match class >> 16 {
+                0x03 | 0x12 if GPU_VENDORS.contains(&vendor_id) => TopoDeviceKind::Gpu {
+                    index: topo.gpus.len(),
+                    name: None,
+                },
+                0x02 => TopoDeviceKind::Nic {
+                    name: nic_name(&path).unwrap_or_else(|| address.clone()),
+                },
+                _ if class == 0x010802 => TopoDeviceKind::Nvme {
+                    name: first_child(&path.join("nvme")).unwrap_or_else(|| address.clone()),
+                },
+                _ => continue,
+            };
+
+            let device = read_device(&path, address, kind, vendor_id);
+            match device.kind {
+                TopoDeviceKind::Gpu { .. } => topo.gpus.push(device),
+                TopoDeviceKind::Nic { .. } => topo.nics.push(device),
+                TopoDeviceKind::Nvme { .. } => topo.nvmes.push(device),
+            }
+        }
+        Ok(topo)
+    }
+
+    /// Number GPUs and name them after a [`GpuCollection`]
+    ///
+    /// GPUs are matched by `GpuStaticInfo::pci_bus_id`. Devices the
+    /// collection doesn't know keep their discovery order after the
+    /// matched ones.
+    pub fn attach_gpus(&mut self, gpus: &GpuCollection) {
+        let known: HashMap<String, (usize, String)> = gpus
+            .gpus()
+            .iter()
+            .filter_map(|gpu| {
+                let info = gpu.static_info().ok()?;
+                let address = normalize_pci_address(info.pci_bus_id.as_deref()?);
+                Some((address, (info.index, info.name)))
+            })
+            .collect();
+
+        let mut next = known.values().map(|(i, _)| i + 1).max().unwrap_or(0);
+        for gpu in &mut self.gpus {
+            gpu.kind = match known.get(&gpu.address) {
+                Some((index, name)) => TopoDeviceKind::Gpu {
+                    index: *index,
+                    name: Some(name.clone()),
+                },
+                None => {
+                    next += 1;
+                    TopoDeviceKind::Gpu {
+                        index: next - 1,
+                        name: None,
+                    }
+                }
+            };
+        }
+        self.gpus.sort_by_key(|g| match g.kind {
+            TopoDeviceKind::Gpu { index, .. } => index,
+            _ => usize::MAX,
+        });
+    }
+
+    /// Record NVLinks reported by a device
+    ///
+    /// Links to an NVSwitch connect the GPU to every other GPU on the
+    /// same switch fabric.
+    pub fn attach_nvlinks(&mut self, device: &dyn Device) {
+        let (Ok(pci), Ok(links)) = (device.pci_info(), device.nvlink_status()) else {
+            return;
+        };
+        let local = normalize_pci_address(&pci.bus_id);
+        let mut per_remote: HashMap<String, u32> = HashMap::new();
+        for link in links.iter().filter(|l| l.state == LinkState::Active) {
+            let remote = if link.remote_device_type.to_lowercase().contains("switch") {
+                NVSWITCH.to_string()
+            } else {
+                normalize_pci_address(&link.remote_pci_bus_id)
+            };
+            *per_remote.entry(remote).or_default() += 1;
+        }
+        for (remote, count) in per_remote {
+            self.set_nvlink(&local, &remote, count);
+        }
+    }
+
+    /// Record `count` NVLinks between two PCI addresses
+    pub fn set_nvlink(&mut self, a: &str, b: &str, count: u32) {
+        self.nvlinks.insert((a.to_string(), b.to_string()), count);
+    }
+
+    /// GPU by index
+    pub fn gpu(&self, index: usize) -> Option<&TopoDevice> {
+        self.gpus
+            .iter()
+            .find(|g| matches!(g.kind, TopoDeviceKind::Gpu { index: i, .. } if i == index))
+    }
+
+    /// All devices: GPUs, then NICs, then NVMe
+    pub fn devices(&self) -> impl Iterator<Item = &TopoDevice> {
+        self.gpus.iter().chain(&self.nics).chain(&self.nvmes)
+    }
+
+    /// How two devices are connected
+    pub fn link(&self, a: &TopoDevice, b: &TopoDevice) -> LinkType {
+        if a.address == b.address {
+            return LinkType::SelfLink;
+        }
+        if let Some(n) = self.nvlink_count(&a.address, &b.address) {
+            return LinkType::NvLink(n);
+        }
+        pcie_link(a, b)
+    }
+
+    /// NICs ordered from closest to farthest from a GPU
+    pub fn nearest_nics(&self, gpu_index: usize) -> Vec<&TopoDevice> {
+        let Some(gpu) = self.gpu(gpu_index) else {
+            return Vec::new();
+        };
+        let mut nics: Vec<_> = self.nics.iter().collect();
+        nics.sort_by_key(|nic| self.link(gpu, nic));
+        nics
+    }
+
+    /// GPU/NIC connection matrix
+    pub fn matrix(&self) -> TopologyMatrix {
+        let devices: Vec<&TopoDevice> = self.gpus.iter().chain(&self.nics).collect();
+        let labels = devices
+            .iter()
+            .map(|d| match d.kind {
+                TopoDeviceKind::Nic { .. } => {
+                    let i = self.nics.iter().position(|n| n.address == d.address);
+                    format!("NIC{}", i.unwrap_or(0))
+                }
+                _ => d.name(),
+            })
+            .collect();
+        TopologyMatrix {
+            labels,
+            links: devices
+                .iter()
+                .map(|a| devices.iter().map(|b| self.link(a, b)).collect())
+                .collect(),
+            cpu_affinity: devices.iter().map(|d| d.cpu_affinity()).collect(),
+            numa_affinity: devices.iter().map(|d| d.numa_node).collect(),
+            nic_names: self.nics.iter().map(TopoDevice::name).collect(),
+        }
+    }
+
+    fn nvlink_count(&self, a: &str, b: &str) -> Option<u32> {
+        let direct = self
+            .nvlinks
+            .get(&(a.to_string(), b.to_string()))
+            .or_else(|| self.nvlinks.get(&(b.to_string(), a.to_string())));
+        if let Some(&n) = direct {
+            return Some(n);
+        }
+        // Both GPUs on an NVSwitch fabric
+        let a_switch = self.nvlinks.get(&(a.to_string(), NVSWITCH.to_string()))?;
+        let b_switch = self.nvlinks.get(&(b.to_string(), NVSWITCH.to_string()))?;
+        Some(*a_switch.min(b_switch))
+    }
+}
+
+/// Pseudo-address for links that end at an NVSwitch
+const NVSWITCH: &str = "nvswitch";
+
+/// PCIe-only classification of two distinct devices
+fn pcie_link(a: &TopoDevice, b: &TopoDevice) -> LinkType {
+    if a.root_complex != b.root_complex {
+        return if a.numa_node == b.numa_node {
+            LinkType::Node
+        } else {
+            LinkType::Sys
+        };
+    }
+    let common = a
+        .bridges
+        .iter()
+        .zip(&b.bridges)
+        .take_while(|(x, y)| x == y)
+        .count();
+    if common == 0 {
+        return LinkType::Phb;
+    }
+    // Below the shared bridge, each side crosses at most one more bridge
+    // (a switch's downstream port) for a single-switch path
+    let a_below = a.bridges.len() - common;
+    let b_below = b.bridges.len() - common;
+    if (common >= 2 || a.bridges == b.bridges) && a_below <= 1 && b_below <= 1 {
+        LinkType::Pix
+    } else {
+        LinkType::Pxb
+    }
+}
+
+/// Read placement and affinity for one endpoint
+fn read_device(path: &Path, address: String, kind: TopoDeviceKind, vendor_id: u16) -> TopoDevice {
+    // The canonical path lists every bridge above the device:
+    // /sys/devices/pci0000:00/0000:00:01.0/0000:01:00.0/0000:02:08.0/0000:03:00.0
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut root_complex = String::new();
+    let mut chain = Vec::new();
+    for component in canonical.iter().map(|c| c.to_string_lossy()) {
+        if let Some(rc) = component.strip_prefix("pci") {
+            root_complex = rc.to_string();
+            chain.clear();
+        } else if is_pci_address(&component) {
+            chain.push(component.to_string());
+        }
+    }
+    // The last entry is the device itself
+    chain.pop();
+
+    let numa_node = fs::read_to_string(path.join("numa_node"))
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .and_then(|n| u32::try_from(n).ok());
+    let local_cpus = fs::read_to_string(path.join("local_cpulist"))
+        .map(|s| parse_cpu_list(&s))
+        .unwrap_or_default();
+
+    TopoDevice {
+        kind,
+        vendor_id,
+        device_id: read_hex(&path.join("device")).unwrap_or(0) as u16,
+        driver: fs::read_link(path.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())),
+        root_complex,
+        root_port: chain.first().cloned(),
+        switch: (chain.len() >= 3).then(|| chain[chain.len() - 2].clone()),
+        bridges: chain,
+        numa_node,
+        local_cpus,
+        address,
+    }
+}
+
+/// RDMA device name if the NIC has one, else its first network interface
+fn nic_name(path: &Path) -> Option<String> {
+    first_child(&path.join("infiniband"))
+        .or_else(|| first_child(&path.join("net")))
+        // virtio-net nests the interface under a virtioN child
+        .or_else(|| {
+            let virtio = fs::read_dir(path)
+                .ok()?
+                .filter_map(|e| e.ok())
+                .find(|e| e.file_name().to_string_lossy().starts_with("virtio"))?;
+            first_child(&virtio.path().join("net"))
+        })
+}
+
+fn first_child(dir: &Path) -> Option<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+fn read_hex(path: &Path) -> Option<u64> {
+    let s = fs::read_to_string(path).ok()?;
+    u64::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// `dddd:bb:dd.f`
+fn is_pci_address(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 12
+        && b[4] == b':'
+        && b[7] == b':'
+        && b[10] == b'.'
+        && s.chars()
+            .enumerate()
+            .all(|(i, c)| matches!(i, 4 | 7 | 10) || c.is_ascii_hexdigit())
+}
+
+/// Normalize a PCI bus ID to sysfs form
+///
+/// NVML reports an 8-digit domain (`00000000:3B:00.0`); sysfs uses 4
+/// lowercase digits (`0000:3b:00.0`).
+pub fn normalize_pci_address(bus_id: &str) -> String {
+    let bus_id = bus_id.trim().to_lowercase();
+    match bus_id.split_once(':') {
+        Some((domain, rest)) if domain.len() > 4 => {
+            let domain = u32::from_str_radix(domain, 16).unwrap_or(0);
+            format!("{:04x}:{}", domain, rest)
+        }
+        Some((domain, rest)) if domain.len() < 4 && rest.contains(':') => {
+            format!("{:0>4}:{}", domain, rest)
+        }
+        Some(_) => bus_id,
+        // Bus-only form (`3b:00.0`) implies domain 0
+        None => bus_id,
+    }
+}
+
+/// Parse a kernel CPU list (`0-3,8,10-11`)
+pub fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+/// Format CPUs as a kernel CPU list (`0-3,8,10-11`)
+pub fn format_cpu_list(cpus: &[u32]) -> String {
+    let mut sorted = cpus.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    let mut ranges: Vec<String> = Vec::new();
+    let mut iter = sorted.into_iter().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+        ranges.push(if start == end {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, end)
+        });
+    }
+    ranges.join(",")
+}
+
+/// Connection matrix between GPUs and NICs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopologyMatrix {
+    /// Row/column labels (`GPU0`, `NIC0`)
+    pub labels: Vec<String>,
+    /// Link between row and column device
+    pub links: Vec<Vec<LinkType>>,
+    /// CPU affinity per row
+    pub cpu_affinity: Vec<String>,
+    /// NUMA node per row
+    pub numa_affinity: Vec<Option<u32>>,
+    /// NIC device names, by NIC index
+    pub nic_names: Vec<String>,
+}
+
+impl fmt::Display for TopologyMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:8}", "")?;
+        for label in &self.labels {
+            write!(f, "{:8}", label)?;
+        }
+        writeln!(f, "{:16}NUMA Affinity", "CPU Affinity")?;
+        for (i, label) in self.labels.iter().enumerate() {
+            write!(f, "{:8}", label)?;
+            for link in &self.links[i] {
+                write!(f, "{:8}", format!(" {}", link))?;
+            }
+            let numa = self.numa_affinity[i].map_or("N/A".to_string(), |n| n.to_string());
+            let cpus = if self.cpu_affinity[i].is_empty() {
+                "N/A"
+            } else {
+                &self.cpu_affinity[i]
+            };
+            writeln!(f, "{:16}{}", cpus, numa)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Legend:")?;
+        writeln!(f)?;
+        writeln!(f, "  X    = Self")?;
+        writeln!(f, "  SYS  = Connection traversing PCIe as well as the SMP interconnect between NUMA nodes")?;
+        writeln!(f, "  NODE = Connection traversing PCIe as well as the interconnect between PCIe Host Bridges within a NUMA node")?;
+        writeln!(
+            f,
+            "  PHB  = Connection traversing PCIe as well as a PCIe Host Bridge (typically the CPU)"
+        )?;
+        writeln!(f, "  PXB  = Connection traversing multiple PCIe bridges (without traversing the PCIe Host Bridge)")?;
+        writeln!(
+            f,
+            "  PIX  = Connection traversing at most a single PCIe bridge"
+        )?;
+        write!(
+            f,
+            "  NV#  = Connection traversing a bonded set of # NVLinks"
+        )?;
+        if !self.nic_names.is_empty() {
+            writeln!(f)?;
+            writeln!(f)?;
+            write!(f, "NIC Legend:")?;
+            for (i, name) in self.nic_names.iter().enumerate() {
+                write!(f, "\n  NIC{}: {}", i, name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// Two sockets: socket 0 has two GPUs and a NIC behind one PCIe
+    /// switch plus an NVMe on the root complex; socket 1 has a GPU and a
+    /// NIC on separate root ports
+    fn fake_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("bus/pci/devices")).unwrap();
+
+        let add = |path: &str, class: &str, vendor: &str, numa: i32, cpus: &str| {
+            let dir = root.join("devices").join(path);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("class"), format!("{}\n", class)).unwrap();
+            fs::write(dir.join("vendor"), format!("{}\n", vendor)).unwrap();
+            fs::write(dir.join("device"), "0x1234\n").unwrap();
+            fs::write(dir.join("numa_node"), format!("{}\n", numa)).unwrap();
+            fs::write(dir.join("local_cpulist"), format!("{}\n", cpus)).unwrap();
+            let address = path.rsplit('/').next().unwrap();
+            symlink(&dir, root.join("bus/pci/devices").join(address)).unwrap();
+            dir
+        };
+
+        let s0 = "pci0000:00/0000:00:01.0/0000:01:00.0";
+        add("pci0000:00/0000:00:01.0", "0x060400", "0x8086", 0, "0-7");
+        add(s0, "0x060400", "0x10b5", 0, "0-7");
+        add(
+            &format!("{}/0000:02:08.0", s0),
+            "0x060400",
+            "0x10b5",
+            0,
+            "0-7",
+        );
+        add(
+            &format!("{}/0000:02:10.0", s0),
+            "0x060400",
+            "0x10b5",
+            0,
+            "0-7",
+        );
+        add(
+            &format!("{}/0000:02:18.0", s0),
+            "0x060400",
+            "0x10b5",
+            0,
+            "0-7",
+        );
+        add(
+            &format!("{}/0000:02:08.0/0000:03:00.0", s0),
+            "0x030200",
+            "0x10de",
+            0,
+            "0-7",
+        );
+        add(
+            &format!("{}/0000:02:10.0/0000:04:00.0", s0),
+            "0x030200",
+            "0x10de",
+            0,
+            "0-7",
+        );
+        let nic0 = add(
+            &format!("{}/0000:02:18.0/0000:05:00.0", s0),
+            "0x020700",
+            "0x15b3",
+            0,
+            "0-7",
+        );
+        fs::create_dir_all(nic0.join("infiniband/mlx5_0")).unwrap();
+        fs::create_dir_all(nic0.join("net/ib0")).unwrap();
+        let nvme = add(
+            "pci0000:00/0000:00:03.0/0000:06:00.0",
+            "0x010802",
+            "0x144d",
+            0,
+            "0-7",
+        );
+        fs::create_dir_all(nvme.join("nvme/nvme0")).unwrap();
+        // BMC VGA on socket 0 is not a GPU
+        add(
+            "pci0000:00/0000:00:1c.0/0000:07:00.0",
+            "0x030000",
+            "0x1a03",
+            0,
+            "0-7",
+        );
+
+        add(
+            "pci0000:80/0000:80:01.0/0000:81:00.0",
+            "0x030200",
+            "0x10de",
+            1,
+            "8-15",
+        );
+        let nic1 = add(
+            "pci0000:80/0000:80:02.0/0000:82:00.0",
+            "0x020000",
+            "0x8086",
+            1,
+            "8-15",
+        );
+        fs::create_dir_all(nic1.join("net/eth1")).unwrap();
+        // SR-IOV virtual function is skipped
+        let vf = add(
+            "pci0000:80/0000:80:02.0/0000:82:00.1",
+            "0x020000",
+            "0x8086",
+            1,
+            "8-15",
+        );
+        symlink(&nic1, vf.join("physfn")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_discover() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let topo = Topology::from_sysfs(root).unwrap();
+        assert_eq!(topo.gpus.len(), 3);
+        assert_eq!(topo.nics.len(), 2);
+        assert_eq!(topo.nvmes.len(), 1);
+
+        let gpu0 = topo.gpu(0).unwrap();
+        assert_eq!(gpu0.address, "0000:03:00.0");
+        assert_eq!(gpu0.root_complex, "0000:00");
+        assert_eq!(gpu0.root_port.as_deref(), Some("0000:00:01.0"));
+        assert_eq!(gpu0.switch.as_deref(), Some("0000:01:00.0"));
+        assert_eq!(gpu0.numa_node, Some(0));
+        assert_eq!(gpu0.cpu_affinity(), "0-7");
+        assert_eq!(topo.nics[0].name(), "mlx5_0");
+        assert_eq!(topo.nics[1].name(), "eth1");
+        assert_eq!(topo.nvmes[0].name(), "nvme0");
+        assert_eq!(topo.nvmes[0].switch, None);
+    }
+
+    #[test]
+    fn test_link_classification() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let mut topo = Topology::from_sysfs(root).unwrap();
+        let (gpu0, gpu1, gpu2) = (
+            topo.gpu(0).unwrap().clone(),
+            topo.gpu(1).unwrap().clone(),
+            topo.gpu(2).unwrap().clone(),
+        );
+        let (nic0, nic1) = (topo.nics[0].clone(), topo.nics[1].clone());
+
+        assert_eq!(topo.link(&gpu0, &gpu0), LinkType::SelfLink);
+        assert_eq!(topo.link(&gpu0, &gpu1), LinkType::Pix);
+        assert_eq!(topo.link(&gpu0, &nic0), LinkType::Pix);
+        assert_eq!(topo.link(&gpu0, &topo.nvmes[0]), LinkType::Phb);
+        assert_eq!(topo.link(&gpu0, &gpu2), LinkType::Sys);
+        assert_eq!(topo.link(&gpu2, &nic1), LinkType::Phb);
+
+        // Same NUMA node, different host bridges
+        let mut other = gpu2.clone();
+        other.numa_node = Some(0);
+        assert_eq!(topo.link(&gpu0, &other), LinkType::Node);
+
+        // NVLink overrides PCIe
+        topo.set_nvlink("0000:03:00.0", "0000:04:00.0", 4);
+        assert_eq!(topo.link(&gpu1, &gpu0), LinkType::NvLink(4));
+
+        assert_eq!(topo.nearest_nics(0)[0].name(), "mlx5_0");
+        assert_eq!(topo.nearest_nics(2)[0].name(), "eth1");
+
+        let matrix = topo.matrix();
+        assert_eq!(matrix.labels, ["GPU0", "GPU1", "GPU2", "NIC0", "NIC1"]);
+        assert_eq!(matrix.links[0][1], LinkType::NvLink(4));
+        let text = matrix.to_string();
+        assert!(text.contains(" NV4"));
+        assert!(text.contains("NIC0: mlx5_0"));
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), [0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(format_cpu_list(&[11, 0, 1, 2, 3, 8, 10]), "0-3,8,10-11");
+        assert_eq!(format_cpu_list(&[]), "");
+        assert_eq!(normalize_pci_address("00000000:3B:00.0"), "0000:3b:00.0");
+        assert_eq!(normalize_pci_address("0000:3b:00.0"), "0000:3b:00.0");
+        assert!(is_pci_address("0000:3b:00.0"));
+        assert!(!is_pci_address("pci0000:00"));
+    }
+}
@@ -1,9 +1,15 @@
 //! Application state management
 
 use crate::agent::{Agent, AgentConfig, AgentResponse};
-use crate::gpu::traits::Device;
+use crate::daemon::DaemonClient;
+use crate::gpu::traits::{Capabilities, Capability, Device};
+use crate::hwlog::{HardwareEvent, HardwareEventMonitor};
+#[cfg(target_os = "linux")]
+use crate::process_network::ProcessNetMonitor;
+use crate::rdma::{RdmaDevice, RdmaMonitor, RdmaPortRate};
+use crate::throttling::{ThrottleCause, ThrottleMonitor};
 use crate::{ProcessMonitor, ProcessMonitorInfo, SiliconMonitor};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
 /// Maximum number of data points to keep in history
@@ -12,6 +18,9 @@ const MAX_HISTORY: usize = 60;
 /// Maximum number of agent responses to keep
 const MAX_AGENT_HISTORY: usize = 10;
 
+/// How long hardware events stay in the events panel
+const HARDWARE_EVENT_WINDOW: Duration = Duration::from_secs(3600);
+
 /// Type of accelerator device
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AcceleratorType {
@@ -89,6 +98,8 @@ pub struct AcceleratorInfo {
     pub serial: Option<String>,
     /// PCIe slot info (for PCIe devices)
     pub pcie_slot: Option<String>,
+    /// Active throttle causes (empty when running at full clocks)
+    pub throttle: Vec<ThrottleCause>,
 }
 
 impl Default for AcceleratorType {
@@ -152,6 +163,8 @@ pub struct App {
     pub scroll_position: usize,
     /// GPU devices for monitoring
     gpu_devices: Vec<Box<dyn Device>>,
+    /// What each GPU device supports (same order as `gpu_devices`)
+    gpu_capabilities: Vec<Capabilities>,
     /// Application configuration
     pub config: crate::config::Config,
     /// Status message to display (cleared after timeout)
@@ -172,6 +185,29 @@ pub struct App {
     process_monitor: Option<ProcessMonitor>,
     /// Cached processes from last update
     pub processes: Vec<ProcessMonitorInfo>,
+    /// Kernel log monitor for hardware events
+    hardware_event_monitor: Option<HardwareEventMonitor>,
+    /// Recent hardware events (Xid, MCE, NVMe resets, ...), oldest first
+    pub hardware_events: Vec<HardwareEvent>,
+    /// CPU and GPU throttle tracking
+    throttle_monitor: ThrottleMonitor,
+    /// Whether any CPU package or core throttled since the last update
+    pub cpu_throttled: bool,
+    /// InfiniBand/RoCE port monitor, when RDMA devices are present
+    rdma_monitor: Option<RdmaMonitor>,
+    /// RDMA devices from the last update
+    pub rdma_devices: Vec<RdmaDevice>,
+    /// RDMA port throughput and error rates since the previous update
+    pub rdma_rates: Vec<RdmaPortRate>,
+    /// Per-process socket throughput tracking
+    #[cfg(target_os = "linux")]
+    process_net_monitor: Option<ProcessNetMonitor>,
+    /// Network receive and send rates (bytes/s) per PID
+    pub process_net_rates: HashMap<u32, (f64, f64)>,
+    /// Sort the process list by network throughput instead of CPU
+    pub sort_by_network: bool,
+    /// Daemon supplying the data when attached; local collectors are unused
+    remote: Option<DaemonClient>,
 }
 
 #[derive(Clone, Default)]
@@ -215,6 +251,8 @@ pub struct GpuInfo {
     pub encoder_last_active: Option<Instant>,
     /// Last time decoder was active (for auto-hide)
     pub decoder_last_active: Option<Instant>,
+    /// Active throttle causes
+    pub throttle: Vec<ThrottleCause>,
 }
 
 impl From<&GpuInfo> for AcceleratorInfo {
@@ -239,6 +277,7 @@ impl From<&GpuInfo> for AcceleratorInfo {
             firmware_version: None,
             serial: None,
             pcie_slot: None,
+            throttle: gpu.throttle.clone(),
         }
     }
 }
@@ -265,34 +304,24 @@ pub struct DiskInfo {
 impl App {
     /// Create a new application instance
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        // Initialize GPU devices
-        let mut gpu_devices: Vec<Box<dyn Device>> = Vec::new();
+        Self::with_remote(None)
+    }
 
-        // Try to detect NVIDIA GPUs
-        #[cfg(feature = "nvidia")]
-        {
-            if let Ok(nvidia_devices) = crate::gpu::nvidia_new::enumerate() {
-                for device in nvidia_devices {
-                    gpu_devices.push(Box::new(device));
-                }
-            }
-        }
+    /// Create an instance that shows a daemon's samples
+    pub fn attach(client: DaemonClient) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::with_remote(Some(client))
+    }
 
-        // Try to detect AMD GPUs
-        #[cfg(feature = "amd")]
-        {
-            if let Ok(mut amd_devices) = crate::gpu::amd_rocm::enumerate() {
-                gpu_devices.append(&mut amd_devices);
-            }
-        }
+    fn with_remote(remote: Option<DaemonClient>) -> Result<Self, Box<dyn std::error::Error>> {
+        let local = remote.is_none();
 
-        // Try to detect Intel GPUs
-        #[cfg(feature = "intel")]
-        {
-            if let Ok(mut intel_devices) = crate::gpu::intel_levelzero::enumerate() {
-                gpu_devices.append(&mut intel_devices);
-            }
-        }
+        // Initialize GPU devices (same detection path as GpuCollection)
+        let gpu_devices = if local {
+            crate::gpu::enumerate_devices()
+        } else {
+            Vec::new()
+        };
+        let gpu_capabilities = gpu_devices.iter().map(|d| d.capabilities()).collect();
 
         // Load or create default config
         let config = crate::config::Config::load().unwrap_or_default();
@@ -334,6 +363,7 @@ impl App {
             last_update: Instant::now(),
             scroll_position: 0,
             gpu_devices,
+            gpu_capabilities,
             config,
             status_message: None,
             agent,
@@ -342,8 +372,20 @@ impl App {
             agent_history: VecDeque::with_capacity(MAX_AGENT_HISTORY),
             agent_loading: false,
             process_display_mode: ProcessDisplayMode::default(),
-            process_monitor: ProcessMonitor::new().ok(),
+            process_monitor: local.then(|| ProcessMonitor::new().ok()).flatten(),
             processes: Vec::new(),
+            hardware_event_monitor: local.then(|| HardwareEventMonitor::new().ok()).flatten(),
+            hardware_events: Vec::new(),
+            throttle_monitor: ThrottleMonitor::new(),
+            cpu_throttled: false,
+            rdma_monitor: local.then(RdmaMonitor::new).filter(|m| m.is_available()),
+            rdma_devices: Vec::new(),
+            rdma_rates: Vec::new(),
+            #[cfg(target_os = "linux")]
+            process_net_monitor: local.then(|| ProcessNetMonitor::new().ok()).flatten(),
+            process_net_rates: HashMap::new(),
+            sort_by_network: false,
+            remote,
         };
 
         // Initial update
@@ -354,12 +396,19 @@ impl App {
 
     /// Update all monitoring data
     pub fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.remote.is_some() {
+            return self.update_remote();
+        }
         self.update_cpu()?;
         self.update_memory()?;
         self.update_gpu()?;
         self.update_system()?;
         self.update_disks()?;
         self.update_processes()?;
+        self.update_hardware_events();
+        self.update_throttling();
+        self.update_rdma();
+        self.update_process_network();
 
         self.last_update = Instant::now();
         Ok(())
@@ -552,10 +601,24 @@ impl App {
         // Get real GPU data from devices
         self.gpu_info.clear();
 
-        for device in &self.gpu_devices {
+        for (idx, device) in self.gpu_devices.iter().enumerate() {
             let name = device.name().unwrap_or_else(|_| "Unknown GPU".to_string());
             let vendor_str = format!("{}", device.vendor());
 
+            let caps = &self.gpu_capabilities[idx];
+
+            // Get throttle reasons (tracked for durations across updates)
+            let throttle = match caps
+                .contains(Capability::ThrottleStatus)
+                .then(|| device.throttle_status())
+            {
+                Some(Ok(status)) => {
+                    self.throttle_monitor.update_gpu(idx, &name, &status);
+                    status.active
+                }
+                _ => Vec::new(),
+            };
+
             // Get memory info
             let (memory_total, memory_used) = if let Ok(mem) = device.memory() {
                 (mem.total, mem.used)
@@ -603,10 +666,12 @@ impl App {
             };
 
             // Get encoder/decoder utilization
-            let (encoder_util, decoder_util) = if let Ok(util) = device.utilization() {
-                (util.encoder, util.decoder)
-            } else {
-                (None, None)
+            let (encoder_util, decoder_util) = match caps
+                .contains(Capability::EncoderDecoder)
+                .then(|| device.utilization())
+            {
+                Some(Ok(util)) => (util.encoder, util.decoder),
+                _ => (None, None),
             };
 
             // Determine if encoder/decoder were active (update timestamp)
@@ -644,6 +709,7 @@ impl App {
                 decoder_util,
                 encoder_last_active,
                 decoder_last_active,
+                throttle,
             });
         }
 
@@ -885,11 +951,180 @@ impl App {
         Ok(())
     }
 
+    fn update_hardware_events(&mut self) {
+        if let Some(ref mut monitor) = self.hardware_event_monitor {
+            let _ = monitor.poll();
+            self.hardware_events = monitor
+                .recent(HARDWARE_EVENT_WINDOW)
+                .into_iter()
+                .cloned()
+                .collect();
+        }
+    }
+
+    fn update_throttling(&mut self) {
+        if self.throttle_monitor.update().is_ok() {
+            self.cpu_throttled = self.throttle_monitor.cpu_throttled();
+        }
+    }
+
+    fn update_rdma(&mut self) {
+        if let Some(ref mut monitor) = self.rdma_monitor {
+            if let Ok(rates) = monitor.refresh() {
+                self.rdma_rates = rates;
+            }
+            self.rdma_devices = monitor.devices().unwrap_or_default();
+        }
+    }
+
+    fn update_process_network(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Some(ref mut monitor) = self.process_net_monitor {
+            if let Ok(usage) = monitor.refresh() {
+                self.process_net_rates = usage
+                    .processes
+                    .iter()
+                    .map(|p| (p.pid, (p.rx_bytes_per_sec, p.tx_bytes_per_sec)))
+                    .collect();
+            }
+        }
+    }
+
+    /// Fill the panels from the attached daemon's latest snapshot
+    fn update_remote(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(client) = &self.remote else {
+            return Ok(());
+        };
+        // Keep the last values on screen while the daemon is unreachable
+        let snapshot = match client.snapshot() {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                self.set_status_message(format!("Daemon unavailable: {}", e));
+                return Ok(());
+            }
+        };
+        let state = snapshot.state;
+
+        if let Some(cpu) = state.cpu {
+            self.cpu_info = CpuInfo {
+                name: cpu.name,
+                cores: cpu.cores,
+                threads: cpu.threads,
+                utilization: cpu.utilization,
+                temperature: cpu.temperature,
+                frequency: cpu.frequency_mhz,
+                per_core_usage: cpu.per_core_usage,
+            };
+        }
+        if let Some(memory) = state.memory {
+            self.memory_info = MemoryInfo {
+                total: memory.total_bytes,
+                used: memory.used_bytes,
+                available: memory.available_bytes,
+                swap_total: memory.swap_total_bytes,
+                swap_used: memory.swap_used_bytes,
+            };
+        }
+        push_history(&mut self.cpu_history, self.cpu_info.utilization as u64);
+        let used_percent = (self.memory_info.used * 100)
+            .checked_div(self.memory_info.total)
+            .unwrap_or(0);
+        push_history(&mut self.memory_history, used_percent);
+
+        self.accelerators = state
+            .accelerators
+            .into_iter()
+            .map(|accel| AcceleratorInfo {
+                accel_type: match accel.accel_type.to_ascii_uppercase().as_str() {
+                    "GPU" => AcceleratorType::Gpu,
+                    "NPU" => AcceleratorType::Npu,
+                    "TPU" => AcceleratorType::Tpu,
+                    "FPGA" => AcceleratorType::Fpga,
+                    "DLA" => AcceleratorType::Dla,
+                    "VPU" => AcceleratorType::Vpu,
+                    "IPU" => AcceleratorType::Ipu,
+                    _ => AcceleratorType::Other,
+                },
+                name: accel.name,
+                vendor: accel.vendor,
+                utilization: accel.utilization,
+                temperature: accel.temperature,
+                power: accel.power_watts,
+                power_limit: accel.power_limit_watts,
+                memory_total: accel.memory_total_bytes,
+                memory_used: accel.memory_used_bytes,
+                clock_core: accel.clock_mhz,
+                clock_memory: accel.memory_clock_mhz,
+                ..Default::default()
+            })
+            .collect();
+        self.accelerator_histories
+            .resize_with(self.accelerators.len(), || {
+                VecDeque::with_capacity(MAX_HISTORY)
+            });
+        for (history, accel) in self
+            .accelerator_histories
+            .iter_mut()
+            .zip(&self.accelerators)
+        {
+            push_history(history, accel.utilization as u64);
+        }
+
+        if let Some(system) = state.system {
+            self.system_info = SystemInfo {
+                hostname: system.hostname,
+                os: system.os,
+                kernel: system.kernel,
+                uptime: Duration::from_secs(system.uptime_secs),
+                ..Default::default()
+            };
+        }
+        self.disk_info = state
+            .disks
+            .into_iter()
+            .map(|disk| DiskInfo {
+                name: disk.name,
+                mount_point: disk.mount_point,
+                total: disk.total_bytes,
+                used: disk.used_bytes,
+                filesystem: disk.filesystem,
+            })
+            .collect();
+        self.processes = snapshot.processes;
+        self.hardware_events = state.hardware_events;
+
+        self.last_update = Instant::now();
+        Ok(())
+    }
+
+    /// Combined network rate (bytes/s) of a process
+    pub fn process_net_rate(&self, pid: u32) -> f64 {
+        self.process_net_rates
+            .get(&pid)
+            .map_or(0.0, |(rx, tx)| rx + tx)
+    }
+
+    /// Toggle sorting the process list by network throughput
+    pub fn toggle_network_sort(&mut self) {
+        self.sort_by_network = !self.sort_by_network;
+        self.scroll_position = 0;
+    }
+
     /// Get filtered processes based on current display mode
     pub fn get_filtered_processes(&self) -> Vec<&ProcessMonitorInfo> {
         use ProcessDisplayMode::*;
 
         match self.process_display_mode {
+            All | Cpu if self.sort_by_network => {
+                // Show processes by network throughput, busiest first
+                let mut procs: Vec<&ProcessMonitorInfo> = self.processes.iter().collect();
+                procs.sort_by(|a, b| {
+                    self.process_net_rate(b.pid)
+                        .total_cmp(&self.process_net_rate(a.pid))
+                        .then(b.cpu_percent.total_cmp(&a.cpu_percent))
+                });
+                procs
+            }
             All => {
                 // Show all processes, sorted by CPU usage then memory
                 let mut procs: Vec<&ProcessMonitorInfo> = self.processes.iter().collect();
@@ -1073,6 +1308,7 @@ impl App {
     pub fn process_mode_name(&self) -> String {
         use ProcessDisplayMode::*;
         match self.process_display_mode {
+            All | Cpu if self.sort_by_network => "Processes by Network".to_string(),
             All => "All Processes".to_string(),
             Cpu => "CPU Processes".to_string(),
             Accelerator(idx) => {
@@ -1218,3 +1454,11 @@ impl App {
             .map(|agent| format!("Cache: {} entries", agent.cache_size()))
     }
 }
+
+/// Append to a history, dropping the oldest point past `MAX_HISTORY`
+fn push_history(history: &mut VecDeque<u64>, value: u64) {
+    history.push_back(value);
+    if history.len() > MAX_HISTORY {
+        history.pop_front();
+    }
+}
@@ -0,0 +1,468 @@
+//! Fleet view: one row per host, polled from each host's `simond`
+//!
+//! The host table can be grouped by tag or swapped for the worst GPUs
+//! across the fleet; Enter opens the regular dashboard attached to the
+//! selected host and returns here when it is closed.
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
+
+use super::app::App;
+use super::ui::{auto_unit, threshold_color};
+use crate::fleet::{group_by_tag, worst_gpus, FleetMonitor, GpuRanking, HostStatus};
+
+/// GPUs listed in the worst-GPU view
+const WORST_GPU_COUNT: usize = 20;
+
+/// What the table shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Hosts,
+    Groups,
+    WorstGpus,
+}
+
+/// Fleet screen state
+struct FleetView {
+    statuses: Vec<HostStatus>,
+    last_poll: Option<Instant>,
+    view: View,
+    ranking: GpuRanking,
+    /// Index into `statuses` of each table row; `None` for group headers
+    rows: Vec<Option<usize>>,
+    table: TableState,
+}
+
+impl FleetView {
+    fn new() -> Self {
+        Self {
+            statuses: Vec::new(),
+            last_poll: None,
+            view: View::Hosts,
+            ranking: GpuRanking::default(),
+            rows: Vec::new(),
+            table: TableState::default(),
+        }
+    }
+
+    /// Host of the selected row
+    fn selected_host(&self) -> Option<usize> {
+        self.table.selected().and_then(|row| *self.rows.get(row)?)
+    }
+
+    /// Move the selection to the next host row in `direction`
+    fn move_selection(&mut self, direction: isize) {
+        let len = self.rows.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let mut row = self.table.selected().map_or(-1, |row| row as isize);
+        for _ in 0..len {
+            row = (row + direction).rem_euclid(len);
+            if self.rows[row as usize].is_some() {
+                self.table.select(Some(row as usize));
+                return;
+            }
+        }
+    }
+
+    fn set_view(&mut self, view: View) {
+        self.view = if self.view == view { View::Hosts } else { view };
+        self.table.select(None);
+    }
+}
+
+/// Run the fleet screen until the user quits
+pub(super) fn run<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    monitor: FleetMonitor,
+    interval: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (refresh_tx, statuses_rx) = spawn_poller(monitor.clone(), interval);
+    let mut view = FleetView::new();
+
+    loop {
+        while let Ok(statuses) = statuses_rx.try_recv() {
+            view.statuses = statuses;
+            view.last_poll = Some(Instant::now());
+        }
+        terminal.draw(|f| draw(f, &mut view, monitor.hosts().len()))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up => view.move_selection(-1),
+            KeyCode::Down => view.move_selection(1),
+            KeyCode::Char('g') => view.set_view(View::Groups),
+            KeyCode::Char('w') => view.set_view(View::WorstGpus),
+            KeyCode::Char('s') => view.ranking = view.ranking.next(),
+            KeyCode::Char('r') => {
+                let _ = refresh_tx.send(());
+            }
+            KeyCode::Enter => {
+                let Some(index) = view.selected_host() else {
+                    continue;
+                };
+                let name = &view.statuses[index].name;
+                if let Some(host) = monitor.hosts().iter().find(|h| &h.name == name) {
+                    let mut app = App::attach(monitor.client(host))?;
+                    super::run_app(terminal, &mut app)?;
+                    terminal.clear()?;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Poll in the background: after every interval, or when asked to
+fn spawn_poller(
+    monitor: FleetMonitor,
+    interval: Duration,
+) -> (Sender<()>, Receiver<Vec<HostStatus>>) {
+    let (refresh_tx, refresh_rx) = mpsc::channel();
+    let (statuses_tx, statuses_rx) = mpsc::channel();
+    std::thread::spawn(move || loop {
+        if statuses_tx.send(monitor.poll()).is_err() {
+            return;
+        }
+        match refresh_rx.recv_timeout(interval) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    });
+    (refresh_tx, statuses_rx)
+}
+
+fn draw(f: &mut Frame, view: &mut FleetView, host_count: usize) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(f.area());
+
+    draw_header(f, view, host_count, chunks[0]);
+    match view.view {
+        View::Hosts | View::Groups => draw_hosts(f, view, chunks[1]),
+        View::WorstGpus => draw_worst_gpus(f, view, chunks[1]),
+    }
+    draw_footer(f, chunks[2]);
+}
+
+fn draw_header(f: &mut Frame, view: &FleetView, host_count: usize, area: Rect) {
+    let up = view.statuses.iter().filter(|s| s.is_up()).count();
+    let down = view.statuses.len() - up;
+    let summaries: Vec<_> = view.statuses.iter().map(HostStatus::summary).collect();
+    let gpus: usize = summaries.iter().map(|s| s.gpu_count).sum();
+    let failing = summaries
+        .iter()
+        .filter(|s| !s.failing_checks.is_empty())
+        .count();
+    let polled = match view.last_poll {
+        Some(at) => format!("polled {}s ago", at.elapsed().as_secs()),
+        None => "polling...".to_string(),
+    };
+
+    let line = Line::from(vec![
+        Span::styled(
+            format!("{} hosts ", host_count),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{} up ", up), Style::default().fg(Color::Green)),
+        Span::styled(
+            format!("{} down ", down),
+            Style::default().fg(if down > 0 {
+                Color::Red
+            } else {
+                Color::DarkGray
+            }),
+        ),
+        Span::raw(format!("│ {} GPUs │ ", gpus)),
+        Span::styled(
+            format!("{} with failing checks ", failing),
+            Style::default().fg(if failing > 0 {
+                Color::Yellow
+            } else {
+                Color::DarkGray
+            }),
+        ),
+        Span::styled(
+            format!("│ {}", polled),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]);
+    let header = Paragraph::new(line).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Silicon Monitor - Fleet"),
+    );
+    f.render_widget(header, area);
+}
+
+fn percent_cell(value: Option<f32>) -> Cell<'static> {
+    match value {
+        Some(value) => {
+            Cell::from(format!("{:.0}%", value)).style(Style::default().fg(threshold_color(value)))
+        }
+        None => Cell::from("-").style(Style::default().fg(Color::DarkGray)),
+    }
+}
+
+fn temperature_cell(value: Option<f32>) -> Cell<'static> {
+    match value {
+        Some(value) => {
+            let color = match value {
+                t if t >= 85.0 => Color::Red,
+                t if t >= 75.0 => Color::Yellow,
+                _ => Color::Green,
+            };
+            Cell::from(format!("{:.0}°C", value)).style(Style::default().fg(color))
+        }
+        None => Cell::from("-").style(Style::default().fg(Color::DarkGray)),
+    }
+}
+
+fn host_row(status: &HostStatus) -> Row<'static> {
+    if !status.is_up() {
+        return Row::new(vec![
+            Cell::from(status.name.clone()),
+            Cell::from("DOWN").style(Style::default().fg(Color::Red)),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(status.error.clone().unwrap_or_default())
+                .style(Style::default().fg(Color::Red)),
+        ]);
+    }
+
+    let summary = status.summary();
+    let health = match (summary.health_score, summary.health_status) {
+        (Some(score), Some(health)) => {
+            let color = match health {
+                crate::health::HealthStatus::Critical => Color::Red,
+                crate::health::HealthStatus::Warning => Color::Yellow,
+                crate::health::HealthStatus::Unknown => Color::DarkGray,
+                _ => Color::Green,
+            };
+            Cell::from(score.to_string()).style(Style::default().fg(color))
+        }
+        _ => Cell::from("-").style(Style::default().fg(Color::DarkGray)),
+    };
+    let gpu_memory = if summary.gpu_memory_total > 0 {
+        format!(
+            "{}/{}",
+            auto_unit(summary.gpu_memory_used),
+            auto_unit(summary.gpu_memory_total)
+        )
+    } else {
+        "-".to_string()
+    };
+
+    Row::new(vec![
+        Cell::from(status.name.clone()),
+        Cell::from("UP").style(Style::default().fg(Color::Green)),
+        Cell::from(summary.gpu_count.to_string()),
+        percent_cell(summary.gpu_utilization),
+        Cell::from(gpu_memory).style(
+            Style::default().fg(threshold_color(summary.gpu_memory_percent().unwrap_or(0.0))),
+        ),
+        temperature_cell(summary.max_gpu_temperature),
+        Cell::from(
+            summary
+                .gpu_power_watts
+                .map_or("-".to_string(), |w| format!("{:.0}W", w)),
+        ),
+        percent_cell(summary.cpu_utilization),
+        percent_cell(summary.memory_percent),
+        health,
+        Cell::from(summary.failing_checks.join(", ")).style(Style::default().fg(Color::Yellow)),
+    ])
+}
+
+fn draw_hosts(f: &mut Frame, view: &mut FleetView, area: Rect) {
+    let mut rows = Vec::new();
+    view.rows.clear();
+    match view.view {
+        View::Groups => {
+            for (tag, hosts) in group_by_tag(&view.statuses) {
+                let up = hosts.iter().filter(|h| h.is_up()).count();
+                rows.push(
+                    Row::new(vec![Cell::from(format!(
+                        "▸ {} ({}/{} up)",
+                        tag,
+                        up,
+                        hosts.len()
+                    ))])
+                    .style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                );
+                view.rows.push(None);
+                for host in hosts {
+                    rows.push(host_row(host));
+                    view.rows
+                        .push(view.statuses.iter().position(|s| std::ptr::eq(s, host)));
+                }
+            }
+        }
+        _ => {
+            for (index, status) in view.statuses.iter().enumerate() {
+                rows.push(host_row(status));
+                view.rows.push(Some(index));
+            }
+        }
+    }
+    if view.table.selected().is_none() {
+        view.move_selection(1);
+    }
+
+    let header = Row::new(vec![
+        "Host",
+        "State",
+        "GPUs",
+        "GPU%",
+        "GPU Mem",
+        "Max T",
+        "Power",
+        "CPU%",
+        "Mem%",
+        "Health",
+        "Failing checks",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+    let widths = [
+        Constraint::Length(16),
+        Constraint::Length(5),
+        Constraint::Length(4),
+        Constraint::Length(5),
+        Constraint::Length(13),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Min(20),
+    ];
+    let title = if view.view == View::Groups {
+        "Hosts by tag"
+    } else {
+        "Hosts"
+    };
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .column_spacing(1);
+    f.render_stateful_widget(table, area, &mut view.table);
+}
+
+fn draw_worst_gpus(f: &mut Frame, view: &mut FleetView, area: Rect) {
+    let gpus = worst_gpus(&view.statuses, view.ranking, WORST_GPU_COUNT);
+    view.rows = gpus
+        .iter()
+        .map(|gpu| view.statuses.iter().position(|s| s.name == gpu.host))
+        .collect();
+    if view.table.selected().is_none() {
+        view.move_selection(1);
+    }
+
+    let rows: Vec<Row> = gpus
+        .iter()
+        .map(|gpu| {
+            Row::new(vec![
+                Cell::from(gpu.host.clone()),
+                Cell::from(gpu.index.to_string()),
+                Cell::from(gpu.name.clone()),
+                percent_cell(Some(gpu.utilization)),
+                percent_cell(Some(gpu.memory_percent)),
+                temperature_cell(gpu.temperature),
+                Cell::from(
+                    gpu.power_watts
+                        .map_or("-".to_string(), |w| format!("{:.0}W", w)),
+                ),
+            ])
+        })
+        .collect();
+    let header = Row::new(vec!["Host", "GPU", "Name", "Util", "Mem", "Temp", "Power"]).style(
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+    let widths = [
+        Constraint::Length(16),
+        Constraint::Length(4),
+        Constraint::Min(20),
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Length(6),
+        Constraint::Length(6),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Worst {} GPUs by {} (s: change)",
+            WORST_GPU_COUNT, view.ranking
+        )))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .column_spacing(1);
+    f.render_stateful_widget(table, area, &mut view.table);
+}
+
+fn draw_footer(f: &mut Frame, area: Rect) {
+    let key = |k: &'static str| {
+        Span::styled(
+            k,
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let help = Paragraph::new(Line::from(vec![
+        key("q"),
+        Span::raw(" Quit  "),
+        key("↑↓"),
+        Span::raw(" Select  "),
+        key("Enter"),
+        Span::raw(" Open host  "),
+        key("g"),
+        Span::raw(" Group by tag  "),
+        key("w"),
+        Span::raw(" Worst GPUs  "),
+        key("s"),
+        Span::raw(" Ranking  "),
+        key("r"),
+        Span::raw(" Refresh"),
+    ]))
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(help, area);
+}
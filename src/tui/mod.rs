@@ -15,12 +15,39 @@ use std::io;
 use std::time::{Duration, Instant};
 
 mod app;
+mod fleet;
 mod ui;
 
 pub use app::{AcceleratorInfo, AcceleratorType, App};
 
 /// Run the TUI application
 pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    run_with(App::new()?)
+}
+
+/// Run the TUI on data from a running `simond` instead of local collectors
+pub fn attach(client: crate::daemon::DaemonClient) -> Result<(), Box<dyn std::error::Error>> {
+    run_with(App::attach(client)?)
+}
+
+/// Run the fleet view over `monitor`'s hosts, polling every `interval`
+pub fn fleet(
+    monitor: crate::fleet::FleetMonitor,
+    interval: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    with_terminal(|terminal| fleet::run(terminal, monitor, interval))
+}
+
+fn run_with(mut app: App) -> Result<(), Box<dyn std::error::Error>> {
+    with_terminal(|terminal| run_app(terminal, &mut app))
+}
+
+/// Set up the terminal, run `screen`, and restore the terminal afterwards
+fn with_terminal(
+    screen: impl FnOnce(
+        &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -28,9 +55,7 @@ pub fn run() -> Result<(), Box<dyn std::error::Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app and run
-    let mut app = App::new()?;
-    let res = run_app(&mut terminal, &mut app);
+    let res = screen(&mut terminal);
 
     // Restore terminal
     disable_raw_mode()?;
@@ -104,6 +129,7 @@ fn run_app<B: Backend>(
                             KeyCode::Up => app.scroll_up(),
                             KeyCode::Down => app.scroll_down(),
                             KeyCode::Char('r') => app.reset_stats(),
+                            KeyCode::Char('n') | KeyCode::Char('N') => app.toggle_network_sort(),
                             KeyCode::Char('a') | KeyCode::Char('A') => app.toggle_agent_input(),
                             KeyCode::Char('c') | KeyCode::Char('C') => {
                                 if app.selected_tab == 5 {
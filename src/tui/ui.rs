@@ -52,7 +52,7 @@ mod glances_colors {
 /// - 50-70%: Cyan (CAREFUL)
 /// - 70-90%: Yellow (WARNING)
 /// - 90-100%: Red (CRITICAL)
-fn threshold_color(percent: f32) -> Color {
+pub(super) fn threshold_color(percent: f32) -> Color {
     match percent {
         p if p >= 90.0 => glances_colors::CRITICAL,
         p if p >= 70.0 => glances_colors::WARNING,
@@ -75,7 +75,7 @@ fn trend_indicator(current: f32, previous: f32) -> (&'static str, Color) {
 }
 
 /// Format bytes to human-readable with auto unit (Glances-style)
-fn auto_unit(bytes: u64) -> String {
+pub(super) fn auto_unit(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -103,12 +103,18 @@ pub fn draw(f: &mut Frame, app: &App) {
     let ram_section_height: u16 = 3; // 1 RAM bar
     let disk_section_height: u16 = 3; // 1 Disk bar (aggregated)
     let network_section_height: u16 = 3; // 1 Network bar
+    let events_section_height: u16 = if app.hardware_events.is_empty() {
+        0
+    } else {
+        (app.hardware_events.len().min(MAX_EVENT_LINES) + 2) as u16 // border + newest events
+    };
 
     let hardware_height = cpu_section_height
         + accelerator_section_height
         + ram_section_height
         + disk_section_height
-        + network_section_height;
+        + network_section_height
+        + events_section_height;
 
     // Calculate remaining space for process list
     let total_height = f.area().height;
@@ -126,6 +132,9 @@ pub fn draw(f: &mut Frame, app: &App) {
     constraints.push(Constraint::Length(ram_section_height)); // RAM
     constraints.push(Constraint::Length(disk_section_height)); // Disk
     constraints.push(Constraint::Length(network_section_height)); // Network
+    if events_section_height > 0 {
+        constraints.push(Constraint::Length(events_section_height)); // Hardware events
+    }
     constraints.push(Constraint::Length(process_height)); // Process list (dynamic)
     constraints.push(Constraint::Length(3)); // Footer
 
@@ -156,6 +165,11 @@ pub fn draw(f: &mut Frame, app: &App) {
     draw_network_bar(f, app, chunks[chunk_idx]);
     chunk_idx += 1;
 
+    if events_section_height > 0 {
+        draw_hardware_events(f, app, chunks[chunk_idx]);
+        chunk_idx += 1;
+    }
+
     draw_nvtop_processes(f, app, chunks[chunk_idx]);
     chunk_idx += 1;
 
@@ -295,7 +309,7 @@ fn draw_single_accelerator(
     };
 
     // Compact: All key metrics with Glances-style formatting
-    let accel_util_label = format!(
+    let mut accel_util_label = format!(
         "{}: {:.0}% @ {} MHz │ MEM: {}/{} ({:.0}%) @ {} MHz │ {:.0}°C │ {:.0}/{:.0}W",
         type_str,
         accel.utilization,
@@ -308,6 +322,10 @@ fn draw_single_accelerator(
         accel.power.unwrap_or(0.0),
         accel.power_limit.unwrap_or(0.0)
     );
+    if !accel.throttle.is_empty() {
+        let causes: Vec<&str> = accel.throttle.iter().map(|c| c.name()).collect();
+        accel_util_label.push_str(&format!(" │ THROTTLED: {}", causes.join(", ")));
+    }
 
     let accel_color = threshold_color(accel.utilization);
 
@@ -423,7 +441,7 @@ fn draw_cpu_bar(f: &mut Frame, app: &App, area: Rect) {
         .unwrap_or(app.cpu_info.utilization);
     let (trend_arrow, _trend_color) = trend_indicator(app.cpu_info.utilization, prev_cpu);
 
-    let cpu_label = format!(
+    let mut cpu_label = format!(
         "CPU {} {:.0}% │ {} cores @ {} MHz │ {:.0}°C",
         trend_arrow,
         app.cpu_info.utilization,
@@ -431,6 +449,9 @@ fn draw_cpu_bar(f: &mut Frame, app: &App, area: Rect) {
         app.cpu_info.frequency.unwrap_or(0),
         app.cpu_info.temperature.unwrap_or(0.0)
     );
+    if app.cpu_throttled {
+        cpu_label.push_str(" │ THROTTLED: thermal");
+    }
 
     let cpu_color = threshold_color(app.cpu_info.utilization);
 
@@ -552,10 +573,11 @@ fn draw_disk_bar(f: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Draw network bar gauge with Glances-style formatting
-fn draw_network_bar(f: &mut Frame, _app: &App, area: Rect) {
+fn draw_network_bar(f: &mut Frame, app: &App, area: Rect) {
     // For Windows, show basic network info with Glances styling
     #[cfg(windows)]
     {
+        let _ = app;
         let net_label = "NET │ Rx: -- │ Tx: -- │ Windows interface";
         let net_gauge = Gauge::default()
             .block(
@@ -579,6 +601,11 @@ fn draw_network_bar(f: &mut Frame, _app: &App, area: Rect) {
 
     #[cfg(not(windows))]
     {
+        if !app.rdma_devices.is_empty() {
+            draw_rdma_bar(f, app, area);
+            return;
+        }
+
         let net_label = "NET │ Rx: -- │ Tx: -- │ Platform-specific";
         let net_gauge = Gauge::default()
             .block(
@@ -601,6 +628,134 @@ fn draw_network_bar(f: &mut Frame, _app: &App, area: Rect) {
     }
 }
 
+/// Maximum number of hardware events shown in the events panel
+const MAX_EVENT_LINES: usize = 4;
+
+/// Draw recent kernel log hardware events (Xid, MCE/EDAC, NVMe resets, ...)
+/// Draw InfiniBand/RoCE ports in the network bar
+///
+/// The gauge shows the busiest port's link utilization.
+#[cfg(not(windows))]
+fn draw_rdma_bar(f: &mut Frame, app: &App, area: Rect) {
+    let mut utilization: f64 = 0.0;
+    let mut problem = false;
+    let ports: Vec<String> = app
+        .rdma_devices
+        .iter()
+        .flat_map(|d| d.ports.iter().map(move |p| (d, p)))
+        .filter(|(_, p)| p.is_link_up())
+        .map(|(device, port)| {
+            let name = format!("{}/{}", device.name, port.port);
+            let rate = app.rdma_rates.iter().find(|r| r.name() == name);
+            utilization = utilization.max(rate.and_then(|r| r.utilization).unwrap_or(0.0));
+            problem |= !port.state.is_active() || rate.is_some_and(|r| r.error_delta > 0);
+            let speed = port
+                .rate_gbps
+                .map(|g| format!("{}G", g))
+                .unwrap_or_else(|| "--".to_string());
+            match rate {
+                Some(r) => format!(
+                    "{} {} {} Rx:{}/s Tx:{}/s",
+                    name,
+                    port.state,
+                    speed,
+                    auto_unit(r.rx_bytes_per_sec as u64),
+                    auto_unit(r.tx_bytes_per_sec as u64)
+                ),
+                None => format!("{} {} {}", name, port.state, speed),
+            }
+        })
+        .collect();
+
+    let label = if ports.is_empty() {
+        "RDMA │ No active links".to_string()
+    } else {
+        format!("RDMA │ {}", ports.join(" │ "))
+    };
+    let color = if problem {
+        glances_colors::WARNING
+    } else {
+        threshold_color(utilization as f32)
+    };
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default().borders(Borders::ALL).title(Span::styled(
+                "Network",
+                Style::default()
+                    .fg(glances_colors::TITLE)
+                    .add_modifier(Modifier::BOLD),
+            )),
+        )
+        .gauge_style(Style::default().fg(color).add_modifier(Modifier::BOLD))
+        .percent(utilization.clamp(0.0, 100.0) as u16)
+        .label(label);
+
+    f.render_widget(gauge, area);
+}
+
+fn draw_hardware_events(f: &mut Frame, app: &App, area: Rect) {
+    use crate::hwlog::EventSeverity;
+
+    let skip = app.hardware_events.len().saturating_sub(MAX_EVENT_LINES);
+    let items: Vec<ListItem> = app
+        .hardware_events
+        .iter()
+        .skip(skip)
+        .rev()
+        .map(|event| {
+            let color = match event.severity {
+                EventSeverity::Critical => glances_colors::CRITICAL,
+                EventSeverity::Warning => glances_colors::WARNING,
+                EventSeverity::Info => glances_colors::CAREFUL,
+            };
+            let age = event
+                .age()
+                .map(|a| format!("{:>3}m ago", a.as_secs() / 60))
+                .unwrap_or_else(|| "       ".to_string());
+
+            let mut spans = vec![
+                Span::styled(
+                    format!("{} ", event.severity),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(age, Style::default().fg(glances_colors::INACTIVE)),
+                Span::raw(" │ "),
+                Span::styled(event.kind.to_string(), Style::default().fg(color)),
+            ];
+            if let Some(ref device) = event.device {
+                spans.push(Span::raw(format!(" on {}", device)));
+            }
+            if let Some(pid) = event.pid {
+                spans.push(Span::styled(
+                    format!(
+                        " │ PID {} {}",
+                        pid,
+                        event.process.as_deref().unwrap_or_default()
+                    ),
+                    Style::default().fg(glances_colors::TITLE),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!("Hardware Events ({} in last hour)", app.hardware_events.len());
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(glances_colors::SEPARATOR))
+            .title(Span::styled(
+                title,
+                Style::default()
+                    .fg(glances_colors::TITLE)
+                    .add_modifier(Modifier::BOLD),
+            )),
+    );
+
+    f.render_widget(list, area);
+}
+
 /// Draw CPU utilization graph with sparkline (DEPRECATED - use draw_cpu_bar)
 #[allow(dead_code)]
 fn draw_cpu_graph(f: &mut Frame, app: &App, area: Rect) {
@@ -806,6 +961,12 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                         .fg(glances_colors::TITLE)
                         .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
                 ),
+                Span::styled(
+                    if app.sort_by_network { "NET▼" } else { "NET" },
+                    Style::default()
+                        .fg(glances_colors::TITLE)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                ),
             ])
             .bottom_margin(1);
 
@@ -836,6 +997,13 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                             Style::default().fg(cpu_color),
                         ),
                         Span::styled(auto_unit(p.memory_bytes), Style::default().fg(Color::White)),
+                        Span::styled(
+                            match app.process_net_rate(p.pid) {
+                                rate if rate > 0.0 => format!("{}/s", auto_unit(rate as u64)),
+                                _ => "-".to_string(),
+                            },
+                            Style::default().fg(Color::White),
+                        ),
                     ])
                 })
                 .collect();
@@ -1032,6 +1200,7 @@ fn draw_nvtop_processes(f: &mut Frame, app: &App, area: Rect) {
                 Constraint::Min(20),    // Name (flexible)
                 Constraint::Length(8),  // CPU%
                 Constraint::Length(12), // Memory
+                Constraint::Length(10), // Network
             ]
         }
         super::app::ProcessDisplayMode::Gpu(_) => {
@@ -1095,6 +1264,13 @@ fn draw_nvtop_footer(f: &mut Frame, _app: &App, area: Rect) {
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(" Reset  "),
+        Span::styled(
+            "n",
+            Style::default()
+                .fg(glances_colors::TITLE)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" Net sort  "),
         Span::styled(
             "↑↓",
             Style::default()
@@ -2,14 +2,44 @@
 //!
 //! This module provides functionality to maximize Jetson performance by setting
 //! all frequencies (CPU, GPU, EMC, engines) to their maximum values.
+//!
+//! It is a native replacement for the `jetson_clocks` script: clocks are
+//! pinned through cpufreq, devfreq and the BPMP debugfs EMC clock, the fan is
+//! set to full speed, and the previous values are stored as JSON so that
+//! [`JetsonClocks::restore`] puts back exactly what was there. Every domain
+//! reports its own outcome, so a read-only or missing node (common in
+//! containers) does not stop the others.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::utils::clocks::JetsonClocks;
+//!
+//! let clocks = JetsonClocks::new();
+//! for result in clocks.enable().unwrap().results {
+//!     println!("{} {}: {:?}", result.domain, result.name, result.error);
+//! }
+//! clocks.restore().unwrap();
+//! ```
 
-use super::verify_sudo_available;
-use crate::error::{SimonError, Result};
+use super::log_privileged_operation;
+use crate::error::{Result, SimonError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+
+/// Default location of the stored pre-change state
+pub const DEFAULT_STORE_PATH: &str = "/var/lib/simon/jetson_clocks.json";
+
+/// Format version of the stored state
+const STORE_VERSION: u32 = 1;
+
+/// devfreq device name fragments that identify the integrated GPU
+const GPU_DEVFREQ_NAMES: &[&str] = &["gpu", "gv11b", "gp10b", "ga10b", "gb10b", "gm20b"];
 
-const JETSON_CLOCKS_PATHS: &[&str] = &["/usr/bin/jetson_clocks", "/home/nvidia/jetson_clocks.sh"];
+/// Fan PWM value for full speed
+const FAN_MAX_PWM: &str = "255";
 
 /// Jetson Clocks status
 #[derive(Debug, Clone)]
@@ -20,171 +50,606 @@ pub struct JetsonClocksStatus {
     pub engines: Vec<String>,
 }
 
-/// Check if jetson_clocks is available
-pub fn is_available() -> bool {
-    JETSON_CLOCKS_PATHS.iter().any(|p| Path::new(p).exists())
+/// Clock domain handled by jetson_clocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClockDomain {
+    /// CPU core (cpufreq)
+    Cpu,
+    /// Integrated GPU (devfreq)
+    Gpu,
+    /// External memory controller (devfreq or BPMP clock)
+    Emc,
+    /// Deep learning accelerator (devfreq)
+    Dla,
+    /// Programmable vision accelerator (devfreq)
+    Pva,
+    /// PWM fan
+    Fan,
 }
 
-/// Find jetson_clocks executable
-fn find_jetson_clocks() -> Result<String> {
-    JETSON_CLOCKS_PATHS
-        .iter()
-        .find(|p| Path::new(p).exists())
-        .map(|s| s.to_string())
-        .ok_or_else(|| SimonError::DeviceNotFound("jetson_clocks not found".to_string()))
+impl fmt::Display for ClockDomain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ClockDomain::Cpu => "CPU",
+            ClockDomain::Gpu => "GPU",
+            ClockDomain::Emc => "EMC",
+            ClockDomain::Dla => "DLA",
+            ClockDomain::Pva => "PVA",
+            ClockDomain::Fan => "FAN",
+        };
+        f.write_str(name)
+    }
 }
 
-/// Enable jetson_clocks (maximize performance)
-pub fn enable() -> Result<()> {
-    // Security: Verify sudo is available
-    verify_sudo_available()?;
+/// A sysfs file and its value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileValue {
+    /// Absolute sysfs path
+    pub path: PathBuf,
+    /// Value (trimmed)
+    pub value: String,
+}
+
+/// Files of one clock domain instance, in write order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainFiles {
+    /// Domain
+    pub domain: ClockDomain,
+    /// Instance name (`cpu0`, `17000000.ga10b`, `hwmon3`, ...)
+    pub name: String,
+    /// Files and values
+    pub files: Vec<FileValue>,
+}
 
-    let jetson_clocks = find_jetson_clocks()?;
+/// Stored pre-change state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockState {
+    /// Format version
+    pub version: u32,
+    /// Domains and their original values
+    pub domains: Vec<DomainFiles>,
+}
 
-    let output = Command::new("sudo")
-        .arg(&jetson_clocks)
-        .output()
-        .map_err(|e| SimonError::Io(e))?;
+/// Outcome for one domain instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainResult {
+    /// Domain
+    pub domain: ClockDomain,
+    /// Instance name
+    pub name: String,
+    /// Error, if any write failed
+    pub error: Option<String>,
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(SimonError::CommandFailed(format!(
-            "Failed to enable jetson_clocks: {}",
-            stderr
-        )));
+/// Per-domain outcome of [`JetsonClocks::enable`] or [`JetsonClocks::restore`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClockReport {
+    /// One entry per domain instance
+    pub results: Vec<DomainResult>,
+}
+
+impl ClockReport {
+    /// Whether every domain succeeded
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|r| r.error.is_none())
     }
 
-    Ok(())
+    /// Domains that failed
+    pub fn failures(&self) -> impl Iterator<Item = &DomainResult> {
+        self.results.iter().filter(|r| r.error.is_some())
+    }
 }
 
-/// Disable jetson_clocks (restore original settings)
-pub fn disable() -> Result<()> {
-    // Security: Verify sudo is available
-    verify_sudo_available()?;
+/// Native jetson_clocks
+#[derive(Debug, Clone)]
+pub struct JetsonClocks {
+    root: PathBuf,
+    store_path: PathBuf,
+}
+
+impl Default for JetsonClocks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JetsonClocks {
+    /// jetson_clocks on the live system, storing state in [`DEFAULT_STORE_PATH`]
+    pub fn new() -> Self {
+        Self {
+            root: PathBuf::from("/"),
+            store_path: PathBuf::from(DEFAULT_STORE_PATH),
+        }
+    }
 
-    let jetson_clocks = find_jetson_clocks()?;
+    /// Use the sysfs tree under `root` (for tests and chroots)
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = root.into();
+        self
+    }
 
-    let output = Command::new("sudo")
-        .arg(&jetson_clocks)
-        .arg("--restore")
-        .output()
-        .map_err(|e| SimonError::Io(e))?;
+    /// Where the pre-change state is stored
+    pub fn with_store_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.store_path = path.into();
+        self
+    }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(SimonError::CommandFailed(format!(
-            "Failed to disable jetson_clocks: {}",
-            stderr
-        )));
+    /// Stored state location
+    pub fn store_path(&self) -> &Path {
+        &self.store_path
     }
 
-    Ok(())
-}
+    /// Domains and the values that pin them to maximum
+    pub fn plan(&self) -> Vec<DomainFiles> {
+        let mut plan = self.cpu_plan();
+        plan.extend(self.devfreq_plan());
+        if !plan.iter().any(|d| d.domain == ClockDomain::Emc) {
+            plan.extend(self.bpmp_emc_plan());
+        }
+        plan.extend(self.fan_plan());
+        plan
+    }
 
-/// Show jetson_clocks status
-pub fn show() -> Result<JetsonClocksStatus> {
-    let jetson_clocks = find_jetson_clocks()?;
-
-    let output = Command::new(&jetson_clocks)
-        .arg("--show")
-        .output()
-        .map_err(|e| SimonError::Io(e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(SimonError::CommandFailed(format!(
-            "Failed to get jetson_clocks status: {}",
-            stderr
-        )));
-    }
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let engines: Vec<String> = stdout
-        .lines()
-        .filter(|line| line.contains("MaxFreq="))
-        .map(|line| {
-            line.split_whitespace()
-                .next()
-                .unwrap_or("unknown")
-                .to_string()
-        })
-        .collect();
+    /// Current values of every file [`JetsonClocks::plan`] writes
+    pub fn read_state(&self) -> ClockState {
+        let domains = self
+            .plan()
+            .into_iter()
+            .map(|planned| DomainFiles {
+                files: planned
+                    .files
+                    .iter()
+                    .filter_map(|file| {
+                        Some(FileValue {
+                            path: file.path.clone(),
+                            value: read_trimmed(&self.under(&file.path))?,
+                        })
+                    })
+                    .collect(),
+                ..planned
+            })
+            .collect();
+        ClockState {
+            version: STORE_VERSION,
+            domains,
+        }
+    }
 
-    // Check if active by examining current frequencies
-    let active = check_if_active()?;
-
-    Ok(JetsonClocksStatus { active, engines })
-}
-
-/// Check if jetson_clocks is currently active
-fn check_if_active() -> Result<bool> {
-    // Check if CPU frequencies are maxed
-    if let Ok(cpu_online) = fs::read_to_string("/sys/devices/system/cpu/online") {
-        for cpu_range in cpu_online.trim().split(',') {
-            if let Some((start, end)) = parse_cpu_range(cpu_range) {
-                for cpu in start..=end {
-                    let min_path = format!(
-                        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_min_freq",
-                        cpu
-                    );
-                    let max_path = format!(
-                        "/sys/devices/system/cpu/cpu{}/cpufreq/scaling_max_freq",
-                        cpu
-                    );
-
-                    if let (Ok(min), Ok(max)) =
-                        (fs::read_to_string(&min_path), fs::read_to_string(&max_path))
-                    {
-                        let min_freq: u32 = min.trim().parse().unwrap_or(0);
-                        let max_freq: u32 = max.trim().parse().unwrap_or(0);
-
-                        if min_freq != max_freq {
-                            return Ok(false);
-                        }
-                    }
-                }
-            }
+    /// Save the current state as JSON (like `jetson_clocks --store`)
+    pub fn store(&self) -> Result<ClockState> {
+        let state = self.read_state();
+        if let Some(dir) = self.store_path.parent() {
+            fs::create_dir_all(dir)?;
         }
+        let json = serde_json::to_string_pretty(&state)
+            .map_err(|e| SimonError::Other(format!("Failed to encode clock state: {}", e)))?;
+        fs::write(&self.store_path, json)?;
+        Ok(state)
     }
 
-    Ok(true)
-}
+    /// Load the stored state
+    pub fn stored(&self) -> Result<ClockState> {
+        let json = fs::read_to_string(&self.store_path).map_err(|e| {
+            SimonError::Configuration(format!(
+                "No stored clock state at {}: {}",
+                self.store_path.display(),
+                e
+            ))
+        })?;
+        let state: ClockState = serde_json::from_str(&json)
+            .map_err(|e| SimonError::Parse(format!("{}: {}", self.store_path.display(), e)))?;
+        if state.version != STORE_VERSION {
+            return Err(SimonError::Parse(format!(
+                "{}: unsupported version {}",
+                self.store_path.display(),
+                state.version
+            )));
+        }
+        Ok(state)
+    }
+
+    /// Pin every clock to maximum and the fan to full speed
+    ///
+    /// The current state is stored first unless a stored state already
+    /// exists, so repeated calls keep the original values.
+    pub fn enable(&self) -> Result<ClockReport> {
+        if !self.store_path.exists() {
+            self.store()?;
+        }
+        Ok(self.apply(&self.plan(), false))
+    }
 
-fn parse_cpu_range(range: &str) -> Option<(u32, u32)> {
-    if range.contains('-') {
-        let parts: Vec<&str> = range.split('-').collect();
-        if parts.len() == 2 {
-            let start = parts[0].parse().ok()?;
-            let end = parts[1].parse().ok()?;
-            return Some((start, end));
+    /// Write back the stored state (like `jetson_clocks --restore`)
+    ///
+    /// The stored state is removed once every domain is restored, so the
+    /// next [`enable`](Self::enable) stores fresh values instead of writing
+    /// back stale ones later. It is kept when a domain fails so the restore
+    /// can be retried.
+    pub fn restore(&self) -> Result<ClockReport> {
+        let report = self.apply(&self.stored()?.domains, true);
+        if report.is_success() {
+            fs::remove_file(&self.store_path)?;
         }
+        Ok(report)
+    }
+
+    /// Whether every domain is at its pinned value
+    pub fn is_active(&self) -> bool {
+        let plan = self.plan();
+        !plan.is_empty()
+            && plan.iter().all(|domain| {
+                domain.files.iter().all(|file| {
+                    read_trimmed(&self.under(&file.path)).as_deref() == Some(file.value.as_str())
+                })
+            })
+    }
+
+    /// Write each domain's files (in reverse order when restoring, so
+    /// minimums drop before maximums do)
+    fn apply(&self, domains: &[DomainFiles], restore: bool) -> ClockReport {
+        let action = if restore { "restore" } else { "enable" };
+        let results = domains
+            .iter()
+            .map(|domain| {
+                let mut files: Vec<&FileValue> = domain.files.iter().collect();
+                if restore {
+                    files.reverse();
+                }
+                let error = files.into_iter().find_map(|file| {
+                    fs::write(self.under(&file.path), &file.value)
+                        .err()
+                        .map(|e| format!("{}: {}", file.path.display(), e))
+                });
+                log_privileged_operation(
+                    &format!("jetson_clocks {} {} {}", action, domain.domain, domain.name),
+                    error.is_none(),
+                );
+                DomainResult {
+                    domain: domain.domain,
+                    name: domain.name.clone(),
+                    error,
+                }
+            })
+            .collect();
+        ClockReport { results }
+    }
+
+    fn under(&self, path: &Path) -> PathBuf {
+        self.root.join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// `scaling_max_freq` then `scaling_min_freq` at `cpuinfo_max_freq`
+    fn cpu_plan(&self) -> Vec<DomainFiles> {
+        let cpu_dir = Path::new("/sys/devices/system/cpu");
+        let mut cpus: Vec<(u32, String)> = list_dir(&self.under(cpu_dir))
+            .into_iter()
+            .filter_map(|name| Some((name.strip_prefix("cpu")?.parse().ok()?, name)))
+            .collect();
+        cpus.sort();
+        cpus.into_iter()
+            .filter_map(|(_, name)| {
+                let cpufreq = cpu_dir.join(&name).join("cpufreq");
+                let max = read_trimmed(&self.under(&cpufreq.join("cpuinfo_max_freq")))?;
+                Some(DomainFiles {
+                    domain: ClockDomain::Cpu,
+                    name,
+                    files: vec![
+                        file_value(cpufreq.join("scaling_max_freq"), &max),
+                        file_value(cpufreq.join("scaling_min_freq"), &max),
+                    ],
+                })
+            })
+            .collect()
+    }
+
+    /// `max_freq` then `min_freq` at the highest available frequency
+    fn devfreq_plan(&self) -> Vec<DomainFiles> {
+        let devfreq_dir = Path::new("/sys/class/devfreq");
+        let mut names = list_dir(&self.under(devfreq_dir));
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let domain = devfreq_domain(&name)?;
+                let dir = devfreq_dir.join(&name);
+                let max = read_trimmed(&self.under(&dir.join("available_frequencies")))?
+                    .split_whitespace()
+                    .filter_map(|f| f.parse::<u64>().ok())
+                    .max()?
+                    .to_string();
+                Some(DomainFiles {
+                    domain,
+                    name,
+                    files: vec![
+                        file_value(dir.join("max_freq"), &max),
+                        file_value(dir.join("min_freq"), &max),
+                    ],
+                })
+            })
+            .collect()
+    }
+
+    /// Lock the BPMP EMC clock at `max_rate`
+    fn bpmp_emc_plan(&self) -> Vec<DomainFiles> {
+        let emc = Path::new("/sys/kernel/debug/bpmp/debug/clk/emc");
+        let Some(max) = read_trimmed(&self.under(&emc.join("max_rate"))) else {
+            return Vec::new();
+        };
+        vec![DomainFiles {
+            domain: ClockDomain::Emc,
+            name: "emc".to_string(),
+            files: vec![
+                file_value(emc.join("mrq_rate_locked"), "1"),
+                file_value(emc.join("rate"), &max),
+            ],
+        }]
+    }
+
+    /// `pwm1` of pwm-fan hwmon devices, or the L4T 32 `target_pwm`
+    fn fan_plan(&self) -> Vec<DomainFiles> {
+        let hwmon_dir = Path::new("/sys/class/hwmon");
+        let mut names = list_dir(&self.under(hwmon_dir));
+        names.sort();
+        let mut plan: Vec<DomainFiles> = names
+            .into_iter()
+            .filter_map(|name| {
+                let dir = hwmon_dir.join(&name);
+                let chip = read_trimmed(&self.under(&dir.join("name")))?;
+                let pwm = dir.join("pwm1");
+                (chip.replace(['-', '_'], "") == "pwmfan" && self.under(&pwm).exists()).then(|| {
+                    DomainFiles {
+                        domain: ClockDomain::Fan,
+                        name,
+                        files: vec![file_value(pwm, FAN_MAX_PWM)],
+                    }
+                })
+            })
+            .collect();
+        let legacy = Path::new("/sys/devices/pwm-fan/target_pwm");
+        if plan.is_empty() && self.under(legacy).exists() {
+            plan.push(DomainFiles {
+                domain: ClockDomain::Fan,
+                name: "pwm-fan".to_string(),
+                files: vec![file_value(legacy.to_path_buf(), FAN_MAX_PWM)],
+            });
+        }
+        plan
+    }
+}
+
+/// Domain of a devfreq device, by name
+fn devfreq_domain(name: &str) -> Option<ClockDomain> {
+    let lower = name.to_lowercase();
+    if lower.contains("nvdla") {
+        Some(ClockDomain::Dla)
+    } else if lower.contains("pva") {
+        Some(ClockDomain::Pva)
+    } else if lower.contains("emc") || lower.ends_with(".mc") {
+        Some(ClockDomain::Emc)
+    } else if GPU_DEVFREQ_NAMES.iter().any(|gpu| lower.contains(gpu)) {
+        Some(ClockDomain::Gpu)
     } else {
-        let cpu = range.parse().ok()?;
-        return Some((cpu, cpu));
+        None
     }
-    None
+}
+
+fn file_value(path: PathBuf, value: &str) -> FileValue {
+    FileValue {
+        path,
+        value: value.to_string(),
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn list_dir(path: &Path) -> Vec<String> {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Check if jetson_clocks can manage any clock on this system
+pub fn is_available() -> bool {
+    !JetsonClocks::new().plan().is_empty()
+}
+
+/// Enable jetson_clocks (maximize performance)
+pub fn enable() -> Result<ClockReport> {
+    JetsonClocks::new().enable()
+}
+
+/// Disable jetson_clocks (restore original settings)
+pub fn disable() -> Result<ClockReport> {
+    JetsonClocks::new().restore()
+}
+
+/// Show jetson_clocks status
+pub fn show() -> Result<JetsonClocksStatus> {
+    let clocks = JetsonClocks::new();
+    let engines = clocks
+        .plan()
+        .iter()
+        .map(|domain| format!("{} {}", domain.domain, domain.name))
+        .collect();
+    Ok(JetsonClocksStatus {
+        active: clocks.is_active(),
+        engines,
+    })
 }
 
 /// Store current configuration
 pub fn store() -> Result<()> {
-    // Security: Verify sudo is available
-    verify_sudo_available()?;
+    JetsonClocks::new().store().map(|_| ())
+}
 
-    let jetson_clocks = find_jetson_clocks()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
 
-    let output = Command::new("sudo")
-        .arg(&jetson_clocks)
-        .arg("--store")
-        .output()
-        .map_err(|e| SimonError::Io(e))?;
+    /// Fake Orin sysfs: two CPUs, GPU, DLA, PVA and an unrelated devfreq
+    /// device, the BPMP EMC clock and a pwm-fan hwmon
+    fn fake_tegra() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        for cpu in ["cpu0", "cpu1"] {
+            let dir = format!("sys/devices/system/cpu/{}/cpufreq", cpu);
+            write(&format!("{}/cpuinfo_max_freq", dir), "1510400\n");
+            write(&format!("{}/scaling_max_freq", dir), "1510400\n");
+            write(&format!("{}/scaling_min_freq", dir), "729600\n");
+        }
+        write("sys/devices/system/cpu/online", "0-1\n");
+        for (device, freqs) in [
+            ("17000000.ga10b", "306000000 408000000 625000000"),
+            ("15880000.nvdla0", "115200000 614400000"),
+            ("16000000.pva0", "115200000 704000000"),
+            ("3b40000.some-other", "1000 2000"),
+        ] {
+            let dir = format!("sys/class/devfreq/{}", device);
+            write(&format!("{}/available_frequencies", dir), freqs);
+            write(&format!("{}/min_freq", dir), "115200000\n");
+            write(&format!("{}/max_freq", dir), "408000000\n");
+        }
+        let emc = "sys/kernel/debug/bpmp/debug/clk/emc";
+        write(&format!("{}/max_rate", emc), "3199000000\n");
+        write(&format!("{}/rate", emc), "2133000000\n");
+        write(&format!("{}/mrq_rate_locked", emc), "0\n");
+        write("sys/class/hwmon/hwmon0/name", "cpu_thermal\n");
+        write("sys/class/hwmon/hwmon3/name", "pwmfan\n");
+        write("sys/class/hwmon/hwmon3/pwm1", "77\n");
+        dir
+    }
+
+    fn read(root: &Path, path: &str) -> String {
+        read_trimmed(&root.join(path)).unwrap()
+    }
+
+    #[test]
+    fn test_enable_store_restore() {
+        let dir = fake_tegra();
+        let root = dir.path();
+        let clocks = JetsonClocks::new()
+            .with_root(root)
+            .with_store_path(root.join("var/lib/simon/jetson_clocks.json"));
+
+        let domains: Vec<String> = clocks
+            .plan()
+            .iter()
+            .map(|d| format!("{} {}", d.domain, d.name))
+            .collect();
+        assert_eq!(
+            domains,
+            vec![
+                "CPU cpu0",
+                "CPU cpu1",
+                "DLA 15880000.nvdla0",
+                "PVA 16000000.pva0",
+                "GPU 17000000.ga10b",
+                "EMC emc",
+                "FAN hwmon3",
+            ]
+        );
+        assert!(!clocks.is_active());
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(SimonError::CommandFailed(format!(
-            "Failed to store jetson_clocks config: {}",
-            stderr
-        )));
+        let report = clocks.enable().unwrap();
+        assert!(report.is_success());
+        assert_eq!(report.results.len(), 7);
+        assert!(clocks.is_active());
+        assert_eq!(
+            read(root, "sys/devices/system/cpu/cpu1/cpufreq/scaling_min_freq"),
+            "1510400"
+        );
+        assert_eq!(
+            read(root, "sys/class/devfreq/17000000.ga10b/min_freq"),
+            "625000000"
+        );
+        assert_eq!(
+            read(root, "sys/kernel/debug/bpmp/debug/clk/emc/rate"),
+            "3199000000"
+        );
+        assert_eq!(read(root, "sys/class/hwmon/hwmon3/pwm1"), "255");
+        assert_eq!(
+            read(root, "sys/class/devfreq/3b40000.some-other/min_freq"),
+            "115200000"
+        );
+
+        // A second enable keeps the original state on disk
+        clocks.enable().unwrap();
+        let stored = clocks.stored().unwrap();
+        assert_eq!(stored.domains[0].files[1].value, "729600");
+
+        let report = clocks.restore().unwrap();
+        assert!(report.is_success());
+        assert!(!clocks.is_active());
+        assert_eq!(
+            read(root, "sys/devices/system/cpu/cpu0/cpufreq/scaling_min_freq"),
+            "729600"
+        );
+        assert_eq!(
+            read(root, "sys/class/devfreq/15880000.nvdla0/max_freq"),
+            "408000000"
+        );
+        assert_eq!(
+            read(root, "sys/kernel/debug/bpmp/debug/clk/emc/mrq_rate_locked"),
+            "0"
+        );
+        assert_eq!(read(root, "sys/class/hwmon/hwmon3/pwm1"), "77");
+        assert!(!clocks.store_path().exists());
+        assert!(clocks.restore().is_err());
+
+        // Values changed after the restore are what the next enable stores
+        fs::write(
+            root.join("sys/devices/system/cpu/cpu0/cpufreq/scaling_min_freq"),
+            "1036800",
+        )
+        .unwrap();
+        clocks.enable().unwrap();
+        clocks.restore().unwrap();
+        assert_eq!(
+            read(root, "sys/devices/system/cpu/cpu0/cpufreq/scaling_min_freq"),
+            "1036800"
+        );
     }
 
-    Ok(())
+    #[test]
+    fn test_per_domain_failure() {
+        let dir = fake_tegra();
+        let root = dir.path();
+        let clocks = JetsonClocks::new()
+            .with_root(root)
+            .with_store_path(root.join("state.json"));
+        // A directory where the GPU's min_freq should be makes that write fail
+        let gpu_min = root.join("sys/class/devfreq/17000000.ga10b/min_freq");
+        fs::remove_file(&gpu_min).unwrap();
+        fs::create_dir(&gpu_min).unwrap();
+
+        let report = clocks.enable().unwrap();
+        assert!(!report.is_success());
+        let failures: Vec<String> = report
+            .failures()
+            .map(|r| format!("{} {}", r.domain, r.name))
+            .collect();
+        assert_eq!(failures, vec!["GPU 17000000.ga10b"]);
+        assert_eq!(
+            read(root, "sys/devices/system/cpu/cpu0/cpufreq/scaling_min_freq"),
+            "1510400"
+        );
+
+        assert!(JetsonClocks::new()
+            .with_root(root)
+            .with_store_path(root.join("missing.json"))
+            .restore()
+            .is_err());
+    }
 }
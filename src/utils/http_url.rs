@@ -0,0 +1,61 @@
+//! Plain `http://` URLs for the exporters that speak HTTP/1.1 over a bare
+//! TCP stream (OTLP, InfluxDB)
+
+/// Split `http://host[:port][/path]` into `host:port` and `/path`
+///
+/// `default_port` is used when the URL has none. HTTPS is rejected; the
+/// exporters have no TLS stack and expect a local collector or proxy.
+pub(crate) fn split_http_url(url: &str, default_port: u16) -> Result<(String, String), String> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        if url.starts_with("https://") {
+            format!(
+                "{}: HTTPS is not supported, use a local collector or proxy",
+                url
+            )
+        } else {
+            format!("{}: expected an http:// URL", url)
+        }
+    })?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(format!("{}: missing host", url));
+    }
+    // A port after the last colon, outside IPv6 brackets
+    let has_port = authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| !port.contains(']'));
+    let authority = if has_port {
+        authority.to_string()
+    } else {
+        format!("{}:{}", authority, default_port)
+    };
+    Ok((authority, path.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_http_url() {
+        assert_eq!(
+            split_http_url("http://collector:4318/v1/metrics", 80).unwrap(),
+            ("collector:4318".to_string(), "/v1/metrics".to_string())
+        );
+        assert_eq!(
+            split_http_url("http://influx", 8086).unwrap(),
+            ("influx:8086".to_string(), "/".to_string())
+        );
+        assert_eq!(split_http_url("http://[::1]/x", 80).unwrap().0, "[::1]:80");
+        assert_eq!(
+            split_http_url("http://[::1]:9000", 80).unwrap().0,
+            "[::1]:9000"
+        );
+        assert!(split_http_url("https://collector/v1/metrics", 80).is_err());
+        assert!(split_http_url("influx:8086", 8086).is_err());
+        assert!(split_http_url("http:///path", 80).is_err());
+    }
+}
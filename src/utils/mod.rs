@@ -1,8 +1,14 @@
 //! Utility modules for advanced Jetson management
 
 pub mod clocks;
+pub mod nvpmodel_conf;
 pub mod power_mode;
 pub mod swap;
+pub mod tegrastats;
 
+mod http_url;
+mod rate_window;
 mod security;
-pub(crate) use security::verify_sudo_available;
+pub(crate) use http_url::split_http_url;
+pub(crate) use rate_window::{RateWindow, DEFAULT_RATE_WINDOW};
+pub(crate) use security::{log_privileged_operation, verify_sudo_available};
@@ -0,0 +1,663 @@
+//! Native nvpmodel.conf parser
+//!
+//! Parses `/etc/nvpmodel.conf` (a link to one of the per-SKU files in
+//! `/etc/nvpmodel/`) without the `nvpmodel` binary. Each power mode exposes
+//! its CPU online mask, per-cluster frequency limits, GPU/EMC/DLA caps and
+//! TPC power-gating mask, and can be compared against live sysfs, either to
+//! find drift in the active mode or to preview what switching modes changes.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::utils::nvpmodel_conf::NvpmodelConfig;
+//! use std::path::Path;
+//!
+//! let config = NvpmodelConfig::load_system().unwrap();
+//! for mode in &config.modes {
+//!     println!("{} {} cpus={:#x}", mode.id, mode.name, mode.cpu_online_mask());
+//! }
+//! for diff in config.drift(Path::new("/")).unwrap() {
+//!     println!("{}", diff);
+//! }
+//! ```
+
+use crate::error::{Result, SimonError};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Default configuration, normally a link into [`SKU_CONFIG_DIR`]
+pub const SYSTEM_CONFIG: &str = "/etc/nvpmodel.conf";
+/// Directory of the per-SKU configurations
+pub const SKU_CONFIG_DIR: &str = "/etc/nvpmodel";
+/// Where nvpmodel records the active mode (`pmode:0002 fmode:quiet`)
+pub const STATUS_FILE: &str = "/var/lib/nvpmodel/status";
+/// Directory of the BPMP clocks that `TYPE=CLOCK` parameters name
+const BPMP_CLOCK_DIR: &str = "/sys/kernel/debug/bpmp/debug/clk";
+
+/// How a parameter is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    /// Arguments are sysfs files
+    File,
+    /// Arguments are BPMP clock names
+    Clock,
+}
+
+/// A `< PARAM TYPE=... NAME=... >` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    /// Parameter name (`CPU_ONLINE`, `GPU`, `EMC`, ...)
+    pub name: String,
+    /// Parameter type
+    pub param_type: ParamType,
+    /// Arguments and the file or clock each one writes
+    pub args: Vec<(String, String)>,
+}
+
+impl Param {
+    /// Sysfs path written for `arg`
+    pub fn path(&self, arg: &str) -> Option<PathBuf> {
+        let target = self.args.iter().find(|(name, _)| name == arg)?.1.as_str();
+        match self.param_type {
+            ParamType::File => Some(PathBuf::from(target)),
+            ParamType::Clock if target.starts_with('/') => Some(PathBuf::from(target)),
+            ParamType::Clock => {
+                let file = if arg.starts_with("MIN") {
+                    "min_rate"
+                } else {
+                    "max_rate"
+                };
+                Some(Path::new(BPMP_CLOCK_DIR).join(target).join(file))
+            }
+        }
+    }
+}
+
+/// One `PARAM ARG VALUE` line of a power mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeSetting {
+    /// Parameter name
+    pub param: String,
+    /// Argument name
+    pub arg: String,
+    /// Value as written (`-1` is the hardware maximum)
+    pub value: String,
+}
+
+/// Frequency limits of a clock domain, in the unit of its sysfs files
+/// (kHz for cpufreq, Hz for devfreq); `-1` means the hardware maximum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreqLimits {
+    /// Parameter name (`CPU_A78_0`, `GPU`, ...)
+    pub name: String,
+    /// Minimum frequency
+    pub min: Option<i64>,
+    /// Maximum frequency
+    pub max: Option<i64>,
+}
+
+/// A `< POWER_MODEL ID=... NAME=... >` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NvpMode {
+    /// Mode ID
+    pub id: u32,
+    /// Mode name
+    pub name: String,
+    /// Settings in file order
+    pub settings: Vec<ModeSetting>,
+}
+
+impl NvpMode {
+    /// Value of `param`/`arg`
+    pub fn value(&self, param: &str, arg: &str) -> Option<&str> {
+        self.settings
+            .iter()
+            .find(|s| s.param == param && s.arg == arg)
+            .map(|s| s.value.as_str())
+    }
+
+    fn number(&self, param: &str, arg: &str) -> Option<i64> {
+        self.value(param, arg)?.parse().ok()
+    }
+
+    /// `CPU_ONLINE` entries as (core, online)
+    pub fn cpu_online(&self) -> Vec<(usize, bool)> {
+        self.settings
+            .iter()
+            .filter(|s| s.param == "CPU_ONLINE")
+            .filter_map(|s| {
+                let core = s.arg.strip_prefix("CORE_")?.parse().ok()?;
+                Some((core, s.value != "0"))
+            })
+            .collect()
+    }
+
+    /// Online cores as a bit mask (bit n = core n)
+    pub fn cpu_online_mask(&self) -> u64 {
+        self.cpu_online()
+            .into_iter()
+            .filter(|(core, online)| *online && *core < 64)
+            .fold(0, |mask, (core, _)| mask | 1 << core)
+    }
+
+    /// Per-cluster CPU frequency limits in kHz (`CPU_A57`, `CPU_A78_0`, ...)
+    pub fn clusters(&self) -> Vec<FreqLimits> {
+        let mut names: Vec<&str> = Vec::new();
+        for setting in &self.settings {
+            if setting.param.starts_with("CPU_")
+                && setting.param != "CPU_ONLINE"
+                && setting.arg.ends_with("_FREQ")
+                && !names.contains(&setting.param.as_str())
+            {
+                names.push(&setting.param);
+            }
+        }
+        names.into_iter().map(|name| self.limits(name)).collect()
+    }
+
+    /// GPU frequency limits in Hz
+    pub fn gpu(&self) -> Option<FreqLimits> {
+        self.settings
+            .iter()
+            .any(|s| s.param == "GPU")
+            .then(|| self.limits("GPU"))
+    }
+
+    /// EMC frequency cap (`0` means uncapped)
+    pub fn emc_cap(&self) -> Option<i64> {
+        self.number("EMC", "MAX_FREQ")
+    }
+
+    /// Maximum frequencies of the DLA and PVA clocks (`DLA0_CORE`, `PVA0_VPS`, ...)
+    pub fn dla_caps(&self) -> Vec<(String, i64)> {
+        self.settings
+            .iter()
+            .filter(|s| {
+                (s.param.starts_with("DLA") || s.param.starts_with("PVA")) && s.arg == "MAX_FREQ"
+            })
+            .filter_map(|s| Some((s.param.clone(), s.value.parse().ok()?)))
+            .collect()
+    }
+
+    /// TPC power-gating mask (set bits are gated TPCs)
+    pub fn tpc_pg_mask(&self) -> Option<u32> {
+        self.value("TPC_POWER_GATING", "TPC_PG_MASK")?.parse().ok()
+    }
+
+    fn limits(&self, param: &str) -> FreqLimits {
+        FreqLimits {
+            name: param.to_string(),
+            min: self.number(param, "MIN_FREQ"),
+            max: self.number(param, "MAX_FREQ"),
+        }
+    }
+}
+
+/// Comparison result of one setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    /// Sysfs holds the expected value
+    Match,
+    /// Sysfs holds a different value
+    Drift,
+    /// The file cannot be read (or the parameter is not declared)
+    Missing,
+    /// `-1`/`0` could not be resolved to a hardware limit
+    Unknown,
+}
+
+/// A mode setting compared against sysfs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingDiff {
+    /// Parameter name
+    pub param: String,
+    /// Argument name
+    pub arg: String,
+    /// File the setting writes
+    pub path: Option<PathBuf>,
+    /// Expected value, with `-1`/`0` limits resolved when possible
+    pub expected: String,
+    /// Current sysfs value
+    pub current: Option<String>,
+    /// Outcome
+    pub status: DiffStatus,
+}
+
+impl fmt::Display for SettingDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} -> {}",
+            self.param,
+            self.arg,
+            self.current.as_deref().unwrap_or("?"),
+            self.expected
+        )?;
+        match self.status {
+            DiffStatus::Match | DiffStatus::Drift => Ok(()),
+            DiffStatus::Missing => write!(f, " (missing)"),
+            DiffStatus::Unknown => write!(f, " (limit unknown)"),
+        }
+    }
+}
+
+/// Parsed nvpmodel configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NvpmodelConfig {
+    /// Declared parameters
+    pub params: Vec<Param>,
+    /// Power modes in file order
+    pub modes: Vec<NvpMode>,
+    /// `< PM_CONFIG DEFAULT=... >`
+    pub default_mode: Option<u32>,
+}
+
+enum Section {
+    None,
+    Param(usize),
+    Mode(usize),
+}
+
+impl NvpmodelConfig {
+    /// Parse configuration text
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut config = Self::default();
+        let mut section = Section::None;
+
+        for (index, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| {
+                SimonError::Parse(format!("nvpmodel.conf line {}: {}", index + 1, message))
+            };
+
+            if let Some(header) = line.strip_prefix('<') {
+                let header = header
+                    .strip_suffix('>')
+                    .ok_or_else(|| error("unterminated header"))?;
+                let mut words = header.split_whitespace();
+                let kind = words.next().unwrap_or_default();
+                let attrs: Vec<(&str, &str)> =
+                    words.filter_map(|word| word.split_once('=')).collect();
+                let attr = |key: &str| attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+
+                section = match kind {
+                    "PARAM" => {
+                        let name = attr("NAME").ok_or_else(|| error("PARAM without NAME"))?;
+                        let param_type = match attr("TYPE") {
+                            Some("CLOCK") => ParamType::Clock,
+                            _ => ParamType::File,
+                        };
+                        config.params.push(Param {
+                            name: name.to_string(),
+                            param_type,
+                            args: Vec::new(),
+                        });
+                        Section::Param(config.params.len() - 1)
+                    }
+                    "POWER_MODEL" => {
+                        let id = attr("ID")
+                            .and_then(|id| id.parse().ok())
+                            .ok_or_else(|| error("POWER_MODEL without a numeric ID"))?;
+                        config.modes.push(NvpMode {
+                            id,
+                            name: attr("NAME").unwrap_or_default().to_string(),
+                            settings: Vec::new(),
+                        });
+                        Section::Mode(config.modes.len() - 1)
+                    }
+                    "PM_CONFIG" => {
+                        config.default_mode = attr("DEFAULT").and_then(|id| id.parse().ok());
+                        Section::None
+                    }
+                    _ => Section::None,
+                };
+                continue;
+            }
+
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match section {
+                Section::Param(index) => match words.as_slice() {
+                    [arg, target] => config.params[index]
+                        .args
+                        .push((arg.to_string(), target.to_string())),
+                    _ => return Err(error("expected `ARG PATH`")),
+                },
+                Section::Mode(index) => match words.as_slice() {
+                    [param, arg, value] => config.modes[index].settings.push(ModeSetting {
+                        param: param.to_string(),
+                        arg: arg.to_string(),
+                        value: value.to_string(),
+                    }),
+                    _ => return Err(error("expected `PARAM ARG VALUE`")),
+                },
+                Section::None => {}
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Load a configuration file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| SimonError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Load [`SYSTEM_CONFIG`]
+    pub fn load_system() -> Result<Self> {
+        Self::load(SYSTEM_CONFIG)
+    }
+
+    /// Per-SKU configuration files in [`SKU_CONFIG_DIR`], sorted
+    pub fn sku_files() -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(SKU_CONFIG_DIR)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "conf"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Mode with `id`
+    pub fn mode(&self, id: u32) -> Option<&NvpMode> {
+        self.modes.iter().find(|mode| mode.id == id)
+    }
+
+    /// Mode named `name` (case-insensitive)
+    pub fn mode_by_name(&self, name: &str) -> Option<&NvpMode> {
+        self.modes
+            .iter()
+            .find(|mode| mode.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Parameter named `name`
+    pub fn param(&self, name: &str) -> Option<&Param> {
+        self.params.iter().find(|param| param.name == name)
+    }
+
+    /// Compare every setting of mode `id` with the sysfs tree under `root`
+    pub fn diff(&self, id: u32, root: &Path) -> Result<Vec<SettingDiff>> {
+        let mode = self
+            .mode(id)
+            .ok_or_else(|| SimonError::InvalidValue(format!("Power mode {} not found", id)))?;
+        Ok(mode
+            .settings
+            .iter()
+            .map(|setting| self.diff_setting(setting, root))
+            .collect())
+    }
+
+    /// Settings of the active mode (from [`STATUS_FILE`]) that no longer
+    /// match sysfs, e.g. after manual clock or hotplug tweaks
+    pub fn drift(&self, root: &Path) -> Result<Vec<SettingDiff>> {
+        let active = active_mode(root)?;
+        Ok(self
+            .diff(active, root)?
+            .into_iter()
+            .filter(|diff| diff.status != DiffStatus::Match)
+            .collect())
+    }
+
+    /// Settings that switching to mode `id` would change
+    pub fn preview(&self, id: u32, root: &Path) -> Result<Vec<SettingDiff>> {
+        Ok(self
+            .diff(id, root)?
+            .into_iter()
+            .filter(|diff| diff.status != DiffStatus::Match)
+            .collect())
+    }
+
+    fn diff_setting(&self, setting: &ModeSetting, root: &Path) -> SettingDiff {
+        let path = self
+            .param(&setting.param)
+            .and_then(|param| param.path(&setting.arg));
+        let current = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(under(root, path)).ok())
+            .map(|value| value.trim().to_string());
+
+        // -1 asks for the hardware maximum, a 0 minimum for the hardware minimum
+        let limit = match (setting.arg.as_str(), setting.value.as_str()) {
+            (arg, "-1") if arg.ends_with("_FREQ") => Some(true),
+            ("MIN_FREQ", "0") => Some(false),
+            _ => None,
+        };
+        let expected = match (limit, &path) {
+            (Some(max), Some(path)) => hardware_limit(root, path, max),
+            (Some(_), None) => None,
+            (None, _) => Some(setting.value.clone()),
+        };
+
+        let status = match (&current, &expected) {
+            (None, _) => DiffStatus::Missing,
+            (Some(_), None) => DiffStatus::Unknown,
+            (Some(current), Some(expected)) if same_value(current, expected) => DiffStatus::Match,
+            (Some(_), Some(_)) => DiffStatus::Drift,
+        };
+        SettingDiff {
+            param: setting.param.clone(),
+            arg: setting.arg.clone(),
+            path,
+            expected: expected.unwrap_or_else(|| setting.value.clone()),
+            current,
+            status,
+        }
+    }
+}
+
+/// Active mode recorded in [`STATUS_FILE`] under `root`
+pub fn active_mode(root: &Path) -> Result<u32> {
+    let status = fs::read_to_string(under(root, Path::new(STATUS_FILE)))?;
+    status
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("pmode:"))
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| SimonError::Parse(format!("no pmode in {}", STATUS_FILE)))
+}
+
+/// `path` inside the tree rooted at `root`
+fn under(root: &Path, path: &Path) -> PathBuf {
+    root.join(path.strip_prefix("/").unwrap_or(path))
+}
+
+/// Hardware max/min behind a limit file: `cpuinfo_*_freq` next to cpufreq's
+/// `scaling_*_freq`, `available_frequencies` next to devfreq's `*_freq`
+fn hardware_limit(root: &Path, path: &Path, max: bool) -> Option<String> {
+    let file = path.file_name()?.to_str()?;
+    let dir = under(root, path.parent()?);
+    if file.starts_with("scaling_") {
+        let limit = if max {
+            "cpuinfo_max_freq"
+        } else {
+            "cpuinfo_min_freq"
+        };
+        return fs::read_to_string(dir.join(limit))
+            .ok()
+            .map(|value| value.trim().to_string());
+    }
+    let available = fs::read_to_string(dir.join("available_frequencies")).ok()?;
+    let frequencies = available
+        .split_whitespace()
+        .filter_map(|f| f.parse::<u64>().ok());
+    let limit = if max {
+        frequencies.max()
+    } else {
+        frequencies.min()
+    };
+    limit.map(|f| f.to_string())
+}
+
+fn same_value(current: &str, expected: &str) -> bool {
+    match (current.parse::<i64>(), expected.parse::<i64>()) {
+        (Ok(current), Ok(expected)) => current == expected,
+        _ => current == expected,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const CONF: &str = "\
+# Orin Nano style configuration
+< PARAM TYPE=FILE NAME=CPU_ONLINE >
+CORE_0 /sys/devices/system/cpu/cpu0/online
+CORE_1 /sys/devices/system/cpu/cpu1/online
+
+< PARAM TYPE=FILE NAME=TPC_POWER_GATING >
+TPC_PG_MASK /sys/devices/gpu.0/tpc_pg_mask
+
+< PARAM TYPE=FILE NAME=CPU_A78_0 >
+MIN_FREQ /sys/devices/system/cpu/cpu0/cpufreq/scaling_min_freq
+MAX_FREQ /sys/devices/system/cpu/cpu0/cpufreq/scaling_max_freq
+
+< PARAM TYPE=FILE NAME=GPU >
+MIN_FREQ /sys/devices/gpu.0/devfreq/17000000.ga10b/min_freq
+MAX_FREQ /sys/devices/gpu.0/devfreq/17000000.ga10b/max_freq
+
+< PARAM TYPE=FILE NAME=EMC >
+MAX_FREQ /sys/kernel/nvpmodel_emc_cap/emc_iso_cap
+
+< PARAM TYPE=CLOCK NAME=DLA0_CORE >
+MAX_FREQ nvdla0
+
+< POWER_MODEL ID=0 NAME=15W >
+CPU_ONLINE CORE_0 1
+CPU_ONLINE CORE_1 1
+TPC_POWER_GATING TPC_PG_MASK 0
+CPU_A78_0 MIN_FREQ 729600
+CPU_A78_0 MAX_FREQ -1
+GPU MIN_FREQ 0
+GPU MAX_FREQ -1
+EMC MAX_FREQ 0
+DLA0_CORE MAX_FREQ 614400000
+
+< POWER_MODEL ID=1 NAME=7W >
+CPU_ONLINE CORE_0 1
+CPU_ONLINE CORE_1 0
+TPC_POWER_GATING TPC_PG_MASK 2
+CPU_A78_0 MIN_FREQ 729600
+CPU_A78_0 MAX_FREQ 960000
+GPU MIN_FREQ 0
+GPU MAX_FREQ 408000000
+EMC MAX_FREQ 2133000000
+
+< PM_CONFIG DEFAULT=0 >
+";
+
+    fn write(root: &Path, path: &str, value: &str) {
+        let path = under(root, Path::new(path));
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, format!("{}\n", value)).unwrap();
+    }
+
+    /// Fake Orin sysfs in the state mode 0 leaves behind
+    fn fake_sysfs() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        write(root, "/sys/devices/system/cpu/cpu0/online", "1");
+        write(root, "/sys/devices/system/cpu/cpu1/online", "1");
+        write(root, "/sys/devices/gpu.0/tpc_pg_mask", "0");
+        let cpufreq = "/sys/devices/system/cpu/cpu0/cpufreq";
+        write(root, &format!("{}/scaling_min_freq", cpufreq), "729600");
+        write(root, &format!("{}/scaling_max_freq", cpufreq), "1510400");
+        write(root, &format!("{}/cpuinfo_max_freq", cpufreq), "1510400");
+        let devfreq = "/sys/devices/gpu.0/devfreq/17000000.ga10b";
+        write(root, &format!("{}/min_freq", devfreq), "306000000");
+        write(root, &format!("{}/max_freq", devfreq), "625000000");
+        write(
+            root,
+            &format!("{}/available_frequencies", devfreq),
+            "306000000 408000000 625000000",
+        );
+        write(root, "/sys/kernel/nvpmodel_emc_cap/emc_iso_cap", "0");
+        write(root, STATUS_FILE, "pmode:0000 fmode:quiet");
+        dir
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let config = NvpmodelConfig::parse(CONF).unwrap();
+        assert_eq!(config.params.len(), 6);
+        assert_eq!(config.default_mode, Some(0));
+        assert_eq!(
+            config.param("DLA0_CORE").unwrap().param_type,
+            ParamType::Clock
+        );
+        assert_eq!(
+            config.param("DLA0_CORE").unwrap().path("MAX_FREQ").unwrap(),
+            Path::new("/sys/kernel/debug/bpmp/debug/clk/nvdla0/max_rate")
+        );
+
+        let maxn = config.mode(0).unwrap();
+        assert_eq!(maxn.cpu_online_mask(), 0b11);
+        assert_eq!(maxn.emc_cap(), Some(0));
+        assert_eq!(maxn.dla_caps(), vec![("DLA0_CORE".to_string(), 614400000)]);
+        let low = config.mode_by_name("7w").unwrap();
+        assert_eq!(low.cpu_online(), vec![(0, true), (1, false)]);
+        assert_eq!(low.tpc_pg_mask(), Some(2));
+        assert_eq!(
+            low.clusters(),
+            vec![FreqLimits {
+                name: "CPU_A78_0".to_string(),
+                min: Some(729600),
+                max: Some(960000),
+            }]
+        );
+        assert_eq!(low.gpu().unwrap().max, Some(408000000));
+
+        assert!(NvpmodelConfig::parse("< POWER_MODEL NAME=X >").is_err());
+        assert!(NvpmodelConfig::parse("< PARAM NAME=GPU >\nMIN_FREQ\n").is_err());
+    }
+
+    #[test]
+    fn test_drift_and_preview() {
+        let dir = fake_sysfs();
+        let root = dir.path();
+        let config = NvpmodelConfig::parse(CONF).unwrap();
+
+        // Live state matches mode 0 except for the debugfs-only DLA clock
+        let drift = config.drift(root).unwrap();
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].param, "DLA0_CORE");
+        assert_eq!(drift[0].status, DiffStatus::Missing);
+
+        // A manual GPU cap shows up as drift against the resolved maximum
+        write(
+            root,
+            "/sys/devices/gpu.0/devfreq/17000000.ga10b/max_freq",
+            "408000000",
+        );
+        let drift = config.drift(root).unwrap();
+        let gpu = drift.iter().find(|d| d.param == "GPU").unwrap();
+        assert_eq!(gpu.status, DiffStatus::Drift);
+        assert_eq!(gpu.to_string(), "GPU MAX_FREQ: 408000000 -> 625000000");
+
+        // Switching to mode 1 changes the core, TPC, CPU max and EMC settings
+        let preview: Vec<String> = config
+            .preview(1, root)
+            .unwrap()
+            .iter()
+            .map(|d| d.to_string())
+            .collect();
+        assert_eq!(
+            preview,
+            vec![
+                "CPU_ONLINE CORE_1: 1 -> 0",
+                "TPC_POWER_GATING TPC_PG_MASK: 0 -> 2",
+                "CPU_A78_0 MAX_FREQ: 1510400 -> 960000",
+                "EMC MAX_FREQ: 0 -> 2133000000",
+            ]
+        );
+        assert!(config.preview(9, root).is_err());
+    }
+}
@@ -3,8 +3,10 @@
 //! NVPModel controls voltage regulators and power tree to optimize power efficiency.
 //! It supports various power budgets with different CPU/GPU configurations.
 
+use super::nvpmodel_conf::{self, NvpmodelConfig, SettingDiff, SYSTEM_CONFIG};
 use super::verify_sudo_available;
 use crate::error::{SimonError, Result};
+use std::path::Path;
 use std::process::Command;
 
 /// Power mode information
@@ -75,7 +77,49 @@ pub fn query() -> Result<PowerMode> {
 }
 
 /// List all available power modes
+///
+/// Read natively from `/etc/nvpmodel.conf` and nvpmodel's status file;
+/// the `nvpmodel` binary is only asked when the configuration is absent.
 pub fn list_modes() -> Result<NVPModelStatus> {
+    if Path::new(SYSTEM_CONFIG).exists() {
+        return status_from_config(&NvpmodelConfig::load_system()?, Path::new("/"));
+    }
+    list_modes_nvpmodel()
+}
+
+/// Modes, default and active mode from a parsed configuration
+///
+/// Without a status file (nvpmodel never ran) the default mode is active,
+/// as nvpmodel applies it at boot.
+fn status_from_config(config: &NvpmodelConfig, root: &Path) -> Result<NVPModelStatus> {
+    let default_id = config
+        .default_mode
+        .ok_or_else(|| SimonError::Parse(format!("no default mode in {}", SYSTEM_CONFIG)))?;
+    let power_mode = |id: u32| -> Result<PowerMode> {
+        let mode = config
+            .mode(id)
+            .ok_or_else(|| SimonError::InvalidValue(format!("Power mode {} not found", id)))?;
+        Ok(PowerMode {
+            id,
+            name: mode.name.clone(),
+            is_default: id == default_id,
+        })
+    };
+
+    let active = nvpmodel_conf::active_mode(root).unwrap_or(default_id);
+    Ok(NVPModelStatus {
+        current: power_mode(active)?,
+        modes: config
+            .modes
+            .iter()
+            .map(|mode| power_mode(mode.id))
+            .collect::<Result<_>>()?,
+        default: power_mode(default_id)?,
+    })
+}
+
+/// Parse the mode list printed by `nvpmodel -p --verbose`
+fn list_modes_nvpmodel() -> Result<NVPModelStatus> {
     let output = Command::new("nvpmodel")
         .arg("-p")
         .arg("--verbose")
@@ -179,15 +223,74 @@ pub fn set_mode(mode_id: u32, force: bool) -> Result<()> {
 
 /// Set power mode by name
 pub fn set_mode_by_name(mode_name: &str, force: bool) -> Result<()> {
-    let status = list_modes()?;
+    let id = if Path::new(SYSTEM_CONFIG).exists() {
+        NvpmodelConfig::load_system()?
+            .mode_by_name(mode_name)
+            .map(|mode| mode.id)
+    } else {
+        list_modes_nvpmodel()?
+            .modes
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(mode_name))
+            .map(|mode| mode.id)
+    };
+    let id = id
+        .ok_or_else(|| SimonError::InvalidValue(format!("Power mode '{}' not found", mode_name)))?;
 
-    let mode = status
-        .modes
-        .iter()
-        .find(|m| m.name.eq_ignore_ascii_case(mode_name))
-        .ok_or_else(|| {
-            SimonError::InvalidValue(format!("Power mode '{}' not found", mode_name))
-        })?;
+    set_mode(id, force)
+}
 
-    set_mode(mode.id, force)
+/// Settings `set_mode(mode_id)` would change, read natively from `/etc/nvpmodel.conf`
+pub fn preview_mode(mode_id: u32) -> Result<Vec<SettingDiff>> {
+    NvpmodelConfig::load_system()?.preview(mode_id, Path::new("/"))
+}
+
+/// Settings of the active mode that no longer match sysfs
+pub fn drift() -> Result<Vec<SettingDiff>> {
+    NvpmodelConfig::load_system()?.drift(Path::new("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const CONF: &str = "\
+< PARAM TYPE=FILE NAME=CPU_ONLINE >
+CORE_0 /sys/devices/system/cpu/cpu0/online
+
+< POWER_MODEL ID=0 NAME=MAXN >
+CPU_ONLINE CORE_0 1
+
+< POWER_MODEL ID=2 NAME=30W >
+CPU_ONLINE CORE_0 1
+
+< PM_CONFIG DEFAULT=2 >
+";
+
+    #[test]
+    fn test_status_from_config() {
+        let config = NvpmodelConfig::parse(CONF).unwrap();
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        // nvpmodel has not run yet: the default mode is active
+        let status = status_from_config(&config, root).unwrap();
+        assert_eq!(status.default.name, "30W");
+        assert_eq!(status.current.id, 2);
+        let modes: Vec<_> = status.modes.iter().map(|m| (m.id, m.is_default)).collect();
+        assert_eq!(modes, vec![(0, false), (2, true)]);
+
+        let status_file = root.join(nvpmodel_conf::STATUS_FILE.trim_start_matches('/'));
+        fs::create_dir_all(status_file.parent().unwrap()).unwrap();
+        fs::write(&status_file, "pmode:0000 fmode:quiet\n").unwrap();
+        let status = status_from_config(&config, root).unwrap();
+        assert_eq!(status.current.name, "MAXN");
+        assert!(!status.current.is_default);
+
+        // A status file left from another configuration
+        fs::write(&status_file, "pmode:0005\n").unwrap();
+        assert!(status_from_config(&config, root).is_err());
+    }
 }
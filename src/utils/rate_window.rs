@@ -0,0 +1,185 @@
+//! Error counter rates over a sliding time window
+//!
+//! Health checks run every few seconds, and one error between two of them
+//! extrapolates to hundreds per hour. Rates are instead taken over the
+//! per-refresh deltas of the last `window`, divided by at least
+//! `min_span`, so a single error reads as a handful per hour right after
+//! startup and settles to its real rate once a full window is covered.
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Default rate window
+pub(crate) const DEFAULT_RATE_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Shortest span a rate is divided by
+pub(crate) const MIN_RATE_SPAN: Duration = Duration::from_secs(900);
+
+/// Per-key counter deltas over a sliding window
+///
+/// `N` counters are tracked per key (e.g. corrected and uncorrected).
+pub(crate) struct RateWindow<K, const N: usize> {
+    /// How far back deltas count
+    window: Duration,
+    /// Time of the first refresh
+    started: Option<Instant>,
+    /// Totals from the previous refresh
+    previous: HashMap<K, [u64; N]>,
+    /// Time of the previous refresh
+    last: Option<Instant>,
+    /// Deltas of each refresh within the window, oldest first
+    deltas: VecDeque<(Instant, HashMap<K, [u64; N]>)>,
+}
+
+/// Counter changes of one key
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WindowedCounts<const N: usize> {
+    /// Change since the previous refresh
+    pub delta: [u64; N],
+    /// Change within the window
+    pub in_window: [u64; N],
+    /// Time since the previous refresh
+    pub interval: Duration,
+    /// Time the window actually covers (at most the window)
+    pub span: Duration,
+}
+
+impl<const N: usize> WindowedCounts<N> {
+    /// Rate of counter `i` per hour over the window
+    pub fn per_hour(&self, i: usize) -> f64 {
+        self.in_window[i] as f64 * 3600.0 / self.span.max(MIN_RATE_SPAN).as_secs_f64()
+    }
+}
+
+impl<K: Clone + Eq + Hash, const N: usize> RateWindow<K, N> {
+    /// Create an empty window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            started: None,
+            previous: HashMap::new(),
+            last: None,
+            deltas: VecDeque::new(),
+        }
+    }
+
+    /// Record new totals and return the changes of every key
+    ///
+    /// The first call records a baseline and returns `None`. Keys that
+    /// appear later start from their first totals, and counters that go
+    /// backwards (reset through sysfs) count as unchanged.
+    pub fn update(
+        &mut self,
+        now: Instant,
+        totals: HashMap<K, [u64; N]>,
+    ) -> Option<Vec<(K, WindowedCounts<N>)>> {
+        let Some(last) = self.last else {
+            self.started = Some(now);
+            self.last = Some(now);
+            self.previous = totals;
+            return None;
+        };
+
+        let deltas: HashMap<K, [u64; N]> = totals
+            .iter()
+            .map(|(key, counts)| {
+                let previous = self.previous.get(key).unwrap_or(counts);
+                let mut delta = [0; N];
+                for i in 0..N {
+                    delta[i] = counts[i].saturating_sub(previous[i]);
+                }
+                (key.clone(), delta)
+            })
+            .collect();
+
+        self.deltas.push_back((now, deltas));
+        while let Some(&(t, _)) = self.deltas.front() {
+            if now.duration_since(t) < self.window {
+                break;
+            }
+            self.deltas.pop_front();
+        }
+
+        let interval = now.duration_since(last);
+        let span = now
+            .duration_since(self.started.unwrap_or(last))
+            .min(self.window);
+        let counts = totals
+            .keys()
+            .map(|key| {
+                let mut in_window = [0; N];
+                for (_, deltas) in &self.deltas {
+                    if let Some(delta) = deltas.get(key) {
+                        for i in 0..N {
+                            in_window[i] += delta[i];
+                        }
+                    }
+                }
+                let delta = self.deltas.back().and_then(|(_, d)| d.get(key)).copied();
+                (
+                    key.clone(),
+                    WindowedCounts {
+                        delta: delta.unwrap_or([0; N]),
+                        in_window,
+                        interval,
+                        span,
+                    },
+                )
+            })
+            .collect();
+
+        self.last = Some(now);
+        self.previous = totals;
+        Some(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_error_is_not_extrapolated() {
+        let mut window = RateWindow::<&str, 1>::new(DEFAULT_RATE_WINDOW);
+        let t0 = Instant::now();
+        assert!(window.update(t0, HashMap::from([("a", [5])])).is_none());
+
+        // One error 10 s later reads as 4/hour, not 360/hour
+        let counts = window
+            .update(t0 + Duration::from_secs(10), HashMap::from([("a", [6])]))
+            .unwrap();
+        assert_eq!(counts[0].1.delta, [1]);
+        assert_eq!(counts[0].1.per_hour(0), 4.0);
+
+        // Still counted while it is within the window
+        let counts = window
+            .update(t0 + Duration::from_secs(1800), HashMap::from([("a", [6])]))
+            .unwrap();
+        assert_eq!(counts[0].1.delta, [0]);
+        assert_eq!(counts[0].1.in_window, [1]);
+        assert_eq!(counts[0].1.per_hour(0), 2.0);
+
+        // And gone once it is older than the window
+        let counts = window
+            .update(t0 + Duration::from_secs(3700), HashMap::from([("a", [6])]))
+            .unwrap();
+        assert_eq!(counts[0].1.in_window, [0]);
+        assert_eq!(counts[0].1.span, DEFAULT_RATE_WINDOW);
+    }
+
+    #[test]
+    fn test_counter_reset_and_new_keys() {
+        let mut window = RateWindow::<u32, 2>::new(DEFAULT_RATE_WINDOW);
+        let t0 = Instant::now();
+        window.update(t0, HashMap::from([(0, [10, 1])]));
+
+        let counts = window
+            .update(
+                t0 + Duration::from_secs(60),
+                HashMap::from([(0, [0, 0]), (1, [3, 0])]),
+            )
+            .unwrap();
+        assert!(counts.iter().all(|(_, c)| c.in_window == [0, 0]));
+    }
+}
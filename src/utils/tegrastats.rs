@@ -0,0 +1,1087 @@
+//! tegrastats log parsing and emission
+//!
+//! Reads the one-line-per-sample format printed by NVIDIA's `tegrastats`
+//! (L4T 32.x through 36.x) into [`TegrastatsLine`]s, converts them to
+//! [`Snapshot`]s for offline analysis and replay, and renders snapshots back
+//! into tegrastats lines.
+//!
+//! A parsed line keeps every field in its original order, including ones it
+//! does not understand, so `line.to_string()` reproduces the input exactly.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use simon::utils::tegrastats::{read_log, TegrastatsLine};
+//! use simon::Simon;
+//!
+//! // Offline analysis of a field log
+//! for line in read_log("tegrastats.log").unwrap() {
+//!     let snapshot = line.to_snapshot();
+//!     println!("{:?} {:.1}%", line.timestamp, snapshot.memory.ram_usage_percent());
+//! }
+//!
+//! // Live emitter
+//! let mut stats = Simon::new().unwrap();
+//! let snapshot = stats.snapshot().unwrap();
+//! println!("{}", TegrastatsLine::from_snapshot(&snapshot));
+//! ```
+
+use crate::core::{
+    cpu::{CpuCore, CpuFrequency, CpuStats, CpuTotal},
+    engine::{EngineInfo, EngineStats},
+    gpu::{GpuFrequency, GpuInfo, GpuStatus, GpuType},
+    memory::{EmcInfo, IramInfo, MemoryStats},
+    platform_info::BoardInfo,
+    power::{PowerRail, PowerStats, TotalPower},
+    process::ProcessStats,
+    temperature::{TemperatureSensor, TemperatureStats},
+};
+use crate::error::{Result, SimonError};
+use crate::stats::Snapshot;
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Timestamp prefix written by `tegrastats` (`04-18-2023 12:34:56`)
+pub const TIMESTAMP_FORMAT: &str = "%m-%d-%Y %H:%M:%S";
+
+/// Temperature tegrastats prints for a sensor that is switched off
+const OFFLINE_MILLICELSIUS: i32 = -256_000;
+
+/// Engines in the order tegrastats prints them; other groups follow by name
+const ENGINE_ORDER: &[&str] = &[
+    "NVENC", "MSENC", "NVDEC", "NVJPG", "VIC", "OFA", "DLA", "PVA", "SE", "CVNAS", "APE",
+];
+
+/// Load and clock of one CPU core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuLoad {
+    /// Busy percentage
+    pub load: u32,
+    /// Frequency in MHz, if printed
+    pub freq: Option<u32>,
+}
+
+/// Reading of a clocked unit (`EMC_FREQ`, `GR3D_FREQ`, `APE`, `NVENC`, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClockReading {
+    /// `off`
+    Off,
+    /// Frequency in MHz without a load (`APE 174`)
+    Frequency(u32),
+    /// Load percentage and frequencies in MHz (`0%@1600`, `0%@[305,305]`)
+    Load {
+        /// Busy percentage
+        load: u32,
+        /// Frequencies in MHz (one per GPC when bracketed)
+        freq: Vec<u32>,
+        /// Frequencies are printed as a bracketed per-GPC list
+        per_gpc: bool,
+    },
+}
+
+/// One field of a tegrastats line
+#[derive(Debug, Clone, PartialEq)]
+pub enum TegraField {
+    /// `RAM 2037/3956MB (lfb 160x4MB)`
+    Ram {
+        /// Used RAM in MB
+        used: u64,
+        /// Total RAM in MB
+        total: u64,
+        /// Largest free blocks as (count, block size in MB)
+        lfb: Option<(u32, u32)>,
+    },
+    /// `SWAP 0/1978MB (cached 0MB)`
+    Swap {
+        /// Used swap in MB
+        used: u64,
+        /// Total swap in MB
+        total: u64,
+        /// Cached swap in MB
+        cached: Option<u64>,
+    },
+    /// `IRAM 0/252kB(lfb 252kB)`
+    Iram {
+        /// Used IRAM in kB
+        used: u64,
+        /// Total IRAM in kB
+        total: u64,
+        /// Largest free block in kB
+        lfb: Option<u64>,
+    },
+    /// `CPU [3%@102,off]`, one entry per core (`None` when off)
+    Cpu(Vec<Option<CpuLoad>>),
+    /// `EMC_FREQ 0%@1600`, `GR3D_FREQ 0%@[305,305]`, `APE 25`, `NVENC off`
+    Clock {
+        /// Name as printed
+        name: String,
+        /// Reading
+        reading: ClockReading,
+    },
+    /// `MTS fg 0% bg 0%` (Xavier on L4T 32)
+    Mts {
+        /// Foreground percentage
+        fg: u32,
+        /// Background percentage
+        bg: u32,
+    },
+    /// `CPU@32.5C`, `tj@47.437C`
+    Temperature {
+        /// Sensor name as printed
+        name: String,
+        /// Temperature in millidegrees Celsius (-256000 when off)
+        millicelsius: i32,
+    },
+    /// `POM_5V_IN 1322/1322` (L4T 32) or `VDD_IN 5000mW/5000mW`
+    Rail {
+        /// Rail name
+        name: String,
+        /// Instantaneous power in mW
+        power: u32,
+        /// Average power in mW
+        average: u32,
+        /// Values carry an `mW` suffix
+        units: bool,
+    },
+    /// A token this parser does not know, kept verbatim
+    Other(String),
+}
+
+/// One sample of tegrastats output
+#[derive(Debug, Clone, PartialEq)]
+pub struct TegrastatsLine {
+    /// Timestamp prefix (L4T 35 and later), if present
+    pub timestamp: Option<NaiveDateTime>,
+    /// Fields in printed order
+    pub fields: Vec<TegraField>,
+}
+
+impl TegrastatsLine {
+    /// Line with the given timestamp prefix
+    pub fn with_timestamp(mut self, timestamp: NaiveDateTime) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Clock reading named `name` (e.g. `EMC_FREQ`)
+    pub fn clock(&self, name: &str) -> Option<&ClockReading> {
+        self.fields.iter().find_map(|field| match field {
+            TegraField::Clock { name: n, reading } if n == name => Some(reading),
+            _ => None,
+        })
+    }
+
+    /// Temperature of sensor `name` in degrees Celsius
+    pub fn temperature(&self, name: &str) -> Option<f32> {
+        self.fields.iter().find_map(|field| match field {
+            TegraField::Temperature {
+                name: n,
+                millicelsius,
+            } if n == name => Some(*millicelsius as f32 / 1000.0),
+            _ => None,
+        })
+    }
+
+    /// Convert to a snapshot
+    ///
+    /// RAM/swap become KB, the EMC goes to `memory.emc`, `GR3D_FREQ` becomes
+    /// GPU `gpu`, other clocks become engine groups (`NVDLA0` is `DLA`/`DLA0`,
+    /// `VIC_FREQ` is `VIC`/`VIC`) and temperatures and rails keep the printed
+    /// names. Board information and uptime are left empty.
+    pub fn to_snapshot(&self) -> Snapshot {
+        let mut cpu = CpuStats::default();
+        let mut gpus = HashMap::new();
+        let mut memory = MemoryStats::default();
+        let mut power = PowerStats::default();
+        let mut temperature = TemperatureStats::default();
+        let mut engines = EngineStats::default();
+
+        for field in &self.fields {
+            match field {
+                TegraField::Ram { used, total, lfb } => {
+                    memory.ram.total = total * 1024;
+                    memory.ram.used = used * 1024;
+                    memory.ram.free = total.saturating_sub(*used) * 1024;
+                    memory.ram.lfb = lfb.map(|(count, _)| count);
+                }
+                TegraField::Swap {
+                    used,
+                    total,
+                    cached,
+                } => {
+                    memory.swap.total = total * 1024;
+                    memory.swap.used = used * 1024;
+                    memory.swap.cached = cached.unwrap_or(0) * 1024;
+                }
+                TegraField::Iram { used, total, lfb } => {
+                    memory.iram = Some(IramInfo {
+                        total: *total,
+                        used: *used,
+                        lfb: lfb.map(|kb| kb as u32),
+                    });
+                }
+                TegraField::Cpu(cores) => {
+                    cpu.cores = cores.iter().enumerate().map(cpu_core).collect();
+                    cpu.total = cpu_total(&cpu.cores);
+                }
+                TegraField::Clock { name, reading } if name == "EMC_FREQ" => {
+                    let (load, freq) = match reading {
+                        ClockReading::Off => (0, 0),
+                        ClockReading::Frequency(freq) => (0, *freq),
+                        ClockReading::Load { load, freq, .. } => {
+                            (*load, freq.first().copied().unwrap_or(0))
+                        }
+                    };
+                    memory.emc = Some(EmcInfo {
+                        online: *reading != ClockReading::Off,
+                        value: load,
+                        current: freq * 1000,
+                        max: 0,
+                        min: 0,
+                    });
+                }
+                TegraField::Clock { name, reading } if name == "GR3D_FREQ" => {
+                    gpus.insert("gpu".to_string(), gpu_info(reading));
+                }
+                TegraField::Clock { name, reading } => {
+                    let (group, engine) = engine_name(name);
+                    let (online, current) = match reading {
+                        ClockReading::Off => (false, 0),
+                        ClockReading::Frequency(freq) => (true, *freq),
+                        ClockReading::Load { freq, .. } => {
+                            (true, freq.first().copied().unwrap_or(0))
+                        }
+                    };
+                    engines.groups.entry(group).or_default().insert(
+                        engine,
+                        EngineInfo {
+                            online,
+                            current,
+                            max: None,
+                            min: None,
+                        },
+                    );
+                }
+                TegraField::Temperature { name, millicelsius } => {
+                    temperature.sensors.insert(
+                        name.clone(),
+                        TemperatureSensor {
+                            online: *millicelsius != OFFLINE_MILLICELSIUS,
+                            temp: *millicelsius as f32 / 1000.0,
+                            max: None,
+                            crit: None,
+                        },
+                    );
+                }
+                TegraField::Rail {
+                    name,
+                    power: mw,
+                    average,
+                    ..
+                } => {
+                    power.rails.insert(
+                        name.clone(),
+                        PowerRail {
+                            online: true,
+                            sensor_type: "tegrastats".to_string(),
+                            voltage: 0,
+                            current: 0,
+                            power: *mw,
+                            average: *average,
+                            warn: None,
+                            crit: None,
+                        },
+                    );
+                }
+                TegraField::Mts { .. } | TegraField::Other(_) => {}
+            }
+        }
+
+        let rails = power.rails.values().filter(|r| r.online);
+        power.total = TotalPower {
+            power: rails.clone().map(|r| r.power).sum(),
+            average: rails.map(|r| r.average).sum(),
+        };
+
+        Snapshot {
+            cpu,
+            gpus,
+            memory,
+            power,
+            temperature,
+            fans: HashMap::new(),
+            board: BoardInfo::default(),
+            processes: ProcessStats::default(),
+            engines,
+            uptime: Duration::ZERO,
+        }
+    }
+
+    /// Render a snapshot in the layout of the board's L4T release
+    ///
+    /// L4T 32 prints rails without units and leaves idle engines out;
+    /// L4T 36 (and unknown boards) print `off` engines and `mW` rails.
+    /// Temperatures and rails are ordered by name, engines in tegrastats
+    /// order. No timestamp is added; see [`TegrastatsLine::with_timestamp`].
+    pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+        let release = snapshot
+            .board
+            .hardware
+            .l4t
+            .as_deref()
+            .and_then(l4t_major)
+            .unwrap_or(36);
+        let mut fields = Vec::new();
+
+        let ram = &snapshot.memory.ram;
+        if ram.total > 0 {
+            fields.push(TegraField::Ram {
+                used: ram.used / 1024,
+                total: ram.total / 1024,
+                lfb: ram.lfb.map(|count| (count, 4)),
+            });
+        }
+        let swap = &snapshot.memory.swap;
+        if swap.total > 0 || ram.total > 0 {
+            fields.push(TegraField::Swap {
+                used: swap.used / 1024,
+                total: swap.total / 1024,
+                cached: Some(swap.cached / 1024),
+            });
+        }
+        if let Some(iram) = &snapshot.memory.iram {
+            fields.push(TegraField::Iram {
+                used: iram.used,
+                total: iram.total,
+                lfb: iram.lfb.map(u64::from),
+            });
+        }
+
+        let mut cores: Vec<&CpuCore> = snapshot.cpu.cores.iter().collect();
+        cores.sort_by_key(|core| core.id);
+        if !cores.is_empty() {
+            fields.push(TegraField::Cpu(
+                cores
+                    .iter()
+                    .map(|core| {
+                        core.online.then(|| CpuLoad {
+                            load: (100.0 - core.idle.unwrap_or(100.0))
+                                .round()
+                                .clamp(0.0, 100.0) as u32,
+                            freq: core.frequency.as_ref().map(|f| f.current),
+                        })
+                    })
+                    .collect(),
+            ));
+        }
+
+        if let Some(emc) = &snapshot.memory.emc {
+            fields.push(TegraField::Clock {
+                name: "EMC_FREQ".to_string(),
+                reading: ClockReading::Load {
+                    load: emc.value,
+                    freq: vec![emc.current / 1000],
+                    per_gpc: false,
+                },
+            });
+        }
+
+        let mut gpu_names: Vec<&String> = snapshot
+            .gpus
+            .iter()
+            .filter(|(_, gpu)| gpu.gpu_type == GpuType::Integrated)
+            .map(|(name, _)| name)
+            .collect();
+        gpu_names.sort();
+        if let Some(gpu) = gpu_names.first().map(|name| &snapshot.gpus[*name]) {
+            let (freq, per_gpc) = match &gpu.frequency.gpc {
+                Some(gpc) if !gpc.is_empty() => (gpc.clone(), true),
+                _ => (vec![gpu.frequency.current], false),
+            };
+            fields.push(TegraField::Clock {
+                name: "GR3D_FREQ".to_string(),
+                reading: ClockReading::Load {
+                    load: gpu.status.load.round().clamp(0.0, 100.0) as u32,
+                    freq,
+                    per_gpc,
+                },
+            });
+        }
+
+        let mut groups: Vec<&String> = snapshot.engines.groups.keys().collect();
+        groups.sort_by_key(|group| {
+            let rank = ENGINE_ORDER
+                .iter()
+                .position(|known| known == group)
+                .unwrap_or(ENGINE_ORDER.len());
+            (rank, group.as_str())
+        });
+        for group in groups {
+            let mut engines: Vec<(&String, &EngineInfo)> =
+                snapshot.engines.groups[group].iter().collect();
+            engines.sort_by_key(|(name, _)| name.as_str());
+            for (name, engine) in engines {
+                if !engine.online && release < 36 {
+                    continue;
+                }
+                fields.push(TegraField::Clock {
+                    name: printed_engine_name(group, name, release),
+                    reading: if engine.online {
+                        ClockReading::Frequency(engine.current)
+                    } else {
+                        ClockReading::Off
+                    },
+                });
+            }
+        }
+
+        let mut sensors: Vec<(String, &TemperatureSensor)> = snapshot
+            .temperature
+            .sensors
+            .iter()
+            .map(|(name, sensor)| (sensor_name(name), sensor))
+            .collect();
+        sensors.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, sensor) in sensors {
+            fields.push(TegraField::Temperature {
+                name,
+                millicelsius: if sensor.online {
+                    (sensor.temp * 1000.0).round() as i32
+                } else {
+                    OFFLINE_MILLICELSIUS
+                },
+            });
+        }
+
+        let mut rails: Vec<(&String, &PowerRail)> = snapshot
+            .power
+            .rails
+            .iter()
+            .filter(|(_, rail)| rail.online)
+            .collect();
+        rails.sort_by_key(|(name, _)| name.as_str());
+        for (name, rail) in rails {
+            fields.push(TegraField::Rail {
+                name: name.replace(char::is_whitespace, "_"),
+                power: rail.power,
+                average: rail.average,
+                units: release >= 34,
+            });
+        }
+
+        Self {
+            timestamp: None,
+            fields,
+        }
+    }
+}
+
+impl FromStr for TegrastatsLine {
+    type Err = SimonError;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut i = 0;
+
+        let timestamp = match tokens.as_slice() {
+            [date, time, ..] if date.len() == 10 && date.as_bytes()[2] == b'-' => Some(
+                NaiveDateTime::parse_from_str(&format!("{} {}", date, time), TIMESTAMP_FORMAT)
+                    .map_err(|e| {
+                        SimonError::Parse(format!("tegrastats timestamp {} {}: {}", date, time, e))
+                    })?,
+            ),
+            _ => None,
+        };
+        if timestamp.is_some() {
+            i = 2;
+        }
+
+        let mut fields = Vec::new();
+        while i < tokens.len() {
+            let token = tokens[i];
+            let next = tokens.get(i + 1).copied();
+            let (field, used) = match (token, next) {
+                ("RAM", Some(value)) => {
+                    let (used, total) = used_total(value, "MB")?;
+                    match (tokens.get(i + 2), tokens.get(i + 3)) {
+                        (Some(&"(lfb"), Some(lfb)) => {
+                            let (count, size) = lfb
+                                .strip_suffix("MB)")
+                                .and_then(|s| s.split_once('x'))
+                                .ok_or_else(|| bad_field("RAM lfb", lfb))?;
+                            let lfb = (number(count, "RAM lfb")?, number(size, "RAM lfb")?);
+                            (
+                                TegraField::Ram {
+                                    used,
+                                    total,
+                                    lfb: Some(lfb),
+                                },
+                                4,
+                            )
+                        }
+                        _ => (
+                            TegraField::Ram {
+                                used,
+                                total,
+                                lfb: None,
+                            },
+                            2,
+                        ),
+                    }
+                }
+                ("SWAP", Some(value)) => {
+                    let (used, total) = used_total(value, "MB")?;
+                    match (tokens.get(i + 2), tokens.get(i + 3)) {
+                        (Some(&"(cached"), Some(cached)) => {
+                            let cached = cached
+                                .strip_suffix("MB)")
+                                .ok_or_else(|| bad_field("SWAP cached", cached))?;
+                            let cached = Some(number(cached, "SWAP cached")?);
+                            (
+                                TegraField::Swap {
+                                    used,
+                                    total,
+                                    cached,
+                                },
+                                4,
+                            )
+                        }
+                        _ => (
+                            TegraField::Swap {
+                                used,
+                                total,
+                                cached: None,
+                            },
+                            2,
+                        ),
+                    }
+                }
+                ("IRAM", Some(value)) => match value.strip_suffix("(lfb") {
+                    Some(value) => {
+                        let (used, total) = used_total(value, "kB")?;
+                        let lfb = tokens.get(i + 2).copied().unwrap_or_default();
+                        let lfb = lfb
+                            .strip_suffix("kB)")
+                            .ok_or_else(|| bad_field("IRAM lfb", lfb))?;
+                        let lfb = Some(number(lfb, "IRAM lfb")?);
+                        (TegraField::Iram { used, total, lfb }, 3)
+                    }
+                    None => {
+                        let (used, total) = used_total(value, "kB")?;
+                        (
+                            TegraField::Iram {
+                                used,
+                                total,
+                                lfb: None,
+                            },
+                            2,
+                        )
+                    }
+                },
+                ("CPU", Some(value)) if value.starts_with('[') => {
+                    let cores = value
+                        .strip_prefix('[')
+                        .and_then(|s| s.strip_suffix(']'))
+                        .ok_or_else(|| bad_field("CPU", value))?
+                        .split(',')
+                        .map(|core| match core {
+                            "off" => Ok(None),
+                            _ => {
+                                let (load, freq) =
+                                    load_freq(core).ok_or_else(|| bad_field("CPU", core))?;
+                                Ok(Some(CpuLoad { load, freq }))
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    (TegraField::Cpu(cores), 2)
+                }
+                ("MTS", Some("fg")) => match (tokens.get(i + 2), tokens.get(i + 4)) {
+                    (Some(fg), Some(bg)) if tokens.get(i + 3) == Some(&"bg") => {
+                        let fg = fg.strip_suffix('%').ok_or_else(|| bad_field("MTS", fg))?;
+                        let bg = bg.strip_suffix('%').ok_or_else(|| bad_field("MTS", bg))?;
+                        let fg = number(fg, "MTS fg")?;
+                        let bg = number(bg, "MTS bg")?;
+                        (TegraField::Mts { fg, bg }, 5)
+                    }
+                    _ => (TegraField::Other(token.to_string()), 1),
+                },
+                _ => {
+                    if let Some(field) = temperature_field(token) {
+                        (field, 1)
+                    } else if let Some(field) = next.and_then(|next| rail_field(token, next)) {
+                        (field, 2)
+                    } else if let Some(reading) =
+                        next.filter(|_| is_name(token)).and_then(clock_reading)
+                    {
+                        let name = token.to_string();
+                        (TegraField::Clock { name, reading }, 2)
+                    } else {
+                        (TegraField::Other(token.to_string()), 1)
+                    }
+                }
+            };
+            fields.push(field);
+            i += used;
+        }
+
+        if !fields.iter().any(|f| !matches!(f, TegraField::Other(_))) {
+            return Err(SimonError::Parse(format!(
+                "not a tegrastats line: {:?}",
+                line.trim()
+            )));
+        }
+        Ok(Self { timestamp, fields })
+    }
+}
+
+impl fmt::Display for TegrastatsLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        if let Some(timestamp) = &self.timestamp {
+            write!(f, "{}", timestamp.format(TIMESTAMP_FORMAT))?;
+            first = false;
+        }
+        for field in &self.fields {
+            if !first {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", field)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TegraField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TegraField::Ram { used, total, lfb } => {
+                write!(f, "RAM {}/{}MB", used, total)?;
+                if let Some((count, size)) = lfb {
+                    write!(f, " (lfb {}x{}MB)", count, size)?;
+                }
+                Ok(())
+            }
+            TegraField::Swap {
+                used,
+                total,
+                cached,
+            } => {
+                write!(f, "SWAP {}/{}MB", used, total)?;
+                if let Some(cached) = cached {
+                    write!(f, " (cached {}MB)", cached)?;
+                }
+                Ok(())
+            }
+            TegraField::Iram { used, total, lfb } => {
+                write!(f, "IRAM {}/{}kB", used, total)?;
+                if let Some(lfb) = lfb {
+                    write!(f, "(lfb {}kB)", lfb)?;
+                }
+                Ok(())
+            }
+            TegraField::Cpu(cores) => {
+                let cores: Vec<String> = cores
+                    .iter()
+                    .map(|core| match core {
+                        None => "off".to_string(),
+                        Some(CpuLoad { load, freq: None }) => format!("{}%", load),
+                        Some(CpuLoad {
+                            load,
+                            freq: Some(freq),
+                        }) => format!("{}%@{}", load, freq),
+                    })
+                    .collect();
+                write!(f, "CPU [{}]", cores.join(","))
+            }
+            TegraField::Clock { name, reading } => match reading {
+                ClockReading::Off => write!(f, "{} off", name),
+                ClockReading::Frequency(freq) => write!(f, "{} {}", name, freq),
+                ClockReading::Load {
+                    load,
+                    freq,
+                    per_gpc,
+                } => {
+                    write!(f, "{} {}%", name, load)?;
+                    let freq: Vec<String> = freq.iter().map(u32::to_string).collect();
+                    if *per_gpc {
+                        write!(f, "@[{}]", freq.join(","))
+                    } else if let Some(freq) = freq.first() {
+                        write!(f, "@{}", freq)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+            TegraField::Mts { fg, bg } => write!(f, "MTS fg {}% bg {}%", fg, bg),
+            TegraField::Temperature { name, millicelsius } => {
+                let sign = if *millicelsius < 0 { "-" } else { "" };
+                let abs = millicelsius.unsigned_abs();
+                match abs % 1000 {
+                    0 => write!(f, "{}@{}{}C", name, sign, abs / 1000),
+                    frac => {
+                        let frac = format!("{:03}", frac);
+                        write!(
+                            f,
+                            "{}@{}{}.{}C",
+                            name,
+                            sign,
+                            abs / 1000,
+                            frac.trim_end_matches('0')
+                        )
+                    }
+                }
+            }
+            TegraField::Rail {
+                name,
+                power,
+                average,
+                units,
+            } => {
+                let unit = if *units { "mW" } else { "" };
+                write!(f, "{} {}{}/{}{}", name, power, unit, average, unit)
+            }
+            TegraField::Other(token) => f.write_str(token),
+        }
+    }
+}
+
+/// Parse a tegrastats log file, skipping blank lines
+///
+/// Errors name the offending line number.
+pub fn read_log(path: impl AsRef<Path>) -> Result<Vec<TegrastatsLine>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)?;
+    parse_log(&text)
+        .map_err(|(number, e)| SimonError::Parse(format!("{}:{}: {}", path.display(), number, e)))
+}
+
+/// Parse the lines of a tegrastats log; the error carries the 1-based line number
+pub fn parse_log(text: &str) -> std::result::Result<Vec<TegrastatsLine>, (usize, SimonError)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| line.parse().map_err(|e| (index + 1, e)))
+        .collect()
+}
+
+/// Major L4T release from `# R32 (release), REVISION: 7.1` or `36.3.0`
+fn l4t_major(l4t: &str) -> Option<u32> {
+    let digits = match l4t.find('R') {
+        Some(pos) => &l4t[pos + 1..],
+        None => l4t.trim(),
+    };
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end].parse().ok()
+}
+
+fn bad_field(field: &str, value: &str) -> SimonError {
+    SimonError::Parse(format!("invalid tegrastats {} value {:?}", field, value))
+}
+
+fn number<T: FromStr>(value: &str, field: &str) -> Result<T> {
+    value.parse().map_err(|_| bad_field(field, value))
+}
+
+/// `2037/3956MB` -> (2037, 3956)
+fn used_total(value: &str, unit: &str) -> Result<(u64, u64)> {
+    let (used, total) = value
+        .strip_suffix(unit)
+        .and_then(|s| s.split_once('/'))
+        .ok_or_else(|| bad_field("memory", value))?;
+    Ok((number(used, "memory")?, number(total, "memory")?))
+}
+
+/// `12%@1190` or `12%`
+fn load_freq(value: &str) -> Option<(u32, Option<u32>)> {
+    match value.split_once('@') {
+        Some((load, freq)) => Some((load.strip_suffix('%')?.parse().ok()?, freq.parse().ok())),
+        None => Some((value.strip_suffix('%')?.parse().ok()?, None)),
+    }
+}
+
+/// Upper-case identifier such as `APE`, `NVDLA0`, `PVA0_FREQ`
+fn is_name(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_uppercase())
+        && token
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn clock_reading(value: &str) -> Option<ClockReading> {
+    if value == "off" {
+        return Some(ClockReading::Off);
+    }
+    if value.bytes().all(|b| b.is_ascii_digit()) {
+        return value.parse().ok().map(ClockReading::Frequency);
+    }
+    let (load, freq) = match value.split_once('@') {
+        Some((load, freq)) => (load, Some(freq)),
+        None => (value, None),
+    };
+    let load = load.strip_suffix('%')?.parse().ok()?;
+    let (freq, per_gpc) = match freq {
+        None => (Vec::new(), false),
+        Some(freq) => match freq.strip_prefix('[').and_then(|f| f.strip_suffix(']')) {
+            Some(list) => (
+                list.split(',')
+                    .map(|f| f.parse().ok())
+                    .collect::<Option<Vec<u32>>>()?,
+                true,
+            ),
+            None => (vec![freq.parse().ok()?], false),
+        },
+    };
+    Some(ClockReading::Load {
+        load,
+        freq,
+        per_gpc,
+    })
+}
+
+/// `tj@47.437C` / `CV0@-256C`
+fn temperature_field(token: &str) -> Option<TegraField> {
+    let (name, value) = token.rsplit_once('@')?;
+    let value: f64 = value.strip_suffix('C')?.parse().ok()?;
+    if name.is_empty() {
+        return None;
+    }
+    Some(TegraField::Temperature {
+        name: name.to_string(),
+        millicelsius: (value * 1000.0).round() as i32,
+    })
+}
+
+/// `VDD_IN 5000mW/5000mW` or `POM_5V_IN 1322/1322`
+fn rail_field(name: &str, value: &str) -> Option<TegraField> {
+    let (power, average) = value.split_once('/')?;
+    let units = power.ends_with("mW");
+    let strip = |s: &'_ str| -> Option<u32> {
+        if units {
+            s.strip_suffix("mW")?.parse().ok()
+        } else {
+            s.parse().ok()
+        }
+    };
+    Some(TegraField::Rail {
+        name: name.to_string(),
+        power: strip(power)?,
+        average: strip(average)?,
+        units,
+    })
+}
+
+fn cpu_core((id, load): (usize, &Option<CpuLoad>)) -> CpuCore {
+    let load = load.as_ref();
+    let busy = load.map(|l| l.load as f32);
+    CpuCore {
+        id,
+        online: load.is_some(),
+        governor: String::new(),
+        frequency: load.and_then(|l| l.freq).map(|current| CpuFrequency {
+            current,
+            min: 0,
+            max: 0,
+        }),
+        user: busy,
+        nice: busy.map(|_| 0.0),
+        system: busy.map(|_| 0.0),
+        idle: busy.map(|busy| 100.0 - busy),
+        model: String::new(),
+    }
+}
+
+fn cpu_total(cores: &[CpuCore]) -> CpuTotal {
+    let online: Vec<&CpuCore> = cores.iter().filter(|c| c.online).collect();
+    if online.is_empty() {
+        return CpuStats::default().total;
+    }
+    let count = online.len() as f32;
+    CpuTotal {
+        user: online.iter().filter_map(|c| c.user).sum::<f32>() / count,
+        nice: 0.0,
+        system: 0.0,
+        idle: online.iter().filter_map(|c| c.idle).sum::<f32>() / count,
+    }
+}
+
+fn gpu_info(reading: &ClockReading) -> GpuInfo {
+    let (load, freq, per_gpc) = match reading {
+        ClockReading::Off => (0, Vec::new(), false),
+        ClockReading::Frequency(freq) => (0, vec![*freq], false),
+        ClockReading::Load {
+            load,
+            freq,
+            per_gpc,
+        } => (*load, freq.clone(), *per_gpc),
+    };
+    GpuInfo {
+        gpu_type: GpuType::Integrated,
+        status: GpuStatus {
+            load: load as f32,
+            railgate: None,
+            tpc_pg_mask: None,
+            scaling_3d: None,
+            memory_used: None,
+            memory_total: None,
+            memory_free: None,
+            temperature: None,
+            power_draw: None,
+            power_limit: None,
+        },
+        frequency: GpuFrequency {
+            current: freq.iter().copied().max().unwrap_or(0),
+            min: 0,
+            max: 0,
+            governor: String::new(),
+            gpc: per_gpc.then_some(freq),
+        },
+        power_control: "tegrastats".to_string(),
+    }
+}
+
+/// Printed engine name -> (group, engine) as used by [`EngineStats`]
+fn engine_name(printed: &str) -> (String, String) {
+    let name = printed.strip_suffix("_FREQ").unwrap_or(printed);
+    let name = match name.strip_prefix("NVDLA") {
+        Some(index) => format!("DLA{}", index),
+        None => name.to_string(),
+    };
+    let group = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let group = if group.is_empty() { &name } else { group }.to_string();
+    (group, name)
+}
+
+/// Engine name as tegrastats prints it on `release`
+fn printed_engine_name(group: &str, engine: &str, release: u32) -> String {
+    match group {
+        "DLA" => format!("NV{}", engine),
+        "PVA" => format!("{}_FREQ", engine),
+        "VIC" if release < 36 => "VIC_FREQ".to_string(),
+        _ => engine.replace(char::is_whitespace, "_"),
+    }
+}
+
+/// Thermal zone type -> tegrastats sensor name (`CPU-therm` -> `CPU`)
+fn sensor_name(zone: &str) -> String {
+    if zone == "thermal-fan-est" {
+        return "thermal".to_string();
+    }
+    let name = ["-thermal", "-therm", "_tegra", "-Die"]
+        .iter()
+        .find_map(|suffix| zone.strip_suffix(suffix))
+        .unwrap_or(zone);
+    name.replace(|c: char| c.is_whitespace() || c == '@', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const L4T32_NANO: &str = "RAM 2037/3956MB (lfb 160x4MB) SWAP 0/1978MB (cached 0MB) \
+        IRAM 0/252kB(lfb 252kB) CPU [3%@102,2%@102,off,off] EMC_FREQ 0%@1600 \
+        GR3D_FREQ 0%@76 APE 25 PLL@30C CPU@32.5C PMIC@100C GPU@31.5C AO@38.5C \
+        thermal@32C POM_5V_IN 1322/1322 POM_5V_GPU 0/0 POM_5V_CPU 124/124";
+    const L4T32_XAVIER: &str = "RAM 2448/15823MB (lfb 2904x4MB) SWAP 0/7911MB (cached 0MB) \
+        CPU [1%@1190,0%@1190,off,off,off,off,off,off] EMC_FREQ 0%@2133 GR3D_FREQ 0%@1377 \
+        NVENC 1075 APE 150 MTS fg 0% bg 0% AO@33C GPU@33C Tdiode@35.5C CPU@34.5C \
+        GPU 0/0 CPU 307/307 SOC 1230/1230";
+    const L4T35_ORIN: &str = "04-18-2023 12:34:56 RAM 3286/30536MB (lfb 6167x4MB) \
+        SWAP 0/15268MB (cached 0MB) CPU [1%@729,0%@729,2%@729,off] EMC_FREQ 0%@2133 \
+        GR3D_FREQ 0%@[305,305] VIC_FREQ 729 APE 174 CV0@-256C CPU@43.218C tj@43.218C \
+        VDD_GPU_SOC 2392mW/2392mW VDD_CPU_CV 399mW/399mW";
+    const L4T36_ORIN: &str = "10-18-2026 09:00:01 RAM 2948/7620MB (lfb 3x4MB) \
+        SWAP 0/3810MB (cached 0MB) CPU [12%@1190,4%@1190,0%@729,0%@729,off,off] \
+        EMC_FREQ 3%@2133 GR3D_FREQ 0%@[305] NVENC off NVDEC off NVJPG off NVJPG1 off \
+        VIC off OFA off NVDLA0 off NVDLA1 off PVA0_FREQ off APE 174 cpu@47.437C \
+        soc2@44.875C gpu@-256C tj@47.437C VDD_IN 5000mW/4987mW VDD_CPU_GPU_CV 600mW/598mW \
+        VDD_SOC 1400mW/1402mW";
+
+    #[test]
+    fn test_round_trip_all_releases() {
+        for sample in [L4T32_NANO, L4T32_XAVIER, L4T35_ORIN, L4T36_ORIN] {
+            let line: TegrastatsLine = sample.parse().unwrap();
+            assert_eq!(line.to_string(), sample);
+            assert!(!line
+                .fields
+                .iter()
+                .any(|f| matches!(f, TegraField::Other(_))));
+        }
+        assert!("hello world".parse::<TegrastatsLine>().is_err());
+        assert!("RAM abc/123MB".parse::<TegrastatsLine>().is_err());
+
+        let log = format!("{}\n\n{}\n", L4T35_ORIN, L4T36_ORIN);
+        assert_eq!(parse_log(&log).unwrap().len(), 2);
+        assert_eq!(parse_log("RAM 1/2MB\ngarbage\n").unwrap_err().0, 2);
+    }
+
+    #[test]
+    fn test_to_snapshot() {
+        let line: TegrastatsLine = L4T32_XAVIER.parse().unwrap();
+        let snapshot = line.to_snapshot();
+        assert_eq!(snapshot.memory.ram.total, 15823 * 1024);
+        assert_eq!(snapshot.memory.ram.lfb, Some(2904));
+        assert_eq!(snapshot.cpu.cores.len(), 8);
+        assert_eq!(snapshot.cpu.online_count(), 2);
+        assert_eq!(
+            snapshot.cpu.cores[0].frequency.as_ref().unwrap().current,
+            1190
+        );
+        assert_eq!(snapshot.memory.emc.as_ref().unwrap().current, 2_133_000);
+        assert_eq!(snapshot.gpus["gpu"].frequency.current, 1377);
+        assert_eq!(
+            snapshot
+                .engines
+                .get_engine("NVENC", "NVENC")
+                .unwrap()
+                .current,
+            1075
+        );
+        assert_eq!(snapshot.temperature.sensors["Tdiode"].temp, 35.5);
+        // Xavier's CPU rail shares its name with the CPU field
+        assert_eq!(snapshot.power.rails["CPU"].power, 307);
+        assert_eq!(snapshot.power.total.power, 1537);
+
+        let line: TegrastatsLine = L4T36_ORIN.parse().unwrap();
+        assert_eq!(line.timestamp.unwrap().to_string(), "2026-10-18 09:00:01");
+        let snapshot = line.to_snapshot();
+        assert_eq!(snapshot.gpus["gpu"].frequency.gpc, Some(vec![305]));
+        assert!(!snapshot.engines.get_engine("DLA", "DLA1").unwrap().online);
+        assert!(snapshot.engines.get_engine("PVA", "PVA0").is_some());
+        assert!(!snapshot.temperature.sensors["gpu"].online);
+        assert_eq!(snapshot.power.rails["VDD_IN"].average, 4987);
+    }
+
+    #[test]
+    fn test_from_snapshot() {
+        let mut snapshot = L4T36_ORIN.parse::<TegrastatsLine>().unwrap().to_snapshot();
+        snapshot.temperature.sensors.insert(
+            "cv0-thermal".to_string(),
+            snapshot.temperature.sensors["tj"].clone(),
+        );
+        snapshot.temperature.sensors.remove("soc2");
+        snapshot.power.rails.remove("VDD_CPU_GPU_CV");
+        let line = TegrastatsLine::from_snapshot(&snapshot);
+        assert_eq!(
+            line.to_string(),
+            "RAM 2948/7620MB (lfb 3x4MB) SWAP 0/3810MB (cached 0MB) \
+             CPU [12%@1190,4%@1190,0%@729,0%@729,off,off] EMC_FREQ 3%@2133 GR3D_FREQ 0%@[305] \
+             NVENC off NVDEC off NVJPG off NVJPG1 off VIC off OFA off NVDLA0 off NVDLA1 off \
+             PVA0_FREQ off APE 174 cpu@47.437C cv0@47.437C gpu@-256C tj@47.437C \
+             VDD_IN 5000mW/4987mW VDD_SOC 1400mW/1402mW"
+        );
+
+        // L4T 32 drops idle engines and rail units
+        snapshot.board.hardware.l4t = Some("# R32 (release), REVISION: 7.1".to_string());
+        let line = TegrastatsLine::from_snapshot(&snapshot).to_string();
+        assert!(line.contains("GR3D_FREQ 0%@[305] APE 174 cpu@"));
+        assert!(line.ends_with("VDD_IN 5000/4987 VDD_SOC 1400/1402"));
+        assert_eq!(l4t_major("36.3.0"), Some(36));
+    }
+}